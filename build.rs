@@ -20,7 +20,11 @@ fn main() {
 fn build_proto() -> Result<(), String> {
     // mark for rerun
 
-    let protos = ["src/pb/key_pair.proto", "src/pb/peer_record.proto"];
+    let protos = [
+        "src/pb/key_pair.proto",
+        "src/pb/peer_record.proto",
+        "src/pb/envelope.proto",
+    ];
     let includes = ["src"];
 
     let mut config = prost_build::Config::default();
@@ -0,0 +1,280 @@
+//! Derive macros for iprs.
+//!
+//! `#[derive(IpldNode)]` implements `iprs::ipld::typed::IpldNode` for
+//! structs and enums, refer `iprs::ipld::typed` module for the supported
+//! representations and attributes.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Fields, GenericParam, Generics,
+    Ident, Lit, Meta, NestedMeta,
+};
+
+/// Derive `IpldNode` for a struct or an enum.
+#[proc_macro_derive(IpldNode, attributes(ipld))]
+pub fn derive_ipld_node(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match do_derive(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn do_derive(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let generics = add_trait_bounds(input.generics.clone());
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let attrs = Attrs::from_attributes(&input.attrs)?;
+
+    let (to_basic, from_node) = match &input.data {
+        Data::Struct(data) => match (&data.fields, attrs.repr.as_deref()) {
+            (Fields::Named(_), None) | (Fields::Named(_), Some("map")) => {
+                let (to, from) = map_repr(&data.fields, quote! { self. }, quote! { node })?;
+                (quote! { Ok(#to) }, quote! { Ok(#name #from) })
+            }
+            (Fields::Named(_), Some("tuple"))
+            | (Fields::Unnamed(_), None)
+            | (Fields::Unnamed(_), Some("tuple")) => {
+                let (to, from) = tuple_repr(&data.fields, quote! { self. }, quote! { node });
+                (quote! { Ok(#to) }, quote! { Ok(#name #from) })
+            }
+            (Fields::Unit, None) => (
+                quote! { Ok(::iprs::ipld::kind::Basic::Null) },
+                quote! {
+                    ::iprs::ipld::typed::expect_null(node)?;
+                    Ok(#name)
+                },
+            ),
+            (_, Some(repr)) => {
+                let msg = format!("unsupported representation {:?} for {}", repr, name);
+                return Err(syn::Error::new(Span::call_site(), msg));
+            }
+        },
+        Data::Enum(data) => {
+            if let Some(repr) = attrs.repr.as_deref() {
+                if repr != "keyed" {
+                    let msg = format!("unsupported representation {:?} for {}", repr, name);
+                    return Err(syn::Error::new(Span::call_site(), msg));
+                }
+            }
+
+            let mut to_arms = vec![];
+            let mut from_arms = vec![];
+            for variant in data.variants.iter() {
+                let ident = &variant.ident;
+                let key = Attrs::from_attributes(&variant.attrs)?.to_key(ident);
+                let (pat, to, from) = match &variant.fields {
+                    Fields::Named(fields) => {
+                        let idents: Vec<&Ident> = fields
+                            .named
+                            .iter()
+                            .filter_map(|f| f.ident.as_ref())
+                            .collect();
+                        let (to, from) = map_repr(&variant.fields, quote! {}, quote! { val })?;
+                        (quote! { { #(#idents),* } }, to, from)
+                    }
+                    Fields::Unnamed(fields) => {
+                        let idents: Vec<Ident> = (0..fields.unnamed.len())
+                            .map(|i| format_ident!("f{}", i))
+                            .collect();
+                        let (to, from) = match idents.len() {
+                            1 => (
+                                quote! { ::iprs::ipld::typed::IpldNode::to_basic(f0)? },
+                                quote! { (::iprs::ipld::typed::IpldNode::from_node(val)?) },
+                            ),
+                            _ => tuple_repr(&variant.fields, quote! {}, quote! { val }),
+                        };
+                        (quote! { ( #(#idents),* ) }, to, from)
+                    }
+                    Fields::Unit => (
+                        quote! {},
+                        quote! { ::iprs::ipld::kind::Basic::Null },
+                        quote! {},
+                    ),
+                };
+
+                to_arms.push(quote! {
+                    #name::#ident #pat => ::iprs::ipld::typed::new_map(
+                        vec![(#key.to_string(), #to)]
+                    ),
+                });
+                let check = match &variant.fields {
+                    Fields::Unit => quote! { ::iprs::ipld::typed::expect_null(val)?; },
+                    _ => quote! {},
+                };
+                from_arms.push(quote! {
+                    #key => {
+                        #check
+                        Ok(#name::#ident #from)
+                    }
+                });
+            }
+
+            let name_str = name.to_string();
+            (
+                quote! {
+                    let val = match self {
+                        #(#to_arms)*
+                    };
+                    Ok(val)
+                },
+                quote! {
+                    let (key, val) = ::iprs::ipld::typed::get_union(node)?;
+                    match key.as_str() {
+                        #(#from_arms)*
+                        key => ::iprs::ipld::typed::unknown_variant(#name_str, key),
+                    }
+                },
+            )
+        }
+        Data::Union(_) => {
+            let msg = "IpldNode can't be derived for unions";
+            return Err(syn::Error::new(Span::call_site(), msg));
+        }
+    };
+
+    let tokens = quote! {
+        impl #impl_generics ::iprs::ipld::typed::IpldNode for #name #ty_generics #where_clause {
+            fn to_basic(&self) -> ::iprs::Result<::iprs::ipld::kind::Basic> {
+                #to_basic
+            }
+
+            fn from_node(node: &dyn ::iprs::ipld::kind::Node) -> ::iprs::Result<Self> {
+                #from_node
+            }
+        }
+    };
+
+    Ok(tokens)
+}
+
+// Return (to_basic, from_node) expressions for map representation of
+// named `fields`. `prefix` is prepended to field access, for enum variants
+// `prefix` is empty and fields are bound by name. `node` names the node
+// to parse from.
+fn map_repr(
+    fields: &Fields,
+    prefix: TokenStream2,
+    node: TokenStream2,
+) -> syn::Result<(TokenStream2, TokenStream2)> {
+    let mut entries = vec![];
+    let mut inits = vec![];
+    for field in fields.iter() {
+        let ident = field.ident.as_ref().unwrap();
+        let key = Attrs::from_attributes(&field.attrs)?.to_key(ident);
+        let access = match prefix.is_empty() {
+            true => quote! { #ident },
+            false => quote! { &#prefix #ident },
+        };
+        entries.push(quote! {
+            (#key.to_string(), ::iprs::ipld::typed::IpldNode::to_basic(#access)?)
+        });
+        inits.push(quote! {
+            #ident: ::iprs::ipld::typed::get_field(#node, #key)?
+        });
+    }
+
+    let to = quote! { ::iprs::ipld::typed::new_map(vec![#(#entries),*]) };
+    let from = quote! { { #(#inits),* } };
+    Ok((to, from))
+}
+
+// Return (to_basic, from_node) expressions for tuple representation of
+// `fields`, in declaration order. For enum variants, `prefix` is empty and
+// fields are bound as f0, f1, ...
+fn tuple_repr(
+    fields: &Fields,
+    prefix: TokenStream2,
+    node: TokenStream2,
+) -> (TokenStream2, TokenStream2) {
+    let n = fields.len();
+    let mut items = vec![];
+    let mut inits = vec![];
+    for (i, field) in fields.iter().enumerate() {
+        let access = match (&field.ident, prefix.is_empty()) {
+            (Some(ident), false) => quote! { &#prefix #ident },
+            (None, false) => {
+                let index = syn::Index::from(i);
+                quote! { &#prefix #index }
+            }
+            (Some(ident), true) => quote! { #ident },
+            (None, true) => {
+                let ident = format_ident!("f{}", i);
+                quote! { #ident }
+            }
+        };
+        items.push(quote! { ::iprs::ipld::typed::IpldNode::to_basic(#access)? });
+
+        let value = quote! { ::iprs::ipld::typed::get_item(#node, #i, #n)? };
+        inits.push(match &field.ident {
+            Some(ident) => quote! { #ident: #value },
+            None => value,
+        });
+    }
+
+    let to = quote! { ::iprs::ipld::typed::new_list(vec![#(#items),*]) };
+    let from = match fields {
+        Fields::Named(_) => quote! { { #(#inits),* } },
+        _ => quote! { ( #(#inits),* ) },
+    };
+    (to, from)
+}
+
+fn add_trait_bounds(mut generics: Generics) -> Generics {
+    for param in generics.params.iter_mut() {
+        if let GenericParam::Type(param) = param {
+            param
+                .bounds
+                .push(parse_quote!(::iprs::ipld::typed::IpldNode));
+        }
+    }
+    generics
+}
+
+// Attributes, #[ipld(repr = "..", rename = "..")]
+#[derive(Default)]
+struct Attrs {
+    repr: Option<String>,
+    rename: Option<String>,
+}
+
+impl Attrs {
+    fn from_attributes(attrs: &[Attribute]) -> syn::Result<Attrs> {
+        let mut val = Attrs::default();
+        for attr in attrs.iter().filter(|a| a.path.is_ident("ipld")) {
+            let list = match attr.parse_meta()? {
+                Meta::List(list) => list,
+                meta => return Err(syn::Error::new_spanned(meta, "expected #[ipld(..)]")),
+            };
+            for item in list.nested.iter() {
+                let nv = match item {
+                    NestedMeta::Meta(Meta::NameValue(nv)) => nv,
+                    item => return Err(syn::Error::new_spanned(item, "expected name = \"value\"")),
+                };
+                let text = match &nv.lit {
+                    Lit::Str(text) => text.value(),
+                    lit => return Err(syn::Error::new_spanned(lit, "expected string literal")),
+                };
+                if nv.path.is_ident("repr") {
+                    val.repr = Some(text)
+                } else if nv.path.is_ident("rename") {
+                    val.rename = Some(text)
+                } else {
+                    return Err(syn::Error::new_spanned(&nv.path, "unknown ipld attribute"));
+                }
+            }
+        }
+        Ok(val)
+    }
+
+    fn to_key(&self, ident: &Ident) -> String {
+        match &self.rename {
+            Some(name) => name.clone(),
+            None => ident.to_string().trim_start_matches("r#").to_string(),
+        }
+    }
+}
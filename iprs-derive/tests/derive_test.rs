@@ -0,0 +1,114 @@
+use iprs::ipld::{
+    kind::{Basic, Key, Node},
+    typed::{new_map, Bytes, IpldNode},
+};
+
+#[derive(Debug, PartialEq, IpldNode)]
+struct Entry {
+    name: String,
+    #[ipld(rename = "Size")]
+    size: u64,
+    data: Option<Bytes>,
+}
+
+#[derive(Debug, PartialEq, IpldNode)]
+#[ipld(repr = "tuple")]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Debug, PartialEq, IpldNode)]
+struct Pair(u8, String);
+
+#[derive(Debug, PartialEq, IpldNode)]
+struct Marker;
+
+#[derive(Debug, PartialEq, IpldNode)]
+enum Tree {
+    Leaf,
+    Value(u32),
+    Edge(u32, u32),
+    #[ipld(rename = "node")]
+    Node {
+        label: String,
+        children: Vec<Tree>,
+    },
+}
+
+#[derive(Debug, PartialEq, IpldNode)]
+struct Wrapper<T> {
+    inner: T,
+}
+
+fn roundtrip<T: IpldNode>(val: &T) -> T {
+    T::from_node(&val.to_basic().unwrap()).unwrap()
+}
+
+#[test]
+fn test_derive_struct_map() {
+    let entry = Entry {
+        name: "a.txt".to_string(),
+        size: 10,
+        data: Some(Bytes(b"hello".to_vec())),
+    };
+    assert_eq!(roundtrip(&entry), entry);
+
+    let node = entry.to_basic().unwrap();
+    assert_eq!(node.len(), Some(3));
+    assert_eq!(
+        node.get(&Key::Text("Size".to_string()))
+            .unwrap()
+            .to_integer(),
+        Some(10)
+    );
+
+    // optional field can be missing, required field can't.
+    let node = new_map(vec![
+        ("name".to_string(), Basic::Text(b"b".to_vec())),
+        ("Size".to_string(), Basic::Integer(1)),
+    ]);
+    let entry = Entry::from_node(&node).unwrap();
+    assert_eq!(entry.data, None);
+
+    let node = new_map(vec![("name".to_string(), Basic::Text(b"b".to_vec()))]);
+    assert!(Entry::from_node(&node).is_err());
+}
+
+#[test]
+fn test_derive_struct_tuple() {
+    let point = Point { x: -1, y: 2 };
+    assert_eq!(roundtrip(&point), point);
+    let node = point.to_basic().unwrap();
+    assert_eq!(node.get(&Key::Offset(1)).unwrap().to_integer(), Some(2));
+
+    let pair = Pair(1, "one".to_string());
+    assert_eq!(roundtrip(&pair), pair);
+
+    assert_eq!(roundtrip(&Marker), Marker);
+    assert!(Marker::from_node(&Basic::Integer(0)).is_err());
+}
+
+#[test]
+fn test_derive_enum_keyed() {
+    let tree = Tree::Node {
+        label: "root".to_string(),
+        children: vec![Tree::Leaf, Tree::Value(1), Tree::Edge(2, 3)],
+    };
+    assert_eq!(roundtrip(&tree), tree);
+
+    let node = tree.to_basic().unwrap();
+    let keys: Vec<Key> = node.iter_entries().map(|(k, _)| k).collect();
+    assert!(keys == vec![Key::Text("node".to_string())]);
+
+    let node = new_map(vec![("Bush".to_string(), Basic::Null)]);
+    assert!(Tree::from_node(&node).is_err());
+}
+
+#[test]
+fn test_derive_generic() {
+    let val = Wrapper {
+        inner: vec![1_u16, 2],
+    };
+    assert_eq!(roundtrip(&val), val);
+}
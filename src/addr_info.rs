@@ -1,6 +1,7 @@
-use std::fmt;
+use std::{collections::HashSet, fmt, net};
 
 use crate::{
+    dialer::rank_addrs,
     multiaddr::{self, Multiaddr},
     peer_id::PeerId,
     Error, Result,
@@ -49,9 +50,28 @@ impl AddrInfo {
             }
         }
 
+        for addr_info in addr_infos.iter_mut() {
+            let addrs: Vec<Multiaddr> = addr_info.addrs.drain(..).collect();
+            addr_info.addrs = AddrSet::from_multiaddrs(addrs)?.to_multiaddrs();
+        }
+
         Ok(addr_infos)
     }
 
+    /// Parse a list of text formated `/p2p` multi-addresses, typically a
+    /// list field from config like `bootstrap` or `peering.peers`, and
+    /// group them by peer. Error message shall include the field, the
+    /// index of offending entry and the reason.
+    pub fn from_text_list(field: &str, items: &[String]) -> Result<Vec<AddrInfo>> {
+        let addrs = Multiaddr::from_text_list(field, items)?;
+        for (i, addr) in addrs.iter().enumerate() {
+            if let Err(err) = Self::from_p2p_multiaddr(addr.clone()) {
+                err_at!(BadAddr, msg: "{}[{}] {:?}: {}", field, i, items[i], err)?
+            }
+        }
+        Self::from_p2p_multiaddrs(addrs)
+    }
+
     pub fn from_p2p_multiaddr(addr: Multiaddr) -> Result<AddrInfo> {
         let mut comps = addr.split()?;
         let peer_id = match comps.pop() {
@@ -92,3 +112,169 @@ impl AddrInfo {
         self.addrs.clone()
     }
 }
+
+/// Class of a multi-address, refer [AddrSet::to_class].
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum AddrClass {
+    /// Address reachable from public internet.
+    Public,
+    /// Loopback, link-local, private-network and unix-socket addresses.
+    Private,
+    /// Address dialed via a relay, that is containing `/p2p-circuit`.
+    Relay,
+}
+
+impl AddrClass {
+    /// Classify `addr`. Addresses with a `/p2p-circuit` component are
+    /// Relay, rest are classified by their leading ip4/ip6/unix
+    /// component, DNS and other addresses are treated as Public.
+    pub fn from_multiaddr(addr: &Multiaddr) -> Result<AddrClass> {
+        let comps = addr.clone().split()?;
+        if comps
+            .iter()
+            .any(|c| matches!(c, Multiaddr::P2pCircuit(_, _)))
+        {
+            return Ok(AddrClass::Relay);
+        }
+
+        let private = match comps
+            .iter()
+            .find(|c| !matches!(c, Multiaddr::Ip6zone(_, _)))
+        {
+            Some(Multiaddr::Ip4(val, _)) => is_private_ip4(&val.to_addr()),
+            Some(Multiaddr::Ip6(val, _)) => is_private_ip6(&val.to_addr()),
+            Some(Multiaddr::Unix(_, _)) => true,
+            _ => false,
+        };
+
+        match private {
+            true => Ok(AddrClass::Private),
+            false => Ok(AddrClass::Public),
+        }
+    }
+}
+
+/// Type AddrSet is a set of multi-addresses of a single peer, without
+/// duplicates. Addresses are normalized before comparing them,
+///
+/// * Trailing `/p2p/<peer-id>` component is stripped.
+/// * `/ip6zone` is stripped when the following ip6 address is not
+///   link-local, zones are meaningful only for link-local addresses.
+/// * Text and binary variants are parsed, hence compared in their
+///   canonical binary form.
+///
+/// Addresses are held in the order they were first inserted.
+#[derive(Clone, Default)]
+pub struct AddrSet {
+    addrs: Vec<Multiaddr>,
+    keys: HashSet<Vec<u8>>,
+}
+
+impl AddrSet {
+    /// Create an empty address set.
+    pub fn new() -> AddrSet {
+        AddrSet::default()
+    }
+
+    /// Create an address set from `addrs`, dropping duplicates.
+    pub fn from_multiaddrs(addrs: Vec<Multiaddr>) -> Result<AddrSet> {
+        let mut set = AddrSet::new();
+        for addr in addrs.into_iter() {
+            set.insert(addr)?;
+        }
+        Ok(set)
+    }
+
+    /// Insert `addr` into the set, return false if an equivalent address
+    /// is already present.
+    pub fn insert(&mut self, addr: Multiaddr) -> Result<bool> {
+        let addr = normalize(addr)?;
+        match self.keys.insert(addr.encode()?) {
+            true => {
+                self.addrs.push(addr);
+                Ok(true)
+            }
+            false => Ok(false),
+        }
+    }
+
+    /// Return whether an equivalent of `addr` is present in the set.
+    pub fn contains(&self, addr: &Multiaddr) -> Result<bool> {
+        let key = normalize(addr.clone())?.encode()?;
+        Ok(self.keys.contains(&key))
+    }
+
+    /// Return the number of addresses in the set.
+    pub fn len(&self) -> usize {
+        self.addrs.len()
+    }
+
+    /// Return whether the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.addrs.is_empty()
+    }
+
+    /// Return normalized addresses, in insertion order.
+    pub fn to_multiaddrs(&self) -> Vec<Multiaddr> {
+        self.addrs.clone()
+    }
+
+    /// Return normalized addresses of `class`, in insertion order.
+    pub fn to_class(&self, class: AddrClass) -> Result<Vec<Multiaddr>> {
+        let mut addrs = vec![];
+        for addr in self.addrs.iter() {
+            if AddrClass::from_multiaddr(addr)? == class {
+                addrs.push(addr.clone())
+            }
+        }
+        Ok(addrs)
+    }
+
+    /// Return normalized addresses ranked for dialing, best first, refer
+    /// [rank_addrs] for details.
+    pub fn to_ranked(&self) -> Result<Vec<Multiaddr>> {
+        rank_addrs(self.addrs.clone())
+    }
+}
+
+fn normalize(addr: Multiaddr) -> Result<Multiaddr> {
+    let mut comps = addr.split()?;
+    if let Some(Multiaddr::P2p(_, _)) | Some(Multiaddr::Ipfs(_, _)) = comps.last() {
+        comps.pop();
+    }
+
+    let mut items = Vec::with_capacity(comps.len());
+    let mut iter = comps.into_iter().peekable();
+    while let Some(comp) = iter.next() {
+        match (&comp, iter.peek()) {
+            (Multiaddr::Ip6zone(_, _), Some(Multiaddr::Ip6(val, _)))
+                if !is_link_local_ip6(&val.to_addr()) => {}
+            _ => items.push(comp),
+        }
+    }
+
+    Multiaddr::join(items)
+}
+
+fn is_private_ip4(addr: &net::Ipv4Addr) -> bool {
+    let octets = addr.octets();
+    let shared = octets[0] == 100 && (octets[1] & 0xc0) == 64; // 100.64.0.0/10
+    addr.is_private()
+        || addr.is_loopback()
+        || addr.is_link_local()
+        || addr.is_unspecified()
+        || shared
+}
+
+fn is_private_ip6(addr: &net::Ipv6Addr) -> bool {
+    let unique_local = (addr.segments()[0] & 0xfe00) == 0xfc00; // fc00::/7
+    addr.is_loopback() || addr.is_unspecified() || unique_local || is_link_local_ip6(addr)
+}
+
+fn is_link_local_ip6(addr: &net::Ipv6Addr) -> bool {
+    (addr.segments()[0] & 0xffc0) == 0xfe80 // fe80::/10
+}
+
+#[cfg(test)]
+#[path = "addr_info_test.rs"]
+mod addr_info_test;
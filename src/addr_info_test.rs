@@ -0,0 +1,89 @@
+use super::*;
+
+const PEER: &str = "QmNnooDu7bfjPFoTZYxMNLWUQJyrVwtbZg5gBMjTezGAJN";
+
+fn ma(text: &str) -> Multiaddr {
+    Multiaddr::from_text(text).unwrap()
+}
+
+#[test]
+fn test_addr_set_dedup() {
+    let mut set = AddrSet::new();
+    assert!(set.is_empty());
+
+    assert_eq!(set.insert(ma("/ip4/1.2.3.4/tcp/4001")).unwrap(), true);
+    assert_eq!(set.insert(ma("/ip4/1.2.3.4/tcp/4001")).unwrap(), false);
+    let text = format!("/ip4/1.2.3.4/tcp/4001/p2p/{}", PEER);
+    assert_eq!(set.insert(ma(&text)).unwrap(), false);
+    assert_eq!(set.insert(Multiaddr::Text(text)).unwrap(), false);
+    let bin = ma("/ip4/1.2.3.4/tcp/4001").encode().unwrap();
+    assert_eq!(set.insert(Multiaddr::Binary(bin)).unwrap(), false);
+
+    // zone is dropped for addresses that are not link-local.
+    assert_eq!(set.insert(ma("/ip6/2001:db8::1/tcp/4001")).unwrap(), true);
+    let addr = ma("/ip6zone/eth0/ip6/2001:db8::1/tcp/4001");
+    assert_eq!(set.insert(addr).unwrap(), false);
+    assert_eq!(
+        set.insert(ma("/ip6zone/eth0/ip6/fe80::1/tcp/4001"))
+            .unwrap(),
+        true
+    );
+    assert_eq!(set.insert(ma("/ip6/fe80::1/tcp/4001")).unwrap(), true);
+
+    // relay address keeps the p2p component of the relay.
+    let text = format!(
+        "/ip4/1.2.3.4/tcp/4001/p2p/{}/p2p-circuit/p2p/{}",
+        PEER, PEER
+    );
+    assert_eq!(set.insert(ma(&text)).unwrap(), true);
+    let text = format!("/ip4/1.2.3.4/tcp/4001/p2p/{}/p2p-circuit", PEER);
+    assert!(set.contains(&ma(&text)).unwrap());
+
+    assert_eq!(set.len(), 5);
+    assert_eq!(set.to_multiaddrs()[0], ma("/ip4/1.2.3.4/tcp/4001"));
+}
+
+#[test]
+fn test_addr_set_class() {
+    let relay = format!("/ip4/1.2.3.4/tcp/4001/p2p/{}/p2p-circuit", PEER);
+    let addrs = vec![
+        ma("/ip4/192.168.1.10/tcp/4001"),
+        ma(&relay),
+        ma("/ip4/1.2.3.4/tcp/4001"),
+        ma("/ip4/127.0.0.1/udp/4001/quic"),
+        ma("/ip4/100.64.1.1/tcp/4001"),
+        ma("/ip6/fd00::1/tcp/4001"),
+        ma("/ip6/2001:db8::1/udp/4001/quic"),
+        ma("/dns4/example.com/tcp/4001"),
+        ma("/unix/tmp/ipfs.sock"),
+    ];
+    let set = AddrSet::from_multiaddrs(addrs).unwrap();
+
+    let public = set.to_class(AddrClass::Public).unwrap();
+    assert_eq!(
+        public,
+        vec![
+            ma("/ip4/1.2.3.4/tcp/4001"),
+            ma("/ip6/2001:db8::1/udp/4001/quic"),
+            ma("/dns4/example.com/tcp/4001"),
+        ]
+    );
+    assert_eq!(set.to_class(AddrClass::Private).unwrap().len(), 5);
+    assert_eq!(set.to_class(AddrClass::Relay).unwrap(), vec![ma(&relay)]);
+
+    let ranked = set.to_ranked().unwrap();
+    assert_eq!(ranked[0], ma("/ip4/127.0.0.1/udp/4001/quic"));
+    assert_eq!(ranked[ranked.len() - 1], ma(&relay));
+}
+
+#[test]
+fn test_from_p2p_multiaddrs_dedup() {
+    let addrs = vec![
+        ma(&format!("/ip4/1.2.3.4/tcp/4001/p2p/{}", PEER)),
+        ma(&format!("/ip4/1.2.3.4/tcp/4001/ipfs/{}", PEER)),
+        ma(&format!("/ip4/1.2.3.5/tcp/4001/p2p/{}", PEER)),
+    ];
+    let addr_infos = AddrInfo::from_p2p_multiaddrs(addrs).unwrap();
+    assert_eq!(addr_infos.len(), 1);
+    assert_eq!(addr_infos[0].to_multiaddrs().len(), 2);
+}
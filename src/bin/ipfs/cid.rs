@@ -0,0 +1,107 @@
+use multibase::Base;
+use structopt::StructOpt;
+
+use std::{fs, time};
+
+use iprs::{
+    cid::Cid,
+    err_at,
+    multicodec::{self, Multicodec},
+    multihash::{self, Multihash},
+    Error, Result,
+};
+
+#[derive(Debug, StructOpt)]
+pub enum CidCommand {
+    /// Hash a file with every enabled multihash algorithm, and report
+    /// throughput, digest size and the resulting CIDv1.
+    Bench {
+        /// Only benchmark algorithms whose name contains one of these
+        /// patterns, like `--codec sha2 --codec blake3`.
+        #[structopt(long = "codec")]
+        codecs: Vec<String>,
+
+        file: String,
+    },
+}
+
+impl CidCommand {
+    pub fn run(self) -> Result<()> {
+        match self {
+            CidCommand::Bench { codecs, file } => bench(&file, &codecs),
+        }
+    }
+}
+
+fn bench(file: &str, patterns: &[String]) -> Result<()> {
+    let data = err_at!(IOError, fs::read(file), "{}", file)?;
+
+    println!("{} bytes from {}", data.len(), file);
+    println!(
+        "{:<16} {:>12} {:>8} {}",
+        "algorithm", "MB/s", "digest", "cid"
+    );
+
+    for code in to_codes(patterns).into_iter() {
+        let codec: Multicodec = code.into();
+        let start = time::Instant::now();
+        let mh = match Multihash::new(codec.clone(), &data) {
+            Ok(mh) => mh,
+            Err(Error::NotImplemented(_, _)) => continue,
+            Err(err) => return Err(err),
+        };
+        let elapsed = start.elapsed().as_secs_f64();
+
+        let throughput = match elapsed {
+            elapsed if elapsed > 0.0 => (data.len() as f64) / elapsed / 1_000_000.0,
+            _ => f64::INFINITY,
+        };
+        let digest_len = mh.to_digest()?.len();
+        let cid = Cid::from_raw(Base::Base32Lower, multicodec::RAW.into(), mh);
+
+        println!(
+            "{:<16} {:>12.1} {:>8} {}",
+            codec.to_string(),
+            throughput,
+            digest_len,
+            cid.to_text(None)?
+        );
+    }
+
+    Ok(())
+}
+
+// multihash algorithms with a fixed digest size, identity and
+// variable-length algorithms have nothing to compare.
+fn to_codes(patterns: &[String]) -> Vec<u128> {
+    let iter = multicodec::multihash_codes().into_iter();
+    iter.filter(|code| multihash::digest_size(*code).is_some())
+        .filter(|code| {
+            let name = Multicodec::from(*code).to_string();
+            patterns.is_empty() || patterns.iter().any(|p| name.contains(p.as_str()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_to_codes() {
+        let codes = to_codes(&[]);
+        assert!(codes.contains(&multicodec::SHA2_256));
+        assert!(!codes.contains(&multicodec::IDENTITY));
+        assert!(!codes.contains(&multicodec::SHAKE_128));
+
+        let codes = to_codes(&["sha2-".to_string(), "blake3".to_string()]);
+        let refcodes = vec![
+            multicodec::SHA2_256,
+            multicodec::SHA2_512,
+            multicodec::BLAKE3,
+            multicodec::DBL_SHA2_256,
+            multicodec::SHA2_256_TRUNC254_PADDED,
+        ];
+        assert_eq!(codes, refcodes);
+    }
+}
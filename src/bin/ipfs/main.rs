@@ -1,19 +1,24 @@
 #![feature(partition_point)]
 
+mod cid;
+
 #[allow(unused_imports)]
-use log::debug;
+use log::{debug, info};
 use structopt::StructOpt;
 
-use std::io;
+use std::{io, iter, time::Duration};
 
 use iprs::{
     err_at,
     ipfsd::{self, Ipfsd},
-    Error, Result,
+    util, Error, Result,
 };
 
 // TODO: cpu-profiling, mem-profiling.
 
+/// Time allowed for subsystems to start, before giving up on the daemon.
+const READY_TIMEOUT: Duration = Duration::from_secs(60);
+
 #[derive(Debug, StructOpt)]
 pub struct Opt {
     #[structopt(long = "seed")]
@@ -27,6 +32,17 @@ pub struct Opt {
 
     #[structopt(long = "trace")]
     trace: bool,
+
+    /// Initialize the ipfs-repo, with a fresh identity, if it does not
+    /// exist, when running as daemon.
+    #[structopt(long = "init-if-missing")]
+    init_if_missing: bool,
+}
+
+#[derive(Debug, StructOpt)]
+pub enum Command {
+    /// Content identifier utilities.
+    Cid(cid::CidCommand),
 }
 
 // main 'o' main
@@ -36,7 +52,49 @@ fn main() -> Result<()> {
     let opts = Opt::from_iter(args.into_iter()); // "ipfs" options
     init_logger(opts.log_file, opts.verbose, opts.trace).unwrap();
 
-    let d = err_at!(ThreadFail, Ipfsd::spawn())?;
+    if !cmd_args.is_empty() {
+        let cmd = Command::from_iter(iter::once("ipfs".to_string()).chain(cmd_args));
+        return match cmd {
+            Command::Cid(cmd) => cmd.run(),
+        };
+    }
+
+    // a running daemon already owns the repo, refuse to start another.
+    let repo = ipfsd::repo_root()?;
+    if let Some(ma) = ipfsd::daemon_addr(&repo)? {
+        err_at!(
+            Invalid,
+            msg: "ipfs daemon already running at {} for {:?}", ma.to_text()?, repo
+        )?
+    }
+
+    let file_repo = match ipfsd::FileRepo::is_initialized(&repo) {
+        true => ipfsd::FileRepo::open(&repo)?,
+        false if opts.init_if_missing => {
+            info!("initializing ipfs-repo at {:?}", repo);
+            ipfsd::FileRepo::init(&repo)?
+        }
+        false => err_at!(
+            Invalid,
+            msg: "no ipfs-repo at {:?}, use --init-if-missing to create one", repo
+        )?,
+    };
+    info!("peer identity {}", file_repo.as_config().to_peer_id()?);
+
+    let ctrl_rx = util::ctrl_channel()?;
+    let notifier = ipfsd::Notifier::from_env()?;
+    let listen = ipfsd::Listen::new(ipfsd::ListenAddrs::new()?, repo);
+    let subsystems: Vec<(Box<dyn ipfsd::Subsystem>, ipfsd::Restart)> =
+        vec![(Box::new(listen), ipfsd::Restart::Never)];
+    let d = Ipfsd::spawn_notify(subsystems, notifier.clone())?;
+    if !d.wait_ready(READY_TIMEOUT)? {
+        err_at!(ThreadFail, msg: "daemon not ready after {:?}", READY_TIMEOUT)?;
+    }
+    notifier.notify_ready()?;
+
+    let tm = err_at!(IPCFail, ctrl_rx.recv())?;
+    info!("received control-c at {:?}, stopping", tm);
+    notifier.notify_stopping()?;
     d.close_wait()?;
 
     Ok(())
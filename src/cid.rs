@@ -5,10 +5,10 @@
 use bs58;
 use multibase::Base;
 
-use std::{fmt, result, str::FromStr};
+use std::{cmp, fmt, hash, result, str::FromStr};
 
 use crate::{
-    multibase::Multibase,
+    multibase::{Multibase, BASE256EMOJI, PROQUINT},
     multicodec::{self, Multicodec},
     multihash::Multihash,
     peer_id::PeerId,
@@ -30,7 +30,12 @@ pub enum Version {
 }
 
 /// Content Identifier.
-#[derive(Clone, Eq, PartialEq)]
+///
+/// Equality, ordering and hashing are based on the version, content type
+/// and multihash, ordered same as the binary encoding, refer
+/// [Cid::encode], so that the same CID in different multibase is the
+/// same key in HashMap and BTreeMap.
+#[derive(Clone)]
 pub enum Cid {
     /// Cid version ZERO. Actually this is legacy.
     /// In the distant future, we may remove this support after sha2 breaks.
@@ -43,6 +48,48 @@ pub enum Cid {
     //Binary(Vec<u8>),
 }
 
+impl Eq for Cid {}
+
+impl PartialEq for Cid {
+    fn eq(&self, other: &Cid) -> bool {
+        self.to_key() == other.to_key()
+    }
+}
+
+impl PartialOrd for Cid {
+    fn partial_cmp(&self, other: &Cid) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Cid {
+    fn cmp(&self, other: &Cid) -> cmp::Ordering {
+        use unsigned_varint::encode;
+
+        match (self, other) {
+            (Cid::Zero(x), Cid::Zero(y)) => x.cmp(y),
+            (Cid::One(_, a, x), Cid::One(_, b, y)) => {
+                let (mut sa, mut sb) = (encode::u128_buffer(), encode::u128_buffer());
+                let a = encode::u128(a.to_code(), &mut sa);
+                let b = encode::u128(b.to_code(), &mut sb);
+                a.cmp(b).then_with(|| x.cmp(y))
+            }
+            // v1 is prefixed with 0x01, v0 with sha2-256 code 0x12.
+            (Cid::Zero(_), Cid::One(..)) => cmp::Ordering::Greater,
+            (Cid::One(..), Cid::Zero(_)) => cmp::Ordering::Less,
+        }
+    }
+}
+
+impl hash::Hash for Cid {
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: hash::Hasher,
+    {
+        self.to_key().hash(state)
+    }
+}
+
 impl fmt::Display for Cid {
     fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
         match self {
@@ -142,7 +189,9 @@ impl Cid {
     }
 
     /// Decode a base encoded CID, human readable text. CID format can
-    /// either be in legacy (v0) format or CIDv1 format.
+    /// either be in legacy (v0) format or CIDv1 format. CIDv1 text can
+    /// also be in base256emoji or proquint, such CIDs default to base32
+    /// for [Self::to_text].
     pub fn from_text(text: &str) -> Result<Cid> {
         let mut chars = text.chars();
         let cid = match (chars.next(), chars.next()) {
@@ -154,15 +203,29 @@ impl Cid {
             }
             (Some('Q'), Some('m')) | (Some('1'), Some(_)) => err_at!(ParseError, msg: "{}", text)?,
             _ => {
-                let (base, bytes) = {
-                    let mb = Multibase::from_text(text)?;
-                    match mb.to_bytes() {
-                        Some(bytes) => (mb.to_base(), bytes),
-                        None => err_at!(ParseError, msg: "{}", text)?,
+                let base = match text.chars().next() {
+                    Some(ch) => match Base::from_code(ch) {
+                        Ok(base) => base,
+                        // not representable as Base, fall back to base32.
+                        Err(_) if ch == BASE256EMOJI || ch == PROQUINT => Base::Base32Lower,
+                        Err(err) => err_at!(ParseError, Err(err), "{}", text)?,
+                    },
+                    None => err_at!(ParseError, msg: "{}", text)?,
+                };
+                // decoded bytes are never longer than the text, typical
+                // CIDs are decoded without allocation.
+                let (mut stack, mut heap) = ([0_u8; 128], vec![]);
+                let buf = match text.len() {
+                    n if n <= stack.len() => &mut stack[..],
+                    n => {
+                        heap.resize(n, 0);
+                        &mut heap[..]
                     }
                 };
+                let n = Multibase::decode_into(text, buf)?;
+                let bytes = &buf[..n];
                 // <multicodec-cidv1><codec><multihash>
-                let (codec, bytes) = Multicodec::decode(&bytes)?;
+                let (codec, bytes) = Multicodec::decode(bytes)?;
                 match codec.to_code() {
                     multicodec::CID_V1 => (),
                     _ => err_at!(ParseError, msg: "CID {}", codec)?,
@@ -190,7 +253,7 @@ impl Cid {
                     let codec = Multicodec::from_code(multicodec::CID_V1)?;
                     codec.encode()?
                 };
-                data.extend_from_slice(&content_type.encode()?);
+                content_type.encode_with(&mut data)?;
                 data.extend_from_slice(&mh.encode()?);
                 let base = base.unwrap_or(fallback_base.clone());
                 Multibase::with_base(base.clone(), &data)?.to_text()?
@@ -248,7 +311,7 @@ impl Cid {
                     let codec = Multicodec::from_code(multicodec::CID_V1)?;
                     codec.encode()?
                 };
-                bytes.extend(content_type.encode()?);
+                content_type.encode_with(&mut bytes)?;
                 bytes.extend(mh.encode()?);
                 bytes
             }
@@ -256,6 +319,21 @@ impl Cid {
         Ok(bytes)
     }
 
+    /// Return the canonical binary form of this CID, refer [Cid::encode].
+    /// Multibase is not part of the binary form, and the output is stable
+    /// across releases, so it is safe to sign and hash.
+    pub fn canonical_bytes(&self) -> Result<Vec<u8>> {
+        self.encode()
+    }
+
+    // content type, None for v0, and multihash, multibase is left out.
+    fn to_key(&self) -> (Option<u128>, &Multihash) {
+        match self {
+            Cid::Zero(mh) => (None, mh),
+            Cid::One(_, content_type, mh) => (Some(content_type.to_code()), mh),
+        }
+    }
+
     /// Return CID version.
     pub fn to_version(&self) -> Version {
         match self {
@@ -290,13 +368,15 @@ impl Cid {
     }
 
     /// If CID is pointing to a peer-id, that is if the content_type is
-    /// _LIBP2P_KEY_, return the PeerId value.
+    /// _LIBP2P_KEY_, return the PeerId value. Return None if the multihash
+    /// is not a valid peer-id, refer `TryFrom<Multihash> for PeerId`.
     pub fn to_peer_id(&self) -> Option<PeerId> {
+        use std::convert::TryFrom;
+
         let code = multicodec::LIBP2P_KEY;
         match self {
             Cid::One(_, content_type, mh) if content_type.to_code() == code => {
-                //
-                Some(mh.clone().into())
+                PeerId::try_from(mh.clone()).ok()
             }
             _ => None,
         }
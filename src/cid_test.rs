@@ -26,6 +26,28 @@ fn test_cid_v1() {
     assert_eq!(cid.to_multihash().to_digest().unwrap(), digest);
 }
 
+#[test]
+fn test_cid_extended_base() {
+    use crate::multibase::{BASE256EMOJI, PROQUINT};
+
+    let cid = Cid::new_v1(Base::Base32Lower, multicodec::RAW.into(), b"beep boop").unwrap();
+    let data = cid.encode().unwrap();
+
+    for ch in [BASE256EMOJI, PROQUINT].iter() {
+        let text = Multibase::with_char(*ch, &data).unwrap().to_text().unwrap();
+        let return_cid = Cid::from_text(&text).unwrap();
+        assert_eq!(return_cid, cid, "{:?}", ch);
+        assert_eq!(return_cid.to_base(), Base::Base32Lower, "{:?}", ch);
+
+        let data = return_cid.encode().unwrap();
+        let return_text = Multibase::with_char(*ch, &data).unwrap().to_text().unwrap();
+        assert_eq!(return_text, text, "{:?}", ch);
+    }
+
+    assert!(Cid::from_text("🚀x").is_err());
+    assert!(Cid::from_text("pro-x").is_err());
+}
+
 #[test]
 fn test_cid_v0() {
     let data = b"beep boop";
@@ -106,3 +128,46 @@ fn to_string_of_base58_v0() {
     let cid = Cid::new_v0(b"foo").unwrap();
     assert_eq!(cid.to_text(None).unwrap(), expected_cid);
 }
+
+#[test]
+fn test_cid_canonical_bytes() {
+    let golden = include_str!("testdata/canonical/cid.golden");
+    for line in golden.lines().filter(|l| !l.starts_with('#')) {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let cid = Cid::from_text(parts[0]).unwrap();
+        let bytes = data_encoding::HEXLOWER.decode(parts[1].as_bytes()).unwrap();
+
+        assert_eq!(cid.canonical_bytes().unwrap(), bytes, "{}", parts[0]);
+        assert_eq!(Cid::decode(&bytes).unwrap().0, cid, "{}", parts[0]);
+    }
+}
+
+#[test]
+fn test_cid_ord_hash() {
+    use std::collections::{BTreeSet, HashSet};
+
+    let cids: Vec<Cid> = (0..8_u8)
+        .map(|i| Cid::new_v1(Base::Base32Lower, multicodec::RAW.into(), &[i]).unwrap())
+        .chain(Some(Cid::new_v0(b"hello world").unwrap()))
+        .collect();
+
+    let set: BTreeSet<Cid> = cids.iter().cloned().collect();
+    assert_eq!(set.len(), cids.len());
+    let items: Vec<Vec<u8>> = set.iter().map(|c| c.encode().unwrap()).collect();
+    let mut sorted = items.clone();
+    sorted.sort();
+    assert_eq!(items, sorted);
+
+    // same CID in a different base is the same key.
+    let text = cids[0].to_text(Some(Base::Base58Btc)).unwrap();
+    let cid = Cid::from_text(&text).unwrap();
+    assert_eq!(cid.to_base(), Base::Base58Btc);
+    assert!(cid == cids[0]);
+    assert!(set.contains(&cid));
+    let hset: HashSet<Cid> = cids.iter().cloned().collect();
+    assert!(hset.contains(&cid));
+
+    // v0 and v1 of the same multihash are different CIDs.
+    let v0 = Cid::new_v0(b"hello world").unwrap();
+    assert!(v0 != v0.clone().into_v1());
+}
@@ -0,0 +1,413 @@
+//! Module implement content provide for Kademlia DHT.
+//!
+//! Provider records are stored on the [K_VALUE] peers closest to the
+//! sha256 of content's multihash, using XOR metric. Finding those peers
+//! is an iterative lookup through the routing layer, abstracted here as
+//! [Routing], and is what makes providing slow. Two optimizations are
+//! implemented by [Provider]:
+//!
+//! * [Provider::provide_many] groups the keys on common prefix, a single
+//!   lookup per group yields candidate peers that are ranked against each
+//!   key in the group. Prefix length is derived from the network size,
+//!   refer [prefix_bits], so that a group spans fewer than k peers. A key
+//!   whose k closest peers are not certainly among the candidates gets a
+//!   lookup of its own, hence records land on the same peers as with
+//!   individual provides.
+//! * Optimistic mode, refer [Provider::set_optimistic], ends a lookup
+//!   once enough peers are found within the distance expected for the
+//!   k closest peers, estimated from the network size, instead of
+//!   waiting for the lookup to converge. Daemon enables it only with
+//!   `optimistic-provide` experiment, refer [Provider::set_experiments].
+
+use std::{collections::BTreeMap, fmt};
+
+use crate::{
+    cid::Cid,
+    ipfsd::{Experiment, Experiments},
+    multihash::Multihash,
+    peer_id::PeerId,
+    Result,
+};
+
+/// Replication factor, number of closest peers to store a record with.
+pub const K_VALUE: usize = 20;
+
+/// Default number of leading key bits shared by keys in a provide batch,
+/// used when network size can't be estimated.
+pub const PREFIX_BITS: usize = 8;
+
+/// Bits added to `log2(network_size / k)`, refer [prefix_bits].
+pub const PREFIX_MARGIN: usize = 1;
+
+// optimistic lookup stops once these many peers, as a fraction of k, are
+// within the expected distance.
+const OPTIMISTIC_RATIO: f64 = 0.75;
+
+/// Key in DHT keyspace.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Key([u8; 32]);
+
+impl fmt::Debug for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let hex: String = self.0.iter().map(|b| format!("{:02x}", b)).collect();
+        write!(f, "Key({})", hex)
+    }
+}
+
+impl Key {
+    /// Key for content identified by `mh`.
+    pub fn from_multihash(mh: &Multihash) -> Result<Key> {
+        Ok(Key::from_bytes(&mh.encode()?))
+    }
+
+    /// Key for peer identified by `peer_id`.
+    pub fn from_peer_id(peer_id: &PeerId) -> Result<Key> {
+        Ok(Key::from_bytes(&peer_id.encode()?))
+    }
+
+    fn from_bytes(data: &[u8]) -> Key {
+        use digest::Digest;
+
+        let mut key = [0_u8; 32];
+        key.copy_from_slice(&sha2::Sha256::digest(data));
+        Key(key)
+    }
+
+    /// XOR distance between two keys.
+    pub fn distance(&self, other: &Key) -> Key {
+        let mut val = [0_u8; 32];
+        for (i, b) in val.iter_mut().enumerate() {
+            *b = self.0[i] ^ other.0[i];
+        }
+        Key(val)
+    }
+
+    /// Number of leading bits shared between two keys.
+    pub fn common_prefix_len(&self, other: &Key) -> usize {
+        let dist = self.distance(other);
+        match dist.0.iter().position(|b| *b != 0) {
+            Some(n) => n * 8 + (dist.0[n].leading_zeros() as usize),
+            None => 256,
+        }
+    }
+
+    /// Return key as fraction of the keyspace, using leading 64 bits.
+    /// Applied on a distance, this is the fraction of the keyspace
+    /// between the two keys.
+    pub fn to_fraction(&self) -> f64 {
+        let mut buf = [0_u8; 8];
+        buf.copy_from_slice(&self.0[..8]);
+        (u64::from_be_bytes(buf) as f64) / 2_f64.powi(64)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Routing layer used by [Provider].
+pub trait Routing {
+    /// Iterative lookup for `count` peers closest to `key`, ordered on
+    /// distance. `stop` is called with the current closest peers after
+    /// each round, lookup shall end early when it returns true.
+    fn closest_peers(
+        &self,
+        key: &Key,
+        count: usize,
+        stop: &mut dyn FnMut(&[PeerId]) -> bool,
+    ) -> Result<Vec<PeerId>>;
+
+    /// Store a provider record for content `mh` with `peer`.
+    fn add_provider(&self, peer: &PeerId, mh: &Multihash) -> Result<()>;
+}
+
+/// Outcome of [Provider::provide_many].
+#[derive(Clone, Debug, Default)]
+pub struct ProvideReport {
+    /// Number of lookups done.
+    pub lookups: usize,
+    /// Number of provider records stored.
+    pub records: usize,
+    /// Content that could not be provided to any peer.
+    pub failed: Vec<Cid>,
+}
+
+/// Provide content to DHT, refer module documentation.
+pub struct Provider<R: Routing> {
+    routing: R,
+    k: usize,
+    prefix_bits: Option<usize>,
+    optimistic: bool,
+    network_size: Option<usize>,
+}
+
+impl<R: Routing> Provider<R> {
+    pub fn new(routing: R) -> Provider<R> {
+        Provider {
+            routing,
+            k: K_VALUE,
+            prefix_bits: None,
+            optimistic: false,
+            network_size: None,
+        }
+    }
+
+    /// Set replication factor, default is [K_VALUE].
+    pub fn set_k(&mut self, k: usize) -> &mut Self {
+        self.k = k;
+        self
+    }
+
+    /// Set number of leading key bits shared by keys that are provided
+    /// using a single lookup. By default it is derived from network size,
+    /// refer [prefix_bits], falling back to [PREFIX_BITS].
+    pub fn set_prefix_bits(&mut self, bits: usize) -> &mut Self {
+        self.prefix_bits = Some(bits);
+        self
+    }
+
+    /// Set the estimated number of peers in the network. If not set, it
+    /// is estimated from the result of the first lookup.
+    pub fn set_network_size(&mut self, network_size: usize) -> &mut Self {
+        self.network_size = Some(network_size);
+        self
+    }
+
+    /// Enable optimistic provide. If `network_size` is None, it is
+    /// estimated from the result of the first lookup.
+    pub fn set_optimistic(&mut self, network_size: Option<usize>) -> &mut Self {
+        self.optimistic = true;
+        self.network_size = network_size;
+        self
+    }
+
+    /// Enable or disable features under experiment, as per `exps`.
+    /// Optimistic provide is enabled only with
+    /// [Experiment::OptimisticProvide].
+    pub fn set_experiments(&mut self, exps: &Experiments) -> &mut Self {
+        match exps.is_enabled(Experiment::OptimisticProvide) {
+            true if !self.optimistic => self.set_optimistic(None),
+            true => self,
+            false => {
+                self.optimistic = false;
+                self
+            }
+        }
+    }
+
+    /// Provide a single content.
+    pub fn provide(&mut self, cid: &Cid) -> Result<ProvideReport> {
+        self.provide_many(&[cid.clone()])
+    }
+
+    /// Provide a batch of content, refer module documentation.
+    pub fn provide_many(&mut self, cids: &[Cid]) -> Result<ProvideReport> {
+        let mut report = ProvideReport::default();
+
+        let keys = cids
+            .iter()
+            .map(|cid| Key::from_multihash(&cid.to_multihash()).map(|key| (key, cid)))
+            .collect::<Result<Vec<(Key, &Cid)>>>()?;
+        // for a lone key, the usual lookup. Otherwise fetch enough
+        // candidates to cover the spread of keys in a group.
+        let count = match keys.len() {
+            1 => self.k,
+            _ => self.k * 2,
+        };
+
+        // estimate network size, from the lookup for the first key, to
+        // pick the prefix length.
+        let mut first = None;
+        if let (None, None, Some((key, _))) = (self.prefix_bits, self.network_size, keys.first()) {
+            let peers = self.lookup(key, count)?;
+            report.lookups += 1;
+            if self.network_size.is_none() {
+                self.network_size = estimate_network_size(key, &peers)?;
+            }
+            first = Some((*key, peers));
+        }
+        let bits = match (self.prefix_bits, self.network_size) {
+            (Some(bits), _) => bits,
+            (None, Some(size)) => prefix_bits(size, self.k),
+            (None, None) => PREFIX_BITS,
+        };
+
+        let mut groups: BTreeMap<Vec<u8>, Vec<(Key, &Cid)>> = BTreeMap::new();
+        for (key, cid) in keys.into_iter() {
+            groups
+                .entry(to_prefix(&key, bits))
+                .or_insert_with(Vec::new)
+                .push((key, cid));
+        }
+
+        for (_, group) in groups.into_iter() {
+            let lead = group[0].0;
+            let peers = match first.take() {
+                Some((key, peers)) if key == lead => peers,
+                other => {
+                    first = other;
+                    report.lookups += 1;
+                    self.lookup(&lead, count)?
+                }
+            };
+            let mut candidates = Candidates::new(&lead, peers, count, self.k)?;
+
+            for (key, cid) in group.into_iter() {
+                // not covered, look it up and rank rest of the group
+                // against its peers.
+                let peers = match candidates.to_closest(&key) {
+                    Some(peers) => peers,
+                    None => {
+                        report.lookups += 1;
+                        let peers = self.lookup(&key, count)?;
+                        candidates = Candidates::new(&key, peers, count, self.k)?;
+                        candidates.to_closest(&key).unwrap_or_default()
+                    }
+                };
+
+                let mh = cid.to_multihash();
+                let mut n = 0;
+                for peer in peers.iter().take(self.k) {
+                    if self.routing.add_provider(peer, &mh).is_ok() {
+                        n += 1;
+                    }
+                }
+                match n {
+                    0 => report.failed.push(cid.clone()),
+                    n => report.records += n,
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    fn lookup(&mut self, key: &Key, count: usize) -> Result<Vec<PeerId>> {
+        let threshold = match (self.optimistic, self.network_size) {
+            (true, Some(size)) if size > 0 => Some((self.k as f64) / (size as f64)),
+            _ => None,
+        };
+        let want = ((self.k as f64) * OPTIMISTIC_RATIO).ceil() as usize;
+
+        let mut stop = |peers: &[PeerId]| match threshold {
+            Some(threshold) => {
+                let n = peers
+                    .iter()
+                    .filter_map(|p| Key::from_peer_id(p).ok())
+                    .filter(|pk| key.distance(pk).to_fraction() <= threshold)
+                    .count();
+                n >= want
+            }
+            None => false,
+        };
+        let peers = self.routing.closest_peers(key, count, &mut stop)?;
+
+        if self.optimistic && self.network_size.is_none() {
+            self.network_size = estimate_network_size(key, &peers)?;
+        }
+        Ok(peers)
+    }
+}
+
+/// Return the prefix length for grouping keys in a provide batch, that is
+/// `ceil(log2(network_size / k))` plus [PREFIX_MARGIN]. Keys sharing
+/// that prefix are expected to have fewer than `k` peers in between.
+pub fn prefix_bits(network_size: usize, k: usize) -> usize {
+    let ratio = (network_size as f64) / (k.max(1) as f64);
+    match ratio > 1.0 {
+        true => (ratio.log2().ceil() as usize + PREFIX_MARGIN).min(256),
+        false => 0,
+    }
+}
+
+// peers returned by a lookup for `lead`, to be ranked against other keys
+// in its group.
+struct Candidates {
+    lead: Key,
+    peers: Vec<(PeerId, Key)>,
+    // lookup returned fewer than requested, that is all known peers.
+    exhausted: bool,
+    // every peer sharing more than these many bits with lead is among
+    // the candidates.
+    depth: usize,
+    k: usize,
+}
+
+impl Candidates {
+    fn new(lead: &Key, peers: Vec<PeerId>, count: usize, k: usize) -> Result<Candidates> {
+        let mut peers = peers
+            .into_iter()
+            .map(|p| Key::from_peer_id(&p).map(|key| (p, key)))
+            .collect::<Result<Vec<(PeerId, Key)>>>()?;
+        peers.sort_by_key(|(_, pk)| lead.distance(pk));
+
+        let depth = match peers.last() {
+            Some((_, pk)) => lead.common_prefix_len(pk),
+            None => 256,
+        };
+        Ok(Candidates {
+            lead: *lead,
+            exhausted: peers.len() < count,
+            peers,
+            depth,
+            k,
+        })
+    }
+
+    // return the peers closest to `key`, if the k closest of them are
+    // certainly among the candidates. Peers sharing more than `depth`
+    // bits with lead form a subtree, if it holds at least k peers and
+    // key falls in it, no peer outside is closer to key.
+    fn to_closest(&self, key: &Key) -> Option<Vec<PeerId>> {
+        let mut peers: Vec<&(PeerId, Key)> = match self.exhausted {
+            true => self.peers.iter().collect(),
+            false if *key == self.lead => self.peers.iter().collect(),
+            false if self.lead.common_prefix_len(key) > self.depth => self
+                .peers
+                .iter()
+                .filter(|(_, pk)| self.lead.common_prefix_len(pk) > self.depth)
+                .collect(),
+            false => return None,
+        };
+        if !self.exhausted && *key != self.lead && peers.len() < self.k {
+            return None;
+        }
+
+        peers.sort_by_key(|(_, pk)| key.distance(pk));
+        Some(peers.into_iter().map(|(p, _)| p.clone()).collect())
+    }
+}
+
+/// Estimate number of peers in the network from the `peers` closest to
+/// `key`, ordered on distance. With peers spread uniformly, the i-th
+/// closest peer is expected at `i / network_size` fraction of keyspace,
+/// network size is fitted to observed distances using least squares.
+pub fn estimate_network_size(key: &Key, peers: &[PeerId]) -> Result<Option<usize>> {
+    let (mut num, mut den) = (0.0, 0.0);
+    for (i, peer) in peers.iter().enumerate() {
+        let frac = key.distance(&Key::from_peer_id(peer)?).to_fraction();
+        let i = (i + 1) as f64;
+        num += i * i;
+        den += i * frac;
+    }
+    match den > 0.0 {
+        true => Ok(Some((num / den).round() as usize)),
+        false => Ok(None),
+    }
+}
+
+// leading `bits` of key, trailing bits of the last byte cleared.
+fn to_prefix(key: &Key, bits: usize) -> Vec<u8> {
+    let bits = bits.min(256);
+    let mut prefix = key.0[..(bits + 7) / 8].to_vec();
+    if let Some(last) = prefix.last_mut() {
+        if bits % 8 != 0 {
+            *last &= 0xff_u8 << (8 - bits % 8);
+        }
+    }
+    prefix
+}
+
+#[cfg(test)]
+#[path = "dht_test.rs"]
+mod dht_test;
@@ -0,0 +1,200 @@
+use multibase::Base;
+
+use std::{cell::RefCell, collections::HashMap};
+
+use super::*;
+use crate::multicodec;
+
+// simulated network, each lookup round doubles the number of peers
+// seen, in a fixed order, and returns the closest among them.
+struct TestRouting {
+    peers: Vec<(PeerId, Key)>,
+    rounds: RefCell<usize>,
+    records: RefCell<HashMap<Vec<u8>, Vec<PeerId>>>,
+}
+
+impl TestRouting {
+    fn new(n: usize) -> TestRouting {
+        let peers = (0..n)
+            .map(|_| {
+                let peer = PeerId::generate().unwrap();
+                let key = Key::from_peer_id(&peer).unwrap();
+                (peer, key)
+            })
+            .collect();
+        TestRouting {
+            peers,
+            rounds: RefCell::new(0),
+            records: RefCell::new(HashMap::new()),
+        }
+    }
+
+    fn to_closest(&self, key: &Key, count: usize) -> Vec<PeerId> {
+        let mut peers = self.peers.clone();
+        peers.sort_by_key(|(_, pk)| key.distance(pk));
+        peers.into_iter().take(count).map(|(p, _)| p).collect()
+    }
+}
+
+impl Routing for &TestRouting {
+    fn closest_peers(
+        &self,
+        key: &Key,
+        count: usize,
+        stop: &mut dyn FnMut(&[PeerId]) -> bool,
+    ) -> Result<Vec<PeerId>> {
+        let mut seen = count;
+        loop {
+            *self.rounds.borrow_mut() += 1;
+            seen = seen.min(self.peers.len());
+            let mut peers = self.peers[..seen].to_vec();
+            peers.sort_by_key(|(_, pk)| key.distance(pk));
+            let peers: Vec<PeerId> = peers.into_iter().take(count).map(|(p, _)| p).collect();
+            if seen == self.peers.len() || stop(&peers) {
+                break Ok(peers);
+            }
+            seen *= 2;
+        }
+    }
+
+    fn add_provider(&self, peer: &PeerId, mh: &Multihash) -> Result<()> {
+        let mut records = self.records.borrow_mut();
+        records
+            .entry(mh.encode()?)
+            .or_insert_with(Vec::new)
+            .push(peer.clone());
+        Ok(())
+    }
+}
+
+fn make_cids(n: usize) -> Vec<Cid> {
+    (0..n)
+        .map(|i| {
+            let data = i.to_string();
+            Cid::new_v1(Base::Base32Lower, multicodec::RAW.into(), data.as_bytes()).unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn test_key() {
+    let a = Key([0_u8; 32]);
+    let mut b = [0_u8; 32];
+    b[1] = 0b0001_0000;
+    let b = Key(b);
+
+    assert_eq!(a.common_prefix_len(&a), 256);
+    assert_eq!(a.common_prefix_len(&b), 11);
+    assert_eq!(a.distance(&b), b);
+    assert_eq!(b.distance(&b), a);
+    assert_eq!(to_prefix(&b, 12), vec![0, 0b0001_0000]);
+    assert_eq!(to_prefix(&b, 11), vec![0, 0]);
+    assert_eq!(to_prefix(&b, 8), vec![0]);
+
+    let mut c = [0_u8; 32];
+    c[0] = 0x80;
+    assert!((Key(c).to_fraction() - 0.5).abs() < f64::EPSILON);
+}
+
+#[test]
+fn test_provide() {
+    let routing = TestRouting::new(200);
+    let cid = make_cids(1).remove(0);
+
+    let mut provider = Provider::new(&routing);
+    provider.set_k(10);
+    let report = provider.provide(&cid).unwrap();
+    assert_eq!(report.lookups, 1);
+    assert_eq!(report.records, 10);
+    assert!(report.failed.is_empty());
+}
+
+#[test]
+fn test_prefix_bits() {
+    assert_eq!(prefix_bits(500, 10), 6 + PREFIX_MARGIN);
+    assert_eq!(prefix_bits(1 << 20, 20), 16 + PREFIX_MARGIN);
+    assert_eq!(prefix_bits(10, 10), 0);
+    assert_eq!(prefix_bits(0, 20), 0);
+    assert_eq!(prefix_bits(usize::MAX, 0), 64 + PREFIX_MARGIN);
+}
+
+#[test]
+fn test_provide_many() {
+    let routing = TestRouting::new(500);
+    let cids = make_cids(1000);
+
+    let mut provider = Provider::new(&routing);
+    provider.set_k(10);
+    let report = provider.provide_many(&cids).unwrap();
+    assert!(provider.network_size.is_some());
+    assert!(report.lookups < cids.len() / 2, "{}", report.lookups);
+    assert_eq!(report.records, 10 * cids.len());
+    assert!(report.failed.is_empty());
+
+    // records land on the k closest peers, same as individual provides.
+    let records = routing.records.borrow();
+    assert_eq!(records.len(), cids.len());
+    for cid in cids.iter() {
+        let mh = cid.to_multihash();
+        let key = Key::from_multihash(&mh).unwrap();
+        let mut peers = records.get(&mh.encode().unwrap()).unwrap().clone();
+        let mut closest = routing.to_closest(&key, 10);
+        peers.sort();
+        closest.sort();
+        assert_eq!(peers, closest, "{}", cid.to_text(None).unwrap());
+    }
+}
+
+#[test]
+fn test_provide_optimistic() {
+    let routing = TestRouting::new(2000);
+    let cids = make_cids(20);
+    let key = Key::from_multihash(&cids[0].to_multihash()).unwrap();
+
+    let size = estimate_network_size(&key, &routing.to_closest(&key, 20))
+        .unwrap()
+        .unwrap();
+    assert!(size > 200 && size < 20000, "{}", size);
+
+    let mut provider = Provider::new(&routing);
+    provider.set_prefix_bits(256);
+    provider.provide_many(&cids).unwrap();
+    let full = *routing.rounds.borrow();
+
+    *routing.rounds.borrow_mut() = 0;
+    let mut provider = Provider::new(&routing);
+    provider.set_prefix_bits(256).set_optimistic(Some(2000));
+    let report = provider.provide_many(&cids).unwrap();
+    let optimistic = *routing.rounds.borrow();
+
+    assert_eq!(report.lookups, 20);
+    assert!(optimistic <= full, "{} {}", optimistic, full);
+}
+
+#[test]
+fn test_provide_experiments() {
+    let routing = TestRouting::new(2000);
+    let cids = make_cids(20);
+
+    let mut exps = Experiments::default();
+    let mut provider = Provider::new(&routing);
+    provider.set_prefix_bits(256).set_experiments(&exps);
+    provider.provide_many(&cids).unwrap();
+    assert!(!provider.optimistic);
+    assert!(provider.network_size.is_none());
+    let full = *routing.rounds.borrow();
+
+    // network size is estimated from the first lookup.
+    *routing.rounds.borrow_mut() = 0;
+    exps.enable(Experiment::OptimisticProvide);
+    let mut provider = Provider::new(&routing);
+    provider.set_prefix_bits(256).set_experiments(&exps);
+    provider.provide_many(&cids).unwrap();
+    assert!(provider.optimistic);
+    assert!(provider.network_size.is_some());
+    assert!(*routing.rounds.borrow() <= full);
+
+    exps.disable(Experiment::OptimisticProvide);
+    provider.set_experiments(&exps);
+    assert!(!provider.optimistic);
+}
@@ -0,0 +1,475 @@
+//! Module implement dialer, with concurrency limits and address ranking.
+//!
+//! Dialing every known address of every peer, all at once, can melt
+//! small routers. [Dialer] caps the number of in-flight address dials,
+//! both globally and per peer. Addresses of a peer are ranked and dialed
+//! in batches, best first, and the first successful connection wins.
+//!
+//! Failed dials are recorded per peer, the last [DIAL_ERRORS] of them,
+//! along with their cause, refer [Dialer::to_dial_errors]. Cause is
+//! classified at the dial site, from the [io::ErrorKind] of connect.
+//! Upper layers report failed negotiation on a dialed connection using
+//! [Dialer::add_negotiation_error].
+//!
+//! Connection established event is dispatched to [SwarmHooks], refer
+//! [Dialer::set_hooks], for the winning connection. A hook returning
+//! error closes the connection and fails the dial.
+//!
+//! For private networks, refer [Dialer::set_psk], connection is wrapped
+//! with the pre-shared-key before it is handed over to hooks and caller.
+
+use crossbeam_channel as cbm;
+use log::{debug, error};
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt, io, result,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering::SeqCst},
+        Arc, Condvar, Mutex,
+    },
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    ipfsd::{ConnInfo, Direction, SwarmHooks},
+    multiaddr::Multiaddr,
+    net_conn::{Conn, DialBackoff, DIAL_TIMEOUT},
+    net_i2p::{self, SamSession},
+    peer_id::PeerId,
+    pnet::Psk,
+    util::{Clock, SystemClock},
+    Error, Result,
+};
+
+/// Default limit for in-flight address dials, same as go-libp2p.
+pub const GLOBAL_LIMIT: usize = 100;
+
+/// Default limit for in-flight address dials to a single peer, same as
+/// go-libp2p.
+pub const PEER_LIMIT: usize = 8;
+
+/// Default number of dial failures remembered for each peer.
+pub const DIAL_ERRORS: usize = 16;
+
+/// Cause of a failed address dial.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DialErrorKind {
+    /// Remote did not respond within the dial timeout.
+    Timeout,
+    /// Remote refused the connection.
+    Refused,
+    /// Connection was established, but protocol negotiation failed.
+    Negotiation,
+    /// Dial was skipped locally, address is under backoff.
+    Gated,
+    /// Any other failure, refer [DialError::reason].
+    Other,
+}
+
+impl fmt::Display for DialErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
+        match self {
+            DialErrorKind::Timeout => write!(f, "timeout"),
+            DialErrorKind::Refused => write!(f, "refused"),
+            DialErrorKind::Negotiation => write!(f, "negotiation"),
+            DialErrorKind::Gated => write!(f, "gated"),
+            DialErrorKind::Other => write!(f, "other"),
+        }
+    }
+}
+
+impl DialErrorKind {
+    /// Classify a failed connect by its io error kind.
+    pub fn from_io_kind(kind: io::ErrorKind) -> DialErrorKind {
+        match kind {
+            io::ErrorKind::TimedOut => DialErrorKind::Timeout,
+            io::ErrorKind::ConnectionRefused => DialErrorKind::Refused,
+            _ => DialErrorKind::Other,
+        }
+    }
+}
+
+/// Failed address dial, refer [Dialer::to_dial_errors].
+#[derive(Clone, Debug)]
+pub struct DialError {
+    pub addr: Multiaddr,
+    pub kind: DialErrorKind,
+    pub reason: String,
+    pub time: SystemTime,
+}
+
+/// Dialer, shareable across threads.
+pub struct Dialer {
+    global_limit: usize,
+    peer_limit: usize,
+    timeout: Duration,
+
+    pool: Arc<SlotPool>,
+    waiting: AtomicUsize,
+    backoff: Mutex<DialBackoff>,
+    error_limit: usize,
+    errors: Mutex<HashMap<PeerId, VecDeque<DialError>>>,
+    sam: Option<Arc<SamSession>>,
+    psk: Option<Psk>,
+    hooks: SwarmHooks,
+    conn_id: AtomicU64,
+    clock: Arc<dyn Clock>,
+}
+
+#[derive(Default)]
+struct Slots {
+    inflight: usize,
+    peers: HashMap<PeerId, usize>,
+}
+
+// shared with dial threads, each of them release its own slot.
+#[derive(Default)]
+struct SlotPool {
+    slots: Mutex<Slots>,
+    cond: Condvar,
+}
+
+impl SlotPool {
+    fn release(&self, peer: &PeerId, n: usize) -> Result<()> {
+        let mut slots = err_at!(Fatal, self.slots.lock())?;
+        slots.inflight -= n;
+        match slots.peers.get_mut(peer) {
+            Some(m) if *m > n => *m -= n,
+            _ => {
+                slots.peers.remove(peer);
+            }
+        }
+        self.cond.notify_all();
+        Ok(())
+    }
+}
+
+// slot held by a dial thread, released when the thread is done, even
+// if its dial outlives the batch.
+struct SlotGuard {
+    pool: Arc<SlotPool>,
+    peer: PeerId,
+}
+
+impl Drop for SlotGuard {
+    fn drop(&mut self) {
+        if let Err(err) = self.pool.release(&self.peer, 1) {
+            error!("dial slot release {}: {}", self.peer.to_short_string(), err);
+        }
+    }
+}
+
+impl Default for Dialer {
+    fn default() -> Dialer {
+        Dialer::new(GLOBAL_LIMIT, PEER_LIMIT)
+    }
+}
+
+impl Dialer {
+    /// Create a new dialer with `global_limit` and `peer_limit` on
+    /// in-flight address dials. Limits are adjusted to a minimum of 1.
+    pub fn new(global_limit: usize, peer_limit: usize) -> Dialer {
+        Dialer {
+            global_limit: global_limit.max(1),
+            peer_limit: peer_limit.max(1),
+            timeout: DIAL_TIMEOUT,
+
+            pool: Arc::new(SlotPool::default()),
+            waiting: AtomicUsize::new(0),
+            backoff: Mutex::new(DialBackoff::new()),
+            error_limit: DIAL_ERRORS,
+            errors: Mutex::new(HashMap::new()),
+            sam: None,
+            psk: None,
+            hooks: SwarmHooks::default(),
+            conn_id: AtomicU64::new(1),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Set timeout for each address dial, default is [DIAL_TIMEOUT].
+    pub fn set_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set the number of dial failures remembered for each peer, default
+    /// is [DIAL_ERRORS]. Zero disables recording.
+    pub fn set_error_limit(&mut self, limit: usize) -> &mut Self {
+        self.error_limit = limit;
+        self
+    }
+
+    /// Set the SAM session for dialing `/garlic64` and `/garlic32`
+    /// addresses. Without it, i2p addresses fail to dial.
+    pub fn set_sam_session(&mut self, sam: Arc<SamSession>) -> &mut Self {
+        self.sam = Some(sam);
+        self
+    }
+
+    /// Set the pre-shared-key for private network, typically read using
+    /// [Psk::from_repo]. Dialed connections are wrapped in
+    /// [PnetConn][crate::pnet::PnetConn].
+    pub fn set_psk(&mut self, psk: Psk) -> &mut Self {
+        self.psk = Some(psk);
+        self
+    }
+
+    /// Set the clock for dial backoff and for timestamping dial errors,
+    /// default is [SystemClock]. Resets the backoff.
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) -> &mut Self {
+        self.backoff = Mutex::new(DialBackoff::with_clock(Arc::clone(&clock)));
+        self.clock = clock;
+        self
+    }
+
+    /// Set hooks to dispatch connection established event, for outbound
+    /// connections made by this dialer.
+    pub fn set_hooks(&mut self, hooks: SwarmHooks) -> &mut Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Dial `peer` on `addrs`. Addresses are ranked using [rank_addrs],
+    /// those under backoff are skipped, and the rest are dialed in
+    /// batches. Return the first successful connection. Dials that lose
+    /// to it keep their slot until they complete or time out. Fail if a
+    /// hook rejects the connection.
+    pub fn dial(&self, peer: &PeerId, addrs: Vec<Multiaddr>) -> Result<Conn> {
+        let addrs = {
+            let backoff = err_at!(Fatal, self.backoff.lock())?;
+            let mut items = vec![];
+            let mut gated = vec![];
+            for addr in rank_addrs(addrs)?.into_iter() {
+                if backoff.is_backoff(peer, &addr)? {
+                    gated.push(addr)
+                } else {
+                    items.push(addr)
+                }
+            }
+            std::mem::drop(backoff);
+
+            for addr in gated.into_iter() {
+                let reason = "under dial backoff".to_string();
+                self.add_dial_error(peer, addr, DialErrorKind::Gated, reason)?;
+            }
+            items
+        };
+
+        let batch_size = self.peer_limit.min(self.global_limit);
+        for batch in addrs.chunks(batch_size) {
+            self.acquire(peer, batch.len())?;
+            if let Some(conn) = self.dial_batch(peer, batch)? {
+                let conn = match self.psk.as_ref() {
+                    Some(psk) => conn.into_pnet(psk),
+                    None => conn,
+                };
+                return self.establish(peer, conn);
+            }
+        }
+
+        err_at!(IOError, msg: "dial {} failed on all addresses", peer.to_short_string())
+    }
+
+    /// Return the number of dials waiting for a slot.
+    pub fn to_queue_depth(&self) -> usize {
+        self.waiting.load(SeqCst)
+    }
+
+    /// Return the number of in-flight address dials.
+    pub fn to_inflight(&self) -> Result<usize> {
+        Ok(err_at!(Fatal, self.pool.slots.lock())?.inflight)
+    }
+
+    /// Return the recent dial failures for `peer`, oldest first.
+    pub fn to_dial_errors(&self, peer: &PeerId) -> Result<Vec<DialError>> {
+        let errors = err_at!(Fatal, self.errors.lock())?;
+        let val = match errors.get(peer) {
+            Some(items) => items.iter().cloned().collect(),
+            None => vec![],
+        };
+        Ok(val)
+    }
+
+    /// Return the recent dial failures for all peers, typically to
+    /// diagnose connectivity issues.
+    pub fn to_all_dial_errors(&self) -> Result<Vec<(PeerId, Vec<DialError>)>> {
+        let errors = err_at!(Fatal, self.errors.lock())?;
+        let val = errors
+            .iter()
+            .map(|(peer, items)| (peer.clone(), items.iter().cloned().collect()))
+            .collect();
+        Ok(val)
+    }
+
+    /// Record that protocol negotiation, like security or muxer upgrade,
+    /// failed on a connection dialed to `peer` on `addr`. Address is put
+    /// under backoff, same as a failed dial.
+    pub fn add_negotiation_error(&self, peer: &PeerId, addr: Multiaddr, err: &Error) -> Result<()> {
+        err_at!(Fatal, self.backoff.lock())?.add_failure(peer, &addr)?;
+        self.add_dial_error(peer, addr, DialErrorKind::Negotiation, err.to_string())
+    }
+
+    /// Forget recorded dial failures for `peer`.
+    pub fn clear_dial_errors(&self, peer: &PeerId) -> Result<()> {
+        err_at!(Fatal, self.errors.lock())?.remove(peer);
+        Ok(())
+    }
+
+    fn add_dial_error(
+        &self,
+        peer: &PeerId,
+        addr: Multiaddr,
+        kind: DialErrorKind,
+        reason: String,
+    ) -> Result<()> {
+        if self.error_limit == 0 {
+            return Ok(());
+        }
+
+        let mut errors = err_at!(Fatal, self.errors.lock())?;
+        let items = errors.entry(peer.clone()).or_insert_with(VecDeque::new);
+        while items.len() >= self.error_limit {
+            items.pop_front();
+        }
+        let time = self.clock.now_system();
+        items.push_back(DialError {
+            addr,
+            kind,
+            reason,
+            time,
+        });
+        Ok(())
+    }
+
+    // dispatch connection established, closing `conn` if rejected.
+    fn establish(&self, peer: &PeerId, conn: Conn) -> Result<Conn> {
+        if self.hooks.is_empty() {
+            return Ok(conn);
+        }
+
+        let info = ConnInfo {
+            id: self.conn_id.fetch_add(1, SeqCst),
+            peer: peer.clone(),
+            local: conn.to_local_addr()?.to_multiaddr()?,
+            remote: conn.to_remote_multiaddr()?,
+            direction: Direction::Outbound,
+        };
+        match self.hooks.connection_established(&info) {
+            Ok(()) => Ok(conn),
+            Err(err) => {
+                debug!("dial {} rejected: {}", peer.to_short_string(), err);
+                conn.close();
+                Err(err)
+            }
+        }
+    }
+
+    // slots for the batch are acquired by the caller, and handed over to
+    // dial threads.
+    fn dial_batch(&self, peer: &PeerId, batch: &[Multiaddr]) -> Result<Option<Conn>> {
+        let (tx, rx) = cbm::bounded(batch.len());
+        for addr in batch.iter() {
+            let (tx, addr, timeout) = (tx.clone(), addr.clone(), self.timeout);
+            let sam = self.sam.clone();
+            let slot = SlotGuard {
+                pool: Arc::clone(&self.pool),
+                peer: peer.clone(),
+            };
+            thread::spawn(move || {
+                let res = dial_addr(&addr, timeout, sam);
+                std::mem::drop(slot);
+                tx.send((addr, res)).ok();
+            });
+        }
+        std::mem::drop(tx);
+
+        // first successful connection wins, rest of them are dropped
+        // as and when they complete.
+        for (addr, res) in rx.iter() {
+            match res {
+                Ok(conn) => {
+                    err_at!(Fatal, self.backoff.lock())?.clear(peer, &addr)?;
+                    return Ok(Some(conn));
+                }
+                Err((kind, err)) => {
+                    debug!("dial {} {:?}: {}", peer.to_short_string(), addr, err);
+                    err_at!(Fatal, self.backoff.lock())?.add_failure(peer, &addr)?;
+                    self.add_dial_error(peer, addr, kind, err.to_string())?;
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn acquire(&self, peer: &PeerId, n: usize) -> Result<()> {
+        self.waiting.fetch_add(1, SeqCst);
+
+        let mut slots = err_at!(Fatal, self.pool.slots.lock())?;
+        loop {
+            let peer_inflight = slots.peers.get(peer).copied().unwrap_or(0);
+            if (slots.inflight + n) <= self.global_limit && (peer_inflight + n) <= self.peer_limit {
+                break;
+            }
+            slots = err_at!(Fatal, self.pool.cond.wait(slots))?;
+        }
+        slots.inflight += n;
+        *slots.peers.entry(peer.clone()).or_insert(0) += n;
+
+        self.waiting.fetch_sub(1, SeqCst);
+        Ok(())
+    }
+}
+
+// dial a single address, failure is classified here while the io error
+// from connect is at hand.
+fn dial_addr(
+    addr: &Multiaddr,
+    timeout: Duration,
+    sam: Option<Arc<SamSession>>,
+) -> result::Result<Conn, (DialErrorKind, Error)> {
+    let res = match (net_i2p::to_destination(addr), sam) {
+        (Ok(Some(_)), Some(sam)) => sam.dial(addr),
+        (Ok(Some(_)), None) => err_at!(NotImplemented, msg: "no sam session for {:?}", addr),
+        _ => {
+            let res = Conn::connect_multiaddr(addr.clone(), timeout);
+            let kind = match &res {
+                Ok(_) => DialErrorKind::Other,
+                Err(err) => DialErrorKind::from_io_kind(err.kind()),
+            };
+            return err_at!(IOError, res, "dial {:?}", addr).map_err(|err| (kind, err));
+        }
+    };
+    res.map_err(|err| (DialErrorKind::Other, err))
+}
+
+/// Rank addresses for dialing, best first. Direct addresses are
+/// preferred over relayed ones, and among them QUIC over TCP over other
+/// transports. Order of equally ranked addresses is preserved.
+pub fn rank_addrs(addrs: Vec<Multiaddr>) -> Result<Vec<Multiaddr>> {
+    let mut items = vec![];
+    for addr in addrs.into_iter() {
+        let comps = addr.clone().split()?;
+        let relay = comps
+            .iter()
+            .any(|c| matches!(c, Multiaddr::P2pCircuit(_, _)));
+        let transport = if comps.iter().any(|c| matches!(c, Multiaddr::Quic(_, _))) {
+            0
+        } else if comps.iter().any(|c| matches!(c, Multiaddr::Tcp(_, _))) {
+            1
+        } else {
+            2
+        };
+        items.push(((relay, transport), addr));
+    }
+    items.sort_by_key(|(rank, _)| *rank);
+
+    Ok(items.into_iter().map(|(_, addr)| addr).collect())
+}
+
+#[cfg(test)]
+#[path = "dialer_test.rs"]
+mod dialer_test;
@@ -0,0 +1,291 @@
+use std::{io, net};
+
+use super::*;
+
+#[test]
+fn test_rank_addrs() {
+    let addrs: Vec<Multiaddr> = vec![
+        "/ip4/1.2.3.4/tcp/4001/p2p-circuit",
+        "/ip4/1.2.3.4/tcp/4001",
+        "/ip4/1.2.3.4/udp/4001/quic",
+        "/ip4/1.2.3.4/tcp/4002",
+        "/dns4/example.com/tcp/443/wss",
+        "/ip4/1.2.3.4/udp/4001/utp",
+    ]
+    .into_iter()
+    .map(|s| Multiaddr::from_text(s).unwrap())
+    .collect();
+
+    let ranked: Vec<usize> = rank_addrs(addrs.clone())
+        .unwrap()
+        .into_iter()
+        .map(|a| addrs.iter().position(|b| *b == a).unwrap())
+        .collect();
+    assert_eq!(ranked, vec![2, 1, 3, 4, 5, 0]);
+}
+
+fn wait_inflight(dialer: &Dialer, n: usize) {
+    let deadline = std::time::Instant::now() + Duration::from_secs(5);
+    while dialer.to_inflight().unwrap() != n {
+        assert!(std::time::Instant::now() < deadline);
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn test_dialer() {
+    let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let peer = PeerId::generate().unwrap();
+    let addrs = vec![
+        Multiaddr::from_text(&format!("/ip4/127.0.0.1/tcp/{}", port)).unwrap(),
+        Multiaddr::from_text("/ip4/127.0.0.1/udp/4001/quic").unwrap(),
+    ];
+
+    let dialer = Dialer::new(4, 1);
+    dialer.dial(&peer, addrs.clone()).unwrap();
+    assert_eq!(dialer.to_queue_depth(), 0);
+    wait_inflight(&dialer, 0);
+
+    // quic address failed and is under backoff, tcp address is not.
+    let backoff = dialer.backoff.lock().unwrap();
+    assert!(!backoff.is_backoff(&peer, &addrs[0]).unwrap());
+    assert!(backoff.is_backoff(&peer, &addrs[1]).unwrap());
+}
+
+#[test]
+fn test_dial_errors() {
+    let port = {
+        let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap().port()
+    };
+
+    let peer = PeerId::generate().unwrap();
+    let addrs = vec![
+        Multiaddr::from_text(&format!("/ip4/127.0.0.1/tcp/{}", port)).unwrap(),
+        Multiaddr::from_text("/ip4/127.0.0.1/udp/4001/quic").unwrap(),
+    ];
+
+    let mut dialer = Dialer::new(4, 4);
+    dialer.set_error_limit(3);
+    assert!(dialer.dial(&peer, addrs.clone()).is_err());
+
+    let mut kinds: Vec<(usize, DialErrorKind)> = dialer
+        .to_dial_errors(&peer)
+        .unwrap()
+        .into_iter()
+        .map(|e| (addrs.iter().position(|a| *a == e.addr).unwrap(), e.kind))
+        .collect();
+    kinds.sort_by_key(|(i, _)| *i);
+    assert_eq!(
+        kinds,
+        vec![(0, DialErrorKind::Refused), (1, DialErrorKind::Other)]
+    );
+
+    // both addresses are under backoff, only the last 3 are remembered.
+    assert!(dialer.dial(&peer, addrs).is_err());
+    let errs = dialer.to_dial_errors(&peer).unwrap();
+    assert_eq!(errs.len(), 3);
+    assert_eq!(errs[1].kind, DialErrorKind::Gated);
+    assert_eq!(errs[2].kind, DialErrorKind::Gated);
+    assert_eq!(errs[2].kind.to_string(), "gated");
+    assert_eq!(dialer.to_all_dial_errors().unwrap().len(), 1);
+
+    dialer.clear_dial_errors(&peer).unwrap();
+    assert!(dialer.to_dial_errors(&peer).unwrap().is_empty());
+}
+
+#[test]
+fn test_dial_slots() {
+    let peer = PeerId::generate().unwrap();
+    let dialer = Dialer::new(4, 2);
+
+    // dial threads release their own slot, as and when they complete.
+    dialer.acquire(&peer, 2).unwrap();
+    let mut guards: Vec<SlotGuard> = (0..2)
+        .map(|_| SlotGuard {
+            pool: Arc::clone(&dialer.pool),
+            peer: peer.clone(),
+        })
+        .collect();
+    assert_eq!(dialer.to_inflight().unwrap(), 2);
+
+    guards.pop();
+    assert_eq!(dialer.to_inflight().unwrap(), 1);
+    assert_eq!(dialer.pool.slots.lock().unwrap().peers.get(&peer), Some(&1));
+    guards.pop();
+    assert_eq!(dialer.to_inflight().unwrap(), 0);
+    assert!(dialer.pool.slots.lock().unwrap().peers.is_empty());
+
+    dialer.acquire(&peer, 2).unwrap();
+    assert_eq!(dialer.to_inflight().unwrap(), 2);
+}
+
+#[test]
+fn test_dial_hooks() {
+    use std::io::Read;
+
+    let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let addr = Multiaddr::from_text(&format!("/ip4/127.0.0.1/tcp/{}", port)).unwrap();
+
+    let (good, bad) = (PeerId::generate().unwrap(), PeerId::generate().unwrap());
+    let events = Arc::new(Mutex::new(vec![]));
+    let mut hooks = SwarmHooks::new();
+    {
+        let (events, good) = (Arc::clone(&events), good.clone());
+        hooks.on_connection_established(move |conn| {
+            let event = (conn.id, conn.remote.clone(), conn.direction);
+            events.lock().unwrap().push(event);
+            match conn.peer == good {
+                true => Ok(()),
+                false => err_at!(Invalid, msg: "peer vetoed"),
+            }
+        });
+    }
+    let mut dialer = Dialer::new(4, 4);
+    dialer.set_hooks(hooks);
+
+    let _conn = dialer.dial(&good, vec![addr.clone()]).unwrap();
+    let msg = dialer
+        .dial(&bad, vec![addr.clone()])
+        .unwrap_err()
+        .to_string();
+    assert!(msg.contains("peer vetoed"), "{}", msg);
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![
+            (1, addr.clone(), Direction::Outbound),
+            (2, addr, Direction::Outbound)
+        ]
+    );
+
+    // vetoed connection is closed.
+    let _ = listener.accept().unwrap();
+    let (mut rejected, _) = listener.accept().unwrap();
+    let mut buf = vec![];
+    assert_eq!(rejected.read_to_end(&mut buf).unwrap(), 0);
+}
+
+#[test]
+fn test_dial_error_kinds() {
+    use crate::util::MockClock;
+
+    let testcases = vec![
+        (io::ErrorKind::TimedOut, DialErrorKind::Timeout),
+        (io::ErrorKind::ConnectionRefused, DialErrorKind::Refused),
+        (io::ErrorKind::InvalidInput, DialErrorKind::Other),
+        (io::ErrorKind::AddrNotAvailable, DialErrorKind::Other),
+    ];
+    for (kind, dial_kind) in testcases.into_iter() {
+        assert_eq!(DialErrorKind::from_io_kind(kind), dial_kind, "{:?}", kind);
+    }
+
+    let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let addr = Multiaddr::from_text(&format!("/ip4/127.0.0.1/tcp/{}", port)).unwrap();
+    let peer = PeerId::generate().unwrap();
+
+    let clock = MockClock::new();
+    let mut dialer = Dialer::new(4, 4);
+    dialer.set_clock(Arc::new(clock.clone()));
+    dialer.dial(&peer, vec![addr.clone()]).unwrap();
+
+    // negotiation failed on the dialed connection, address backs off
+    // until the clock moves past it.
+    clock.advance(Duration::from_secs(1));
+    let err = err_at!(DecodeError, msg: "bad noise handshake").unwrap_err();
+    dialer
+        .add_negotiation_error(&peer, addr.clone(), &err)
+        .unwrap();
+    let errs = dialer.to_dial_errors(&peer).unwrap();
+    assert_eq!(errs.len(), 1);
+    assert_eq!(errs[0].kind, DialErrorKind::Negotiation);
+    assert!(errs[0].reason.contains("bad noise handshake"));
+    assert_eq!(errs[0].time, clock.now_system());
+
+    assert!(dialer.dial(&peer, vec![addr.clone()]).is_err());
+    assert_eq!(
+        dialer.to_dial_errors(&peer).unwrap()[1].kind,
+        DialErrorKind::Gated
+    );
+    clock.advance(crate::net_conn::BACKOFF_MAX);
+    dialer.dial(&peer, vec![addr]).unwrap();
+}
+
+// protocol that upgrades a connection to its own protocol path.
+#[derive(Clone)]
+struct Proto(&'static str);
+
+impl crate::multistream::Protocol<Conn, String> for Proto {
+    fn to_proto_path(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn try_match(&self, proto: &str) -> bool {
+        self.0 == proto
+    }
+
+    fn upgrade(self, _conn: Conn) -> String {
+        self.0.to_string()
+    }
+}
+
+// a peer with the wrong key never sends a valid frame, don't wait on it.
+fn set_read_timeout(conn: &Conn) {
+    match conn {
+        Conn::Pnet { conn } => set_read_timeout(conn.as_inner()),
+        Conn::Tcp { conn, .. } => conn.set_read_timeout(Some(Duration::from_secs(1))).unwrap(),
+        _ => unreachable!(),
+    }
+}
+
+// dial with `dial_key` a listener accepting with `accept_key`, and
+// negotiate multistream on the connection, return the dialer's outcome
+// along with the listener's.
+fn pnet_negotiate(dial_key: &str, accept_key: &str) -> (Result<()>, Result<()>) {
+    use crate::{multistream::Multistream, net_conn::Listener};
+
+    type Select = Multistream<Conn, String, Proto>;
+
+    let ma = Multiaddr::from_text("/ip4/127.0.0.1/tcp/0").unwrap();
+    let listener = Listener::bind_multiaddr(ma).unwrap();
+    let addr = listener.to_local_addr().unwrap().to_multiaddr().unwrap();
+
+    let psk = Psk::from_swarm_key(accept_key.as_bytes()).unwrap();
+    let handle = thread::spawn(move || {
+        let mut conn = listener.accept_with(Some(&psk))?;
+        assert!(conn.is_pnet());
+        set_read_timeout(&conn);
+        let mut select = Select::new_v1(None);
+        select.add_handler(Proto("/ipfs/id/1.0.0"));
+        select.negotiate(&mut conn)
+    });
+
+    let mut dialer = Dialer::new(4, 4);
+    dialer.set_psk(Psk::from_swarm_key(dial_key.as_bytes()).unwrap());
+    let peer = PeerId::generate().unwrap();
+    let mut conn = dialer.dial(&peer, vec![addr]).unwrap();
+    assert!(conn.is_pnet());
+    set_read_timeout(&conn);
+    let res = Select::new_v1(Some(Proto("/ipfs/id/1.0.0"))).negotiate(&mut conn);
+
+    (res, handle.join().unwrap())
+}
+
+#[test]
+fn test_dial_pnet() {
+    let key = "/key/swarm/psk/1.0.0/\n/base16/\n\
+        e5cb10b6ba1a4d2a1f1e9c2a8c64ca1e6c8bc10a2f5fd5e4d5ad2fb9b2c1d3e4\n";
+    let other = key.replace("e5cb", "0000");
+
+    let (dialer, listener) = pnet_negotiate(key, key);
+    dialer.unwrap();
+    listener.unwrap();
+
+    // peers with mismatched keys fail the handshake.
+    let (dialer, listener) = pnet_negotiate(key, &other);
+    assert!(dialer.is_err());
+    assert!(listener.is_err());
+}
@@ -0,0 +1,153 @@
+//! Module implement [Arbitrary] for types defined by this package.
+//!
+//! Enabled with `arbitrary` feature, so that downstream crates can fuzz
+//! code paths consuming these types without writing generators by hand.
+//! Generated values are always valid, for example a generated Multihash
+//! carries the digest of generated data and a generated Multiaddr can be
+//! encoded and parsed back.
+
+use arbitrary::{Arbitrary, Unstructured};
+use multibase::Base;
+
+use std::{collections::BTreeMap, net};
+
+use crate::{
+    cid::Cid,
+    ipld::kind::{Basic, Key, Node},
+    multiaddr::Multiaddr,
+    multicodec::{self, Multicodec},
+    multihash::Multihash,
+};
+
+/// Maximum nesting of generated list and map values.
+pub const MAX_DEPTH: usize = 4;
+
+const BASES: [Base; 6] = [
+    Base::Base16Lower,
+    Base::Base32Lower,
+    Base::Base32Upper,
+    Base::Base58Btc,
+    Base::Base64,
+    Base::Base64Url,
+];
+
+impl<'a> Arbitrary<'a> for Multicodec {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let cp = u.choose(&multicodec::TABLE)?;
+        Ok(cp.code.into())
+    }
+}
+
+impl<'a> Arbitrary<'a> for Multihash {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let codes = multicodec::multihash_codes();
+        let code = *u.choose(&codes)?;
+        let data: Vec<u8> = u.arbitrary()?;
+
+        // not all hash algorithms in the table are supported, fall back
+        // to sha2-256.
+        match Multihash::new(code.into(), &data) {
+            Ok(mh) => Ok(mh),
+            Err(_) => match Multihash::new(multicodec::SHA2_256.into(), &data) {
+                Ok(mh) => Ok(mh),
+                Err(_) => Err(arbitrary::Error::IncorrectFormat),
+            },
+        }
+    }
+}
+
+impl<'a> Arbitrary<'a> for Cid {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let cid = match u.arbitrary::<bool>()? {
+            true => {
+                let data: Vec<u8> = u.arbitrary()?;
+                match Multihash::new(multicodec::SHA2_256.into(), &data) {
+                    Ok(mh) => Cid::Zero(mh),
+                    Err(_) => return Err(arbitrary::Error::IncorrectFormat),
+                }
+            }
+            false => {
+                let base = u.choose(&BASES)?.clone();
+                Cid::from_raw(base, u.arbitrary()?, u.arbitrary()?)
+            }
+        };
+        Ok(cid)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Multiaddr {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let port: u16 = u.arbitrary()?;
+        let text = match u.int_in_range(0..=3)? {
+            0 => {
+                let ip = net::Ipv4Addr::from(u.arbitrary::<[u8; 4]>()?);
+                format!("/ip4/{}/tcp/{}", ip, port)
+            }
+            1 => {
+                let ip = net::Ipv6Addr::from(u.arbitrary::<[u16; 8]>()?);
+                format!("/ip6/{}/tcp/{}", ip, port)
+            }
+            2 => {
+                let ip = net::Ipv4Addr::from(u.arbitrary::<[u8; 4]>()?);
+                format!("/ip4/{}/udp/{}/quic", ip, port)
+            }
+            _ => {
+                let n = u.int_in_range(1..=16)?;
+                let mut name = String::default();
+                for _ in 0..n {
+                    name.push(*u.choose(b"abcdefghijklmnopqrstuvwxyz0123456789")? as char);
+                }
+                format!("/dns4/{}.com/tcp/{}", name, port)
+            }
+        };
+
+        match Multiaddr::from_text(&text) {
+            Ok(maddr) => Ok(maddr),
+            Err(_) => Err(arbitrary::Error::IncorrectFormat),
+        }
+    }
+}
+
+impl<'a> Arbitrary<'a> for Basic {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        arbitrary_basic(u, 0)
+    }
+}
+
+fn arbitrary_basic(u: &mut Unstructured, depth: usize) -> arbitrary::Result<Basic> {
+    let n = match depth {
+        depth if depth < MAX_DEPTH => 8,
+        _ => 6,
+    };
+
+    let val = match u.int_in_range(0..=n)? {
+        0 => Basic::Null,
+        1 => Basic::Bool(u.arbitrary()?),
+        2 => Basic::Integer(u.arbitrary::<i64>()?.into()),
+        3 => Basic::Float(u.arbitrary()?),
+        4 => Basic::Text(u.arbitrary::<String>()?.into_bytes()),
+        5 => Basic::Bytes(u.arbitrary()?),
+        6 => Basic::Link(u.arbitrary()?),
+        7 => {
+            let mut list: Vec<Box<dyn Node>> = vec![];
+            for _ in 0..u.int_in_range(0..=4)? {
+                list.push(Box::new(arbitrary_basic(u, depth + 1)?));
+            }
+            Basic::List(Box::new(list))
+        }
+        _ => {
+            let mut dict: BTreeMap<Key, Box<dyn Node>> = BTreeMap::new();
+            for _ in 0..u.int_in_range(0..=4)? {
+                let key: String = u.arbitrary()?;
+                dict.insert(Key::Text(key), Box::new(arbitrary_basic(u, depth + 1)?));
+            }
+            Basic::Map(Box::new(dict))
+        }
+    };
+
+    Ok(val)
+}
+
+#[cfg(test)]
+#[path = "fuzz_test.rs"]
+mod fuzz_test;
@@ -0,0 +1,34 @@
+use rand::{prelude::random, rngs::SmallRng, Rng, SeedableRng};
+
+use super::*;
+
+#[test]
+fn test_arbitrary() {
+    let seed: u64 = random();
+    println!("test_arbitrary seed {}", seed);
+    let mut rng = SmallRng::seed_from_u64(seed);
+
+    for _ in 0..100 {
+        let bytes: Vec<u8> = (0..1024).map(|_| rng.gen()).collect();
+        let mut u = Unstructured::new(&bytes);
+
+        let cid: Cid = u.arbitrary().unwrap();
+        let data = cid.encode().unwrap();
+        let (val, rem) = Cid::decode(&data).unwrap();
+        assert_eq!(val.encode().unwrap(), data);
+        assert_eq!(rem.len(), 0);
+
+        // generated multiaddrs are always valid, refer module doc.
+        let maddr: Multiaddr = u.arbitrary().unwrap();
+        let text = maddr.to_text().unwrap();
+        let other = Multiaddr::from_text(&text).unwrap();
+        assert_eq!(other.to_text().unwrap(), text);
+        let data = maddr.encode().unwrap();
+        let (other, rem) = Multiaddr::decode(&data).unwrap();
+        assert_eq!(other.encode().unwrap(), data);
+        assert_eq!(rem.len(), 0);
+
+        let val: Basic = u.arbitrary().unwrap();
+        val.to_kind();
+    }
+}
@@ -53,3 +53,74 @@ fn ed25519_api() {
 //        .to_public_key()
 //        .verify("hello world".as_bytes(), &signature))
 //}
+
+#[test]
+fn ed25519_protobuf_roundtrip() {
+    use prost::Message;
+
+    let kp = Keypair::generate_ed25519().unwrap();
+    let peer_id = kp.to_public_key().into_peer_id().unwrap();
+
+    let text = kp.to_base64_protobuf().unwrap();
+    let kp = Keypair::from_base64_protobuf(&text).unwrap();
+    assert_eq!(kp.to_public_key().into_peer_id().unwrap(), peer_id);
+
+    // legacy go-libp2p encoding, with redundant public-key appended to
+    // the 64 byte secret-key + public-key.
+    let data = kp.into_protobuf_encoding().unwrap();
+    let mut privkey = key_pair_proto::PrivateKey::decode(data.as_slice()).unwrap();
+    assert_eq!(privkey.data.len(), 64);
+    let public_key = privkey.data[32..].to_vec();
+    privkey.data.extend_from_slice(&public_key);
+    let mut data = Vec::with_capacity(privkey.encoded_len());
+    privkey.encode(&mut data).unwrap();
+    let kp = Keypair::from_protobuf_encoding(&data).unwrap();
+    assert_eq!(kp.to_public_key().into_peer_id().unwrap(), peer_id);
+}
+
+#[test]
+fn rsa_protobuf_roundtrip() {
+    let kp = Keypair::from_rsa_pkcs8(&mut RSA_KEY.to_vec()).unwrap();
+    let peer_id = kp.to_public_key().into_peer_id().unwrap();
+
+    let data = kp.into_protobuf_encoding().unwrap();
+    let kp = Keypair::from_protobuf_encoding(&data).unwrap();
+    assert_eq!(kp.to_public_key().into_peer_id().unwrap(), peer_id);
+    assert_eq!(kp.into_protobuf_encoding().unwrap(), data);
+
+    let signature = kp.sign(b"hello world").unwrap();
+    let kp = Keypair::from_base64_protobuf(&kp.to_base64_protobuf().unwrap()).unwrap();
+    assert!(kp.to_public_key().verify(b"hello world", &signature));
+}
+
+#[test]
+fn did_key_roundtrip() {
+    let pk = Keypair::generate_ed25519().unwrap().to_public_key();
+    let did = pk.to_did_key().unwrap();
+    // multicodec prefix 0xed01 always encodes to z6Mk in base58btc.
+    assert!(did.starts_with("did:key:z6Mk"), "{}", did);
+    assert_eq!(PublicKey::from_did_key(&did).unwrap(), pk);
+
+    #[cfg(feature = "secp256k1")]
+    {
+        let pk = Keypair::generate_secp256k1().unwrap().to_public_key();
+        let did = pk.to_did_key().unwrap();
+        assert!(did.starts_with("did:key:zQ3s"), "{}", did);
+        assert_eq!(PublicKey::from_did_key(&did).unwrap(), pk);
+    }
+
+    assert!(PublicKey::from_did_key("did:web:example.com").is_err());
+
+    // p256-pub, from the did:key spec test vectors.
+    let did = "did:key:zDnaerDaTF5BXEavCrfRZEk316dpbLsfPDZ3WJ5hRTPFU2169";
+    let err = PublicKey::from_did_key(did).unwrap_err().to_string();
+    assert!(err.contains("p256-pub"), "{}", err);
+}
+
+#[test]
+fn public_key_fingerprint() {
+    let pk = Keypair::generate_ed25519().unwrap().to_public_key();
+    let mh = pk.fingerprint().unwrap();
+    assert_eq!(mh.to_codec().unwrap().to_code(), multicodec::SHA2_256);
+    assert!(mh == pk.fingerprint().unwrap());
+}
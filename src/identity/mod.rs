@@ -21,14 +21,18 @@
 //! A node's network identity, its public-key is its identity.
 
 pub mod ed25519;
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(any(not(target_arch = "wasm32"), feature = "rsa-pure"))]
 pub mod rsa;
 #[cfg(feature = "secp256k1")]
 pub mod secp256k1;
 
-use crate::{pb::key_pair_proto, peer_id::PeerId, Error, Result};
-
-// TODO: implement protobuf store for Private-key/Secret-key.
+use crate::{
+    multicodec::{self, Multicodec},
+    multihash::Multihash,
+    pb::key_pair_proto,
+    peer_id::PeerId,
+    Error, Result,
+};
 
 /// Identity keypair of a node.
 ///
@@ -120,6 +124,97 @@ impl Keypair {
         }
     }
 
+    /// Decode a keypair from a protobuf PrivateKey structure. This is the
+    /// format used by go-ipfs to store keys in its `keystore` directory.
+    pub fn from_protobuf_encoding(bytes: &[u8]) -> Result<Keypair> {
+        use prost::Message;
+        use zeroize::Zeroize;
+
+        let mut privkey = err_at!(DecodeError, key_pair_proto::PrivateKey::decode(bytes))?;
+
+        let key_type = match key_pair_proto::KeyType::from_i32(privkey.r#type) {
+            Some(typ) => Ok(typ),
+            None => err_at!(DecodeError, msg: "unknown key type: {}", privkey.r#type),
+        }?;
+
+        let res = match key_type {
+            key_pair_proto::KeyType::Ed25519 => match privkey.data.len() {
+                // older go-libp2p appended a redundant copy of the public-key.
+                96 if privkey.data[32..64] != privkey.data[64..] => {
+                    err_at!(DecodeError, msg: "Ed25519 redundant public key mismatch")
+                }
+                64 | 96 => ed25519::Keypair::decode(&mut privkey.data[..64]).map(Keypair::Ed25519),
+                n => err_at!(DecodeError, msg: "Ed25519 private key length {}", n),
+            },
+            #[cfg(not(target_arch = "wasm32"))]
+            key_pair_proto::KeyType::Rsa => {
+                rsa::Keypair::from_pkcs1(&mut privkey.data).map(Keypair::Rsa)
+            }
+            #[cfg(target_arch = "wasm32")]
+            key_pair_proto::KeyType::Rsa => {
+                err_at!(DecodeError, msg: "RSA disabled at compile-time")
+            }
+            #[cfg(feature = "secp256k1")]
+            key_pair_proto::KeyType::Secp256k1 => {
+                let secret_key = secp256k1::SecretKey::from_bytes(&mut privkey.data)?;
+                Ok(Keypair::Secp256k1(secp256k1::Keypair::from(secret_key)))
+            }
+            #[cfg(not(feature = "secp256k1"))]
+            key_pair_proto::KeyType::Secp256k1 => {
+                err_at!(DecodeError, msg: "secp256k1 disabled at compile-time")
+            }
+        };
+
+        privkey.data.zeroize();
+        res
+    }
+
+    /// Encode the keypair into a protobuf PrivateKey structure, refer
+    /// [Self::from_protobuf_encoding] for details. RSA keys are encoded
+    /// as PKCS#1 RSAPrivateKey, same as go-libp2p.
+    pub fn into_protobuf_encoding(&self) -> Result<Vec<u8>> {
+        use prost::Message;
+
+        let privkey = match self {
+            Keypair::Ed25519(pair) => key_pair_proto::PrivateKey {
+                r#type: key_pair_proto::KeyType::Ed25519 as i32,
+                data: pair.encode().to_vec(),
+            },
+            #[cfg(not(target_arch = "wasm32"))]
+            Keypair::Rsa(pair) => key_pair_proto::PrivateKey {
+                r#type: key_pair_proto::KeyType::Rsa as i32,
+                data: pair.encode_pkcs1(),
+            },
+            #[cfg(feature = "secp256k1")]
+            Keypair::Secp256k1(pair) => key_pair_proto::PrivateKey {
+                r#type: key_pair_proto::KeyType::Secp256k1 as i32,
+                data: pair.as_secret_key().to_bytes().to_vec(),
+            },
+        };
+
+        let mut buf = Vec::with_capacity(privkey.encoded_len());
+        err_at!(EncodeError, privkey.encode(&mut buf))?;
+        Ok(buf)
+    }
+
+    /// Decode a keypair from base64 encoded protobuf PrivateKey, as found
+    /// in the `Identity.PrivKey` field of go-ipfs config file.
+    pub fn from_base64_protobuf(text: &str) -> Result<Keypair> {
+        let mut bytes = err_at!(DecodeError, data_encoding::BASE64.decode(text.as_bytes()))?;
+        let res = Self::from_protobuf_encoding(&bytes);
+        {
+            use zeroize::Zeroize;
+            bytes.zeroize();
+        }
+        res
+    }
+
+    /// Encode the keypair into base64 encoded protobuf PrivateKey, refer
+    /// [Self::from_base64_protobuf] for details.
+    pub fn to_base64_protobuf(&self) -> Result<String> {
+        Ok(data_encoding::BASE64.encode(&self.into_protobuf_encoding()?))
+    }
+
     pub fn try_clone(&self) -> Result<Self> {
         use Keypair::*;
 
@@ -137,7 +232,7 @@ impl Keypair {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum PublicKey {
     Ed25519(ed25519::PublicKey),
-    #[cfg(not(target_arch = "wasm32"))]
+    #[cfg(any(not(target_arch = "wasm32"), feature = "rsa-pure"))]
     Rsa(rsa::PublicKey),
     #[cfg(feature = "secp256k1")]
     Secp256k1(secp256k1::PublicKey),
@@ -153,7 +248,7 @@ impl PublicKey {
 
         match self {
             Ed25519(pk) => pk.verify(msg, sig),
-            #[cfg(not(target_arch = "wasm32"))]
+            #[cfg(any(not(target_arch = "wasm32"), feature = "rsa-pure"))]
             Rsa(pk) => pk.verify(msg, sig),
             #[cfg(feature = "secp256k1")]
             Secp256k1(pk) => pk.verify(msg, sig),
@@ -170,7 +265,7 @@ impl PublicKey {
                 r#type: key_pair_proto::KeyType::Ed25519 as i32,
                 data: key.encode().to_vec(),
             },
-            #[cfg(not(target_arch = "wasm32"))]
+            #[cfg(any(not(target_arch = "wasm32"), feature = "rsa-pure"))]
             PublicKey::Rsa(key) => key_pair_proto::PublicKey {
                 r#type: key_pair_proto::KeyType::Rsa as i32,
                 data: key.encode_x509()?,
@@ -204,11 +299,11 @@ impl PublicKey {
             key_pair_proto::KeyType::Ed25519 => {
                 ed25519::PublicKey::decode(&pubkey.data).map(PublicKey::Ed25519)
             }
-            #[cfg(not(target_arch = "wasm32"))]
+            #[cfg(any(not(target_arch = "wasm32"), feature = "rsa-pure"))]
             key_pair_proto::KeyType::Rsa => {
                 rsa::PublicKey::decode_x509(&pubkey.data).map(PublicKey::Rsa)
             }
-            #[cfg(target_arch = "wasm32")]
+            #[cfg(all(target_arch = "wasm32", not(feature = "rsa-pure")))]
             key_pair_proto::KeyType::Rsa => {
                 err_at!(DecodeError, msg: "RSA disabled at compile-time")
             }
@@ -227,6 +322,57 @@ impl PublicKey {
     pub fn into_peer_id(self) -> Result<PeerId> {
         PeerId::from_public_key(self)
     }
+
+    /// Return the fingerprint for this public key, which is the SHA2-256
+    /// multihash of its protobuf encoding.
+    pub fn fingerprint(&self) -> Result<Multihash> {
+        let data = self.clone().into_protobuf_encoding()?;
+        Multihash::new(multicodec::SHA2_256.into(), &data)
+    }
+
+    /// Encode this public key as a [did:key] identifier, raw key bytes
+    /// prefixed with its multicodec and encoded in base58btc.
+    ///
+    /// [did:key]: https://w3c-ccg.github.io/did-method-key/
+    pub fn to_did_key(&self) -> Result<String> {
+        let (code, data) = match self {
+            PublicKey::Ed25519(key) => (multicodec::ED25519_PUB, key.encode().to_vec()),
+            #[cfg(any(not(target_arch = "wasm32"), feature = "rsa-pure"))]
+            PublicKey::Rsa(_) => err_at!(NotImplemented, msg: "did:key for RSA")?,
+            #[cfg(feature = "secp256k1")]
+            PublicKey::Secp256k1(key) => (multicodec::SECP256K1_PUB, key.encode().to_vec()),
+        };
+
+        let mut bytes = Multicodec::from_code(code)?.encode()?;
+        bytes.extend_from_slice(&data);
+        Ok("did:key:z".to_string() + &bs58::encode(bytes).into_string())
+    }
+
+    /// Decode a public key from [did:key] identifier, refer
+    /// [PublicKey::to_did_key] for details. Only `ed25519-pub` and
+    /// `secp256k1-pub` keys are supported. `p256-pub` fails with
+    /// NotImplemented, since there is no P-256 key type, and any other
+    /// codec fails with BadCodec.
+    ///
+    /// [did:key]: https://w3c-ccg.github.io/did-method-key/
+    pub fn from_did_key(did: &str) -> Result<PublicKey> {
+        let text = match did.strip_prefix("did:key:z") {
+            Some(text) => Ok(text),
+            None => err_at!(BadInput, msg: "not a base58btc did:key {}", did),
+        }?;
+        let bytes = err_at!(DecodeError, bs58::decode(text.as_bytes()).into_vec())?;
+        let (codec, data) = Multicodec::decode(&bytes)?;
+
+        match codec.to_code() {
+            multicodec::ED25519_PUB => ed25519::PublicKey::decode(data).map(PublicKey::Ed25519),
+            #[cfg(feature = "secp256k1")]
+            multicodec::SECP256K1_PUB => {
+                secp256k1::PublicKey::decode(data).map(PublicKey::Secp256k1)
+            }
+            multicodec::P256_PUB => err_at!(NotImplemented, msg: "did:key with {}", codec),
+            _ => err_at!(BadCodec, msg: "did:key with {}", codec),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -19,18 +19,26 @@
 // DEALINGS IN THE SOFTWARE.
 
 //! RSA keys
+//!
+//! On wasm targets, with `rsa-pure` feature, only public keys are
+//! supported, using the pure-rust [rsa] crate to verify signatures.
 
 use asn1_der::{Asn1Der, Asn1DerError, DerObject, DerTag, DerValue, FromDerObject, IntoDerObject};
 use lazy_static::lazy_static;
+#[cfg(not(target_arch = "wasm32"))]
 use ring::rand::SystemRandom;
+#[cfg(not(target_arch = "wasm32"))]
 use ring::signature::KeyPair;
+#[cfg(not(target_arch = "wasm32"))]
 use ring::signature::{RsaKeyPair, RSA_PKCS1_2048_8192_SHA256, RSA_PKCS1_SHA256};
-use zeroize::Zeroize;
+#[cfg(not(target_arch = "wasm32"))]
+use zeroize::{Zeroize, Zeroizing};
 
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::Arc;
 use std::{
     fmt::{self, Write},
     result,
-    sync::Arc,
 };
 
 use crate::{Error, Result};
@@ -38,11 +46,15 @@ use crate::{Error, Result};
 // TODO: should we zeroize Keypair upon Drop ?
 
 /// An RSA keypair.
+#[cfg(not(target_arch = "wasm32"))]
 #[derive(Clone)]
 pub struct Keypair {
     key_pair: Arc<RsaKeyPair>,
+    // PKCS#1 RSAPrivateKey, ring doesn't hand out the private key.
+    pkcs1: Arc<Zeroizing<Vec<u8>>>,
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl Keypair {
     /// Decode an RSA keypair from a DER-encoded private key in PKCS#8
     /// PrivateKeyInfo format (i.e. unencrypted) as defined in [RFC5208].
@@ -52,15 +64,47 @@ impl Keypair {
         let key_pair = match RsaKeyPair::from_pkcs8(&der) {
             Ok(val) => Ok(val),
             Err(err) => err_at!(DecodeError, Err(err), "RSA PKCS#8 PrivateKeyInfo"),
-        }?;
+        };
+        let pkcs1 = key_pair.and_then(|key_pair| {
+            let pkcs1 = pkcs8_to_pkcs1(der)?;
+            Ok((key_pair, pkcs1))
+        });
 
         der.zeroize();
 
+        let (key_pair, pkcs1) = pkcs1?;
         Ok(Keypair {
             key_pair: Arc::new(key_pair),
+            pkcs1: Arc::new(pkcs1),
         })
     }
 
+    /// Decode an RSA keypair from a DER-encoded private key in PKCS#1
+    /// RSAPrivateKey format as defined in [RFC3447]. This is the format
+    /// used by go-libp2p to serialize RSA private keys.
+    ///
+    /// [RFC3447]: https://tools.ietf.org/html/rfc3447#appendix-A.1.2
+    pub fn from_pkcs1(der: &mut [u8]) -> Result<Keypair> {
+        let key_pair = match RsaKeyPair::from_der(&der) {
+            Ok(val) => Ok(val),
+            Err(err) => err_at!(DecodeError, Err(err), "RSA PKCS#1 RSAPrivateKey"),
+        };
+        let pkcs1 = Zeroizing::new(der.to_vec());
+
+        der.zeroize();
+
+        Ok(Keypair {
+            key_pair: Arc::new(key_pair?),
+            pkcs1: Arc::new(pkcs1),
+        })
+    }
+
+    /// Encode the private key in DER as a PKCS#1 RSAPrivateKey structure,
+    /// refer [Self::from_pkcs1].
+    pub fn encode_pkcs1(&self) -> Vec<u8> {
+        self.pkcs1.to_vec()
+    }
+
     /// Get public key from the keypair.
     pub fn to_public_key(&self) -> PublicKey {
         PublicKey {
@@ -80,6 +124,22 @@ impl Keypair {
     }
 }
 
+// Unwrap the PKCS#1 RSAPrivateKey from the `privateKey` OCTET STRING of
+// PKCS#8 PrivateKeyInfo, `SEQUENCE { version, algorithm, privateKey, .. }`.
+#[cfg(not(target_arch = "wasm32"))]
+fn pkcs8_to_pkcs1(der: &[u8]) -> Result<Zeroizing<Vec<u8>>> {
+    let mut items = match Vec::<DerObject>::deserialize(der.iter()) {
+        Ok(items) => items,
+        Err(err) => err_at!(DecodeError, Err(err), "RSA PKCS#8 PrivateKeyInfo")?,
+    };
+    match items.get_mut(2) {
+        Some(item) if item.tag == DerTag::OctetString => {
+            Ok(Zeroizing::new(std::mem::take(&mut item.value.data)))
+        }
+        _ => err_at!(DecodeError, msg: "RSA PKCS#8 missing privateKey"),
+    }
+}
+
 /// An RSA public key.
 #[derive(Clone, PartialEq, Eq)]
 pub struct PublicKey {
@@ -88,6 +148,7 @@ pub struct PublicKey {
 
 impl PublicKey {
     /// Verify an RSA signature on a message using the public key.
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn verify(&self, msg: &[u8], signature: &[u8]) -> bool {
         use ring::signature::UnparsedPublicKey;
 
@@ -95,6 +156,26 @@ impl PublicKey {
         key.verify(msg, signature).is_ok()
     }
 
+    /// Verify an RSA signature on a message using the public key.
+    #[cfg(target_arch = "wasm32")]
+    pub fn verify(&self, msg: &[u8], signature: &[u8]) -> bool {
+        use ::rsa::{Hash, PaddingScheme, PublicKey as _, PublicKeyParts, RSAPublicKey};
+        use sha2::{Digest, Sha256};
+
+        let key = match RSAPublicKey::from_pkcs1(&self.bin) {
+            Ok(key) => key,
+            Err(_) => return false,
+        };
+        // match ring's RSA_PKCS1_2048_8192_SHA256 constraints.
+        let bits = key.n().bits();
+        if bits < 2048 || bits > 8192 {
+            return false;
+        }
+
+        let padding = PaddingScheme::new_pkcs1v15_sign(Some(Hash::SHA2_256));
+        key.verify(padding, &Sha256::digest(msg), signature).is_ok()
+    }
+
     /// Encode the RSA public key in DER as a PKCS#1 RSAPublicKey structure,
     /// as defined in [RFC3447].
     ///
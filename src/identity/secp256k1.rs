@@ -19,9 +19,15 @@
 // DEALINGS IN THE SOFTWARE.
 
 //! Secp256k1 keys.
+//!
+//! Uses the pure-rust [k256] backend by default, which also builds for
+//! wasm targets. Enable the `secp256k1-native` feature to use the
+//! [libsecp256k1] backend instead.
 
 use asn1_der::{DerObject, FromDerObject};
+#[cfg(feature = "secp256k1-native")]
 use rand::RngCore;
+#[cfg(feature = "secp256k1-native")]
 use secp256k1::{Message, Signature};
 use sha2::{Digest as ShaDigestTrait, Sha256};
 use zeroize::Zeroize;
@@ -69,14 +75,24 @@ impl fmt::Debug for Keypair {
 
 /// Promote a Secp256k1 secret key into a keypair.
 impl From<SecretKey> for Keypair {
+    #[cfg(feature = "secp256k1-native")]
     fn from(val: SecretKey) -> Keypair {
         let public_key = PublicKey {
             public_key: secp256k1::PublicKey::from_secret_key(&val.secret_key),
         };
         Keypair {
-            secret_key: SecretKey {
-                secret_key: val.secret_key,
-            },
+            secret_key: val,
+            public_key,
+        }
+    }
+
+    #[cfg(not(feature = "secp256k1-native"))]
+    fn from(val: SecretKey) -> Keypair {
+        let public_key = PublicKey {
+            public_key: k256::ecdsa::SigningKey::from(&val.secret_key).verify_key(),
+        };
+        Keypair {
+            secret_key: val,
             public_key,
         }
     }
@@ -92,7 +108,10 @@ impl From<Keypair> for SecretKey {
 /// A Secp256k1 secret key.
 #[derive(Clone)]
 pub struct SecretKey {
+    #[cfg(feature = "secp256k1-native")]
     secret_key: secp256k1::SecretKey,
+    #[cfg(not(feature = "secp256k1-native"))]
+    secret_key: k256::SecretKey,
 }
 
 impl fmt::Debug for SecretKey {
@@ -104,6 +123,7 @@ impl fmt::Debug for SecretKey {
 impl SecretKey {
     // TODO: should we try drand.love ?
     /// Generate a new Secp256k1 secret key.
+    #[cfg(feature = "secp256k1-native")]
     pub fn generate() -> SecretKey {
         let mut r = rand::thread_rng();
         let mut b = [0; secp256k1::util::SECRET_KEY_SIZE];
@@ -117,12 +137,25 @@ impl SecretKey {
         }
     }
 
+    /// Generate a new Secp256k1 secret key.
+    #[cfg(not(feature = "secp256k1-native"))]
+    pub fn generate() -> SecretKey {
+        let secret_key = k256::SecretKey::random(rand::thread_rng());
+        SecretKey { secret_key }
+    }
+
     /// Create a secret key from a byte slice, zeroing the slice on success.
     /// If the bytes do not constitute a valid Secp256k1 secret key, an
     /// error is returned.
     pub fn from_bytes(mut sk: impl AsMut<[u8]>) -> Result<SecretKey> {
         let sk_bytes = sk.as_mut();
-        let secret_key = match secp256k1::SecretKey::parse_slice(&*sk_bytes) {
+
+        #[cfg(feature = "secp256k1-native")]
+        let res = secp256k1::SecretKey::parse_slice(&*sk_bytes);
+        #[cfg(not(feature = "secp256k1-native"))]
+        let res = k256::SecretKey::from_bytes(&*sk_bytes);
+
+        let secret_key = match res {
             Ok(secret_key) => Ok(secret_key),
             err @ Err(_) => err_at!(DecodeError, err, "secp256k1 secret key"),
         }?;
@@ -168,17 +201,43 @@ impl SecretKey {
     /// ECDSA signature, as defined in [RFC3278].
     ///
     /// [RFC3278]: https://tools.ietf.org/html/rfc3278#section-8.2
+    #[cfg(feature = "secp256k1-native")]
     pub fn sign(&self, msg: &[u8]) -> Result<Vec<u8>> {
         self.sign_hash(Sha256::digest(msg).as_ref())
     }
 
+    /// Sign a message with this secret key, producing a DER-encoded
+    /// ECDSA signature, as defined in [RFC3278].
+    ///
+    /// [RFC3278]: https://tools.ietf.org/html/rfc3278#section-8.2
+    #[cfg(not(feature = "secp256k1-native"))]
+    pub fn sign(&self, msg: &[u8]) -> Result<Vec<u8>> {
+        use k256::ecdsa::{signature::DigestSigner, Signature, SigningKey};
+
+        let signer = SigningKey::from(&self.secret_key);
+        let res: std::result::Result<Signature, _> =
+            signer.try_sign_digest(Sha256::new().chain(msg));
+        let sig = err_at!(SigningError, res, "secp256k1 sign")?;
+        Ok(sig.to_asn1().as_bytes().to_vec())
+    }
+
     /// Returns the raw bytes of the secret key.
+    #[cfg(feature = "secp256k1-native")]
     pub fn to_bytes(&self) -> [u8; 32] {
         self.secret_key.serialize()
     }
 
+    /// Returns the raw bytes of the secret key.
+    #[cfg(not(feature = "secp256k1-native"))]
+    pub fn to_bytes(&self) -> [u8; 32] {
+        let mut bytes = [0; 32];
+        bytes.copy_from_slice(&self.secret_key.to_bytes());
+        bytes
+    }
+
     /// Sign a raw message of length 256 bits with this secret key, produces a DER-encoded
     /// ECDSA signature.
+    #[cfg(feature = "secp256k1-native")]
     fn sign_hash(&self, msg: &[u8]) -> Result<Vec<u8>> {
         let m = match Message::parse_slice(msg) {
             Ok(m) => Ok(m),
@@ -195,9 +254,13 @@ impl SecretKey {
 /// A Secp256k1 public key.
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub struct PublicKey {
+    #[cfg(feature = "secp256k1-native")]
     public_key: secp256k1::PublicKey,
+    #[cfg(not(feature = "secp256k1-native"))]
+    public_key: k256::ecdsa::VerifyingKey,
 }
 
+#[cfg(feature = "secp256k1-native")]
 impl PublicKey {
     /// Verify the Secp256k1 signature on a message using the public key.
     pub fn verify(&self, msg: &[u8], signature: &[u8]) -> bool {
@@ -237,6 +300,58 @@ impl PublicKey {
     }
 }
 
+#[cfg(not(feature = "secp256k1-native"))]
+impl PublicKey {
+    /// Verify the Secp256k1 signature on a message using the public key.
+    pub fn verify(&self, msg: &[u8], signature: &[u8]) -> bool {
+        use k256::ecdsa::{signature::DigestVerifier, Signature};
+
+        match Signature::from_asn1(signature) {
+            // k256 only accepts "low S" signatures, other implementations
+            // don't insist on that, normalize before verifying.
+            Ok(mut sig) => match sig.normalize_s() {
+                Ok(_) => {
+                    let digest = Sha256::new().chain(msg);
+                    self.public_key.verify_digest(digest, &sig).is_ok()
+                }
+                Err(_) => false,
+            },
+            Err(_) => false,
+        }
+    }
+
+    /// Encode the public key in compressed form, i.e. with one coordinate
+    /// represented by a single bit.
+    pub fn encode(&self) -> [u8; 33] {
+        self.public_key.to_bytes()
+    }
+
+    /// Encode the public key in uncompressed form.
+    pub fn encode_uncompressed(&self) -> [u8; 65] {
+        use k256::elliptic_curve::sec1::ToEncodedPoint;
+
+        let mut bytes = [0; 65];
+        bytes.copy_from_slice(self.public_key.to_encoded_point(false).as_bytes());
+        bytes
+    }
+
+    /// Decode a public key from a byte slice in the the format produced
+    /// by `encode`.
+    pub fn decode(k: &[u8]) -> Result<PublicKey> {
+        if k.len() != 33 {
+            err_at!(DecodeError, msg: "secp256k1 public key length {}", k.len())?
+        }
+        match k256::ecdsa::VerifyingKey::from_sec1_bytes(k) {
+            Ok(public_key) => Ok(PublicKey { public_key }),
+            Err(err) => err_at!(
+                DecodeError,
+                Err(err),
+                "failed to parse secp256k1 public key"
+            ),
+        }
+    }
+}
+
 #[cfg(test)]
 #[path = "secp256k1_test.rs"]
 mod secp256k1_test;
@@ -5,6 +5,17 @@ fn secp256k1_secret_from_bytes() {
     let sk1 = SecretKey::generate();
     let mut sk_bytes = sk1.to_bytes();
     let sk2 = SecretKey::from_bytes(&mut sk_bytes).unwrap();
-    assert_eq!(sk1.secret_key.serialize(), sk2.secret_key.serialize());
+    assert_eq!(sk1.to_bytes(), sk2.to_bytes());
     assert_eq!(sk_bytes, [0; 32]);
 }
+
+#[test]
+fn secp256k1_sign_verify() {
+    let kp = Keypair::generate();
+    let sig = kp.as_secret_key().sign(b"hello world").unwrap();
+    assert!(kp.as_public_key().verify(b"hello world", &sig));
+    assert!(!kp.as_public_key().verify(b"hello world!", &sig));
+
+    let pk = PublicKey::decode(&kp.as_public_key().encode()).unwrap();
+    assert_eq!(&pk, kp.as_public_key());
+}
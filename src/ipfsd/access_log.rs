@@ -0,0 +1,317 @@
+//! Module implement per-request access logging for gateway and API
+//! servers.
+//!
+//! Each served request is described by an [AccessEntry] and written as a
+//! single line, either as a JSON object or in Apache combined log format,
+//! so that existing log pipelines can consume it. Timestamps are in UTC.
+//!
+//! Combined format, with request duration in microseconds appended, as
+//! done by `%D` in apache:
+//!
+//! ```text
+//! <remote> - - [10/Oct/2000:13:55:36 +0000] "GET /ipfs/<cid> HTTP/1.1" 200 2326 "<referer>" "<user-agent>" <micros>
+//! ```
+//!
+//! Lines are written to stdout, stderr or appended to a file, refer
+//! [AccessLog::open]. Servers build an entry with
+//! [AccessEntry::from_request] and log it, once the response is sent,
+//! with the node's logger, refer [IpfsNode::as_access_log].
+//!
+//! [IpfsNode::as_access_log]: crate::ipfsd::IpfsNode::as_access_log
+
+use std::{
+    fmt, fs,
+    io::{self, Write},
+    net, result,
+    str::FromStr,
+    sync::Mutex,
+    time,
+};
+
+use crate::{ipld::json, Error, Result};
+
+/// Access log output for process standard output.
+pub const LOG_STDOUT: &str = "stdout";
+
+/// Access log output for process standard error.
+pub const LOG_STDERR: &str = "stderr";
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Format for access log lines.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LogFormat {
+    /// One JSON object per line.
+    Json,
+    /// Apache combined log format.
+    Combined,
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
+        match self {
+            LogFormat::Json => write!(f, "json"),
+            LogFormat::Combined => write!(f, "combined"),
+        }
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<LogFormat> {
+        match s {
+            "json" => Ok(LogFormat::Json),
+            "combined" => Ok(LogFormat::Combined),
+            _ => err_at!(BadInput, msg: "unknown access log format {:?}", s),
+        }
+    }
+}
+
+/// A served request.
+#[derive(Clone, Debug)]
+pub struct AccessEntry {
+    /// Server that handled the request, like `gateway` or `api`.
+    pub server: String,
+    /// Time at which the request was received.
+    pub time: time::SystemTime,
+    pub remote: Option<net::SocketAddr>,
+    pub method: String,
+    /// Request path, including query.
+    pub path: String,
+    /// CID resolved from path, if any.
+    pub cid: Option<String>,
+    pub protocol: String,
+    pub status: u16,
+    /// Number of body bytes sent in response.
+    pub bytes: u64,
+    pub duration: time::Duration,
+    pub referer: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl AccessEntry {
+    /// Start an entry for request received by `server` from `remote`,
+    /// timed from now. `request_line` is the first line of HTTP request,
+    /// like `GET /ipfs/<cid> HTTP/1.1`, and `headers` are the request
+    /// headers, from which `Referer` and `User-Agent` are picked. Server
+    /// shall fill `cid`, `status`, `bytes` and `duration` once the
+    /// response is sent.
+    pub fn from_request(
+        server: &str,
+        remote: Option<net::SocketAddr>,
+        request_line: &str,
+        headers: &[(String, String)],
+    ) -> Result<AccessEntry> {
+        let parts: Vec<&str> = request_line.split_ascii_whitespace().collect();
+        let (method, path, protocol) = match parts.as_slice() {
+            [method, path, protocol] => (method, path, protocol),
+            _ => err_at!(BadInput, msg: "bad request line {:?}", request_line)?,
+        };
+        let header = |name: &str| {
+            headers
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, val)| val.clone())
+        };
+
+        let val = AccessEntry {
+            server: server.to_string(),
+            time: time::SystemTime::now(),
+            remote,
+            method: method.to_string(),
+            path: path.to_string(),
+            cid: None,
+            protocol: protocol.to_string(),
+            status: 0,
+            bytes: 0,
+            duration: time::Duration::default(),
+            referer: header("referer"),
+            user_agent: header("user-agent"),
+        };
+        Ok(val)
+    }
+
+    /// Format entry as a single line, without the trailing newline.
+    pub fn to_line(&self, format: LogFormat) -> Result<String> {
+        match format {
+            LogFormat::Json => self.to_json(),
+            LogFormat::Combined => self.to_combined(),
+        }
+    }
+
+    fn to_json(&self) -> Result<String> {
+        let (date, hms) = to_utc(self.time)?;
+        let fields = vec![
+            ("server", json_str(&self.server)?),
+            (
+                "time",
+                format!(
+                    "\"{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z\"",
+                    date.0, date.1, date.2, hms.0, hms.1, hms.2
+                ),
+            ),
+            ("remote", json_opt(&self.remote.map(|a| a.to_string()))?),
+            ("method", json_str(&self.method)?),
+            ("path", json_str(&self.path)?),
+            ("cid", json_opt(&self.cid)?),
+            ("protocol", json_str(&self.protocol)?),
+            ("status", self.status.to_string()),
+            ("bytes", self.bytes.to_string()),
+            ("duration_us", self.duration.as_micros().to_string()),
+            ("referer", json_opt(&self.referer)?),
+            ("user_agent", json_opt(&self.user_agent)?),
+        ];
+        let fields: Vec<String> = fields
+            .into_iter()
+            .map(|(key, val)| format!("\"{}\":{}", key, val))
+            .collect();
+        Ok(format!("{{{}}}", fields.join(",")))
+    }
+
+    fn to_combined(&self) -> Result<String> {
+        let (date, hms) = to_utc(self.time)?;
+        let remote = match self.remote {
+            Some(addr) => addr.ip().to_string(),
+            None => "-".to_string(),
+        };
+        let line = format!(
+            "{} - - [{:02}/{}/{:04}:{:02}:{:02}:{:02} +0000] \"{} {} {}\" {} {} {} {} {}",
+            remote,
+            date.2,
+            MONTHS[(date.1 - 1) as usize],
+            date.0,
+            hms.0,
+            hms.1,
+            hms.2,
+            clf_escape(&self.method),
+            clf_escape(&self.path),
+            clf_escape(&self.protocol),
+            self.status,
+            self.bytes,
+            clf_opt(&self.referer),
+            clf_opt(&self.user_agent),
+            self.duration.as_micros(),
+        );
+        Ok(line)
+    }
+}
+
+/// Access logger, shareable across server threads.
+pub struct AccessLog {
+    format: LogFormat,
+    writer: Mutex<Box<dyn Write + Send>>,
+}
+
+impl AccessLog {
+    /// Create a logger writing lines to `writer`.
+    pub fn new(format: LogFormat, writer: Box<dyn Write + Send>) -> AccessLog {
+        AccessLog {
+            format,
+            writer: Mutex::new(writer),
+        }
+    }
+
+    /// Create a logger writing lines to `output`, which is [LOG_STDOUT],
+    /// [LOG_STDERR] or a file path. Files are created if missing and lines
+    /// are appended to them.
+    pub fn open(format: LogFormat, output: &str) -> Result<AccessLog> {
+        let writer: Box<dyn Write + Send> = match output {
+            LOG_STDOUT => Box::new(io::stdout()),
+            LOG_STDERR => Box::new(io::stderr()),
+            path => {
+                let mut opts = fs::OpenOptions::new();
+                opts.create(true).append(true);
+                Box::new(err_at!(IOError, opts.open(path), "{:?}", path)?)
+            }
+        };
+        Ok(AccessLog::new(format, writer))
+    }
+
+    /// Create a logger from config values `format` and `output`, output
+    /// defaults to [LOG_STDOUT], refer [AccessLog::open]. None `format`
+    /// disables access logging.
+    pub fn from_config(format: Option<&str>, output: Option<&str>) -> Result<Option<AccessLog>> {
+        match format {
+            Some(format) => {
+                let output = output.unwrap_or(LOG_STDOUT);
+                Ok(Some(AccessLog::open(format.parse()?, output)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    pub fn to_format(&self) -> LogFormat {
+        self.format
+    }
+
+    /// Write a line for `entry`.
+    pub fn log(&self, entry: &AccessEntry) -> Result<()> {
+        let line = entry.to_line(self.format)?;
+        let mut writer = err_at!(Fatal, self.writer.lock())?;
+        err_at!(IOError, writeln!(writer, "{}", line))?;
+        err_at!(IOError, writer.flush())
+    }
+}
+
+fn json_str(s: &str) -> Result<String> {
+    let mut buf = Vec::with_capacity(s.len() + 2);
+    json::encode_string(s, &mut buf);
+    err_at!(Fatal, String::from_utf8(buf))
+}
+
+fn json_opt(s: &Option<String>) -> Result<String> {
+    match s {
+        Some(s) => json_str(s),
+        None => Ok("null".to_string()),
+    }
+}
+
+// escape as done by apache, non printable bytes as \xNN.
+fn clf_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'"' => out.push_str("\\\""),
+            b'\\' => out.push_str("\\\\"),
+            0x20..=0x7e => out.push(b as char),
+            b => out.push_str(&format!("\\x{:02x}", b)),
+        }
+    }
+    out
+}
+
+fn clf_opt(s: &Option<String>) -> String {
+    match s {
+        Some(s) => format!("\"{}\"", clf_escape(s)),
+        None => "\"-\"".to_string(),
+    }
+}
+
+// return ((year, month, day), (hour, minute, second)) in UTC.
+fn to_utc(t: time::SystemTime) -> Result<((i64, u32, u32), (u64, u64, u64))> {
+    let secs = err_at!(Fatal, t.duration_since(time::UNIX_EPOCH))?.as_secs();
+    let (days, rem) = (secs / 86400, secs % 86400);
+
+    // civil from days, refer http://howardhinnant.github.io/date_algorithms.html
+    let z = (days as i64) + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    Ok((
+        (year, month, day),
+        (rem / 3600, (rem % 3600) / 60, rem % 60),
+    ))
+}
+
+#[cfg(test)]
+#[path = "access_log_test.rs"]
+mod access_log_test;
@@ -0,0 +1,93 @@
+use super::*;
+
+fn make_entry() -> AccessEntry {
+    AccessEntry {
+        server: "gateway".to_string(),
+        time: time::UNIX_EPOCH + time::Duration::from_secs(971186136),
+        remote: Some("10.1.2.3:54321".parse().unwrap()),
+        method: "GET".to_string(),
+        path: "/ipfs/bafkqaaa?filename=\"a\".txt".to_string(),
+        cid: Some("bafkqaaa".to_string()),
+        protocol: "HTTP/1.1".to_string(),
+        status: 200,
+        bytes: 2326,
+        duration: time::Duration::from_micros(1500),
+        referer: None,
+        user_agent: Some("curl/7.68.0".to_string()),
+    }
+}
+
+#[test]
+fn test_access_entry() {
+    let entry = make_entry();
+
+    let line = entry.to_line(LogFormat::Combined).unwrap();
+    let expected = concat!(
+        r#"10.1.2.3 - - [10/Oct/2000:13:55:36 +0000] "GET /ipfs/bafkqaaa?filename=\"a\".txt HTTP/1.1""#,
+        r#" 200 2326 "-" "curl/7.68.0" 1500"#,
+    );
+    assert_eq!(line, expected);
+
+    let line = entry.to_line(LogFormat::Json).unwrap();
+    let val: serde_json::Value = serde_json::from_str(&line).unwrap();
+    assert_eq!(val["time"], "2000-10-10T13:55:36Z");
+    assert_eq!(val["remote"], "10.1.2.3:54321");
+    assert_eq!(val["path"], "/ipfs/bafkqaaa?filename=\"a\".txt");
+    assert_eq!(val["cid"], "bafkqaaa");
+    assert_eq!(val["status"], 200);
+    assert_eq!(val["bytes"], 2326);
+    assert_eq!(val["duration_us"], 1500);
+    assert!(val["referer"].is_null());
+
+    assert_eq!("json".parse::<LogFormat>().unwrap(), LogFormat::Json);
+    assert_eq!(LogFormat::Combined.to_string(), "combined");
+    assert!("common".parse::<LogFormat>().is_err());
+}
+
+#[test]
+fn test_to_utc() {
+    let t = |secs| time::UNIX_EPOCH + time::Duration::from_secs(secs);
+    assert_eq!(to_utc(t(0)).unwrap(), ((1970, 1, 1), (0, 0, 0)));
+    assert_eq!(to_utc(t(951782400)).unwrap(), ((2000, 2, 29), (0, 0, 0)));
+    assert_eq!(
+        to_utc(t(1709251199)).unwrap(),
+        ((2024, 2, 29), (23, 59, 59))
+    );
+}
+
+#[test]
+fn test_access_request() {
+    let headers = vec![
+        ("user-agent".to_string(), "curl/7.68.0".to_string()),
+        ("Referer".to_string(), "http://localhost/".to_string()),
+    ];
+    let remote = Some("10.1.2.3:54321".parse().unwrap());
+    let line = "GET /ipfs/bafkqaaa HTTP/1.1";
+    let entry = AccessEntry::from_request("gateway", remote, line, &headers).unwrap();
+    assert_eq!(entry.method, "GET");
+    assert_eq!(entry.path, "/ipfs/bafkqaaa");
+    assert_eq!(entry.protocol, "HTTP/1.1");
+    assert_eq!(entry.user_agent.as_deref(), Some("curl/7.68.0"));
+    assert_eq!(entry.referer.as_deref(), Some("http://localhost/"));
+    assert!(AccessEntry::from_request("api", None, "GET /", &[]).is_err());
+
+    // lines are appended to file output.
+    let path = std::env::temp_dir().join(format!("iprs-access-log-{}", std::process::id()));
+    std::fs::remove_file(&path).ok();
+    let output = path.to_str().unwrap();
+    for _ in 0..2 {
+        let log = AccessLog::open(LogFormat::Combined, output).unwrap();
+        log.log(&make_entry()).unwrap();
+    }
+    let text = std::fs::read_to_string(&path).unwrap();
+    let expected = make_entry().to_line(LogFormat::Combined).unwrap();
+    assert_eq!(text, format!("{}\n{}\n", expected, expected));
+    std::fs::remove_file(&path).unwrap();
+
+    let log = AccessLog::from_config(Some("json"), None).unwrap().unwrap();
+    assert_eq!(log.to_format(), LogFormat::Json);
+    assert!(AccessLog::from_config(None, Some(LOG_STDERR))
+        .unwrap()
+        .is_none());
+    assert!(AccessLog::open(LogFormat::Json, "/nonexistent/access.log").is_err());
+}
@@ -0,0 +1,86 @@
+//! Locate a running ipfs-daemon via the `api` file in ipfs-repo.
+//!
+//! A daemon serving the API writes its listen address into `<repo>/api`,
+//! CLI subcommands can use [daemon_addr] to decide whether to talk to the
+//! running daemon or to access the repo directly.
+
+use log::{debug, warn};
+
+use std::{env, fs, path, time::Duration};
+
+use crate::{multiaddr::Multiaddr, net_conn::Conn, Error, Result};
+
+/// Name of the file, under ipfs-repo, holding the API listen address.
+pub const API_FILE: &str = "api";
+
+/// Timeout for dialing the API address, a daemon on the same host
+/// accepts well within this.
+pub const API_DIAL_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Return the root of ipfs-repo, from `IPFS_PATH` environment variable,
+/// defaults to `$HOME/.ipfs`.
+pub fn repo_root() -> Result<path::PathBuf> {
+    match env::var_os("IPFS_PATH") {
+        Some(root) => Ok(root.into()),
+        None => match dirs::home_dir() {
+            Some(home) => Ok(home.join(".ipfs")),
+            None => err_at!(FilePath, msg: "missing home directory"),
+        },
+    }
+}
+
+/// Read the API address from `api` file in ipfs-repo, return None if
+/// the file is missing.
+pub fn read_api_file(repo: &path::Path) -> Result<Option<Multiaddr>> {
+    let loc = repo.join(API_FILE);
+    if !loc.exists() {
+        return Ok(None);
+    }
+
+    let text = err_at!(IOError, fs::read_to_string(&loc), "{:?}", loc)?;
+    Ok(Some(Multiaddr::from_text(text.trim())?))
+}
+
+/// Write the API address into `api` file in ipfs-repo, should be called
+/// once the API listener is bound.
+pub fn write_api_file(repo: &path::Path, ma: &Multiaddr) -> Result<()> {
+    let loc = repo.join(API_FILE);
+    err_at!(IOError, fs::write(&loc, ma.to_text()?), "{:?}", loc)
+}
+
+/// Remove the `api` file from ipfs-repo, should be called when the API
+/// listener is closed.
+pub fn remove_api_file(repo: &path::Path) -> Result<()> {
+    let loc = repo.join(API_FILE);
+    match loc.exists() {
+        true => err_at!(IOError, fs::remove_file(&loc), "{:?}", loc),
+        false => Ok(()),
+    }
+}
+
+/// Return the API address of a running daemon for ipfs-repo. If the
+/// `api` file is missing, malformed or stale, that is nothing accepts on
+/// the address within [API_DIAL_TIMEOUT], return None and caller shall
+/// fall back to offline mode.
+pub fn daemon_addr(repo: &path::Path) -> Result<Option<Multiaddr>> {
+    let ma = match read_api_file(repo) {
+        Ok(Some(ma)) => ma,
+        Ok(None) => return Ok(None),
+        Err(err) => {
+            warn!("ignoring api file in {:?}: {}", repo, err);
+            return Ok(None);
+        }
+    };
+
+    match Conn::dial_multiaddr_timeout(ma.clone(), API_DIAL_TIMEOUT) {
+        Ok(_) => Ok(Some(ma)),
+        Err(err) => {
+            debug!("stale api file in {:?}: {}", repo, err);
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "api_file_test.rs"]
+mod api_file_test;
@@ -0,0 +1,32 @@
+use std::net::TcpListener;
+
+use super::*;
+
+#[test]
+fn test_daemon_addr() {
+    let repo = env::temp_dir().join(format!("iprs-api-file-test-{}", std::process::id()));
+    fs::create_dir_all(&repo).unwrap();
+
+    assert!(daemon_addr(&repo).unwrap().is_none());
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let ma = Multiaddr::from_text(&format!("/ip4/127.0.0.1/tcp/{}", port)).unwrap();
+    write_api_file(&repo, &ma).unwrap();
+    assert_eq!(read_api_file(&repo).unwrap(), Some(ma.clone()));
+    assert_eq!(daemon_addr(&repo).unwrap(), Some(ma));
+
+    // stale, nothing is listening.
+    drop(listener);
+    assert!(daemon_addr(&repo).unwrap().is_none());
+
+    // malformed, fall back to offline.
+    fs::write(repo.join(API_FILE), "not a multiaddr").unwrap();
+    assert!(read_api_file(&repo).is_err());
+    assert!(daemon_addr(&repo).unwrap().is_none());
+
+    remove_api_file(&repo).unwrap();
+    assert!(!repo.join(API_FILE).exists());
+    remove_api_file(&repo).unwrap();
+    fs::remove_dir_all(&repo).ok();
+}
@@ -1,92 +1,136 @@
+//! Module implement ipfs configuration, same layout as go-ipfs config
+//! file, `config` under the repo root, refer [FileRepo][crate::ipfsd::FileRepo].
+//!
+//! Only the `Identity` section is decoded from, and encoded into, the
+//! config file for now, refer [Config::from_json]. Remaining sections
+//! are left to their defaults.
+
 // TODO: HumanOutput for config value ready for pretty printing,
 // in json format.
 
+use std::collections::BTreeMap;
+
+use crate::{
+    addr_info::AddrInfo,
+    identity::Keypair,
+    ipfsd::Experiments,
+    ipld::{
+        json,
+        kind::{Basic, Node},
+        typed::{self, IpldNode},
+    },
+    multiaddr::Multiaddr,
+    peer_id::PeerId,
+    pubsub, Error, Result,
+};
+
 /// Inter-Planetary file system configuration.
-#[derive(Clone)]
+#[derive(Default)]
 pub struct Config {
-    identity: Identity,     // local node's peer identity
-    datastore: Datastore,   // local node's storage
-    addrs: Addresses,       // local node's addresses
-    mounts: Mounts,         // local node's mount points
-    discovery: Discovery,   // local node's discovery mechanisms
-    routing: Routing,       // local node's routing settings
-    ipns: Ipns,             // Ipns settings
-    bootstrap: Vec<String>, // local nodes's bootstrap peer addresses
-    gateway: Gateway,       // local node's gateway server options
-    api: API,               // local node's API settings
-    swarm: Swarm,
-    auto_nat: AutoNAT,
-    pubsub: PubsubConfig,
-    peering: Peering,
-
-    provider: Provider,
-    reprovider: Reprovider,
-    experimental: Experiments,
-    plugins: Plugins,
+    pub identity: Identity,       // local node's peer identity
+    pub datastore: Datastore,     // local node's storage
+    pub addrs: Addresses,         // local node's addresses
+    pub mounts: Mounts,           // local node's mount points
+    pub discovery: Discovery,     // local node's discovery mechanisms
+    pub routing: Routing,         // local node's routing settings
+    pub ipns: Ipns,               // Ipns settings
+    pub bootstrap: Vec<AddrInfo>, // local nodes's bootstrap peer addresses
+    pub gateway: Gateway,         // local node's gateway server options
+    pub api: Api,                 // local node's API settings
+    pub swarm: Swarm,
+    pub auto_nat: AutoNAT,
+    pub pubsub: PubsubConfig,
+    pub peering: Peering,
+
+    pub provider: Provider,
+    pub reprovider: Reprovider,
+    pub experimental: Experiments, // parsed using Experiments::from_names
+    pub plugins: Plugins,
+    pub policy: Policy,
 }
 
 /// Configuration of local node's identity.
+#[derive(Default)]
 pub struct Identity {
-    peer_id: String,
-    priv_key: Option<String>,
+    pub peer_id: String,
+    pub priv_key: Option<String>,
     // `priv_key` shall be decoded into `key_pair`, we might also
     // add other ways of picking up the key_pair, other than from
     // config file, i.e `priv_key`.
-    key_pair: Option<identity::KeyPair>,
+    pub key_pair: Option<Keypair>,
 }
 
 // Datastore tracks the configuration of the datastore.
+#[derive(Default)]
 pub struct Datastore {
-    storage_max: String,       // in B, kB, kiB, MB, ...
-    storage_gc_watermark: u64, // in percentage to multiply on StorageMax
-    gc_period: String,         // in ns, us, ms, s, m, h
-    spec: toml::Value,
-    hash_on_read: bool,
-    bloom_filtersize: usizea,
+    pub storage_max: String,       // in B, kB, kiB, MB, ...
+    pub storage_gc_watermark: u64, // in percentage to multiply on StorageMax
+    pub gc_period: String,         // in ns, us, ms, s, m, h
+    pub spec: Option<Basic>,
+    pub hash_on_read: bool,
+    pub bloom_filtersize: usize,
 }
 
-// Addresses stores the (string) multiaddr addresses for the node.
+// Addresses stores the multiaddr addresses for the node, parsed from
+// config using Multiaddr::from_text_list.
+#[derive(Default)]
 pub struct Addresses {
-    swarm: Vec<String>,       // addresses for the swarm to listen on
-    announce: Vec<String>,    // swarm addresses to announce to the network
-    no_announce: Vec<String>, // swarm addresses not to announce to the network
-    api: Vec<String>,         // address for the local API (RPC)
-    gateway: Vec<String>,     // address to listen on for IPFS HTTP object gateway
+    // addresses for the swarm to listen on, ipfsd::ListenAddrs
+    pub swarm: Vec<Multiaddr>,
+    // swarm addresses to announce to the network
+    pub announce: Vec<Multiaddr>,
+    // swarm addresses not to announce to the network
+    pub no_announce: Vec<Multiaddr>,
+    // addresses for the local API (RPC), can be /unix/<path>
+    pub api: Vec<Multiaddr>,
+    // addresses to listen on for IPFS HTTP object gateway
+    pub gateway: Vec<Multiaddr>,
 }
 
 // Mounts stores the (string) mount points
+#[derive(Default)]
 pub struct Mounts {
-    ipfs: String,
-    ipns: String,
-    fuse_allow_other: bool,
+    pub ipfs: String,
+    pub ipns: String,
+    pub fuse_allow_other: bool,
 }
 
+#[derive(Default)]
 pub struct Discovery {
-    mdns: Mdns,
+    pub mdns: Mdns,
 }
 
+#[derive(Default)]
 pub struct Mdns {
-    enabled: bool,
-    interval: u64, // Time in seconds between discovery rounds
+    pub enabled: bool,
+    pub interval: u64, // Time in seconds between discovery rounds
 }
 
 // Routing defines configuration options for libp2p routing
+#[derive(Default)]
 pub struct Routing {
     // Type sets default daemon routing mode.
     // Can be one of "dht", "dhtclient", "dhtserver", "none", or unset.
-    r#type: String,
+    pub r#type: String,
+    // TrustlessGateways lists HTTP gateways to fetch blocks from, tried
+    // in order, alongside bitswap. Blocks are verified locally, refer
+    // ipfsd::TrustlessClient. Like ["http://127.0.0.1:8080"].
+    pub trustless_gateways: Vec<String>,
+    pub trustless_timeout: String, // like "30s"
 }
 
+#[derive(Default)]
 pub struct Ipns {
-    republish_period: String,
-    record_lifetime: String,
-    resolve_cachesize: usize,
+    pub republish_period: String,
+    pub record_lifetime: String,
+    pub resolve_cachesize: usize,
 }
 
+#[derive(Default)]
 pub struct GatewaySpec {
     // Paths is explicit list of path prefixes that should be handled by
     // this gateway. Example: `["/ipfs", "/ipns", "/api"]`
-    paths: Vec<String>,
+    pub paths: Vec<String>,
     // UseSubdomains indicates whether or not this gateway uses subdomains
     // for IPFS resources instead of paths. That is: http://CID.ipfs.GATEWAY/...
     //
@@ -95,23 +139,25 @@ pub struct GatewaySpec {
     //
     // We do not support using both paths and subdomains for a single domain
     // for security reasons (Origin isolation).
-    use_subdomains: bool,
+    pub use_subdomains: bool,
     // NoDNSLink configures this gateway to _not_ resolve DNSLink for the FQDN
     // provided in `Host` HTTP header.
-    no_dnslink: bool,
+    pub no_dnslink: bool,
 }
 
 // Gateway contains options for the HTTP gateway server.
+#[derive(Default)]
 pub struct Gateway {
     // HTTPHeaders configures the headers that should be returned by this
     // gateway.
-    http_headers: toml::Value, // HTTP headers to return with the gateway
+    // HTTP headers to return with the gateway
+    pub http_headers: BTreeMap<String, Vec<String>>,
     // RootRedirect is the path to which requests to `/` on this gateway
     // should be redirected.
-    root_redirect: String,
+    pub root_redirect: String,
     // Writable enables PUT/POST request handling by this gateway. Usually,
     // writing is done through the API, not the gateway.
-    writable: bool,
+    pub writable: bool,
     // PathPrefixes  is an array of acceptable url paths that a client can
     // specify in X-Ipfs-Path-Prefix header.
     //
@@ -128,91 +174,130 @@ pub struct Gateway {
     //    proxy_set_header X-Ipfs-Gateway-Prefix /blog;
     //    proxy_pass http://127.0.0.1:8080;
     //  }
-    path_prefixes: Vec<String>,
+    pub path_prefixes: Vec<String>,
     // FIXME: Not yet implemented
-    api_commands: Vec<String>,
+    pub api_commands: Vec<String>,
     // NoFetch configures the gateway to _not_ fetch blocks in response to
     // requests.
-    no_fetch: bool,
+    pub no_fetch: bool,
     // NoDNSLink configures the gateway to _not_ perform DNS TXT record
     // lookups in response to requests with values in `Host` HTTP header.
     // This flag can be overriden per FQDN in PublicGateways.
-    no_dnslink: bool,
+    pub no_dnslink: bool,
     // PublicGateways configures behavior of known public gateways.
     // Each key is a fully qualified domain name (FQDN).
-    public_gateways: toml::Value,
+    pub public_gateways: BTreeMap<String, GatewaySpec>,
+    // AccessLog enables per-request access logging, "json" or "combined".
+    pub access_log: Option<String>,
+    // AccessLogOutput is "stdout", "stderr" or a file path, default is
+    // "stdout".
+    pub access_log_output: Option<String>,
 }
 
+#[derive(Default)]
 pub struct Api {
-    http_headers: toml::Value, // HTTP headers to return with the API.
+    // HTTP headers to return with the API.
+    pub http_headers: BTreeMap<String, Vec<String>>,
+    // AccessLog enables per-request access logging, "json" or "combined".
+    pub access_log: Option<String>,
+    // AccessLogOutput is "stdout", "stderr" or a file path, default is
+    // "stdout".
+    pub access_log_output: Option<String>,
 }
 
+#[derive(Default)]
 pub struct Swarm {
     // AddrFilters specifies a set libp2p addresses that we should never
-    // dial or receive connections from.
-    addr_filters: Vec<String>,
+    // dial or receive connections from. Kept as text until multiaddr
+    // supports /ipcidr.
+    pub addr_filters: Vec<String>,
     // DisableBandwidthMetrics disables recording of bandwidth metrics for a
     // slight reduction in memory usage. You probably don't need to set this
     // flag.
-    disable_bandwidth_metrics: bool,
+    pub disable_bandwidth_metrics: bool,
     // DisableNatPortMap turns off NAT port mapping (UPnP, etc.).
-    disable_nat_portmap: bool,
+    pub disable_nat_portmap: bool,
     // EnableRelayHop makes this node act as a public relay, relaying
     // traffic between other nodes.
-    enable_relay_hop: bool,
+    pub enable_relay_hop: bool,
     // EnableAutoRelay enables the "auto relay" feature.
     //
     // When both EnableAutoRelay and EnableRelayHop are set, this go-ipfs node
     // will advertise itself as a public relay. Otherwise it will find and use
     // advertised public relays when it determines that it's not reachable
     // from the public internet.
-    enable_auto_relay: bool,
+    pub enable_auto_relay: bool,
     // Transports contains flags to enable/disable libp2p transports.
-    transports: Transports,
+    pub transports: Transports,
     // ConnMgr configures the connection manager.
-    connmgr: ConnMgr,
+    pub connmgr: ConnMgr,
+    // DialTimeout, in seconds, for establishing transport connection.
+    // Defaults to net_conn::DIAL_TIMEOUT.
+    pub dial_timeout: u64,
+    // HandshakeTimeout, in seconds, for security handshake on a newly
+    // established connection.
+    pub handshake_timeout: u64,
+    // NegotiateTimeout, in seconds, for multiplexer negotiation on a
+    // secured connection.
+    pub negotiate_timeout: u64,
+    // DialBackoffMax, in seconds, is the upper limit on backoff period
+    // for addresses that failed to dial. Defaults to net_conn::BACKOFF_MAX.
+    pub dial_backoff_max: u64,
 }
 
+#[derive(Default)]
 pub struct Transports {
     // Network specifies the base transports we'll use for dialing. To
     // listen on a transport, add the transport to your Addresses.Swarm.
-    network: Network,
+    pub network: Network,
     // Security specifies the transports used to encrypt insecure network
     // transports.
-    security: Security,
+    pub security: Security,
     // Multiplexers specifies the transports used to multiplex multiple
     // connections over a single duplex connection.
-    multiplexers: Multiplexers,
+    pub multiplexers: Multiplexers,
 }
 
+// Priority of a transport, lower value is preferred, None picks the
+// default priority and negative value disables the transport.
+pub type Priority = Option<i64>;
+
+// Ternary is a tri-state flag, None picks the default.
+pub type Ternary = Option<bool>;
+
+#[derive(Default)]
 pub struct Security {
-    tls: Priority,   // Defaults to 100.
-    secio: Priority, // Defaults to 200.
-    noise: Priority, // Defaults to 300.
+    pub tls: Priority,   // Defaults to 100.
+    pub secio: Priority, // Defaults to 200.
+    pub noise: Priority, // Defaults to 300.
 }
+#[derive(Default)]
 pub struct Network {
-    quic: Ternary,
-    tcp: Ternary,
-    web_socket: Ternary,
-    relay: Ternary,
+    pub quic: Ternary,
+    pub tcp: Ternary,
+    pub web_socket: Ternary,
+    pub relay: Ternary,
 }
+#[derive(Default)]
 pub struct Multiplexers {
-    yamux: Priority, // Defaults to 100.
-    mplex: Priority, // Defaults to 200.
+    pub yamux: Priority, // Defaults to 100.
+    pub mplex: Priority, // Defaults to 200.
 }
 
 // ConnMgr defines configuration options for the libp2p connection manager
+#[derive(Default)]
 pub struct ConnMgr {
-    r#type: String,
-    low_water: i64,
-    high_water: i64,
-    grace_period: String,
+    pub r#type: String,
+    pub low_water: i64,
+    pub high_water: i64,
+    pub grace_period: String,
 }
 
 // AutoNAT configures the node's AutoNAT subsystem.
+#[derive(Default)]
 pub struct AutoNAT {
     // Service configures the node's AutoNAT service mode.
-    service: AutoNATService,
+    pub service: AutoNATService,
 
     // Throttle configures AutoNAT dialback throttling.
     //
@@ -221,39 +306,178 @@ pub struct AutoNAT {
     //
     // By default, the limits will be a total of 30 dialbacks, with a
     // per-peer max of 3 peer, resetting every minute.
-    throttle AutoNATThrottle,
+    pub throttle: Option<AutoNATThrottle>,
 }
 
 // AutoNATThrottleConfig configures the throttle limites
+#[derive(Default)]
 pub struct AutoNATThrottle {
     // GlobalLimit and PeerLimit sets the global and per-peer dialback
     // limits. The AutoNAT service will only perform the specified number of
     // dialbacks per interval.
     //
     // Setting either to 0 will disable the appropriate limit.
-    global_limit: u64,
-    peer_limit: u64,
+    pub global_limit: u64,
+    pub peer_limit: u64,
     // Interval specifies how frequently this node should reset the
     // global/peer dialback limits.
     //
     // When unset, this defaults to 1 minute.
-    interval: u64,
+    pub interval: u64,
 }
 
-enum AutoNATService {
+pub enum AutoNATService {
     // Unset indicates that the user has not set the AutoNATService mode.
     //
     // When unset, nodes configured to be public DHT nodes will _also_
     // perform limited AutoNAT dialbacks.
     Unset,
     // Enabled indicates that the user has enabled the AutoNATService.
-    Enabled
+    Enabled,
     // Disabled indicates that the user has disabled the AutoNATService.
-    Disabled
-)
+    Disabled,
+}
+
+impl Default for AutoNATService {
+    fn default() -> Self {
+        AutoNATService::Unset
+    }
+}
+
+// Policy restricts hash functions and IPLD codecs accepted while decoding
+// CIDs and blocks, lists are parsed using ipfsd::codes_from_names and
+// applied to ipfsd::SecurityPolicy.
+#[derive(Default)]
+pub struct Policy {
+    pub deny_hashes: Vec<String>,  // like ["md4", "md5", "sha1"]
+    pub allow_hashes: Vec<String>, // when not empty, accept only these
+    pub deny_codecs: Vec<String>,
+    pub allow_codecs: Vec<String>, // when not empty, accept only these
+}
+
+// PubsubConfig configures the pubsub subsystem.
+#[derive(Default)]
+pub struct PubsubConfig {
+    pub router: String, // "gossipsub" or "floodsub"
+    // Score configures gossipsub v1.1 peer scoring, converted into
+    // pubsub::ScoreParams and pubsub::ScoreThresholds using
+    // PubsubScore::to_score. When unset, peers are not scored.
+    pub score: Option<pubsub::PubsubScore>,
+}
+
+// Peering configures the peering service, peers listed here are kept
+// connected, parsed from config using AddrInfo::from_text_list.
+#[derive(Default)]
+pub struct Peering {
+    pub peers: Vec<AddrInfo>,
+}
+
+// Provider configures announcing content to the routing system.
+#[derive(Default)]
+pub struct Provider {
+    pub strategy: String, // Unused, reserved
+}
+
+// Reprovider configures periodic re-announcing of local content.
+#[derive(Default)]
+pub struct Reprovider {
+    pub interval: String, // like "12h", period to reprovide local objects
+    pub strategy: String, // "all", "pinned" or "roots"
+}
+
+// Plugins configures the loaded plugins, keyed by plugin name.
+#[derive(Default)]
+pub struct Plugins {
+    pub plugins: BTreeMap<String, Plugin>,
+}
+
+#[derive(Default)]
+pub struct Plugin {
+    pub disabled: bool,
+    pub config: Option<Basic>,
+}
 
 impl Config {
-    pub fn to_peer_id(&self) -> PeerId {
-        todo!()
+    /// Decode configuration from its json encoding, as stored in the
+    /// repo's `config` file. Only the `Identity` section is decoded for
+    /// now, rest of the sections are left to their default.
+    pub fn from_json(data: &[u8]) -> Result<Config> {
+        let node = json::decode(data)?;
+        let val = Config {
+            identity: typed::get_field(&node, "Identity")?,
+            ..Config::default()
+        };
+        Ok(val)
+    }
+
+    /// Encode configuration into json, refer [Config::from_json].
+    pub fn to_json(&self) -> Result<Vec<u8>> {
+        let entries = vec![("Identity".to_string(), self.identity.to_basic()?)];
+        json::encode(&typed::new_map(entries))
+    }
+
+    /// Return local node's peer-id, refer [Identity::to_peer_id].
+    pub fn to_peer_id(&self) -> Result<PeerId> {
+        self.identity.to_peer_id()
+    }
+}
+
+impl Identity {
+    /// Create identity for `key_pair`, `priv_key` is set to its base64
+    /// encoded protobuf.
+    pub fn from_keypair(key_pair: Keypair) -> Result<Identity> {
+        let peer_id = key_pair.to_public_key().into_peer_id()?;
+        let val = Identity {
+            peer_id: peer_id.to_base58btc()?,
+            priv_key: Some(key_pair.to_base64_protobuf()?),
+            key_pair: Some(key_pair),
+        };
+        Ok(val)
+    }
+
+    /// Decode `peer_id`.
+    pub fn to_peer_id(&self) -> Result<PeerId> {
+        PeerId::from_text(&self.peer_id)
+    }
+
+    /// Decode `priv_key` into a key-pair. Fail if `priv_key` is missing
+    /// or if its public key does not hash to `peer_id`.
+    pub fn to_keypair(&self) -> Result<Keypair> {
+        let text = match &self.priv_key {
+            Some(text) => text,
+            None => err_at!(Invalid, msg: "missing PrivKey for {}", self.peer_id)?,
+        };
+        let key_pair = Keypair::from_base64_protobuf(text)?;
+        let peer_id = key_pair.to_public_key().into_peer_id()?;
+        if peer_id != self.to_peer_id()? {
+            err_at!(Invalid, msg: "PrivKey does not match PeerID {}", self.peer_id)?
+        }
+        Ok(key_pair)
+    }
+}
+
+impl IpldNode for Identity {
+    fn to_basic(&self) -> Result<Basic> {
+        let entries = vec![
+            ("PeerID".to_string(), self.peer_id.to_basic()?),
+            ("PrivKey".to_string(), self.priv_key.to_basic()?),
+        ];
+        Ok(typed::new_map(entries))
+    }
+
+    fn from_node(node: &dyn Node) -> Result<Self> {
+        let mut val = Identity {
+            peer_id: typed::get_field(node, "PeerID")?,
+            priv_key: typed::get_field(node, "PrivKey")?,
+            key_pair: None,
+        };
+        if val.priv_key.is_some() {
+            val.key_pair = Some(val.to_keypair()?);
+        }
+        Ok(val)
     }
 }
+
+#[cfg(test)]
+#[path = "config_test.rs"]
+mod config_test;
@@ -0,0 +1,41 @@
+use super::*;
+
+#[test]
+fn test_config_identity() {
+    let kp = Keypair::generate_ed25519().unwrap();
+    let peer_id = kp.to_public_key().into_peer_id().unwrap();
+
+    let config = Config {
+        identity: Identity::from_keypair(kp).unwrap(),
+        ..Config::default()
+    };
+    assert_eq!(config.to_peer_id().unwrap(), peer_id);
+
+    let data = config.to_json().unwrap();
+    let config = Config::from_json(&data).unwrap();
+    assert_eq!(config.to_peer_id().unwrap(), peer_id);
+    let kp = config.identity.key_pair.as_ref().unwrap();
+    assert_eq!(kp.to_public_key().into_peer_id().unwrap(), peer_id);
+    let kp = config.identity.to_keypair().unwrap();
+    assert_eq!(kp.to_public_key().into_peer_id().unwrap(), peer_id);
+
+    // PrivKey is optional, but the key-pair is then unavailable.
+    let text = format!(
+        r#"{{"Identity":{{"PeerID":"{}"}}}}"#,
+        peer_id.to_base58btc().unwrap()
+    );
+    let config = Config::from_json(text.as_bytes()).unwrap();
+    assert!(config.identity.key_pair.is_none());
+    assert!(config.identity.to_keypair().is_err());
+
+    // PrivKey must match PeerID.
+    let other = Keypair::generate_ed25519().unwrap();
+    let text = format!(
+        r#"{{"Identity":{{"PeerID":"{}","PrivKey":"{}"}}}}"#,
+        peer_id.to_base58btc().unwrap(),
+        other.to_base64_protobuf().unwrap()
+    );
+    assert!(Config::from_json(text.as_bytes()).is_err());
+
+    assert!(Config::from_json(b"{}").is_err());
+}
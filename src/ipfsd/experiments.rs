@@ -0,0 +1,134 @@
+//! Module implement typed toggles for experimental features.
+//!
+//! Subsystems check [Experiments] at startup to enable experimental
+//! code paths. The active set is reported by the daemon, so that results
+//! from research runs can be reproduced.
+
+use std::{collections::BTreeSet, fmt, result, str::FromStr};
+
+use crate::{Error, Result};
+
+macro_rules! experiments {
+    ($(
+        #[$doc:meta]
+        ($var:ident, $name:expr),
+    )*) => (
+        /// Experimental features that can be toggled from config.
+        #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+        pub enum Experiment {
+            $(
+                #[$doc]
+                $var,
+            )*
+        }
+
+        impl Experiment {
+            /// Return all experiments.
+            pub fn all() -> Vec<Experiment> {
+                vec![$(Experiment::$var,)*]
+            }
+
+            /// Return the name of this experiment, as used in config.
+            pub fn to_name(&self) -> &'static str {
+                match self {
+                    $(Experiment::$var => $name,)*
+                }
+            }
+        }
+
+        impl FromStr for Experiment {
+            type Err = Error;
+
+            fn from_str(s: &str) -> Result<Experiment> {
+                match s {
+                    $($name => Ok(Experiment::$var),)*
+                    _ => err_at!(BadInput, msg: "unknown experiment {:?}", s),
+                }
+            }
+        }
+    );
+}
+
+experiments![
+    /// Add files by reference, without copying them into the blockstore.
+    (Filestore, "filestore"),
+    /// Add content by url reference.
+    (Urlstore, "urlstore"),
+    /// Shard large directories using HAMT.
+    (Sharding, "sharding"),
+    /// Serve and fetch DAGs using graphsync protocol.
+    (Graphsync, "graphsync"),
+    /// Tunnel TCP streams over libp2p streams, `ipfs p2p` commands.
+    (Libp2pStreamMounting, "libp2p-stream-mounting"),
+    /// Proxy HTTP requests to remote peers over libp2p streams.
+    (P2pHttpProxy, "p2p-http-proxy"),
+    /// Provide only the roots of pinned DAGs.
+    (StrategicProviding, "strategic-providing"),
+    /// DHT client that maintains a full routing table.
+    (AcceleratedDht, "accelerated-dht"),
+    /// Provide records without waiting for a full DHT lookup.
+    (OptimisticProvide, "optimistic-provide"),
+];
+
+impl fmt::Display for Experiment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
+        write!(f, "{}", self.to_name())
+    }
+}
+
+/// Set of enabled experiments, all experiments are disabled by default.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Experiments {
+    enabled: BTreeSet<Experiment>,
+}
+
+impl fmt::Display for Experiments {
+    fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
+        write!(f, "{}", self.to_names().join(","))
+    }
+}
+
+impl Experiments {
+    /// Parse a list of experiment names, typically the `Experimental`
+    /// list from config. `field` names the list, error message shall
+    /// include the field, the index of offending entry and the reason.
+    pub fn from_names(field: &str, names: &[String]) -> Result<Experiments> {
+        let mut val = Experiments::default();
+        for (i, name) in names.iter().enumerate() {
+            match name.parse() {
+                Ok(exp) => val.enable(exp),
+                Err(err) => err_at!(BadInput, msg: "{}[{}] {:?}: {}", field, i, name, err)?,
+            };
+        }
+        Ok(val)
+    }
+
+    /// Enable experiment `exp`.
+    pub fn enable(&mut self, exp: Experiment) -> &mut Self {
+        self.enabled.insert(exp);
+        self
+    }
+
+    /// Disable experiment `exp`.
+    pub fn disable(&mut self, exp: Experiment) -> &mut Self {
+        self.enabled.remove(&exp);
+        self
+    }
+
+    /// Return whether experiment `exp` is enabled.
+    pub fn is_enabled(&self, exp: Experiment) -> bool {
+        self.enabled.contains(&exp)
+    }
+
+    /// Return names of enabled experiments, in a stable order.
+    pub fn to_names(&self) -> Vec<String> {
+        self.enabled
+            .iter()
+            .map(|e| e.to_name().to_string())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+#[path = "experiments_test.rs"]
+mod experiments_test;
@@ -0,0 +1,32 @@
+use super::*;
+
+#[test]
+fn test_experiments() {
+    for exp in Experiment::all().into_iter() {
+        assert_eq!(exp.to_name().parse::<Experiment>().unwrap(), exp);
+    }
+
+    let names: Vec<String> = vec!["optimistic-provide", "graphsync"]
+        .into_iter()
+        .map(|s| s.to_string())
+        .collect();
+    let mut exps = Experiments::from_names("Experimental", &names).unwrap();
+    assert!(exps.is_enabled(Experiment::Graphsync));
+    assert!(exps.is_enabled(Experiment::OptimisticProvide));
+    assert!(!exps.is_enabled(Experiment::AcceleratedDht));
+    assert_eq!(exps.to_string(), "graphsync,optimistic-provide");
+
+    exps.disable(Experiment::Graphsync)
+        .enable(Experiment::AcceleratedDht);
+    assert_eq!(
+        exps.to_names(),
+        vec!["accelerated-dht", "optimistic-provide"]
+    );
+
+    let names = vec!["graphsync".to_string(), "warp-drive".to_string()];
+    let msg = Experiments::from_names("Experimental", &names)
+        .unwrap_err()
+        .to_string();
+    assert!(msg.contains("Experimental[1]"), "{}", msg);
+    assert!(msg.contains("warp-drive"), "{}", msg);
+}
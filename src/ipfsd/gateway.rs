@@ -0,0 +1,289 @@
+//! Module implement response metadata for files served from the gateway.
+//!
+//! Content type is sniffed from the leading bytes of UnixFS content,
+//! falling back on the file extension and then on a text/binary check.
+//! `?filename=<name>` and `?download=true` query parameters control the
+//! `Content-Disposition` header, so that browsers save the file under a
+//! sensible name instead of its CID.
+//!
+//! Request paths, `/ipfs/<cid>/...`, are parsed by [GatewayPath], which
+//! rejects CIDs whose hash function or codec is denied by the gateway's
+//! [SecurityPolicy], before any block is looked up.
+
+use std::{path, str};
+
+use crate::{cid::Cid, ipfsd::SecurityPolicy, Error, Result};
+
+/// Number of leading bytes needed by [sniff_content_type].
+pub const SNIFF_LEN: usize = 512;
+
+/// Content type for data that is neither recognised nor text.
+pub const OCTET_STREAM: &str = "application/octet-stream";
+
+// signatures, as (offset, magic-bytes, content-type).
+const MAGIC: [(usize, &[u8], &str); 13] = [
+    (0, b"\x89PNG\r\n\x1a\n", "image/png"),
+    (0, b"\xff\xd8\xff", "image/jpeg"),
+    (0, b"GIF87a", "image/gif"),
+    (0, b"GIF89a", "image/gif"),
+    (0, b"BM", "image/bmp"),
+    (0, b"%PDF-", "application/pdf"),
+    (0, b"PK\x03\x04", "application/zip"),
+    (0, b"\x1f\x8b\x08", "application/gzip"),
+    (0, b"\0asm", "application/wasm"),
+    (4, b"ftyp", "video/mp4"),
+    (0, b"\x1a\x45\xdf\xa3", "video/webm"),
+    (0, b"OggS", "audio/ogg"),
+    (0, b"ID3", "audio/mpeg"),
+];
+
+// RIFF containers, "RIFF" at offset 0 followed by the form-type at
+// offset 8, as (form-type, content-type).
+const RIFF_MAGIC: [(&[u8], &str); 2] = [(b"WEBP", "image/webp"), (b"WAVE", "audio/wav")];
+
+// case-insensitive text prefixes, after byte-order-mark and leading
+// white-space.
+const TEXT_MAGIC: [(&str, &str); 5] = [
+    ("<!doctype html", "text/html; charset=utf-8"),
+    ("<html", "text/html; charset=utf-8"),
+    ("<svg", "image/svg+xml"),
+    ("<?xml", "text/xml; charset=utf-8"),
+    ("%!ps-adobe-", "application/postscript"),
+];
+
+const EXTENSIONS: [(&str, &str); 22] = [
+    ("html", "text/html; charset=utf-8"),
+    ("htm", "text/html; charset=utf-8"),
+    ("css", "text/css; charset=utf-8"),
+    ("js", "application/javascript"),
+    ("mjs", "application/javascript"),
+    ("json", "application/json"),
+    ("txt", "text/plain; charset=utf-8"),
+    ("md", "text/markdown; charset=utf-8"),
+    ("csv", "text/csv; charset=utf-8"),
+    ("xml", "text/xml; charset=utf-8"),
+    ("svg", "image/svg+xml"),
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("ico", "image/x-icon"),
+    ("pdf", "application/pdf"),
+    ("wasm", "application/wasm"),
+    ("mp4", "video/mp4"),
+    ("mp3", "audio/mpeg"),
+    ("woff2", "font/woff2"),
+];
+
+/// Return the content type for file `data`, of which only the first
+/// [SNIFF_LEN] bytes are looked at. `name`, typically the last path
+/// segment or the `?filename=` parameter, is used when content is not
+/// recognised by its magic bytes.
+pub fn sniff_content_type(data: &[u8], name: Option<&str>) -> &'static str {
+    let data = &data[..data.len().min(SNIFF_LEN)];
+
+    for (off, magic, content_type) in MAGIC.iter() {
+        if data.len() >= off + magic.len() && &data[*off..off + magic.len()] == *magic {
+            return *content_type;
+        }
+    }
+    if data.len() >= 12 && &data[..4] == b"RIFF" {
+        for (form, content_type) in RIFF_MAGIC.iter() {
+            if &data[8..12] == *form {
+                return *content_type;
+            }
+        }
+    }
+
+    let text = {
+        let n = data.len().min(32);
+        let text = String::from_utf8_lossy(&data[..n]);
+        text.trim_start_matches('\u{feff}')
+            .trim_start()
+            .to_lowercase()
+    };
+    for (prefix, content_type) in TEXT_MAGIC.iter() {
+        if text.starts_with(prefix) {
+            return *content_type;
+        }
+    }
+
+    let ext = name
+        .and_then(|name| path::Path::new(name).extension())
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase());
+    if let Some(ext) = ext {
+        for (e, content_type) in EXTENSIONS.iter() {
+            if *e == ext {
+                return *content_type;
+            }
+        }
+    }
+
+    if is_text(data) {
+        "text/plain; charset=utf-8"
+    } else {
+        OCTET_STREAM
+    }
+}
+
+// utf8 without control characters, other than white-space. Data might
+// be cut in the middle of a multi-byte sequence.
+fn is_text(data: &[u8]) -> bool {
+    let text = match str::from_utf8(data) {
+        Ok(text) => text,
+        Err(err) if err.error_len().is_none() => {
+            str::from_utf8(&data[..err.valid_up_to()]).unwrap_or_default()
+        }
+        Err(_) => return false,
+    };
+    text.chars()
+        .all(|ch| !ch.is_control() || ch.is_ascii_whitespace())
+}
+
+/// Content path requested from the gateway, `/ipfs/<cid>[/<name>...]`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GatewayPath {
+    /// Root of the requested content.
+    pub cid: Cid,
+    /// Percent-decoded path segments under the root, empty segments are
+    /// skipped.
+    pub names: Vec<String>,
+}
+
+impl GatewayPath {
+    /// Parse the request path, without the query part. Fail if the root
+    /// CID's hash function or codec is denied by `policy`, gateway shall
+    /// respond with `400 Bad Request`.
+    pub fn from_path(path: &str, policy: &SecurityPolicy) -> Result<GatewayPath> {
+        let mut segments = path.split('/').filter(|s| !s.is_empty());
+        match segments.next() {
+            Some("ipfs") => (),
+            _ => err_at!(BadInput, msg: "gateway path {:?} not under /ipfs", path)?,
+        }
+        let cid = match segments.next() {
+            Some(text) => Cid::from_text(text)?,
+            None => err_at!(BadInput, msg: "gateway path {:?} missing cid", path)?,
+        };
+        policy.check_cid(&cid)?;
+
+        let mut names = vec![];
+        for segment in segments {
+            // `+` is a space only in the query part.
+            names.push(percent_decode(&segment.replace('+', "%2B"))?);
+        }
+        Ok(GatewayPath { cid, names })
+    }
+}
+
+/// Gateway query parameters that control how a file is served.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct GatewayParams {
+    /// From `?filename=`, name to save the file as.
+    pub filename: Option<String>,
+    /// From `?download=true`, ask the browser to save the file instead of
+    /// opening it.
+    pub download: bool,
+}
+
+impl GatewayParams {
+    /// Parse the query part of the request URL, without the leading `?`.
+    /// Unknown parameters are ignored.
+    pub fn from_query(query: &str) -> Result<GatewayParams> {
+        let mut val = GatewayParams::default();
+        for param in query.split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = match param.find('=') {
+                Some(n) => (&param[..n], &param[n + 1..]),
+                None => (param, ""),
+            };
+            match key {
+                "filename" => val.filename = Some(percent_decode(value)?),
+                "download" => val.download = value == "true",
+                _ => (),
+            }
+        }
+        Ok(val)
+    }
+
+    /// Return the value for `Content-Disposition` header, None if neither
+    /// filename nor download is requested. Non-ascii filenames are sent
+    /// as `filename*`, refer RFC 6266, with an ascii fallback.
+    pub fn to_content_disposition(&self) -> Option<String> {
+        let kind = match self.download {
+            true => "attachment",
+            false => "inline",
+        };
+        let name = match &self.filename {
+            Some(name) if !name.is_empty() => name,
+            _ if self.download => return Some(kind.to_string()),
+            _ => return None,
+        };
+
+        let ascii: String = name
+            .chars()
+            .map(|ch| match ch {
+                '"' | '\\' => '_',
+                ch if ch.is_ascii() && !ch.is_ascii_control() => ch,
+                _ => '_',
+            })
+            .collect();
+        let val = if ascii == *name {
+            format!("{}; filename=\"{}\"", kind, ascii)
+        } else {
+            let encoded = percent_encode(name);
+            format!(
+                "{}; filename=\"{}\"; filename*=UTF-8''{}",
+                kind, ascii, encoded
+            )
+        };
+        Some(val)
+    }
+}
+
+fn percent_decode(s: &str) -> Result<String> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                // from_str_radix accepts a sign, like "+1", check digits.
+                let hex = match s.get(i + 1..i + 3) {
+                    Some(hex) if hex.bytes().all(|b| b.is_ascii_hexdigit()) => hex,
+                    _ => err_at!(BadInput, msg: "bad percent encoding {:?}", s)?,
+                };
+                out.push(err_at!(BadInput, u8::from_str_radix(hex, 16), "{:?}", s)?);
+                i += 3;
+            }
+            b'+' => {
+                out.push(b' ');
+                i += 1;
+            }
+            b => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    err_at!(BadInput, String::from_utf8(out), "{:?}", s)
+}
+
+// percent-encode everything except RFC 5987 attr-char.
+fn percent_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' => out.push(b as char),
+            b'!' | b'#' | b'$' | b'&' | b'+' | b'-' | b'.' | b'^' | b'_' | b'`' | b'|' | b'~' => {
+                out.push(b as char)
+            }
+            b => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+#[path = "gateway_test.rs"]
+mod gateway_test;
@@ -0,0 +1,113 @@
+use super::*;
+
+#[test]
+fn test_sniff_content_type() {
+    let testcases: Vec<(&[u8], Option<&str>, &str)> = vec![
+        (b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR", None, "image/png"),
+        (b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR", Some("a.txt"), "image/png"),
+        (b"%PDF-1.4\n", Some("paper"), "application/pdf"),
+        (b"RIFF\0\0\0\0WEBPVP8 ", None, "image/webp"),
+        (b"RIFF\x24\0\0\0WAVEfmt ", None, "audio/wav"),
+        (b"RIFX\0\0\0\0WEBPVP8 ", None, OCTET_STREAM),
+        (b"\0\0\0\0\0\0\0\0WAVEfmt ", None, OCTET_STREAM),
+        (b"\0\0\0\x18ftypmp42", None, "video/mp4"),
+        (b"  <!DOCTYPE html><html>", None, "text/html; charset=utf-8"),
+        (b"\xef\xbb\xbf<html>", None, "text/html; charset=utf-8"),
+        (b"<svg xmlns=", Some("logo"), "image/svg+xml"),
+        (
+            b"body { margin: 0 }",
+            Some("style.CSS"),
+            "text/css; charset=utf-8",
+        ),
+        (
+            b"export default 1;",
+            Some("app.js"),
+            "application/javascript",
+        ),
+        (b"hello world\n", None, "text/plain; charset=utf-8"),
+        (
+            b"hello world\n",
+            Some("readme.unknown"),
+            "text/plain; charset=utf-8",
+        ),
+        (b"\0\x01\x02\x03", None, OCTET_STREAM),
+        (b"", None, "text/plain; charset=utf-8"),
+    ];
+    for (data, name, content_type) in testcases.into_iter() {
+        assert_eq!(sniff_content_type(data, name), content_type, "{:?}", data);
+    }
+
+    // utf8 sequence cut at SNIFF_LEN is still text.
+    let mut data = vec![b'a'; SNIFF_LEN - 1];
+    data.extend_from_slice("é".as_bytes());
+    assert_eq!(sniff_content_type(&data, None), "text/plain; charset=utf-8");
+}
+
+#[test]
+fn test_gateway_params() {
+    let params = GatewayParams::from_query("").unwrap();
+    assert_eq!(params, GatewayParams::default());
+    assert_eq!(params.to_content_disposition(), None);
+
+    let params = GatewayParams::from_query("filename=report%202020.pdf&x=1").unwrap();
+    assert_eq!(params.filename.as_deref(), Some("report 2020.pdf"));
+    assert!(!params.download);
+    assert_eq!(
+        params.to_content_disposition().unwrap(),
+        "inline; filename=\"report 2020.pdf\""
+    );
+
+    let params = GatewayParams::from_query("download=true").unwrap();
+    assert_eq!(params.to_content_disposition().unwrap(), "attachment");
+
+    let params = GatewayParams::from_query("download=true&filename=%C3%A9t%C3%A9.txt").unwrap();
+    assert_eq!(
+        params.to_content_disposition().unwrap(),
+        "attachment; filename=\"_t_.txt\"; filename*=UTF-8''%C3%A9t%C3%A9.txt"
+    );
+
+    assert!(GatewayParams::from_query("filename=%zz").is_err());
+    assert!(GatewayParams::from_query("filename=%2").is_err());
+    assert!(GatewayParams::from_query("filename=%ff").is_err());
+    assert!(GatewayParams::from_query("filename=%+1").is_err());
+    assert!(GatewayParams::from_query("filename=%-1").is_err());
+}
+
+#[test]
+fn test_gateway_path() {
+    use crate::{multicodec, multihash::Multihash};
+    use multibase::Base;
+
+    let policy = SecurityPolicy::strict();
+    let cid = Cid::new_v1(Base::Base32Lower, multicodec::RAW.into(), b"hello").unwrap();
+    let text = cid.to_text(None).unwrap();
+
+    let path = GatewayPath::from_path(&format!("/ipfs/{}", text), &policy).unwrap();
+    assert!(path.cid == cid);
+    assert!(path.names.is_empty());
+    let req = format!("/ipfs/{}/docs//a+b%20c.txt", text);
+    let path = GatewayPath::from_path(&req, &policy).unwrap();
+    assert_eq!(
+        path.names,
+        vec!["docs".to_string(), "a+b c.txt".to_string()]
+    );
+
+    assert!(GatewayPath::from_path("/ipns/example.com", &policy).is_err());
+    assert!(GatewayPath::from_path("/ipfs/", &policy).is_err());
+    assert!(GatewayPath::from_path("/ipfs/not-a-cid", &policy).is_err());
+
+    // denied hash and denied codec.
+    let hash = Multihash::new(multicodec::SHA1.into(), b"hello").unwrap();
+    let weak = Cid::from_raw(Base::Base32Lower, multicodec::RAW.into(), hash);
+    let req = format!("/ipfs/{}", weak.to_text(None).unwrap());
+    let msg = GatewayPath::from_path(&req, &policy)
+        .unwrap_err()
+        .to_string();
+    assert!(msg.contains("sha1"), "{}", msg);
+    assert!(GatewayPath::from_path(&req, &SecurityPolicy::default()).is_ok());
+
+    let mut policy = SecurityPolicy::default();
+    policy.deny_codec(multicodec::RAW);
+    let req = format!("/ipfs/{}", text);
+    assert!(GatewayPath::from_path(&req, &policy).is_err());
+}
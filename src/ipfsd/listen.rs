@@ -0,0 +1,204 @@
+//! Module implement the daemon's listeners, for swarm, API and gateway.
+//!
+//! [ListenAddrs] holds the listen addresses, same as `Addresses.swarm`,
+//! `Addresses.api` and `Addresses.gateway` in config. By default the swarm
+//! listens on [SWARM_PORT] for both TCP and QUIC, refer [swarm_addrs],
+//! while API and gateway listen on the loopback interface.
+//!
+//! [Listen] is the [Subsystem] that binds them when the daemon starts,
+//! so that [Ipfsd::wait_ready][crate::ipfsd::Ipfsd::wait_ready] returns,
+//! and `READY=1` is notified, only after all of them are listening.
+//!
+//! When the repo has a `swarm.key`, refer [Psk::from_repo], connections
+//! accepted on swarm listeners are wrapped for the private network, and
+//! [Listen] refuses to start if any swarm address is on a transport that
+//! can't be wrapped, like QUIC.
+
+use crossbeam_channel as cbm;
+use log::{error, info};
+
+use std::path;
+
+use crate::{
+    ipfsd::{api_file, task::Subsystem},
+    multiaddr::Multiaddr,
+    net_conn::{check_collisions, swarm_addrs, Conn, Listener, SWARM_PORT},
+    pnet::Psk,
+    Error, Result,
+};
+
+/// Default address for the API listener.
+pub const API_ADDR: &str = "/ip4/127.0.0.1/tcp/5001";
+
+/// Default address for the gateway listener.
+pub const GATEWAY_ADDR: &str = "/ip4/127.0.0.1/tcp/8080";
+
+/// Listen addresses for swarm, API and gateway.
+#[derive(Clone, Debug)]
+pub struct ListenAddrs {
+    pub swarm: Vec<Multiaddr>,
+    pub api: Vec<Multiaddr>,
+    pub gateway: Vec<Multiaddr>,
+}
+
+/// Listeners bound for [ListenAddrs], in the same order.
+pub struct Bound {
+    pub swarm: Vec<Listener>,
+    pub api: Vec<Listener>,
+    pub gateway: Vec<Listener>,
+}
+
+impl ListenAddrs {
+    /// Default listen addresses, swarm on [swarm_addrs] for [SWARM_PORT],
+    /// API on [API_ADDR] and gateway on [GATEWAY_ADDR].
+    pub fn new() -> Result<ListenAddrs> {
+        let val = ListenAddrs {
+            swarm: Multiaddr::from_text_list("Addresses.swarm", &swarm_addrs(SWARM_PORT))?,
+            api: vec![Multiaddr::from_text(API_ADDR)?],
+            gateway: vec![Multiaddr::from_text(GATEWAY_ADDR)?],
+        };
+        Ok(val)
+    }
+
+    /// Fail if any of the swarm addresses can't be protected by a
+    /// pre-shared-key, only stream transports are wrapped.
+    pub fn check_pnet(&self) -> Result<()> {
+        for ma in self.swarm.iter() {
+            let comps = ma.clone().split()?;
+            if comps.iter().any(|c| matches!(c, Multiaddr::Udp(_, _))) {
+                err_at!(Invalid, msg: "private network can't protect {}", ma.to_text()?)?
+            }
+        }
+        Ok(())
+    }
+
+    /// Bind all the listeners. Addresses are checked for collisions
+    /// across swarm, API and gateway before binding any of them, and
+    /// either all of them are bound or none.
+    pub fn bind(&self) -> Result<Bound> {
+        let mut addrs = self.swarm.clone();
+        addrs.extend_from_slice(&self.api);
+        addrs.extend_from_slice(&self.gateway);
+        check_collisions(&addrs)?;
+
+        let swarm = Listener::bind_all(self.swarm.clone())?;
+        let api = match Listener::bind_all(self.api.clone()) {
+            Ok(api) => api,
+            Err(err) => {
+                swarm.into_iter().for_each(|listn| listn.close());
+                return Err(err);
+            }
+        };
+        let gateway = match Listener::bind_all(self.gateway.clone()) {
+            Ok(gateway) => gateway,
+            Err(err) => {
+                swarm.into_iter().for_each(|listn| listn.close());
+                api.into_iter().for_each(|listn| listn.close());
+                return Err(err);
+            }
+        };
+
+        Ok(Bound {
+            swarm,
+            api,
+            gateway,
+        })
+    }
+}
+
+impl Bound {
+    /// Close all listeners, removing socket files of `/unix` listeners.
+    pub fn close(self) {
+        let iter = self.swarm.into_iter().chain(self.api.into_iter());
+        iter.chain(self.gateway.into_iter())
+            .for_each(|listn| listn.close());
+    }
+}
+
+/// Subsystem holding the listeners for swarm, API and gateway.
+pub struct Listen {
+    addrs: ListenAddrs,
+    repo: path::PathBuf,
+    psk: Option<Psk>,
+    bound: Option<Bound>,
+}
+
+impl Listen {
+    /// Listen on `addrs`, the API address is published in the `api` file
+    /// under `repo`, refer [write_api_file][crate::ipfsd::write_api_file].
+    pub fn new(addrs: ListenAddrs, repo: path::PathBuf) -> Listen {
+        Listen {
+            addrs,
+            repo,
+            psk: None,
+            bound: None,
+        }
+    }
+
+    /// Accept a connection on the swarm listener at `index`. Connection is
+    /// wrapped for the private network, if the repo has a `swarm.key`.
+    pub fn accept_swarm(&self, index: usize) -> Result<Conn> {
+        let listn = match self.bound.as_ref().and_then(|b| b.swarm.get(index)) {
+            Some(listn) => listn,
+            None => err_at!(Invalid, msg: "no swarm listener at {}", index)?,
+        };
+        listn.accept_with(self.psk.as_ref())
+    }
+
+    /// Return the local address of each bound API listener.
+    pub fn to_api_addrs(&self) -> Result<Vec<Multiaddr>> {
+        match &self.bound {
+            Some(bound) => bound
+                .api
+                .iter()
+                .map(|listn| listn.to_local_addr()?.to_multiaddr())
+                .collect(),
+            None => Ok(vec![]),
+        }
+    }
+}
+
+impl Subsystem for Listen {
+    fn to_name(&self) -> String {
+        "listen".to_string()
+    }
+
+    fn start(&mut self) -> Result<()> {
+        self.psk = Psk::from_repo(&self.repo)?;
+        if self.psk.is_some() {
+            self.addrs.check_pnet()?;
+            info!("swarm in private network, {:?}", self.repo);
+        }
+
+        self.bound = Some(self.addrs.bind()?);
+        let res = match self.to_api_addrs()?.first() {
+            Some(ma) => {
+                info!("api listening on {}", ma.to_text()?);
+                api_file::write_api_file(&self.repo, ma)
+            }
+            None => Ok(()),
+        };
+        match (&res, self.bound.take()) {
+            (Err(_), Some(bound)) => bound.close(),
+            (_, bound) => self.bound = bound,
+        }
+        res
+    }
+
+    fn run(&mut self, fin: &cbm::Receiver<()>) -> Result<()> {
+        let _ = fin.recv();
+
+        if let Err(err) = api_file::remove_api_file(&self.repo) {
+            error!("remove api file in {:?}: {}", self.repo, err);
+        }
+        match self.bound.take() {
+            Some(bound) => bound.close(),
+            None => err_at!(Fatal, msg: "listeners not bound")?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[path = "listen_test.rs"]
+mod listen_test;
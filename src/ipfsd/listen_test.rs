@@ -0,0 +1,110 @@
+use super::*;
+
+#[test]
+fn test_listen_addrs() {
+    let addrs = ListenAddrs::new().unwrap();
+    let texts: Vec<String> = addrs.swarm.iter().map(|ma| ma.to_text().unwrap()).collect();
+    assert_eq!(texts, swarm_addrs(SWARM_PORT));
+    assert_eq!(addrs.api[0].to_text().unwrap(), API_ADDR);
+    assert_eq!(addrs.gateway[0].to_text().unwrap(), GATEWAY_ADDR);
+
+    let local = |text: &str| vec![Multiaddr::from_text(text).unwrap()];
+    let addrs = ListenAddrs {
+        swarm: local("/ip4/127.0.0.1/tcp/0"),
+        api: local("/ip4/127.0.0.1/tcp/0"),
+        gateway: local("/ip4/127.0.0.1/tcp/0"),
+    };
+    let bound = addrs.bind().unwrap();
+    assert_eq!(bound.swarm.len(), 1);
+    assert_eq!(bound.api.len(), 1);
+    assert_eq!(bound.gateway.len(), 1);
+    bound.close();
+
+    // gateway colliding with swarm is rejected before binding.
+    let mut bad = addrs.clone();
+    bad.swarm = local("/ip4/0.0.0.0/tcp/45011");
+    bad.gateway = local("/ip4/127.0.0.1/tcp/45011");
+    assert!(bad.bind().is_err());
+
+    // listeners bound so far are closed when gateway fails to bind.
+    let path = std::env::temp_dir().join(format!("iprs-listen-{}.sock", std::process::id()));
+    std::fs::remove_file(&path).ok();
+    let mut bad = addrs;
+    bad.api = local(&format!("/unix{}", path.to_str().unwrap()));
+    bad.gateway = local("/ip4/1.1.1.1/tcp/1");
+    assert!(bad.bind().is_err());
+    assert!(!path.exists());
+}
+
+#[test]
+fn test_listen_ready() {
+    use crate::ipfsd::{read_api_file, Ipfsd, Notifier, Restart};
+    use std::{fs, time::Duration};
+
+    let repo = std::env::temp_dir().join(format!("iprs-listen-repo-{}", std::process::id()));
+    fs::create_dir_all(&repo).unwrap();
+    let local = |text: &str| vec![Multiaddr::from_text(text).unwrap()];
+    let addrs = ListenAddrs {
+        swarm: local("/ip4/127.0.0.1/tcp/0"),
+        api: local("/ip4/127.0.0.1/tcp/0"),
+        gateway: local("/ip4/127.0.0.1/tcp/0"),
+    };
+
+    // ready only after the api is listening, and its address published.
+    let sys: Box<dyn Subsystem> = Box::new(Listen::new(addrs.clone(), repo.clone()));
+    let ipfsd = Ipfsd::spawn_notify(vec![(sys, Restart::Never)], Notifier::default()).unwrap();
+    assert!(ipfsd.wait_ready(Duration::from_secs(5)).unwrap());
+    let ma = read_api_file(&repo).unwrap().unwrap();
+    assert!(crate::net_conn::Conn::dial_multiaddr(ma).is_ok());
+    ipfsd.close_wait().unwrap();
+    assert!(read_api_file(&repo).unwrap().is_none());
+
+    // failing to bind fails the daemon.
+    let mut bad = addrs;
+    bad.gateway = local("/ip4/1.1.1.1/tcp/1");
+    let sys: Box<dyn Subsystem> = Box::new(Listen::new(bad, repo.clone()));
+    let ipfsd =
+        Ipfsd::spawn_notify(vec![(sys, Restart::OnFailure(3))], Notifier::default()).unwrap();
+    assert!(ipfsd.wait_ready(Duration::from_secs(5)).is_err());
+    assert!(ipfsd.close_wait().is_err());
+    assert!(read_api_file(&repo).unwrap().is_none());
+
+    fs::remove_dir_all(&repo).unwrap();
+}
+
+#[test]
+fn test_listen_pnet() {
+    use crate::pnet::SWARM_KEY_FILE;
+    use std::fs;
+
+    let repo = std::env::temp_dir().join(format!("iprs-listen-pnet-{}", std::process::id()));
+    fs::create_dir_all(&repo).unwrap();
+    let key = "/key/swarm/psk/1.0.0/\n/base16/\n\
+        e5cb10b6ba1a4d2a1f1e9c2a8c64ca1e6c8bc10a2f5fd5e4d5ad2fb9b2c1d3e4\n";
+    fs::write(repo.join(SWARM_KEY_FILE), key).unwrap();
+
+    let local = |text: &str| vec![Multiaddr::from_text(text).unwrap()];
+    let addrs = ListenAddrs {
+        swarm: local("/ip4/127.0.0.1/tcp/0"),
+        api: local("/ip4/127.0.0.1/tcp/0"),
+        gateway: local("/ip4/127.0.0.1/tcp/0"),
+    };
+
+    // accepted swarm connections are wrapped for the private network.
+    let mut listen = Listen::new(addrs.clone(), repo.clone());
+    listen.start().unwrap();
+    let listn = &listen.bound.as_ref().unwrap().swarm[0];
+    let ma = listn.to_local_addr().unwrap().to_multiaddr().unwrap();
+    let _conn = Conn::dial_multiaddr(ma).unwrap();
+    assert!(listen.accept_swarm(0).unwrap().is_pnet());
+    assert!(listen.accept_swarm(1).is_err());
+    listen.bound.take().unwrap().close();
+
+    // quic can't be wrapped, swarm refuses to start.
+    let mut bad = addrs;
+    bad.swarm = local("/ip4/127.0.0.1/udp/0/quic");
+    assert!(bad.check_pnet().is_err());
+    assert!(Listen::new(bad, repo.clone()).start().is_err());
+
+    fs::remove_dir_all(&repo).unwrap();
+}
@@ -1,3 +1,46 @@
+mod access_log;
+mod api_file;
+mod config;
+mod experiments;
+mod gateway;
+mod listen;
+mod node;
+mod notify;
+mod pinset;
+mod plugin;
+mod policy;
+mod repo_fs;
+mod routing_snapshot;
+#[cfg(not(target_arch = "wasm32"))]
+mod seal;
+mod swarm_hooks;
+mod task;
 mod thread;
+mod trustless;
 
-pub use thread::{Ipfsd, Client, Req, Res};
+pub use access_log::{AccessEntry, AccessLog, LogFormat, LOG_STDERR, LOG_STDOUT};
+pub use api_file::{daemon_addr, read_api_file, remove_api_file, repo_root, write_api_file};
+pub use config::{Config, Identity};
+pub use experiments::{Experiment, Experiments};
+pub use gateway::{sniff_content_type, GatewayParams, GatewayPath, OCTET_STREAM, SNIFF_LEN};
+pub use listen::{Bound, Listen, ListenAddrs, API_ADDR, GATEWAY_ADDR};
+pub use node::{Blocks, Dag, IpfsNode, Names, NodeBuilder, NodeRepo, Pins, Pubsub};
+pub use notify::{
+    notify, notify_ready, notify_stopping, notify_watchdog, watchdog_interval, Notifier,
+};
+pub use pinset::{PinMode, Pinset};
+pub use plugin::{Codec, Datastore, Plugins, Tracer};
+pub use policy::{codes_from_names, SecurityPolicy, WEAK_HASHES};
+pub use repo_fs::{loc_config, loc_datastore, FileRepo, CONFIG_FILE, DATASTORE_DIR};
+pub use routing_snapshot::{
+    RoutingPeer, RoutingSnapshot, RoutingTable, Snapshotter, MAX_PEER_AGE, SNAPSHOT_FILE,
+    SNAPSHOT_INTERVAL,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use seal::{RepoKey, SealedStore};
+pub use swarm_hooks::{ConnHook, ConnInfo, Direction, StreamInfo, SwarmHooks};
+pub use task::{
+    restart_backoff, HealthCheck, Restart, State, Subsystem, RESTART_BACKOFF, RESTART_BACKOFF_MAX,
+};
+pub use thread::{Client, Ipfsd, Req, Res, HEALTH_INTERVAL};
+pub use trustless::{HttpGet, HttpResponse, TcpHttp, TrustlessClient, FETCH_TIMEOUT, RAW_BLOCK};
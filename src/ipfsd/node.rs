@@ -0,0 +1,652 @@
+//! Module implement an embeddable IPFS node.
+//!
+//! Applications can run an IPFS node in-process, instead of shelling out
+//! to the CLI, using [IpfsNode::builder]:
+//!
+//! ```ignore
+//! let mut builder = IpfsNode::builder();
+//! builder.set_repo(NodeRepo::Memory).set_pubsub(true);
+//! let node = builder.build()?;
+//! let cid = node.to_blocks().put(multicodec::RAW.into(), b"hello")?;
+//! ```
+//!
+//! Operations are grouped into handles, [Blocks], [Dag], [Pins], [Names]
+//! and [Pubsub], borrowed from the node. Custom datastores and IPLD codecs
+//! are injected via [Plugins], and daemon subsystems, like swarm and
+//! gateway, are supervised by [Ipfsd] once the node is built.
+//!
+//! Names are resolved locally and pubsub messages are delivered to local
+//! subscribers, until the node is wired to routing and a pubsub router.
+//!
+//! For [NodeRepo::Disk], pins and names are persisted as dag-cbor DAGs in
+//! the repo's blockstore, their roots are recorded in [PINS_FILE] and
+//! [NAMES_FILE] under the repo root and loaded when the node is built.
+//!
+//! Blocks are admitted under the node's [SecurityPolicy], refer
+//! [NodeBuilder::set_policy], including blocks imported from CAR
+//! archives, refer [Dag::import]. The node is Send and Sync, handles can
+//! be used from several threads.
+
+use bytes::Bytes;
+use crossbeam_channel as cbm;
+use multibase::Base;
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    cid::Cid,
+    ipfsd::{
+        AccessLog, Codec, Datastore, Experiments, Ipfsd, Notifier, PinMode, Pinset, Plugins,
+        Restart, SecurityPolicy, Subsystem,
+    },
+    ipld::{
+        block::{get_node, Block},
+        car,
+        kind::{Basic, Node},
+        store::{MemStore, Store},
+        traverse::{Budget, Traversal},
+        typed::IpldNode,
+    },
+    multicodec::{self, Multicodec},
+    multihash::Multihash,
+    peer_id::PeerId,
+    pubsub::{Message, Topics, Validation},
+    Error, Result,
+};
+
+/// Name of the file, under [NodeRepo::Disk] root, holding the root of
+/// the pinset DAG.
+pub const PINS_FILE: &str = "pins";
+
+/// Name of the file, under [NodeRepo::Disk] root, holding the root of
+/// the names block, `{name: &Any}`.
+pub const NAMES_FILE: &str = "names";
+
+/// Repo backing an [IpfsNode].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum NodeRepo {
+    /// Blocks are held in memory and lost when the node is dropped.
+    Memory,
+    /// Blocks are held under `root`, using the named datastore backend,
+    /// refer [NodeBuilder::add_datastore].
+    Disk { root: PathBuf, datastore: String },
+}
+
+/// Builder for [IpfsNode], refer module documentation.
+pub struct NodeBuilder {
+    repo: NodeRepo,
+    plugins: Plugins,
+    subsystems: Vec<(Box<dyn Subsystem>, Restart)>,
+    pubsub: bool,
+    hash: Multicodec,
+    base: Base,
+    policy: SecurityPolicy,
+    experiments: Experiments,
+    notifier: Option<Notifier>,
+    access_log: Option<AccessLog>,
+}
+
+impl Default for NodeBuilder {
+    fn default() -> NodeBuilder {
+        NodeBuilder {
+            repo: NodeRepo::Memory,
+            plugins: Plugins::new(),
+            subsystems: vec![],
+            pubsub: false,
+            hash: multicodec::SHA2_256.into(),
+            base: Base::Base32Lower,
+            policy: SecurityPolicy::default(),
+            experiments: Experiments::default(),
+            notifier: None,
+            access_log: None,
+        }
+    }
+}
+
+impl NodeBuilder {
+    /// Set the repo, default is [NodeRepo::Memory].
+    pub fn set_repo(&mut self, repo: NodeRepo) -> &mut Self {
+        self.repo = repo;
+        self
+    }
+
+    /// Set plugins, replacing codecs and datastores registered so far.
+    pub fn set_plugins(&mut self, plugins: Plugins) -> &mut Self {
+        self.plugins = plugins;
+        self
+    }
+
+    /// Register an IPLD codec, refer [Plugins::add_codec].
+    pub fn add_codec(&mut self, codec: Arc<dyn Codec>) -> Result<&mut Self> {
+        self.plugins.add_codec(codec)?;
+        Ok(self)
+    }
+
+    /// Register a datastore backend, refer [Plugins::add_datastore].
+    pub fn add_datastore(&mut self, ds: Arc<dyn Datastore>) -> Result<&mut Self> {
+        self.plugins.add_datastore(ds)?;
+        Ok(self)
+    }
+
+    /// Add a subsystem to be supervised by the node's daemon, in the
+    /// order they shall be started, refer [Ipfsd::spawn_with].
+    pub fn add_subsystem(&mut self, sys: Box<dyn Subsystem>, restart: Restart) -> &mut Self {
+        self.subsystems.push((sys, restart));
+        self
+    }
+
+    /// Enable or disable pubsub, disabled by default.
+    pub fn set_pubsub(&mut self, enable: bool) -> &mut Self {
+        self.pubsub = enable;
+        self
+    }
+
+    /// Set hash algorithm for new blocks, default is sha2-256.
+    pub fn set_hash(&mut self, hash: Multicodec) -> &mut Self {
+        self.hash = hash;
+        self
+    }
+
+    /// Set multibase encoding for new CIDs, default is base32.
+    pub fn set_base(&mut self, base: Base) -> &mut Self {
+        self.base = base;
+        self
+    }
+
+    /// Set the policy for blocks admitted into the repo, default accepts
+    /// all, refer [SecurityPolicy::strict]. Hash for new blocks must be
+    /// allowed by the policy.
+    pub fn set_policy(&mut self, policy: SecurityPolicy) -> &mut Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Set experiments enabled for the node's subsystems, refer
+    /// [IpfsNode::as_experiments].
+    pub fn set_experiments(&mut self, experiments: Experiments) -> &mut Self {
+        self.experiments = experiments;
+        self
+    }
+
+    /// Set the service manager notifier for the node's daemon, default is
+    /// read from process environment, refer [Notifier::from_env].
+    pub fn set_notifier(&mut self, notifier: Notifier) -> &mut Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Set the logger for requests served by the node's gateway and API,
+    /// refer [IpfsNode::as_access_log]. Access logging is disabled by
+    /// default.
+    pub fn set_access_log(&mut self, access_log: AccessLog) -> &mut Self {
+        self.access_log = Some(access_log);
+        self
+    }
+
+    /// Open the repo, load its pins and names, and spawn the daemon with
+    /// configured subsystems.
+    pub fn build(self) -> Result<IpfsNode> {
+        self.policy.check_hash(self.hash.to_code())?;
+
+        let (store, pins, names) = match &self.repo {
+            NodeRepo::Memory => {
+                let store: Box<dyn Store + Send> = Box::new(MemStore::new());
+                (store, Pinset::new(), BTreeMap::new())
+            }
+            NodeRepo::Disk { root, datastore } => {
+                err_at!(IOError, fs::create_dir_all(root), "{:?}", root)?;
+                let store = open_datastore(&self.plugins, root, datastore)?;
+                let pins = match read_root(root, PINS_FILE)? {
+                    Some(cid) => Pinset::from_root(&cid, store.as_ref())?,
+                    None => Pinset::new(),
+                };
+                let names = match read_root(root, NAMES_FILE)? {
+                    Some(cid) => IpldNode::from_node(&get_node(&cid, store.as_ref())?)?,
+                    None => BTreeMap::new(),
+                };
+                (store, pins, names)
+            }
+        };
+        let ipfsd = match self.subsystems.is_empty() {
+            true => None,
+            false => {
+                let notifier = match self.notifier {
+                    Some(notifier) => notifier,
+                    None => Notifier::from_env()?,
+                };
+                Some(Ipfsd::spawn_notify(self.subsystems, notifier)?)
+            }
+        };
+        let pubsub = match self.pubsub {
+            true => Some(Mutex::new(LocalPubsub::default())),
+            false => None,
+        };
+
+        let val = IpfsNode {
+            repo: self.repo,
+            plugins: self.plugins,
+            hash: self.hash,
+            base: self.base,
+            policy: self.policy,
+            experiments: self.experiments,
+            access_log: self.access_log,
+            store: Mutex::new(store),
+            pins: Mutex::new(pins),
+            names: Mutex::new(names),
+            pubsub,
+            ipfsd,
+        };
+        Ok(val)
+    }
+}
+
+/// IPFS node embedded in application, refer module documentation.
+pub struct IpfsNode {
+    repo: NodeRepo,
+    plugins: Plugins,
+    hash: Multicodec,
+    base: Base,
+    policy: SecurityPolicy,
+    experiments: Experiments,
+    access_log: Option<AccessLog>,
+    store: Mutex<Box<dyn Store + Send>>,
+    pins: Mutex<Pinset>,
+    names: Mutex<BTreeMap<String, Cid>>,
+    pubsub: Option<Mutex<LocalPubsub>>,
+    ipfsd: Option<Ipfsd>,
+}
+
+impl IpfsNode {
+    pub fn builder() -> NodeBuilder {
+        NodeBuilder::default()
+    }
+
+    pub fn to_repo(&self) -> NodeRepo {
+        self.repo.clone()
+    }
+
+    pub fn as_plugins(&self) -> &Plugins {
+        &self.plugins
+    }
+
+    pub fn as_policy(&self) -> &SecurityPolicy {
+        &self.policy
+    }
+
+    /// Return the experiments enabled for the node, subsystems check them
+    /// at startup.
+    pub fn as_experiments(&self) -> &Experiments {
+        &self.experiments
+    }
+
+    /// Return the logger for requests served by the node's gateway and
+    /// API, None if access logging is disabled.
+    pub fn as_access_log(&self) -> Option<&AccessLog> {
+        self.access_log.as_ref()
+    }
+
+    /// Return the daemon supervising subsystems, None if no subsystem
+    /// was added.
+    pub fn as_ipfsd(&self) -> Option<&Ipfsd> {
+        self.ipfsd.as_ref()
+    }
+
+    pub fn to_blocks(&self) -> Blocks {
+        Blocks { node: self }
+    }
+
+    pub fn to_dag(&self) -> Dag {
+        Dag { node: self }
+    }
+
+    pub fn to_pins(&self) -> Pins {
+        Pins { node: self }
+    }
+
+    pub fn to_names(&self) -> Names {
+        Names { node: self }
+    }
+
+    /// Return pubsub handle, fail if pubsub is not enabled.
+    pub fn to_pubsub(&self) -> Result<Pubsub> {
+        match &self.pubsub {
+            Some(pubsub) => Ok(Pubsub { pubsub }),
+            None => err_at!(Invalid, msg: "pubsub not enabled"),
+        }
+    }
+
+    // record `root` of a DAG, whose blocks are already in store, in file
+    // `name` under the repo root. Memory repo keeps nothing.
+    fn write_root(&self, name: &str, root: &Cid) -> Result<()> {
+        match &self.repo {
+            NodeRepo::Memory => Ok(()),
+            NodeRepo::Disk { root: dir, .. } => {
+                let (loc, tmp) = (dir.join(name), dir.join(format!("{}.tmp", name)));
+                err_at!(IOError, fs::write(&tmp, root.to_text(None)?), "{:?}", tmp)?;
+                err_at!(IOError, fs::rename(&tmp, &loc), "{:?}", loc)
+            }
+        }
+    }
+
+    fn save_pins(&self, pins: &Pinset) -> Result<()> {
+        if let NodeRepo::Memory = self.repo {
+            return Ok(());
+        }
+        let (root, blocks) = pins.to_blocks(self.hash)?;
+        let mut store = err_at!(Fatal, self.store.lock())?;
+        for block in blocks.into_iter() {
+            store.put(block)?;
+        }
+        self.write_root(PINS_FILE, &root)
+    }
+
+    fn save_names(&self, names: &BTreeMap<String, Cid>) -> Result<()> {
+        if let NodeRepo::Memory = self.repo {
+            return Ok(());
+        }
+        let block = Block::from_dag_cbor(self.hash, &names.to_basic()?)?;
+        let root = block.to_cid()?;
+        err_at!(Fatal, self.store.lock())?.put(block)?;
+        self.write_root(NAMES_FILE, &root)
+    }
+
+    /// Shutdown the node, stopping its subsystems.
+    pub fn close_wait(self) -> Result<()> {
+        match self.ipfsd {
+            Some(ipfsd) => ipfsd.close_wait(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Handle for raw block operations.
+pub struct Blocks<'a> {
+    node: &'a IpfsNode,
+}
+
+impl<'a> Blocks<'a> {
+    /// Add `data` as a block of `content_type`, return its cid. Data is
+    /// copied once into the block, refer [Blocks::put_bytes].
+    pub fn put(&self, content_type: Multicodec, data: &[u8]) -> Result<Cid> {
+        self.put_bytes(content_type, Bytes::copy_from_slice(data))
+    }
+
+    /// Same as [Blocks::put], but the block shares `data` without
+    /// copying it.
+    pub fn put_bytes(&self, content_type: Multicodec, data: Bytes) -> Result<Cid> {
+        let mh = Multihash::new(self.node.hash, &data)?;
+        let cid = Cid::from_raw(self.node.base, content_type, mh);
+        self.put_block(Block::new(cid.clone(), data))?;
+        Ok(cid)
+    }
+
+    /// Add a block, fail if its data doesn't match its cid, or if its
+    /// hash or codec is denied by the node's [SecurityPolicy].
+    pub fn put_block(&self, block: Block) -> Result<()> {
+        self.node.policy.check_cid(&block.to_cid()?)?;
+        if !block.verify()? {
+            err_at!(BadInput, msg: "block data doesn't match {}", block.to_cid()?)?
+        }
+        err_at!(Fatal, self.node.store.lock())?.put(block)
+    }
+
+    /// Return the block for `cid`, None if not in repo.
+    pub fn get(&self, cid: &Cid) -> Result<Option<Block>> {
+        err_at!(Fatal, self.node.store.lock())?.get(cid)
+    }
+
+    pub fn has(&self, cid: &Cid) -> Result<bool> {
+        Ok(self.get(cid)?.is_some())
+    }
+}
+
+/// Handle for IPLD data-model operations, using codecs registered with
+/// the node's [Plugins].
+pub struct Dag<'a> {
+    node: &'a IpfsNode,
+}
+
+impl<'a> Dag<'a> {
+    /// Encode `node` using `codec` and add it as a block, return its cid.
+    /// Fail if `codec` is denied by the node's [SecurityPolicy].
+    pub fn put(&self, node: &dyn Node, codec: Multicodec) -> Result<Cid> {
+        self.node.policy.check_codec(codec.to_code())?;
+        let data = match self.node.plugins.to_codec(codec.to_code()) {
+            Some(c) => c.encode(node)?,
+            None => err_at!(NotImplemented, msg: "no codec registered for {}", codec)?,
+        };
+        self.node.to_blocks().put_bytes(codec, data.into())
+    }
+
+    /// Decode the block for `cid` into data-model, None if not in repo.
+    pub fn get(&self, cid: &Cid) -> Result<Option<Basic>> {
+        match self.node.to_blocks().get(cid)? {
+            Some(block) => Ok(Some(self.node.plugins.decode_block(&block)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Import blocks from CARv1 archive `reader` into the repo, refer
+    /// [car::import]. Reader's policy is set to the node's
+    /// [SecurityPolicy], blocks are verified by the reader. When `pin`
+    /// is true, archive roots that are in repo are pinned recursively
+    /// once all blocks are imported, fail if a root's DAG is incomplete.
+    ///
+    /// Interrupted imports can be resumed using [car::Reader::resume]
+    /// from the offset returned by [car::Reader::to_offset], roots read
+    /// before the interruption are pinned by the resumed import.
+    pub fn import<R>(&self, reader: &mut car::Reader<R>, pin: bool) -> Result<car::Import>
+    where
+        R: io::Read,
+    {
+        reader.set_policy(self.node.policy.clone());
+        let res = car::import(reader, |block| {
+            err_at!(Fatal, self.node.store.lock())?.put(block)
+        })?;
+
+        if pin {
+            let (blocks, pins) = (self.node.to_blocks(), self.node.to_pins());
+            for (root, found) in res.roots.iter() {
+                if *found || blocks.has(root)? {
+                    pins.pin(root.clone(), PinMode::Recursive)?;
+                }
+            }
+        }
+        Ok(res)
+    }
+
+    /// Export the DAG under `root` as CARv1 archive into `w`, refer
+    /// [car::export]. Fail if the DAG is incomplete.
+    pub fn export<W>(&self, root: &Cid, w: W) -> Result<car::Export>
+    where
+        W: io::Write,
+    {
+        let store = err_at!(Fatal, self.node.store.lock())?;
+        let store: &dyn Store = store.as_ref();
+        car::export(root, store, w)
+    }
+}
+
+/// Handle for pinning operations.
+pub struct Pins<'a> {
+    node: &'a IpfsNode,
+}
+
+impl<'a> Pins<'a> {
+    /// Pin `cid` with `mode`, fail if the block is not in repo. For
+    /// recursive pins, every block under `cid` must be in repo.
+    pub fn pin(&self, cid: Cid, mode: PinMode) -> Result<()> {
+        match mode {
+            PinMode::Direct if !self.node.to_blocks().has(&cid)? => {
+                err_at!(Invalid, msg: "pin {}, block not in repo", cid)?
+            }
+            PinMode::Direct => (),
+            PinMode::Recursive => {
+                let store = err_at!(Fatal, self.node.store.lock())?;
+                let store: &dyn Store = store.as_ref();
+                for visit in Traversal::new(&cid, store, Budget::default()) {
+                    if let Err(err) = visit {
+                        err_at!(Invalid, msg: "pin {}, incomplete DAG: {}", cid, err)?
+                    }
+                }
+            }
+        }
+
+        let mut pins = err_at!(Fatal, self.node.pins.lock())?;
+        pins.pin(cid, mode)?;
+        self.node.save_pins(&pins)
+    }
+
+    /// Unpin `cid`, return false if it was not pinned.
+    pub fn unpin(&self, cid: &Cid) -> Result<bool> {
+        let mut pins = err_at!(Fatal, self.node.pins.lock())?;
+        let ok = pins.unpin(cid)?;
+        if ok {
+            self.node.save_pins(&pins)?;
+        }
+        Ok(ok)
+    }
+
+    pub fn to_mode(&self, cid: &Cid) -> Result<Option<PinMode>> {
+        err_at!(Fatal, self.node.pins.lock())?.to_mode(cid)
+    }
+
+    pub fn to_pins(&self) -> Result<Vec<(Cid, PinMode)>> {
+        Ok(err_at!(Fatal, self.node.pins.lock())?.to_pins())
+    }
+
+    /// Export the pinset, along with the pinned blocks, as CARv1 archive
+    /// into `w`, for backup. Archive is rooted at the pinset's root, refer
+    /// [Pinset], root block is written first followed by the buckets.
+    /// For recursive pins the whole DAG is written, for direct pins only
+    /// the pinned block.
+    pub fn export<W>(&self, w: W) -> Result<car::Export>
+    where
+        W: io::Write,
+    {
+        let pins = err_at!(Fatal, self.node.pins.lock())?;
+        let (root, mut blocks) = pins.to_blocks(self.node.hash)?;
+        let store = err_at!(Fatal, self.node.store.lock())?;
+        let store: &dyn Store = store.as_ref();
+
+        let mut writer = car::Writer::new(w, &[root])?;
+        let mut visited = HashSet::new();
+        let mut n_blocks = 0;
+
+        // pinset blocks are not in store for memory repo, and their links
+        // are the pins themselves, hence written one by one.
+        blocks.rotate_right(1);
+        for block in blocks.iter() {
+            if visited.insert(block.to_multihash()?.encode()?) {
+                writer.write_block(block)?;
+                n_blocks += 1;
+            }
+        }
+        for (cid, mode) in pins.to_pins().into_iter() {
+            let recursive = mode == PinMode::Recursive;
+            n_blocks += car::write_dag(&mut writer, &cid, store, &mut visited, recursive)?;
+        }
+
+        let size = writer.to_size();
+        writer.into_inner()?;
+        Ok(car::Export { n_blocks, size })
+    }
+}
+
+/// Handle for name operations, names are resolved locally.
+pub struct Names<'a> {
+    node: &'a IpfsNode,
+}
+
+impl<'a> Names<'a> {
+    /// Point `name` to `cid`, return the previous value.
+    pub fn publish(&self, name: &str, cid: Cid) -> Result<Option<Cid>> {
+        let mut names = err_at!(Fatal, self.node.names.lock())?;
+        let old = names.insert(name.to_string(), cid);
+        self.node.save_names(&names)?;
+        Ok(old)
+    }
+
+    pub fn resolve(&self, name: &str) -> Result<Option<Cid>> {
+        let names = err_at!(Fatal, self.node.names.lock())?;
+        Ok(names.get(name).cloned())
+    }
+}
+
+#[derive(Default)]
+struct LocalPubsub {
+    topics: Topics,
+    subscribers: BTreeMap<String, Vec<cbm::Sender<Message>>>,
+}
+
+/// Handle for pubsub operations, messages are delivered to local
+/// subscribers after validation, refer [Topics].
+pub struct Pubsub<'a> {
+    pubsub: &'a Mutex<LocalPubsub>,
+}
+
+impl<'a> Pubsub<'a> {
+    /// Subscribe to `topic`, messages are received on the returned
+    /// channel. Dropping the receiver unsubscribes.
+    pub fn subscribe(&self, topic: &str) -> Result<cbm::Receiver<Message>> {
+        let (tx, rx) = cbm::unbounded();
+        let mut pubsub = err_at!(Fatal, self.pubsub.lock())?;
+        pubsub
+            .subscribers
+            .entry(topic.to_string())
+            .or_insert_with(Vec::new)
+            .push(tx);
+        Ok(rx)
+    }
+
+    /// Publish `msg` from `src`, return the validation outcome. Only
+    /// accepted messages are delivered.
+    pub fn publish(&self, src: &PeerId, msg: Message) -> Result<Validation> {
+        let mut pubsub = err_at!(Fatal, self.pubsub.lock())?;
+        let res = pubsub.topics.validate(src, &msg);
+        if res.is_accepted() {
+            if let Some(subs) = pubsub.subscribers.get_mut(&msg.topic) {
+                subs.retain(|tx| tx.send(msg.clone()).is_ok());
+            }
+        }
+        Ok(res)
+    }
+
+    /// Apply `f` on the topic registry, to add validators and message-id
+    /// functions.
+    pub fn with_topics<F, T>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Topics) -> T,
+    {
+        let mut pubsub = err_at!(Fatal, self.pubsub.lock())?;
+        Ok(f(&mut pubsub.topics))
+    }
+}
+
+fn open_datastore(plugins: &Plugins, root: &Path, name: &str) -> Result<Box<dyn Store + Send>> {
+    match plugins.to_datastore(name) {
+        Some(ds) => ds.open(root),
+        None => err_at!(NotImplemented, msg: "no datastore registered as {:?}", name),
+    }
+}
+
+// read the root cid recorded in file `name` under `dir`, None if missing.
+fn read_root(dir: &Path, name: &str) -> Result<Option<Cid>> {
+    let loc = dir.join(name);
+    match loc.exists() {
+        true => {
+            let text = err_at!(IOError, fs::read_to_string(&loc), "{:?}", loc)?;
+            Ok(Some(Cid::from_text(text.trim())?))
+        }
+        false => Ok(None),
+    }
+}
+
+#[cfg(test)]
+#[path = "node_test.rs"]
+mod node_test;
@@ -0,0 +1,413 @@
+use crossbeam_channel as cbm;
+
+use std::process;
+
+use super::*;
+use crate::ipfsd::{Experiment, LogFormat, LOG_STDOUT};
+
+// blocks outlive the node, like a datastore on disk.
+#[derive(Default)]
+struct MemDatastore {
+    roots: Mutex<Vec<PathBuf>>,
+    store: Arc<Mutex<MemStore>>,
+}
+
+struct SharedStore(Arc<Mutex<MemStore>>);
+
+impl Store for SharedStore {
+    fn get(&self, cid: &Cid) -> Result<Option<Block>> {
+        self.0.lock().unwrap().get(cid)
+    }
+
+    fn put(&mut self, block: Block) -> Result<()> {
+        self.0.lock().unwrap().put(block)
+    }
+}
+
+impl Datastore for MemDatastore {
+    fn to_name(&self) -> String {
+        "mem".to_string()
+    }
+
+    fn open(&self, root: &Path) -> Result<Box<dyn Store + Send>> {
+        self.roots.lock().unwrap().push(root.to_path_buf());
+        Ok(Box::new(SharedStore(Arc::clone(&self.store))))
+    }
+}
+
+fn disk_repo(name: &str) -> (PathBuf, NodeRepo) {
+    let root = std::env::temp_dir().join(format!("iprs-node-{}-{}", name, process::id()));
+    fs::remove_dir_all(&root).ok();
+    let repo = NodeRepo::Disk {
+        root: root.clone(),
+        datastore: "mem".to_string(),
+    };
+    (root, repo)
+}
+
+struct Idle;
+
+impl Subsystem for Idle {
+    fn to_name(&self) -> String {
+        "idle".to_string()
+    }
+
+    fn run(&mut self, fin: &cbm::Receiver<()>) -> Result<()> {
+        let _ = fin.recv();
+        Ok(())
+    }
+}
+
+#[test]
+fn test_node_blocks() {
+    let node = IpfsNode::builder().build().unwrap();
+    assert_eq!(node.to_repo(), NodeRepo::Memory);
+    assert!(node.as_access_log().is_none());
+    assert!(node.as_ipfsd().is_none());
+
+    let blocks = node.to_blocks();
+    let cid = blocks.put(multicodec::RAW.into(), b"hello").unwrap();
+    assert!(blocks.has(&cid).unwrap());
+    let block = blocks.get(&cid).unwrap().unwrap();
+    assert_eq!(block.as_block_data().unwrap(), b"hello");
+
+    let bad = Block::new(cid, b"world".to_vec().into());
+    assert!(blocks.put_block(bad).is_err());
+    node.close_wait().unwrap();
+}
+
+#[test]
+fn test_node_dag_pins_names() {
+    let node = IpfsNode::builder().build().unwrap();
+
+    let value = Basic::Text(b"hello".to_vec());
+    let cid = node
+        .to_dag()
+        .put(&value, multicodec::DAG_CBOR.into())
+        .unwrap();
+    assert_eq!(cid.to_content_type().to_code(), multicodec::DAG_CBOR);
+    let node_value = node.to_dag().get(&cid).unwrap().unwrap();
+    assert_eq!(node_value.as_string().unwrap().unwrap(), "hello");
+
+    let pins = node.to_pins();
+    pins.pin(cid.clone(), PinMode::Recursive).unwrap();
+    assert_eq!(pins.to_mode(&cid).unwrap(), Some(PinMode::Recursive));
+    let missing = Cid::new_v1(Base::Base32Lower, multicodec::RAW.into(), b"x").unwrap();
+    assert!(pins.pin(missing, PinMode::Direct).is_err());
+    assert!(pins.unpin(&cid).unwrap());
+    assert!(pins.to_pins().unwrap().is_empty());
+
+    let names = node.to_names();
+    assert!(names.resolve("site").unwrap().is_none());
+    assert!(names.publish("site", cid.clone()).unwrap().is_none());
+    assert!(names.resolve("site").unwrap().unwrap() == cid);
+}
+
+#[test]
+fn test_node_pin_recursive() {
+    let node = IpfsNode::builder().build().unwrap();
+    let missing = Cid::new_v1(Base::Base32Lower, multicodec::RAW.into(), b"x").unwrap();
+
+    let mut links = BTreeMap::new();
+    links.insert("child".to_string(), missing.clone());
+    let value = links.to_basic().unwrap();
+    let cid = node
+        .to_dag()
+        .put(&value, multicodec::DAG_CBOR.into())
+        .unwrap();
+
+    // child not in repo, recursive pin fails, direct pin needs only root.
+    let pins = node.to_pins();
+    assert!(pins.pin(cid.clone(), PinMode::Recursive).is_err());
+    assert_eq!(pins.to_mode(&cid).unwrap(), None);
+    pins.pin(cid.clone(), PinMode::Direct).unwrap();
+    assert_eq!(pins.to_mode(&cid).unwrap(), Some(PinMode::Direct));
+
+    let child = node.to_blocks().put(multicodec::RAW.into(), b"x").unwrap();
+    assert!(child == missing);
+    pins.pin(cid.clone(), PinMode::Recursive).unwrap();
+    assert_eq!(pins.to_mode(&cid).unwrap(), Some(PinMode::Recursive));
+}
+
+#[test]
+fn test_node_import() {
+    use crate::ipld::car;
+
+    // root linking to a child, and a second root not in the archive.
+    let src = IpfsNode::builder().build().unwrap();
+    let child = src.to_blocks().put(multicodec::RAW.into(), b"x").unwrap();
+    let mut links = BTreeMap::new();
+    links.insert("child".to_string(), child.clone());
+    let value = links.to_basic().unwrap();
+    let root = src
+        .to_dag()
+        .put(&value, multicodec::DAG_CBOR.into())
+        .unwrap();
+    let missing = Cid::new_v1(Base::Base32Lower, multicodec::RAW.into(), b"y").unwrap();
+
+    let write_car = |cids: &[&Cid]| {
+        let mut w = car::Writer::new(vec![], &[root.clone(), missing.clone()]).unwrap();
+        for cid in cids.iter() {
+            let block = src.to_blocks().get(cid).unwrap().unwrap();
+            w.write_block(&block).unwrap();
+        }
+        w.into_inner().unwrap()
+    };
+
+    let buf = write_car(&[&child, &root]);
+    let node = IpfsNode::builder().build().unwrap();
+    let mut reader = car::Reader::new(buf.as_slice()).unwrap();
+    let res = node.to_dag().import(&mut reader, true).unwrap();
+    assert_eq!(res.n_blocks, 2);
+    assert_eq!(
+        res.roots,
+        vec![(root.clone(), true), (missing.clone(), false)]
+    );
+    let pins = node.to_pins();
+    assert_eq!(pins.to_mode(&root).unwrap(), Some(PinMode::Recursive));
+    assert_eq!(pins.to_mode(&missing).unwrap(), None);
+
+    // resumed import pins the root read before the interruption.
+    let buf = write_car(&[&root, &child]);
+    let node = IpfsNode::builder().build().unwrap();
+    let mut reader = car::Reader::new(buf.as_slice()).unwrap();
+    node.to_blocks()
+        .put_block(reader.next().unwrap().unwrap())
+        .unwrap();
+    let off = reader.to_offset();
+    let mut reader = car::Reader::resume(&buf[off as usize..], reader.to_roots(), off);
+    let res = node.to_dag().import(&mut reader, true).unwrap();
+    assert_eq!(res.roots[0], (root.clone(), false));
+    assert_eq!(
+        node.to_pins().to_mode(&root).unwrap(),
+        Some(PinMode::Recursive)
+    );
+
+    // incomplete DAG is imported, but not pinned.
+    let buf = write_car(&[&root]);
+    let node = IpfsNode::builder().build().unwrap();
+    let mut reader = car::Reader::new(buf.as_slice()).unwrap();
+    assert!(node.to_dag().import(&mut reader, true).is_err());
+    assert!(node.to_blocks().has(&root).unwrap());
+    assert_eq!(node.to_pins().to_mode(&root).unwrap(), None);
+    let mut reader = car::Reader::new(buf.as_slice()).unwrap();
+    assert_eq!(
+        node.to_dag().import(&mut reader, false).unwrap().n_blocks,
+        1
+    );
+
+    // blocks are admitted under node's policy.
+    let mut policy = SecurityPolicy::default();
+    policy.deny_codec(multicodec::DAG_CBOR);
+    let mut builder = IpfsNode::builder();
+    builder.set_policy(policy);
+    let node = builder.build().unwrap();
+    let buf = write_car(&[&child, &root]);
+    let mut reader = car::Reader::new(buf.as_slice()).unwrap();
+    assert!(node.to_dag().import(&mut reader, false).is_err());
+    assert!(node.to_blocks().has(&child).unwrap());
+    assert!(!node.to_blocks().has(&root).unwrap());
+}
+
+#[test]
+fn test_node_export() {
+    use crate::ipld::car;
+
+    // recursive pin on a root linking to a child, direct pin on a root
+    // whose child is not in repo.
+    let src = IpfsNode::builder().build().unwrap();
+    let child = src.to_blocks().put(multicodec::RAW.into(), b"x").unwrap();
+    let missing = Cid::new_v1(Base::Base32Lower, multicodec::RAW.into(), b"y").unwrap();
+    let put_root = |name: &str, cid: &Cid| {
+        let mut links = BTreeMap::new();
+        links.insert(name.to_string(), cid.clone());
+        let value = links.to_basic().unwrap();
+        src.to_dag()
+            .put(&value, multicodec::DAG_CBOR.into())
+            .unwrap()
+    };
+    let (root, direct) = (put_root("child", &child), put_root("missing", &missing));
+    let pins = src.to_pins();
+    pins.pin(root.clone(), PinMode::Recursive).unwrap();
+    pins.pin(direct.clone(), PinMode::Direct).unwrap();
+
+    let mut buf = vec![];
+    assert_eq!(src.to_dag().export(&root, &mut buf).unwrap().n_blocks, 2);
+    assert!(src.to_dag().export(&direct, vec![]).is_err());
+
+    // pinset root, one bucket, and three pinned blocks.
+    let mut buf = vec![];
+    let res = pins.export(&mut buf).unwrap();
+    assert_eq!(res.n_blocks, 5);
+    assert_eq!(res.size, buf.len());
+
+    let node = IpfsNode::builder().build().unwrap();
+    let mut reader = car::Reader::new(buf.as_slice()).unwrap();
+    let res = node.to_dag().import(&mut reader, false).unwrap();
+    assert_eq!(res.n_blocks, 5);
+    let (pinset_root, found) = res.roots[0].clone();
+    assert!(found);
+
+    let store = node.store.lock().unwrap();
+    let pinset = Pinset::from_root(&pinset_root, store.as_ref()).unwrap();
+    assert_eq!(pinset.to_pins(), pins.to_pins().unwrap());
+    for cid in [&root, &child, &direct].iter() {
+        assert!(store.get(cid).unwrap().is_some());
+    }
+}
+
+#[test]
+fn test_node_policy() {
+    let mut policy = SecurityPolicy::default();
+    policy
+        .deny_hash(multicodec::SHA1)
+        .deny_codec(multicodec::DAG_CBOR);
+    let mut experiments = Experiments::default();
+    experiments.enable(Experiment::OptimisticProvide);
+
+    let mut builder = IpfsNode::builder();
+    builder
+        .set_policy(policy.clone())
+        .set_experiments(experiments);
+    let node = builder.build().unwrap();
+    assert_eq!(node.as_policy(), &policy);
+    assert!(node
+        .as_experiments()
+        .is_enabled(Experiment::OptimisticProvide));
+
+    let blocks = node.to_blocks();
+    assert!(blocks.put(multicodec::RAW.into(), b"hello").is_ok());
+    let value = Basic::Text(b"hello".to_vec());
+    let res = node.to_dag().put(&value, multicodec::DAG_CBOR.into());
+    assert!(res.is_err());
+
+    // denied hash, both for blocks added to the node and for the node's
+    // own hash.
+    let hash = Multihash::new(multicodec::SHA1.into(), b"hello").unwrap();
+    let cid = Cid::from_raw(Base::Base32Lower, multicodec::RAW.into(), hash);
+    let block = Block::new(cid, Bytes::from_static(b"hello"));
+    assert!(blocks.put_block(block).is_err());
+
+    let mut builder = IpfsNode::builder();
+    builder.set_policy(policy).set_hash(multicodec::SHA1.into());
+    assert!(builder.build().is_err());
+}
+
+#[test]
+fn test_node_persist() {
+    let (root, repo) = disk_repo("persist");
+    let ds = Arc::new(MemDatastore::default());
+    let build = || {
+        let mut builder = IpfsNode::builder();
+        builder
+            .set_repo(repo.clone())
+            .add_datastore(ds.clone())
+            .unwrap();
+        builder.build().unwrap()
+    };
+
+    let node = build();
+    let cid = node
+        .to_blocks()
+        .put(multicodec::RAW.into(), b"hello")
+        .unwrap();
+    node.to_pins().pin(cid.clone(), PinMode::Recursive).unwrap();
+    node.to_names().publish("site", cid.clone()).unwrap();
+    node.close_wait().unwrap();
+    assert!(root.join(PINS_FILE).exists());
+    assert!(root.join(NAMES_FILE).exists());
+
+    let node = build();
+    let pins = node.to_pins();
+    assert_eq!(
+        pins.to_pins().unwrap(),
+        vec![(cid.clone(), PinMode::Recursive)]
+    );
+    assert!(node.to_names().resolve("site").unwrap().unwrap() == cid);
+    assert!(pins.unpin(&cid).unwrap());
+    node.close_wait().unwrap();
+
+    let node = build();
+    assert!(node.to_pins().to_pins().unwrap().is_empty());
+    node.close_wait().unwrap();
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_node_pubsub() {
+    let node = IpfsNode::builder().build().unwrap();
+    assert!(node.to_pubsub().is_err());
+
+    let mut builder = IpfsNode::builder();
+    builder.set_pubsub(true);
+    let node = builder.build().unwrap();
+    let pubsub = node.to_pubsub().unwrap();
+
+    let rx = pubsub.subscribe("crdt").unwrap();
+    pubsub
+        .with_topics(|topics| {
+            topics.add_validator(
+                "crdt",
+                Arc::new(|_: &PeerId, msg: &Message| match msg.data.is_empty() {
+                    true => Validation::Reject,
+                    false => Validation::Accept,
+                }),
+            );
+        })
+        .unwrap();
+
+    let src = PeerId::generate().unwrap();
+    let mut msg = Message {
+        from: Some(src.clone()),
+        data: vec![],
+        seqno: None,
+        topic: "crdt".to_string(),
+    };
+    assert_eq!(
+        pubsub.publish(&src, msg.clone()).unwrap(),
+        Validation::Reject
+    );
+    msg.data = b"hello".to_vec();
+    assert_eq!(pubsub.publish(&src, msg).unwrap(), Validation::Accept);
+
+    assert_eq!(rx.try_recv().unwrap().data, b"hello".to_vec());
+    assert!(rx.try_recv().is_err());
+}
+
+#[test]
+fn test_node_builder() {
+    let (root, repo) = disk_repo("builder");
+
+    let mut builder = IpfsNode::builder();
+    builder.set_repo(repo.clone());
+    assert!(builder.build().is_err());
+
+    let ds = Arc::new(MemDatastore::default());
+    let mut builder = IpfsNode::builder();
+    builder
+        .set_repo(repo.clone())
+        .add_datastore(ds.clone())
+        .unwrap()
+        .set_hash(multicodec::SHA2_512.into())
+        .set_notifier(Notifier::default())
+        .set_access_log(AccessLog::open(LogFormat::Json, LOG_STDOUT).unwrap())
+        .add_subsystem(Box::new(Idle), Restart::Never);
+    let node = builder.build().unwrap();
+    assert_eq!(node.to_repo(), repo);
+    assert_eq!(node.as_access_log().unwrap().to_format(), LogFormat::Json);
+    assert_eq!(*ds.roots.lock().unwrap(), vec![root.clone()]);
+
+    let cid = node
+        .to_blocks()
+        .put(multicodec::RAW.into(), b"hello")
+        .unwrap();
+    let codec = cid.to_multihash().to_codec().unwrap();
+    assert_eq!(codec.to_code(), multicodec::SHA2_512);
+
+    let ipfsd = node.as_ipfsd().unwrap();
+    assert!(ipfsd.wait_ready(std::time::Duration::from_secs(5)).unwrap());
+    node.close_wait().unwrap();
+
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,126 @@
+//! Module implement service manager notification, refer sd_notify(3).
+//!
+//! When ipfs-daemon is run as a systemd service with `Type=notify`, the
+//! service manager passes a datagram socket in `NOTIFY_SOCKET`. Daemon
+//! shall send `READY=1` once the swarm, API and gateway are listening,
+//! and when `WatchdogSec=` is configured, keep sending `WATCHDOG=1`
+//! within the interval passed in `WATCHDOG_USEC`. Outside systemd, these
+//! variables are not set and notifications are skipped.
+//!
+//! [Notifier] reads these variables once, [Notifier::from_env] from the
+//! process environment and [Notifier::from_vars] from a lookup function.
+
+use std::{env, ffi::OsString, os::unix::net::UnixDatagram, process, time::Duration};
+
+use crate::{Error, Result};
+
+/// Notification settings passed by the service manager.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Notifier {
+    socket: Option<OsString>,
+    watchdog: Option<Duration>,
+}
+
+impl Notifier {
+    /// Read `NOTIFY_SOCKET`, `WATCHDOG_USEC` and `WATCHDOG_PID` from the
+    /// process environment.
+    pub fn from_env() -> Result<Notifier> {
+        Notifier::from_vars(|key| env::var_os(key))
+    }
+
+    /// Read `NOTIFY_SOCKET`, `WATCHDOG_USEC` and `WATCHDOG_PID` using
+    /// `getenv`, which returns None for variables that are not set.
+    pub fn from_vars<F>(getenv: F) -> Result<Notifier>
+    where
+        F: Fn(&str) -> Option<OsString>,
+    {
+        let socket = getenv("NOTIFY_SOCKET").filter(|path| !path.is_empty());
+        let to_string = |key: &str| getenv(key).map(|val| val.to_string_lossy().to_string());
+
+        let usec = match to_string("WATCHDOG_USEC") {
+            Some(usec) => err_at!(ParseError, usec.parse::<u64>(), "WATCHDOG_USEC")?,
+            None => 0,
+        };
+        // watchdog is meant for another process.
+        let pid = match to_string("WATCHDOG_PID") {
+            Some(pid) => err_at!(ParseError, pid.parse::<u32>(), "WATCHDOG_PID")?,
+            None => process::id(),
+        };
+        let watchdog = match usec {
+            0 => None,
+            _ if pid != process::id() => None,
+            usec => Some(Duration::from_micros(usec)),
+        };
+
+        Ok(Notifier { socket, watchdog })
+    }
+
+    /// Send `state` to the service manager, like "READY=1" or
+    /// "STOPPING=1". Return false if not running under a service manager.
+    pub fn notify(&self, state: &str) -> Result<bool> {
+        let path = match &self.socket {
+            Some(path) => path,
+            None => return Ok(false),
+        };
+        // abstract socket addresses are not supported by std.
+        if path.to_string_lossy().starts_with('@') {
+            err_at!(NotImplemented, msg: "abstract NOTIFY_SOCKET {:?}", path)?
+        }
+
+        let sock = err_at!(IOError, UnixDatagram::unbound())?;
+        err_at!(IOError, sock.send_to(state.as_bytes(), path), "{:?}", path)?;
+        Ok(true)
+    }
+
+    /// Notify that daemon is ready to serve.
+    pub fn notify_ready(&self) -> Result<bool> {
+        self.notify("READY=1")
+    }
+
+    /// Notify that daemon is shutting down.
+    pub fn notify_stopping(&self) -> Result<bool> {
+        self.notify("STOPPING=1")
+    }
+
+    /// Ping the service manager's watchdog.
+    pub fn notify_watchdog(&self) -> Result<bool> {
+        self.notify("WATCHDOG=1")
+    }
+
+    /// Return the watchdog interval set by the service manager for this
+    /// process, None if watchdog is disabled. Watchdog shall be pinged at
+    /// least once within the interval, typically at half of it.
+    pub fn to_watchdog_interval(&self) -> Option<Duration> {
+        self.watchdog
+    }
+}
+
+/// Send `state` to the service manager, refer [Notifier::notify].
+pub fn notify(state: &str) -> Result<bool> {
+    Notifier::from_env()?.notify(state)
+}
+
+/// Notify that daemon is ready to serve.
+pub fn notify_ready() -> Result<bool> {
+    notify("READY=1")
+}
+
+/// Notify that daemon is shutting down.
+pub fn notify_stopping() -> Result<bool> {
+    notify("STOPPING=1")
+}
+
+/// Ping the service manager's watchdog.
+pub fn notify_watchdog() -> Result<bool> {
+    notify("WATCHDOG=1")
+}
+
+/// Return the watchdog interval from process environment, refer
+/// [Notifier::to_watchdog_interval].
+pub fn watchdog_interval() -> Result<Option<Duration>> {
+    Ok(Notifier::from_env()?.to_watchdog_interval())
+}
+
+#[cfg(test)]
+#[path = "notify_test.rs"]
+mod notify_test;
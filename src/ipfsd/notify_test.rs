@@ -0,0 +1,55 @@
+use std::{collections::HashMap, fs};
+
+use super::*;
+
+fn notifier(vars: &[(&str, String)]) -> Result<Notifier> {
+    let vars: HashMap<String, OsString> = vars
+        .iter()
+        .map(|(key, val)| (key.to_string(), val.into()))
+        .collect();
+    Notifier::from_vars(|key| vars.get(key).cloned())
+}
+
+#[test]
+fn test_notify() {
+    assert!(!notifier(&[]).unwrap().notify_ready().unwrap());
+    let n = notifier(&[("NOTIFY_SOCKET", "".to_string())]).unwrap();
+    assert!(!n.notify_ready().unwrap());
+
+    let path = env::temp_dir().join(format!("iprs-notify-test-{}", process::id()));
+    fs::remove_file(&path).ok();
+    let sock = UnixDatagram::bind(&path).unwrap();
+    let path_s = path.to_str().unwrap().to_string();
+
+    let n = notifier(&[("NOTIFY_SOCKET", path_s)]).unwrap();
+    assert!(n.notify_ready().unwrap());
+    assert!(n.notify_watchdog().unwrap());
+    let mut buf = [0_u8; 64];
+    let n = sock.recv(&mut buf).unwrap();
+    assert_eq!(&buf[..n], b"READY=1");
+    let n = sock.recv(&mut buf).unwrap();
+    assert_eq!(&buf[..n], b"WATCHDOG=1");
+    fs::remove_file(&path).unwrap();
+
+    let n = notifier(&[("NOTIFY_SOCKET", "@ipfsd".to_string())]).unwrap();
+    assert!(n.notify_stopping().is_err());
+}
+
+#[test]
+fn test_watchdog_interval() {
+    let usec = ("WATCHDOG_USEC", "2000000".to_string());
+    let pid = process::id();
+
+    assert_eq!(notifier(&[]).unwrap().to_watchdog_interval(), None);
+    let n = notifier(&[usec.clone()]).unwrap();
+    assert_eq!(n.to_watchdog_interval(), Some(Duration::from_secs(2)));
+    let n = notifier(&[usec.clone(), ("WATCHDOG_PID", pid.to_string())]).unwrap();
+    assert_eq!(n.to_watchdog_interval(), Some(Duration::from_secs(2)));
+    let n = notifier(&[usec, ("WATCHDOG_PID", (pid + 1).to_string())]).unwrap();
+    assert_eq!(n.to_watchdog_interval(), None);
+    let n = notifier(&[("WATCHDOG_USEC", "0".to_string())]).unwrap();
+    assert_eq!(n.to_watchdog_interval(), None);
+
+    assert!(notifier(&[("WATCHDOG_USEC", "bad".to_string())]).is_err());
+    assert!(notifier(&[("WATCHDOG_PID", "bad".to_string())]).is_err());
+}
@@ -0,0 +1,184 @@
+//! Module implement pinset persisted as a dag-cbor DAG.
+//!
+//! Pins are sorted on their binary CID and split into buckets of at most
+//! [BUCKET_SIZE] entries. Each bucket is a block,
+//! `{"pins": [[&Any, "direct" | "recursive"], ...]}`, referred from the
+//! root block, `{"version": 1, "buckets": [&Bucket, ...]}`. Since the
+//! pinset is just another DAG in the blockstore, it is replicated along
+//! with the blocks. [Pins::export][crate::ipfsd::Pins::export] backs up
+//! the pinset, along with the pinned blocks, as a CAR archive rooted at
+//! the pinset's root.
+
+use std::{collections::BTreeMap, fmt, result, str::FromStr};
+
+use crate::{
+    cid::Cid,
+    ipld::{
+        block::{get_node, Block},
+        kind::{Basic, Node},
+        store::Store,
+        typed::{self, IpldNode},
+    },
+    multicodec::Multicodec,
+    Error, Result,
+};
+
+/// Version of the pinset DAG layout, refer module documentation.
+pub const VERSION: u64 = 1;
+
+/// Maximum number of pins in a bucket.
+pub const BUCKET_SIZE: usize = 8192;
+
+/// Pinning mode.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PinMode {
+    /// Pin only the block.
+    Direct,
+    /// Pin the block and every block under it.
+    Recursive,
+}
+
+impl fmt::Display for PinMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
+        match self {
+            PinMode::Direct => write!(f, "direct"),
+            PinMode::Recursive => write!(f, "recursive"),
+        }
+    }
+}
+
+impl FromStr for PinMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<PinMode> {
+        match s {
+            "direct" => Ok(PinMode::Direct),
+            "recursive" => Ok(PinMode::Recursive),
+            _ => err_at!(BadInput, msg: "unknown pin mode {:?}", s),
+        }
+    }
+}
+
+/// Set of pinned CIDs, refer module documentation.
+#[derive(Clone, Default)]
+pub struct Pinset {
+    // keyed on binary CID, for stable ordering.
+    pins: BTreeMap<Vec<u8>, (Cid, PinMode)>,
+}
+
+impl Pinset {
+    pub fn new() -> Pinset {
+        Pinset::default()
+    }
+
+    /// Load pinset from DAG under `root`.
+    pub fn from_root(root: &Cid, store: &dyn Store) -> Result<Pinset> {
+        let root = get_node(root, store)?;
+        match typed::get_field::<u64>(&root, "version")? {
+            VERSION => (),
+            version => err_at!(DecodeError, msg: "unsupported pinset version {}", version)?,
+        }
+
+        let mut val = Pinset::new();
+        for bucket in typed::get_field::<Vec<Cid>>(&root, "buckets")?.iter() {
+            let bucket = get_node(bucket, store)?;
+            for entry in typed::get_field::<Vec<Entry>>(&bucket, "pins")?.into_iter() {
+                val.pin(entry.cid, entry.mode)?;
+            }
+        }
+
+        Ok(val)
+    }
+
+    /// Pin `cid` with `mode`, replacing the mode if already pinned.
+    pub fn pin(&mut self, cid: Cid, mode: PinMode) -> Result<()> {
+        self.pins.insert(cid.encode()?, (cid, mode));
+        Ok(())
+    }
+
+    /// Unpin `cid`, return false if it was not pinned.
+    pub fn unpin(&mut self, cid: &Cid) -> Result<bool> {
+        Ok(self.pins.remove(&cid.encode()?).is_some())
+    }
+
+    /// Return the pinning mode for `cid`, None if not pinned.
+    pub fn to_mode(&self, cid: &Cid) -> Result<Option<PinMode>> {
+        Ok(self.pins.get(&cid.encode()?).map(|(_, mode)| *mode))
+    }
+
+    /// Return all pins, ordered on binary CID.
+    pub fn to_pins(&self) -> Vec<(Cid, PinMode)> {
+        self.pins.values().cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pins.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pins.is_empty()
+    }
+
+    /// Encode pinset into dag-cbor blocks, digest is computed using `hash`
+    /// algorithm. Return the root along with the blocks, root being the
+    /// last. Same pinset always encodes to the same root.
+    pub fn to_blocks(&self, hash: Multicodec) -> Result<(Cid, Vec<Block>)> {
+        self.do_to_blocks(hash, BUCKET_SIZE)
+    }
+
+    fn do_to_blocks(&self, hash: Multicodec, bucket_size: usize) -> Result<(Cid, Vec<Block>)> {
+        let pins: Vec<&(Cid, PinMode)> = self.pins.values().collect();
+
+        let mut blocks = vec![];
+        let mut buckets = vec![];
+        for chunk in pins.chunks(bucket_size) {
+            let mut entries = vec![];
+            for (cid, mode) in chunk.iter() {
+                let entry = Entry {
+                    cid: cid.clone(),
+                    mode: *mode,
+                };
+                entries.push(entry.to_basic()?);
+            }
+            let bucket = typed::new_map(vec![("pins".to_string(), typed::new_list(entries))]);
+            let block = Block::from_dag_cbor(hash, &bucket)?;
+            buckets.push(Basic::Link(block.to_cid()?));
+            blocks.push(block);
+        }
+
+        let root = typed::new_map(vec![
+            ("version".to_string(), VERSION.to_basic()?),
+            ("buckets".to_string(), typed::new_list(buckets)),
+        ]);
+        let block = Block::from_dag_cbor(hash, &root)?;
+        let cid = block.to_cid()?;
+        blocks.push(block);
+
+        Ok((cid, blocks))
+    }
+}
+
+// pin entry in a bucket, [&Any, mode]
+struct Entry {
+    cid: Cid,
+    mode: PinMode,
+}
+
+impl IpldNode for Entry {
+    fn to_basic(&self) -> Result<Basic> {
+        let items = vec![self.cid.to_basic()?, self.mode.to_string().to_basic()?];
+        Ok(typed::new_list(items))
+    }
+
+    fn from_node(node: &dyn Node) -> Result<Self> {
+        let val = Entry {
+            cid: typed::get_item(node, 0, 2)?,
+            mode: typed::get_item::<String>(node, 1, 2)?.parse()?,
+        };
+        Ok(val)
+    }
+}
+
+#[cfg(test)]
+#[path = "pinset_test.rs"]
+mod pinset_test;
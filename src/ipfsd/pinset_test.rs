@@ -0,0 +1,72 @@
+use multibase::Base;
+
+use crate::{ipld::store::MemStore, multicodec};
+
+use super::*;
+
+fn make_cids(n: usize) -> Vec<Cid> {
+    (0..n)
+        .map(|i| {
+            let data = i.to_string();
+            Cid::new_v1(Base::Base32Lower, multicodec::RAW.into(), data.as_bytes()).unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn test_pinset() {
+    let cids = make_cids(3);
+
+    let mut pinset = Pinset::new();
+    pinset.pin(cids[0].clone(), PinMode::Recursive).unwrap();
+    pinset.pin(cids[1].clone(), PinMode::Direct).unwrap();
+    pinset.pin(cids[2].clone(), PinMode::Direct).unwrap();
+    pinset.pin(cids[2].clone(), PinMode::Recursive).unwrap();
+    assert_eq!(pinset.len(), 3);
+    assert_eq!(pinset.to_mode(&cids[1]).unwrap(), Some(PinMode::Direct));
+    assert_eq!(pinset.to_mode(&cids[2]).unwrap(), Some(PinMode::Recursive));
+
+    assert!(pinset.unpin(&cids[1]).unwrap());
+    assert!(!pinset.unpin(&cids[1]).unwrap());
+    assert_eq!(pinset.to_mode(&cids[1]).unwrap(), None);
+    assert_eq!(pinset.len(), 2);
+
+    assert_eq!("recursive".parse::<PinMode>().unwrap(), PinMode::Recursive);
+    assert!("indirect".parse::<PinMode>().is_err());
+}
+
+#[test]
+fn test_pinset_dag() {
+    let cids = make_cids(10);
+    let mut pinset = Pinset::new();
+    for (i, cid) in cids.iter().enumerate() {
+        let mode = match i % 2 {
+            0 => PinMode::Direct,
+            _ => PinMode::Recursive,
+        };
+        pinset.pin(cid.clone(), mode).unwrap();
+    }
+
+    let hash: Multicodec = multicodec::SHA2_256.into();
+    let (root, blocks) = pinset.do_to_blocks(hash, 4).unwrap();
+    // 3 buckets and the root.
+    assert_eq!(blocks.len(), 4);
+    assert_eq!(blocks.last().unwrap().to_cid().unwrap(), root);
+
+    let mut store = MemStore::new();
+    for block in blocks.into_iter() {
+        store.put(block).unwrap();
+    }
+    let loaded = Pinset::from_root(&root, &store).unwrap();
+    assert_eq!(loaded.to_pins(), pinset.to_pins());
+
+    // encoding is deterministic.
+    let (root2, _) = loaded.do_to_blocks(hash, 4).unwrap();
+    assert_eq!(root2, root);
+
+    let (root, blocks) = Pinset::new().to_blocks(hash).unwrap();
+    assert_eq!(blocks.len(), 1);
+    let mut store = MemStore::new();
+    store.put(blocks[0].clone()).unwrap();
+    assert!(Pinset::from_root(&root, &store).unwrap().is_empty());
+}
@@ -0,0 +1,202 @@
+//! Module implement plugin registry for ipfs-daemon.
+//!
+//! External crates can contribute IPLD codecs, datastore backends and
+//! tracers to the daemon, without forking it, by registering them with
+//! [Plugins] before the daemon is started. Registration happens at
+//! compile time, as trait objects, there is no dynamic loading.
+
+use std::{collections::BTreeMap, fmt, path, sync::Arc};
+
+use crate::{
+    ipld::{
+        block::Block,
+        cbor::Cbor,
+        json,
+        kind::{Basic, Node},
+        messagepack,
+        store::Store,
+    },
+    multicodec::{self, Multicodec},
+    Error, Result,
+};
+
+/// IPLD codec contributed by a plugin.
+pub trait Codec: Send + Sync {
+    /// Content-type handled by this codec.
+    fn to_codec(&self) -> Multicodec;
+
+    /// Encode data-model into block data.
+    fn encode(&self, node: &dyn Node) -> Result<Vec<u8>>;
+
+    /// Decode block data into data-model.
+    fn decode(&self, data: &[u8]) -> Result<Basic>;
+}
+
+/// Datastore backend contributed by a plugin.
+pub trait Datastore: Send + Sync {
+    /// Name of the backend, as referred by datastore spec in config.
+    fn to_name(&self) -> String;
+
+    /// Open the backend under repo `root`. Store is shared by the
+    /// node's handles, hence must be Send.
+    fn open(&self, root: &path::Path) -> Result<Box<dyn Store + Send>>;
+}
+
+/// Tracer contributed by a plugin, invoked by subsystems on notable
+/// events. Tracers must not block.
+pub trait Tracer: Send + Sync {
+    fn on_event(&self, subsystem: &str, event: &str);
+}
+
+/// Registry of plugins. Refer module documentation for details.
+#[derive(Clone)]
+pub struct Plugins {
+    codecs: BTreeMap<u128, Arc<dyn Codec>>,
+    datastores: BTreeMap<String, Arc<dyn Datastore>>,
+    tracers: Vec<Arc<dyn Tracer>>,
+}
+
+impl fmt::Debug for Plugins {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let codecs: Vec<String> = self
+            .codecs
+            .values()
+            .map(|c| c.to_codec().to_string())
+            .collect();
+        let datastores: Vec<&String> = self.datastores.keys().collect();
+        write!(
+            f,
+            "Plugins<codecs:{:?},datastores:{:?},tracers:{}>",
+            codecs,
+            datastores,
+            self.tracers.len()
+        )
+    }
+}
+
+impl Default for Plugins {
+    fn default() -> Plugins {
+        let mut plugins = Plugins {
+            codecs: BTreeMap::new(),
+            datastores: BTreeMap::new(),
+            tracers: vec![],
+        };
+        for codec in builtin_codecs().into_iter() {
+            let code = codec.to_codec().to_code();
+            plugins.codecs.insert(code, codec);
+        }
+        plugins
+    }
+}
+
+impl Plugins {
+    /// Create a registry with built-in codecs, raw, dag-cbor, json and
+    /// messagepack, registered.
+    pub fn new() -> Plugins {
+        Plugins::default()
+    }
+
+    /// Register an IPLD codec, fail if its content-type is already
+    /// registered.
+    pub fn add_codec(&mut self, codec: Arc<dyn Codec>) -> Result<&mut Self> {
+        let code = codec.to_codec().to_code();
+        if self.codecs.contains_key(&code) {
+            err_at!(Invalid, msg: "codec {} already registered", codec.to_codec())?
+        }
+        self.codecs.insert(code, codec);
+        Ok(self)
+    }
+
+    /// Register a datastore backend, fail if a backend with the same name
+    /// is already registered.
+    pub fn add_datastore(&mut self, ds: Arc<dyn Datastore>) -> Result<&mut Self> {
+        let name = ds.to_name();
+        if self.datastores.contains_key(&name) {
+            err_at!(Invalid, msg: "datastore {:?} already registered", name)?
+        }
+        self.datastores.insert(name, ds);
+        Ok(self)
+    }
+
+    /// Register a tracer.
+    pub fn add_tracer(&mut self, tracer: Arc<dyn Tracer>) -> &mut Self {
+        self.tracers.push(tracer);
+        self
+    }
+
+    /// Return the codec registered for `code`.
+    pub fn to_codec(&self, code: u128) -> Option<Arc<dyn Codec>> {
+        self.codecs.get(&code).cloned()
+    }
+
+    /// Return the datastore backend registered as `name`.
+    pub fn to_datastore(&self, name: &str) -> Option<Arc<dyn Datastore>> {
+        self.datastores.get(name).cloned()
+    }
+
+    /// Decode block into data-model, using the codec registered for the
+    /// block's content-type.
+    pub fn decode_block(&self, block: &Block) -> Result<Basic> {
+        let codec = block.to_cid()?.to_content_type();
+        match self.codecs.get(&codec.to_code()) {
+            Some(c) => c.decode(block.as_block_data()?),
+            None => err_at!(NotImplemented, msg: "no codec registered for {}", codec),
+        }
+    }
+
+    /// Dispatch event to all registered tracers.
+    pub fn trace(&self, subsystem: &str, event: &str) {
+        self.tracers
+            .iter()
+            .for_each(|t| t.on_event(subsystem, event))
+    }
+}
+
+fn builtin_codecs() -> Vec<Arc<dyn Codec>> {
+    vec![
+        Arc::new(Builtin(multicodec::RAW)),
+        Arc::new(Builtin(multicodec::DAG_CBOR)),
+        Arc::new(Builtin(multicodec::JSON)),
+        Arc::new(Builtin(multicodec::MESSAGEPACK)),
+    ]
+}
+
+// Codecs implemented by this package.
+struct Builtin(u128);
+
+impl Codec for Builtin {
+    fn to_codec(&self) -> Multicodec {
+        self.0.into()
+    }
+
+    fn encode(&self, node: &dyn Node) -> Result<Vec<u8>> {
+        use std::convert::TryFrom;
+
+        match self.0 {
+            multicodec::RAW => match node.as_bytes() {
+                Some(byts) => Ok(byts.to_vec()),
+                None => err_at!(EncodeError, msg: "raw codec expects bytes kind"),
+            },
+            multicodec::DAG_CBOR => Cbor::try_from(node)?.canonical_bytes(),
+            multicodec::JSON => json::encode(node),
+            multicodec::MESSAGEPACK => messagepack::encode(node),
+            code => err_at!(Fatal, msg: "unexpected builtin codec {:x}", code),
+        }
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Basic> {
+        use std::convert::TryFrom;
+
+        match self.0 {
+            multicodec::RAW => Ok(Basic::Bytes(data.to_vec())),
+            multicodec::DAG_CBOR => Basic::try_from(Cbor::decode(&mut &data[..])?),
+            multicodec::JSON => json::decode(data),
+            multicodec::MESSAGEPACK => messagepack::decode(data),
+            code => err_at!(Fatal, msg: "unexpected builtin codec {:x}", code),
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "plugin_test.rs"]
+mod plugin_test;
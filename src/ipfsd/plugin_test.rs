@@ -0,0 +1,84 @@
+use multibase::Base::Base32Lower;
+
+use std::sync::Mutex;
+
+use super::*;
+use crate::{cid::Cid, ipld::store::MemStore, multihash::Multihash};
+
+// private-use code-point.
+const TEXT_CODEC: u128 = 0x300001;
+
+struct TextCodec;
+
+impl Codec for TextCodec {
+    fn to_codec(&self) -> Multicodec {
+        TEXT_CODEC.into()
+    }
+
+    fn encode(&self, node: &dyn Node) -> Result<Vec<u8>> {
+        match node.as_string() {
+            Some(text) => Ok(text?.as_bytes().to_vec()),
+            None => err_at!(EncodeError, msg: "expected text"),
+        }
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Basic> {
+        Ok(Basic::Text(data.to_vec()))
+    }
+}
+
+struct MemDatastore;
+
+impl Datastore for MemDatastore {
+    fn to_name(&self) -> String {
+        "mem".to_string()
+    }
+
+    fn open(&self, _root: &path::Path) -> Result<Box<dyn Store + Send>> {
+        Ok(Box::new(MemStore::new()))
+    }
+}
+
+#[derive(Default)]
+struct Events(Mutex<Vec<String>>);
+
+impl Tracer for Events {
+    fn on_event(&self, subsystem: &str, event: &str) {
+        self.0
+            .lock()
+            .unwrap()
+            .push(format!("{}:{}", subsystem, event));
+    }
+}
+
+#[test]
+fn test_plugins() {
+    let mut plugins = Plugins::new();
+    assert!(plugins.to_codec(multicodec::DAG_CBOR).is_some());
+    assert!(plugins.to_codec(TEXT_CODEC).is_none());
+
+    plugins.add_codec(Arc::new(TextCodec)).unwrap();
+    assert!(plugins.add_codec(Arc::new(TextCodec)).is_err());
+
+    let block = {
+        let data = b"hello".to_vec();
+        let mh = Multihash::new(multicodec::SHA2_256.into(), &data).unwrap();
+        let cid = Cid::from_raw(Base32Lower, TEXT_CODEC.into(), mh);
+        Block::new(cid, data.into())
+    };
+    let node = plugins.decode_block(&block).unwrap();
+    assert_eq!(node.as_string().unwrap().unwrap(), "hello");
+    let codec = plugins.to_codec(TEXT_CODEC).unwrap();
+    assert_eq!(codec.encode(&node).unwrap(), b"hello".to_vec());
+
+    plugins.add_datastore(Arc::new(MemDatastore)).unwrap();
+    assert!(plugins.add_datastore(Arc::new(MemDatastore)).is_err());
+    let ds = plugins.to_datastore("mem").unwrap();
+    assert!(ds.open(path::Path::new("/tmp")).is_ok());
+    assert!(plugins.to_datastore("badger").is_none());
+
+    let events = Arc::new(Events::default());
+    plugins.add_tracer(events.clone());
+    plugins.trace("repo", "open");
+    assert_eq!(*events.0.lock().unwrap(), vec!["repo:open".to_string()]);
+}
@@ -0,0 +1,139 @@
+//! Module implement security policy for content accepted by the daemon.
+//!
+//! A [SecurityPolicy] restricts the hash functions and IPLD codecs that
+//! are acceptable when decoding CIDs and blocks. Each list can either
+//! deny specific code-points, or allow only the listed code-points.
+//! Deployments pick the lists from config, [SecurityPolicy::strict]
+//! gives a policy that forbids broken hash functions.
+
+use std::{collections::BTreeSet, convert::TryFrom};
+
+use crate::{
+    cid::Cid,
+    multicodec::{self, Multicodec},
+    Error, Result,
+};
+
+/// Hash functions that are no longer collision resistant.
+pub const WEAK_HASHES: [u128; 3] = [multicodec::MD4, multicodec::MD5, multicodec::SHA1];
+
+/// Restrict hash functions and IPLD codecs, default policy accepts all.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SecurityPolicy {
+    hashes: Rule,
+    codecs: Rule,
+}
+
+// When `allow` is Some, only the listed code-points are accepted, and
+// `deny` is checked after `allow`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+struct Rule {
+    allow: Option<BTreeSet<u128>>,
+    deny: BTreeSet<u128>,
+}
+
+impl Rule {
+    fn is_allowed(&self, code: u128) -> bool {
+        let allowed = match &self.allow {
+            Some(allow) => allow.contains(&code),
+            None => true,
+        };
+        allowed && !self.deny.contains(&code)
+    }
+}
+
+impl SecurityPolicy {
+    /// Policy that denies [WEAK_HASHES], and accepts all codecs.
+    pub fn strict() -> SecurityPolicy {
+        let mut val = SecurityPolicy::default();
+        for code in WEAK_HASHES.iter() {
+            val.deny_hash(*code);
+        }
+        val
+    }
+
+    /// Deny hash function `code`.
+    pub fn deny_hash(&mut self, code: u128) -> &mut Self {
+        self.hashes.deny.insert(code);
+        self
+    }
+
+    /// Accept only hash functions in `codes`, hash functions denied with
+    /// [SecurityPolicy::deny_hash] remain denied.
+    pub fn allow_hashes(&mut self, codes: &[u128]) -> &mut Self {
+        self.hashes.allow = Some(codes.iter().cloned().collect());
+        self
+    }
+
+    /// Deny IPLD codec `code`.
+    pub fn deny_codec(&mut self, code: u128) -> &mut Self {
+        self.codecs.deny.insert(code);
+        self
+    }
+
+    /// Accept only IPLD codecs in `codes`, codecs denied with
+    /// [SecurityPolicy::deny_codec] remain denied.
+    pub fn allow_codecs(&mut self, codes: &[u128]) -> &mut Self {
+        self.codecs.allow = Some(codes.iter().cloned().collect());
+        self
+    }
+
+    /// Return whether hash function `code` is acceptable.
+    pub fn is_hash_allowed(&self, code: u128) -> bool {
+        self.hashes.is_allowed(code)
+    }
+
+    /// Return whether IPLD codec `code` is acceptable.
+    pub fn is_codec_allowed(&self, code: u128) -> bool {
+        self.codecs.is_allowed(code)
+    }
+
+    /// Fail with BadCodec, naming the offending code, if hash function
+    /// `code` is not acceptable.
+    pub fn check_hash(&self, code: u128) -> Result<()> {
+        match self.is_hash_allowed(code) {
+            true => Ok(()),
+            false => err_at!(BadCodec, msg: "hash {} denied by policy", to_label(code)),
+        }
+    }
+
+    /// Fail with BadCodec, naming the offending code, if IPLD codec
+    /// `code` is not acceptable.
+    pub fn check_codec(&self, code: u128) -> Result<()> {
+        match self.is_codec_allowed(code) {
+            true => Ok(()),
+            false => err_at!(BadCodec, msg: "codec {} denied by policy", to_label(code)),
+        }
+    }
+
+    /// Check both the content-type and the hash function of `cid`.
+    pub fn check_cid(&self, cid: &Cid) -> Result<()> {
+        self.check_codec(cid.to_content_type().to_code())?;
+        self.check_hash(cid.to_multihash().to_codec()?.to_code())
+    }
+}
+
+/// Parse a list of multicodec names, typically a policy list from config.
+/// `field` names the list, error message shall include the field, the
+/// index of offending entry and the reason.
+pub fn codes_from_names(field: &str, names: &[String]) -> Result<Vec<u128>> {
+    let mut codes = vec![];
+    for (i, name) in names.iter().enumerate() {
+        match Multicodec::try_from(name.as_str()) {
+            Ok(codec) => codes.push(codec.to_code()),
+            Err(err) => err_at!(BadInput, msg: "{}[{}] {:?}: {}", field, i, name, err)?,
+        }
+    }
+    Ok(codes)
+}
+
+fn to_label(code: u128) -> String {
+    match Multicodec::from(code).to_codepoint() {
+        Some(cp) => format!("{} (0x{:x})", cp.name, code),
+        None => format!("0x{:x}", code),
+    }
+}
+
+#[cfg(test)]
+#[path = "policy_test.rs"]
+mod policy_test;
@@ -0,0 +1,52 @@
+use multibase::Base;
+
+use super::*;
+
+#[test]
+fn test_security_policy() {
+    let policy = SecurityPolicy::default();
+    for code in WEAK_HASHES.iter() {
+        assert!(policy.is_hash_allowed(*code));
+    }
+
+    let policy = SecurityPolicy::strict();
+    assert!(policy.is_hash_allowed(multicodec::SHA2_256));
+    assert!(!policy.is_hash_allowed(multicodec::SHA1));
+    assert!(policy.is_codec_allowed(multicodec::DAG_CBOR));
+
+    let msg = policy.check_hash(multicodec::MD5).unwrap_err().to_string();
+    assert!(msg.contains("BadCodec"), "{}", msg);
+    assert!(msg.contains("md5 (0xd5)"), "{}", msg);
+
+    let mut policy = SecurityPolicy::strict();
+    policy
+        .allow_hashes(&[multicodec::SHA2_256, multicodec::SHA1])
+        .allow_codecs(&[multicodec::RAW, multicodec::DAG_CBOR])
+        .deny_codec(multicodec::DAG_CBOR);
+    assert!(policy.is_hash_allowed(multicodec::SHA2_256));
+    assert!(!policy.is_hash_allowed(multicodec::SHA1));
+    assert!(!policy.is_hash_allowed(multicodec::BLAKE3));
+    assert!(policy.is_codec_allowed(multicodec::RAW));
+    assert!(!policy.is_codec_allowed(multicodec::DAG_CBOR));
+    assert!(!policy.is_codec_allowed(multicodec::DAG_PB));
+
+    let cid = Cid::new_v1(Base::Base32Lower, multicodec::RAW.into(), b"hello").unwrap();
+    policy.check_cid(&cid).unwrap();
+    let cid = Cid::new_v0(b"hello").unwrap();
+    let msg = policy.check_cid(&cid).unwrap_err().to_string();
+    assert!(msg.contains("dag-pb (0x70)"), "{}", msg);
+}
+
+#[test]
+fn test_codes_from_names() {
+    let names = vec!["md5".to_string(), "sha1".to_string()];
+    let codes = codes_from_names("Policy.DenyHashes", &names).unwrap();
+    assert_eq!(codes, vec![multicodec::MD5, multicodec::SHA1]);
+
+    let names = vec!["md5".to_string(), "md6".to_string()];
+    let msg = codes_from_names("Policy.DenyHashes", &names)
+        .unwrap_err()
+        .to_string();
+    assert!(msg.contains("Policy.DenyHashes[1]"), "{}", msg);
+    assert!(msg.contains("md6"), "{}", msg);
+}
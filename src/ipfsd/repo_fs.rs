@@ -1,84 +1,123 @@
-//import (
-//	filestore "github.com/ipfs/go-filestore"
-//	keystore "github.com/ipfs/go-ipfs/keystore"
-//	ds "github.com/ipfs/go-datastore"
-//	config "github.com/ipfs/go-ipfs-config"
-//	ma "github.com/multiformats/go-multiaddr"
-//)
-
-/// Environment variable point to the ipfs-repo path.
-pub fn default_root() -> path::Path {
-    use std::env;
-
-    let ipfs_path = env::var("IPFS_PATH").unwrap_or("./ipfs".to_string());
-
-    let mut root = dirs::home_dir();
-    root.push(ipfs_path);
-    root.to_path()
+//! Module implement file based ipfs-repo, rooted at [repo_root].
+//!
+//! An initialized repo holds the [Config] file, named [CONFIG_FILE],
+//! carrying the local node's identity. Use [FileRepo::init] to create
+//! one and [FileRepo::open] to load it.
+//!
+//! [repo_root]: crate::ipfsd::repo_root
+
+use std::{fs, path};
+
+use crate::{
+    identity::Keypair,
+    ipfsd::config::{Config, Identity},
+    pnet::Psk,
+    Error, Result,
+};
+
+// TODO: rest of go-ipfs Repo interface, BackupConfig, SetConfigKey,
+// GetConfigKey, GetStorageUsage, Keystore, FileManager.
+
+/// Name of the configuration file under ipfs-repo.
+pub const CONFIG_FILE: &str = "config";
+
+/// Name of the datastore directory under ipfs-repo.
+pub const DATASTORE_DIR: &str = "datastore";
+
+/// Return location of configuration file under ipfs-repo `root`.
+pub fn loc_config(root: &path::Path) -> path::PathBuf {
+    root.join(CONFIG_FILE)
 }
 
-pub fn loc_config(root: path::Path, file: Option<ffi::OsString>) -> path::Path {
-    let file = file.unwrap_or("config".to_os_string());
-    let loc: path::PathBuf = vec![root, file].iter().collect();
-    loc.to_path()
+/// Return location of datastore directory under ipfs-repo `root`.
+pub fn loc_datastore(root: &path::Path) -> path::PathBuf {
+    root.join(DATASTORE_DIR)
 }
 
-pub fn loc_datastore(root: path::Path, sub_dir: Option<ffi::OsString>) -> path::Path {
-    let sub_dir = sub_dir.unwrap_or("datastore".to_os_string());
-    let loc: path::PathBuf = vec![root, file].iter().collect();
-    loc.to_path()
-}
-
-// Repo represents all persistent data of a given ipfs node.
-struct FileRepo {
+/// FileRepo represents all persistent data of a given ipfs node.
+pub struct FileRepo {
+    root: path::PathBuf,
     config: Config,
 }
 
 impl FileRepo {
-    /// Returns the ipfs configuration file from the repo. Changes made
-    /// to the returned config are not automatically persisted.
-    fn to_config() -> Result<Config> {
-        todo!()
+    /// Initialize a new ipfs-repo under `root`, generating a fresh ed25519
+    /// identity for the local node. Fail if `root` is already initialized.
+    pub fn init(root: &path::Path) -> Result<FileRepo> {
+        if FileRepo::is_initialized(root) {
+            err_at!(Invalid, msg: "ipfs-repo {:?} already initialized", root)?
+        }
+        err_at!(IOError, fs::create_dir_all(root), "{:?}", root)?;
+
+        let config = Config {
+            identity: Identity::from_keypair(Keypair::generate_ed25519()?)?,
+            ..Config::default()
+        };
+        let mut repo = FileRepo {
+            root: root.to_path_buf(),
+            config: Config::default(),
+        };
+        repo.set_config(config)?;
+
+        let loc = loc_datastore(root);
+        err_at!(IOError, fs::create_dir_all(&loc), "{:?}", loc)?;
+
+        Ok(repo)
     }
 
-    /// BackupConfig creates a backup of the current configuration file using
-    /// the given prefix for naming.
-    BackupConfig(prefix string) (string, error)
-
-    // SetConfig persists the given configuration struct to storage.
-    SetConfig(*config.Config) error
-
-    // SetConfigKey sets the given key-value pair within the config and persists it to storage.
-    SetConfigKey(key string, value interface{}) error
-
-    // GetConfigKey reads the value for the given key from the configuration in storage.
-    GetConfigKey(key string) (interface{}, error)
-
-    // Datastore returns a reference to the configured data storage backend.
-    Datastore() Datastore
-
-    // GetStorageUsage returns the number of bytes stored.
-    GetStorageUsage() (uint64, error)
-
-    // Keystore returns a reference to the key management interface.
-    Keystore() keystore.Keystore
+    /// Open an initialized ipfs-repo under `root`.
+    pub fn open(root: &path::Path) -> Result<FileRepo> {
+        let loc = loc_config(root);
+        if !loc.exists() {
+            err_at!(Invalid, msg: "ipfs-repo {:?} not initialized", root)?
+        }
+        let data = err_at!(IOError, fs::read(&loc), "{:?}", loc)?;
+        let config = Config::from_json(&data)?;
+
+        let val = FileRepo {
+            root: root.to_path_buf(),
+            config,
+        };
+        Ok(val)
+    }
 
-    // FileManager returns a reference to the filestore file manager.
-    FileManager() *filestore.FileManager
+    /// Return whether an ipfs-repo is initialized under `root`.
+    pub fn is_initialized(root: &path::Path) -> bool {
+        loc_config(root).exists()
+    }
 
-    // SetAPIAddr sets the API address in the repo.
-    SetAPIAddr(addr ma.Multiaddr) error
+    /// Return the root directory of this ipfs-repo.
+    pub fn as_root(&self) -> &path::Path {
+        &self.root
+    }
 
-    // SwarmKey returns the configured shared symmetric key for the private networks feature.
-    SwarmKey() ([]byte, error)
+    /// Return the ipfs configuration loaded from this ipfs-repo. Changes
+    /// made to the configuration are not persisted, refer
+    /// [FileRepo::set_config].
+    pub fn as_config(&self) -> &Config {
+        &self.config
+    }
 
-    close
-}
+    /// Persist `config` into this ipfs-repo. Configuration is written to
+    /// a temporary file and renamed, so that a crash never leaves a
+    /// partial configuration behind.
+    pub fn set_config(&mut self, config: Config) -> Result<()> {
+        let loc = loc_config(&self.root);
+        let tmp = self.root.join(format!("{}.tmp", CONFIG_FILE));
+        err_at!(IOError, fs::write(&tmp, config.to_json()?), "{:?}", tmp)?;
+        err_at!(IOError, fs::rename(&tmp, &loc), "{:?}", loc)?;
+
+        self.config = config;
+        Ok(())
+    }
 
-// Datastore is the interface required from a datastore to be
-// acceptable to FSRepo.
-type Datastore interface {
-	ds.Batching // must be thread-safe
+    /// Return the configured pre-shared-key for private networks, refer
+    /// [Psk::from_repo].
+    pub fn to_swarm_key(&self) -> Result<Option<Psk>> {
+        Psk::from_repo(&self.root)
+    }
 }
 
-
+#[cfg(test)]
+#[path = "repo_fs_test.rs"]
+mod repo_fs_test;
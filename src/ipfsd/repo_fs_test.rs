@@ -0,0 +1,28 @@
+use std::{env, process};
+
+use super::*;
+
+#[test]
+fn test_file_repo() {
+    let root = env::temp_dir().join(format!("iprs-repo-fs-test-{}", process::id()));
+    fs::remove_dir_all(&root).ok();
+
+    assert!(!FileRepo::is_initialized(&root));
+    assert!(FileRepo::open(&root).is_err());
+
+    let repo = FileRepo::init(&root).unwrap();
+    assert!(FileRepo::is_initialized(&root));
+    assert!(loc_datastore(&root).is_dir());
+    assert_eq!(repo.as_root(), root.as_path());
+    let peer_id = repo.as_config().to_peer_id().unwrap();
+    assert!(repo.to_swarm_key().unwrap().is_none());
+
+    assert!(FileRepo::init(&root).is_err());
+
+    let repo = FileRepo::open(&root).unwrap();
+    assert_eq!(repo.as_config().to_peer_id().unwrap(), peer_id);
+    let kp = repo.as_config().identity.key_pair.as_ref().unwrap();
+    assert_eq!(kp.to_public_key().into_peer_id().unwrap(), peer_id);
+
+    fs::remove_dir_all(&root).unwrap();
+}
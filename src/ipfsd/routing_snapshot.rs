@@ -0,0 +1,350 @@
+//! Module implement routing table snapshot persisted as a dag-cbor DAG.
+//!
+//! Daemon saves the peers in its routing table, along with their
+//! addresses and when they were last seen, on shutdown and every
+//! [SNAPSHOT_INTERVAL]. On startup the snapshot is loaded to warm the
+//! table, so that nodes that run intermittently need not go through a
+//! full bootstrap each time.
+//!
+//! Peers are sorted on their binary peer-id and split into buckets of at
+//! most [BUCKET_SIZE] entries. Each bucket is a block,
+//! `{"peers": [[peer-id-bytes, [multiaddr-bytes, ...], last-seen], ...]}`,
+//! where last-seen is seconds since UNIX epoch, referred from the root
+//! block, `{"version": 1, "buckets": [&Bucket, ...]}`.
+//!
+//! The DAG is saved as a CAR archive, [SNAPSHOT_FILE] under ipfs-repo.
+//! [Snapshotter] is the daemon subsystem that loads it into a
+//! [RoutingTable] at startup and saves it back periodically and on
+//! shutdown.
+
+use crossbeam_channel::{self as cbm, RecvTimeoutError};
+use log::{debug, error, warn};
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time,
+};
+
+use crate::{
+    cid::Cid,
+    ipfsd::Subsystem,
+    ipld::{
+        block::{get_node, Block},
+        car,
+        kind::{Basic, Node},
+        store::{MemStore, Store},
+        typed::{self, Bytes, IpldNode},
+    },
+    multiaddr::Multiaddr,
+    multicodec::{self, Multicodec},
+    peer_id::PeerId,
+    Error, Result,
+};
+
+/// Version of the snapshot DAG layout, refer module documentation.
+pub const VERSION: u64 = 1;
+
+/// Maximum number of peers in a bucket.
+pub const BUCKET_SIZE: usize = 4096;
+
+/// Default interval for saving the snapshot while daemon is running.
+pub const SNAPSHOT_INTERVAL: time::Duration = time::Duration::from_secs(10 * 60);
+
+/// Default age beyond which peers are dropped when loading a snapshot,
+/// addresses of peers not seen for long are unlikely to be reachable.
+pub const MAX_PEER_AGE: time::Duration = time::Duration::from_secs(7 * 24 * 3600);
+
+/// Name of the file, under ipfs-repo, holding the snapshot.
+pub const SNAPSHOT_FILE: &str = "routing.car";
+
+/// Routing table whose peers are persisted across restarts, refer
+/// [Snapshotter].
+pub trait RoutingTable: Send + Sync {
+    /// Return the peers in the table.
+    fn to_peers(&self) -> Vec<RoutingPeer>;
+
+    /// Add peers loaded from a snapshot, most recently seen first.
+    fn add_peers(&self, peers: Vec<RoutingPeer>);
+}
+
+/// Routing table entry.
+#[derive(Clone, Debug)]
+pub struct RoutingPeer {
+    pub peer_id: PeerId,
+    pub addrs: Vec<Multiaddr>,
+    /// Seconds since UNIX epoch.
+    pub last_seen: u64,
+}
+
+/// Snapshot of routing table, refer module documentation.
+#[derive(Clone, Default)]
+pub struct RoutingSnapshot {
+    // keyed on binary peer-id, for stable ordering.
+    peers: BTreeMap<Vec<u8>, RoutingPeer>,
+}
+
+impl RoutingSnapshot {
+    pub fn new() -> RoutingSnapshot {
+        RoutingSnapshot::default()
+    }
+
+    /// Load snapshot from DAG under `root`.
+    pub fn from_root(root: &Cid, store: &dyn Store) -> Result<RoutingSnapshot> {
+        let root = get_node(root, store)?;
+        match typed::get_field::<u64>(&root, "version")? {
+            VERSION => (),
+            version => err_at!(DecodeError, msg: "unsupported snapshot version {}", version)?,
+        }
+
+        let mut val = RoutingSnapshot::new();
+        for bucket in typed::get_field::<Vec<Cid>>(&root, "buckets")?.iter() {
+            let bucket = get_node(bucket, store)?;
+            for peer in typed::get_field::<Vec<RoutingPeer>>(&bucket, "peers")?.into_iter() {
+                val.add_peer(peer)?;
+            }
+        }
+
+        Ok(val)
+    }
+
+    /// Add peer to snapshot. If peer is already present, the entry with
+    /// the latest `last_seen` is retained.
+    pub fn add_peer(&mut self, peer: RoutingPeer) -> Result<()> {
+        let key = peer.peer_id.encode()?;
+        match self.peers.get(&key) {
+            Some(old) if old.last_seen > peer.last_seen => (),
+            _ => {
+                self.peers.insert(key, peer);
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove peers last seen before `now - max_age`, return the number
+    /// of peers removed.
+    pub fn prune(&mut self, now: time::SystemTime, max_age: time::Duration) -> Result<usize> {
+        let now = err_at!(Fatal, now.duration_since(time::UNIX_EPOCH))?;
+        let cutoff = now.as_secs().saturating_sub(max_age.as_secs());
+
+        let n = self.peers.len();
+        self.peers.retain(|_, peer| peer.last_seen >= cutoff);
+        Ok(n - self.peers.len())
+    }
+
+    /// Return all peers, ordered on binary peer-id.
+    pub fn to_peers(&self) -> Vec<RoutingPeer> {
+        self.peers.values().cloned().collect()
+    }
+
+    /// Return peers ordered on `last_seen`, most recent first. Useful to
+    /// dial the most likely reachable peers first when warming the table.
+    pub fn to_recent_peers(&self) -> Vec<RoutingPeer> {
+        let mut peers = self.to_peers();
+        peers.sort_by(|a, b| b.last_seen.cmp(&a.last_seen));
+        peers
+    }
+
+    pub fn len(&self) -> usize {
+        self.peers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.peers.is_empty()
+    }
+
+    /// Encode snapshot into dag-cbor blocks, digest is computed using
+    /// `hash` algorithm. Return the root along with the blocks, root
+    /// being the last.
+    pub fn to_blocks(&self, hash: Multicodec) -> Result<(Cid, Vec<Block>)> {
+        self.do_to_blocks(hash, BUCKET_SIZE)
+    }
+
+    /// Save snapshot as [SNAPSHOT_FILE] under `repo`. Archive is written
+    /// to a temporary file and renamed, so that a crash never leaves a
+    /// partial snapshot behind.
+    pub fn save(&self, repo: &Path, hash: Multicodec) -> Result<()> {
+        let (root, blocks) = self.to_blocks(hash)?;
+
+        let loc = repo.join(SNAPSHOT_FILE);
+        let tmp = repo.join(format!("{}.tmp", SNAPSHOT_FILE));
+        let file = err_at!(IOError, fs::File::create(&tmp), "{:?}", tmp)?;
+        let mut w = car::Writer::new(file, &[root])?;
+        for block in blocks.iter() {
+            w.write_block(block)?;
+        }
+        let file = w.into_inner()?;
+        err_at!(IOError, file.sync_all(), "{:?}", tmp)?;
+        err_at!(IOError, fs::rename(&tmp, &loc), "{:?}", loc)
+    }
+
+    /// Load snapshot from [SNAPSHOT_FILE] under `repo`, return None if
+    /// the file is missing.
+    pub fn load(repo: &Path) -> Result<Option<RoutingSnapshot>> {
+        let loc = repo.join(SNAPSHOT_FILE);
+        if !loc.exists() {
+            return Ok(None);
+        }
+
+        let file = err_at!(IOError, fs::File::open(&loc), "{:?}", loc)?;
+        let mut r = car::Reader::new(file)?;
+        let root = match r.to_roots().as_slice() {
+            [root] => root.clone(),
+            roots => err_at!(DecodeError, msg: "snapshot with {} roots", roots.len())?,
+        };
+        let mut store = MemStore::new();
+        car::import(&mut r, |block| store.put(block))?;
+
+        Ok(Some(RoutingSnapshot::from_root(&root, &store)?))
+    }
+
+    fn do_to_blocks(&self, hash: Multicodec, bucket_size: usize) -> Result<(Cid, Vec<Block>)> {
+        let peers: Vec<&RoutingPeer> = self.peers.values().collect();
+
+        let mut blocks = vec![];
+        let mut buckets = vec![];
+        for chunk in peers.chunks(bucket_size) {
+            let mut entries = vec![];
+            for peer in chunk.iter() {
+                entries.push(peer.to_basic()?);
+            }
+            let bucket = typed::new_map(vec![("peers".to_string(), typed::new_list(entries))]);
+            let block = Block::from_dag_cbor(hash, &bucket)?;
+            buckets.push(Basic::Link(block.to_cid()?));
+            blocks.push(block);
+        }
+
+        let root = typed::new_map(vec![
+            ("version".to_string(), VERSION.to_basic()?),
+            ("buckets".to_string(), typed::new_list(buckets)),
+        ]);
+        let block = Block::from_dag_cbor(hash, &root)?;
+        let cid = block.to_cid()?;
+        blocks.push(block);
+
+        Ok((cid, blocks))
+    }
+}
+
+// peer entry in a bucket, [peer-id, [multiaddr, ...], last-seen]
+impl IpldNode for RoutingPeer {
+    fn to_basic(&self) -> Result<Basic> {
+        let mut addrs = vec![];
+        for addr in self.addrs.iter() {
+            addrs.push(Bytes(addr.encode()?).to_basic()?);
+        }
+        let items = vec![
+            Bytes(self.peer_id.encode()?).to_basic()?,
+            typed::new_list(addrs),
+            self.last_seen.to_basic()?,
+        ];
+        Ok(typed::new_list(items))
+    }
+
+    fn from_node(node: &dyn Node) -> Result<Self> {
+        let peer_id = {
+            let bytes = typed::get_item::<Bytes>(node, 0, 3)?;
+            match PeerId::decode(&bytes.0)? {
+                (peer_id, []) => peer_id,
+                (_, rem) => err_at!(DecodeError, msg: "peer-id trailing {} bytes", rem.len())?,
+            }
+        };
+        let mut addrs = vec![];
+        for bytes in typed::get_item::<Vec<Bytes>>(node, 1, 3)?.into_iter() {
+            match Multiaddr::decode(&bytes.0)? {
+                (addr, []) => addrs.push(addr),
+                (_, rem) => err_at!(DecodeError, msg: "multiaddr trailing {} bytes", rem.len())?,
+            }
+        }
+
+        let val = RoutingPeer {
+            peer_id,
+            addrs,
+            last_seen: typed::get_item(node, 2, 3)?,
+        };
+        Ok(val)
+    }
+}
+
+/// Subsystem persisting a [RoutingTable], refer module documentation.
+pub struct Snapshotter {
+    table: Arc<dyn RoutingTable>,
+    repo: PathBuf,
+    interval: time::Duration,
+    max_age: time::Duration,
+}
+
+impl Snapshotter {
+    /// Persist `table` under `repo`, saved every [SNAPSHOT_INTERVAL] and
+    /// peers older than [MAX_PEER_AGE] dropped on load.
+    pub fn new(table: Arc<dyn RoutingTable>, repo: PathBuf) -> Snapshotter {
+        Snapshotter {
+            table,
+            repo,
+            interval: SNAPSHOT_INTERVAL,
+            max_age: MAX_PEER_AGE,
+        }
+    }
+
+    /// Set the interval for saving, default is [SNAPSHOT_INTERVAL].
+    pub fn set_interval(&mut self, interval: time::Duration) -> &mut Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Set the age beyond which peers are dropped on load, default is
+    /// [MAX_PEER_AGE].
+    pub fn set_max_age(&mut self, max_age: time::Duration) -> &mut Self {
+        self.max_age = max_age;
+        self
+    }
+
+    fn save(&self) -> Result<()> {
+        let mut snapshot = RoutingSnapshot::new();
+        for peer in self.table.to_peers().into_iter() {
+            snapshot.add_peer(peer)?;
+        }
+        snapshot.save(&self.repo, multicodec::SHA2_256.into())?;
+        debug!("saved routing snapshot with {} peers", snapshot.len());
+        Ok(())
+    }
+}
+
+impl Subsystem for Snapshotter {
+    fn to_name(&self) -> String {
+        "routing-snapshot".to_string()
+    }
+
+    // a bad snapshot only costs a full bootstrap, don't fail the daemon.
+    fn start(&mut self) -> Result<()> {
+        match RoutingSnapshot::load(&self.repo) {
+            Ok(Some(mut snapshot)) => {
+                snapshot.prune(time::SystemTime::now(), self.max_age)?;
+                debug!("loaded routing snapshot with {} peers", snapshot.len());
+                self.table.add_peers(snapshot.to_recent_peers());
+            }
+            Ok(None) => (),
+            Err(err) => warn!("ignoring routing snapshot in {:?}: {}", self.repo, err),
+        }
+        Ok(())
+    }
+
+    fn run(&mut self, fin: &cbm::Receiver<()>) -> Result<()> {
+        loop {
+            match fin.recv_timeout(self.interval) {
+                Err(RecvTimeoutError::Timeout) => {
+                    if let Err(err) = self.save() {
+                        error!("save routing snapshot: {}", err);
+                    }
+                }
+                _ => break self.save(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "routing_snapshot_test.rs"]
+mod routing_snapshot_test;
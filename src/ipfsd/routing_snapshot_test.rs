@@ -0,0 +1,161 @@
+use std::{sync::Mutex, thread};
+
+use super::*;
+
+fn make_peers(n: usize) -> Vec<RoutingPeer> {
+    (0..n)
+        .map(|i| RoutingPeer {
+            peer_id: PeerId::generate().unwrap(),
+            addrs: vec![
+                Multiaddr::from_text(&format!("/ip4/10.0.0.{}/tcp/4001", i)).unwrap(),
+                Multiaddr::from_text(&format!("/ip4/10.0.0.{}/udp/4001/quic", i)).unwrap(),
+            ],
+            last_seen: 1_000_000 + (i as u64) * 100,
+        })
+        .collect()
+}
+
+fn to_keys(peers: &[RoutingPeer]) -> Vec<(Vec<u8>, Vec<Multiaddr>, u64)> {
+    peers
+        .iter()
+        .map(|p| (p.peer_id.encode().unwrap(), p.addrs.clone(), p.last_seen))
+        .collect()
+}
+
+#[test]
+fn test_routing_snapshot() {
+    let peers = make_peers(3);
+
+    let mut snapshot = RoutingSnapshot::new();
+    for peer in peers.iter() {
+        snapshot.add_peer(peer.clone()).unwrap();
+    }
+    assert_eq!(snapshot.len(), 3);
+
+    // older entry does not replace a newer one.
+    let mut old = peers[2].clone();
+    old.last_seen = 0;
+    old.addrs = vec![];
+    snapshot.add_peer(old).unwrap();
+    assert_eq!(snapshot.len(), 3);
+
+    let recent = snapshot.to_recent_peers();
+    assert_eq!(recent[0].peer_id, peers[2].peer_id);
+    assert_eq!(recent[0].addrs, peers[2].addrs);
+    assert_eq!(recent[2].peer_id, peers[0].peer_id);
+
+    let now = time::UNIX_EPOCH + time::Duration::from_secs(1_000_150);
+    let n = snapshot.prune(now, time::Duration::from_secs(100)).unwrap();
+    assert_eq!(n, 1);
+    assert_eq!(snapshot.len(), 2);
+    assert!(snapshot
+        .to_peers()
+        .iter()
+        .all(|p| p.peer_id != peers[0].peer_id));
+}
+
+#[test]
+fn test_routing_snapshot_dag() {
+    let peers = make_peers(10);
+    let mut snapshot = RoutingSnapshot::new();
+    for peer in peers.into_iter() {
+        snapshot.add_peer(peer).unwrap();
+    }
+
+    let hash: Multicodec = multicodec::SHA2_256.into();
+    let (root, blocks) = snapshot.do_to_blocks(hash, 4).unwrap();
+    // 3 buckets and the root.
+    assert_eq!(blocks.len(), 4);
+    assert_eq!(blocks.last().unwrap().to_cid().unwrap(), root);
+
+    let mut store = MemStore::new();
+    for block in blocks.into_iter() {
+        store.put(block).unwrap();
+    }
+    let loaded = RoutingSnapshot::from_root(&root, &store).unwrap();
+    assert_eq!(to_keys(&loaded.to_peers()), to_keys(&snapshot.to_peers()));
+
+    // encoding is deterministic.
+    let (root2, _) = loaded.do_to_blocks(hash, 4).unwrap();
+    assert_eq!(root2, root);
+
+    let (root, blocks) = RoutingSnapshot::new().to_blocks(hash).unwrap();
+    assert_eq!(blocks.len(), 1);
+    let mut store = MemStore::new();
+    store.put(blocks[0].clone()).unwrap();
+    assert!(RoutingSnapshot::from_root(&root, &store)
+        .unwrap()
+        .is_empty());
+}
+
+struct MemTable(Mutex<Vec<RoutingPeer>>);
+
+impl RoutingTable for MemTable {
+    fn to_peers(&self) -> Vec<RoutingPeer> {
+        self.0.lock().unwrap().clone()
+    }
+
+    fn add_peers(&self, peers: Vec<RoutingPeer>) {
+        self.0.lock().unwrap().extend(peers)
+    }
+}
+
+fn now_secs() -> u64 {
+    let now = time::SystemTime::now().duration_since(time::UNIX_EPOCH);
+    now.unwrap().as_secs()
+}
+
+#[test]
+fn test_routing_snapshot_file() {
+    let repo = std::env::temp_dir().join(format!("iprs-routing-{}", std::process::id()));
+    fs::create_dir_all(&repo).unwrap();
+    fs::remove_file(repo.join(SNAPSHOT_FILE)).ok();
+    assert!(RoutingSnapshot::load(&repo).unwrap().is_none());
+
+    let mut snapshot = RoutingSnapshot::new();
+    for peer in make_peers(5).into_iter() {
+        snapshot.add_peer(peer).unwrap();
+    }
+    snapshot.save(&repo, multicodec::SHA2_256.into()).unwrap();
+    let loaded = RoutingSnapshot::load(&repo).unwrap().unwrap();
+    assert_eq!(to_keys(&loaded.to_peers()), to_keys(&snapshot.to_peers()));
+    assert!(!repo.join(format!("{}.tmp", SNAPSHOT_FILE)).exists());
+
+    // load at start, stale peers dropped.
+    let mut peers = make_peers(2);
+    peers[0].last_seen = now_secs();
+    let table = Arc::new(MemTable(Mutex::new(peers.clone())));
+    let mut sys = Snapshotter::new(table.clone(), repo.clone());
+    sys.set_interval(time::Duration::from_millis(10));
+    let (fin_tx, fin_rx) = cbm::bounded(1);
+    fin_tx.send(()).unwrap();
+    sys.run(&fin_rx).unwrap();
+
+    let table = Arc::new(MemTable(Mutex::new(vec![])));
+    let mut sys = Snapshotter::new(table.clone(), repo.clone());
+    sys.start().unwrap();
+    assert_eq!(to_keys(&table.to_peers()), to_keys(&peers[..1]));
+
+    // periodic save, while running.
+    table.add_peers(make_peers(3));
+    sys.set_interval(time::Duration::from_millis(10));
+    let handle = thread::spawn(move || sys.run(&fin_rx));
+    let deadline = time::Instant::now() + time::Duration::from_secs(5);
+    while RoutingSnapshot::load(&repo).unwrap().unwrap().len() != 4 {
+        assert!(time::Instant::now() < deadline);
+        thread::sleep(time::Duration::from_millis(5));
+    }
+    fin_tx.send(()).unwrap();
+    handle.join().unwrap().unwrap();
+
+    // bad snapshot is ignored.
+    fs::write(repo.join(SNAPSHOT_FILE), b"garbage").unwrap();
+    assert!(RoutingSnapshot::load(&repo).is_err());
+    let table = Arc::new(MemTable(Mutex::new(vec![])));
+    Snapshotter::new(table.clone(), repo.clone())
+        .start()
+        .unwrap();
+    assert!(table.to_peers().is_empty());
+
+    fs::remove_dir_all(&repo).unwrap();
+}
@@ -0,0 +1,179 @@
+//! Module implement encryption at rest for repo values.
+//!
+//! Values are sealed using AES-256-GCM with a [RepoKey], derived from a
+//! repo passphrase or read from a key file. Each sealed value carries a
+//! random nonce, and is bound to its datastore key, hence a sealed value
+//! can't be swapped under another key without detection.
+//!
+//! Sealed format: `<nonce 12-bytes><ciphertext><tag 16-bytes>`
+
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN},
+    pbkdf2,
+    rand::{SecureRandom, SystemRandom},
+};
+use zeroize::Zeroize;
+
+use std::{fs, num::NonZeroU32, path};
+
+use crate::{
+    cid::Cid,
+    ipld::{block::Block, store::Store},
+    Error, Result,
+};
+
+/// Number of PBKDF2-HMAC-SHA256 iterations to derive key from passphrase.
+pub const PBKDF2_ITERATIONS: u32 = 100_000;
+
+/// Key to seal and open repo values.
+pub struct RepoKey {
+    key: [u8; 32],
+}
+
+impl Drop for RepoKey {
+    fn drop(&mut self) {
+        self.key.zeroize()
+    }
+}
+
+impl RepoKey {
+    /// Derive key from `passphrase`. `salt` shall be unique for the repo
+    /// and persisted along with it.
+    pub fn from_passphrase(passphrase: &[u8], salt: &[u8]) -> RepoKey {
+        let mut key = [0_u8; 32];
+        let iterations = NonZeroU32::new(PBKDF2_ITERATIONS).unwrap();
+        pbkdf2::derive(
+            pbkdf2::PBKDF2_HMAC_SHA256,
+            iterations,
+            salt,
+            passphrase,
+            &mut key,
+        );
+        RepoKey { key }
+    }
+
+    /// Read key from file at `loc`, file shall contain 32-byte key as 64
+    /// hex characters.
+    pub fn from_key_file(loc: &path::Path) -> Result<RepoKey> {
+        use data_encoding::HEXLOWER_PERMISSIVE;
+
+        let mut text = err_at!(IOError, fs::read_to_string(loc))?;
+        let res = HEXLOWER_PERMISSIVE.decode(text.trim().as_bytes());
+        text.zeroize();
+
+        let mut data = err_at!(DecodeError, res, "key file {:?}", loc)?;
+        let res = match data.len() {
+            32 => {
+                let mut key = [0_u8; 32];
+                key.copy_from_slice(&data);
+                Ok(RepoKey { key })
+            }
+            n => err_at!(BadInput, msg: "key file {:?} has {} bytes", loc, n),
+        };
+        data.zeroize();
+        res
+    }
+
+    /// Seal `value` stored under datastore `key`.
+    pub fn seal(&self, key: &[u8], value: &[u8]) -> Result<Vec<u8>> {
+        let mut nonce = [0_u8; NONCE_LEN];
+        err_at!(Fatal, SystemRandom::new().fill(&mut nonce))?;
+
+        let mut in_out = value.to_vec();
+        let nonce_val = Nonce::assume_unique_for_key(nonce);
+        err_at!(
+            EncodeError,
+            self.to_aead_key()?
+                .seal_in_place_append_tag(nonce_val, Aad::from(key), &mut in_out)
+        )?;
+
+        let mut data = Vec::with_capacity(NONCE_LEN + in_out.len());
+        data.extend_from_slice(&nonce);
+        data.extend_from_slice(&in_out);
+        Ok(data)
+    }
+
+    /// Open `data` sealed under datastore `key`, fail if data was
+    /// tampered, or sealed with another repo key or datastore key.
+    pub fn open(&self, key: &[u8], data: &[u8]) -> Result<Vec<u8>> {
+        if data.len() < (NONCE_LEN + AES_256_GCM.tag_len()) {
+            err_at!(DecodeError, msg: "sealed value too short {}", data.len())?
+        }
+
+        let mut nonce = [0_u8; NONCE_LEN];
+        nonce.copy_from_slice(&data[..NONCE_LEN]);
+        let nonce = Nonce::assume_unique_for_key(nonce);
+
+        let mut in_out = data[NONCE_LEN..].to_vec();
+        let n = err_at!(
+            DecodeError,
+            self.to_aead_key()?
+                .open_in_place(nonce, Aad::from(key), &mut in_out)
+        )?
+        .len();
+        in_out.truncate(n);
+
+        Ok(in_out)
+    }
+
+    fn to_aead_key(&self) -> Result<LessSafeKey> {
+        let key = err_at!(Fatal, UnboundKey::new(&AES_256_GCM, &self.key))?;
+        Ok(LessSafeKey::new(key))
+    }
+}
+
+/// Store wrapper, holding sealed blocks in the underlying store and
+/// opening them on read. Blocks are bound to their cid.
+pub struct SealedStore<S> {
+    key: RepoKey,
+    inner: S,
+}
+
+impl<S> SealedStore<S> {
+    pub fn new(key: RepoKey, inner: S) -> SealedStore<S> {
+        SealedStore { key, inner }
+    }
+
+    /// Seal `block` for writing into underlying store.
+    pub fn seal_block(&self, block: &Block) -> Result<Block> {
+        let cid = block.to_cid()?;
+        let data = self.key.seal(&cid.encode()?, block.as_block_data()?)?;
+        Ok(Block::new(cid, data.into()))
+    }
+
+    /// Return a reference to the underlying store.
+    pub fn as_inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Return a mutable reference to the underlying store.
+    pub fn as_mut_inner(&mut self) -> &mut S {
+        &mut self.inner
+    }
+}
+
+impl<S: Store> Store for SealedStore<S> {
+    fn get(&self, cid: &Cid) -> Result<Option<Block>> {
+        if let Some(block) = Block::from_identity(cid)? {
+            return Ok(Some(block));
+        }
+
+        match self.inner.get(cid)? {
+            Some(block) => {
+                let cid = block.to_cid()?;
+                let data = self.key.open(&cid.encode()?, block.as_block_data()?)?;
+                Ok(Some(Block::new(cid, data.into())))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn put(&mut self, block: Block) -> Result<()> {
+        let block = self.seal_block(&block)?;
+        self.inner.put(block)
+    }
+}
+
+#[cfg(test)]
+#[path = "seal_test.rs"]
+mod seal_test;
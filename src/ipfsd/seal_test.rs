@@ -0,0 +1,72 @@
+use multibase::Base::Base32Lower;
+
+use super::*;
+use crate::{ipld::store::MemStore, multicodec};
+
+#[test]
+fn test_repo_key() {
+    let key = RepoKey::from_passphrase(b"correct horse", b"repo-salt");
+
+    let data = key.seal(b"/blocks/a", b"hello world").unwrap();
+    assert_eq!(data.len(), 12 + 11 + 16);
+    assert_eq!(key.open(b"/blocks/a", &data).unwrap(), b"hello world");
+
+    // fresh nonce for every seal.
+    assert!(key.seal(b"/blocks/a", b"hello world").unwrap() != data);
+
+    // bound to datastore key.
+    assert!(key.open(b"/blocks/b", &data).is_err());
+
+    let mut bad = data.clone();
+    bad[14] ^= 0x01;
+    assert!(key.open(b"/blocks/a", &bad).is_err());
+    assert!(key.open(b"/blocks/a", &data[..20]).is_err());
+
+    let other = RepoKey::from_passphrase(b"correct horse", b"other-salt");
+    assert!(other.open(b"/blocks/a", &data).is_err());
+}
+
+#[test]
+fn test_repo_key_file() {
+    let loc = std::env::temp_dir().join("iprs-seal-test.key");
+
+    fs::write(&loc, format!("{}\n", "ab".repeat(32))).unwrap();
+    let key = RepoKey::from_key_file(&loc).unwrap();
+    let data = key.seal(b"k", b"v").unwrap();
+    assert_eq!(key.open(b"k", &data).unwrap(), b"v");
+
+    fs::write(&loc, "abcd").unwrap();
+    assert!(RepoKey::from_key_file(&loc).is_err());
+
+    fs::remove_file(&loc).unwrap();
+}
+
+#[test]
+fn test_sealed_store() {
+    let key = RepoKey::from_passphrase(b"passphrase", b"salt");
+    let mut store = SealedStore::new(key, MemStore::new());
+
+    let block = {
+        let cid = Cid::new_v1(Base32Lower, multicodec::RAW.into(), b"data").unwrap();
+        Block::new(cid, b"data".to_vec().into())
+    };
+    let cid = block.to_cid().unwrap();
+
+    let sealed = store.seal_block(&block).unwrap();
+    assert!(sealed.as_block_data().unwrap() != b"data");
+    store.as_mut_inner().put(sealed).unwrap();
+
+    let val = store.get(&cid).unwrap().unwrap();
+    assert_eq!(val.as_block_data().unwrap(), b"data");
+    assert!(val.verify().unwrap());
+
+    // put seals the block.
+    let mut store = SealedStore::new(RepoKey::from_passphrase(b"pass", b"salt"), MemStore::new());
+    store.put(block).unwrap();
+    let inner = store.as_inner().get(&cid).unwrap().unwrap();
+    assert!(inner.as_block_data().unwrap() != b"data");
+    assert_eq!(
+        store.get(&cid).unwrap().unwrap().as_block_data().unwrap(),
+        b"data"
+    );
+}
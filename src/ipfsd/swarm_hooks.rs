@@ -0,0 +1,213 @@
+//! Module implement connection event hooks for the swarm.
+//!
+//! External crates embedding the daemon can observe, and veto, swarm
+//! activity by registering a [ConnHook] with [SwarmHooks], or a closure
+//! using one of the `on_*` methods. Swarm dispatches three events, in
+//! the order they happen on a connection:
+//!
+//! * connection established, after security and muxer upgrade, when the
+//!   remote peer is known.
+//! * stream opened, inbound or outbound, before protocol negotiation.
+//! * protocol negotiated on a stream, via multistream-select.
+//!
+//! Hooks are invoked in registration order, on the swarm thread, they
+//! must not block. A hook returning error rejects the connection or
+//! stream, remaining hooks are skipped and swarm closes it. This allows
+//! for custom access control, while accounting and instrumentation hooks
+//! simply return Ok.
+//!
+//! Connection established is dispatched by [Dialer][crate::dialer::Dialer]
+//! for outbound connections, stream events are dispatched while
+//! negotiating, by [Multistream][crate::multistream::Multistream].
+
+use std::{fmt, sync::Arc};
+
+use crate::{multiaddr::Multiaddr, peer_id::PeerId, Result};
+
+/// Direction of a connection or stream, relative to local node.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Direction {
+    Inbound,
+    Outbound,
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Direction::Inbound => write!(f, "inbound"),
+            Direction::Outbound => write!(f, "outbound"),
+        }
+    }
+}
+
+/// Connection as seen by hooks.
+#[derive(Clone, Debug)]
+pub struct ConnInfo {
+    /// Swarm assigned id, unique for the life of the daemon.
+    pub id: u64,
+    pub peer: PeerId,
+    pub local: Multiaddr,
+    pub remote: Multiaddr,
+    pub direction: Direction,
+}
+
+/// Stream as seen by hooks.
+#[derive(Clone, Debug)]
+pub struct StreamInfo {
+    /// Swarm assigned id, unique within the connection.
+    pub id: u64,
+    pub direction: Direction,
+}
+
+/// Hook for connection events, refer module documentation. All methods
+/// default to accept.
+pub trait ConnHook: Send + Sync {
+    fn on_connection_established(&self, _conn: &ConnInfo) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_stream_opened(&self, _conn: &ConnInfo, _stream: &StreamInfo) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_protocol_negotiated(
+        &self,
+        _conn: &ConnInfo,
+        _stream: &StreamInfo,
+        _protocol: &str,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+type EstablishedFn = dyn Fn(&ConnInfo) -> Result<()> + Send + Sync;
+type StreamFn = dyn Fn(&ConnInfo, &StreamInfo) -> Result<()> + Send + Sync;
+type NegotiatedFn = dyn Fn(&ConnInfo, &StreamInfo, &str) -> Result<()> + Send + Sync;
+
+// Adapts closures registered using SwarmHooks::on_* methods.
+enum FnHook {
+    Established(Box<EstablishedFn>),
+    Stream(Box<StreamFn>),
+    Negotiated(Box<NegotiatedFn>),
+}
+
+impl ConnHook for FnHook {
+    fn on_connection_established(&self, conn: &ConnInfo) -> Result<()> {
+        match self {
+            FnHook::Established(f) => f(conn),
+            _ => Ok(()),
+        }
+    }
+
+    fn on_stream_opened(&self, conn: &ConnInfo, stream: &StreamInfo) -> Result<()> {
+        match self {
+            FnHook::Stream(f) => f(conn, stream),
+            _ => Ok(()),
+        }
+    }
+
+    fn on_protocol_negotiated(
+        &self,
+        conn: &ConnInfo,
+        stream: &StreamInfo,
+        protocol: &str,
+    ) -> Result<()> {
+        match self {
+            FnHook::Negotiated(f) => f(conn, stream, protocol),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Registry of connection hooks, refer module documentation.
+#[derive(Clone, Default)]
+pub struct SwarmHooks {
+    hooks: Vec<Arc<dyn ConnHook>>,
+}
+
+impl fmt::Debug for SwarmHooks {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SwarmHooks<{}>", self.hooks.len())
+    }
+}
+
+impl SwarmHooks {
+    pub fn new() -> SwarmHooks {
+        SwarmHooks::default()
+    }
+
+    /// Register a hook for all connection events.
+    pub fn add_hook(&mut self, hook: Arc<dyn ConnHook>) -> &mut Self {
+        self.hooks.push(hook);
+        self
+    }
+
+    /// Register a closure called when a connection is established.
+    pub fn on_connection_established<F>(&mut self, f: F) -> &mut Self
+    where
+        F: 'static + Fn(&ConnInfo) -> Result<()> + Send + Sync,
+    {
+        self.add_hook(Arc::new(FnHook::Established(Box::new(f))))
+    }
+
+    /// Register a closure called when a stream is opened.
+    pub fn on_stream_opened<F>(&mut self, f: F) -> &mut Self
+    where
+        F: 'static + Fn(&ConnInfo, &StreamInfo) -> Result<()> + Send + Sync,
+    {
+        self.add_hook(Arc::new(FnHook::Stream(Box::new(f))))
+    }
+
+    /// Register a closure called when a protocol is negotiated on a
+    /// stream.
+    pub fn on_protocol_negotiated<F>(&mut self, f: F) -> &mut Self
+    where
+        F: 'static + Fn(&ConnInfo, &StreamInfo, &str) -> Result<()> + Send + Sync,
+    {
+        self.add_hook(Arc::new(FnHook::Negotiated(Box::new(f))))
+    }
+
+    pub fn len(&self) -> usize {
+        self.hooks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    /// Dispatch connection established event, called by swarm. Error
+    /// from a hook rejects the connection.
+    pub fn connection_established(&self, conn: &ConnInfo) -> Result<()> {
+        for hook in self.hooks.iter() {
+            hook.on_connection_established(conn)?;
+        }
+        Ok(())
+    }
+
+    /// Dispatch stream opened event, called by swarm. Error from a hook
+    /// rejects the stream.
+    pub fn stream_opened(&self, conn: &ConnInfo, stream: &StreamInfo) -> Result<()> {
+        for hook in self.hooks.iter() {
+            hook.on_stream_opened(conn, stream)?;
+        }
+        Ok(())
+    }
+
+    /// Dispatch protocol negotiated event, called by swarm. Error from a
+    /// hook rejects the stream.
+    pub fn protocol_negotiated(
+        &self,
+        conn: &ConnInfo,
+        stream: &StreamInfo,
+        protocol: &str,
+    ) -> Result<()> {
+        for hook in self.hooks.iter() {
+            hook.on_protocol_negotiated(conn, stream, protocol)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[path = "swarm_hooks_test.rs"]
+mod swarm_hooks_test;
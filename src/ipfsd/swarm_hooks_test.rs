@@ -0,0 +1,103 @@
+use std::sync::Mutex;
+
+use super::*;
+use crate::Error;
+
+#[derive(Default)]
+struct Counter(Mutex<Vec<String>>);
+
+impl ConnHook for Counter {
+    fn on_connection_established(&self, conn: &ConnInfo) -> Result<()> {
+        let event = format!("established:{}:{}", conn.id, conn.direction);
+        self.0.lock().unwrap().push(event);
+        Ok(())
+    }
+
+    fn on_protocol_negotiated(
+        &self,
+        conn: &ConnInfo,
+        stream: &StreamInfo,
+        protocol: &str,
+    ) -> Result<()> {
+        let event = format!("negotiated:{}:{}:{}", conn.id, stream.id, protocol);
+        self.0.lock().unwrap().push(event);
+        Ok(())
+    }
+}
+
+fn make_conn(id: u64) -> ConnInfo {
+    ConnInfo {
+        id,
+        peer: PeerId::generate().unwrap(),
+        local: Multiaddr::from_text("/ip4/127.0.0.1/tcp/4001").unwrap(),
+        remote: Multiaddr::from_text("/ip4/10.0.0.1/tcp/4001").unwrap(),
+        direction: Direction::Inbound,
+    }
+}
+
+#[test]
+fn test_swarm_hooks() {
+    let counter = Arc::new(Counter::default());
+    let mut hooks = SwarmHooks::new();
+    assert!(hooks.is_empty());
+
+    hooks.add_hook(counter.clone()).on_protocol_negotiated(
+        |_conn, _stream, protocol| match protocol {
+            "/ipfs/bitswap/1.2.0" => Ok(()),
+            _ => err_at!(Invalid, msg: "protocol {} denied", protocol),
+        },
+    );
+    assert_eq!(hooks.len(), 2);
+
+    let conn = make_conn(1);
+    let stream = StreamInfo {
+        id: 3,
+        direction: Direction::Outbound,
+    };
+    hooks.connection_established(&conn).unwrap();
+    hooks.stream_opened(&conn, &stream).unwrap();
+    hooks
+        .protocol_negotiated(&conn, &stream, "/ipfs/bitswap/1.2.0")
+        .unwrap();
+    assert!(hooks
+        .protocol_negotiated(&conn, &stream, "/ipfs/kad/1.0.0")
+        .is_err());
+
+    assert_eq!(
+        *counter.0.lock().unwrap(),
+        vec![
+            "established:1:inbound".to_string(),
+            "negotiated:1:3:/ipfs/bitswap/1.2.0".to_string(),
+            "negotiated:1:3:/ipfs/kad/1.0.0".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_swarm_hooks_reject() {
+    let mut hooks = SwarmHooks::new();
+    let denied = PeerId::generate().unwrap();
+    let seen = Arc::new(Mutex::new(0));
+
+    {
+        let denied = denied.clone();
+        hooks.on_connection_established(move |conn| match conn.peer == denied {
+            true => err_at!(Invalid, msg: "peer denied"),
+            false => Ok(()),
+        });
+    }
+    {
+        let seen = Arc::clone(&seen);
+        hooks.on_connection_established(move |_conn| {
+            *seen.lock().unwrap() += 1;
+            Ok(())
+        });
+    }
+
+    hooks.connection_established(&make_conn(1)).unwrap();
+    let mut conn = make_conn(2);
+    conn.peer = denied;
+    assert!(hooks.connection_established(&conn).is_err());
+    // hooks after the rejecting one are skipped.
+    assert_eq!(*seen.lock().unwrap(), 1);
+}
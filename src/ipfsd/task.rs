@@ -0,0 +1,295 @@
+use crossbeam_channel::{self as cbm, select};
+use log::{debug, error};
+
+use std::{
+    any::Any,
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use crate::{Error, Result};
+
+/// Delay before the first restart of a failed subsystem, doubled for
+/// every subsequent restart, refer [restart_backoff].
+pub const RESTART_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Maximum delay between restarts of a failed subsystem.
+pub const RESTART_BACKOFF_MAX: Duration = Duration::from_secs(30);
+
+/// Probe returning error if a running subsystem is unhealthy, refer
+/// [Subsystem::to_health_check]. Called from the daemon thread, hence
+/// shall not block.
+pub type HealthCheck = Box<dyn Fn() -> Result<()> + Send>;
+
+/// Subsystems like repo, swarm, gateway and api are run as supervised
+/// tasks, under ipfs-daemon.
+pub trait Subsystem: Send {
+    /// Name of this subsystem, used in logs and status.
+    fn to_name(&self) -> String;
+
+    /// Prepare the subsystem, like binding its listeners, before it is
+    /// [run][Subsystem::run]. Subsystem is marked as running, and counted
+    /// as ready by [Ipfsd::wait_ready][crate::ipfsd::Ipfsd::wait_ready],
+    /// only after this returns. Default does nothing.
+    fn start(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Run the subsystem until a message is received on `fin`, or until
+    /// it fails. Returning an error, or panicking, might restart the
+    /// subsystem, depending on its [Restart] policy.
+    fn run(&mut self, fin: &cbm::Receiver<()>) -> Result<()>;
+
+    /// Return a probe to check the health of this subsystem while it is
+    /// running, called once after [Subsystem::start]. Default is None,
+    /// subsystem is deemed healthy as long as it has not failed.
+    fn to_health_check(&self) -> Option<HealthCheck> {
+        None
+    }
+}
+
+/// Return the delay before `n`th restart of a failed subsystem,
+/// starting from [RESTART_BACKOFF] and capped at [RESTART_BACKOFF_MAX].
+pub fn restart_backoff(n: usize) -> Duration {
+    let shift = n.saturating_sub(1).min(16) as u32;
+    (RESTART_BACKOFF * (1_u32 << shift)).min(RESTART_BACKOFF_MAX)
+}
+
+/// Restart policy for a supervised subsystem.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Restart {
+    /// Don't restart, a failed subsystem stays failed.
+    Never,
+    /// Restart on failure, upto specified number of times.
+    OnFailure(usize),
+}
+
+/// State of a supervised subsystem.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum State {
+    Starting,
+    Running,
+    /// Failed and restarted, number of restarts so far.
+    Restarted(usize),
+    Stopping,
+    Stopped,
+    /// Failed and not restarted, with reason.
+    Failed(String),
+}
+
+impl fmt::Display for State {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            State::Starting => write!(f, "starting"),
+            State::Running => write!(f, "running"),
+            State::Restarted(n) => write!(f, "running, restarted {} times", n),
+            State::Stopping => write!(f, "stopping"),
+            State::Stopped => write!(f, "stopped"),
+            State::Failed(reason) => write!(f, "failed: {}", reason),
+        }
+    }
+}
+
+/// Handle to a supervised subsystem, running in its own thread.
+pub(crate) struct Task {
+    name: String,
+    state: Arc<Mutex<State>>,
+    health: Arc<Mutex<Option<HealthCheck>>>,
+    fin_tx: cbm::Sender<()>,
+    handle: Option<thread::JoinHandle<Result<()>>>,
+}
+
+impl Task {
+    pub(crate) fn spawn(sys: Box<dyn Subsystem>, restart: Restart) -> Task {
+        Task::spawn_after(sys, restart, None).0
+    }
+
+    /// Same as [Task::spawn], but start the subsystem only after `after`
+    /// receives true, that is, after the previous subsystem is running.
+    /// Returned receiver is signalled once this subsystem is running,
+    /// or closed if it failed to start.
+    pub(crate) fn spawn_after(
+        sys: Box<dyn Subsystem>,
+        restart: Restart,
+        after: Option<cbm::Receiver<bool>>,
+    ) -> (Task, cbm::Receiver<bool>) {
+        let name = sys.to_name();
+        let state = Arc::new(Mutex::new(State::Starting));
+        let health = Arc::new(Mutex::new(None));
+        let (fin_tx, fin_rx) = cbm::bounded(1);
+        let (started_tx, started_rx) = cbm::bounded(1);
+
+        let handle = {
+            let sv = Supervisor {
+                restart,
+                fin: fin_rx,
+                after,
+                started: started_tx,
+                state: Arc::clone(&state),
+                health: Arc::clone(&health),
+            };
+            thread::spawn(move || supervise(sys, sv))
+        };
+
+        let task = Task {
+            name,
+            state,
+            health,
+            fin_tx,
+            handle: Some(handle),
+        };
+        (task, started_rx)
+    }
+
+    pub(crate) fn to_name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub(crate) fn to_state_ref(&self) -> Arc<Mutex<State>> {
+        Arc::clone(&self.state)
+    }
+
+    pub(crate) fn to_health_ref(&self) -> Arc<Mutex<Option<HealthCheck>>> {
+        Arc::clone(&self.health)
+    }
+
+    pub(crate) fn to_state(&self) -> State {
+        match self.state.lock() {
+            Ok(state) => state.clone(),
+            Err(err) => State::Failed(format!("{}", err)),
+        }
+    }
+
+    /// Signal the subsystem to stop and wait for its thread to exit.
+    pub(crate) fn close_wait(&mut self) -> Result<()> {
+        {
+            let mut state = err_at!(Fatal, self.state.lock())?;
+            match &*state {
+                State::Stopped | State::Failed(_) => (),
+                _ => *state = State::Stopping,
+            }
+        }
+        // subsystem might have already exited, in which case fin is moot.
+        self.fin_tx.send(()).ok();
+
+        match self.handle.take() {
+            Some(handle) => match handle.join() {
+                Ok(val) => val,
+                Err(err) => err_at!(ThreadFail, msg: "{} {:?}", self.name, err),
+            },
+            None => Ok(()),
+        }
+    }
+}
+
+// supervisor side of a task, refer Task::spawn_after.
+struct Supervisor {
+    restart: Restart,
+    fin: cbm::Receiver<()>,
+    after: Option<cbm::Receiver<bool>>,
+    started: cbm::Sender<bool>,
+    state: Arc<Mutex<State>>,
+    health: Arc<Mutex<Option<HealthCheck>>>,
+}
+
+fn supervise(mut sys: Box<dyn Subsystem>, sv: Supervisor) -> Result<()> {
+    let name = sys.to_name();
+    let (fin, state) = (&sv.fin, &sv.state);
+    let mut restarts = 0;
+
+    // previous subsystem failed to start, or daemon is closing, don't
+    // start this one.
+    if let Some(after) = sv.after.as_ref() {
+        let ok = select! {
+            recv(after) -> ok => ok.unwrap_or(false),
+            recv(fin) -> _ => false,
+        };
+        if !ok {
+            debug!("subsystem {} not started", name);
+            return set_state(state, State::Stopped);
+        }
+    }
+
+    if let Err(err) = catch_panic(&name, || sys.start()) {
+        error!("subsystem {} start: {}", name, err);
+        set_state(state, State::Failed(err.to_string()))?;
+        return Err(err);
+    }
+
+    *err_at!(Fatal, sv.health.lock())? = sys.to_health_check();
+    {
+        let mut state = err_at!(Fatal, state.lock())?;
+        if *state == State::Starting {
+            *state = State::Running;
+        }
+    }
+    sv.started.send(true).ok();
+
+    loop {
+        let res = catch_panic(&name, || sys.run(fin));
+        let stopping = *err_at!(Fatal, state.lock())? == State::Stopping;
+
+        match (res, sv.restart) {
+            (Ok(()), _) => {
+                debug!("subsystem {} stopped", name);
+                break set_state(state, State::Stopped);
+            }
+            (Err(err), Restart::OnFailure(max)) if !stopping && restarts < max => {
+                restarts += 1;
+                let backoff = restart_backoff(restarts);
+                error!(
+                    "subsystem {} restart {}/{} after {:?}: {}",
+                    name, restarts, max, backoff, err
+                );
+                // stopped while backing off, subsystem stays failed.
+                if fin.recv_timeout(backoff).is_ok() {
+                    set_state(state, State::Failed(err.to_string()))?;
+                    break Err(err);
+                }
+                set_state(state, State::Restarted(restarts))?;
+            }
+            (Err(err), _) => {
+                set_state(state, State::Failed(err.to_string()))?;
+                break Err(err);
+            }
+        }
+    }
+}
+
+// a panicking subsystem is treated as failed, instead of taking down
+// the supervisor thread along with it.
+fn catch_panic<F>(name: &str, f: F) -> Result<()>
+where
+    F: FnOnce() -> Result<()>,
+{
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(res) => res,
+        Err(payload) => {
+            let reason = panic_reason(payload.as_ref());
+            err_at!(ThreadFail, msg: "subsystem {} panic: {}", name, reason)
+        }
+    }
+}
+
+fn panic_reason(payload: &(dyn Any + Send)) -> String {
+    match payload.downcast_ref::<&str>() {
+        Some(reason) => reason.to_string(),
+        None => match payload.downcast_ref::<String>() {
+            Some(reason) => reason.clone(),
+            None => "unknown".to_string(),
+        },
+    }
+}
+
+fn set_state(state: &Mutex<State>, new_state: State) -> Result<()> {
+    let mut state = err_at!(Fatal, state.lock())?;
+    *state = new_state;
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "task_test.rs"]
+mod task_test;
@@ -0,0 +1,264 @@
+use std::{
+    sync::atomic::{AtomicUsize, Ordering::SeqCst},
+    time::{Duration, Instant},
+};
+
+use super::*;
+use crate::ipfsd::Ipfsd;
+
+// fails `fails` times, by panic or by error, before running until fin.
+struct Flaky {
+    panic: bool,
+    fails: usize,
+    runs: Arc<AtomicUsize>,
+}
+
+impl Subsystem for Flaky {
+    fn to_name(&self) -> String {
+        "flaky".to_string()
+    }
+
+    fn run(&mut self, fin: &cbm::Receiver<()>) -> Result<()> {
+        let n = self.runs.fetch_add(1, SeqCst);
+        match n < self.fails {
+            true if self.panic => panic!("flaky run {}", n),
+            true => err_at!(IOError, msg: "flaky run {}", n),
+            false => {
+                let _ = fin.recv();
+                Ok(())
+            }
+        }
+    }
+}
+
+struct BadStart;
+
+impl Subsystem for BadStart {
+    fn to_name(&self) -> String {
+        "bad-start".to_string()
+    }
+
+    fn start(&mut self) -> Result<()> {
+        err_at!(IOError, msg: "address in use")
+    }
+
+    fn run(&mut self, _: &cbm::Receiver<()>) -> Result<()> {
+        unreachable!()
+    }
+}
+
+fn flaky(panic: bool, fails: usize) -> (Box<dyn Subsystem>, Arc<AtomicUsize>) {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let sys = Flaky {
+        panic,
+        fails,
+        runs: Arc::clone(&runs),
+    };
+    (Box::new(sys), runs)
+}
+
+fn wait_state<F>(task: &Task, cond: F) -> State
+where
+    F: Fn(&State) -> bool,
+{
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        let state = task.to_state();
+        if cond(&state) {
+            break state;
+        }
+        assert!(Instant::now() < deadline, "{}", state);
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+fn is_failed(state: &State) -> bool {
+    matches!(state, State::Failed(_))
+}
+
+#[test]
+fn test_task_restart() {
+    for panic in [false, true].iter() {
+        let (sys, runs) = flaky(*panic, 2);
+        let mut task = Task::spawn(sys, Restart::OnFailure(2));
+        wait_state(&task, |state| *state == State::Restarted(2));
+        assert!(task.close_wait().is_ok());
+        assert_eq!(task.to_state(), State::Stopped);
+        assert_eq!(runs.load(SeqCst), 3);
+
+        let (sys, runs) = flaky(*panic, 3);
+        let mut task = Task::spawn(sys, Restart::OnFailure(2));
+        match wait_state(&task, is_failed) {
+            State::Failed(reason) => assert!(reason.contains("flaky run 2"), "{}", reason),
+            state => panic!("{}", state),
+        }
+        assert!(task.close_wait().is_err());
+        assert_eq!(runs.load(SeqCst), 3);
+
+        let (sys, runs) = flaky(*panic, 1);
+        let mut task = Task::spawn(sys, Restart::Never);
+        wait_state(&task, is_failed);
+        assert!(task.close_wait().is_err());
+        assert_eq!(runs.load(SeqCst), 1);
+    }
+}
+
+#[test]
+fn test_task_start_fail() {
+    let mut task = Task::spawn(Box::new(BadStart), Restart::OnFailure(10));
+    let state = wait_state(&task, is_failed);
+    assert!(task.close_wait().is_err());
+    match state {
+        State::Failed(reason) => assert!(reason.contains("address in use"), "{}", reason),
+        state => panic!("{}", state),
+    }
+}
+
+#[test]
+fn test_ipfsd_status() {
+    let (sys, _) = flaky(true, 1);
+    let ipfsd = Ipfsd::spawn_with(vec![(sys, Restart::OnFailure(1))]).unwrap();
+    assert!(ipfsd.wait_ready(Duration::from_secs(5)).unwrap());
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while ipfsd.status() != vec![("flaky".to_string(), State::Restarted(1))] {
+        assert!(Instant::now() < deadline, "{:?}", ipfsd.status());
+        thread::sleep(Duration::from_millis(5));
+    }
+    ipfsd.close_wait().unwrap();
+
+    let (sys, _) = flaky(false, 0);
+    let bad: Box<dyn Subsystem> = Box::new(BadStart);
+    let subsystems = vec![(sys, Restart::Never), (bad, Restart::Never)];
+    let ipfsd = Ipfsd::spawn_with(subsystems).unwrap();
+    assert!(ipfsd.wait_ready(Duration::from_secs(5)).is_err());
+    assert!(ipfsd.close_wait().is_err());
+}
+
+#[test]
+fn test_restart_backoff() {
+    assert_eq!(restart_backoff(0), RESTART_BACKOFF);
+    assert_eq!(restart_backoff(1), RESTART_BACKOFF);
+    assert_eq!(restart_backoff(2), RESTART_BACKOFF * 2);
+    assert_eq!(restart_backoff(4), RESTART_BACKOFF * 8);
+    assert_eq!(restart_backoff(100), RESTART_BACKOFF_MAX);
+
+    // failing subsystem is not restarted in a tight loop.
+    let (sys, runs) = flaky(false, 10);
+    let mut task = Task::spawn(sys, Restart::OnFailure(10));
+    wait_state(&task, |state| *state == State::Restarted(1));
+    thread::sleep(RESTART_BACKOFF);
+    assert!(runs.load(SeqCst) <= 3, "{}", runs.load(SeqCst));
+
+    // stopped while backing off, stays failed.
+    assert!(task.close_wait().is_err());
+    assert!(is_failed(&task.to_state()));
+}
+
+// records its name in `order` when started, after `delay`.
+struct Ordered {
+    name: &'static str,
+    delay: Duration,
+    order: Arc<Mutex<Vec<&'static str>>>,
+}
+
+impl Subsystem for Ordered {
+    fn to_name(&self) -> String {
+        self.name.to_string()
+    }
+
+    fn start(&mut self) -> Result<()> {
+        thread::sleep(self.delay);
+        self.order.lock().unwrap().push(self.name);
+        Ok(())
+    }
+
+    fn run(&mut self, fin: &cbm::Receiver<()>) -> Result<()> {
+        let _ = fin.recv();
+        Ok(())
+    }
+}
+
+#[test]
+fn test_ipfsd_start_order() {
+    let order = Arc::new(Mutex::new(vec![]));
+    let ordered = |name, millis| -> (Box<dyn Subsystem>, Restart) {
+        let sys = Ordered {
+            name,
+            delay: Duration::from_millis(millis),
+            order: Arc::clone(&order),
+        };
+        (Box::new(sys), Restart::Never)
+    };
+
+    // slow subsystem is running before the next one is started.
+    let subsystems = vec![ordered("repo", 100), ordered("swarm", 0), ordered("api", 0)];
+    let ipfsd = Ipfsd::spawn_with(subsystems).unwrap();
+    assert!(ipfsd.wait_ready(Duration::from_secs(5)).unwrap());
+    assert_eq!(*order.lock().unwrap(), vec!["repo", "swarm", "api"]);
+    ipfsd.close_wait().unwrap();
+
+    // subsystems after a failed one are not started.
+    order.lock().unwrap().clear();
+    let bad: Box<dyn Subsystem> = Box::new(BadStart);
+    let subsystems = vec![ordered("repo", 0), (bad, Restart::Never), ordered("api", 0)];
+    let ipfsd = Ipfsd::spawn_with(subsystems).unwrap();
+    assert!(ipfsd.wait_ready(Duration::from_secs(5)).is_err());
+    thread::sleep(Duration::from_millis(50));
+    let status = ipfsd.status();
+    assert_eq!(status[0].1, State::Running);
+    assert!(is_failed(&status[1].1));
+    assert_eq!(status[2].1, State::Stopped);
+    assert!(ipfsd.close_wait().is_err());
+    assert_eq!(*order.lock().unwrap(), vec!["repo"]);
+}
+
+// healthy as long as the flag is set.
+struct Probed(Arc<std::sync::atomic::AtomicBool>);
+
+impl Subsystem for Probed {
+    fn to_name(&self) -> String {
+        "probed".to_string()
+    }
+
+    fn run(&mut self, fin: &cbm::Receiver<()>) -> Result<()> {
+        let _ = fin.recv();
+        Ok(())
+    }
+
+    fn to_health_check(&self) -> Option<HealthCheck> {
+        let healthy = Arc::clone(&self.0);
+        let check = move || match healthy.load(SeqCst) {
+            true => Ok(()),
+            false => err_at!(IOError, msg: "probe failed"),
+        };
+        Some(Box::new(check))
+    }
+}
+
+#[test]
+fn test_ipfsd_health() {
+    let healthy = Arc::new(std::sync::atomic::AtomicBool::new(true));
+    let (sys, _) = flaky(false, 0);
+    let subsystems = vec![
+        (sys, Restart::Never),
+        (
+            Box::new(Probed(Arc::clone(&healthy))) as Box<dyn Subsystem>,
+            Restart::Never,
+        ),
+    ];
+    let ipfsd = Ipfsd::spawn_with(subsystems).unwrap();
+    assert!(ipfsd.wait_ready(Duration::from_secs(5)).unwrap());
+
+    // only subsystems with a health check are reported.
+    let health = ipfsd.health();
+    assert_eq!(health.len(), 1);
+    assert_eq!(health[0].0, "probed");
+    assert!(health[0].1.is_ok());
+
+    healthy.store(false, SeqCst);
+    let health = ipfsd.health();
+    let err = health[0].1.as_ref().unwrap_err().to_string();
+    assert!(err.contains("probe failed"), "{}", err);
+
+    ipfsd.close_wait().unwrap();
+}
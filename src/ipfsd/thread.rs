@@ -1,11 +1,26 @@
 use crossbeam_channel::{self as cbm, select};
 use log::{debug, error};
 
-use std::thread;
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
 
-use crate::{util, Error, Result};
+use crate::{
+    ipfsd::{
+        notify::Notifier,
+        task::{HealthCheck, Restart, State, Subsystem, Task},
+    },
+    util, Error, Result,
+};
 
 const MAX_CHANSIZE: usize = 16;
+const READY_POLL: Duration = Duration::from_millis(10);
+
+/// Interval for checking the health of running subsystems, refer
+/// [Subsystem::to_health_check].
+pub const HEALTH_INTERVAL: Duration = Duration::from_secs(10);
 
 pub enum Req {
     Fin,
@@ -47,19 +62,117 @@ impl Client {
     }
 }
 
-/// Ipfs daemon.
+/// Ipfs daemon, supervising one or more subsystems.
 pub struct Ipfsd {
     tx: cbm::Sender<(Req, Option<cbm::Sender<Res>>)>,
     handle: Option<thread::JoinHandle<Result<()>>>,
+    tasks: Vec<Task>,
 }
 
 impl Ipfsd {
     /// Create a daemon, using asynchronous channel with infinite buffer.
     pub fn spawn() -> Result<Ipfsd> {
+        Self::spawn_with(vec![])
+    }
+
+    /// Create a daemon supervising `subsystems`. Subsystems are started
+    /// in the supplied order, each one only after the previous one is
+    /// running, and shut down in reverse order, so supply them as repo,
+    /// swarm, gateway, api. If a subsystem fails to start, subsystems
+    /// after it are not started. Failed subsystems are restarted as per
+    /// their [Restart] policy, with backoff, refer [restart_backoff].
+    ///
+    /// Health of running subsystems is checked every [HEALTH_INTERVAL],
+    /// refer [Ipfsd::health]. When run under a service manager with
+    /// watchdog enabled, watchdog is pinged at half the interval as long
+    /// as no subsystem has failed or is unhealthy, refer [Notifier::from_env].
+    ///
+    /// [restart_backoff]: crate::ipfsd::restart_backoff
+    pub fn spawn_with(subsystems: Vec<(Box<dyn Subsystem>, Restart)>) -> Result<Ipfsd> {
+        Self::spawn_notify(subsystems, Notifier::from_env()?)
+    }
+
+    /// Same as [Ipfsd::spawn_with], with explicit `notifier`.
+    pub fn spawn_notify(
+        subsystems: Vec<(Box<dyn Subsystem>, Restart)>,
+        notifier: Notifier,
+    ) -> Result<Ipfsd> {
         debug!("spawned in async mode");
+        let watchdog = notifier.to_watchdog_interval().map(|interval| interval / 2);
+
+        let mut tasks: Vec<Task> = vec![];
+        let mut after = None;
+        for (sys, restart) in subsystems.into_iter() {
+            let (task, started) = Task::spawn_after(sys, restart, after.take());
+            tasks.push(task);
+            after = Some(started);
+        }
+
         let (tx, rx) = cbm::bounded(MAX_CHANSIZE);
-        let handle = Some(thread::spawn(|| run(rx)));
-        Ok(Ipfsd { tx, handle })
+        let states = tasks
+            .iter()
+            .map(|task| Probe {
+                name: task.to_name(),
+                state: task.to_state_ref(),
+                health: task.to_health_ref(),
+            })
+            .collect();
+        let handle = Some(thread::spawn(move || run(rx, notifier, watchdog, states)));
+
+        Ok(Ipfsd { tx, handle, tasks })
+    }
+
+    /// Wait until all subsystems have started, refer [Subsystem::start],
+    /// for upto `timeout`. Return false on timeout and fail if any of the
+    /// subsystem failed. Typically followed by
+    /// [notify_ready][crate::ipfsd::notify_ready].
+    pub fn wait_ready(&self, timeout: Duration) -> Result<bool> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let mut ready = true;
+            for (name, state) in self.status().into_iter() {
+                match state {
+                    State::Starting => ready = false,
+                    State::Failed(reason) => {
+                        err_at!(ThreadFail, msg: "subsystem {} failed: {}", name, reason)?
+                    }
+                    _ => (),
+                }
+            }
+
+            if ready {
+                break Ok(true);
+            } else if Instant::now() >= deadline {
+                break Ok(false);
+            }
+            thread::sleep(READY_POLL);
+        }
+    }
+
+    /// Return the name and state of each supervised subsystem.
+    pub fn status(&self) -> Vec<(String, State)> {
+        self.tasks
+            .iter()
+            .map(|task| (task.to_name(), task.to_state()))
+            .collect()
+    }
+
+    /// Run the health check of each running subsystem, and return the
+    /// name and outcome for each of them. Subsystems that are not running,
+    /// or have no health check, are skipped.
+    pub fn health(&self) -> Vec<(String, Result<()>)> {
+        let mut items = vec![];
+        for task in self.tasks.iter() {
+            let probe = Probe {
+                name: task.to_name(),
+                state: task.to_state_ref(),
+                health: task.to_health_ref(),
+            };
+            if let Some(res) = probe.check_health() {
+                items.push((probe.name, res))
+            }
+        }
+        items
     }
 
     /// Return a sender channel.
@@ -69,23 +182,43 @@ impl Ipfsd {
         }
     }
 
-    /// Recommended call to exit and shutdown the daemon.
+    /// Recommended call to exit and shutdown the daemon. Subsystems are
+    /// stopped in the reverse order of spawn, the first error is returned
+    /// after all of them are stopped.
     pub fn close_wait(mut self) -> Result<()> {
-        self.to_client().request(Req::Fin)?;
+        let mut res = Ok(());
+        while let Some(mut task) = self.tasks.pop() {
+            let r = task.close_wait();
+            if res.is_ok() {
+                res = r;
+            }
+        }
+
+        // request() would install a control-c handler, which is allowed
+        // only once per process, post instead and wait for the exit.
+        self.to_client().post(Req::Fin)?;
         match self.handle.take() {
             Some(handle) => match handle.join() {
-                Ok(val) => Ok(val?),
-                Err(err) => err_at!(ThreadFail, msg: "{:?}", err),
+                Ok(val) => val?,
+                Err(err) => err_at!(ThreadFail, msg: "{:?}", err)?,
             },
-            None => Ok(()),
+            None => (),
         }
+
+        res
     }
 }
 
 impl Drop for Ipfsd {
     fn drop(&mut self) {
+        while let Some(mut task) = self.tasks.pop() {
+            if let Err(err) = task.close_wait() {
+                error!("subsystem {} close fail {}", task.to_name(), err);
+            }
+        }
+
         match self.handle.take() {
-            Some(handle) => match self.to_client().request(Req::Fin) {
+            Some(handle) => match self.to_client().post(Req::Fin) {
                 Ok(_) => match handle.join() {
                     Ok(_) => debug!("ipfsd dropped"),
                     Err(err) => error!("drop fail {:?}", err),
@@ -97,17 +230,80 @@ impl Drop for Ipfsd {
     }
 }
 
-fn run(rx: cbm::Receiver<(Req, Option<cbm::Sender<Res>>)>) -> Result<()> {
-    for q in rx {
-        match q {
-            (Req::Fin, tx) => {
-                run_fin(tx)?;
-                break;
+// shared state of a supervised subsystem, for the daemon thread.
+struct Probe {
+    name: String,
+    state: Arc<Mutex<State>>,
+    health: Arc<Mutex<Option<HealthCheck>>>,
+}
+
+impl Probe {
+    // return None if subsystem is not running or has no health check.
+    fn check_health(&self) -> Option<Result<()>> {
+        match self.state.lock().as_deref() {
+            Ok(State::Running) | Ok(State::Restarted(_)) => (),
+            _ => return None,
+        }
+        match self.health.lock() {
+            Ok(health) => health.as_ref().map(|check| check()),
+            Err(err) => Some(err_at!(Fatal, msg: "{}", err)),
+        }
+    }
+}
+
+fn run(
+    rx: cbm::Receiver<(Req, Option<cbm::Sender<Res>>)>,
+    notifier: Notifier,
+    watchdog: Option<Duration>,
+    states: Vec<Probe>,
+) -> Result<()> {
+    let tick = match watchdog {
+        Some(interval) => cbm::tick(interval),
+        None => cbm::never(),
+    };
+    let health_tick = cbm::tick(HEALTH_INTERVAL);
+
+    loop {
+        select! {
+            recv(rx) -> q => match q {
+                Ok((Req::Fin, tx)) => break run_fin(tx),
+                Err(_) => break Ok(()),
+            },
+            recv(tick) -> _ => ping_watchdog(&notifier, &states),
+            recv(health_tick) -> _ => {
+                for probe in states.iter() {
+                    if let Some(Err(err)) = probe.check_health() {
+                        error!("subsystem {} unhealthy: {}", probe.name, err);
+                    }
+                }
             }
         }
     }
+}
 
-    Ok(())
+// skip the ping if any subsystem has failed, or is unhealthy, so that
+// service manager can restart the daemon.
+fn ping_watchdog(notifier: &Notifier, states: &[Probe]) {
+    for probe in states.iter() {
+        let failed = match probe.state.lock() {
+            Ok(state) => match &*state {
+                State::Failed(reason) => Some(reason.clone()),
+                _ => None,
+            },
+            Err(err) => Some(err.to_string()),
+        };
+        let reason = match (failed, probe.check_health()) {
+            (Some(reason), _) => reason,
+            (None, Some(Err(err))) => err.to_string(),
+            (None, _) => continue,
+        };
+        error!("skip watchdog, subsystem {} failed: {}", probe.name, reason);
+        return;
+    }
+
+    if let Err(err) = notifier.notify_watchdog() {
+        error!("watchdog ping: {}", err);
+    }
 }
 
 fn run_fin(tx: Option<cbm::Sender<Res>>) -> Result<()> {
@@ -118,3 +314,7 @@ fn run_fin(tx: Option<cbm::Sender<Res>>) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+#[path = "thread_test.rs"]
+mod thread_test;
@@ -0,0 +1,82 @@
+use std::{ffi::OsString, fs, os::unix::net::UnixDatagram, process};
+
+use super::*;
+
+// runs until fin, or fails right away.
+struct Sys(bool);
+
+impl Subsystem for Sys {
+    fn to_name(&self) -> String {
+        "sys".to_string()
+    }
+
+    fn run(&mut self, fin: &cbm::Receiver<()>) -> Result<()> {
+        match self.0 {
+            true => {
+                let _ = fin.recv();
+                Ok(())
+            }
+            false => err_at!(IOError, msg: "sys failed"),
+        }
+    }
+}
+
+// count watchdog pings received on `sock` within `timeout`.
+fn count_pings(sock: &UnixDatagram, timeout: Duration) -> usize {
+    let deadline = Instant::now() + timeout;
+    let mut buf = [0_u8; 64];
+    let mut n = 0;
+    while Instant::now() < deadline {
+        if let Ok(m) = sock.recv(&mut buf) {
+            assert_eq!(&buf[..m], b"WATCHDOG=1");
+            n += 1;
+        }
+    }
+    n
+}
+
+#[test]
+fn test_watchdog() {
+    let path = std::env::temp_dir().join(format!("iprs-watchdog-{}", process::id()));
+    fs::remove_file(&path).ok();
+    let sock = UnixDatagram::bind(&path).unwrap();
+    sock.set_read_timeout(Some(Duration::from_millis(10)))
+        .unwrap();
+
+    let vars = |key: &str| match key {
+        "NOTIFY_SOCKET" => Some(OsString::from(&path)),
+        "WATCHDOG_USEC" => Some(OsString::from("20000")),
+        _ => None,
+    };
+    let notifier = Notifier::from_vars(vars).unwrap();
+
+    // pinged while all subsystems are healthy.
+    let subsystems: Vec<(Box<dyn Subsystem>, Restart)> =
+        vec![(Box::new(Sys(true)), Restart::Never)];
+    let ipfsd = Ipfsd::spawn_notify(subsystems, notifier.clone()).unwrap();
+    assert!(ipfsd.wait_ready(Duration::from_secs(5)).unwrap());
+    assert!(count_pings(&sock, Duration::from_millis(200)) > 0);
+    ipfsd.close_wait().unwrap();
+    count_pings(&sock, Duration::from_millis(50));
+
+    // not pinged once a subsystem has failed.
+    let subsystems: Vec<(Box<dyn Subsystem>, Restart)> = vec![
+        (Box::new(Sys(true)), Restart::Never),
+        (Box::new(Sys(false)), Restart::Never),
+    ];
+    let ipfsd = Ipfsd::spawn_notify(subsystems, notifier).unwrap();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !ipfsd
+        .status()
+        .iter()
+        .any(|(_, s)| matches!(s, State::Failed(_)))
+    {
+        assert!(Instant::now() < deadline, "{:?}", ipfsd.status());
+        thread::sleep(Duration::from_millis(5));
+    }
+    count_pings(&sock, Duration::from_millis(50));
+    assert_eq!(count_pings(&sock, Duration::from_millis(200)), 0);
+    assert!(ipfsd.close_wait().is_err());
+
+    fs::remove_file(&path).unwrap();
+}
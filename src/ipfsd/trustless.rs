@@ -0,0 +1,328 @@
+//! Module implement trustless gateway client, fetching blocks over HTTP.
+//!
+//! Nodes behind restrictive firewalls, that can't reach peers over
+//! bitswap, can fetch blocks from public [trustless gateways]. Blocks are
+//! requested as `GET <gateway>/ipfs/<cid>?format=raw` with
+//! `Accept: application/vnd.ipld.raw`, and since the gateway is not
+//! trusted, every block is verified against the multihash in its CID
+//! before it is returned. Gateways are tried in order, a gateway that
+//! fails or serves bad data is skipped for the next one.
+//!
+//! Verification is only as strong as the hash, CIDs using a
+//! non-cryptographic hash, like murmur3, or a digest shorter than
+//! [MIN_DIGEST_SIZE] are rejected before any request is made, and so are
+//! CIDs denied by the [SecurityPolicy], [SecurityPolicy::strict] by
+//! default.
+//!
+//! [TrustlessClient] implements [Store], hence can be used as a read-only
+//! content source wherever a store is expected.
+//!
+//! Built-in transport, [TcpHttp], speaks plain HTTP/1.0. `https://`
+//! gateways need a TLS capable transport supplied by the application,
+//! refer [TrustlessClient::set_http].
+//!
+//! [trustless gateways]: https://specs.ipfs.tech/http-gateways/trustless-gateway/
+
+use std::{
+    io::{Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    str,
+    sync::Arc,
+    time::Duration,
+};
+
+use crate::{
+    cid::Cid,
+    ipfsd::SecurityPolicy,
+    ipld::{block::Block, cbor::MAX_BLOCK_SIZE, store::Store},
+    multicodec, Error, Result,
+};
+
+/// Media type for a single raw block.
+pub const RAW_BLOCK: &str = "application/vnd.ipld.raw";
+
+/// Default timeout for a single block request.
+pub const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Minimum digest size, in bytes, for blocks fetched from a gateway.
+pub const MIN_DIGEST_SIZE: usize = 20;
+
+/// Hash functions that offer no protection against a forging gateway.
+pub const NON_CRYPTO_HASHES: [u128; 2] = [multicodec::MURMUR3_32, multicodec::MURMUR3_128];
+
+// allowance for status line and headers, over the block size.
+const MAX_HEADER_SIZE: usize = 16 * 1024;
+
+/// Response to an HTTP GET request.
+#[derive(Clone, Debug, Default)]
+pub struct HttpResponse {
+    pub status: u16,
+    /// Header names are in lower case.
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// Return the value of header `name`, matched case insensitive.
+    pub fn to_header(&self, name: &str) -> Option<&str> {
+        let name = name.to_lowercase();
+        self.headers
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, val)| val.as_str())
+    }
+}
+
+/// HTTP transport used by [TrustlessClient].
+pub trait HttpGet: Send + Sync {
+    /// GET `url` with `accept` header, response body shall not exceed
+    /// `max_body` bytes.
+    fn get(
+        &self,
+        url: &str,
+        accept: &str,
+        max_body: usize,
+        timeout: Duration,
+    ) -> Result<HttpResponse>;
+}
+
+/// Plain HTTP/1.0 transport over TCP, supports `http://` urls only.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TcpHttp;
+
+impl HttpGet for TcpHttp {
+    fn get(
+        &self,
+        url: &str,
+        accept: &str,
+        max_body: usize,
+        timeout: Duration,
+    ) -> Result<HttpResponse> {
+        let (host, port, path) = parse_http_url(url)?;
+
+        let mut stream = connect(&host, port, timeout)?;
+        err_at!(IOError, stream.set_read_timeout(Some(timeout)))?;
+        err_at!(IOError, stream.set_write_timeout(Some(timeout)))?;
+
+        let req = format!(
+            "GET {} HTTP/1.0\r\nHost: {}\r\nAccept: {}\r\nUser-Agent: iprs\r\n\r\n",
+            path, host, accept
+        );
+        err_at!(IOError, stream.write_all(req.as_bytes()))?;
+
+        let limit = (max_body + MAX_HEADER_SIZE) as u64;
+        let mut data = vec![];
+        err_at!(IOError, stream.take(limit + 1).read_to_end(&mut data))?;
+        if data.len() as u64 > limit {
+            err_at!(BadInput, msg: "response from {} exceeds {} bytes", url, limit)?
+        }
+
+        parse_response(&data)
+    }
+}
+
+/// Client for trustless gateways, refer module documentation.
+pub struct TrustlessClient {
+    gateways: Vec<String>,
+    http: Arc<dyn HttpGet>,
+    timeout: Duration,
+    policy: SecurityPolicy,
+}
+
+impl TrustlessClient {
+    /// Create a client for `gateways`, like `http://127.0.0.1:8080`,
+    /// tried in the supplied order.
+    pub fn new(gateways: Vec<String>) -> Result<TrustlessClient> {
+        let mut urls = vec![];
+        for gw in gateways.into_iter() {
+            if !gw.starts_with("http://") && !gw.starts_with("https://") {
+                err_at!(BadInput, msg: "gateway url {:?}", gw)?
+            }
+            urls.push(gw.trim_end_matches('/').to_string());
+        }
+        if urls.is_empty() {
+            err_at!(BadInput, msg: "no trustless gateway")?
+        }
+
+        let val = TrustlessClient {
+            gateways: urls,
+            http: Arc::new(TcpHttp),
+            timeout: FETCH_TIMEOUT,
+            policy: SecurityPolicy::strict(),
+        };
+        Ok(val)
+    }
+
+    /// Set HTTP transport, default is [TcpHttp].
+    pub fn set_http(&mut self, http: Arc<dyn HttpGet>) -> &mut Self {
+        self.http = http;
+        self
+    }
+
+    /// Set timeout for each request, default is [FETCH_TIMEOUT].
+    pub fn set_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set the policy for CIDs fetched, default is
+    /// [SecurityPolicy::strict].
+    pub fn set_policy(&mut self, policy: SecurityPolicy) -> &mut Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn to_gateways(&self) -> Vec<String> {
+        self.gateways.clone()
+    }
+
+    /// Fetch and verify the block for `cid`. Return None if none of the
+    /// gateways have it, and the last error if all of them failed.
+    pub fn fetch_block(&self, cid: &Cid) -> Result<Option<Block>> {
+        if let Some(block) = Block::from_identity(cid)? {
+            return Ok(Some(block));
+        }
+        self.check_cid(cid)?;
+
+        let cid_text = cid.to_text(None)?;
+        let (mut not_found, mut res) = (false, Ok(None));
+        for gw in self.gateways.iter() {
+            let url = format!("{}/ipfs/{}?format=raw", gw, cid_text);
+            match self.fetch_from(&url, cid) {
+                Ok(Some(block)) => return Ok(Some(block)),
+                Ok(None) => not_found = true,
+                Err(err) => res = Err(err),
+            }
+        }
+
+        match not_found {
+            true => Ok(None),
+            false => res,
+        }
+    }
+
+    // a gateway can forge blocks for weak or short digests.
+    fn check_cid(&self, cid: &Cid) -> Result<()> {
+        self.policy.check_cid(cid)?;
+
+        let mh = cid.to_multihash();
+        let codec = mh.to_codec()?;
+        if NON_CRYPTO_HASHES.contains(&codec.to_code()) {
+            err_at!(BadCodec, msg: "non-cryptographic hash {} for {}", codec, cid)?
+        }
+        let n = mh.to_digest()?.len();
+        if n < MIN_DIGEST_SIZE {
+            err_at!(BadInput, msg: "digest {} bytes, below {} for {}", n, MIN_DIGEST_SIZE, cid)?
+        }
+        Ok(())
+    }
+
+    fn fetch_from(&self, url: &str, cid: &Cid) -> Result<Option<Block>> {
+        let max_body = MAX_BLOCK_SIZE as usize;
+        let resp = self.http.get(url, RAW_BLOCK, max_body, self.timeout)?;
+        match resp.status {
+            200 => (),
+            404 | 410 => return Ok(None),
+            status => err_at!(IOError, msg: "{} status {}", url, status)?,
+        }
+        if let Some(ct) = resp.to_header("content-type") {
+            if !ct.starts_with(RAW_BLOCK) {
+                err_at!(DecodeError, msg: "{} content-type {:?}", url, ct)?
+            }
+        }
+        if resp.body.len() > max_body {
+            err_at!(DecodeError, msg: "{} block size {}", url, resp.body.len())?
+        }
+
+        let block = Block::new(cid.clone(), resp.body.into());
+        match block.verify()? {
+            true => Ok(Some(block)),
+            false => err_at!(DecodeError, msg: "{} block doesn't match multihash", url),
+        }
+    }
+}
+
+impl Store for TrustlessClient {
+    fn get(&self, cid: &Cid) -> Result<Option<Block>> {
+        self.fetch_block(cid)
+    }
+}
+
+// return (host, port, path) from `http://host[:port][/path]`.
+fn parse_http_url(url: &str) -> Result<(String, u16, String)> {
+    let rest = match url.strip_prefix("http://") {
+        Some(rest) => rest,
+        None => err_at!(NotImplemented, msg: "only http:// urls, got {:?}", url)?,
+    };
+    let (authority, path) = match rest.find('/') {
+        Some(off) => (&rest[..off], &rest[off..]),
+        None => (rest, "/"),
+    };
+    // bracketed IPv6 literals, like [::1]:8080
+    let (host, port) = match authority.rfind(':') {
+        Some(off) if !authority[off..].contains(']') => {
+            let port = err_at!(BadInput, authority[off + 1..].parse::<u16>(), "{}", url)?;
+            (&authority[..off], port)
+        }
+        _ => (authority, 80),
+    };
+    if host.is_empty() {
+        err_at!(BadInput, msg: "missing host in {:?}", url)?
+    }
+    Ok((host.to_string(), port, path.to_string()))
+}
+
+fn connect(host: &str, port: u16, timeout: Duration) -> Result<TcpStream> {
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let addrs = err_at!(IOError, (host, port).to_socket_addrs(), "{}", host)?;
+
+    let mut last_err = None;
+    for addr in addrs {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(stream) => return Ok(stream),
+            Err(err) => last_err = Some(err),
+        }
+    }
+    match last_err {
+        Some(err) => err_at!(IOError, Err(err), "{}:{}", host, port),
+        None => err_at!(IOError, msg: "no address for {}", host),
+    }
+}
+
+fn parse_response(data: &[u8]) -> Result<HttpResponse> {
+    let off = match data.windows(4).position(|w| w == b"\r\n\r\n") {
+        Some(off) => off,
+        None => err_at!(DecodeError, msg: "incomplete http response")?,
+    };
+    let head = err_at!(DecodeError, str::from_utf8(&data[..off]))?;
+
+    let mut lines = head.split("\r\n");
+    // status line, like HTTP/1.1 200 OK
+    let status = match lines.next().map(|l| l.split(' ').collect::<Vec<&str>>()) {
+        Some(parts) if parts.len() >= 2 && parts[0].starts_with("HTTP/") => {
+            err_at!(DecodeError, parts[1].parse::<u16>(), "status")?
+        }
+        _ => err_at!(DecodeError, msg: "bad http status line")?,
+    };
+    let mut headers = vec![];
+    for line in lines {
+        match line.find(':') {
+            Some(n) => headers.push((
+                line[..n].trim().to_lowercase(),
+                line[n + 1..].trim().to_string(),
+            )),
+            None => err_at!(DecodeError, msg: "bad http header {:?}", line)?,
+        }
+    }
+
+    let body = data[off + 4..].to_vec();
+    Ok(HttpResponse {
+        status,
+        headers,
+        body,
+    })
+}
+
+#[cfg(test)]
+#[path = "trustless_test.rs"]
+mod trustless_test;
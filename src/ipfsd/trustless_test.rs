@@ -0,0 +1,178 @@
+use multibase::Base::Base32Lower;
+
+use std::{net::TcpListener, sync::Mutex, thread};
+
+use super::*;
+use crate::{multicodec, multihash::Multihash};
+
+// fake transport, serves `blocks` keyed on url, and records requests.
+struct FakeHttp {
+    blocks: Vec<(String, u16, Vec<u8>)>,
+    urls: Mutex<Vec<String>>,
+}
+
+impl HttpGet for FakeHttp {
+    fn get(&self, url: &str, accept: &str, _: usize, _: Duration) -> Result<HttpResponse> {
+        assert_eq!(accept, RAW_BLOCK);
+        self.urls.lock().unwrap().push(url.to_string());
+        for (u, status, body) in self.blocks.iter() {
+            if url.starts_with(u.as_str()) {
+                return Ok(HttpResponse {
+                    status: *status,
+                    headers: vec![("content-type".to_string(), RAW_BLOCK.to_string())],
+                    body: body.clone(),
+                });
+            }
+        }
+        err_at!(IOError, msg: "connection refused {}", url)
+    }
+}
+
+fn make_cid(data: &[u8]) -> Cid {
+    Cid::new_v1(Base32Lower, multicodec::RAW.into(), data).unwrap()
+}
+
+#[test]
+fn test_trustless_new() {
+    assert!(TrustlessClient::new(vec![]).is_err());
+    assert!(TrustlessClient::new(vec!["ftp://example.com".to_string()]).is_err());
+    let client = TrustlessClient::new(vec!["http://127.0.0.1:8080/".to_string()]).unwrap();
+    assert_eq!(
+        client.to_gateways(),
+        vec!["http://127.0.0.1:8080".to_string()]
+    );
+
+    assert_eq!(
+        parse_http_url("http://[::1]:8080/ipfs/x?format=raw").unwrap(),
+        ("[::1]".to_string(), 8080, "/ipfs/x?format=raw".to_string())
+    );
+    assert_eq!(
+        parse_http_url("http://example.com").unwrap(),
+        ("example.com".to_string(), 80, "/".to_string())
+    );
+    assert!(parse_http_url("https://example.com").is_err());
+    assert!(parse_http_url("http://example.com:http/").is_err());
+}
+
+#[test]
+fn test_trustless_fetch() {
+    let cid = make_cid(b"hello");
+    let http = Arc::new(FakeHttp {
+        blocks: vec![
+            ("http://bad".to_string(), 200, b"tampered".to_vec()),
+            ("http://empty".to_string(), 404, vec![]),
+            ("http://good".to_string(), 200, b"hello".to_vec()),
+        ],
+        urls: Mutex::new(vec![]),
+    });
+    let gateways = vec!["http://down", "http://bad", "http://good"];
+    let mut client =
+        TrustlessClient::new(gateways.into_iter().map(|s| s.to_string()).collect()).unwrap();
+    client.set_http(http.clone());
+
+    let block = client.get(&cid).unwrap().unwrap();
+    assert_eq!(block.as_block_data().unwrap(), b"hello");
+    assert!(block.to_cid().unwrap() == cid);
+
+    let text = cid.to_text(None).unwrap();
+    assert_eq!(
+        *http.urls.lock().unwrap(),
+        vec![
+            format!("http://down/ipfs/{}?format=raw", text),
+            format!("http://bad/ipfs/{}?format=raw", text),
+            format!("http://good/ipfs/{}?format=raw", text),
+        ]
+    );
+
+    // bad data is an error, not found is None.
+    let mut client = TrustlessClient::new(vec!["http://bad".to_string()]).unwrap();
+    client.set_http(http.clone());
+    assert!(client.fetch_block(&cid).is_err());
+    let mut client =
+        TrustlessClient::new(vec!["http://bad".to_string(), "http://empty".to_string()]).unwrap();
+    client.set_http(http.clone());
+    assert!(client.fetch_block(&cid).unwrap().is_none());
+
+    // identity cids are not fetched.
+    let mh = Multihash::new(multicodec::IDENTITY.into(), b"inline").unwrap();
+    let cid = Cid::from_raw(Base32Lower, multicodec::RAW.into(), mh);
+    let n = http.urls.lock().unwrap().len();
+    assert!(client.fetch_block(&cid).unwrap().is_some());
+    assert_eq!(http.urls.lock().unwrap().len(), n);
+}
+
+#[test]
+fn test_trustless_weak_cid() {
+    let http = Arc::new(FakeHttp {
+        blocks: vec![("http://good".to_string(), 200, b"hello".to_vec())],
+        urls: Mutex::new(vec![]),
+    });
+    let mut client = TrustlessClient::new(vec!["http://good".to_string()]).unwrap();
+    client.set_http(http.clone());
+
+    // truncated digest.
+    let digest = make_cid(b"hello").to_multihash().to_digest().unwrap();
+    let mh = Multihash::from_digest(multicodec::SHA2_256.into(), &digest[..8]).unwrap();
+    let cid = Cid::from_raw(Base32Lower, multicodec::RAW.into(), mh);
+    assert!(client.fetch_block(&cid).is_err());
+
+    // non-cryptographic hash.
+    let mh = Multihash::new(multicodec::MURMUR3_128.into(), b"hello").unwrap();
+    let cid = Cid::from_raw(Base32Lower, multicodec::RAW.into(), mh);
+    assert!(client.fetch_block(&cid).is_err());
+
+    // broken hash, denied by the strict policy.
+    let mh = Multihash::new(multicodec::SHA1.into(), b"hello").unwrap();
+    let cid = Cid::from_raw(Base32Lower, multicodec::RAW.into(), mh);
+    assert!(client.fetch_block(&cid).is_err());
+    assert!(http.urls.lock().unwrap().is_empty());
+
+    client.set_policy(SecurityPolicy::default());
+    assert!(client.fetch_block(&cid).unwrap().is_some());
+    assert_eq!(http.urls.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn test_tcp_http() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let handle = thread::spawn(move || {
+        let (mut conn, _) = listener.accept().unwrap();
+        let mut req = vec![0_u8; 1024];
+        let n = conn.read(&mut req).unwrap();
+        let req = String::from_utf8(req[..n].to_vec()).unwrap();
+        conn.write_all(b"HTTP/1.1 200 OK\r\nContent-Type: application/vnd.ipld.raw\r\n\r\nhello")
+            .unwrap();
+        req
+    });
+
+    let cid = make_cid(b"hello");
+    let client = TrustlessClient::new(vec![format!("http://127.0.0.1:{}", port)]).unwrap();
+    let block = client.fetch_block(&cid).unwrap().unwrap();
+    assert_eq!(block.as_block_data().unwrap(), b"hello");
+
+    let req = handle.join().unwrap();
+    let path = format!(
+        "GET /ipfs/{}?format=raw HTTP/1.0\r\n",
+        cid.to_text(None).unwrap()
+    );
+    assert!(req.starts_with(&path), "{}", req);
+    assert!(
+        req.contains("Accept: application/vnd.ipld.raw\r\n"),
+        "{}",
+        req
+    );
+}
+
+#[test]
+fn test_parse_response() {
+    let resp = parse_response(b"HTTP/1.1 404 Not Found\r\nX-Foo: bar\r\n\r\n").unwrap();
+    assert_eq!(resp.status, 404);
+    assert_eq!(resp.to_header("x-foo"), Some("bar"));
+    assert_eq!(resp.to_header("X-FOO"), Some("bar"));
+    assert!(resp.body.is_empty());
+
+    assert!(parse_response(b"HTTP/1.1 200 OK\r\n").is_err());
+    assert!(parse_response(b"SSH-2.0\r\n\r\n").is_err());
+}
@@ -5,14 +5,31 @@
 //!
 //! [cid]: https://github.com/multiformats/cid
 
-use std::{fmt, result};
+use bytes::Bytes;
+use multibase::Base;
 
-use crate::{cid::Cid, multihash::Multihash, Result};
+use std::{cmp, convert::TryFrom, fmt, result, thread};
+
+use crate::{
+    cid::Cid,
+    ipld::{
+        cbor::Cbor,
+        kind::{Basic, Node},
+        store::Store,
+    },
+    multicodec::{self, Multicodec},
+    multihash::Multihash,
+    Error, Result,
+};
 
 /// Block composed of Cid and opaque-data.
+///
+/// Opaque-data is held as reference counted [Bytes], cloning a block
+/// or its data shall not copy the payload.
+#[derive(Clone)]
 pub struct Block {
     cid: Cid,
-    data: Vec<u8>,
+    data: Bytes,
 }
 
 impl fmt::Display for Block {
@@ -23,19 +40,92 @@ impl fmt::Display for Block {
 
 impl From<(Cid, Vec<u8>)> for Block {
     fn from((cid, data): (Cid, Vec<u8>)) -> Self {
+        Self::new(cid, data.into())
+    }
+}
+
+impl From<(Cid, Bytes)> for Block {
+    fn from((cid, data): (Cid, Bytes)) -> Self {
         Self::new(cid, data)
     }
 }
 
 impl Block {
     /// New block from Cid and opaque-data.
-    pub fn new(cid: Cid, data: Vec<u8>) -> Self {
+    pub fn new(cid: Cid, data: Bytes) -> Self {
         Block { cid, data }
     }
 
+    /// Encode `node` as a dag-cbor block, in canonical form. Digest is
+    /// computed using `hash` algorithm and Cid is composed in version-1
+    /// format.
+    pub fn from_dag_cbor(hash: Multicodec, node: &dyn Node) -> Result<Block> {
+        let data = Cbor::try_from(node)?.canonical_bytes()?;
+        let mh = Multihash::new(hash, &data)?;
+        let cid = Cid::from_raw(Base::Base32Lower, multicodec::DAG_CBOR.into(), mh);
+        Ok(Block::new(cid, data.into()))
+    }
+
+    /// Create a block for each `(content_type, data)` item, hashing the
+    /// payloads in parallel using a pool of `workers` threads. Digest is
+    /// computed using `hash` algorithm and Cid is composed in version-1
+    /// format. Returned blocks are in the same order as `items`.
+    pub fn hash_many<I>(hash: Multicodec, items: I, workers: usize) -> Result<Vec<Block>>
+    where
+        I: Iterator<Item = (Multicodec, Bytes)>,
+    {
+        use multibase::Base::Base32Lower;
+
+        let items: Vec<(Multicodec, Bytes)> = items.collect();
+        if items.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let workers = cmp::max(1, cmp::min(workers, items.len()));
+        let chunk_size = (items.len() + workers - 1) / workers;
+
+        let mut handles = vec![];
+        for chunk in items.chunks(chunk_size) {
+            let chunk = chunk.to_vec();
+            handles.push(thread::spawn(move || -> Result<Vec<Block>> {
+                let mut blocks = vec![];
+                for (content_type, data) in chunk.into_iter() {
+                    let mh = Multihash::new(hash, &data)?;
+                    let cid = Cid::from_raw(Base32Lower, content_type, mh);
+                    blocks.push(Block::new(cid, data));
+                }
+                Ok(blocks)
+            }));
+        }
+
+        let mut blocks = Vec::with_capacity(items.len());
+        for handle in handles.into_iter() {
+            match handle.join() {
+                Ok(res) => blocks.extend(res?),
+                Err(err) => err_at!(ThreadFail, msg: "{:?}", err)?,
+            }
+        }
+
+        Ok(blocks)
+    }
+
+    /// Return the block inlined in an identity `cid`, None if `cid` is
+    /// not using identity hash. Such blocks are never stored, their data
+    /// is the digest itself.
+    pub fn from_identity(cid: &Cid) -> Result<Option<Block>> {
+        let mh = cid.to_multihash();
+        match mh.to_codec()?.to_code() {
+            multicodec::IDENTITY => {
+                let data = mh.to_digest()?;
+                Ok(Some(Block::new(cid.clone(), data.into())))
+            }
+            _ => Ok(None),
+        }
+    }
+
     /// Return the underlying opaque-data.
     pub fn to_block_data(&self) -> Result<Vec<u8>> {
-        Ok(self.data.clone())
+        Ok(self.data.to_vec())
     }
 
     /// Return reference to underlying opaque-data.
@@ -43,6 +133,12 @@ impl Block {
         Ok(&self.data)
     }
 
+    /// Return a reference counted handle to the underlying opaque-data,
+    /// without copying the payload.
+    pub fn to_bytes(&self) -> Bytes {
+        self.data.clone()
+    }
+
     /// Return the Cid for this block.
     pub fn to_cid(&self) -> Result<Cid> {
         Ok(self.cid.clone())
@@ -52,6 +148,41 @@ impl Block {
     pub fn to_multihash(&self) -> Result<Multihash> {
         Ok(self.cid.to_multihash())
     }
+
+    /// Return the Cids linked from this block. Supported for dag-pb,
+    /// dag-cbor and raw blocks.
+    pub fn to_links(&self) -> Result<Vec<Cid>> {
+        use crate::ipld::dag_pb;
+
+        match self.cid.to_content_type().to_code() {
+            multicodec::DAG_PB => {
+                let links = dag_pb::decode_links(&self.data)?;
+                Ok(links.into_iter().map(|l| l.to_cid()).collect())
+            }
+            multicodec::DAG_CBOR => {
+                let mut links = vec![];
+                Cbor::decode(&mut self.data.as_ref())?.collect_links(&mut links);
+                Ok(links)
+            }
+            multicodec::RAW => Ok(vec![]),
+            _ => err_at!(NotImplemented, msg: "links for {}", self.cid.to_content_type()),
+        }
+    }
+
+    /// Decode block into data-model. Raw blocks are decoded as Bytes
+    /// kind, they are leaves and can't be traversed further.
+    pub fn to_node(&self) -> Result<Basic> {
+        use crate::ipld::{json, messagepack};
+
+        match self.cid.to_content_type().to_code() {
+            multicodec::RAW => Ok(Basic::Bytes(self.data.to_vec())),
+            multicodec::DAG_CBOR => Basic::try_from(Cbor::decode(&mut self.data.as_ref())?),
+            multicodec::JSON => json::decode(&self.data),
+            multicodec::MESSAGEPACK => messagepack::decode(&self.data),
+            _ => err_at!(NotImplemented, msg: "decode {}", self.cid.to_content_type()),
+        }
+    }
+
     /// Verify whether the multihash in Cid, matches with the block's
     /// opaque data.
     pub fn verify(&self) -> Result<bool> {
@@ -59,7 +190,19 @@ impl Block {
             Cid::Zero(mh) => mh,
             Cid::One(_, _, mh) => mh,
         };
-        let computed_mh = Multihash::new(mh.to_codec()?, &self.data)?;
-        Ok(mh == &computed_mh)
+        mh.verify(&self.data)
     }
 }
+
+/// Fetch the block for `cid` from `store` and decode it into data-model,
+/// fail if the block is missing.
+pub fn get_node(cid: &Cid, store: &dyn Store) -> Result<Basic> {
+    match store.get(cid)? {
+        Some(block) => block.to_node(),
+        None => err_at!(Invalid, msg: "block {} not in store", cid.to_text(None)?),
+    }
+}
+
+#[cfg(test)]
+#[path = "block_test.rs"]
+mod block_test;
@@ -0,0 +1,115 @@
+use multibase::Base::Base32Lower;
+
+use super::*;
+use crate::ipld::{
+    kind::Node,
+    store::{MemStore, Store},
+};
+
+#[test]
+fn test_block_identity() {
+    let mh = Multihash::new(multicodec::IDENTITY.into(), b"hello").unwrap();
+    let cid = Cid::from_raw(Base32Lower, multicodec::RAW.into(), mh);
+
+    let block = Block::from_identity(&cid).unwrap().unwrap();
+    assert_eq!(block.as_block_data().unwrap(), b"hello");
+    assert!(block.verify().unwrap());
+
+    // identity cids are resolved without touching the store.
+    let store = MemStore::new();
+    let block = store.get(&cid).unwrap().unwrap();
+    assert_eq!(block.as_block_data().unwrap(), b"hello");
+    assert_eq!(block.to_node().unwrap().as_bytes(), Some(b"hello".as_ref()));
+
+    let mh = Multihash::new(multicodec::SHA2_256.into(), b"hello").unwrap();
+    let cid = Cid::from_raw(Base32Lower, multicodec::RAW.into(), mh);
+    assert!(Block::from_identity(&cid).unwrap().is_none());
+    assert!(store.get(&cid).unwrap().is_none());
+}
+
+#[test]
+fn test_block_to_node() {
+    let block = {
+        let mh = Multihash::new(multicodec::SHA2_256.into(), b"raw data").unwrap();
+        let cid = Cid::from_raw(Base32Lower, multicodec::RAW.into(), mh);
+        Block::new(cid, b"raw data".to_vec().into())
+    };
+    let node = block.to_node().unwrap();
+    assert_eq!(node.as_bytes(), Some(b"raw data".as_ref()));
+    assert_eq!(block.to_links().unwrap().len(), 0);
+
+    let block = {
+        let data = b"{\"a\":1}".to_vec();
+        let mh = Multihash::new(multicodec::SHA2_256.into(), &data).unwrap();
+        let cid = Cid::from_raw(Base32Lower, multicodec::JSON.into(), mh);
+        Block::new(cid, data.into())
+    };
+    let node = block.to_node().unwrap();
+    assert_eq!(node.len(), Some(1));
+}
+
+#[test]
+fn test_block_zero_copy() {
+    let data = Bytes::from(b"hello world".to_vec());
+    let cid = Cid::new_v1(Base32Lower, multicodec::RAW.into(), &data).unwrap();
+    let block = Block::new(cid, data.clone());
+
+    // cloning the block, or its payload, shares the same buffer.
+    let ptr = data.as_ptr();
+    assert_eq!(block.as_block_data().unwrap().as_ptr(), ptr);
+    assert_eq!(block.clone().as_block_data().unwrap().as_ptr(), ptr);
+    assert_eq!(block.to_bytes().as_ptr(), ptr);
+}
+
+#[test]
+fn test_block_hash_many() {
+    let items: Vec<(Multicodec, Bytes)> = (0..37)
+        .map(|i| {
+            let content_type = match i % 2 {
+                0 => multicodec::RAW.into(),
+                _ => multicodec::DAG_CBOR.into(),
+            };
+            (content_type, Bytes::from(vec![i as u8; i * 100]))
+        })
+        .collect();
+
+    for hash in [multicodec::SHA2_256, multicodec::BLAKE3].iter() {
+        let hash: Multicodec = (*hash).into();
+        // hash each block on its own.
+        let refs: Vec<Cid> = items
+            .iter()
+            .map(|(content_type, data)| {
+                let mh = Multihash::new(hash, data).unwrap();
+                Cid::from_raw(Base32Lower, *content_type, mh)
+            })
+            .collect();
+
+        for workers in [0, 1, 4, 64].iter() {
+            let blocks = Block::hash_many(hash, items.clone().into_iter(), *workers).unwrap();
+            assert_eq!(blocks.len(), items.len());
+            for (i, block) in blocks.iter().enumerate() {
+                assert!(block.to_cid().unwrap() == refs[i], "{} {}", workers, i);
+                assert_eq!(block.as_block_data().unwrap().as_ptr(), items[i].1.as_ptr());
+                assert!(block.verify().unwrap());
+            }
+        }
+    }
+
+    let blocks = Block::hash_many(multicodec::SHA2_256.into(), vec![].into_iter(), 4).unwrap();
+    assert!(blocks.is_empty());
+}
+
+#[test]
+fn test_block_dag_cbor() {
+    let node = Basic::Text(b"hello".to_vec());
+    let block = Block::from_dag_cbor(multicodec::SHA2_256.into(), &node).unwrap();
+    let cid = block.to_cid().unwrap();
+    assert_eq!(cid.to_content_type().to_code(), multicodec::DAG_CBOR);
+    assert!(block.verify().unwrap());
+
+    let mut store = MemStore::new();
+    assert!(get_node(&cid, &store).is_err());
+    store.put(block).unwrap();
+    let val = get_node(&cid, &store).unwrap();
+    assert_eq!(val.as_string().unwrap().unwrap(), "hello");
+}
@@ -0,0 +1,350 @@
+//! Module implement CARv1 archive, refer [car spec] for details.
+//!
+//! [Writer] streams a DAG into an archive, [Reader] streams blocks out
+//! of an archive verifying each block against its CID, and [import]
+//! feeds those blocks into a block sink. [export] walks a DAG in a
+//! [Store] and writes it out as an archive. Blocks whose hash function or
+//! codec is denied by the reader's [SecurityPolicy] are rejected, refer
+//! [Reader::set_policy].
+//!
+//! [car spec]: https://ipld.io/specs/transport/car/carv1/
+
+use bytes::Bytes;
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    convert::{TryFrom, TryInto},
+    io,
+};
+
+use crate::{
+    cid::Cid,
+    ipfsd::SecurityPolicy,
+    ipld::{
+        block::Block,
+        cbor::{Cbor, Info, Tag, MAX_BLOCK_SIZE, TAG_IPLD_CID},
+        kind::Basic,
+        store::Store,
+        typed,
+    },
+    util, Error, Result,
+};
+
+/// CAR format version implemented by this module.
+pub const VERSION: u64 = 1;
+
+/// Streaming writer for CARv1 archives. Header is written when the
+/// writer is created, blocks are written as and when they are supplied.
+pub struct Writer<W>
+where
+    W: io::Write,
+{
+    w: W,
+    n: usize,
+}
+
+impl<W> Writer<W>
+where
+    W: io::Write,
+{
+    /// Create a new archive with `roots`, writing to `w`.
+    pub fn new(mut w: W, roots: &[Cid]) -> Result<Writer<W>> {
+        let header = encode_header(roots)?;
+        let n = write_frame(&mut w, &[&header])?;
+        Ok(Writer { w, n })
+    }
+
+    /// Append block to the archive, return the number of bytes written.
+    pub fn write_block(&mut self, block: &Block) -> Result<usize> {
+        let cid = block.to_cid()?.encode()?;
+        let n = write_frame(&mut self.w, &[&cid, block.as_block_data()?])?;
+        self.n += n;
+        Ok(n)
+    }
+
+    /// Return the total number of bytes written so far.
+    pub fn to_size(&self) -> usize {
+        self.n
+    }
+
+    /// Flush and return the underlying writer.
+    pub fn into_inner(mut self) -> Result<W> {
+        err_at!(IOError, self.w.flush())?;
+        Ok(self.w)
+    }
+}
+
+/// Streaming reader for CARv1 archives. Header is read when the reader
+/// is created, blocks are read as the reader is iterated. Each block is
+/// verified against its CID, iteration yields an error and stops on the
+/// first bad frame, mismatching block or block denied by policy.
+pub struct Reader<R>
+where
+    R: io::Read,
+{
+    r: R,
+    roots: Vec<Cid>,
+    policy: SecurityPolicy,
+    off: u64,
+    done: bool,
+}
+
+impl<R> Reader<R>
+where
+    R: io::Read,
+{
+    /// Open archive from `r`, reading its header.
+    pub fn new(mut r: R) -> Result<Reader<R>> {
+        let header = util::read_lpm_max(&mut r, MAX_HEADER_SIZE)?;
+        let roots = decode_header(&header)?;
+        let off = (prefix_len(header.len()) + header.len()) as u64;
+        Ok(Reader {
+            r,
+            roots,
+            policy: SecurityPolicy::default(),
+            off,
+            done: false,
+        })
+    }
+
+    /// Resume reading an archive whose header was read earlier. `r` must
+    /// be positioned at `off`, as returned by [Reader::to_offset], and
+    /// `roots` are the archive's roots.
+    pub fn resume(r: R, roots: Vec<Cid>, off: u64) -> Reader<R> {
+        Reader {
+            r,
+            roots,
+            policy: SecurityPolicy::default(),
+            off,
+            done: false,
+        }
+    }
+
+    /// Set the policy for blocks read from the archive, default accepts
+    /// all. Policy is checked before verifying the block's data.
+    pub fn set_policy(&mut self, policy: SecurityPolicy) -> &mut Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Return the roots listed in the archive's header.
+    pub fn to_roots(&self) -> Vec<Cid> {
+        self.roots.clone()
+    }
+
+    /// Return the offset, in bytes, of the next block in the archive.
+    /// Offset after the last good block can be used to resume an
+    /// interrupted read.
+    pub fn to_offset(&self) -> u64 {
+        self.off
+    }
+
+    fn read_block(&mut self) -> Result<Option<Block>> {
+        let mut first = [0_u8; 1];
+        if err_at!(IOError, self.r.read(&mut first))? == 0 {
+            return Ok(None);
+        }
+
+        let mut r = io::Read::chain(&first[..], &mut self.r);
+        let frame = Bytes::from(util::read_lpm_max(&mut r, MAX_FRAME_SIZE)?);
+        let (cid, data) = Cid::decode(&frame)?;
+        self.policy.check_cid(&cid)?;
+        // block data shares the frame's buffer.
+        let block = Block::new(cid.clone(), frame.slice_ref(data));
+        if !block.verify()? {
+            err_at!(HashFail, msg: "block {} doesn't match its data", cid.to_text(None)?)?
+        }
+
+        self.off += (prefix_len(frame.len()) + frame.len()) as u64;
+        Ok(Some(block))
+    }
+}
+
+impl<R> Iterator for Reader<R>
+where
+    R: io::Read,
+{
+    type Item = Result<Block>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.read_block() {
+            Ok(Some(block)) => Some(Ok(block)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// Outcome of [import].
+#[derive(Clone, Debug)]
+pub struct Import {
+    /// Roots from archive header, along with whether the root block was
+    /// found in the archive.
+    pub roots: Vec<(Cid, bool)>,
+    /// Number of blocks imported.
+    pub n_blocks: usize,
+    /// Offset in archive, after the last imported block.
+    pub off: u64,
+}
+
+/// Read all blocks from `reader` and hand them over to `put`, typically
+/// a closure that adds the block to a blockstore. On error, blocks
+/// handed over so far remain valid, and the import can be resumed from
+/// [Reader::to_offset]. To import into a node and pin the roots, refer
+/// [Dag::import][crate::ipfsd::Dag::import].
+pub fn import<R, F>(reader: &mut Reader<R>, mut put: F) -> Result<Import>
+where
+    R: io::Read,
+    F: FnMut(Block) -> Result<()>,
+{
+    let mut roots = vec![];
+    for root in reader.to_roots().into_iter() {
+        roots.push((root.to_multihash().encode()?, root));
+    }
+    let mut found = vec![false; roots.len()];
+
+    let mut n_blocks = 0;
+    for block in &mut *reader {
+        let block = block?;
+        let key = block.to_multihash()?.encode()?;
+        for (i, (root, _)) in roots.iter().enumerate() {
+            found[i] = found[i] || root == &key;
+        }
+        put(block)?;
+        n_blocks += 1;
+    }
+
+    let val = Import {
+        roots: roots.into_iter().map(|(_, cid)| cid).zip(found).collect(),
+        n_blocks,
+        off: reader.to_offset(),
+    };
+    Ok(val)
+}
+
+/// Outcome of [export].
+#[derive(Clone, Debug)]
+pub struct Export {
+    /// Number of blocks written to the archive.
+    pub n_blocks: usize,
+    /// Size of the archive in bytes, including the header.
+    pub size: usize,
+}
+
+/// Write the DAG under `root`, from `store`, as an archive into `w`, with
+/// `root` as its only root. Blocks are written in depth-first order,
+/// starting from `root`, and each block is written once even if it is
+/// linked more than once. Fail if any block of the DAG is missing.
+pub fn export<W>(root: &Cid, store: &dyn Store, w: W) -> Result<Export>
+where
+    W: io::Write,
+{
+    let mut writer = Writer::new(w, &[root.clone()])?;
+    let n_blocks = write_dag(&mut writer, root, store, &mut HashSet::new(), true)?;
+
+    let size = writer.to_size();
+    writer.into_inner()?;
+    Ok(Export { n_blocks, size })
+}
+
+/// Write blocks of the DAG under `root`, from `store`, into `writer` in
+/// depth-first order, return the number of blocks written. Blocks whose
+/// multihash is in `visited` are skipped, and written blocks are added
+/// to it, so that a block shared by several DAGs of an archive is written
+/// once. If `recursive` is false only the `root` block is written. Fail
+/// if any block is missing.
+pub fn write_dag<W>(
+    writer: &mut Writer<W>,
+    root: &Cid,
+    store: &dyn Store,
+    visited: &mut HashSet<Vec<u8>>,
+    recursive: bool,
+) -> Result<usize>
+where
+    W: io::Write,
+{
+    let mut n_blocks = 0;
+
+    let mut stack = vec![root.clone()];
+    while let Some(cid) = stack.pop() {
+        if !visited.insert(cid.to_multihash().encode()?) {
+            continue;
+        }
+        let block = match store.get(&cid)? {
+            Some(block) => block,
+            None => err_at!(Invalid, msg: "export missing block {}", cid.to_text(None)?)?,
+        };
+        writer.write_block(&block)?;
+        n_blocks += 1;
+        if recursive {
+            // first link is visited first.
+            stack.extend(block.to_links()?.into_iter().rev());
+        }
+    }
+
+    Ok(n_blocks)
+}
+
+/// Encode CARv1 header, as DAG-CBOR `{roots: [&Any], version: 1}`.
+pub fn encode_header(roots: &[Cid]) -> Result<Vec<u8>> {
+    let roots: Vec<Cbor> = roots
+        .iter()
+        .map(|cid| Cbor::Major6(TAG_IPLD_CID.into(), Tag::Link(cid.clone())))
+        .collect();
+
+    let mut dict = BTreeMap::new();
+    let n: u64 = err_at!(FailConvert, roots.len().try_into())?;
+    dict.insert("roots".to_string(), Cbor::Major4(n.into(), roots));
+    dict.insert("version".to_string(), Cbor::Major0(VERSION.into(), VERSION));
+
+    Cbor::Major5(Info::from(2_u64), dict).canonical_bytes()
+}
+
+/// Decode CARv1 header, return the list of roots.
+pub fn decode_header(data: &[u8]) -> Result<Vec<Cid>> {
+    let header = Basic::try_from(Cbor::decode(&mut &data[..])?)?;
+    match typed::get_field::<u64>(&header, "version")? {
+        VERSION => (),
+        version => err_at!(DecodeError, msg: "unsupported car version {}", version)?,
+    }
+    typed::get_field(&header, "roots")
+}
+
+// upper limits on the size of header and of a block frame, cid + data.
+const MAX_HEADER_SIZE: usize = 1024 * 1024;
+const MAX_FRAME_SIZE: usize = (MAX_BLOCK_SIZE as usize) + 1024;
+
+fn prefix_len(n: usize) -> usize {
+    let mut scratch = [0_u8; 10];
+    unsigned_varint::encode::usize(n, &mut scratch).len()
+}
+
+// write a length prefixed frame, made up of one or more `parts`.
+fn write_frame<W: io::Write>(w: &mut W, parts: &[&[u8]]) -> Result<usize> {
+    use unsigned_varint::encode as uve;
+
+    let len: usize = parts.iter().map(|p| p.len()).sum();
+
+    let mut scratch = [0_u8; 10];
+    let prefix = uve::usize(len, &mut scratch);
+    err_at!(IOError, w.write_all(prefix))?;
+    for part in parts.iter() {
+        err_at!(IOError, w.write_all(part))?;
+    }
+
+    Ok(prefix.len() + len)
+}
+
+#[cfg(test)]
+#[path = "car_test.rs"]
+mod car_test;
@@ -0,0 +1,186 @@
+use data_encoding::HEXLOWER;
+
+use super::*;
+
+#[test]
+fn test_car_writer() {
+    // raw block, sha2-256 of the data is a948904f...a192a447.
+    let cid: Cid = "bafkreifjjcie6lypi6ny7amxnfftagclbuxndqonfipmb64f2km2devei4"
+        .parse()
+        .unwrap();
+    let block = Block::new(cid.clone(), b"hello world\n".to_vec().into());
+    assert!(block.verify().unwrap());
+
+    let header = encode_header(&[cid.clone()]).unwrap();
+    let ref_header = concat!(
+        "a2",
+        "65726f6f7473", // "roots"
+        "81d82a5825",
+        "00",
+        "01551220a948904f2f0f479b8f8197694b30184b0d2ed1c1cd2a1ec0fb85d299a192a447",
+        "6776657273696f6e", // "version"
+        "01",
+    );
+    assert_eq!(HEXLOWER.encode(&header), ref_header);
+
+    let mut w = Writer::new(vec![], &[cid.clone()]).unwrap();
+    let n = w.write_block(&block).unwrap();
+    assert_eq!(n, 1 + 36 + 12);
+    assert_eq!(w.to_size(), 1 + header.len() + n);
+
+    let buf = w.into_inner().unwrap();
+    assert_eq!(buf[0] as usize, header.len());
+    assert_eq!(&buf[1..(1 + header.len())], header.as_slice());
+
+    let frame = &buf[(1 + header.len())..];
+    assert_eq!(frame[0] as usize, 36 + 12);
+    assert_eq!(&frame[1..37], cid.encode().unwrap().as_slice());
+    assert_eq!(&frame[37..], b"hello world\n");
+
+    let blocks: Vec<Block> = Reader::new(buf.as_slice())
+        .unwrap()
+        .map(|b| b.unwrap())
+        .collect();
+    assert_eq!(blocks.len(), 1);
+    assert!(blocks[0].to_cid().unwrap() == cid);
+}
+
+#[test]
+fn test_car_reader() {
+    use crate::{ipld::store::MemStore, multicodec};
+    use multibase::Base;
+
+    let blocks: Vec<Block> = ["hello", "world", "ciao"]
+        .iter()
+        .map(|s| {
+            let cid = Cid::new_v1(Base::Base32Lower, multicodec::RAW.into(), s.as_bytes());
+            Block::new(cid.unwrap(), s.as_bytes().to_vec().into())
+        })
+        .collect();
+    let roots = vec![blocks[2].to_cid().unwrap(), blocks[0].to_cid().unwrap()];
+    let missing = Cid::new_v0(b"missing").unwrap();
+
+    let mut w = Writer::new(vec![], &[roots[0].clone(), missing.clone()]).unwrap();
+    for block in blocks.iter() {
+        w.write_block(block).unwrap();
+    }
+    let buf = w.into_inner().unwrap();
+
+    let mut reader = Reader::new(buf.as_slice()).unwrap();
+    assert_eq!(reader.to_roots(), vec![roots[0].clone(), missing.clone()]);
+    let mut store = MemStore::new();
+    let res = import(&mut reader, |block| store.put(block)).unwrap();
+    assert_eq!(res.n_blocks, 3);
+    assert_eq!(res.off, buf.len() as u64);
+    assert_eq!(res.roots, vec![(roots[0].clone(), true), (missing, false)]);
+    assert_eq!(store.len(), 3);
+
+    // resume after the first block.
+    let mut reader = Reader::new(buf.as_slice()).unwrap();
+    reader.next().unwrap().unwrap();
+    let off = reader.to_offset() as usize;
+    let reader = Reader::resume(&buf[off..], reader.to_roots(), off as u64);
+    let cids: Vec<Cid> = reader.map(|b| b.unwrap().to_cid().unwrap()).collect();
+    assert_eq!(cids, vec![blocks[1].to_cid().unwrap(), roots[0].clone()]);
+
+    // corrupt the data of last block.
+    let mut bad = buf.clone();
+    *bad.last_mut().unwrap() ^= 0xff;
+    let res: Vec<Result<Block>> = Reader::new(bad.as_slice()).unwrap().collect();
+    assert_eq!(res.len(), 3);
+    assert!(res[0].is_ok() && res[1].is_ok() && res[2].is_err());
+}
+
+#[test]
+fn test_car_policy() {
+    use crate::{ipld::store::MemStore, multicodec, multihash::Multihash};
+    use multibase::Base;
+
+    let hash = Multihash::new(multicodec::SHA1.into(), b"hello").unwrap();
+    let weak = Cid::from_raw(Base::Base32Lower, multicodec::RAW.into(), hash);
+    let cid = Cid::new_v1(Base::Base32Lower, multicodec::DAG_CBOR.into(), b"\x01").unwrap();
+    let blocks = vec![
+        Block::new(weak.clone(), b"hello".to_vec().into()),
+        Block::new(cid.clone(), b"\x01".to_vec().into()),
+    ];
+
+    let mut w = Writer::new(vec![], &[weak]).unwrap();
+    for block in blocks.iter() {
+        w.write_block(block).unwrap();
+    }
+    let buf = w.into_inner().unwrap();
+
+    // default policy accepts all.
+    let mut reader = Reader::new(buf.as_slice()).unwrap();
+    let mut store = MemStore::new();
+    assert_eq!(import(&mut reader, |b| store.put(b)).unwrap().n_blocks, 2);
+
+    // denied hash stops the import at the first block.
+    let mut reader = Reader::new(buf.as_slice()).unwrap();
+    reader.set_policy(SecurityPolicy::strict());
+    let mut store = MemStore::new();
+    let msg = import(&mut reader, |b| store.put(b))
+        .unwrap_err()
+        .to_string();
+    assert!(msg.contains("sha1"), "{}", msg);
+    assert_eq!(store.len(), 0);
+
+    // denied codec, blocks before it are imported.
+    let mut policy = SecurityPolicy::default();
+    policy.deny_codec(multicodec::DAG_CBOR);
+    let mut reader = Reader::new(buf.as_slice()).unwrap();
+    reader.set_policy(policy);
+    let mut store = MemStore::new();
+    let msg = import(&mut reader, |b| store.put(b))
+        .unwrap_err()
+        .to_string();
+    assert!(msg.contains("dag-cbor"), "{}", msg);
+    assert_eq!(store.len(), 1);
+    assert_eq!(reader.to_offset() as usize, buf.len() - (1 + 36 + 1));
+}
+
+#[test]
+fn test_car_export() {
+    use crate::{
+        ipld::store::MemStore,
+        unixfs::{importer::Options, test_util},
+    };
+    use std::collections::BTreeSet;
+
+    // leaf "abcd" is linked thrice from the root.
+    let mut store = MemStore::new();
+    let data = b"abcdabcdabcdxyz";
+    let (root, blocks) = test_util::import_into(Options::default(), data, &mut store);
+    assert_eq!(store.len(), 3);
+
+    let mut buf = vec![];
+    let res = export(&root, &store, &mut buf).unwrap();
+    assert_eq!(res.n_blocks, 3);
+    assert_eq!(res.size, buf.len());
+
+    // re-import, same roots and same set of blocks, each block once,
+    // root first.
+    let mut reader = Reader::new(buf.as_slice()).unwrap();
+    let mut cids = vec![];
+    let mut other = MemStore::new();
+    let res = import(&mut reader, |block| {
+        cids.push(block.to_cid()?.to_text(None)?);
+        other.put(block)
+    })
+    .unwrap();
+    assert_eq!(res.roots, vec![(root.clone(), true)]);
+    assert_eq!(cids[0], root.to_text(None).unwrap());
+
+    let set: BTreeSet<String> = cids.iter().cloned().collect();
+    assert_eq!(set.len(), cids.len());
+    let ref_set: BTreeSet<String> = blocks
+        .iter()
+        .map(|b| b.to_cid().unwrap().to_text(None).unwrap())
+        .collect();
+    assert_eq!(set, ref_set);
+
+    // missing block fails the export.
+    let mut partial = MemStore::new();
+    partial.put(blocks.last().unwrap().clone()).unwrap();
+    assert!(export(&root, &partial, vec![]).is_err());
+}
@@ -14,6 +14,10 @@ pub const TAG_IPLD_CID: u64 = 42;
 /// Recursion limit for nested Cbor objects.
 pub const RECURSION_LIMIT: u32 = 1000;
 
+/// Default upper limit on the size of an encoded Cbor value, 2MiB, same
+/// as the maximum block size accepted by go-ipfs.
+pub const MAX_BLOCK_SIZE: u64 = 2 * 1024 * 1024;
+
 /// Cbor type, sole purpose is to correspond with [Basic] data-model.
 #[derive(Clone)]
 pub enum Cbor {
@@ -42,15 +46,19 @@ impl TryFrom<&dyn Node> for Cbor {
             },
             Integer => match node.to_integer().unwrap() {
                 num if num >= 0 => {
-                    let num: u64 = err_at!(FailConvert, num.try_into())?;
+                    let num: u64 = err_at!(FailConvert, num.try_into(), "integer {}", num)?;
                     Major0(num.into(), num)
                 }
+                // major type 1 holds -1-n, hence -(2^64) is the smallest.
                 num => {
-                    let num: u64 = err_at!(FailConvert, u64::try_from(i128::abs(num)))? - 1;
-                    Major1(num.into(), num)
+                    let n: u64 = err_at!(FailConvert, (-1 - num).try_into(), "integer {}", num)?;
+                    Major1(n.into(), n)
                 }
             },
-            Float => Cbor::try_from(SimpleValue::F64(node.to_float().unwrap()))?,
+            Float => match node.to_float().unwrap() {
+                num if num.is_finite() => Cbor::try_from(SimpleValue::F64(num))?,
+                num => err_at!(FailConvert, msg: "float {} not allowed in dag-cbor", num)?,
+            },
             Bytes => {
                 let byts = node.as_bytes().unwrap().to_vec();
                 let n: u64 = err_at!(FailConvert, byts.len().try_into())?;
@@ -93,64 +101,83 @@ impl TryFrom<&dyn Node> for Cbor {
 }
 
 impl Cbor {
-    /// Serialize this cbor value.
+    /// Serialize this cbor value. Headers are always encoded in their
+    /// shortest form, irrespective of the [Info] held by the value, and map
+    /// keys are sorted length-first as required by DAG-CBOR.
     pub fn encode(&self, buf: &mut Vec<u8>) -> Result<usize> {
         self.do_encode(buf, 1)
     }
 
+    /// Return the canonical DAG-CBOR serialization of this value. Output
+    /// is stable across releases, refer [Cbor::encode] for the rules.
+    pub fn canonical_bytes(&self) -> Result<Vec<u8>> {
+        let mut buf = vec![];
+        self.encode(&mut buf)?;
+        Ok(buf)
+    }
+
     fn do_encode(&self, buf: &mut Vec<u8>, depth: u32) -> Result<usize> {
         if depth > RECURSION_LIMIT {
             return err_at!(FailCbor, msg: "encode recursion limit exceeded");
         }
 
         match self {
-            Cbor::Major0(info, num) => {
-                let n = encode_hdr(Major::M0, *info, buf)?;
+            Cbor::Major0(_, num) => {
+                let n = encode_hdr(Major::M0, (*num).into(), buf)?;
                 Ok(n + encode_addnl(*num, buf)?)
             }
-            Cbor::Major1(info, num) => {
-                let n = encode_hdr(Major::M1, *info, buf)?;
-                Ok(n + encode_addnl(*num - 1, buf)?)
+            Cbor::Major1(_, num) => {
+                let n = encode_hdr(Major::M1, (*num).into(), buf)?;
+                Ok(n + encode_addnl(*num, buf)?)
             }
-            Cbor::Major2(info, byts) => {
-                let n = encode_hdr(Major::M2, *info, buf)?;
-                let m = encode_addnl(byts.len().try_into().unwrap(), buf)?;
-                buf.copy_from_slice(&byts);
+            Cbor::Major2(_, byts) => {
+                let len: u64 = err_at!(FailCbor, byts.len().try_into())?;
+                let n = encode_hdr(Major::M2, len.into(), buf)?;
+                let m = encode_addnl(len, buf)?;
+                buf.extend_from_slice(&byts);
                 Ok(n + m + byts.len())
             }
-            Cbor::Major3(info, text) => {
-                let n = encode_hdr(Major::M3, *info, buf)?;
-                let m = encode_addnl(text.len().try_into().unwrap(), buf)?;
-                buf.copy_from_slice(text);
+            Cbor::Major3(_, text) => {
+                let len: u64 = err_at!(FailCbor, text.len().try_into())?;
+                let n = encode_hdr(Major::M3, len.into(), buf)?;
+                let m = encode_addnl(len, buf)?;
+                buf.extend_from_slice(text);
                 Ok(n + m + text.len())
             }
-            Cbor::Major4(info, list) => {
-                let n = encode_hdr(Major::M4, *info, buf)?;
-                let m = encode_addnl(list.len().try_into().unwrap(), buf)?;
+            Cbor::Major4(_, list) => {
+                let len: u64 = err_at!(FailCbor, list.len().try_into())?;
+                let n = encode_hdr(Major::M4, len.into(), buf)?;
+                let m = encode_addnl(len, buf)?;
                 let mut acc = 0;
                 for x in list {
                     acc += x.do_encode(buf, depth + 1)?;
                 }
                 Ok(n + m + acc)
             }
-            Cbor::Major5(info, dict) => {
-                let n = encode_hdr(Major::M5, *info, buf)?;
-                let m = encode_addnl(dict.len().try_into().unwrap(), buf)?;
+            Cbor::Major5(_, dict) => {
+                let len: u64 = err_at!(FailCbor, dict.len().try_into())?;
+                let n = encode_hdr(Major::M5, len.into(), buf)?;
+                let m = encode_addnl(len, buf)?;
+                // DAG-CBOR sorts keys by length first and then bytewise.
+                let mut keys: Vec<&String> = dict.keys().collect();
+                keys.sort_by(|a, b| a.len().cmp(&b.len()).then(a.cmp(b)));
+
                 let mut acc = 0;
-                for (key, val) in dict.iter() {
+                for key in keys.into_iter() {
                     let info: Info = {
-                        let num: u64 = key.len().try_into().unwrap();
+                        let num: u64 = err_at!(FailCbor, key.len().try_into())?;
                         num.into()
                     };
                     acc += Cbor::Major3(info, key.as_bytes().to_vec()).encode(buf)?;
-                    acc += val.do_encode(buf, depth + 1)?;
+                    acc += dict[key].do_encode(buf, depth + 1)?;
                 }
                 Ok(n + m + acc)
             }
-            Cbor::Major6(info, tagg) => {
-                let n = encode_hdr(Major::M6, *info, buf)?;
-                let m = tagg.encode(buf)?;
-                Ok(n + m)
+            Cbor::Major6(_, tagg) => {
+                let num = u64::from(tagg.clone());
+                let n = encode_hdr(Major::M6, num.into(), buf)?;
+                let m = encode_addnl(num, buf)?;
+                Ok(n + m + tagg.encode(buf)?)
             }
             Cbor::Major7(info, sval) => {
                 let n = encode_hdr(Major::M7, *info, buf)?;
@@ -160,12 +187,31 @@ impl Cbor {
         }
     }
 
-    /// Deserialize a bytes from reader `r` to Cbor value.
+    /// Collect links, tagged Cids, from this value in depth first order.
+    pub fn collect_links(&self, links: &mut Vec<Cid>) {
+        match self {
+            Cbor::Major4(_, list) => list.iter().for_each(|x| x.collect_links(links)),
+            Cbor::Major5(_, dict) => dict.values().for_each(|x| x.collect_links(links)),
+            Cbor::Major6(_, Tag::Link(cid)) => links.push(cid.clone()),
+            _ => (),
+        }
+    }
+
+    /// Deserialize a bytes from reader `r` to Cbor value, encoded value
+    /// shall not exceed [MAX_BLOCK_SIZE].
     pub fn decode<R: io::Read>(r: &mut R) -> Result<Cbor> {
-        Self::do_decode(r, 1)
+        Self::decode_max(r, MAX_BLOCK_SIZE)
+    }
+
+    /// Deserialize a bytes from reader `r` to Cbor value, fail if the
+    /// encoded value exceeds `max` bytes. Lengths are checked before
+    /// allocating, so that crafted headers can't exhaust memory.
+    pub fn decode_max<R: io::Read>(r: &mut R, max: u64) -> Result<Cbor> {
+        let mut r = io::Read::take(r, max);
+        Self::do_decode(&mut r, 1, max)
     }
 
-    fn do_decode<R: io::Read>(r: &mut R, depth: u32) -> Result<Cbor> {
+    fn do_decode<R: io::Read>(r: &mut R, depth: u32, max: u64) -> Result<Cbor> {
         if depth > RECURSION_LIMIT {
             return err_at!(FailCbor, msg: "decode recursion limt exceeded");
         }
@@ -176,36 +222,36 @@ impl Cbor {
             Major::M0 => Cbor::Major0(info, decode_addnl(info, r)?),
             Major::M1 => Cbor::Major1(info, decode_addnl(info, r)?),
             Major::M2 => {
-                let n: usize = decode_addnl(info, r)?.try_into().unwrap();
+                let n = decode_len(info, r, max)?;
                 let mut data = vec![0; n];
-                err_at!(IOError, r.read(&mut data))?;
+                err_at!(IOError, r.read_exact(&mut data))?;
                 Cbor::Major2(info, data)
             }
             Major::M3 => {
-                let n: usize = decode_addnl(info, r)?.try_into().unwrap();
+                let n = decode_len(info, r, max)?;
                 let mut data = vec![0; n];
-                err_at!(IOError, r.read(&mut data))?;
+                err_at!(IOError, r.read_exact(&mut data))?;
                 Cbor::Major3(info, data)
             }
             Major::M4 => {
                 let mut list: Vec<Cbor> = vec![];
-                let n = decode_addnl(info, r)?;
+                let n = decode_len(info, r, max)?;
                 for _ in 0..n {
-                    list.push(Self::do_decode(r, depth + 1)?);
+                    list.push(Self::do_decode(r, depth + 1, max)?);
                 }
                 Cbor::Major4(info, list)
             }
             Major::M5 => {
                 let mut dict: BTreeMap<String, Cbor> = BTreeMap::new();
-                let n = decode_addnl(info, r)?;
+                let n = decode_len(info, r, max)?;
                 for _ in 0..n {
-                    let key = extract_key(Self::decode(r)?)?;
-                    let val = Self::do_decode(r, depth + 1)?;
+                    let key = extract_key(Self::do_decode(r, depth + 1, max)?)?;
+                    let val = Self::do_decode(r, depth + 1, max)?;
                     dict.insert(key, val);
                 }
                 Cbor::Major5(info, dict)
             }
-            Major::M6 => Cbor::Major6(info, Tag::decode(info, r)?),
+            Major::M6 => Cbor::Major6(info, Tag::decode(info, r, depth, max)?),
             Major::M7 => Cbor::Major7(info, SimpleValue::decode(info, r)?),
         };
         Ok(val)
@@ -311,7 +357,7 @@ fn encode_hdr(major: Major, info: Info, buf: &mut Vec<u8>) -> Result<usize> {
 
 fn decode_hdr<R: io::Read>(r: &mut R) -> Result<(Major, Info)> {
     let mut scratch = [0_u8; 8];
-    err_at!(IOError, r.read(&mut scratch[..1]))?;
+    err_at!(IOError, r.read_exact(&mut scratch[..1]))?;
 
     let b = scratch[0];
 
@@ -325,15 +371,15 @@ fn encode_addnl(num: u64, buf: &mut Vec<u8>) -> Result<usize> {
     let n = match num {
         0..=23 => 0,
         n if n <= (u8::MAX as u64) => {
-            scratch.copy_from_slice(&(n as u8).to_be_bytes());
+            scratch[..1].copy_from_slice(&(n as u8).to_be_bytes());
             1
         }
         n if n <= (u16::MAX as u64) => {
-            scratch.copy_from_slice(&(n as u16).to_be_bytes());
+            scratch[..2].copy_from_slice(&(n as u16).to_be_bytes());
             2
         }
         n if n <= (u32::MAX as u64) => {
-            scratch.copy_from_slice(&(n as u32).to_be_bytes());
+            scratch[..4].copy_from_slice(&(n as u32).to_be_bytes());
             4
         }
         n => {
@@ -341,28 +387,37 @@ fn encode_addnl(num: u64, buf: &mut Vec<u8>) -> Result<usize> {
             8
         }
     };
-    buf.copy_from_slice(&scratch[..n]);
+    buf.extend_from_slice(&scratch[..n]);
     Ok(n)
 }
 
+// Decode length of bytes, text, list or map, each byte or item takes
+// atleast one byte, hence length can't exceed `max`.
+fn decode_len<R: io::Read>(info: Info, r: &mut R, max: u64) -> Result<usize> {
+    match decode_addnl(info, r)? {
+        n if n > max => err_at!(FailCbor, msg: "length {} exceeds limit {}", n, max),
+        n => err_at!(FailConvert, n.try_into()),
+    }
+}
+
 fn decode_addnl<R: io::Read>(info: Info, r: &mut R) -> Result<u64> {
     let mut scratch = [0_u8; 8];
     let n = match info {
         Info::Tiny(num) => num as u64,
         Info::U8 => {
-            err_at!(IOError, r.read(&mut scratch[..1]))?;
+            err_at!(IOError, r.read_exact(&mut scratch[..1]))?;
             u8::from_be_bytes(scratch[..1].try_into().unwrap()) as u64
         }
         Info::U16 => {
-            err_at!(IOError, r.read(&mut scratch[..2]))?;
+            err_at!(IOError, r.read_exact(&mut scratch[..2]))?;
             u16::from_be_bytes(scratch[..2].try_into().unwrap()) as u64
         }
         Info::U32 => {
-            err_at!(IOError, r.read(&mut scratch[..4]))?;
+            err_at!(IOError, r.read_exact(&mut scratch[..4]))?;
             u32::from_be_bytes(scratch[..4].try_into().unwrap()) as u64
         }
         Info::U64 => {
-            err_at!(IOError, r.read(&mut scratch[..8]))?;
+            err_at!(IOError, r.read_exact(&mut scratch[..8]))?;
             u64::from_be_bytes(scratch[..8].try_into().unwrap()) as u64
         }
         _ => err_at!(FailCbor, msg: "no additional value")?,
@@ -387,22 +442,22 @@ impl Tag {
     fn encode(&self, buf: &mut Vec<u8>) -> Result<usize> {
         match self {
             Tag::Link(cid) => {
-                buf.copy_from_slice(&TAG_IPLD_CID.to_be_bytes());
-                let n = {
-                    let data = cid.encode()?;
-                    let m: u64 = err_at!(FailCbor, data.len().try_into())?;
-                    Cbor::Major2(m.into(), data).encode(buf)?
-                };
-                Ok(1 + n)
+                // CID bytes are prefixed with the multibase identity prefix.
+                let mut data = vec![0x00];
+                data.extend_from_slice(&cid.encode()?);
+                let m: u64 = err_at!(FailCbor, data.len().try_into())?;
+                Cbor::Major2(m.into(), data).encode(buf)
             }
         }
     }
 
-    fn decode<R: io::Read>(info: Info, r: &mut R) -> Result<Tag> {
+    // `r` is already limited by the caller, recurse with the same reader
+    // type, wrapping it again would not terminate monomorphization.
+    fn decode<R: io::Read>(info: Info, r: &mut R, depth: u32, max: u64) -> Result<Tag> {
         match decode_addnl(info, r)? {
-            42 => match Cbor::decode(r)? {
-                Cbor::Major2(_, bytes) => {
-                    let (cid, _) = Cid::decode(&bytes)?;
+            42 => match Cbor::do_decode(r, depth + 1, max)? {
+                Cbor::Major2(_, bytes) if bytes.first() == Some(&0x00) => {
+                    let (cid, _) = Cid::decode(&bytes[1..])?;
                     Ok(Tag::Link(cid))
                 }
                 _ => err_at!(FailCbor, msg: "invalid cid"),
@@ -464,11 +519,11 @@ impl SimpleValue {
                 1
             }
             F16(f) => {
-                scratch.copy_from_slice(&f.to_be_bytes());
+                scratch[..2].copy_from_slice(&f.to_be_bytes());
                 2
             }
             F32(f) => {
-                scratch.copy_from_slice(&f.to_be_bytes());
+                scratch[..4].copy_from_slice(&f.to_be_bytes());
                 4
             }
             F64(f) => {
@@ -476,7 +531,7 @@ impl SimpleValue {
                 8
             }
         };
-        buf.copy_from_slice(&scratch[..n]);
+        buf.extend_from_slice(&scratch[..n]);
         Ok(n)
     }
 
@@ -491,12 +546,12 @@ impl SimpleValue {
             Info::U8 => err_at!(FailCbor, msg: "simple-value-unassigned1")?,
             Info::U16 => err_at!(FailCbor, msg: "simple-value-f16")?,
             Info::U32 => {
-                err_at!(IOError, r.read(&mut scratch[..4]))?;
+                err_at!(IOError, r.read_exact(&mut scratch[..4]))?;
                 let val = f32::from_be_bytes(scratch[..4].try_into().unwrap());
                 SimpleValue::F32(val)
             }
             Info::U64 => {
-                err_at!(IOError, r.read(&mut scratch[..8]))?;
+                err_at!(IOError, r.read_exact(&mut scratch[..8]))?;
                 let val = f64::from_be_bytes(scratch[..8].try_into().unwrap());
                 SimpleValue::F64(val)
             }
@@ -518,3 +573,7 @@ fn extract_key(val: Cbor) -> Result<String> {
         _ => err_at!(FailCbor, msg: "invalid key"),
     }
 }
+
+#[cfg(test)]
+#[path = "cbor_test.rs"]
+mod cbor_test;
@@ -0,0 +1,131 @@
+use crate::ipld::typed::IpldNode;
+
+use super::*;
+
+#[test]
+fn test_canonical_bytes() {
+    let golden = include_str!("../testdata/canonical/dag_cbor.golden");
+    let lines: Vec<&str> = golden.lines().filter(|l| !l.starts_with('#')).collect();
+
+    for line in lines.into_iter() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let bytes = data_encoding::HEXLOWER.decode(parts[1].as_bytes()).unwrap();
+
+        let val = match parts[0] {
+            // deliberately hold a non-minimal Info.
+            "uint" => Cbor::Major0(Info::U64, 500),
+            "nint" => Cbor::Major1(Info::Tiny(1), 1),
+            "text" => {
+                let text = "abcdefghijklmnopqrstuvwx".as_bytes().to_vec();
+                Cbor::Major3(Info::U8, text)
+            }
+            "float" => Cbor::try_from(SimpleValue::F64(1.5)).unwrap(),
+            "map" => {
+                let mut dict = BTreeMap::new();
+                dict.insert("a".to_string(), Cbor::Major0(Info::Tiny(1), 1));
+                let list = vec![
+                    Cbor::try_from(SimpleValue::True).unwrap(),
+                    Cbor::try_from(SimpleValue::Null).unwrap(),
+                ];
+                dict.insert("bb".to_string(), Cbor::Major4(Info::Tiny(2), list));
+                dict.insert("c".to_string(), Cbor::Major1(Info::Tiny(1), 1));
+                Cbor::Major5(Info::Tiny(3), dict)
+            }
+            "link" => {
+                let text = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+                let tag = Tag::Link(Cid::from_text(text).unwrap());
+                Cbor::Major6(Info::U8, tag)
+            }
+            name => panic!("unknown golden value {}", name),
+        };
+
+        assert_eq!(val.canonical_bytes().unwrap(), bytes, "{}", parts[0]);
+
+        let val = Cbor::decode(&mut bytes.as_slice()).unwrap();
+        assert_eq!(val.canonical_bytes().unwrap(), bytes, "{}", parts[0]);
+    }
+}
+
+#[test]
+fn test_decode_max() {
+    // byte-string header claiming 4GiB, with no payload.
+    let data = vec![0x5a, 0xff, 0xff, 0xff, 0xff];
+    assert!(Cbor::decode(&mut data.as_slice()).is_err());
+
+    // list header claiming more items than bytes allowed.
+    let data = vec![0x98, 0x10, 0x01, 0x02];
+    assert!(Cbor::decode_max(&mut data.as_slice(), 8).is_err());
+
+    // truncated payload.
+    let data = vec![0x44, 0x01, 0x02];
+    assert!(Cbor::decode(&mut data.as_slice()).is_err());
+
+    let data = vec![0x44, 0x01, 0x02, 0x03, 0x04];
+    assert!(Cbor::decode_max(&mut data.as_slice(), 4).is_err());
+    match Cbor::decode_max(&mut data.as_slice(), 5).unwrap() {
+        Cbor::Major2(_, byts) => assert_eq!(byts, vec![1, 2, 3, 4]),
+        _ => unreachable!(),
+    }
+
+    // links inside a list are counted against the same limit.
+    let text = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+    let link = Cbor::Major6(Info::U8, Tag::Link(Cid::from_text(text).unwrap()));
+    let val = Cbor::Major4(Info::Tiny(2), vec![link.clone(), link]);
+    let data = val.canonical_bytes().unwrap();
+    let n = data.len() as u64;
+    assert!(Cbor::decode_max(&mut data.as_slice(), n - 1).is_err());
+    let mut links = vec![];
+    Cbor::decode_max(&mut data.as_slice(), n)
+        .unwrap()
+        .collect_links(&mut links);
+    assert_eq!(links.len(), 2);
+}
+
+#[test]
+fn test_number_policy() {
+    use crate::ipld::kind::Basic;
+
+    // integer range of major type 0 and 1.
+    let max = u64::MAX as i128;
+    for num in [0, -1, max, -max - 1].iter() {
+        let val = Basic::Integer(*num);
+        let cbor = Cbor::try_from(&val as &dyn Node).unwrap();
+        let mut buf = vec![];
+        cbor.encode(&mut buf).unwrap();
+        let val = Basic::try_from(Cbor::decode(&mut buf.as_slice()).unwrap()).unwrap();
+        assert_eq!(val.to_integer(), Some(*num));
+    }
+    for num in [max + 1, -max - 2, i128::MIN, i128::MAX].iter() {
+        let val = Basic::Integer(*num);
+        assert!(Cbor::try_from(&val as &dyn Node).is_err(), "{}", num);
+    }
+
+    // floats must be finite.
+    for num in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY].iter() {
+        let val = Basic::Float(*num);
+        assert!(Cbor::try_from(&val as &dyn Node).is_err(), "{}", num);
+
+        let mut buf = vec![];
+        Cbor::try_from(SimpleValue::F64(*num))
+            .unwrap()
+            .encode(&mut buf)
+            .unwrap();
+        let cbor = Cbor::decode(&mut buf.as_slice()).unwrap();
+        assert!(Basic::try_from(cbor).is_err(), "{}", num);
+    }
+
+    // checked getters.
+    let val = Basic::Integer(300);
+    let node: &dyn Node = &val;
+    assert_eq!(node.to_int().unwrap(), 300);
+    assert!(node.to_finite_float().is_err());
+    assert_eq!(u16::from_node(node).unwrap(), 300);
+    assert_eq!(i64::from_node(node).unwrap(), 300);
+    assert!(u8::from_node(node).is_err());
+
+    let val = Basic::Float(f64::NAN);
+    let node: &dyn Node = &val;
+    assert!(node.to_finite_float().is_err());
+    assert!(node.to_int().is_err());
+    assert!(f64::from_node(node).is_err());
+}
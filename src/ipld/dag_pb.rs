@@ -0,0 +1,90 @@
+//! Module implement decoding of dag-pb links, refer [dag-pb spec].
+//!
+//! [dag-pb spec]: https://ipld.io/specs/codecs/dag-pb/spec/
+
+use crate::{cid::Cid, Error, Result};
+
+/// Link from a dag-pb node, PBLink.
+#[derive(Clone, Debug)]
+pub struct Link {
+    cid: Cid,
+    name: Option<String>,
+    tsize: Option<u64>,
+}
+
+impl Link {
+    pub fn to_cid(&self) -> Cid {
+        self.cid.clone()
+    }
+
+    pub fn to_name(&self) -> Option<String> {
+        self.name.clone()
+    }
+
+    /// Return the cumulative size of the linked sub-dag, if present.
+    pub fn to_tsize(&self) -> Option<u64> {
+        self.tsize
+    }
+}
+
+/// Decode links, in order, from a dag-pb encoded node.
+pub fn decode_links(mut data: &[u8]) -> Result<Vec<Link>> {
+    let mut links = vec![];
+    while !data.is_empty() {
+        let (tag, val, rem) = decode_field(data)?;
+        match (tag, val) {
+            (2, Field::Bytes(buf)) => links.push(decode_link(buf)?),
+            (1, Field::Bytes(_)) => (),
+            (tag, _) => err_at!(DecodeError, msg: "dag-pb PBNode invalid field {}", tag)?,
+        }
+        data = rem;
+    }
+    Ok(links)
+}
+
+fn decode_link(mut data: &[u8]) -> Result<Link> {
+    let (mut cid, mut name, mut tsize) = (None, None, None);
+    while !data.is_empty() {
+        let (tag, val, rem) = decode_field(data)?;
+        match (tag, val) {
+            (1, Field::Bytes(buf)) => cid = Some(Cid::decode(buf)?.0),
+            (2, Field::Bytes(buf)) => {
+                let s = err_at!(DecodeError, std::str::from_utf8(buf))?;
+                name = Some(s.to_string());
+            }
+            (3, Field::Varint(n)) => tsize = Some(n),
+            (tag, _) => err_at!(DecodeError, msg: "dag-pb PBLink invalid field {}", tag)?,
+        }
+        data = rem;
+    }
+
+    match cid {
+        Some(cid) => Ok(Link { cid, name, tsize }),
+        None => err_at!(DecodeError, msg: "dag-pb PBLink without Hash"),
+    }
+}
+
+enum Field<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+}
+
+fn decode_field(data: &[u8]) -> Result<(u64, Field, &[u8])> {
+    use unsigned_varint::decode as uvd;
+
+    let (key, data) = err_at!(DecodeError, uvd::u64(data))?;
+    match key & 0x7 {
+        0 => {
+            let (n, data) = err_at!(DecodeError, uvd::u64(data))?;
+            Ok((key >> 3, Field::Varint(n), data))
+        }
+        2 => {
+            let (n, data) = err_at!(DecodeError, uvd::usize(data))?;
+            if n > data.len() {
+                err_at!(DecodeError, msg: "dag-pb insufficient bytes {}", n)?
+            }
+            Ok((key >> 3, Field::Bytes(&data[..n]), &data[n..]))
+        }
+        wt => err_at!(DecodeError, msg: "dag-pb invalid wire type {}", wt),
+    }
+}
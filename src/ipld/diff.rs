@@ -0,0 +1,142 @@
+//! Module implement deterministic diffing of two DAGs.
+//!
+//! [diff] walks both DAGs from their roots and matches links of nodes at
+//! the same path. Links are matched by name for dag-pb nodes whose links
+//! are uniquely named, like unixfs directories, and by offset otherwise.
+//! Subtrees whose CIDs match are skipped without fetching their blocks,
+//! so the cost of a diff is proportional to the size of the change.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    cid::Cid,
+    ipld::{dag_pb, store::Store},
+    multicodec, Error, Result,
+};
+
+/// Kind of change to a path.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ChangeKind {
+    /// Subtree is present only in the new DAG.
+    Added,
+    /// Subtree is present only in the old DAG.
+    Removed,
+    /// Path is present in both DAGs, but with different blocks.
+    Changed,
+}
+
+/// Item returned by [diff].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Change {
+    /// `/` separated link names, or offsets, from root to the changed
+    /// block, empty for root.
+    pub path: String,
+    pub kind: ChangeKind,
+    /// Block in the old DAG, None when Added.
+    pub old: Option<Cid>,
+    /// Block in the new DAG, None when Removed.
+    pub new: Option<Cid>,
+}
+
+/// Compute the changes from DAG under `old_root` to DAG under `new_root`.
+/// Changes are ordered depth first, parents before children. Added and
+/// Removed subtrees are reported once, at their root, use
+/// [Traversal][crate::ipld::traverse::Traversal] to list their blocks.
+/// Blocks of the changed paths must be present in the store.
+pub fn diff(old_root: &Cid, new_root: &Cid, store: &dyn Store) -> Result<Vec<Change>> {
+    let mut changes = vec![];
+    do_diff(String::default(), old_root, new_root, store, &mut changes)?;
+    Ok(changes)
+}
+
+fn do_diff(
+    path: String,
+    old: &Cid,
+    new: &Cid,
+    store: &dyn Store,
+    changes: &mut Vec<Change>,
+) -> Result<()> {
+    if is_same(old, new)? {
+        return Ok(());
+    }
+
+    changes.push(Change {
+        path: path.clone(),
+        kind: ChangeKind::Changed,
+        old: Some(old.clone()),
+        new: Some(new.clone()),
+    });
+
+    let old_links = to_links(old, store)?;
+    let new_order = to_links(new, store)?;
+    let mut new_links: BTreeMap<String, Cid> = new_order.iter().cloned().collect();
+
+    for (seg, old_cid) in old_links.into_iter() {
+        let child = join(&path, &seg);
+        match new_links.remove(&seg) {
+            Some(new_cid) => do_diff(child, &old_cid, &new_cid, store, changes)?,
+            None => changes.push(Change {
+                path: child,
+                kind: ChangeKind::Removed,
+                old: Some(old_cid),
+                new: None,
+            }),
+        }
+    }
+    for (seg, _) in new_order.into_iter() {
+        if let Some(new_cid) = new_links.remove(&seg) {
+            changes.push(Change {
+                path: join(&path, &seg),
+                kind: ChangeKind::Added,
+                old: None,
+                new: Some(new_cid),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+// cids addressing the same content, irrespective of multibase and
+// version.
+fn is_same(a: &Cid, b: &Cid) -> Result<bool> {
+    let ok = a.to_content_type() == b.to_content_type()
+        && a.to_multihash().encode()? == b.to_multihash().encode()?;
+    Ok(ok)
+}
+
+// return links of block `cid` as (path-segment, cid).
+fn to_links(cid: &Cid, store: &dyn Store) -> Result<Vec<(String, Cid)>> {
+    let block = match store.get(cid)? {
+        Some(block) => block,
+        None => err_at!(Invalid, msg: "block {} not in store", cid.to_text(None)?)?,
+    };
+
+    if cid.to_content_type().to_code() == multicodec::DAG_PB {
+        let links = dag_pb::decode_links(block.as_block_data()?)?;
+        let names: Vec<String> = links
+            .iter()
+            .filter_map(|l| l.to_name())
+            .filter(|name| !name.is_empty())
+            .collect();
+        let unique: BTreeSet<&String> = names.iter().collect();
+        if names.len() == links.len() && unique.len() == links.len() {
+            let iter = names.into_iter().zip(links.into_iter());
+            return Ok(iter.map(|(name, l)| (name, l.to_cid())).collect());
+        }
+    }
+
+    let links = block.to_links()?.into_iter().enumerate();
+    Ok(links.map(|(off, cid)| (off.to_string(), cid)).collect())
+}
+
+fn join(path: &str, seg: &str) -> String {
+    match path {
+        "" => seg.to_string(),
+        path => format!("{}/{}", path, seg),
+    }
+}
+
+#[cfg(test)]
+#[path = "diff_test.rs"]
+mod diff_test;
@@ -0,0 +1,59 @@
+use super::*;
+use crate::{
+    ipld::store::MemStore,
+    unixfs::{importer::Options, test_util},
+};
+
+fn import(data: Vec<u8>, store: &mut MemStore) -> Cid {
+    test_util::import_into(Options::default(), &data, store).0
+}
+
+#[test]
+fn test_diff() {
+    let mut store = MemStore::new();
+
+    let data: Vec<u8> = (0..10).collect();
+    let old = import(data.clone(), &mut store);
+    assert_eq!(diff(&old, &old, &store).unwrap(), vec![]);
+
+    let mut modified = data.clone();
+    modified[9] = 100;
+    let new = import(modified, &mut store);
+    let changes = diff(&old, &new, &store).unwrap();
+    let paths: Vec<(&str, ChangeKind)> =
+        changes.iter().map(|c| (c.path.as_str(), c.kind)).collect();
+    assert_eq!(
+        paths,
+        vec![("", ChangeKind::Changed), ("2", ChangeKind::Changed)]
+    );
+    assert_eq!(changes[0].old, Some(old.clone()));
+    assert_eq!(changes[0].new, Some(new.clone()));
+
+    let extended: Vec<u8> = (0..13).collect();
+    let new = import(extended, &mut store);
+    let changes = diff(&old, &new, &store).unwrap();
+    let paths: Vec<(&str, ChangeKind)> =
+        changes.iter().map(|c| (c.path.as_str(), c.kind)).collect();
+    assert_eq!(
+        paths,
+        vec![
+            ("", ChangeKind::Changed),
+            ("2", ChangeKind::Changed),
+            ("3", ChangeKind::Added)
+        ]
+    );
+
+    let changes = diff(&new, &old, &store).unwrap();
+    assert_eq!(changes[2].kind, ChangeKind::Removed);
+    assert_eq!(changes[2].new, None);
+}
+
+#[test]
+fn test_diff_missing_block() {
+    let mut store = MemStore::new();
+    let old = import((0..10).collect(), &mut store);
+
+    let mut other = MemStore::new();
+    let new = import((10..20).collect(), &mut other);
+    assert!(diff(&old, &new, &store).is_err());
+}
@@ -0,0 +1,323 @@
+//! Module implement plain JSON codec (0x0200) for IPLD data-model.
+//!
+//! Plain JSON can't represent Bytes and Link kinds, as per [spec],
+//! encoding a node that contains them fails, use dag-json for such
+//! data. Integers without fraction and exponent are decoded as Integer
+//! kind, rest of the numbers as Float kind.
+//!
+//! [spec]: https://ipld.io/specs/codecs/dag-json/spec/
+
+use std::collections::BTreeMap;
+
+use crate::{
+    ipld::{
+        cbor::RECURSION_LIMIT,
+        kind::{Basic, Key, Kind, Node},
+    },
+    Error, Result,
+};
+
+/// Encode `node` into plain JSON text.
+pub fn encode(node: &dyn Node) -> Result<Vec<u8>> {
+    let mut buf = vec![];
+    encode_node(node, &mut buf, 1)?;
+    Ok(buf)
+}
+
+/// Decode plain JSON text into data-model.
+pub fn decode(data: &[u8]) -> Result<Basic> {
+    let mut p = Parser { data, off: 0 };
+    let val = p.parse_value(1)?;
+    p.skip_whitespace();
+    match p.off {
+        off if off < data.len() => err_at!(DecodeError, msg: "json trailing data at {}", off),
+        _ => Ok(val),
+    }
+}
+
+fn encode_node(node: &dyn Node, buf: &mut Vec<u8>, depth: u32) -> Result<()> {
+    if depth > RECURSION_LIMIT {
+        err_at!(EncodeError, msg: "json encode recursion limit exceeded")?
+    }
+
+    match node.to_kind() {
+        Kind::Null => buf.extend_from_slice(b"null"),
+        Kind::Bool => match node.to_bool() {
+            Some(true) => buf.extend_from_slice(b"true"),
+            _ => buf.extend_from_slice(b"false"),
+        },
+        Kind::Integer => {
+            let num = node.to_integer().unwrap_or(0);
+            buf.extend_from_slice(num.to_string().as_bytes())
+        }
+        Kind::Float => match node.to_float() {
+            Some(num) if num.is_finite() => {
+                let mut text = num.to_string();
+                if !text.contains('.') && !text.contains('e') {
+                    text.push_str(".0");
+                }
+                buf.extend_from_slice(text.as_bytes())
+            }
+            num => err_at!(EncodeError, msg: "json can't encode float {:?}", num)?,
+        },
+        Kind::Text => match node.as_string() {
+            Some(text) => encode_string(text?, buf),
+            None => err_at!(EncodeError, msg: "json missing text")?,
+        },
+        Kind::Bytes => err_at!(EncodeError, msg: "json can't encode bytes kind")?,
+        Kind::Link => err_at!(EncodeError, msg: "json can't encode link kind")?,
+        Kind::List => {
+            buf.push(b'[');
+            for (i, item) in node.iter().enumerate() {
+                if i > 0 {
+                    buf.push(b',');
+                }
+                encode_node(item, buf, depth + 1)?;
+            }
+            buf.push(b']');
+        }
+        Kind::Map => {
+            buf.push(b'{');
+            for (i, (key, val)) in node.iter_entries().enumerate() {
+                if i > 0 {
+                    buf.push(b',');
+                }
+                match key {
+                    Key::Text(key) => encode_string(&key, buf),
+                    Key::Keyable(key) => encode_string(&key.to_string(), buf),
+                    key => err_at!(EncodeError, msg: "json map key {}", key)?,
+                }
+                buf.push(b':');
+                encode_node(val, buf, depth + 1)?;
+            }
+            buf.push(b'}');
+        }
+    }
+
+    Ok(())
+}
+
+/// Append `text` to `buf` as a quoted JSON string, escaping quotes,
+/// back-slashes and control characters.
+pub fn encode_string(text: &str, buf: &mut Vec<u8>) {
+    buf.push(b'"');
+    for ch in text.chars() {
+        match ch {
+            '"' => buf.extend_from_slice(b"\\\""),
+            '\\' => buf.extend_from_slice(b"\\\\"),
+            '\n' => buf.extend_from_slice(b"\\n"),
+            '\r' => buf.extend_from_slice(b"\\r"),
+            '\t' => buf.extend_from_slice(b"\\t"),
+            '\u{08}' => buf.extend_from_slice(b"\\b"),
+            '\u{0c}' => buf.extend_from_slice(b"\\f"),
+            ch if (ch as u32) < 0x20 => {
+                buf.extend_from_slice(format!("\\u{:04x}", ch as u32).as_bytes())
+            }
+            ch => {
+                let mut scratch = [0_u8; 4];
+                buf.extend_from_slice(ch.encode_utf8(&mut scratch).as_bytes())
+            }
+        }
+    }
+    buf.push(b'"');
+}
+
+struct Parser<'a> {
+    data: &'a [u8],
+    off: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn parse_value(&mut self, depth: u32) -> Result<Basic> {
+        if depth > RECURSION_LIMIT {
+            err_at!(DecodeError, msg: "json decode recursion limit exceeded")?
+        }
+
+        self.skip_whitespace();
+        let val = match self.peek()? {
+            b'n' => self.parse_literal("null", Basic::Null)?,
+            b't' => self.parse_literal("true", Basic::Bool(true))?,
+            b'f' => self.parse_literal("false", Basic::Bool(false))?,
+            b'"' => Basic::Text(self.parse_string()?.into_bytes()),
+            b'[' => {
+                self.off += 1;
+                let mut list: Vec<Box<dyn Node>> = vec![];
+                self.skip_whitespace();
+                if self.peek()? == b']' {
+                    self.off += 1;
+                } else {
+                    loop {
+                        list.push(Box::new(self.parse_value(depth + 1)?));
+                        match self.next_token()? {
+                            b',' => continue,
+                            b']' => break,
+                            ch => err_at!(DecodeError, msg: "json list {:?}", ch as char)?,
+                        }
+                    }
+                }
+                Basic::List(Box::new(list))
+            }
+            b'{' => {
+                self.off += 1;
+                let mut dict: BTreeMap<Key, Box<dyn Node>> = BTreeMap::new();
+                self.skip_whitespace();
+                if self.peek()? == b'}' {
+                    self.off += 1;
+                } else {
+                    loop {
+                        self.skip_whitespace();
+                        let key = self.parse_string()?;
+                        if self.next_token()? != b':' {
+                            err_at!(DecodeError, msg: "json expected ':' at {}", self.off)?
+                        }
+                        let val = self.parse_value(depth + 1)?;
+                        if dict.insert(Key::Text(key.clone()), Box::new(val)).is_some() {
+                            err_at!(DecodeError, msg: "json duplicate key {:?}", key)?
+                        }
+                        match self.next_token()? {
+                            b',' => continue,
+                            b'}' => break,
+                            ch => err_at!(DecodeError, msg: "json map {:?}", ch as char)?,
+                        }
+                    }
+                }
+                Basic::Map(Box::new(dict))
+            }
+            b'-' | b'0'..=b'9' => self.parse_number()?,
+            ch => err_at!(DecodeError, msg: "json invalid char {:?}", ch as char)?,
+        };
+
+        Ok(val)
+    }
+
+    fn parse_literal(&mut self, lit: &str, val: Basic) -> Result<Basic> {
+        if self.data[self.off..].starts_with(lit.as_bytes()) {
+            self.off += lit.len();
+            Ok(val)
+        } else {
+            err_at!(DecodeError, msg: "json invalid literal at {}", self.off)
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Basic> {
+        let start = self.off;
+        let mut float = false;
+        while let Some(ch) = self.data.get(self.off) {
+            match ch {
+                b'0'..=b'9' | b'-' | b'+' => (),
+                b'.' | b'e' | b'E' => float = true,
+                _ => break,
+            }
+            self.off += 1;
+        }
+
+        let text = err_at!(
+            DecodeError,
+            std::str::from_utf8(&self.data[start..self.off])
+        )?;
+        let val = match float {
+            true => Basic::Float(err_at!(DecodeError, text.parse::<f64>(), "{}", text)?),
+            false => Basic::Integer(err_at!(DecodeError, text.parse::<i128>(), "{}", text)?),
+        };
+        Ok(val)
+    }
+
+    fn parse_string(&mut self) -> Result<String> {
+        if self.peek()? != b'"' {
+            err_at!(DecodeError, msg: "json expected string at {}", self.off)?
+        }
+        self.off += 1;
+
+        let mut buf: Vec<u8> = vec![];
+        loop {
+            let ch = self.peek()?;
+            self.off += 1;
+            match ch {
+                b'"' => break,
+                b'\\' => {
+                    let esc = self.peek()?;
+                    self.off += 1;
+                    match esc {
+                        b'"' => buf.push(b'"'),
+                        b'\\' => buf.push(b'\\'),
+                        b'/' => buf.push(b'/'),
+                        b'b' => buf.push(0x08),
+                        b'f' => buf.push(0x0c),
+                        b'n' => buf.push(b'\n'),
+                        b'r' => buf.push(b'\r'),
+                        b't' => buf.push(b'\t'),
+                        b'u' => {
+                            let ch = self.parse_unicode()?;
+                            let mut scratch = [0_u8; 4];
+                            buf.extend_from_slice(ch.encode_utf8(&mut scratch).as_bytes());
+                        }
+                        esc => err_at!(DecodeError, msg: "json escape {:?}", esc as char)?,
+                    }
+                }
+                ch if ch < 0x20 => err_at!(DecodeError, msg: "json control char in string")?,
+                ch => buf.push(ch),
+            }
+        }
+
+        err_at!(DecodeError, String::from_utf8(buf))
+    }
+
+    fn parse_unicode(&mut self) -> Result<char> {
+        let hi = self.parse_hex4()?;
+        let code = match hi {
+            0xD800..=0xDBFF => {
+                if !self.data[self.off..].starts_with(b"\\u") {
+                    err_at!(DecodeError, msg: "json lone surrogate {:x}", hi)?
+                }
+                self.off += 2;
+                let lo = self.parse_hex4()?;
+                if !(0xDC00..=0xDFFF).contains(&lo) {
+                    err_at!(DecodeError, msg: "json invalid surrogate {:x}", lo)?
+                }
+                0x10000 + ((hi - 0xD800) << 10) + (lo - 0xDC00)
+            }
+            code => code,
+        };
+
+        match std::char::from_u32(code) {
+            Some(ch) => Ok(ch),
+            None => err_at!(DecodeError, msg: "json invalid code point {:x}", code),
+        }
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32> {
+        if self.data.len() < self.off + 4 {
+            err_at!(DecodeError, msg: "json insufficient bytes for \\u")?
+        }
+        let text = err_at!(
+            DecodeError,
+            std::str::from_utf8(&self.data[self.off..self.off + 4])
+        )?;
+        self.off += 4;
+        err_at!(DecodeError, u32::from_str_radix(text, 16))
+    }
+
+    fn next_token(&mut self) -> Result<u8> {
+        self.skip_whitespace();
+        let ch = self.peek()?;
+        self.off += 1;
+        Ok(ch)
+    }
+
+    fn peek(&self) -> Result<u8> {
+        match self.data.get(self.off) {
+            Some(ch) => Ok(*ch),
+            None => err_at!(DecodeError, msg: "json unexpected end of input"),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') = self.data.get(self.off) {
+            self.off += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "json_test.rs"]
+mod json_test;
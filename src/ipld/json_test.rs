@@ -0,0 +1,50 @@
+use super::*;
+
+#[test]
+fn test_json_roundtrip() {
+    let testcases = vec![
+        ("null", "null"),
+        ("true", "true"),
+        (" false ", "false"),
+        ("-123", "-123"),
+        ("1.5", "1.5"),
+        ("2e3", "2000.0"),
+        (
+            r#""a\"b\\c\n\u00e9\ud83d\ude00""#,
+            "\"a\\\"b\\\\c\\n\u{e9}\u{1f600}\"",
+        ),
+        ("[1, [2, []], {}]", "[1,[2,[]],{}]"),
+        (
+            r#"{"b": 1, "a": [true, null]}"#,
+            r#"{"a":[true,null],"b":1}"#,
+        ),
+    ];
+
+    for (input, output) in testcases.into_iter() {
+        let val = decode(input.as_bytes()).unwrap();
+        let data = encode(&val).unwrap();
+        assert_eq!(std::str::from_utf8(&data).unwrap(), output, "{}", input);
+    }
+}
+
+#[test]
+fn test_json_invalid() {
+    let testcases = vec![
+        "",
+        "nul",
+        "[1,",
+        "[1 2]",
+        "{\"a\" 1}",
+        "{1: 2}",
+        "\"abc",
+        "1 2",
+        "{\"a\":1,\"a\":2}",
+        "\"\\ud83d\"",
+    ];
+    for input in testcases.into_iter() {
+        assert!(decode(input.as_bytes()).is_err(), "{}", input);
+    }
+
+    assert!(encode(&Basic::Bytes(vec![1, 2])).is_err());
+    assert!(encode(&Basic::Float(f64::NAN)).is_err());
+}
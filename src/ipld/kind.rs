@@ -47,6 +47,26 @@ pub trait Node {
     fn as_bytes(&self) -> Option<&[u8]>;
 
     fn as_link(&self) -> Option<&Cid>;
+
+    /// Return integer value, fail if node is not of integer kind. Use
+    /// `T::try_from` for a range checked conversion into `T`, generic
+    /// methods would make Node unusable as trait object.
+    fn to_int(&self) -> Result<i128> {
+        match self.to_integer() {
+            Some(num) => Ok(num),
+            None => err_at!(FailConvert, msg: "expected integer kind"),
+        }
+    }
+
+    /// Return float value, fail if node is not of float kind or if the
+    /// value is NaN or infinite.
+    fn to_finite_float(&self) -> Result<f64> {
+        match self.to_float() {
+            Some(num) if num.is_finite() => Ok(num),
+            Some(num) => err_at!(FailConvert, msg: "float {} not finite", num),
+            None => err_at!(FailConvert, msg: "expected float kind"),
+        }
+    }
 }
 
 /// A subset of Basic, that can be used to index into recursive type, like
@@ -142,6 +162,16 @@ impl Key {
 }
 
 /// Basic defines IPLD data-model.
+///
+/// Conversion policy for numbers, when encoding to and decoding from
+/// codecs,
+///
+/// * Integer must be within `-(2^64)..=(2^64 - 1)`, the range of DAG-CBOR
+///   major types 0 and 1, encoding values outside this range fail.
+/// * Float must be finite, encoding or decoding NaN and infinities fail.
+/// * Use [Node::to_int] and [Node::to_finite_float] to read numbers into
+///   narrower rust types, out of range values are errors, never
+///   truncated.
 pub enum Basic {
     Null,
     Bool(bool),
@@ -351,8 +381,14 @@ impl TryFrom<Cbor> for Basic {
             Major7(_, cbor::SimpleValue::F16(_)) => {
                 err_at!(FailConvert, msg: "half-precision not supported")?
             }
-            Major7(_, cbor::SimpleValue::F32(val)) => Float(val as f64),
-            Major7(_, cbor::SimpleValue::F64(val)) => Float(val),
+            Major7(_, cbor::SimpleValue::F32(val)) if val.is_finite() => Float(val as f64),
+            Major7(_, cbor::SimpleValue::F64(val)) if val.is_finite() => Float(val),
+            Major7(_, cbor::SimpleValue::F32(val)) => {
+                err_at!(FailConvert, msg: "float {} not allowed in dag-cbor", val)?
+            }
+            Major7(_, cbor::SimpleValue::F64(val)) => {
+                err_at!(FailConvert, msg: "float {} not allowed in dag-cbor", val)?
+            }
             Major7(_, cbor::SimpleValue::Break) => {
                 err_at!(FailConvert, msg: "indefinite length not supported")?
             }
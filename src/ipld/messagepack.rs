@@ -0,0 +1,260 @@
+//! Module implement MessagePack codec (0x0201) for IPLD data-model.
+//!
+//! Values are encoded in their shortest form, floats are always encoded
+//! as float64, map keys must be text. MessagePack has no representation
+//! for Link kind, encoding a node that contains links fails. Extension
+//! types are not supported while decoding. Refer [spec] for details.
+//!
+//! [spec]: https://github.com/msgpack/msgpack/blob/master/spec.md
+
+use std::{collections::BTreeMap, convert::TryInto};
+
+use crate::{
+    ipld::{
+        cbor::RECURSION_LIMIT,
+        kind::{Basic, Key, Kind, Node},
+    },
+    Error, Result,
+};
+
+/// Encode `node` into MessagePack.
+pub fn encode(node: &dyn Node) -> Result<Vec<u8>> {
+    let mut buf = vec![];
+    encode_node(node, &mut buf, 1)?;
+    Ok(buf)
+}
+
+/// Decode MessagePack into data-model.
+pub fn decode(data: &[u8]) -> Result<Basic> {
+    let (val, rem) = decode_value(data, 1)?;
+    match rem.len() {
+        0 => Ok(val),
+        n => err_at!(DecodeError, msg: "messagepack trailing {} bytes", n),
+    }
+}
+
+fn encode_node(node: &dyn Node, buf: &mut Vec<u8>, depth: u32) -> Result<()> {
+    if depth > RECURSION_LIMIT {
+        err_at!(EncodeError, msg: "messagepack encode recursion limit exceeded")?
+    }
+
+    match node.to_kind() {
+        Kind::Null => buf.push(0xc0),
+        Kind::Bool => match node.to_bool() {
+            Some(true) => buf.push(0xc3),
+            _ => buf.push(0xc2),
+        },
+        Kind::Integer => encode_integer(node.to_integer().unwrap_or(0), buf)?,
+        Kind::Float => {
+            buf.push(0xcb);
+            buf.extend_from_slice(&node.to_float().unwrap_or(0.0).to_be_bytes());
+        }
+        Kind::Text => match node.as_string() {
+            Some(text) => encode_str(text?, buf)?,
+            None => err_at!(EncodeError, msg: "messagepack missing text")?,
+        },
+        Kind::Bytes => {
+            let byts = node.as_bytes().unwrap_or(&[]);
+            encode_len(byts.len(), [0x00, 0xc4, 0xc5, 0xc6], 0, buf)?;
+            buf.extend_from_slice(byts);
+        }
+        Kind::Link => err_at!(EncodeError, msg: "messagepack can't encode link kind")?,
+        Kind::List => {
+            encode_len(node.len().unwrap_or(0), [0x90, 0x00, 0xdc, 0xdd], 16, buf)?;
+            for item in node.iter() {
+                encode_node(item, buf, depth + 1)?;
+            }
+        }
+        Kind::Map => {
+            encode_len(node.len().unwrap_or(0), [0x80, 0x00, 0xde, 0xdf], 16, buf)?;
+            for (key, val) in node.iter_entries() {
+                match key {
+                    Key::Text(key) => encode_str(&key, buf)?,
+                    Key::Keyable(key) => encode_str(&key.to_string(), buf)?,
+                    key => err_at!(EncodeError, msg: "messagepack map key {}", key)?,
+                }
+                encode_node(val, buf, depth + 1)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn encode_integer(num: i128, buf: &mut Vec<u8>) -> Result<()> {
+    match num {
+        0..=0x7f => buf.push(num as u8),
+        -32..=-1 => buf.push(num as i8 as u8),
+        0x80..=0xff => buf.extend_from_slice(&[0xcc, num as u8]),
+        0x100..=0xffff => {
+            buf.push(0xcd);
+            buf.extend_from_slice(&(num as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            buf.push(0xce);
+            buf.extend_from_slice(&(num as u32).to_be_bytes());
+        }
+        num if num > 0 => {
+            let num: u64 = err_at!(EncodeError, num.try_into())?;
+            buf.push(0xcf);
+            buf.extend_from_slice(&num.to_be_bytes());
+        }
+        -0x80..=-33 => buf.extend_from_slice(&[0xd0, num as i8 as u8]),
+        -0x8000..=-0x81 => {
+            buf.push(0xd1);
+            buf.extend_from_slice(&(num as i16).to_be_bytes());
+        }
+        -0x8000_0000..=-0x8001 => {
+            buf.push(0xd2);
+            buf.extend_from_slice(&(num as i32).to_be_bytes());
+        }
+        num => {
+            let num: i64 = err_at!(EncodeError, num.try_into())?;
+            buf.push(0xd3);
+            buf.extend_from_slice(&num.to_be_bytes());
+        }
+    }
+    Ok(())
+}
+
+fn encode_str(text: &str, buf: &mut Vec<u8>) -> Result<()> {
+    encode_len(text.len(), [0xa0, 0xd9, 0xda, 0xdb], 32, buf)?;
+    buf.extend_from_slice(text.as_bytes());
+    Ok(())
+}
+
+// Encode length header, `codes` are for fix-form, 8-bit, 16-bit and
+// 32-bit length respectively, fix-form is used for length < `fix_limit`,
+// zero codes are not applicable for the type.
+fn encode_len(len: usize, codes: [u8; 4], fix_limit: usize, buf: &mut Vec<u8>) -> Result<()> {
+    match len {
+        len if len < fix_limit => buf.push(codes[0] | (len as u8)),
+        len if len <= 0xff && codes[1] != 0 => buf.extend_from_slice(&[codes[1], len as u8]),
+        len if len <= 0xffff => {
+            buf.push(codes[2]);
+            buf.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len if len <= 0xffff_ffff => {
+            buf.push(codes[3]);
+            buf.extend_from_slice(&(len as u32).to_be_bytes());
+        }
+        len => err_at!(EncodeError, msg: "messagepack length {} too large", len)?,
+    }
+    Ok(())
+}
+
+fn decode_value(data: &[u8], depth: u32) -> Result<(Basic, &[u8])> {
+    if depth > RECURSION_LIMIT {
+        err_at!(DecodeError, msg: "messagepack decode recursion limit exceeded")?
+    }
+
+    let (code, data) = read_bytes(data, 1)?;
+    let val = match code[0] {
+        b @ 0x00..=0x7f => (Basic::Integer(b.into()), data),
+        b @ 0x80..=0x8f => decode_map((b & 0x0f).into(), data, depth)?,
+        b @ 0x90..=0x9f => decode_list((b & 0x0f).into(), data, depth)?,
+        b @ 0xa0..=0xbf => decode_str((b & 0x1f).into(), data)?,
+        0xc0 => (Basic::Null, data),
+        0xc2 => (Basic::Bool(false), data),
+        0xc3 => (Basic::Bool(true), data),
+        b @ 0xc4..=0xc6 => {
+            let (n, data) = read_uint(data, 1 << (b - 0xc4))?;
+            let (byts, data) = read_bytes(data, n as usize)?;
+            (Basic::Bytes(byts.to_vec()), data)
+        }
+        0xca => {
+            let (byts, data) = read_bytes(data, 4)?;
+            let num = f32::from_be_bytes(byts.try_into().unwrap());
+            (Basic::Float(num.into()), data)
+        }
+        0xcb => {
+            let (byts, data) = read_bytes(data, 8)?;
+            (
+                Basic::Float(f64::from_be_bytes(byts.try_into().unwrap())),
+                data,
+            )
+        }
+        b @ 0xcc..=0xcf => {
+            let (n, data) = read_uint(data, 1 << (b - 0xcc))?;
+            (Basic::Integer(n.into()), data)
+        }
+        b @ 0xd0..=0xd3 => {
+            let n = 1 << (b - 0xd0);
+            let (byts, data) = read_bytes(data, n)?;
+            let num: i128 = match n {
+                1 => (byts[0] as i8).into(),
+                2 => i16::from_be_bytes(byts.try_into().unwrap()).into(),
+                4 => i32::from_be_bytes(byts.try_into().unwrap()).into(),
+                _ => i64::from_be_bytes(byts.try_into().unwrap()).into(),
+            };
+            (Basic::Integer(num), data)
+        }
+        b @ 0xd9..=0xdb => {
+            let (n, data) = read_uint(data, 1 << (b - 0xd9))?;
+            decode_str(n as usize, data)?
+        }
+        b @ 0xdc..=0xdd => {
+            let (n, data) = read_uint(data, 2 << (b - 0xdc))?;
+            decode_list(n as usize, data, depth)?
+        }
+        b @ 0xde..=0xdf => {
+            let (n, data) = read_uint(data, 2 << (b - 0xde))?;
+            decode_map(n as usize, data, depth)?
+        }
+        b @ 0xe0..=0xff => (Basic::Integer((b as i8).into()), data),
+        b => err_at!(DecodeError, msg: "messagepack unsupported type {:x}", b)?,
+    };
+
+    Ok(val)
+}
+
+fn decode_str(n: usize, data: &[u8]) -> Result<(Basic, &[u8])> {
+    let (byts, data) = read_bytes(data, n)?;
+    err_at!(DecodeError, std::str::from_utf8(byts))?;
+    Ok((Basic::Text(byts.to_vec()), data))
+}
+
+fn decode_list(n: usize, mut data: &[u8], depth: u32) -> Result<(Basic, &[u8])> {
+    let mut list: Vec<Box<dyn Node>> = vec![];
+    for _ in 0..n {
+        let (val, rem) = decode_value(data, depth + 1)?;
+        list.push(Box::new(val));
+        data = rem;
+    }
+    Ok((Basic::List(Box::new(list)), data))
+}
+
+fn decode_map(n: usize, mut data: &[u8], depth: u32) -> Result<(Basic, &[u8])> {
+    let mut dict: BTreeMap<Key, Box<dyn Node>> = BTreeMap::new();
+    for _ in 0..n {
+        let (key, rem) = decode_value(data, depth + 1)?;
+        let key = match key {
+            Basic::Text(key) => err_at!(DecodeError, String::from_utf8(key))?,
+            _ => err_at!(DecodeError, msg: "messagepack map key must be text")?,
+        };
+        let (val, rem) = decode_value(rem, depth + 1)?;
+        if dict.insert(Key::Text(key.clone()), Box::new(val)).is_some() {
+            err_at!(DecodeError, msg: "messagepack duplicate key {:?}", key)?
+        }
+        data = rem;
+    }
+    Ok((Basic::Map(Box::new(dict)), data))
+}
+
+fn read_uint(data: &[u8], n: usize) -> Result<(u64, &[u8])> {
+    let (byts, data) = read_bytes(data, n)?;
+    let num = byts.iter().fold(0_u64, |acc, b| (acc << 8) | u64::from(*b));
+    Ok((num, data))
+}
+
+fn read_bytes(data: &[u8], n: usize) -> Result<(&[u8], &[u8])> {
+    if data.len() < n {
+        err_at!(DecodeError, msg: "messagepack insufficient bytes {}", n)
+    } else {
+        Ok((&data[..n], &data[n..]))
+    }
+}
+
+#[cfg(test)]
+#[path = "messagepack_test.rs"]
+mod messagepack_test;
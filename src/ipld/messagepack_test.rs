@@ -0,0 +1,55 @@
+use data_encoding::HEXLOWER;
+
+use super::*;
+use crate::ipld::json;
+
+#[test]
+fn test_messagepack() {
+    // (json input, messagepack as hex)
+    let testcases = vec![
+        ("null", "c0"),
+        ("true", "c3"),
+        ("false", "c2"),
+        ("0", "00"),
+        ("127", "7f"),
+        ("128", "cc80"),
+        ("256", "cd0100"),
+        ("65536", "ce00010000"),
+        ("4294967296", "cf0000000100000000"),
+        ("-1", "ff"),
+        ("-32", "e0"),
+        ("-33", "d0df"),
+        ("-129", "d1ff7f"),
+        ("-32769", "d2ffff7fff"),
+        ("-2147483649", "d3ffffffff7fffffff"),
+        ("1.5", "cb3ff8000000000000"),
+        ("\"abc\"", "a3616263"),
+        ("[1, [2]]", "92019102"),
+        (r#"{"a": 1}"#, "81a16101"),
+    ];
+
+    for (input, hex) in testcases.into_iter() {
+        let val = json::decode(input.as_bytes()).unwrap();
+        let data = encode(&val).unwrap();
+        assert_eq!(HEXLOWER.encode(&data), hex, "{}", input);
+
+        let val = decode(&data).unwrap();
+        assert_eq!(HEXLOWER.encode(&encode(&val).unwrap()), hex, "{}", input);
+    }
+
+    let val = decode(&HEXLOWER.decode(b"c403010203").unwrap()).unwrap();
+    assert_eq!(val.as_bytes(), Some(&[1_u8, 2, 3][..]));
+
+    let val = decode(&HEXLOWER.decode(b"ca3fc00000").unwrap()).unwrap();
+    assert_eq!(val.to_float(), Some(1.5));
+
+    let text = "x".repeat(40);
+    let data = encode(&Basic::Text(text.as_bytes().to_vec())).unwrap();
+    assert_eq!(&data[..2], &[0xd9, 40]);
+
+    // invalid, insufficient bytes, trailing bytes and ext-type.
+    for hex in vec!["a2", "9201", "0000", "d40100"].into_iter() {
+        let data = HEXLOWER.decode(hex.as_bytes()).unwrap();
+        assert!(decode(&data).is_err(), "{}", hex);
+    }
+}
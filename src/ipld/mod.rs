@@ -1,7 +1,20 @@
 pub mod block;
+pub mod car;
 pub mod cbor;
+pub mod dag_pb;
+pub mod diff;
+pub mod json;
 pub mod kind;
+pub mod messagepack;
 pub mod schema;
+pub mod stat;
+pub mod store;
+pub mod transform;
+pub mod traverse;
+pub mod typed;
+pub mod viz;
+
+pub use diff::diff;
 
 //#[cfg(test)]
 //#[path = "sgrammar_test.rs"]
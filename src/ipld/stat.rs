@@ -0,0 +1,111 @@
+//! Module implement DAG statistics, like `ipfs dag stat`.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{cid::Cid, ipld::store::Store, Error, Result};
+
+/// Statistics for a DAG, or a sub-DAG.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Stat {
+    /// Number of unique blocks.
+    pub num_blocks: usize,
+    /// Cumulative size of unique blocks, in bytes.
+    pub total_size: u64,
+    /// Number of links to a block that is already counted.
+    pub num_duplicates: usize,
+    /// Number of levels below root, zero for a DAG with a single block.
+    pub max_depth: usize,
+}
+
+/// Compute statistics for DAGs within a store. Fetched blocks and the
+/// depth of each sub-DAG are memoized, so that computing statistics for
+/// overlapping DAGs, for example every subtree under a root, does not
+/// fetch or walk the same blocks again.
+pub struct Stater<'a> {
+    store: &'a dyn Store,
+    memo: HashMap<Vec<u8>, Entry>,
+}
+
+struct Entry {
+    size: u64,
+    links: Vec<Cid>,
+    depth: Option<usize>,
+}
+
+impl<'a> Stater<'a> {
+    pub fn new(store: &'a dyn Store) -> Stater<'a> {
+        Stater {
+            store,
+            memo: HashMap::new(),
+        }
+    }
+
+    /// Compute statistics for the DAG under `root`. All blocks under
+    /// root must be present in the store.
+    pub fn stat(&mut self, root: &Cid) -> Result<Stat> {
+        let mut stat = Stat::default();
+        let mut visited = HashSet::new();
+        let mut stack = vec![root.clone()];
+
+        while let Some(cid) = stack.pop() {
+            let key = cid.encode()?;
+            if !visited.insert(key.clone()) {
+                stat.num_duplicates += 1;
+                continue;
+            }
+
+            let entry = self.fetch(&cid)?;
+            stat.num_blocks += 1;
+            stat.total_size += entry.size;
+            stack.extend(entry.links.iter().rev().cloned());
+        }
+
+        stat.max_depth = self.to_depth(root, 0)?;
+        Ok(stat)
+    }
+
+    fn to_depth(&mut self, cid: &Cid, level: usize) -> Result<usize> {
+        if level > crate::ipld::cbor::RECURSION_LIMIT as usize {
+            err_at!(Invalid, msg: "dag deeper than {}", level)?
+        }
+
+        let links = {
+            let entry = self.fetch(cid)?;
+            if let Some(depth) = entry.depth {
+                return Ok(depth);
+            }
+            entry.links.clone()
+        };
+
+        let mut depth = 0;
+        for link in links.iter() {
+            depth = depth.max(self.to_depth(link, level + 1)? + 1);
+        }
+
+        if let Some(entry) = self.memo.get_mut(&cid.encode()?) {
+            entry.depth = Some(depth);
+        }
+        Ok(depth)
+    }
+
+    fn fetch(&mut self, cid: &Cid) -> Result<&Entry> {
+        let key = cid.encode()?;
+        if !self.memo.contains_key(&key) {
+            let block = match self.store.get(cid)? {
+                Some(block) => block,
+                None => err_at!(Invalid, msg: "block {} not in store", cid.to_text(None)?)?,
+            };
+            let entry = Entry {
+                size: block.as_block_data()?.len() as u64,
+                links: block.to_links()?,
+                depth: None,
+            };
+            self.memo.insert(key.clone(), entry);
+        }
+        Ok(&self.memo[&key])
+    }
+}
+
+#[cfg(test)]
+#[path = "stat_test.rs"]
+mod stat_test;
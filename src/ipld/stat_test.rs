@@ -0,0 +1,35 @@
+use super::*;
+use crate::{
+    ipld::store::MemStore,
+    unixfs::{importer::Options, test_util},
+};
+
+#[test]
+fn test_stat() {
+    let opts = Options::from_cid_version(crate::cid::Version::One).unwrap();
+    // first two chunks are identical, hence the same raw leaf.
+    let data: Vec<u8> = vec![1, 1, 1, 1, 1, 1, 1, 1, 2, 2];
+    let mut store = MemStore::new();
+    let (root, blocks) = test_util::import_into(opts, &data, &mut store);
+    let root_size = blocks.last().unwrap().as_block_data().unwrap().len() as u64;
+
+    let mut stater = Stater::new(&store);
+    let stat = stater.stat(&root).unwrap();
+    assert_eq!(stat.num_blocks, 3);
+    assert_eq!(stat.total_size, root_size + 4 + 2);
+    assert_eq!(stat.num_duplicates, 1);
+    assert_eq!(stat.max_depth, 1);
+
+    let leaf = blocks[0].to_cid().unwrap();
+    let stat = stater.stat(&leaf).unwrap();
+    let ref_stat = Stat {
+        num_blocks: 1,
+        total_size: 4,
+        num_duplicates: 0,
+        max_depth: 0,
+    };
+    assert_eq!(stat, ref_stat);
+
+    let store = MemStore::new();
+    assert!(Stater::new(&store).stat(&root).is_err());
+}
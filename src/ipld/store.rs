@@ -0,0 +1,60 @@
+//! Module define the block store interface used by DAG utilities.
+
+use std::collections::HashMap;
+
+use crate::{cid::Cid, ipld::block::Block, Error, Result};
+
+/// Store of blocks, addressed by Cid.
+pub trait Store {
+    /// Return the block for `cid`, None if not present in the store.
+    /// Identity cids shall be resolved inline, refer [Block::from_identity].
+    fn get(&self, cid: &Cid) -> Result<Option<Block>>;
+
+    /// Add block to store. Default fails, for read-only stores.
+    fn put(&mut self, block: Block) -> Result<()> {
+        err_at!(NotImplemented, msg: "read-only store, put {}", block.to_cid()?)
+    }
+}
+
+/// In-memory store, indexed on the multihash of block's Cid, hence the
+/// same block can be fetched using CIDv0 or CIDv1.
+#[derive(Clone, Default)]
+pub struct MemStore {
+    blocks: HashMap<Vec<u8>, Block>,
+}
+
+impl MemStore {
+    pub fn new() -> MemStore {
+        MemStore::default()
+    }
+
+    /// Add block to store, an existing block with same multihash is
+    /// replaced.
+    pub fn put(&mut self, block: Block) -> Result<()> {
+        let key = block.to_multihash()?.encode()?;
+        self.blocks.insert(key, block);
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+}
+
+impl Store for MemStore {
+    fn get(&self, cid: &Cid) -> Result<Option<Block>> {
+        if let Some(block) = Block::from_identity(cid)? {
+            return Ok(Some(block));
+        }
+        let key = cid.to_multihash().encode()?;
+        Ok(self.blocks.get(&key).cloned())
+    }
+
+    fn put(&mut self, block: Block) -> Result<()> {
+        MemStore::put(self, block)
+    }
+}
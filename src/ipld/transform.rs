@@ -0,0 +1,117 @@
+//! Module implement copy-on-write transformation of dag-cbor DAGs.
+//!
+//! Only the blocks along the path, the spine, are rewritten. Sub-DAGs
+//! that are not on the path are referred to by their existing links,
+//! hence are reused as is. This is the basic primitive for mutating
+//! large DAGs, like MFS and HAMT.
+
+use crate::{
+    cid::Cid,
+    ipld::{
+        block::Block,
+        cbor::{Cbor, Tag, TAG_IPLD_CID},
+        store::Store,
+    },
+    multicodec,
+    multihash::Multihash,
+    Error, Result,
+};
+
+/// Apply `f` on the value found at `path` under `root`, and return the
+/// new root along with the newly created blocks, root being the last.
+///
+/// `path` is a `/` separated list of map keys and list offsets. Links
+/// found along the path are traversed, but the value at the end of the
+/// path is passed to `f` as is, even if it is a link. New blocks retain
+/// the cid-version, content-type and hash algorithm of the blocks they
+/// replace.
+pub fn transform<F>(root: &Cid, path: &str, store: &dyn Store, f: F) -> Result<(Cid, Vec<Block>)>
+where
+    F: FnOnce(Cbor) -> Result<Cbor>,
+{
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    let mut tr = Transform {
+        store,
+        f: Some(f),
+        blocks: vec![],
+    };
+    let cid = tr.do_block(root, &segments)?;
+
+    Ok((cid, tr.blocks))
+}
+
+struct Transform<'a, F> {
+    store: &'a dyn Store,
+    f: Option<F>,
+    blocks: Vec<Block>,
+}
+
+impl<'a, F> Transform<'a, F>
+where
+    F: FnOnce(Cbor) -> Result<Cbor>,
+{
+    fn do_block(&mut self, cid: &Cid, segments: &[&str]) -> Result<Cid> {
+        let codec = cid.to_content_type();
+        match codec.to_code() {
+            multicodec::DAG_CBOR => (),
+            multicodec::RAW => err_at!(Invalid, msg: "raw block {} is a leaf", cid.to_text(None)?)?,
+            _ => err_at!(NotImplemented, msg: "transform for {}", codec)?,
+        }
+
+        let block = match self.store.get(cid)? {
+            Some(block) => block,
+            None => err_at!(Invalid, msg: "block {} not in store", cid.to_text(None)?)?,
+        };
+        let val = Cbor::decode(&mut block.as_block_data()?)?;
+        let data = self.do_value(val, segments)?.canonical_bytes()?;
+
+        let new_cid = {
+            let mh = Multihash::new(cid.to_multihash().to_codec()?, &data)?;
+            match cid {
+                Cid::Zero(_) => Cid::Zero(mh),
+                Cid::One(base, content_type, _) => Cid::One(base.clone(), *content_type, mh),
+            }
+        };
+        self.blocks.push(Block::new(new_cid.clone(), data.into()));
+
+        Ok(new_cid)
+    }
+
+    fn do_value(&mut self, val: Cbor, segments: &[&str]) -> Result<Cbor> {
+        let val = match (segments, val) {
+            ([], val) => match self.f.take() {
+                Some(f) => f(val)?,
+                None => err_at!(Fatal, msg: "transform applied twice")?,
+            },
+            (segments, Cbor::Major6(_, Tag::Link(cid))) => {
+                let cid = self.do_block(&cid, segments)?;
+                Cbor::Major6(TAG_IPLD_CID.into(), Tag::Link(cid))
+            }
+            ([seg, rest @ ..], Cbor::Major5(info, mut dict)) => {
+                let child = match dict.remove(*seg) {
+                    Some(child) => child,
+                    None => err_at!(Invalid, msg: "key {:?} not found", seg)?,
+                };
+                dict.insert(seg.to_string(), self.do_value(child, rest)?);
+                Cbor::Major5(info, dict)
+            }
+            ([seg, rest @ ..], Cbor::Major4(info, mut list)) => {
+                let off: usize = err_at!(Invalid, seg.parse(), "offset {:?}", seg)?;
+                if off >= list.len() {
+                    err_at!(Invalid, msg: "offset {} out of bound {}", off, list.len())?
+                }
+                let child = list.remove(off);
+                list.insert(off, self.do_value(child, rest)?);
+                Cbor::Major4(info, list)
+            }
+            ([seg, ..], _) => err_at!(Invalid, msg: "can't index {:?} into scalar", seg)?,
+        };
+
+        Ok(val)
+    }
+}
+
+#[cfg(test)]
+#[path = "transform_test.rs"]
+mod transform_test;
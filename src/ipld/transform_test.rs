@@ -0,0 +1,75 @@
+use multibase::Base::Base32Lower;
+
+use std::collections::BTreeMap;
+
+use super::*;
+use crate::{
+    ipld::{cbor::Info, store::MemStore},
+    multicodec::Multicodec,
+};
+
+fn make_block(val: Cbor) -> Block {
+    let data = val.canonical_bytes().unwrap();
+    let cid = Cid::new_v1(
+        Base32Lower,
+        Multicodec::from_code(multicodec::DAG_CBOR).unwrap(),
+        &data,
+    );
+    Block::new(cid.unwrap(), data.into())
+}
+
+fn make_map(items: Vec<(&str, Cbor)>) -> Cbor {
+    let n = items.len() as u64;
+    let dict: BTreeMap<String, Cbor> = items.into_iter().map(|(k, v)| (k.to_string(), v)).collect();
+    Cbor::Major5(n.into(), dict)
+}
+
+fn make_int(n: u64) -> Cbor {
+    Cbor::Major0(n.into(), n)
+}
+
+fn make_link(block: &Block) -> Cbor {
+    Cbor::Major6(TAG_IPLD_CID.into(), Tag::Link(block.to_cid().unwrap()))
+}
+
+#[test]
+fn test_transform() {
+    let child = make_block(make_map(vec![("x", make_int(1))]));
+    let other = make_block(make_map(vec![("y", make_int(1))]));
+    let list = Cbor::Major4(Info::from(2_u64), vec![make_int(10), make_link(&child)]);
+    let root = make_block(make_map(vec![
+        ("a", make_link(&child)),
+        ("b", make_link(&other)),
+        ("l", list),
+    ]));
+
+    let mut store = MemStore::new();
+    for block in vec![child.clone(), other.clone(), root.clone()].into_iter() {
+        store.put(block).unwrap();
+    }
+    let root_cid = root.to_cid().unwrap();
+
+    let (new_root, blocks) = transform(&root_cid, "a/x", &store, |_| Ok(make_int(2))).unwrap();
+    assert_eq!(blocks.len(), 2);
+    assert!(new_root != root_cid);
+    assert!(blocks[1].to_cid().unwrap() == new_root);
+
+    let new_child = make_block(make_map(vec![("x", make_int(2))]));
+    assert!(blocks[0].to_cid().unwrap() == new_child.to_cid().unwrap());
+
+    // untouched sub-dag is reused.
+    let links = blocks[1].to_links().unwrap();
+    assert!(links.contains(&other.to_cid().unwrap()));
+    assert!(links.contains(&child.to_cid().unwrap())); // via "l/1"
+    assert!(links.contains(&new_child.to_cid().unwrap()));
+
+    let (_, blocks) = transform(&root_cid, "l/0", &store, |_| Ok(make_int(11))).unwrap();
+    assert_eq!(blocks.len(), 1);
+
+    let (_, blocks) = transform(&root_cid, "l/1/x", &store, |_| Ok(make_int(3))).unwrap();
+    assert_eq!(blocks.len(), 2);
+
+    assert!(transform(&root_cid, "c", &store, Ok).is_err());
+    assert!(transform(&root_cid, "l/2", &store, Ok).is_err());
+    assert!(transform(&root_cid, "a/x/z", &store, Ok).is_err());
+}
@@ -0,0 +1,133 @@
+//! Module implement streaming traversal of a DAG.
+//!
+//! [Traversal] is an iterator that walks the DAG depth first, in link
+//! order, and yields each block as it is visited, so that consumers, like
+//! CAR export, can start before the walk completes. Budget limits are
+//! enforced during the walk, the iterator yields an error and stops when
+//! a limit is exceeded.
+
+use std::time::Instant;
+
+use crate::{
+    cid::Cid,
+    ipld::{block::Block, kind::Basic, store::Store},
+    Error, Result,
+};
+
+/// Limits on a traversal, None for no limit.
+#[derive(Clone, Debug, Default)]
+pub struct Budget {
+    /// Maximum number of blocks to visit.
+    pub max_blocks: Option<usize>,
+    /// Maximum cumulative size of visited blocks, in bytes.
+    pub max_bytes: Option<u64>,
+    /// Traversal fails once past this instant.
+    pub deadline: Option<Instant>,
+}
+
+/// Item yielded by [Traversal].
+#[derive(Clone)]
+pub struct Visit {
+    /// `/` separated link offsets from root to this block, empty for
+    /// root.
+    pub path: String,
+    pub block: Block,
+}
+
+impl Visit {
+    /// Decode visited block into data-model, refer [Block::to_node].
+    pub fn to_node(&self) -> Result<Basic> {
+        self.block.to_node()
+    }
+}
+
+/// Iterator over blocks of a DAG, refer module documentation.
+pub struct Traversal<'a> {
+    store: &'a dyn Store,
+    budget: Budget,
+    stack: Vec<(String, Cid)>,
+    n_blocks: usize,
+    n_bytes: u64,
+    done: bool,
+}
+
+impl<'a> Traversal<'a> {
+    /// Create a traversal of DAG under `root`. All blocks under root must
+    /// be present in the store.
+    pub fn new(root: &Cid, store: &'a dyn Store, budget: Budget) -> Traversal<'a> {
+        Traversal {
+            store,
+            budget,
+            stack: vec![(String::default(), root.clone())],
+            n_blocks: 0,
+            n_bytes: 0,
+            done: false,
+        }
+    }
+
+    /// Return the number of blocks and bytes visited so far.
+    pub fn to_visited(&self) -> (usize, u64) {
+        (self.n_blocks, self.n_bytes)
+    }
+
+    fn visit(&mut self, path: String, cid: Cid) -> Result<Visit> {
+        if let Some(deadline) = self.budget.deadline {
+            if Instant::now() > deadline {
+                err_at!(Invalid, msg: "traversal deadline exceeded")?
+            }
+        }
+
+        let block = match self.store.get(&cid)? {
+            Some(block) => block,
+            None => err_at!(Invalid, msg: "block {} not in store", cid.to_text(None)?)?,
+        };
+
+        let size = block.as_block_data()?.len() as u64;
+        match self.budget.max_blocks {
+            Some(max) if self.n_blocks >= max => {
+                err_at!(Invalid, msg: "traversal exceeds {} blocks", max)?
+            }
+            _ => (),
+        }
+        match self.budget.max_bytes {
+            Some(max) if (self.n_bytes + size) > max => {
+                err_at!(Invalid, msg: "traversal exceeds {} bytes", max)?
+            }
+            _ => (),
+        }
+        self.n_blocks += 1;
+        self.n_bytes += size;
+
+        let links = block.to_links()?;
+        for (off, link) in links.into_iter().enumerate().rev() {
+            let path = match path.as_str() {
+                "" => off.to_string(),
+                path => format!("{}/{}", path, off),
+            };
+            self.stack.push((path, link));
+        }
+
+        Ok(Visit { path, block })
+    }
+}
+
+impl<'a> Iterator for Traversal<'a> {
+    type Item = Result<Visit>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let (path, cid) = self.stack.pop()?;
+        let res = self.visit(path, cid);
+        if res.is_err() {
+            self.done = true;
+        }
+        Some(res)
+    }
+}
+
+#[cfg(test)]
+#[path = "traverse_test.rs"]
+mod traverse_test;
@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use super::*;
+use crate::{
+    ipld::store::MemStore,
+    unixfs::{importer::Options, test_util},
+};
+
+fn make_dag() -> (Cid, Vec<Block>, MemStore) {
+    let data: Vec<u8> = (0..10).collect();
+    let mut store = MemStore::new();
+    let (root, blocks) = test_util::import_into(Options::default(), &data, &mut store);
+
+    (root, blocks, store)
+}
+
+#[test]
+fn test_traversal() {
+    let (root, blocks, store) = make_dag();
+
+    let mut trav = Traversal::new(&root, &store, Budget::default());
+    let visits: Vec<Visit> = trav.by_ref().map(|v| v.unwrap()).collect();
+    let paths: Vec<&str> = visits.iter().map(|v| v.path.as_str()).collect();
+    assert_eq!(paths, vec!["", "0", "1", "2"]);
+    assert_eq!(visits[0].block.to_cid().unwrap(), root);
+    for (visit, block) in visits[1..].iter().zip(blocks.iter()) {
+        assert_eq!(visit.block.to_cid().unwrap(), block.to_cid().unwrap());
+    }
+
+    let n_bytes: usize = blocks
+        .iter()
+        .map(|b| b.as_block_data().unwrap().len())
+        .sum();
+    assert_eq!(trav.to_visited(), (4, n_bytes as u64));
+}
+
+#[test]
+fn test_traversal_budget() {
+    let (root, _, store) = make_dag();
+
+    let budget = Budget {
+        max_blocks: Some(2),
+        ..Budget::default()
+    };
+    let res: Vec<Result<Visit>> = Traversal::new(&root, &store, budget).collect();
+    assert_eq!(res.len(), 3);
+    assert!(res[0].is_ok() && res[1].is_ok() && res[2].is_err());
+
+    let budget = Budget {
+        max_bytes: Some(1),
+        ..Budget::default()
+    };
+    let res: Vec<Result<Visit>> = Traversal::new(&root, &store, budget).collect();
+    assert_eq!(res.len(), 1);
+    assert!(res[0].is_err());
+
+    let budget = Budget {
+        deadline: Some(Instant::now() - Duration::from_secs(1)),
+        ..Budget::default()
+    };
+    let res: Vec<Result<Visit>> = Traversal::new(&root, &store, budget).collect();
+    assert_eq!(res.len(), 1);
+    assert!(res[0].is_err());
+}
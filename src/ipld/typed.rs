@@ -0,0 +1,298 @@
+//! Module implement conversion of plain rust types to and from IPLD
+//! data-model.
+//!
+//! Types implementing [IpldNode] can be converted into [Basic], and hence
+//! encoded with any of the codecs, and parsed back from any [Node].
+//! Implementations are provided for scalars, [Cid], [Bytes], Option, Vec,
+//! Box and text-keyed BTreeMap. Structs and enums can derive the trait
+//! with `#[derive(IpldNode)]`, enabling the `derive` feature,
+//!
+//! * Structs with named fields use map representation, add
+//!   `#[ipld(repr = "tuple")]` for tuple representation. Tuple structs
+//!   use tuple representation and unit structs are Null.
+//! * Enums use keyed-union representation, `{"<Variant>": <value>}`.
+//! * Fields and variants can be renamed with `#[ipld(rename = "name")]`.
+//!
+//! Functions in this module, other than the trait, are helpers for the
+//! generated code.
+
+use std::{collections::BTreeMap, convert::TryFrom};
+
+#[cfg(feature = "derive")]
+pub use iprs_derive::IpldNode;
+
+use crate::{
+    cid::Cid,
+    ipld::kind::{Basic, Key, Kind, Node},
+    Error, Result,
+};
+
+/// Conversion between a rust type and IPLD data-model.
+pub trait IpldNode: Sized {
+    /// Convert value into data-model.
+    fn to_basic(&self) -> Result<Basic>;
+
+    /// Parse value from data-model.
+    fn from_node(node: &dyn Node) -> Result<Self>;
+
+    /// Value to use when a map field is missing, fields are required
+    /// unless this returns Some.
+    fn from_absent() -> Option<Self> {
+        None
+    }
+}
+
+/// Byte string, since `Vec<u8>` is a list of integers in data-model.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Bytes(pub Vec<u8>);
+
+impl From<Vec<u8>> for Bytes {
+    fn from(val: Vec<u8>) -> Bytes {
+        Bytes(val)
+    }
+}
+
+impl IpldNode for Bytes {
+    fn to_basic(&self) -> Result<Basic> {
+        Ok(Basic::Bytes(self.0.clone()))
+    }
+
+    fn from_node(node: &dyn Node) -> Result<Self> {
+        match node.as_bytes() {
+            Some(val) => Ok(Bytes(val.to_vec())),
+            None => err_at!(FailConvert, msg: "expected bytes kind"),
+        }
+    }
+}
+
+impl IpldNode for bool {
+    fn to_basic(&self) -> Result<Basic> {
+        Ok(Basic::Bool(*self))
+    }
+
+    fn from_node(node: &dyn Node) -> Result<Self> {
+        match node.to_bool() {
+            Some(val) => Ok(val),
+            None => err_at!(FailConvert, msg: "expected bool kind"),
+        }
+    }
+}
+
+macro_rules! impl_integer {
+    ($($type:ty),*) => (
+        $(
+            impl IpldNode for $type {
+                fn to_basic(&self) -> Result<Basic> {
+                    Ok(Basic::Integer(i128::from(*self)))
+                }
+
+                fn from_node(node: &dyn Node) -> Result<Self> {
+                    let num = node.to_int()?;
+                    err_at!(FailConvert, <$type>::try_from(num), "integer {}", num)
+                }
+            }
+        )*
+    );
+}
+
+impl_integer![i8, i16, i32, i64, i128, u8, u16, u32, u64];
+
+impl IpldNode for f64 {
+    fn to_basic(&self) -> Result<Basic> {
+        Ok(Basic::Float(*self))
+    }
+
+    fn from_node(node: &dyn Node) -> Result<Self> {
+        node.to_finite_float()
+    }
+}
+
+impl IpldNode for String {
+    fn to_basic(&self) -> Result<Basic> {
+        Ok(Basic::Text(self.as_bytes().to_vec()))
+    }
+
+    fn from_node(node: &dyn Node) -> Result<Self> {
+        match node.as_string() {
+            Some(val) => Ok(val?.to_string()),
+            None => err_at!(FailConvert, msg: "expected text kind"),
+        }
+    }
+}
+
+impl IpldNode for Cid {
+    fn to_basic(&self) -> Result<Basic> {
+        Ok(Basic::Link(self.clone()))
+    }
+
+    fn from_node(node: &dyn Node) -> Result<Self> {
+        match node.as_link() {
+            Some(val) => Ok(val.clone()),
+            None => err_at!(FailConvert, msg: "expected link kind"),
+        }
+    }
+}
+
+impl<T: IpldNode> IpldNode for Option<T> {
+    fn to_basic(&self) -> Result<Basic> {
+        match self {
+            Some(val) => val.to_basic(),
+            None => Ok(Basic::Null),
+        }
+    }
+
+    fn from_node(node: &dyn Node) -> Result<Self> {
+        match node.is_null() {
+            true => Ok(None),
+            false => Ok(Some(T::from_node(node)?)),
+        }
+    }
+
+    fn from_absent() -> Option<Self> {
+        Some(None)
+    }
+}
+
+impl<T: IpldNode> IpldNode for Box<T> {
+    fn to_basic(&self) -> Result<Basic> {
+        self.as_ref().to_basic()
+    }
+
+    fn from_node(node: &dyn Node) -> Result<Self> {
+        Ok(Box::new(T::from_node(node)?))
+    }
+}
+
+impl<T: IpldNode> IpldNode for Vec<T> {
+    fn to_basic(&self) -> Result<Basic> {
+        let mut items = vec![];
+        for item in self.iter() {
+            items.push(item.to_basic()?);
+        }
+        Ok(new_list(items))
+    }
+
+    fn from_node(node: &dyn Node) -> Result<Self> {
+        expect_kind(node, Kind::List, "list")?;
+        let mut items = vec![];
+        for item in node.iter() {
+            items.push(T::from_node(item)?);
+        }
+        Ok(items)
+    }
+}
+
+impl<T: IpldNode> IpldNode for BTreeMap<String, T> {
+    fn to_basic(&self) -> Result<Basic> {
+        let mut entries = vec![];
+        for (key, val) in self.iter() {
+            entries.push((key.clone(), val.to_basic()?));
+        }
+        Ok(new_map(entries))
+    }
+
+    fn from_node(node: &dyn Node) -> Result<Self> {
+        expect_kind(node, Kind::Map, "map")?;
+        let mut dict = BTreeMap::new();
+        for (key, val) in node.iter_entries() {
+            dict.insert(to_key_text(&key)?, T::from_node(val)?);
+        }
+        Ok(dict)
+    }
+}
+
+/// Return a list node from `items`.
+pub fn new_list(items: Vec<Basic>) -> Basic {
+    let list: Vec<Box<dyn Node>> = items
+        .into_iter()
+        .map(|item| Box::new(item) as Box<dyn Node>)
+        .collect();
+    Basic::List(Box::new(list))
+}
+
+/// Return a map node from `entries`.
+pub fn new_map(entries: Vec<(String, Basic)>) -> Basic {
+    let mut dict: BTreeMap<Key, Box<dyn Node>> = BTreeMap::new();
+    for (key, val) in entries.into_iter() {
+        dict.insert(Key::Text(key), Box::new(val));
+    }
+    Basic::Map(Box::new(dict))
+}
+
+/// Parse field `name` from map `node`, missing field is parsed using
+/// [IpldNode::from_absent].
+pub fn get_field<T: IpldNode>(node: &dyn Node, name: &str) -> Result<T> {
+    expect_kind(node, Kind::Map, name)?;
+
+    // avoid Node::get(), missing key is not an error for optional fields.
+    let key = Key::Text(name.to_string());
+    match node.iter_entries().find(|(k, _)| k == &key) {
+        Some((_, val)) => match T::from_node(val) {
+            Ok(val) => Ok(val),
+            Err(err) => err_at!(FailConvert, msg: "field {:?}: {}", name, err),
+        },
+        None => match T::from_absent() {
+            Some(val) => Ok(val),
+            None => err_at!(FailConvert, msg: "missing field {:?}", name),
+        },
+    }
+}
+
+/// Parse item at `off` from list `node`, where list is expected to have
+/// `n` items.
+pub fn get_item<T: IpldNode>(node: &dyn Node, off: usize, n: usize) -> Result<T> {
+    expect_kind(node, Kind::List, "tuple")?;
+    match node.len() {
+        Some(len) if len == n => (),
+        len => err_at!(FailConvert, msg: "expected {} items, got {:?}", n, len)?,
+    }
+    let val = node.get(&Key::Offset(off))?;
+    match T::from_node(val) {
+        Ok(val) => Ok(val),
+        Err(err) => err_at!(FailConvert, msg: "item {}: {}", off, err),
+    }
+}
+
+/// Return the single (key, value) entry of keyed-union `node`.
+pub fn get_union(node: &dyn Node) -> Result<(String, &dyn Node)> {
+    expect_kind(node, Kind::Map, "union")?;
+    let mut iter = node.iter_entries();
+    match (iter.next(), iter.next()) {
+        (Some((key, val)), None) => Ok((to_key_text(&key)?, val)),
+        _ => err_at!(FailConvert, msg: "union must have exactly one entry"),
+    }
+}
+
+/// Check that `node` is Null, as is the case for unit structs and unit
+/// variants.
+pub fn expect_null(node: &dyn Node) -> Result<()> {
+    match node.is_null() {
+        true => Ok(()),
+        false => err_at!(FailConvert, msg: "expected null kind"),
+    }
+}
+
+/// Fail for keyed-union `variant` that is not known to type `name`.
+pub fn unknown_variant<T>(name: &str, variant: &str) -> Result<T> {
+    err_at!(FailConvert, msg: "unknown variant {:?} for {}", variant, name)
+}
+
+fn expect_kind(node: &dyn Node, kind: Kind, what: &str) -> Result<()> {
+    match (node.to_kind(), kind) {
+        (Kind::Map, Kind::Map) | (Kind::List, Kind::List) => Ok(()),
+        (_, Kind::Map) => err_at!(FailConvert, msg: "{} expected map kind", what),
+        (_, _) => err_at!(FailConvert, msg: "{} expected list kind", what),
+    }
+}
+
+fn to_key_text(key: &Key) -> Result<String> {
+    match key {
+        Key::Text(key) => Ok(key.clone()),
+        Key::Keyable(key) => Ok(key.to_string()),
+        key => err_at!(FailConvert, msg: "expected text key, got {}", key),
+    }
+}
+
+#[cfg(test)]
+#[path = "typed_test.rs"]
+mod typed_test;
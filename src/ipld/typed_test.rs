@@ -0,0 +1,88 @@
+use std::{collections::BTreeMap, convert::TryFrom};
+
+use super::*;
+use crate::{ipld::cbor::Cbor, multicodec};
+
+fn roundtrip<T: IpldNode>(val: &T) -> T {
+    T::from_node(&val.to_basic().unwrap()).unwrap()
+}
+
+#[test]
+fn test_typed_scalars() {
+    assert_eq!(roundtrip(&true), true);
+    assert_eq!(roundtrip(&-10_i8), -10);
+    assert_eq!(roundtrip(&u64::MAX), u64::MAX);
+    assert_eq!(roundtrip(&1.5_f64), 1.5);
+    assert_eq!(roundtrip(&"hello".to_string()), "hello");
+    assert_eq!(roundtrip(&Bytes(vec![1, 2, 3])), Bytes(vec![1, 2, 3]));
+
+    let cid = Cid::new_v1(
+        multibase::Base::Base32Lower,
+        multicodec::RAW.into(),
+        b"hello",
+    )
+    .unwrap();
+    assert!(roundtrip(&cid) == cid);
+
+    // out of range and wrong kind.
+    assert!(u8::from_node(&Basic::Integer(256)).is_err());
+    assert!(u8::from_node(&Basic::Integer(-1)).is_err());
+    assert!(String::from_node(&Basic::Integer(1)).is_err());
+    assert!(Bytes::from_node(&Basic::Text(b"x".to_vec())).is_err());
+}
+
+#[test]
+fn test_typed_containers() {
+    let val: Vec<Option<u32>> = vec![Some(1), None, Some(3)];
+    assert_eq!(roundtrip(&val), val);
+    assert_eq!(roundtrip(&Box::new(10_u32)), Box::new(10));
+
+    let mut dict = BTreeMap::new();
+    dict.insert("a".to_string(), vec![1_i64, 2]);
+    dict.insert("b".to_string(), vec![]);
+    assert_eq!(roundtrip(&dict), dict);
+
+    // through a codec.
+    let mut data = vec![];
+    let basic = dict.to_basic().unwrap();
+    let cbor = Cbor::try_from(&basic as &dyn Node).unwrap();
+    cbor.encode(&mut data).unwrap();
+    let node = Basic::try_from(Cbor::decode(&mut data.as_slice()).unwrap()).unwrap();
+    assert_eq!(
+        BTreeMap::<String, Vec<i64>>::from_node(&node).unwrap(),
+        dict
+    );
+
+    assert!(Vec::<u8>::from_node(&Basic::Bytes(vec![1])).is_err());
+}
+
+#[test]
+fn test_typed_helpers() {
+    let node = new_map(vec![
+        ("name".to_string(), Basic::Text(b"x".to_vec())),
+        ("size".to_string(), Basic::Integer(10)),
+    ]);
+    assert_eq!(get_field::<String>(&node, "name").unwrap(), "x");
+    assert_eq!(get_field::<u32>(&node, "size").unwrap(), 10);
+    assert_eq!(get_field::<Option<u32>>(&node, "missing").unwrap(), None);
+    assert!(get_field::<u32>(&node, "missing").is_err());
+    assert!(get_field::<String>(&node, "size").is_err());
+
+    let node = new_list(vec![Basic::Integer(1), Basic::Bool(true)]);
+    assert_eq!(get_item::<u8>(&node, 0, 2).unwrap(), 1);
+    assert_eq!(get_item::<bool>(&node, 1, 2).unwrap(), true);
+    assert!(get_item::<u8>(&node, 0, 3).is_err());
+
+    let node = new_map(vec![("Leaf".to_string(), Basic::Integer(1))]);
+    let (key, val) = get_union(&node).unwrap();
+    assert_eq!(key, "Leaf");
+    assert_eq!(val.to_integer(), Some(1));
+    assert!(get_union(&new_map(vec![])).is_err());
+}
+
+#[test]
+fn test_typed_unit() {
+    assert!(expect_null(&Basic::Null).is_ok());
+    assert!(expect_null(&Basic::Integer(0)).is_err());
+    assert!(unknown_variant::<u8>("Tree", "Bush").is_err());
+}
@@ -0,0 +1,118 @@
+//! Module implement DOT (GraphViz) and JSON views of a DAG.
+//!
+//! Both views walk the DAG breadth first from a root, up to a maximum
+//! depth, each block is visited once. Blocks missing in the store are
+//! included in the view, without their links.
+
+use std::collections::{HashSet, VecDeque};
+
+use crate::{
+    cid::Cid,
+    ipld::{
+        json,
+        kind::Basic,
+        store::Store,
+        typed::{new_list, new_map},
+    },
+    multicodec::Multicodec,
+    Error, Result,
+};
+
+/// Node in DAG view.
+struct VizNode {
+    cid: String,
+    codec: Multicodec,
+    size: Option<usize>, // None if block is missing in store.
+    links: Vec<String>,
+}
+
+/// Return the DAG under `root` in GraphViz's DOT format. Walk at most
+/// `depth` levels below root, None for no limit.
+pub fn to_dot(root: &Cid, store: &dyn Store, depth: Option<usize>) -> Result<String> {
+    let mut lines = vec!["digraph dag {".to_string()];
+    for node in walk(root, store, depth)?.into_iter() {
+        let attrs = match node.size {
+            Some(size) => format!("label=\"{}\\n{} {}B\"", node.cid, node.codec, size),
+            None => format!("label=\"{}\\n{}\", style=dashed", node.cid, node.codec),
+        };
+        lines.push(format!("  \"{}\" [{}];", node.cid, attrs));
+        for link in node.links.iter() {
+            lines.push(format!("  \"{}\" -> \"{}\";", node.cid, link));
+        }
+    }
+    lines.push("}".to_string());
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Return the DAG under `root` as JSON adjacency list, in the format,
+///
+/// `{"root": <cid>, "nodes": [{"cid": <cid>, "codec": <name>, "size": <n>,
+/// "links": [<cid>, ..]}, ..]}`
+///
+/// `size` is null for blocks missing in the store. Walk at most `depth`
+/// levels below root, None for no limit. Encoded using the [json] codec,
+/// hence map keys are sorted.
+pub fn to_json(root: &Cid, store: &dyn Store, depth: Option<usize>) -> Result<String> {
+    let text = |s: String| Basic::Text(s.into_bytes());
+
+    let mut nodes = vec![];
+    for node in walk(root, store, depth)?.into_iter() {
+        let size = match node.size {
+            Some(size) => Basic::Integer(size as i128),
+            None => Basic::Null,
+        };
+        let links = node.links.into_iter().map(text).collect();
+        nodes.push(new_map(vec![
+            ("cid".to_string(), text(node.cid)),
+            ("codec".to_string(), text(node.codec.to_string())),
+            ("size".to_string(), size),
+            ("links".to_string(), new_list(links)),
+        ]));
+    }
+
+    let val = new_map(vec![
+        ("root".to_string(), text(root.to_text(None)?)),
+        ("nodes".to_string(), new_list(nodes)),
+    ]);
+    err_at!(EncodeError, String::from_utf8(json::encode(&val)?))
+}
+
+fn walk(root: &Cid, store: &dyn Store, depth: Option<usize>) -> Result<Vec<VizNode>> {
+    let mut nodes = vec![];
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::new();
+
+    queue.push_back((root.clone(), 0));
+    visited.insert(root.to_text(None)?);
+
+    while let Some((cid, level)) = queue.pop_front() {
+        let mut node = VizNode {
+            cid: cid.to_text(None)?,
+            codec: cid.to_content_type(),
+            size: None,
+            links: vec![],
+        };
+
+        if let Some(block) = store.get(&cid)? {
+            node.size = Some(block.as_block_data()?.len());
+            if depth.map(|depth| level < depth).unwrap_or(true) {
+                for link in block.to_links()?.into_iter() {
+                    let text = link.to_text(None)?;
+                    node.links.push(text.clone());
+                    if visited.insert(text) {
+                        queue.push_back((link, level + 1));
+                    }
+                }
+            }
+        }
+
+        nodes.push(node);
+    }
+
+    Ok(nodes)
+}
+
+#[cfg(test)]
+#[path = "viz_test.rs"]
+mod viz_test;
@@ -0,0 +1,47 @@
+use super::*;
+use crate::{
+    ipld::store::MemStore,
+    unixfs::{importer::Options, test_util},
+};
+
+#[test]
+fn test_viz() {
+    let data: Vec<u8> = (0..10).collect();
+    let blocks = test_util::import_blocks(Options::default(), &data);
+
+    let root = blocks.last().unwrap().to_cid().unwrap();
+    let mut store = MemStore::new();
+    for block in blocks.iter().take(blocks.len() - 1) {
+        store.put(block.clone()).unwrap();
+    }
+
+    // missing root.
+    let dot = to_dot(&root, &store, None).unwrap();
+    assert!(dot.contains("style=dashed"), "{}", dot);
+    assert_eq!(dot.matches("->").count(), 0, "{}", dot);
+
+    store.put(blocks.last().unwrap().clone()).unwrap();
+    let dot = to_dot(&root, &store, None).unwrap();
+    assert!(dot.starts_with("digraph dag {\n"), "{}", dot);
+    assert_eq!(dot.matches("->").count(), 3, "{}", dot);
+    assert!(!dot.contains("style=dashed"), "{}", dot);
+
+    let dot = to_dot(&root, &store, Some(0)).unwrap();
+    assert_eq!(dot.matches("->").count(), 0, "{}", dot);
+
+    let json = to_json(&root, &store, None).unwrap();
+    let root_text = root.to_text(None).unwrap();
+    assert!(
+        json.ends_with(&format!(",\"root\":\"{}\"}}", root_text)),
+        "{}",
+        json
+    );
+    assert_eq!(json.matches("\"cid\":").count(), 4, "{}", json);
+    assert_eq!(json.matches("\"codec\":\"dag-pb\"").count(), 4, "{}", json);
+
+    // round trips through the json codec.
+    let val = crate::ipld::json::decode(json.as_bytes()).unwrap();
+    let node: &dyn crate::ipld::kind::Node = &val;
+    assert_eq!(node.len(), Some(2));
+    assert_eq!(crate::ipld::json::encode(node).unwrap(), json.as_bytes());
+}
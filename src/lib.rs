@@ -25,6 +25,10 @@ extern crate data_encoding_macro;
 #[macro_use]
 pub mod util;
 pub mod cid;
+pub mod dht;
+pub mod dialer;
+#[cfg(feature = "arbitrary")]
+pub mod fuzz;
 pub mod multibase;
 pub mod multicodec;
 // mod multiformat;
@@ -32,9 +36,17 @@ pub mod addr_info;
 pub mod multistream;
 pub mod net_addr;
 pub mod net_conn;
+pub mod net_i2p;
 pub mod pb;
 pub mod peer_id;
 pub mod peer_record;
+pub mod pnet;
+pub mod pubsub;
+pub mod record;
+#[cfg(feature = "serde")]
+mod serde_impl;
+#[cfg(feature = "spec-tests")]
+pub mod spec_tests;
 
 // modules that have its own sub-directories
 pub mod identity;
@@ -42,6 +54,7 @@ pub mod ipfsd;
 pub mod ipld;
 pub mod multiaddr;
 pub mod multihash;
+pub mod unixfs;
 
 /// Type alias for Result return type, used by this package.
 pub type Result<T> = result::Result<T, Error>;
@@ -23,7 +23,7 @@ impl Dns4 {
 
     pub(crate) fn to_text(&self) -> Result<String> {
         use std::str::from_utf8;
-        Ok("/dns4".to_string() + &err_at!(DecodeError, from_utf8(&self.addr))?)
+        Ok("/dns4/".to_string() + &err_at!(DecodeError, from_utf8(&self.addr))?)
     }
 
     pub(crate) fn decode(data: &[u8]) -> Result<(Self, &[u8])> {
@@ -24,7 +24,7 @@ impl Dns6 {
     pub(crate) fn to_text(&self) -> Result<String> {
         use std::str::from_utf8;
 
-        let s = "/dns6".to_string();
+        let s = "/dns6/".to_string();
         Ok(s + &err_at!(DecodeError, from_utf8(&self.addr))?)
     }
 
@@ -22,7 +22,7 @@ impl Garlic32 {
     }
 
     pub(crate) fn to_text(&self) -> Result<String> {
-        Ok("/garlic32".to_string() + &to_garlic32(&self.addr)?)
+        Ok("/garlic32/".to_string() + &to_garlic32(&self.addr)?)
     }
 
     pub(crate) fn decode(data: &[u8]) -> Result<(Self, &[u8])> {
@@ -43,6 +43,12 @@ impl Garlic32 {
         Ok(val)
     }
 
+    // base32 name, resolved to a destination by SAM bridge.
+    pub(crate) fn to_destination(&self) -> String {
+        let name = GARLIC32.encode(&self.addr);
+        format!("{}.b32.i2p", name.trim_end_matches('='))
+    }
+
     pub(crate) fn encode(&self) -> Result<Vec<u8>> {
         use unsigned_varint::encode::u128 as uv_encode;
 
@@ -22,7 +22,7 @@ impl Garlic64 {
     }
 
     pub(crate) fn to_text(&self) -> Result<String> {
-        Ok("/garlic64".to_string() + &to_garlic64(&self.addr)?)
+        Ok("/garlic64/".to_string() + &to_garlic64(&self.addr)?)
     }
 
     pub(crate) fn decode(data: &[u8]) -> Result<(Self, &[u8])> {
@@ -43,6 +43,11 @@ impl Garlic64 {
         Ok(val)
     }
 
+    // destination in i2p base64, as used by SAM bridge.
+    pub(crate) fn to_destination(&self) -> String {
+        GARLIC64.encode(&self.addr)
+    }
+
     pub(crate) fn encode(&self) -> Result<Vec<u8>> {
         use unsigned_varint::encode::u128 as uv_encode;
 
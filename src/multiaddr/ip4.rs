@@ -30,7 +30,7 @@ impl Ip4 {
     }
 
     pub(crate) fn to_text(&self) -> Result<String> {
-        Ok("/ip4".to_string() + &self.addr.to_string())
+        Ok("/ip4/".to_string() + &self.addr.to_string())
     }
 
     pub(crate) fn decode(data: &[u8]) -> Result<(Self, &[u8])> {
@@ -30,7 +30,7 @@ impl Ip6 {
     }
 
     pub(crate) fn to_text(&self) -> Result<String> {
-        Ok("/ip6".to_string() + &self.addr.to_string())
+        Ok("/ip6/".to_string() + &self.addr.to_string())
     }
 
     pub(crate) fn decode(data: &[u8]) -> Result<(Self, &[u8])> {
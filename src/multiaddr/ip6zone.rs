@@ -24,7 +24,7 @@ impl Ip6zone {
     pub(crate) fn to_text(&self) -> Result<String> {
         use std::str::from_utf8;
 
-        let s = "/ip6zone".to_string();
+        let s = "/ip6zone/".to_string();
         Ok(s + &err_at!(DecodeError, from_utf8(&self.addr))?)
     }
 
@@ -35,6 +35,8 @@ pub(crate) mod utp;
 pub(crate) mod ws;
 pub(crate) mod wss;
 
+use std::{fmt, result};
+
 use crate::{
     multiaddr::{
         dccp::Dccp, dns::Dns, dns4::Dns4, dns6::Dns6, dnsaddr::Dnsaddr, garlic32::Garlic32,
@@ -50,7 +52,7 @@ use crate::{
 macro_rules! impl_multiaddr {
     ($(
         #[$doc:meta]
-        ($var:ident, $type:ident, $name:expr, $code:path),
+        ($var:ident, $type:ident, $name:expr, $code:path, $syntax:expr),
     )*) => (
         /// Type implement a multiaddress.
         ///
@@ -76,29 +78,42 @@ macro_rules! impl_multiaddr {
             /// Parse text formated multi-address. Refer to
             /// [spec](https://multiformats.io/multiaddr/) for details.
             pub fn from_text(text: &str) -> Result<Multiaddr> {
+                Ok(Self::parse_text(text)?)
+            }
+
+            /// Same as [Multiaddr::from_text], but return a typed error
+            /// locating the offending component, refer [ParseError].
+            pub fn parse_text(text: &str) -> result::Result<Multiaddr, ParseError> {
                 let parts: Vec<&str> = text.split('/').collect();
 
-                if parts.len() == 0 {
-                    err_at!(BadAddr, msg: "empty multiaddr {}", text)
-                } else if parts[0].is_empty() {
-                    err_at!(BadAddr, msg: "multiaddr must start with '/'")
-                } else if parts[1..].len() == 0 {
-                    err_at!(BadAddr, msg: "empty multiaddr {}", text)
+                if text.is_empty() || text == "/" {
+                    Err(ParseError::new(0, "", "/<protocol>/..", "empty multiaddr"))
+                } else if !parts[0].is_empty() {
+                    Err(ParseError::new(0, parts[0], "/<protocol>/..", "must start with '/'"))
                 } else {
-                    match Self::parse_text_parts(&parts[1..])? {
+                    match Self::parse_text_parts(&parts[1..], 0)? {
                         (ma, []) => Ok(ma),
-                        (_, _) => err_at!(BadAddr, msg: "invalid multiaddr {:?}", text),
+                        (_, tail) => {
+                            let off = text.len() - tail.join("/").len();
+                            let index = parts.len() - 1 - tail.len();
+                            let reason = format!("unconsumed text at byte {}", off);
+                            Err(ParseError::new(index, tail[0], "", reason))
+                        }
                     }
                 }
             }
 
-            pub(crate) fn parse_text_parts<'a, 'b>(parts: &'a [&'b str]) -> Result<(Multiaddr, &'a [&'b str])> {
+            pub(crate) fn parse_text_parts<'a, 'b>(
+                parts: &'a [&'b str],
+                index: usize,
+            ) -> result::Result<(Multiaddr, &'a [&'b str]), ParseError> {
                 let (maddr, tail) = match parts {
                     [] => (Multiaddr::None, parts),
                     $(
                         [$name, ..] => {
-                            let (val, tail) = $type::from_text(&parts[1..])?;
-                            let (ma, tail) = Self::parse_text_parts(tail)?;
+                            let (val, tail) = $type::from_text(&parts[1..])
+                                .map_err(|err| ParseError::new(index, $name, $syntax, err))?;
+                            let (ma, tail) = Self::parse_text_parts(tail, index + 1)?;
                             (Multiaddr::$var(val, Box::new(ma)), tail)
                         }
                     )*
@@ -106,28 +121,44 @@ macro_rules! impl_multiaddr {
                         // first try parsing it for ip4
                         match Ip4::from_text(&parts[1..]) {
                             Ok((val, tail)) => {
-                                let (ma, tail) = Self::parse_text_parts(tail)?;
+                                let (ma, tail) = Self::parse_text_parts(tail, index + 1)?;
                                 (Multiaddr::Ip4(val, Box::new(ma)), tail)
                             }
                             Err(_) => {
                                 // then try parsing it for ip6
-                                let (val, tail) = Ip6::from_text(&parts[1..])?;
-                                let (ma, tail) = Self::parse_text_parts(tail)?;
+                                let (val, tail) = Ip6::from_text(&parts[1..]).map_err(|err| {
+                                    ParseError::new(index, "ip", "/ip/<ip4-or-ip6-address>", err)
+                                })?;
+                                let (ma, tail) = Self::parse_text_parts(tail, index + 1)?;
                                 (Multiaddr::Ip6(val, Box::new(ma)), tail)
                             }
                         }
                     }
                     ["ipfs", ..] => {
-                        let (val, tail) = P2p::from_text(&parts[1..])?;
-                        let (ma, tail) = Self::parse_text_parts(tail)?;
+                        let (val, tail) = P2p::from_text(&parts[1..])
+                            .map_err(|err| ParseError::new(index, "ipfs", "/ipfs/<peer-id>", err))?;
+                        let (ma, tail) = Self::parse_text_parts(tail, index + 1)?;
                         (Multiaddr::P2p(val, Box::new(ma)), tail)
                     }
-                    parts => err_at!(BadAddr, msg: "invalid multiaddr {:?}", parts)?,
+                    [name, ..] => Err(ParseError::new(
+                        index, name, "/<protocol>/..", "unknown protocol"
+                    ))?,
                 };
 
                 Ok((maddr, tail))
             }
 
+            /// Return the expected text syntax for protocol `name`, like
+            /// `/tcp/<port>`. Return None if protocol is not known.
+            pub fn to_syntax(name: &str) -> Option<&'static str> {
+                match name {
+                    $($name => Some($syntax),)*
+                    "ip" => Some("/ip/<ip4-or-ip6-address>"),
+                    "ipfs" => Some("/ipfs/<peer-id>"),
+                    _ => None,
+                }
+            }
+
             /// Convert this multi-address into text format.
             pub fn to_text(&self) -> Result<String> {
                 let text = match self {
@@ -264,6 +295,57 @@ macro_rules! impl_multiaddr {
     );
 }
 
+/// Error parsing text formated multi-address, locating the offending
+/// component. Convertible into [Error::BadAddr].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+    /// Index of the offending component, counting from 0. For
+    /// `/ip4/1.2.3.4/tcp/port` the offending `tcp` component is at 1.
+    pub index: usize,
+    /// Protocol name of the offending component, as given in text.
+    pub protocol: String,
+    /// Expected syntax for the component, like `/tcp/<port>`.
+    pub expected: String,
+    /// Reason for failure.
+    pub reason: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
+        write!(
+            f,
+            "component {} {:?}: {}",
+            self.index, self.protocol, self.reason
+        )?;
+        match self.expected.as_str() {
+            "" => Ok(()),
+            expected => write!(f, ", expected {}", expected),
+        }
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(err: ParseError) -> Error {
+        use log::error;
+
+        let prefix = format!("{}:{}", file!(), line!());
+        let err = Error::BadAddr(prefix, err.to_string());
+        error!("{}", err);
+        err
+    }
+}
+
+impl ParseError {
+    fn new<R: fmt::Display>(index: usize, protocol: &str, expected: &str, reason: R) -> Self {
+        ParseError {
+            index,
+            protocol: protocol.to_string(),
+            expected: expected.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
 impl Multiaddr {
     /// IsThinWaist returns whether a Multiaddr starts with "Thin Waist"
     /// Protocols. This means: /{IP4, IP6}[/{TCP, UDP}]
@@ -294,69 +376,136 @@ impl Multiaddr {
 
         Ok(val)
     }
+
+    /// Return the canonical binary form of this multi-address. Text and
+    /// binary variants are parsed and re-encoded, hence two multiaddrs
+    /// describing the same address give identical bytes.
+    pub fn canonical_bytes(&self) -> Result<Vec<u8>> {
+        self.clone().parse()?.encode()
+    }
+
+    /// Return whether the leading components of this multi-address are
+    /// same as the components of `prefix`.
+    pub fn starts_with(&self, prefix: &Multiaddr) -> Result<bool> {
+        let comps = self.to_component_bytes()?;
+        let prefix = prefix.to_component_bytes()?;
+        Ok(comps.len() >= prefix.len() && comps.iter().zip(prefix.iter()).all(|(a, b)| a == b))
+    }
+
+    /// Return whether the trailing components of this multi-address are
+    /// same as the components of `suffix`.
+    pub fn ends_with(&self, suffix: &Multiaddr) -> Result<bool> {
+        let comps = self.to_component_bytes()?;
+        let suffix = suffix.to_component_bytes()?;
+        Ok(comps.len() >= suffix.len()
+            && comps
+                .iter()
+                .rev()
+                .zip(suffix.iter().rev())
+                .all(|(a, b)| a == b))
+    }
+
+    /// Return whether any component of this multi-address is of protocol
+    /// `code`, like multicodec::P2P_CIRCUIT.
+    pub fn contains_protocol(&self, code: u128) -> Result<bool> {
+        let comps = self.clone().split()?;
+        Ok(comps
+            .iter()
+            .any(|c| c.to_multicodec().map(|m| m.to_code()) == Some(code)))
+    }
+
+    // components are compared in their binary form, so that parsed, text
+    // and binary variants of the same address compare equal.
+    fn to_component_bytes(&self) -> Result<Vec<Vec<u8>>> {
+        let mut items = vec![];
+        for comp in self.clone().split()?.into_iter() {
+            items.push(comp.encode()?);
+        }
+        Ok(items)
+    }
+
+    /// Parse a list of text formated multi-addresses, typically a list
+    /// field from config. `field` names the list, error message shall
+    /// include the field, the index of offending entry and the reason.
+    pub fn from_text_list(field: &str, items: &[String]) -> Result<Vec<Multiaddr>> {
+        let mut addrs = Vec::with_capacity(items.len());
+        for (i, item) in items.iter().enumerate() {
+            match Multiaddr::from_text(item) {
+                Ok(addr) => addrs.push(addr),
+                Err(err) => err_at!(BadAddr, msg: "{}[{}] {:?}: {}", field, i, item, err)?,
+            }
+        }
+        Ok(addrs)
+    }
 }
 
 impl_multiaddr![
     /// Internet-protocol version 4
-    (Ip4, Ip4, "ip4", multicodec::IP4),
+    (Ip4, Ip4, "ip4", multicodec::IP4, "/ip4/<ipv4-address>"),
     /// Internet-protocol version 6
-    (Ip6, Ip6, "ip6", multicodec::IP6),
+    (Ip6, Ip6, "ip6", multicodec::IP6, "/ip6/<ipv6-address>"),
     /// Transport control protocol
-    (Tcp, Tcp, "tcp", multicodec::TCP),
+    (Tcp, Tcp, "tcp", multicodec::TCP, "/tcp/<port>"),
     /// Domain name service
-    (Dns, Dns, "dns", multicodec::DNS),
+    (Dns, Dns, "dns", multicodec::DNS, "/dns/<domain-name>"),
     /// Domain name service, for IP4
-    (Dns4, Dns4, "dns4", multicodec::DNS4),
+    (Dns4, Dns4, "dns4", multicodec::DNS4, "/dns4/<domain-name>"),
     /// Domain name service, for IP6
-    (Dns6, Dns6, "dns6", multicodec::DNS6),
+    (Dns6, Dns6, "dns6", multicodec::DNS6, "/dns6/<domain-name>"),
     /// Domain name service, automatic
-    (Dnsaddr, Dnsaddr, "dnsaddr", multicodec::DNSADDR),
+    (Dnsaddr, Dnsaddr, "dnsaddr", multicodec::DNSADDR, "/dnsaddr/<domain-name>"),
     /// User datagram protocol
-    (Udp, Udp, "udp", multicodec::UDP),
+    (Udp, Udp, "udp", multicodec::UDP, "/udp/<port>"),
     /// Datagram congestion control protocol
-    (Dccp, Dccp, "dccp", multicodec::DCCP),
+    (Dccp, Dccp, "dccp", multicodec::DCCP, "/dccp/<port>"),
     /// Ip-6-zone
-    (Ip6zone, Ip6zone, "ip6zone", multicodec::IP6ZONE),
+    (Ip6zone, Ip6zone, "ip6zone", multicodec::IP6ZONE, "/ip6zone/<zone-id>"),
     /// Stream control transmission protocol
-    (Sctp, Sctp, "sctp", multicodec::SCTP),
+    (Sctp, Sctp, "sctp", multicodec::SCTP, "/sctp/<port>"),
     /// Onion routing for Tor network.
-    (Onion, Onion, "onion", multicodec::ONION),
+    (Onion, Onion, "onion", multicodec::ONION, "/onion/<base32-hash>:<port>"),
     /// Onion routing for Tor network.
-    (Onion3, Onion3, "onion3", multicodec::ONION3),
+    (Onion3, Onion3, "onion3", multicodec::ONION3, "/onion3/<base32-hash>:<port>"),
     /// Garlic routing for invisible internet protocol
-    (Garlic32, Garlic32, "garlic32", multicodec::GARLIC32),
+    (Garlic32, Garlic32, "garlic32", multicodec::GARLIC32, "/garlic32/<base32-address>"),
     /// Garlic routine for invisible internet protocol
-    (Garlic64, Garlic64, "garlic64", multicodec::GARLIC64),
+    (Garlic64, Garlic64, "garlic64", multicodec::GARLIC64, "/garlic64/<base64-address>"),
     /// Peer-2-peer addressing for ipfs and affiliated network
-    (P2p, P2p, "p2p", multicodec::P2P),
+    (P2p, P2p, "p2p", multicodec::P2P, "/p2p/<peer-id>"),
     /// Unix socket addressing
-    (Unix, Unix, "unix", multicodec::UNIX),
+    (Unix, Unix, "unix", multicodec::UNIX, "/unix/<path>"),
     /// Utp addressing
-    (Utp, Utp, "utp", multicodec::UTP),
+    (Utp, Utp, "utp", multicodec::UTP, "/utp"),
     /// Udt addressing
-    (Udt, Udt, "udt", multicodec::UDT),
+    (Udt, Udt, "udt", multicodec::UDT, "/udt"),
     /// Quic addressing
-    (Quic, Quic, "quic", multicodec::QUIC),
+    (Quic, Quic, "quic", multicodec::QUIC, "/quic"),
     /// Addressing for HTTP protocol
-    (Http, Http, "http", multicodec::HTTP),
+    (Http, Http, "http", multicodec::HTTP, "/http"),
     /// Https addressing
-    (Https, Https, "https", multicodec::HTTPS),
+    (Https, Https, "https", multicodec::HTTPS, "/https"),
     /// p2p-circuit addressing
     (
         P2pCircuit,
         P2pCircuit,
         "p2p-circuit",
-        multicodec::P2P_CIRCUIT
+        multicodec::P2P_CIRCUIT,
+        "/p2p-circuit"
     ),
     /// p2p-webrtc-direct addressing
     (
         P2pWebRtcDirect,
         P2pWebRtcDirect,
         "p2p-webrtc-direct",
-        multicodec::P2P_WEBRTC_DIRECT
+        multicodec::P2P_WEBRTC_DIRECT,
+        "/p2p-webrtc-direct"
     ),
     /// ws addressing
-    (Ws, Ws, "ws", multicodec::WS),
+    (Ws, Ws, "ws", multicodec::WS, "/ws"),
     /// wss addressing
-    (Wss, Wss, "wss", multicodec::WSS),
+    (Wss, Wss, "wss", multicodec::WSS, "/wss"),
 ];
+
+#[cfg(test)]
+#[path = "multiaddr_test.rs"]
+mod multiaddr_test;
@@ -0,0 +1,149 @@
+use super::*;
+
+#[test]
+fn test_canonical_bytes() {
+    let golden = include_str!("../testdata/canonical/multiaddr.golden");
+    for line in golden.lines().filter(|l| !l.starts_with('#')) {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let bytes = data_encoding::HEXLOWER.decode(parts[1].as_bytes()).unwrap();
+
+        let ma = Multiaddr::from_text(parts[0]).unwrap();
+        assert_eq!(ma.canonical_bytes().unwrap(), bytes, "{}", parts[0]);
+
+        let ma = Multiaddr::Text(parts[0].to_string());
+        assert_eq!(ma.canonical_bytes().unwrap(), bytes, "{}", parts[0]);
+
+        let ma = Multiaddr::Binary(bytes.clone());
+        assert_eq!(ma.canonical_bytes().unwrap(), bytes, "{}", parts[0]);
+    }
+}
+
+#[test]
+fn test_from_text_list() {
+    let items: Vec<String> = vec!["/ip4/127.0.0.1/tcp/4001", "/ip6/::1/udp/4001/quic"]
+        .into_iter()
+        .map(|s| s.to_string())
+        .collect();
+    let addrs = Multiaddr::from_text_list("Addresses.swarm", &items).unwrap();
+    assert_eq!(addrs.len(), 2);
+
+    let items: Vec<String> = vec!["/ip4/127.0.0.1/tcp/4001", "/ip4/127.0.0.1/tcp/port"]
+        .into_iter()
+        .map(|s| s.to_string())
+        .collect();
+    let err = Multiaddr::from_text_list("Addresses.swarm", &items).unwrap_err();
+    let msg = err.to_string();
+    assert!(msg.contains("Addresses.swarm[1]"), "{}", msg);
+    assert!(msg.contains("/ip4/127.0.0.1/tcp/port"), "{}", msg);
+}
+
+#[test]
+fn test_starts_ends_with() {
+    let peer = "QmNnooDu7bfjPFoTZYxMNLWUQJyrVwtbZg5gBMjTezGAJN";
+    let text = format!(
+        "/ip4/1.2.3.4/tcp/4001/p2p/{}/p2p-circuit/p2p/{}",
+        peer, peer
+    );
+    let ma = Multiaddr::from_text(&text).unwrap();
+
+    let prefix = Multiaddr::from_text("/ip4/1.2.3.4/tcp/4001").unwrap();
+    assert_eq!(ma.starts_with(&prefix).unwrap(), true);
+    assert_eq!(ma.ends_with(&prefix).unwrap(), false);
+    assert_eq!(ma.starts_with(&Multiaddr::None).unwrap(), true);
+    assert_eq!(prefix.starts_with(&ma).unwrap(), false);
+
+    let suffix = Multiaddr::Text(format!("/p2p-circuit/p2p/{}", peer));
+    assert_eq!(ma.ends_with(&suffix).unwrap(), true);
+    assert_eq!(ma.starts_with(&suffix).unwrap(), false);
+
+    let suffix = Multiaddr::from_text(&format!("/ipfs/{}", peer)).unwrap();
+    assert_eq!(ma.ends_with(&suffix).unwrap(), true);
+
+    let other = Multiaddr::from_text("/ip4/1.2.3.5/tcp/4001").unwrap();
+    assert_eq!(ma.starts_with(&other).unwrap(), false);
+
+    assert_eq!(ma.contains_protocol(multicodec::P2P_CIRCUIT).unwrap(), true);
+    assert_eq!(ma.contains_protocol(multicodec::TCP).unwrap(), true);
+    assert_eq!(ma.contains_protocol(multicodec::UDP).unwrap(), false);
+    assert_eq!(
+        prefix.contains_protocol(multicodec::P2P_CIRCUIT).unwrap(),
+        false
+    );
+}
+
+#[test]
+fn test_to_text() {
+    let golden = include_str!("../testdata/canonical/multiaddr.golden");
+    for line in golden.lines().filter(|l| !l.starts_with('#')) {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let bytes = data_encoding::HEXLOWER.decode(parts[1].as_bytes()).unwrap();
+
+        let ma = Multiaddr::Binary(bytes);
+        assert_eq!(ma.to_text().unwrap(), parts[0]);
+        let ma = Multiaddr::from_text(parts[0]).unwrap();
+        assert_eq!(ma.to_text().unwrap(), parts[0]);
+    }
+}
+
+#[test]
+fn test_p2p_text_forms() {
+    use crate::peer_id::PeerId;
+    use multibase::Base;
+
+    let peer = "QmNnooDu7bfjPFoTZYxMNLWUQJyrVwtbZg5gBMjTezGAJN";
+    let cid = PeerId::from_text(peer)
+        .unwrap()
+        .to_base_text(Base::Base32Lower)
+        .unwrap();
+
+    let legacy = format!("/ip4/1.2.3.4/tcp/4001/p2p/{}", peer);
+    let text = format!("/ip4/1.2.3.4/tcp/4001/p2p/{}", cid);
+    let ma_legacy = Multiaddr::from_text(&legacy).unwrap();
+    let ma = Multiaddr::from_text(&text).unwrap();
+
+    // text is emitted in the form it was parsed from.
+    assert_eq!(ma_legacy.to_text().unwrap(), legacy);
+    assert_eq!(ma.to_text().unwrap(), text);
+
+    // binary encoding is the same for both forms, and decodes to legacy.
+    let bytes = ma.encode().unwrap();
+    assert_eq!(bytes, ma_legacy.encode().unwrap());
+    let (decoded, _) = Multiaddr::decode(&bytes).unwrap();
+    assert_eq!(decoded.to_text().unwrap(), legacy);
+    assert_eq!(decoded, ma);
+}
+
+#[test]
+fn test_parse_error() {
+    let err = Multiaddr::parse_text("/ip4/127.0.0.1/tcp/port").unwrap_err();
+    assert_eq!(err.index, 1);
+    assert_eq!(err.protocol, "tcp");
+    assert_eq!(err.expected, "/tcp/<port>");
+
+    let err = Multiaddr::parse_text("/ip4/127.0.0.1/tcp/4001/xyz/1").unwrap_err();
+    assert_eq!(err.index, 2);
+    assert_eq!(err.protocol, "xyz");
+    assert!(err.reason.contains("unknown protocol"), "{}", err);
+
+    let err = Multiaddr::parse_text("/ip4/300.0.0.1").unwrap_err();
+    assert_eq!((err.index, err.protocol.as_str()), (0, "ip4"));
+    assert_eq!(err.expected, "/ip4/<ipv4-address>");
+
+    assert_eq!(Multiaddr::parse_text("ip4/1.2.3.4").unwrap_err().index, 0);
+    assert!(Multiaddr::parse_text("").is_err());
+    assert!(Multiaddr::parse_text("/").is_err());
+
+    let err: Error = Multiaddr::parse_text("/ip6/::1/udp/port/quic")
+        .unwrap_err()
+        .into();
+    let msg = err.to_string();
+    assert!(msg.contains("BadAddr"), "{}", msg);
+    assert!(msg.contains("component 1 \"udp\""), "{}", msg);
+    assert!(msg.contains("expected /udp/<port>"), "{}", msg);
+
+    assert_eq!(
+        Multiaddr::to_syntax("onion3"),
+        Some("/onion3/<base32-hash>:<port>")
+    );
+    assert_eq!(Multiaddr::to_syntax("xyz"), None);
+}
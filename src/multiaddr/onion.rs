@@ -25,7 +25,7 @@ impl Onion {
     }
 
     pub(crate) fn to_text(&self) -> Result<String> {
-        Ok("/onion".to_string() + &to_onion_text(&self.hash, self.port)?)
+        Ok("/onion/".to_string() + &to_onion_text(&self.hash, self.port)?)
     }
 
     pub(crate) fn decode(data: &[u8]) -> Result<(Self, &[u8])> {
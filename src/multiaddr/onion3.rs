@@ -25,7 +25,7 @@ impl Onion3 {
     }
 
     pub(crate) fn to_text(&self) -> Result<String> {
-        Ok("/onion3".to_string() + &to_onion3_text(&self.hash, self.port)?)
+        Ok("/onion3/".to_string() + &to_onion3_text(&self.hash, self.port)?)
     }
 
     pub(crate) fn decode(data: &[u8]) -> Result<(Self, &[u8])> {
@@ -1,24 +1,45 @@
+use multibase::Base;
+
 use crate::{
+    multibase::Multibase,
     multicodec::{self, Multicodec},
     peer_id::PeerId,
     Error, Result,
 };
 
-#[derive(Clone, Eq, PartialEq, Debug)]
+// Peer-id text can either be base58btc encoded multihash, or multibase
+// encoded CIDv1 with libp2p-key codec. `base` remembers the CID form's
+// base, so that text is emitted in the form it was parsed from, binary
+// encoding is the same for both forms.
+#[derive(Clone, Eq, Debug)]
 pub struct P2p {
     peer_id: PeerId,
+    base: Option<Base>,
+}
+
+impl PartialEq for P2p {
+    fn eq(&self, other: &P2p) -> bool {
+        self.peer_id == other.peer_id
+    }
 }
 
 impl P2p {
     pub(crate) fn new(peer_id: PeerId) -> Self {
-        P2p { peer_id }
+        P2p {
+            peer_id,
+            base: None,
+        }
     }
 
     pub(crate) fn from_text<'a, 'b>(parts: &'a [&'b str]) -> Result<(Self, &'a [&'b str])> {
         let val = match parts {
             [addr, tail @ ..] => {
                 let peer_id = PeerId::from_text(addr)?;
-                (P2p { peer_id }, tail)
+                let base = match addr.starts_with("Qm") || addr.starts_with('1') {
+                    true => None,
+                    false => Some(Multibase::from_text(addr)?.try_to_base()?),
+                };
+                (P2p { peer_id, base }, tail)
             }
             _ => err_at!(BadAddr, msg: "p2p {:?}", parts)?,
         };
@@ -27,7 +48,11 @@ impl P2p {
     }
 
     pub(crate) fn to_text(&self) -> Result<String> {
-        Ok("/p2p".to_string() + &self.peer_id.to_base58btc()?)
+        let text = match &self.base {
+            None => self.peer_id.to_base58btc()?,
+            Some(base) => self.peer_id.to_base_text(base.clone())?,
+        };
+        Ok("/p2p/".to_string() + &text)
     }
 
     pub(crate) fn decode(data: &[u8]) -> Result<(Self, &[u8])> {
@@ -40,8 +65,7 @@ impl P2p {
             };
             let (peer_id, _) = PeerId::decode(addr)?;
 
-            let val = P2p { peer_id };
-            (val, data)
+            (P2p::new(peer_id), data)
         };
 
         Ok(val)
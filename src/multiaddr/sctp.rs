@@ -24,7 +24,7 @@ impl Sctp {
     }
 
     pub(crate) fn to_text(&self) -> Result<String> {
-        Ok("/sctp".to_string() + &self.port.to_string())
+        Ok("/sctp/".to_string() + &self.port.to_string())
     }
 
     pub(crate) fn decode(data: &[u8]) -> Result<(Self, &[u8])> {
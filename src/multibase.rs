@@ -2,8 +2,11 @@
 //!
 //! [multibase]: https://github.com/multiformats/multibase
 
+use lazy_static::lazy_static;
 use multibase;
 
+use std::collections::HashMap;
+
 use crate::{Error, Result};
 
 /// Type to encode/decode bytes into/from multi-base formats.
@@ -13,16 +16,52 @@ use crate::{Error, Result};
 /// [multibase]: https://github.com/multiformats/multibase
 #[derive(Clone, Eq, PartialEq)]
 pub struct Multibase {
-    base: multibase::Base,
+    base: Codec,
     data: Option<Vec<u8>>,
 }
 
+#[derive(Clone, Eq, PartialEq)]
+enum Codec {
+    Base(multibase::Base),
+    // not implemented by the multibase package.
+    Base256Emoji,
+    Proquint,
+}
+
+/// Prefix for base256emoji, refer [BASE256EMOJI_ALPHABET].
+pub const BASE256EMOJI: char = '🚀';
+
+/// Alphabet for base256emoji, each byte is encoded as the emoji at its
+/// index.
+pub const BASE256EMOJI_ALPHABET: &str = "🚀🪐☄🛰🌌🌑🌒🌓🌔🌕🌖🌗🌘🌍🌏🌎🐉☀💻🖥💾💿😂❤😍🤣😊🙏💕😭😘👍😅👏😁🔥🥰💔💖💙😢🤔😆🙄💪😉☺👌🤗💜😔😎😇🌹🤦🎉💞✌✨🤷😱😌🌸🙌😋💗💚😏💛🙂💓🤩😄😀🖤😃💯🙈👇🎶😒🤭❣😜💋👀😪😑💥🙋😞😩😡🤪👊🥳😥🤤👉💃😳✋😚😝😴🌟😬🙃🍀🌷😻😓⭐✅🥺🌈😈🤘💦✔😣🏃💐☹🎊💘😠☝😕🌺🎂🌻😐🖕💝🙊😹🗣💫💀👑🎵🤞😛🔴😤🌼😫⚽🤙☕🏆🤫👈😮🙆🍻🍃🐶💁😲🌿🧡🎁⚡🌞🎈❌✊👋😰🤨😶🤝🚶💰🍓💢🤟🙁🚨💨🤬✈🎀🍺🤓😙💟🌱😖👶🥴▶➡❓💎💸⬇😨🌚🦋😷🕺⚠🙅😟😵👎🤲🤠🤧📌🔵💅🧐🐾🍒😗🤑🌊🤯🐷☎💧😯💆👆🎤🙇🍑❄🌴💣🐸💌📍🥀🤢👅💡💩👐📸👻🤐🤮🎼🥵🚩🍎🍊👼💍📣🥂";
+
+/// Prefix for proquint, encoded text starts with [PROQUINT_PREFIX].
+pub const PROQUINT: char = 'p';
+
+/// Proquint text, including the multibase prefix, starts with `pro-`
+/// followed by `-` separated words, each word encoding 16 bits as
+/// consonant-vowel-consonant-vowel-consonant. Trailing odd byte is
+/// padded with zero bits to 16 bits, and only the first three letters,
+/// consonant-vowel-consonant, are kept. Refer
+/// [proquint](https://arxiv.org/html/0901.4016).
+pub const PROQUINT_PREFIX: &str = "pro-";
+
+const PROQUINT_CONSONANTS: &[u8; 16] = b"bdfghjklmnprstvz";
+const PROQUINT_VOWELS: &[u8; 4] = b"aiou";
+
+lazy_static! {
+    static ref EMOJI_INDEX: HashMap<char, u8> = {
+        let iter = BASE256EMOJI_ALPHABET.chars().enumerate();
+        iter.map(|(i, ch)| (ch, i as u8)).collect()
+    };
+}
+
 impl Multibase {
     /// Create a multibase encoder from one of the many base formats.
     /// Subsequently encode() on this value will encode the supplied `data`.
     pub fn with_base(base: multibase::Base, data: &[u8]) -> Result<Multibase> {
         Ok(Multibase {
-            base,
+            base: Codec::Base(base),
             data: Some(data.to_vec()),
         })
     }
@@ -34,7 +73,9 @@ impl Multibase {
     /// [specification]: https://github.com/multiformats/multibase/blob/master/multibase.csv
     pub fn with_char(ch: char, data: &[u8]) -> Result<Multibase> {
         let base = match multibase::Base::from_code(ch) {
-            Ok(base) => Ok(base),
+            Ok(base) => Ok(Codec::Base(base)),
+            Err(_) if ch == BASE256EMOJI => Ok(Codec::Base256Emoji),
+            Err(_) if ch == PROQUINT => Ok(Codec::Proquint),
             Err(e) => err_at!(BadInput, Err(e), "bad char `{}`", ch),
         }?;
 
@@ -48,9 +89,17 @@ impl Multibase {
     /// have the <base-prefix> followed by the actual base-representation
     /// of the `input`.
     pub fn to_text(&self) -> Result<String> {
-        let text = match &self.data {
-            Some(data) => multibase::encode(self.base.clone(), data),
-            None => "".to_string(),
+        let text = match (&self.base, &self.data) {
+            (Codec::Base(base), Some(data)) => multibase::encode(base.clone(), data),
+            (Codec::Base256Emoji, Some(data)) => {
+                let alphabet: Vec<char> = BASE256EMOJI_ALPHABET.chars().collect();
+                let mut text = String::with_capacity(4 * (data.len() + 1));
+                text.push(BASE256EMOJI);
+                data.iter().for_each(|b| text.push(alphabet[*b as usize]));
+                text
+            }
+            (Codec::Proquint, Some(data)) => to_proquint(data),
+            (_, None) => "".to_string(),
         };
         Ok(text)
     }
@@ -60,7 +109,30 @@ impl Multibase {
     /// format and the original raw-data. Refer [Self::to_base],
     /// [Self::to_bytes].
     pub fn from_text(text: &str) -> Result<Multibase> {
-        let (base, data) = err_at!(BadInput, multibase::decode(text))?;
+        let (base, data) = match text.strip_prefix(BASE256EMOJI) {
+            None if text.starts_with(PROQUINT) => {
+                let mut data = Vec::with_capacity(text.len() / 3);
+                from_proquint(text, |b| {
+                    data.push(b);
+                    Ok(())
+                })?;
+                (Codec::Proquint, data)
+            }
+            Some(text) => {
+                let mut data = Vec::with_capacity(text.len() / 4);
+                for (i, ch) in text.chars().enumerate() {
+                    match EMOJI_INDEX.get(&ch) {
+                        Some(b) => data.push(*b),
+                        None => err_at!(BadInput, msg: "base256emoji {:?} at {}", ch, i)?,
+                    }
+                }
+                (Codec::Base256Emoji, data)
+            }
+            None => {
+                let (base, data) = err_at!(BadInput, multibase::decode(text))?;
+                (Codec::Base(base), data)
+            }
+        };
         let val = Multibase {
             base,
             data: Some(data),
@@ -69,9 +141,95 @@ impl Multibase {
         Ok(val)
     }
 
-    /// Return the `Base` format type.
+    /// Decode <base-prefix> followed by the base-representation into
+    /// `buf`, return the number of bytes written. Unlike [Self::from_text]
+    /// this does not allocate for identity, base256emoji, proquint and the rfc4648
+    /// family of bases, other bases are decoded into a temporary buffer.
+    /// Fail if `buf` is not large enough.
+    pub fn decode_into(text: &str, buf: &mut [u8]) -> Result<usize> {
+        let ch = match text.chars().next() {
+            Some(ch) => ch,
+            None => err_at!(BadInput, msg: "empty multibase text")?,
+        };
+        let input = &text[ch.len_utf8()..];
+
+        if ch == BASE256EMOJI {
+            let mut n = 0;
+            for (i, ch) in input.chars().enumerate() {
+                match (EMOJI_INDEX.get(&ch), buf.get_mut(n)) {
+                    (Some(b), Some(out)) => *out = *b,
+                    (None, _) => err_at!(BadInput, msg: "base256emoji {:?} at {}", ch, i)?,
+                    (_, None) => err_at!(BadInput, msg: "buffer too small {}", buf.len())?,
+                }
+                n += 1;
+            }
+            return Ok(n);
+        }
+        if ch == PROQUINT {
+            let mut n = 0;
+            from_proquint(text, |b| match buf.get_mut(n) {
+                Some(out) => {
+                    *out = b;
+                    n += 1;
+                    Ok(())
+                }
+                None => err_at!(BadInput, msg: "buffer too small {}", buf.len()),
+            })?;
+            return Ok(n);
+        }
+
+        let base = err_at!(
+            BadInput,
+            multibase::Base::from_code(ch),
+            "bad char `{}`",
+            ch
+        )?;
+        let n = match (base, to_encoding(base)) {
+            (multibase::Base::Identity, _) => copy_into(input.as_bytes(), buf)?,
+            (_, Some(enc)) => {
+                let input = input.as_bytes();
+                let n = err_at!(BadInput, enc.decode_len(input.len()))?;
+                if n > buf.len() {
+                    err_at!(BadInput, msg: "buffer too small {} < {}", buf.len(), n)?
+                }
+                match enc.decode_mut(input, &mut buf[..n]) {
+                    Ok(n) => n,
+                    Err(err) => err_at!(BadInput, msg: "{:?} {}", base, err.error)?,
+                }
+            }
+            (base, None) => copy_into(&err_at!(BadInput, base.decode(input))?, buf)?,
+        };
+
+        Ok(n)
+    }
+
+    /// Return the `Base` format type. Panics for bases not implemented
+    /// by multibase package, like base256emoji and proquint, use
+    /// [Self::try_to_base] when the base is not known upfront.
     pub fn to_base(&self) -> multibase::Base {
-        self.base.clone()
+        match self.try_to_base() {
+            Ok(base) => base,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Return the `Base` format type. Fail for bases not implemented by
+    /// multibase package, like base256emoji and proquint.
+    pub fn try_to_base(&self) -> Result<multibase::Base> {
+        match &self.base {
+            Codec::Base(base) => Ok(base.clone()),
+            Codec::Base256Emoji => err_at!(NotImplemented, msg: "base256emoji as multibase::Base"),
+            Codec::Proquint => err_at!(NotImplemented, msg: "proquint as multibase::Base"),
+        }
+    }
+
+    /// Return the multibase prefix character.
+    pub fn to_char(&self) -> char {
+        match &self.base {
+            Codec::Base(base) => base.code(),
+            Codec::Base256Emoji => BASE256EMOJI,
+            Codec::Proquint => PROQUINT,
+        }
     }
 
     /// Return the decoded original binary-data from base-format.
@@ -80,7 +238,150 @@ impl Multibase {
     }
 }
 
-pub const TABLE: [(&'static str, char, &'static str); 23] = [
+fn to_proquint(data: &[u8]) -> String {
+    let con = |x: u16| PROQUINT_CONSONANTS[(x & 0xf) as usize] as char;
+    let vo = |x: u16| PROQUINT_VOWELS[(x & 0x3) as usize] as char;
+
+    let mut text = String::with_capacity(4 + (data.len() * 3));
+    text.push_str(PROQUINT_PREFIX);
+    for (i, chunk) in data.chunks(2).enumerate() {
+        if i > 0 {
+            text.push('-');
+        }
+        match chunk {
+            [a, b] => {
+                let x = u16::from_be_bytes([*a, *b]);
+                text.push(con(x >> 12));
+                text.push(vo(x >> 10));
+                text.push(con(x >> 6));
+                text.push(vo(x >> 4));
+                text.push(con(x));
+            }
+            chunk => {
+                // padded to 16 bits, only the first three letters.
+                let x = chunk[0] as u16;
+                text.push(con(x >> 4));
+                text.push(vo(x >> 2));
+                text.push(con((x & 0x3) << 2));
+            }
+        }
+    }
+    text
+}
+
+// decode proquint `text`, including the multibase prefix, calling `emit`
+// for each decoded byte.
+fn from_proquint<F>(text: &str, mut emit: F) -> Result<()>
+where
+    F: FnMut(u8) -> Result<()>,
+{
+    let words = match text.strip_prefix(PROQUINT_PREFIX) {
+        Some("") => return Ok(()),
+        Some(words) => words,
+        None => err_at!(BadInput, msg: "proquint missing {:?}", PROQUINT_PREFIX)?,
+    };
+
+    let mut iter = words.split('-').enumerate().peekable();
+    while let Some((i, word)) = iter.next() {
+        let last = iter.peek().is_none();
+        // consonants and vowels alternate, 4 and 2 bits respectively.
+        let bits = word
+            .bytes()
+            .enumerate()
+            .try_fold(0_u32, |acc, (j, ch)| match j % 2 {
+                0 => PROQUINT_CONSONANTS
+                    .iter()
+                    .position(|c| *c == ch)
+                    .map(|x| (acc << 4) | (x as u32)),
+                _ => PROQUINT_VOWELS
+                    .iter()
+                    .position(|c| *c == ch)
+                    .map(|x| (acc << 2) | (x as u32)),
+            });
+        match (word.len(), bits) {
+            (5, Some(x)) => {
+                emit((x >> 8) as u8)?;
+                emit(x as u8)?;
+            }
+            // trailing odd byte, padding bits in the last consonant are 0.
+            (3, Some(x)) if last && (x & 0x3) == 0 => {
+                emit((((x >> 6) << 4) | (((x >> 4) & 0x3) << 2) | ((x & 0xf) >> 2)) as u8)?;
+            }
+            _ => err_at!(BadInput, msg: "proquint bad word {:?} at {}", word, i)?,
+        }
+    }
+
+    Ok(())
+}
+
+// same encodings as used by multibase package, refer its encoding.rs.
+const BASE2: data_encoding::Encoding = new_encoding! {
+    symbols: "01",
+};
+
+const BASE8: data_encoding::Encoding = new_encoding! {
+    symbols: "01234567",
+};
+
+const BASE32_NOPAD_LOWER: data_encoding::Encoding = new_encoding! {
+    symbols: "abcdefghijklmnopqrstuvwxyz234567",
+};
+
+const BASE32_PAD_LOWER: data_encoding::Encoding = new_encoding! {
+    symbols: "abcdefghijklmnopqrstuvwxyz234567",
+    padding: '=',
+};
+
+const BASE32HEX_NOPAD_LOWER: data_encoding::Encoding = new_encoding! {
+    symbols: "0123456789abcdefghijklmnopqrstuv",
+};
+
+const BASE32HEX_PAD_LOWER: data_encoding::Encoding = new_encoding! {
+    symbols: "0123456789abcdefghijklmnopqrstuv",
+    padding: '=',
+};
+
+const BASE32Z: data_encoding::Encoding = new_encoding! {
+    symbols: "ybndrfg8ejkmcpqxot1uwisza345h769",
+};
+
+fn to_encoding(base: multibase::Base) -> Option<data_encoding::Encoding> {
+    use multibase::Base::*;
+
+    let enc = match base {
+        Base2 => BASE2,
+        Base8 => BASE8,
+        Base16Lower => data_encoding::HEXLOWER,
+        Base16Upper => data_encoding::HEXUPPER,
+        Base32Lower => BASE32_NOPAD_LOWER,
+        Base32Upper => data_encoding::BASE32_NOPAD,
+        Base32PadLower => BASE32_PAD_LOWER,
+        Base32PadUpper => data_encoding::BASE32,
+        Base32HexLower => BASE32HEX_NOPAD_LOWER,
+        Base32HexUpper => data_encoding::BASE32HEX_NOPAD,
+        Base32HexPadLower => BASE32HEX_PAD_LOWER,
+        Base32HexPadUpper => data_encoding::BASE32HEX,
+        Base32Z => BASE32Z,
+        Base64 => data_encoding::BASE64_NOPAD,
+        Base64Pad => data_encoding::BASE64,
+        Base64Url => data_encoding::BASE64URL_NOPAD,
+        Base64UrlPad => data_encoding::BASE64URL,
+        _ => return None,
+    };
+    Some(enc)
+}
+
+fn copy_into(data: &[u8], buf: &mut [u8]) -> Result<usize> {
+    match buf.get_mut(..data.len()) {
+        Some(buf) => {
+            buf.copy_from_slice(data);
+            Ok(data.len())
+        }
+        None => err_at!(BadInput, msg: "buffer too small {} < {}", buf.len(), data.len()),
+    }
+}
+
+pub const TABLE: [(&'static str, char, &'static str); 25] = [
     (
         "identity",
         '\0',
@@ -136,6 +437,16 @@ pub const TABLE: [(&'static str, char, &'static str); 23] = [
     ("base64pad", 'M', "rfc4648 with padding - MIME encoding"),
     ("base64url", 'u', "rfc4648 no padding"),
     ("base64urlpad", 'U', "rfc4648 with padding"),
+    (
+        "proquint",
+        'p',
+        "Proquint (https://arxiv.org/html/0901.4016)",
+    ),
+    (
+        "base256emoji",
+        '🚀',
+        "base256 with custom alphabet using variable-sized-codepoints",
+    ),
 ];
 
 #[cfg(test)]
@@ -95,3 +95,97 @@ fn test_bs58_multibase() {
 
     assert_eq!(&out1.as_bytes()[1..], &out2.as_bytes()[1..])
 }
+
+#[test]
+fn test_base256emoji() {
+    let mb = Multibase::with_char(BASE256EMOJI, b"yes mani !").unwrap();
+    let text = mb.to_text().unwrap();
+    assert_eq!(text, "🚀🏃✋🌈😅🌷🤤😻🌟😅👏");
+    assert_eq!(mb.to_char(), '🚀');
+    assert!(mb.try_to_base().is_err());
+
+    let mb = Multibase::from_text("🚀🚀🚀🏃✋🌈😅🌷🤤😻🌟😅👏").unwrap();
+    assert_eq!(mb.to_bytes().unwrap(), b"\x00\x00yes mani !".to_vec());
+    assert_eq!(mb.to_char(), BASE256EMOJI);
+
+    for b in 0..=255_u8 {
+        let text = Multibase::with_char(BASE256EMOJI, &[b])
+            .unwrap()
+            .to_text()
+            .unwrap();
+        let data = Multibase::from_text(&text).unwrap().to_bytes().unwrap();
+        assert_eq!(data, vec![b]);
+    }
+
+    assert!(Multibase::from_text("🚀🏃x").is_err());
+    assert_eq!(
+        Multibase::from_text("🚀").unwrap().to_bytes().unwrap(),
+        vec![]
+    );
+
+    let mb = Multibase::with_char('z', b"yes mani !").unwrap();
+    assert_eq!(mb.to_char(), 'z');
+    assert_eq!(mb.to_base(), multibase::Base::Base58Btc);
+    assert_eq!(mb.try_to_base().unwrap(), multibase::Base::Base58Btc);
+}
+
+#[test]
+fn test_decode_into() {
+    let data = b"\x00yes mani !";
+    let mut buf = [0_u8; 64];
+    for (_, ch, _) in TABLE.iter() {
+        let text = match Multibase::with_char(*ch, data) {
+            Ok(mb) => mb.to_text().unwrap(),
+            Err(_) => continue, // base36 is not supported.
+        };
+        let n = Multibase::decode_into(&text, &mut buf).unwrap();
+        assert_eq!(&buf[..n], &data[..], "{:?}", ch);
+        assert!(
+            Multibase::decode_into(&text, &mut buf[..4]).is_err(),
+            "{:?}",
+            ch
+        );
+    }
+
+    assert!(Multibase::decode_into("", &mut buf).is_err());
+    assert!(Multibase::decode_into("bAA", &mut buf).is_err());
+    assert_eq!(Multibase::decode_into("f", &mut buf).unwrap(), 0);
+}
+
+#[test]
+fn test_proquint() {
+    let mb = Multibase::with_char(PROQUINT, b"yes mani !").unwrap();
+    assert_eq!(mb.to_text().unwrap(), "pro-lojoj-lasob-kujod-kunon-fabod");
+    assert_eq!(mb.to_char(), 'p');
+    assert!(mb.try_to_base().is_err());
+
+    // 127.0.0.1 and odd length.
+    let mb = Multibase::from_text("pro-lusab-babad").unwrap();
+    assert_eq!(mb.to_bytes().unwrap(), vec![127, 0, 0, 1]);
+    let mb = Multibase::from_text("pro-badun-kijug-fadot-kajov-kohob-fah").unwrap();
+    assert_eq!(mb.to_bytes().unwrap(), b"\x00yes mani !".to_vec());
+
+    for data in [&b""[..], &[0xff], &[0x12, 0x34, 0x56]].iter() {
+        let text = Multibase::with_char(PROQUINT, data)
+            .unwrap()
+            .to_text()
+            .unwrap();
+        let mut buf = [0_u8; 8];
+        let n = Multibase::decode_into(&text, &mut buf).unwrap();
+        assert_eq!(&buf[..n], *data, "{}", text);
+    }
+
+    assert!(Multibase::from_text("pro-lojo").is_err());
+    assert!(Multibase::from_text("pro-fad-lojoj").is_err());
+    assert!(Multibase::from_text("pro-lojox").is_err());
+    assert!(Multibase::from_text("pro-faz").is_err());
+    assert!(Multibase::from_text("pro-fad").is_err());
+    assert_eq!(
+        Multibase::with_char(PROQUINT, b"!")
+            .unwrap()
+            .to_text()
+            .unwrap(),
+        "pro-fah"
+    );
+    assert!(Multibase::from_text("plojoj").is_err());
+}
@@ -9,7 +9,7 @@
 
 use lazy_static::lazy_static;
 
-use std::{fmt, result};
+use std::{collections::HashMap, convert::TryFrom, fmt, result, str::FromStr};
 
 use crate::{Error, Result};
 
@@ -46,6 +46,14 @@ impl<'a> From<&'a Codepoint> for Multicodec {
     }
 }
 
+impl<'a> TryFrom<&'a str> for Multicodec {
+    type Error = Error;
+
+    fn try_from(name: &'a str) -> Result<Multicodec> {
+        Multicodec::from_name(name)
+    }
+}
+
 impl Multicodec {
     /// Create a new Multicodec from u128 code value. Returned value is useful
     /// for encoding multi-codec unsigned_varint integer value.
@@ -53,27 +61,170 @@ impl Multicodec {
         Ok(code.into())
     }
 
+    /// Create a Multicodec from its registered name, like "dag-cbor",
+    /// aliases are accepted. Lookup is on default [TABLE].
+    pub fn from_name(name: &str) -> Result<Multicodec> {
+        match Codepoint::from_name(name) {
+            Some(cp) => Ok(cp.into()),
+            None => err_at!(BadCodec, msg: "unknown multicodec name {:?}", name),
+        }
+    }
+
     /// Read the prefix bytes for encoded multi-codec unsigned_varint integer
     /// value and return remaining unparsed slice.
     ///
     /// Return [Error] if `buf's` content can't be recognised.
     pub fn decode(buf: &[u8]) -> Result<(Multicodec, &[u8])> {
-        let (code, rem) = err_at!(Invalid, unsigned_varint::decode::u128(buf))?;
+        use unsigned_varint::decode;
+
+        // registered codes fit in u64, most of them in a single byte, and
+        // this runs for every component of every multiaddr and CID.
+        if let Some(b) = buf.first() {
+            if decode::is_last(*b) {
+                return Ok((Multicodec { code: *b as u128 }, &buf[1..]));
+            }
+        }
+        // varint ending within 9 bytes holds at most 63 bits, u64 decode
+        // doesn't check for overflow on the 10th byte.
+        let (code, rem) = match buf.iter().take(9).any(|b| decode::is_last(*b)) {
+            true => {
+                let (code, rem) = err_at!(Invalid, decode::u64(buf))?;
+                (code as u128, rem)
+            }
+            false => err_at!(Invalid, decode::u128(buf))?,
+        };
         Ok((Multicodec { code }, rem))
     }
 
     /// Encode multi-codec unsigned_varint integer.
     pub fn encode(&self) -> Result<Vec<u8>> {
-        let mut buf: [u8; 19] = Default::default();
-        let data = unsigned_varint::encode::u128(self.code, &mut buf).to_vec();
-
+        let mut data = Vec::with_capacity(4);
+        self.encode_with(&mut data)?;
         Ok(data)
     }
 
+    /// Similar to [Self::encode], but append to `buf`, return the number of
+    /// bytes appended.
+    pub fn encode_with(&self, buf: &mut Vec<u8>) -> Result<usize> {
+        use unsigned_varint::encode;
+
+        let n = match self.code {
+            code if code < 0x80 => {
+                buf.push(code as u8);
+                1
+            }
+            code if code <= (u64::MAX as u128) => {
+                let mut scratch = encode::u64_buffer();
+                let data = encode::u64(code as u64, &mut scratch);
+                buf.extend_from_slice(data);
+                data.len()
+            }
+            code => {
+                let mut scratch = encode::u128_buffer();
+                let data = encode::u128(code, &mut scratch);
+                buf.extend_from_slice(data);
+                data.len()
+            }
+        };
+        Ok(n)
+    }
+
     /// Return the underlying code-value.
     pub fn to_code(&self) -> u128 {
         self.code
     }
+
+    /// Return the code-point from default [TABLE], None if the code is
+    /// not registered.
+    pub fn to_codepoint(&self) -> Option<&'static Codepoint> {
+        Codepoint::from_code(self.code)
+    }
+
+    /// Iterate over code-points in default [TABLE] tagged as `tag`,
+    /// including aliases.
+    pub fn codepoints_by_tag(tag: Tag) -> impl Iterator<Item = &'static Codepoint> {
+        TABLE.iter().filter(move |cp| cp.tag == tag)
+    }
+
+    /// Return the tag of this code from default [TABLE], None if the code
+    /// is not registered.
+    pub fn to_tag(&self) -> Option<Tag> {
+        self.to_codepoint().map(|cp| cp.tag)
+    }
+
+    /// Return whether this code is a registered hash function.
+    pub fn is_multihash(&self) -> bool {
+        self.to_tag() == Some(Tag::Multihash)
+    }
+
+    /// Return whether this code is a registered IPLD codec, or CID
+    /// version.
+    pub fn is_ipld(&self) -> bool {
+        self.to_tag() == Some(Tag::Ipld)
+    }
+
+    /// Return whether this code is a registered multiaddr protocol.
+    pub fn is_multiaddr(&self) -> bool {
+        self.to_tag() == Some(Tag::Multiaddr)
+    }
+}
+
+/// Tag of a code-point, groups code-points by their use.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Tag {
+    Multihash,
+    Multiaddr,
+    Ipld,
+    Key,
+    Serialization,
+    Namespace,
+    Multiformat,
+    Libp2p,
+    Filecoin,
+    Holochain,
+    Zeroxcert,
+}
+
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
+        let name = match self {
+            Tag::Multihash => "multihash",
+            Tag::Multiaddr => "multiaddr",
+            Tag::Ipld => "ipld",
+            Tag::Key => "key",
+            Tag::Serialization => "serialization",
+            Tag::Namespace => "namespace",
+            Tag::Multiformat => "multiformat",
+            Tag::Libp2p => "libp2p",
+            Tag::Filecoin => "filecoin",
+            Tag::Holochain => "holochain",
+            Tag::Zeroxcert => "zeroxcert",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Tag {
+    type Err = Error;
+
+    /// Parse tag as named in multicodec [TABLE].
+    fn from_str(s: &str) -> Result<Tag> {
+        let tag = match s {
+            "multihash" => Tag::Multihash,
+            "multiaddr" => Tag::Multiaddr,
+            "ipld" => Tag::Ipld,
+            "key" => Tag::Key,
+            "serialization" => Tag::Serialization,
+            "namespace" => Tag::Namespace,
+            "multiformat" => Tag::Multiformat,
+            "libp2p" => Tag::Libp2p,
+            "filecoin" => Tag::Filecoin,
+            "holochain" => Tag::Holochain,
+            "zeroxcert" => Tag::Zeroxcert,
+            _ => err_at!(BadCodec, msg: "unknown multicodec tag {:?}", s)?,
+        };
+        Ok(tag)
+    }
 }
 
 /// Type describing a single code-point in the multicodec table.
@@ -84,13 +235,116 @@ pub struct Codepoint {
     /// Name the code-point.
     pub name: String,
     /// Tag the code-point.
-    pub tag: String,
+    pub tag: Tag,
+}
+
+impl Codepoint {
+    /// Lookup default [TABLE] by `code`. Lookups use a hash map built once
+    /// and shared across threads.
+    pub fn from_code(code: u128) -> Option<&'static Codepoint> {
+        BY_CODE.get(&code).map(|off| &TABLE[*off])
+    }
+
+    /// Lookup default [TABLE] by `name`, like "dag-cbor". Lookups use a
+    /// hash map built once and shared across threads.
+    pub fn from_name(name: &str) -> Option<&'static Codepoint> {
+        BY_NAME.get(name).map(|off| &TABLE[*off])
+    }
+}
+
+/// First code in the private-use range, refer [Registry].
+pub const PRIVATE_USE_START: u128 = 0x300000;
+
+/// Last code in the private-use range, refer [Registry].
+pub const PRIVATE_USE_END: u128 = 0x3fffff;
+
+/// Code-point table that starts from default [TABLE] and can be extended
+/// with custom code-points at runtime, useful to experiment with draft
+/// codecs. Custom code-points must fall within the private-use range,
+/// [PRIVATE_USE_START] to [PRIVATE_USE_END], and their code and name
+/// must not clash with existing entries.
+#[derive(Clone, Default)]
+pub struct Registry {
+    custom: Vec<Codepoint>,
+    code_index: HashMap<u128, usize>,
+    name_index: HashMap<String, usize>,
+}
+
+impl fmt::Debug for Registry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
+        let names: Vec<&String> = self.custom.iter().map(|cp| &cp.name).collect();
+        write!(f, "Registry<{},{:?}>", TABLE.len(), names)
+    }
+}
+
+impl Registry {
+    /// Create a registry with code-points from default [TABLE].
+    pub fn new() -> Registry {
+        Registry::default()
+    }
+
+    /// Register a custom code-point, refer [Registry] for constraints.
+    pub fn register(&mut self, cp: Codepoint) -> Result<&mut Self> {
+        if cp.code < PRIVATE_USE_START || cp.code > PRIVATE_USE_END {
+            err_at!(BadCodec, msg: "code 0x{:x} not in private-use range", cp.code)?
+        }
+        if let Some(old) = self.by_code(cp.code) {
+            err_at!(BadCodec, msg: "code 0x{:x} registered as {:?}", cp.code, old.name)?
+        }
+        if let Some(old) = self.by_name(&cp.name) {
+            err_at!(BadCodec, msg: "name {:?} registered as 0x{:x}", cp.name, old.code)?
+        }
+
+        let off = self.custom.len();
+        self.code_index.insert(cp.code, off);
+        self.name_index.insert(cp.name.clone(), off);
+        self.custom.push(cp);
+        Ok(self)
+    }
+
+    /// Lookup by `code`, default [TABLE] first and then custom
+    /// code-points.
+    pub fn by_code(&self, code: u128) -> Option<&Codepoint> {
+        match Codepoint::from_code(code) {
+            Some(cp) => Some(cp),
+            None => self.code_index.get(&code).map(|off| &self.custom[*off]),
+        }
+    }
+
+    /// Lookup by `name`, default [TABLE] first and then custom
+    /// code-points.
+    pub fn by_name(&self, name: &str) -> Option<&Codepoint> {
+        match Codepoint::from_name(name) {
+            Some(cp) => Some(cp),
+            None => self.name_index.get(name).map(|off| &self.custom[*off]),
+        }
+    }
+
+    /// Return all code-points tagged as `tag`, like [Tag::Multihash].
+    pub fn by_tag(&self, tag: Tag) -> Vec<&Codepoint> {
+        self.iter().filter(|cp| cp.tag == tag).collect()
+    }
+
+    /// Iterate over all code-points, default [TABLE] followed by custom
+    /// code-points in the order they were registered.
+    pub fn iter(&self) -> impl Iterator<Item = &Codepoint> {
+        TABLE.iter().chain(self.custom.iter())
+    }
+
+    /// Return the number of code-points, including aliases.
+    pub fn len(&self) -> usize {
+        TABLE.len() + self.custom.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 }
 
 macro_rules! code_points {
     ($(
         #[$doc:meta]
-        ($label:ident, $code:expr, $name:expr, $tag:expr),
+        ($label:ident, $code:expr, $name:expr, $tag:ident),
     )*) => (
         $(
             #[$doc]
@@ -99,13 +353,13 @@ macro_rules! code_points {
         /// Alias, for P2P for backward compatibility
         pub const IPFS: u128 = 0x01a5;
 
+        // print registered name, hex code for unknown codes.
         impl fmt::Display for Multicodec {
             fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
-                let name = match self.code {
-                    $( $code => $name, )*
-                    _ => "@#bad-code#@",
-                };
-                write!(f, "{}", name)
+                match self.code {
+                    $( $code => write!(f, "{}", $name), )*
+                    code => write!(f, "0x{:x}", code),
+                }
             }
         }
 
@@ -121,31 +375,49 @@ macro_rules! code_points {
                     $(Codepoint {
                         code: $code,
                         name: $name.to_string(),
-                        tag: $tag.to_string()
+                        tag: Tag::$tag,
                     },)*
                 ];
                 // aliases
                 table.push(Codepoint {
                     code: 0x01a5,
                     name: "ipfs".to_string(),
-                    tag: "multiaddr".to_string(),
+                    tag: Tag::Multiaddr,
                 });
                 table
             };
 
+            // Index into TABLE keyed by code, aliases don't shadow the
+            // registered name of a code.
+            static ref BY_CODE: HashMap<u128, usize> = {
+                let mut index = HashMap::with_capacity(TABLE.len());
+                for (off, cp) in TABLE.iter().enumerate() {
+                    index.entry(cp.code).or_insert(off);
+                }
+                index
+            };
+
+            // Index into TABLE keyed by name, including aliases.
+            static ref BY_NAME: HashMap<String, usize> = {
+                let mut index = HashMap::with_capacity(TABLE.len());
+                for (off, cp) in TABLE.iter().enumerate() {
+                    index.entry(cp.name.clone()).or_insert(off);
+                }
+                index
+            };
+
             // Pre-sorted table of multihash code values, only codes tagged as
-            // "multihash" will be gathered in this table.
+            // Tag::Multihash will be gathered in this table.
             static ref TABLE_MULTIHASH: Vec<Codepoint> = {
                 let mut codes = Vec::default();
                 $(
-                    match $tag {
-                        "multihash" => codes.push(Codepoint {
+                    if matches!(Tag::$tag, Tag::Multihash) {
+                        codes.push(Codepoint {
                             code: $code,
                             name: $name.to_string(),
-                            tag: $tag.to_string()
-                        }),
-                        _ => ()
-                    };
+                            tag: Tag::$tag,
+                        })
+                    }
                 )*
                 codes
             };
@@ -155,964 +427,964 @@ macro_rules! code_points {
 
 code_points![
     /// _multihash_, Raw binary
-    (IDENTITY, 0x00, "identity", "multihash"),
+    (IDENTITY, 0x00, "identity", Multihash),
     /// _ipld_, Content identifier for IPFS, version 1
-    (CID_V1, 0x01, "cidv1", "ipld"),
+    (CID_V1, 0x01, "cidv1", Ipld),
     /// _ipld_, Content identifier for IPFS, version 2
-    (CID_V2, 0x02, "cidv2", "ipld"),
+    (CID_V2, 0x02, "cidv2", Ipld),
     /// _ipld_, Content identifier for IPFS, version 3
-    (CID_V3, 0x03, "cidv3", "ipld"),
+    (CID_V3, 0x03, "cidv3", Ipld),
     /// _multiaddr_, Internet Protocol version 4
-    (IP4, 0x04, "ip4", "multiaddr"),
+    (IP4, 0x04, "ip4", Multiaddr),
     /// _multiaddr_, Transport Control Protocol
-    (TCP, 0x06, "tcp", "multiaddr"),
+    (TCP, 0x06, "tcp", Multiaddr),
     /// _multihash_, Secure Hash Algorithm 1
-    (SHA1, 0x11, "sha1", "multihash"),
+    (SHA1, 0x11, "sha1", Multihash),
     /// _multihash_, Secure Hash Algorithm 2, 256 bits
-    (SHA2_256, 0x12, "sha2-256", "multihash"),
+    (SHA2_256, 0x12, "sha2-256", Multihash),
     /// _multihash_, Secure Hash Algorithm 2, 512 bits
-    (SHA2_512, 0x13, "sha2-512", "multihash"),
+    (SHA2_512, 0x13, "sha2-512", Multihash),
     /// _multihash_, Secure Hash Algorithm 3, 512 bits
-    (SHA3_512, 0x14, "sha3-512", "multihash"),
+    (SHA3_512, 0x14, "sha3-512", Multihash),
     /// _multihash_, Secure Hash Algorithm 3, 384 bits
-    (SHA3_384, 0x15, "sha3-384", "multihash"),
+    (SHA3_384, 0x15, "sha3-384", Multihash),
     /// _multihash_, Secure Hash Algorithm 3, 256 bits
-    (SHA3_256, 0x16, "sha3-256", "multihash"),
+    (SHA3_256, 0x16, "sha3-256", Multihash),
     /// _multihash_, Secure Hash Algorithm 3, 224 bits
-    (SHA3_224, 0x17, "sha3-224", "multihash"),
+    (SHA3_224, 0x17, "sha3-224", Multihash),
     /// _multihash_, Secure Hash Algorithm 3, 128 bit security and variable output.
-    (SHAKE_128, 0x18, "shake-128", "multihash"),
+    (SHAKE_128, 0x18, "shake-128", Multihash),
     /// _multihash_, Secure Hash Algorithm 3, 256 bit security and variable output.
-    (SHAKE_256, 0x19, "shake-256", "multihash"),
+    (SHAKE_256, 0x19, "shake-256", Multihash),
     /// _multihash_, Secure Hash Algorithm 3
-    (KECCAK_224, 0x1a, "keccak-224", "multihash"),
+    (KECCAK_224, 0x1a, "keccak-224", Multihash),
     /// _multihash_, Secure Hash Algorithm 3
-    (KECCAK_256, 0x1b, "keccak-256", "multihash"),
+    (KECCAK_256, 0x1b, "keccak-256", Multihash),
     /// _multihash_, Secure Hash Algorithm 3
-    (KECCAK_384, 0x1c, "keccak-384", "multihash"),
+    (KECCAK_384, 0x1c, "keccak-384", Multihash),
     /// _multihash_, Secure Hash Algorithm 3
-    (KECCAK_512, 0x1d, "keccak-512", "multihash"),
+    (KECCAK_512, 0x1d, "keccak-512", Multihash),
     /// _multihash_, Blake3 Algorithm
-    (BLAKE3, 0x1e, "blake3", "multihash"),
+    (BLAKE3, 0x1e, "blake3", Multihash),
     /// _multiaddr_, Datagram congestion protocol
-    (DCCP, 0x21, "dccp", "multiaddr"),
+    (DCCP, 0x21, "dccp", Multiaddr),
     /// _multihash_, Murmur3 hash algorithm, 128-bit security
-    (MURMUR3_128, 0x22, "murmur3-128", "multihash"),
+    (MURMUR3_128, 0x22, "murmur3-128", Multihash),
     /// _multihash_, Murmur3 hash algorithm, 32-bit security
-    (MURMUR3_32, 0x23, "murmur3-32", "multihash"),
+    (MURMUR3_32, 0x23, "murmur3-32", Multihash),
     /// _multiaddr_, Internet Protocol version 6
-    (IP6, 0x29, "ip6", "multiaddr"),
+    (IP6, 0x29, "ip6", Multiaddr),
     /// _multiaddr_
-    (IP6ZONE, 0x2a, "ip6zone", "multiaddr"),
+    (IP6ZONE, 0x2a, "ip6zone", Multiaddr),
     /// _namespace_
-    (PATH, 0x2f, "path", "namespace"),
+    (PATH, 0x2f, "path", Namespace),
     /// _multiformat_, multicodec specification from [multiformats][http://multiformats.io]
-    (MULTICODEC, 0x30, "multicodec", "multiformat"),
+    (MULTICODEC, 0x30, "multicodec", Multiformat),
     /// _multiformat_, multihash specification from [multiformats][http://multiformats.io]
-    (MULTIHASH, 0x31, "multihash", "multiformat"),
+    (MULTIHASH, 0x31, "multihash", Multiformat),
     /// _multiformat_, multiaddr specification from [multiformats][http://multiformats.io]
-    (MULTIADDR, 0x32, "multiaddr", "multiformat"),
+    (MULTIADDR, 0x32, "multiaddr", Multiformat),
     /// _multiformat_, multibase specification from [multiformats][http://multiformats.io]
-    (MULTIBASE, 0x33, "multibase", "multiformat"),
+    (MULTIBASE, 0x33, "multibase", Multiformat),
     /// _multiaddr_, Domain name system
-    (DNS, 0x35, "dns", "multiaddr"),
+    (DNS, 0x35, "dns", Multiaddr),
     /// _multiaddr_
-    (DNS4, 0x36, "dns4", "multiaddr"),
+    (DNS4, 0x36, "dns4", Multiaddr),
     /// _multiaddr_
-    (DNS6, 0x37, "dns6", "multiaddr"),
+    (DNS6, 0x37, "dns6", Multiaddr),
     /// _multiaddr_
-    (DNSADDR, 0x38, "dnsaddr", "multiaddr"),
+    (DNSADDR, 0x38, "dnsaddr", Multiaddr),
     /// _serialization_, Protocol buffer
-    (PROTOBUF, 0x50, "protobuf", "serialization"),
+    (PROTOBUF, 0x50, "protobuf", Serialization),
     /// _serialization_, Concise Binary Object Representation
-    (CBOR, 0x51, "cbor", "serialization"),
+    (CBOR, 0x51, "cbor", Serialization),
     /// _ipld_
-    (RAW, 0x55, "raw", "ipld"),
+    (RAW, 0x55, "raw", Ipld),
     /// _multihash_
-    (DBL_SHA2_256, 0x56, "dbl-sha2-256", "multihash"),
+    (DBL_SHA2_256, 0x56, "dbl-sha2-256", Multihash),
     /// _serialization_
-    (RLP, 0x60, "rlp", "serialization"),
+    (RLP, 0x60, "rlp", Serialization),
     /// _serialization_
-    (BENCODE, 0x63, "bencode", "serialization"),
+    (BENCODE, 0x63, "bencode", Serialization),
     /// _ipld_
-    (DAG_PB, 0x70, "dag-pb", "ipld"),
+    (DAG_PB, 0x70, "dag-pb", Ipld),
     /// _ipld_
-    (DAG_CBOR, 0x71, "dag-cbor", "ipld"),
+    (DAG_CBOR, 0x71, "dag-cbor", Ipld),
     /// _ipld_
-    (LIBP2P_KEY, 0x72, "libp2p-key", "ipld"),
+    (LIBP2P_KEY, 0x72, "libp2p-key", Ipld),
     /// _ipld_
-    (GIT_RAW, 0x78, "git-raw", "ipld"),
+    (GIT_RAW, 0x78, "git-raw", Ipld),
     /// _ipld_
-    (TORRENT_INFO, 0x7b, "torrent-info", "ipld"),
+    (TORRENT_INFO, 0x7b, "torrent-info", Ipld),
     /// _ipld_
-    (TORRENT_FILE, 0x7c, "torrent-file", "ipld"),
+    (TORRENT_FILE, 0x7c, "torrent-file", Ipld),
     /// _ipld_
-    (LEOFCOIN_BLOCK, 0x81, "leofcoin-block", "ipld"),
+    (LEOFCOIN_BLOCK, 0x81, "leofcoin-block", Ipld),
     /// _ipld_
-    (LEOFCOIN_TX, 0x82, "leofcoin-tx", "ipld"),
+    (LEOFCOIN_TX, 0x82, "leofcoin-tx", Ipld),
     /// _ipld_
-    (LEOFCOIN_PR, 0x83, "leofcoin-pr", "ipld"),
+    (LEOFCOIN_PR, 0x83, "leofcoin-pr", Ipld),
     /// _multiaddr_
-    (SCTP, 0x84, "sctp", "multiaddr"),
+    (SCTP, 0x84, "sctp", Multiaddr),
     /// _ipld_
-    (DAG_JOSE, 0x85, "dag-jose", "ipld"),
+    (DAG_JOSE, 0x85, "dag-jose", Ipld),
     /// _ipld_
-    (DAG_COSE, 0x86, "dag-cose", "ipld"),
+    (DAG_COSE, 0x86, "dag-cose", Ipld),
     /// _ipld_
-    (ETH_BLOCK, 0x90, "eth-block", "ipld"),
+    (ETH_BLOCK, 0x90, "eth-block", Ipld),
     /// _ipld_
-    (ETH_BLOCK_LIST, 0x91, "eth-block-list", "ipld"),
+    (ETH_BLOCK_LIST, 0x91, "eth-block-list", Ipld),
     /// _ipld_
-    (ETH_TX_TRIE, 0x92, "eth-tx-trie", "ipld"),
+    (ETH_TX_TRIE, 0x92, "eth-tx-trie", Ipld),
     /// _ipld_
-    (ETH_TX, 0x93, "eth-tx", "ipld"),
+    (ETH_TX, 0x93, "eth-tx", Ipld),
     /// _ipld_
-    (ETH_TX_RECEIPT_TRIE, 0x94, "eth-tx-receipt-trie", "ipld"),
+    (ETH_TX_RECEIPT_TRIE, 0x94, "eth-tx-receipt-trie", Ipld),
     /// _ipld_
-    (ETH_TX_RECEIPT, 0x95, "eth-tx-receipt", "ipld"),
+    (ETH_TX_RECEIPT, 0x95, "eth-tx-receipt", Ipld),
     /// _ipld_
-    (ETH_STATE_TRIE, 0x96, "eth-state-trie", "ipld"),
+    (ETH_STATE_TRIE, 0x96, "eth-state-trie", Ipld),
     /// _ipld_
-    (ETH_ACCOUNT_SNAPSHOT, 0x97, "eth-account-snapshot", "ipld"),
+    (ETH_ACCOUNT_SNAPSHOT, 0x97, "eth-account-snapshot", Ipld),
     /// _ipld_
-    (ETH_STORAGE_TRIE, 0x98, "eth-storage-trie", "ipld"),
+    (ETH_STORAGE_TRIE, 0x98, "eth-storage-trie", Ipld),
     /// _ipld_
-    (BITCOIN_BLOCK, 0xb0, "bitcoin-block", "ipld"),
+    (BITCOIN_BLOCK, 0xb0, "bitcoin-block", Ipld),
     /// _ipld_
-    (BITCOIN_TX, 0xb1, "bitcoin-tx", "ipld"),
+    (BITCOIN_TX, 0xb1, "bitcoin-tx", Ipld),
     /// _ipld_
     (
         BITCOIN_WITNESS_COMMITMENT,
         0xb2,
         "bitcoin-witness-commitment",
-        "ipld"
+        Ipld
     ),
     /// _ipld_
-    (ZCASH_BLOCK, 0xc0, "zcash-block", "ipld"),
+    (ZCASH_BLOCK, 0xc0, "zcash-block", Ipld),
     /// _ipld_
-    (ZCASH_TX, 0xc1, "zcash-tx", "ipld"),
+    (ZCASH_TX, 0xc1, "zcash-tx", Ipld),
     /// _namespace_, Ceramic Document Id
-    (DOCID, 0xce, "docid", "namespace"),
+    (DOCID, 0xce, "docid", Namespace),
     /// _ipld_
-    (STELLAR_BLOCK, 0xd0, "stellar-block", "ipld"),
+    (STELLAR_BLOCK, 0xd0, "stellar-block", Ipld),
     /// _ipld_
-    (STELLAR_TX, 0xd1, "stellar-tx", "ipld"),
+    (STELLAR_TX, 0xd1, "stellar-tx", Ipld),
     /// _multihash_
-    (MD4, 0xd4, "md4", "multihash"),
+    (MD4, 0xd4, "md4", Multihash),
     /// _multihash_
-    (MD5, 0xd5, "md5", "multihash"),
+    (MD5, 0xd5, "md5", Multihash),
     /// _multihash_
-    (BMT, 0xd6, "bmt", "multihash"),
+    (BMT, 0xd6, "bmt", Multihash),
     /// _ipld_
-    (DECRED_BLOCK, 0xe0, "decred-block", "ipld"),
+    (DECRED_BLOCK, 0xe0, "decred-block", Ipld),
     /// _ipld_
-    (DECRED_TX, 0xe1, "decred-tx", "ipld"),
+    (DECRED_TX, 0xe1, "decred-tx", Ipld),
     /// _namespace_
-    (IPLD_NS, 0xe2, "ipld-ns", "namespace"),
+    (IPLD_NS, 0xe2, "ipld-ns", Namespace),
     /// _namespace_
-    (IPFS_NS, 0xe3, "ipfs-ns", "namespace"),
+    (IPFS_NS, 0xe3, "ipfs-ns", Namespace),
     /// _namespace_
-    (SWARM_NS, 0xe4, "swarm-ns", "namespace"),
+    (SWARM_NS, 0xe4, "swarm-ns", Namespace),
     /// _namespace_
-    (IPNS_NS, 0xe5, "ipns-ns", "namespace"),
+    (IPNS_NS, 0xe5, "ipns-ns", Namespace),
     /// _namespace_
-    (ZERONET, 0xe6, "zeronet", "namespace"),
+    (ZERONET, 0xe6, "zeronet", Namespace),
     /// _key_
-    (SECP256K1_PUB, 0xe7, "secp256k1-pub", "key"),
+    (SECP256K1_PUB, 0xe7, "secp256k1-pub", Key),
     /// _key_
-    (BLS12_381_G1_PUB, 0xea, "bls12_381-g1-pub", "key"),
+    (BLS12_381_G1_PUB, 0xea, "bls12_381-g1-pub", Key),
     /// _key_
-    (BLS12_381_G2_PUB, 0xeb, "bls12_381-g2-pub", "key"),
+    (BLS12_381_G2_PUB, 0xeb, "bls12_381-g2-pub", Key),
     /// _key_
-    (X25519_PUB, 0xec, "x25519-pub", "key"),
+    (X25519_PUB, 0xec, "x25519-pub", Key),
     /// _key_
-    (ED25519_PUB, 0xed, "ed25519-pub", "key"),
+    (ED25519_PUB, 0xed, "ed25519-pub", Key),
     /// _key_, BLS12-381 concatenated public keys in both the G1 and G2 fields
-    (BLS12_381_G1G2_PUB, 0xee, "bls12_381-g1g2-pub", "key"),
+    (BLS12_381_G1G2_PUB, 0xee, "bls12_381-g1g2-pub", Key),
     /// _ipld_
-    (DASH_BLOCK, 0xf0, "dash-block", "ipld"),
+    (DASH_BLOCK, 0xf0, "dash-block", Ipld),
     /// _ipld_
-    (DASH_TX, 0xf1, "dash-tx", "ipld"),
+    (DASH_TX, 0xf1, "dash-tx", Ipld),
     /// _ipld_
-    (SWARM_MANIFEST, 0xfa, "swarm-manifest", "ipld"),
+    (SWARM_MANIFEST, 0xfa, "swarm-manifest", Ipld),
     /// _ipld_
-    (SWARM_FEED, 0xfb, "swarm-feed", "ipld"),
+    (SWARM_FEED, 0xfb, "swarm-feed", Ipld),
     /// _multiaddr_
-    (UDP, 0x0111, "udp", "multiaddr"),
+    (UDP, 0x0111, "udp", Multiaddr),
     /// _multiaddr_
-    (P2P_WEBRTC_STAR, 0x0113, "p2p-webrtc-star", "multiaddr"),
+    (P2P_WEBRTC_STAR, 0x0113, "p2p-webrtc-star", Multiaddr),
     /// _multiaddr_
-    (P2P_WEBRTC_DIRECT, 0x0114, "p2p-webrtc-direct", "multiaddr"),
+    (P2P_WEBRTC_DIRECT, 0x0114, "p2p-webrtc-direct", Multiaddr),
     /// _multiaddr_
-    (P2P_STARDUST, 0x0115, "p2p-stardust", "multiaddr"),
+    (P2P_STARDUST, 0x0115, "p2p-stardust", Multiaddr),
     /// _multiaddr_
-    (P2P_CIRCUIT, 0x0122, "p2p-circuit", "multiaddr"),
+    (P2P_CIRCUIT, 0x0122, "p2p-circuit", Multiaddr),
     /// _ipld_
-    (DAG_JSON, 0x0129, "dag-json", "ipld"),
+    (DAG_JSON, 0x0129, "dag-json", Ipld),
     /// _multiaddr_
-    (UDT, 0x012d, "udt", "multiaddr"),
+    (UDT, 0x012d, "udt", Multiaddr),
     /// _multiaddr_
-    (UTP, 0x012e, "utp", "multiaddr"),
+    (UTP, 0x012e, "utp", Multiaddr),
     /// _multiaddr_
-    (UNIX, 0x0190, "unix", "multiaddr"),
+    (UNIX, 0x0190, "unix", Multiaddr),
     /// _multiaddr_
-    (P2P, 0x01a5, "p2p", "multiaddr"),
+    (P2P, 0x01a5, "p2p", Multiaddr),
     /// _multiaddr_
-    (HTTPS, 0x01bb, "https", "multiaddr"),
+    (HTTPS, 0x01bb, "https", Multiaddr),
     /// _multiaddr_
-    (ONION, 0x01bc, "onion", "multiaddr"),
+    (ONION, 0x01bc, "onion", Multiaddr),
     /// _multiaddr_
-    (ONION3, 0x01bd, "onion3", "multiaddr"),
+    (ONION3, 0x01bd, "onion3", Multiaddr),
     /// _multiaddr_
-    (GARLIC64, 0x01be, "garlic64", "multiaddr"),
+    (GARLIC64, 0x01be, "garlic64", Multiaddr),
     /// _multiaddr_
-    (GARLIC32, 0x01bf, "garlic32", "multiaddr"),
+    (GARLIC32, 0x01bf, "garlic32", Multiaddr),
     /// _multiaddr_
-    (TLS, 0x01c0, "tls", "multiaddr"),
+    (TLS, 0x01c0, "tls", Multiaddr),
     /// _multiaddr_
-    (QUIC, 0x01cc, "quic", "multiaddr"),
+    (QUIC, 0x01cc, "quic", Multiaddr),
     /// _multiaddr_
-    (WS, 0x01dd, "ws", "multiaddr"),
+    (WS, 0x01dd, "ws", Multiaddr),
     /// _multiaddr_
-    (WSS, 0x01de, "wss", "multiaddr"),
+    (WSS, 0x01de, "wss", Multiaddr),
     /// _multiaddr_
     (
         P2P_WEBSOCKET_STAR,
         0x01df,
         "p2p-websocket-star",
-        "multiaddr"
+        Multiaddr
     ),
     /// _multiaddr_
-    (HTTP, 0x01e0, "http", "multiaddr"),
+    (HTTP, 0x01e0, "http", Multiaddr),
     /// _serialization_
-    (JSON, 0x0200, "json", "serialization"),
+    (JSON, 0x0200, "json", Serialization),
     /// _serialization_
-    (MESSAGEPACK, 0x0201, "messagepack", "serialization"),
+    (MESSAGEPACK, 0x0201, "messagepack", Serialization),
     /// _libp2p_
-    (LIBP2P_PEER_RECORD, 0x0301, "libp2p-peer-record", "libp2p"),
+    (LIBP2P_PEER_RECORD, 0x0301, "libp2p-peer-record", Libp2p),
     /// _multihash_
     (
         SHA2_256_TRUNC254_PADDED,
         0x1012,
         "sha2-256-trunc254-padded",
-        "multihash"
+        Multihash
     ),
     /// _multihash_
-    (RIPEMD_128, 0x1052, "ripemd-128", "multihash"),
+    (RIPEMD_128, 0x1052, "ripemd-128", Multihash),
     /// _multihash_
-    (RIPEMD_160, 0x1053, "ripemd-160", "multihash"),
+    (RIPEMD_160, 0x1053, "ripemd-160", Multihash),
     /// _multihash_
-    (RIPEMD_256, 0x1054, "ripemd-256", "multihash"),
+    (RIPEMD_256, 0x1054, "ripemd-256", Multihash),
     /// _multihash_
-    (RIPEMD_320, 0x1055, "ripemd-320", "multihash"),
+    (RIPEMD_320, 0x1055, "ripemd-320", Multihash),
     /// _multihash_
-    (X11, 0x1100, "x11", "multihash"),
+    (X11, 0x1100, "x11", Multihash),
     /// _key_
-    (P256_PUB, 0x1200, "p256-pub", "key"),
+    (P256_PUB, 0x1200, "p256-pub", Key),
     /// _key_
-    (P384_PUB, 0x1201, "p384-pub", "key"),
+    (P384_PUB, 0x1201, "p384-pub", Key),
     /// _key_
-    (P521_PUB, 0x1202, "p521-pub", "key"),
+    (P521_PUB, 0x1202, "p521-pub", Key),
     /// _key_
-    (ED448_PUB, 0x1203, "ed448-pub", "key"),
+    (ED448_PUB, 0x1203, "ed448-pub", Key),
     /// _key_
-    (X448_PUB, 0x1204, "x448-pub", "key"),
+    (X448_PUB, 0x1204, "x448-pub", Key),
     /// _key_, Ed25519 private key
-    (ED25519_PRIV, 0x1300, "ed25519-priv", "key"),
+    (ED25519_PRIV, 0x1300, "ed25519-priv", Key),
     /// _multihash_
-    (KANGAROOTWELVE, 0x1d01, "kangarootwelve", "multihash"),
+    (KANGAROOTWELVE, 0x1d01, "kangarootwelve", Multihash),
     /// _multihash_
-    (SM3_256, 0x534d, "sm3-256", "multihash"),
+    (SM3_256, 0x534d, "sm3-256", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 8-bit digest.
-    (BLAKE2B_8, 0xb201, "blake2b-8", "multihash"),
+    (BLAKE2B_8, 0xb201, "blake2b-8", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 16-bit digest.
-    (BLAKE2B_16, 0xb202, "blake2b-16", "multihash"),
+    (BLAKE2B_16, 0xb202, "blake2b-16", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 24-bit digest.
-    (BLAKE2B_24, 0xb203, "blake2b-24", "multihash"),
+    (BLAKE2B_24, 0xb203, "blake2b-24", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 32-bit digest.
-    (BLAKE2B_32, 0xb204, "blake2b-32", "multihash"),
+    (BLAKE2B_32, 0xb204, "blake2b-32", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 40-bit digest.
-    (BLAKE2B_40, 0xb205, "blake2b-40", "multihash"),
+    (BLAKE2B_40, 0xb205, "blake2b-40", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 48-bit digest.
-    (BLAKE2B_48, 0xb206, "blake2b-48", "multihash"),
+    (BLAKE2B_48, 0xb206, "blake2b-48", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 56-bit digest.
-    (BLAKE2B_56, 0xb207, "blake2b-56", "multihash"),
+    (BLAKE2B_56, 0xb207, "blake2b-56", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 64-bit digest.
-    (BLAKE2B_64, 0xb208, "blake2b-64", "multihash"),
+    (BLAKE2B_64, 0xb208, "blake2b-64", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 72-bit digest.
-    (BLAKE2B_72, 0xb209, "blake2b-72", "multihash"),
+    (BLAKE2B_72, 0xb209, "blake2b-72", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 80-bit digest.
-    (BLAKE2B_80, 0xb20a, "blake2b-80", "multihash"),
+    (BLAKE2B_80, 0xb20a, "blake2b-80", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 88-bit digest.
-    (BLAKE2B_88, 0xb20b, "blake2b-88", "multihash"),
+    (BLAKE2B_88, 0xb20b, "blake2b-88", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 96-bit digest.
-    (BLAKE2B_96, 0xb20c, "blake2b-96", "multihash"),
+    (BLAKE2B_96, 0xb20c, "blake2b-96", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 104-bit digest.
-    (BLAKE2B_104, 0xb20d, "blake2b-104", "multihash"),
+    (BLAKE2B_104, 0xb20d, "blake2b-104", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 112-bit digest.
-    (BLAKE2B_112, 0xb20e, "blake2b-112", "multihash"),
+    (BLAKE2B_112, 0xb20e, "blake2b-112", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 120-bit digest.
-    (BLAKE2B_120, 0xb20f, "blake2b-120", "multihash"),
+    (BLAKE2B_120, 0xb20f, "blake2b-120", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 128-bit digest.
-    (BLAKE2B_128, 0xb210, "blake2b-128", "multihash"),
+    (BLAKE2B_128, 0xb210, "blake2b-128", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 136-bit digest.
-    (BLAKE2B_136, 0xb211, "blake2b-136", "multihash"),
+    (BLAKE2B_136, 0xb211, "blake2b-136", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 144-bit digest.
-    (BLAKE2B_144, 0xb212, "blake2b-144", "multihash"),
+    (BLAKE2B_144, 0xb212, "blake2b-144", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 152-bit digest.
-    (BLAKE2B_152, 0xb213, "blake2b-152", "multihash"),
+    (BLAKE2B_152, 0xb213, "blake2b-152", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 160-bit digest.
-    (BLAKE2B_160, 0xb214, "blake2b-160", "multihash"),
+    (BLAKE2B_160, 0xb214, "blake2b-160", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 168-bit digest.
-    (BLAKE2B_168, 0xb215, "blake2b-168", "multihash"),
+    (BLAKE2B_168, 0xb215, "blake2b-168", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 176-bit digest.
-    (BLAKE2B_176, 0xb216, "blake2b-176", "multihash"),
+    (BLAKE2B_176, 0xb216, "blake2b-176", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 184-bit digest.
-    (BLAKE2B_184, 0xb217, "blake2b-184", "multihash"),
+    (BLAKE2B_184, 0xb217, "blake2b-184", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 192-bit digest.
-    (BLAKE2B_192, 0xb218, "blake2b-192", "multihash"),
+    (BLAKE2B_192, 0xb218, "blake2b-192", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 200-bit digest.
-    (BLAKE2B_200, 0xb219, "blake2b-200", "multihash"),
+    (BLAKE2B_200, 0xb219, "blake2b-200", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 208-bit digest.
-    (BLAKE2B_208, 0xb21a, "blake2b-208", "multihash"),
+    (BLAKE2B_208, 0xb21a, "blake2b-208", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 216-bit digest.
-    (BLAKE2B_216, 0xb21b, "blake2b-216", "multihash"),
+    (BLAKE2B_216, 0xb21b, "blake2b-216", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 224-bit digest.
-    (BLAKE2B_224, 0xb21c, "blake2b-224", "multihash"),
+    (BLAKE2B_224, 0xb21c, "blake2b-224", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 232-bit digest.
-    (BLAKE2B_232, 0xb21d, "blake2b-232", "multihash"),
+    (BLAKE2B_232, 0xb21d, "blake2b-232", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 240-bit digest.
-    (BLAKE2B_240, 0xb21e, "blake2b-240", "multihash"),
+    (BLAKE2B_240, 0xb21e, "blake2b-240", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 248-bit digest.
-    (BLAKE2B_248, 0xb21f, "blake2b-248", "multihash"),
+    (BLAKE2B_248, 0xb21f, "blake2b-248", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 256-bit digest.
-    (BLAKE2B_256, 0xb220, "blake2b-256", "multihash"),
+    (BLAKE2B_256, 0xb220, "blake2b-256", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 264-bit digest.
-    (BLAKE2B_264, 0xb221, "blake2b-264", "multihash"),
+    (BLAKE2B_264, 0xb221, "blake2b-264", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 272-bit digest.
-    (BLAKE2B_272, 0xb222, "blake2b-272", "multihash"),
+    (BLAKE2B_272, 0xb222, "blake2b-272", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 280-bit digest.
-    (BLAKE2B_280, 0xb223, "blake2b-280", "multihash"),
+    (BLAKE2B_280, 0xb223, "blake2b-280", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 288-bit digest.
-    (BLAKE2B_288, 0xb224, "blake2b-288", "multihash"),
+    (BLAKE2B_288, 0xb224, "blake2b-288", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 296-bit digest.
-    (BLAKE2B_296, 0xb225, "blake2b-296", "multihash"),
+    (BLAKE2B_296, 0xb225, "blake2b-296", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 304-bit digest.
-    (BLAKE2B_304, 0xb226, "blake2b-304", "multihash"),
+    (BLAKE2B_304, 0xb226, "blake2b-304", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 312-bit digest.
-    (BLAKE2B_312, 0xb227, "blake2b-312", "multihash"),
+    (BLAKE2B_312, 0xb227, "blake2b-312", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 320-bit digest.
-    (BLAKE2B_320, 0xb228, "blake2b-320", "multihash"),
+    (BLAKE2B_320, 0xb228, "blake2b-320", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 328-bit digest.
-    (BLAKE2B_328, 0xb229, "blake2b-328", "multihash"),
+    (BLAKE2B_328, 0xb229, "blake2b-328", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 336-bit digest.
-    (BLAKE2B_336, 0xb22a, "blake2b-336", "multihash"),
+    (BLAKE2B_336, 0xb22a, "blake2b-336", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 344-bit digest.
-    (BLAKE2B_344, 0xb22b, "blake2b-344", "multihash"),
+    (BLAKE2B_344, 0xb22b, "blake2b-344", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 352-bit digest.
-    (BLAKE2B_352, 0xb22c, "blake2b-352", "multihash"),
+    (BLAKE2B_352, 0xb22c, "blake2b-352", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 360-bit digest.
-    (BLAKE2B_360, 0xb22d, "blake2b-360", "multihash"),
+    (BLAKE2B_360, 0xb22d, "blake2b-360", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 368-bit digest.
-    (BLAKE2B_368, 0xb22e, "blake2b-368", "multihash"),
+    (BLAKE2B_368, 0xb22e, "blake2b-368", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 376-bit digest.
-    (BLAKE2B_376, 0xb22f, "blake2b-376", "multihash"),
+    (BLAKE2B_376, 0xb22f, "blake2b-376", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 384-bit digest.
-    (BLAKE2B_384, 0xb230, "blake2b-384", "multihash"),
+    (BLAKE2B_384, 0xb230, "blake2b-384", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 392-bit digest.
-    (BLAKE2B_392, 0xb231, "blake2b-392", "multihash"),
+    (BLAKE2B_392, 0xb231, "blake2b-392", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 400-bit digest.
-    (BLAKE2B_400, 0xb232, "blake2b-400", "multihash"),
+    (BLAKE2B_400, 0xb232, "blake2b-400", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 408-bit digest.
-    (BLAKE2B_408, 0xb233, "blake2b-408", "multihash"),
+    (BLAKE2B_408, 0xb233, "blake2b-408", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 416-bit digest.
-    (BLAKE2B_416, 0xb234, "blake2b-416", "multihash"),
+    (BLAKE2B_416, 0xb234, "blake2b-416", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 424-bit digest.
-    (BLAKE2B_424, 0xb235, "blake2b-424", "multihash"),
+    (BLAKE2B_424, 0xb235, "blake2b-424", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 432-bit digest.
-    (BLAKE2B_432, 0xb236, "blake2b-432", "multihash"),
+    (BLAKE2B_432, 0xb236, "blake2b-432", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 440-bit digest.
-    (BLAKE2B_440, 0xb237, "blake2b-440", "multihash"),
+    (BLAKE2B_440, 0xb237, "blake2b-440", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 448-bit digest.
-    (BLAKE2B_448, 0xb238, "blake2b-448", "multihash"),
+    (BLAKE2B_448, 0xb238, "blake2b-448", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 456-bit digest.
-    (BLAKE2B_456, 0xb239, "blake2b-456", "multihash"),
+    (BLAKE2B_456, 0xb239, "blake2b-456", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 464-bit digest.
-    (BLAKE2B_464, 0xb23a, "blake2b-464", "multihash"),
+    (BLAKE2B_464, 0xb23a, "blake2b-464", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 472-bit digest.
-    (BLAKE2B_472, 0xb23b, "blake2b-472", "multihash"),
+    (BLAKE2B_472, 0xb23b, "blake2b-472", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 480-bit digest.
-    (BLAKE2B_480, 0xb23c, "blake2b-480", "multihash"),
+    (BLAKE2B_480, 0xb23c, "blake2b-480", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 488-bit digest.
-    (BLAKE2B_488, 0xb23d, "blake2b-488", "multihash"),
+    (BLAKE2B_488, 0xb23d, "blake2b-488", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 496-bit digest.
-    (BLAKE2B_496, 0xb23e, "blake2b-496", "multihash"),
+    (BLAKE2B_496, 0xb23e, "blake2b-496", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 504-bit digest.
-    (BLAKE2B_504, 0xb23f, "blake2b-504", "multihash"),
+    (BLAKE2B_504, 0xb23f, "blake2b-504", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 512-bit digest.
-    (BLAKE2B_512, 0xb240, "blake2b-512", "multihash"),
+    (BLAKE2B_512, 0xb240, "blake2b-512", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 8-bit digest.
-    (BLAKE2S_8, 0xb241, "blake2s-8", "multihash"),
+    (BLAKE2S_8, 0xb241, "blake2s-8", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 16-bit digest.
-    (BLAKE2S_16, 0xb242, "blake2s-16", "multihash"),
+    (BLAKE2S_16, 0xb242, "blake2s-16", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 24-bit digest.
-    (BLAKE2S_24, 0xb243, "blake2s-24", "multihash"),
+    (BLAKE2S_24, 0xb243, "blake2s-24", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 32-bit digest.
-    (BLAKE2S_32, 0xb244, "blake2s-32", "multihash"),
+    (BLAKE2S_32, 0xb244, "blake2s-32", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 40-bit digest.
-    (BLAKE2S_40, 0xb245, "blake2s-40", "multihash"),
+    (BLAKE2S_40, 0xb245, "blake2s-40", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 48-bit digest.
-    (BLAKE2S_48, 0xb246, "blake2s-48", "multihash"),
+    (BLAKE2S_48, 0xb246, "blake2s-48", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 56-bit digest.
-    (BLAKE2S_56, 0xb247, "blake2s-56", "multihash"),
+    (BLAKE2S_56, 0xb247, "blake2s-56", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 64-bit digest.
-    (BLAKE2S_64, 0xb248, "blake2s-64", "multihash"),
+    (BLAKE2S_64, 0xb248, "blake2s-64", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 72-bit digest.
-    (BLAKE2S_72, 0xb249, "blake2s-72", "multihash"),
+    (BLAKE2S_72, 0xb249, "blake2s-72", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 80-bit digest.
-    (BLAKE2S_80, 0xb24a, "blake2s-80", "multihash"),
+    (BLAKE2S_80, 0xb24a, "blake2s-80", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 88-bit digest.
-    (BLAKE2S_88, 0xb24b, "blake2s-88", "multihash"),
+    (BLAKE2S_88, 0xb24b, "blake2s-88", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 96-bit digest.
-    (BLAKE2S_96, 0xb24c, "blake2s-96", "multihash"),
+    (BLAKE2S_96, 0xb24c, "blake2s-96", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 104-bit digest.
-    (BLAKE2S_104, 0xb24d, "blake2s-104", "multihash"),
+    (BLAKE2S_104, 0xb24d, "blake2s-104", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 112-bit digest.
-    (BLAKE2S_112, 0xb24e, "blake2s-112", "multihash"),
+    (BLAKE2S_112, 0xb24e, "blake2s-112", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 120-bit digest.
-    (BLAKE2S_120, 0xb24f, "blake2s-120", "multihash"),
+    (BLAKE2S_120, 0xb24f, "blake2s-120", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 128-bit digest.
-    (BLAKE2S_128, 0xb250, "blake2s-128", "multihash"),
+    (BLAKE2S_128, 0xb250, "blake2s-128", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 136-bit digest.
-    (BLAKE2S_136, 0xb251, "blake2s-136", "multihash"),
+    (BLAKE2S_136, 0xb251, "blake2s-136", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 144-bit digest.
-    (BLAKE2S_144, 0xb252, "blake2s-144", "multihash"),
+    (BLAKE2S_144, 0xb252, "blake2s-144", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 152-bit digest.
-    (BLAKE2S_152, 0xb253, "blake2s-152", "multihash"),
+    (BLAKE2S_152, 0xb253, "blake2s-152", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 160-bit digest.
-    (BLAKE2S_160, 0xb254, "blake2s-160", "multihash"),
+    (BLAKE2S_160, 0xb254, "blake2s-160", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 168-bit digest.
-    (BLAKE2S_168, 0xb255, "blake2s-168", "multihash"),
+    (BLAKE2S_168, 0xb255, "blake2s-168", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 176-bit digest.
-    (BLAKE2S_176, 0xb256, "blake2s-176", "multihash"),
+    (BLAKE2S_176, 0xb256, "blake2s-176", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 184-bit digest.
-    (BLAKE2S_184, 0xb257, "blake2s-184", "multihash"),
+    (BLAKE2S_184, 0xb257, "blake2s-184", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 192-bit digest.
-    (BLAKE2S_192, 0xb258, "blake2s-192", "multihash"),
+    (BLAKE2S_192, 0xb258, "blake2s-192", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 200-bit digest.
-    (BLAKE2S_200, 0xb259, "blake2s-200", "multihash"),
+    (BLAKE2S_200, 0xb259, "blake2s-200", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 208-bit digest.
-    (BLAKE2S_208, 0xb25a, "blake2s-208", "multihash"),
+    (BLAKE2S_208, 0xb25a, "blake2s-208", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 216-bit digest.
-    (BLAKE2S_216, 0xb25b, "blake2s-216", "multihash"),
+    (BLAKE2S_216, 0xb25b, "blake2s-216", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 224-bit digest.
-    (BLAKE2S_224, 0xb25c, "blake2s-224", "multihash"),
+    (BLAKE2S_224, 0xb25c, "blake2s-224", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 232-bit digest.
-    (BLAKE2S_232, 0xb25d, "blake2s-232", "multihash"),
+    (BLAKE2S_232, 0xb25d, "blake2s-232", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 240-bit digest.
-    (BLAKE2S_240, 0xb25e, "blake2s-240", "multihash"),
+    (BLAKE2S_240, 0xb25e, "blake2s-240", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 248-bit digest.
-    (BLAKE2S_248, 0xb25f, "blake2s-248", "multihash"),
+    (BLAKE2S_248, 0xb25f, "blake2s-248", Multihash),
     /// _multihash_, Blake2b hashing algorithm for 256-bit digest.
-    (BLAKE2S_256, 0xb260, "blake2s-256", "multihash"),
+    (BLAKE2S_256, 0xb260, "blake2s-256", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state, 8-bit digest.
-    (SKEIN256_8, 0xb301, "skein256-8", "multihash"),
+    (SKEIN256_8, 0xb301, "skein256-8", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state, 16-bit digest.
-    (SKEIN256_16, 0xb302, "skein256-16", "multihash"),
+    (SKEIN256_16, 0xb302, "skein256-16", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state, 24-bit digest.
-    (SKEIN256_24, 0xb303, "skein256-24", "multihash"),
+    (SKEIN256_24, 0xb303, "skein256-24", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state, 32-bit digest.
-    (SKEIN256_32, 0xb304, "skein256-32", "multihash"),
+    (SKEIN256_32, 0xb304, "skein256-32", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state, 40-bit digest.
-    (SKEIN256_40, 0xb305, "skein256-40", "multihash"),
+    (SKEIN256_40, 0xb305, "skein256-40", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state, 48-bit digest.
-    (SKEIN256_48, 0xb306, "skein256-48", "multihash"),
+    (SKEIN256_48, 0xb306, "skein256-48", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state, 56-bit digest.
-    (SKEIN256_56, 0xb307, "skein256-56", "multihash"),
+    (SKEIN256_56, 0xb307, "skein256-56", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state, 64-bit digest.
-    (SKEIN256_64, 0xb308, "skein256-64", "multihash"),
+    (SKEIN256_64, 0xb308, "skein256-64", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state, 72-bit digest.
-    (SKEIN256_72, 0xb309, "skein256-72", "multihash"),
+    (SKEIN256_72, 0xb309, "skein256-72", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state, 80-bit digest.
-    (SKEIN256_80, 0xb30a, "skein256-80", "multihash"),
+    (SKEIN256_80, 0xb30a, "skein256-80", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state, 88-bit digest.
-    (SKEIN256_88, 0xb30b, "skein256-88", "multihash"),
+    (SKEIN256_88, 0xb30b, "skein256-88", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state, 96-bit digest.
-    (SKEIN256_96, 0xb30c, "skein256-96", "multihash"),
+    (SKEIN256_96, 0xb30c, "skein256-96", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state,1 04-bit digest.
-    (SKEIN256_104, 0xb30d, "skein256-104", "multihash"),
+    (SKEIN256_104, 0xb30d, "skein256-104", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state,1 12-bit digest.
-    (SKEIN256_112, 0xb30e, "skein256-112", "multihash"),
+    (SKEIN256_112, 0xb30e, "skein256-112", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state,1 20-bit digest.
-    (SKEIN256_120, 0xb30f, "skein256-120", "multihash"),
+    (SKEIN256_120, 0xb30f, "skein256-120", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state,1 28-bit digest.
-    (SKEIN256_128, 0xb310, "skein256-128", "multihash"),
+    (SKEIN256_128, 0xb310, "skein256-128", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state,1 36-bit digest.
-    (SKEIN256_136, 0xb311, "skein256-136", "multihash"),
+    (SKEIN256_136, 0xb311, "skein256-136", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state,1 44-bit digest.
-    (SKEIN256_144, 0xb312, "skein256-144", "multihash"),
+    (SKEIN256_144, 0xb312, "skein256-144", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state,1 52-bit digest.
-    (SKEIN256_152, 0xb313, "skein256-152", "multihash"),
+    (SKEIN256_152, 0xb313, "skein256-152", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state,1 60-bit digest.
-    (SKEIN256_160, 0xb314, "skein256-160", "multihash"),
+    (SKEIN256_160, 0xb314, "skein256-160", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state,1 68-bit digest.
-    (SKEIN256_168, 0xb315, "skein256-168", "multihash"),
+    (SKEIN256_168, 0xb315, "skein256-168", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state,1 76-bit digest.
-    (SKEIN256_176, 0xb316, "skein256-176", "multihash"),
+    (SKEIN256_176, 0xb316, "skein256-176", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state,1 84-bit digest.
-    (SKEIN256_184, 0xb317, "skein256-184", "multihash"),
+    (SKEIN256_184, 0xb317, "skein256-184", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state,1 92-bit digest.
-    (SKEIN256_192, 0xb318, "skein256-192", "multihash"),
+    (SKEIN256_192, 0xb318, "skein256-192", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state,2 00-bit digest.
-    (SKEIN256_200, 0xb319, "skein256-200", "multihash"),
+    (SKEIN256_200, 0xb319, "skein256-200", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state,2 08-bit digest.
-    (SKEIN256_208, 0xb31a, "skein256-208", "multihash"),
+    (SKEIN256_208, 0xb31a, "skein256-208", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state,2 16-bit digest.
-    (SKEIN256_216, 0xb31b, "skein256-216", "multihash"),
+    (SKEIN256_216, 0xb31b, "skein256-216", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state,2 24-bit digest.
-    (SKEIN256_224, 0xb31c, "skein256-224", "multihash"),
+    (SKEIN256_224, 0xb31c, "skein256-224", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state,2 32-bit digest.
-    (SKEIN256_232, 0xb31d, "skein256-232", "multihash"),
+    (SKEIN256_232, 0xb31d, "skein256-232", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state,2 40-bit digest.
-    (SKEIN256_240, 0xb31e, "skein256-240", "multihash"),
+    (SKEIN256_240, 0xb31e, "skein256-240", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state,2 48-bit digest.
-    (SKEIN256_248, 0xb31f, "skein256-248", "multihash"),
+    (SKEIN256_248, 0xb31f, "skein256-248", Multihash),
     /// _multihash_, Skein hashing algorithm 256-bit state,2 56-bit digest.
-    (SKEIN256_256, 0xb320, "skein256-256", "multihash"),
+    (SKEIN256_256, 0xb320, "skein256-256", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 8-bit digest.
-    (SKEIN512_8, 0xb321, "skein512-8", "multihash"),
+    (SKEIN512_8, 0xb321, "skein512-8", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 16-bit digest.
-    (SKEIN512_16, 0xb322, "skein512-16", "multihash"),
+    (SKEIN512_16, 0xb322, "skein512-16", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 24-bit digest.
-    (SKEIN512_24, 0xb323, "skein512-24", "multihash"),
+    (SKEIN512_24, 0xb323, "skein512-24", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 32-bit digest.
-    (SKEIN512_32, 0xb324, "skein512-32", "multihash"),
+    (SKEIN512_32, 0xb324, "skein512-32", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 40-bit digest.
-    (SKEIN512_40, 0xb325, "skein512-40", "multihash"),
+    (SKEIN512_40, 0xb325, "skein512-40", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 48-bit digest.
-    (SKEIN512_48, 0xb326, "skein512-48", "multihash"),
+    (SKEIN512_48, 0xb326, "skein512-48", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 56-bit digest.
-    (SKEIN512_56, 0xb327, "skein512-56", "multihash"),
+    (SKEIN512_56, 0xb327, "skein512-56", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 64-bit digest.
-    (SKEIN512_64, 0xb328, "skein512-64", "multihash"),
+    (SKEIN512_64, 0xb328, "skein512-64", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 72-bit digest.
-    (SKEIN512_72, 0xb329, "skein512-72", "multihash"),
+    (SKEIN512_72, 0xb329, "skein512-72", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 80-bit digest.
-    (SKEIN512_80, 0xb32a, "skein512-80", "multihash"),
+    (SKEIN512_80, 0xb32a, "skein512-80", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 88-bit digest.
-    (SKEIN512_88, 0xb32b, "skein512-88", "multihash"),
+    (SKEIN512_88, 0xb32b, "skein512-88", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 96-bit digest.
-    (SKEIN512_96, 0xb32c, "skein512-96", "multihash"),
+    (SKEIN512_96, 0xb32c, "skein512-96", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 104-bit digest.
-    (SKEIN512_104, 0xb32d, "skein512-104", "multihash"),
+    (SKEIN512_104, 0xb32d, "skein512-104", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 112-bit digest.
-    (SKEIN512_112, 0xb32e, "skein512-112", "multihash"),
+    (SKEIN512_112, 0xb32e, "skein512-112", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 120-bit digest.
-    (SKEIN512_120, 0xb32f, "skein512-120", "multihash"),
+    (SKEIN512_120, 0xb32f, "skein512-120", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 128-bit digest.
-    (SKEIN512_128, 0xb330, "skein512-128", "multihash"),
+    (SKEIN512_128, 0xb330, "skein512-128", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 136-bit digest.
-    (SKEIN512_136, 0xb331, "skein512-136", "multihash"),
+    (SKEIN512_136, 0xb331, "skein512-136", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 144-bit digest.
-    (SKEIN512_144, 0xb332, "skein512-144", "multihash"),
+    (SKEIN512_144, 0xb332, "skein512-144", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 152-bit digest.
-    (SKEIN512_152, 0xb333, "skein512-152", "multihash"),
+    (SKEIN512_152, 0xb333, "skein512-152", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 160-bit digest.
-    (SKEIN512_160, 0xb334, "skein512-160", "multihash"),
+    (SKEIN512_160, 0xb334, "skein512-160", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 168-bit digest.
-    (SKEIN512_168, 0xb335, "skein512-168", "multihash"),
+    (SKEIN512_168, 0xb335, "skein512-168", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 176-bit digest.
-    (SKEIN512_176, 0xb336, "skein512-176", "multihash"),
+    (SKEIN512_176, 0xb336, "skein512-176", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 184-bit digest.
-    (SKEIN512_184, 0xb337, "skein512-184", "multihash"),
+    (SKEIN512_184, 0xb337, "skein512-184", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 192-bit digest.
-    (SKEIN512_192, 0xb338, "skein512-192", "multihash"),
+    (SKEIN512_192, 0xb338, "skein512-192", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 200-bit digest.
-    (SKEIN512_200, 0xb339, "skein512-200", "multihash"),
+    (SKEIN512_200, 0xb339, "skein512-200", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 208-bit digest.
-    (SKEIN512_208, 0xb33a, "skein512-208", "multihash"),
+    (SKEIN512_208, 0xb33a, "skein512-208", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 216-bit digest.
-    (SKEIN512_216, 0xb33b, "skein512-216", "multihash"),
+    (SKEIN512_216, 0xb33b, "skein512-216", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 224-bit digest.
-    (SKEIN512_224, 0xb33c, "skein512-224", "multihash"),
+    (SKEIN512_224, 0xb33c, "skein512-224", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 232-bit digest.
-    (SKEIN512_232, 0xb33d, "skein512-232", "multihash"),
+    (SKEIN512_232, 0xb33d, "skein512-232", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 240-bit digest.
-    (SKEIN512_240, 0xb33e, "skein512-240", "multihash"),
+    (SKEIN512_240, 0xb33e, "skein512-240", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 248-bit digest.
-    (SKEIN512_248, 0xb33f, "skein512-248", "multihash"),
+    (SKEIN512_248, 0xb33f, "skein512-248", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 256-bit digest.
-    (SKEIN512_256, 0xb340, "skein512-256", "multihash"),
+    (SKEIN512_256, 0xb340, "skein512-256", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 264-bit digest.
-    (SKEIN512_264, 0xb341, "skein512-264", "multihash"),
+    (SKEIN512_264, 0xb341, "skein512-264", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 272-bit digest.
-    (SKEIN512_272, 0xb342, "skein512-272", "multihash"),
+    (SKEIN512_272, 0xb342, "skein512-272", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 280-bit digest.
-    (SKEIN512_280, 0xb343, "skein512-280", "multihash"),
+    (SKEIN512_280, 0xb343, "skein512-280", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 288-bit digest.
-    (SKEIN512_288, 0xb344, "skein512-288", "multihash"),
+    (SKEIN512_288, 0xb344, "skein512-288", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 296-bit digest.
-    (SKEIN512_296, 0xb345, "skein512-296", "multihash"),
+    (SKEIN512_296, 0xb345, "skein512-296", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 304-bit digest.
-    (SKEIN512_304, 0xb346, "skein512-304", "multihash"),
+    (SKEIN512_304, 0xb346, "skein512-304", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 312-bit digest.
-    (SKEIN512_312, 0xb347, "skein512-312", "multihash"),
+    (SKEIN512_312, 0xb347, "skein512-312", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 320-bit digest.
-    (SKEIN512_320, 0xb348, "skein512-320", "multihash"),
+    (SKEIN512_320, 0xb348, "skein512-320", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 328-bit digest.
-    (SKEIN512_328, 0xb349, "skein512-328", "multihash"),
+    (SKEIN512_328, 0xb349, "skein512-328", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 336-bit digest.
-    (SKEIN512_336, 0xb34a, "skein512-336", "multihash"),
+    (SKEIN512_336, 0xb34a, "skein512-336", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 344-bit digest.
-    (SKEIN512_344, 0xb34b, "skein512-344", "multihash"),
+    (SKEIN512_344, 0xb34b, "skein512-344", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 352-bit digest.
-    (SKEIN512_352, 0xb34c, "skein512-352", "multihash"),
+    (SKEIN512_352, 0xb34c, "skein512-352", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 360-bit digest.
-    (SKEIN512_360, 0xb34d, "skein512-360", "multihash"),
+    (SKEIN512_360, 0xb34d, "skein512-360", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 368-bit digest.
-    (SKEIN512_368, 0xb34e, "skein512-368", "multihash"),
+    (SKEIN512_368, 0xb34e, "skein512-368", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 376-bit digest.
-    (SKEIN512_376, 0xb34f, "skein512-376", "multihash"),
+    (SKEIN512_376, 0xb34f, "skein512-376", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 384-bit digest.
-    (SKEIN512_384, 0xb350, "skein512-384", "multihash"),
+    (SKEIN512_384, 0xb350, "skein512-384", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 392-bit digest.
-    (SKEIN512_392, 0xb351, "skein512-392", "multihash"),
+    (SKEIN512_392, 0xb351, "skein512-392", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 400-bit digest.
-    (SKEIN512_400, 0xb352, "skein512-400", "multihash"),
+    (SKEIN512_400, 0xb352, "skein512-400", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 408-bit digest.
-    (SKEIN512_408, 0xb353, "skein512-408", "multihash"),
+    (SKEIN512_408, 0xb353, "skein512-408", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 416-bit digest.
-    (SKEIN512_416, 0xb354, "skein512-416", "multihash"),
+    (SKEIN512_416, 0xb354, "skein512-416", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 424-bit digest.
-    (SKEIN512_424, 0xb355, "skein512-424", "multihash"),
+    (SKEIN512_424, 0xb355, "skein512-424", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 432-bit digest.
-    (SKEIN512_432, 0xb356, "skein512-432", "multihash"),
+    (SKEIN512_432, 0xb356, "skein512-432", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 440-bit digest.
-    (SKEIN512_440, 0xb357, "skein512-440", "multihash"),
+    (SKEIN512_440, 0xb357, "skein512-440", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 448-bit digest.
-    (SKEIN512_448, 0xb358, "skein512-448", "multihash"),
+    (SKEIN512_448, 0xb358, "skein512-448", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 456-bit digest.
-    (SKEIN512_456, 0xb359, "skein512-456", "multihash"),
+    (SKEIN512_456, 0xb359, "skein512-456", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 464-bit digest.
-    (SKEIN512_464, 0xb35a, "skein512-464", "multihash"),
+    (SKEIN512_464, 0xb35a, "skein512-464", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 472-bit digest.
-    (SKEIN512_472, 0xb35b, "skein512-472", "multihash"),
+    (SKEIN512_472, 0xb35b, "skein512-472", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 480-bit digest.
-    (SKEIN512_480, 0xb35c, "skein512-480", "multihash"),
+    (SKEIN512_480, 0xb35c, "skein512-480", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 488-bit digest.
-    (SKEIN512_488, 0xb35d, "skein512-488", "multihash"),
+    (SKEIN512_488, 0xb35d, "skein512-488", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 496-bit digest.
-    (SKEIN512_496, 0xb35e, "skein512-496", "multihash"),
+    (SKEIN512_496, 0xb35e, "skein512-496", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 504-bit digest.
-    (SKEIN512_504, 0xb35f, "skein512-504", "multihash"),
+    (SKEIN512_504, 0xb35f, "skein512-504", Multihash),
     /// _multihash_, Skein hashing algorithm 512-bit state, 512-bit digest.
-    (SKEIN512_512, 0xb360, "skein512-512", "multihash"),
+    (SKEIN512_512, 0xb360, "skein512-512", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 8-bit digest.
-    (SKEIN1024_8, 0xb361, "skein1024-8", "multihash"),
+    (SKEIN1024_8, 0xb361, "skein1024-8", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 16-bit digest.
-    (SKEIN1024_16, 0xb362, "skein1024-16", "multihash"),
+    (SKEIN1024_16, 0xb362, "skein1024-16", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 24-bit digest.
-    (SKEIN1024_24, 0xb363, "skein1024-24", "multihash"),
+    (SKEIN1024_24, 0xb363, "skein1024-24", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 32-bit digest.
-    (SKEIN1024_32, 0xb364, "skein1024-32", "multihash"),
+    (SKEIN1024_32, 0xb364, "skein1024-32", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 40-bit digest.
-    (SKEIN1024_40, 0xb365, "skein1024-40", "multihash"),
+    (SKEIN1024_40, 0xb365, "skein1024-40", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 48-bit digest.
-    (SKEIN1024_48, 0xb366, "skein1024-48", "multihash"),
+    (SKEIN1024_48, 0xb366, "skein1024-48", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 56-bit digest.
-    (SKEIN1024_56, 0xb367, "skein1024-56", "multihash"),
+    (SKEIN1024_56, 0xb367, "skein1024-56", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 64-bit digest.
-    (SKEIN1024_64, 0xb368, "skein1024-64", "multihash"),
+    (SKEIN1024_64, 0xb368, "skein1024-64", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 72-bit digest.
-    (SKEIN1024_72, 0xb369, "skein1024-72", "multihash"),
+    (SKEIN1024_72, 0xb369, "skein1024-72", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 80-bit digest.
-    (SKEIN1024_80, 0xb36a, "skein1024-80", "multihash"),
+    (SKEIN1024_80, 0xb36a, "skein1024-80", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 88-bit digest.
-    (SKEIN1024_88, 0xb36b, "skein1024-88", "multihash"),
+    (SKEIN1024_88, 0xb36b, "skein1024-88", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 96-bit digest.
-    (SKEIN1024_96, 0xb36c, "skein1024-96", "multihash"),
+    (SKEIN1024_96, 0xb36c, "skein1024-96", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 104-bit digest.
-    (SKEIN1024_104, 0xb36d, "skein1024-104", "multihash"),
+    (SKEIN1024_104, 0xb36d, "skein1024-104", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 112-bit digest.
-    (SKEIN1024_112, 0xb36e, "skein1024-112", "multihash"),
+    (SKEIN1024_112, 0xb36e, "skein1024-112", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 120-bit digest.
-    (SKEIN1024_120, 0xb36f, "skein1024-120", "multihash"),
+    (SKEIN1024_120, 0xb36f, "skein1024-120", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 128-bit digest.
-    (SKEIN1024_128, 0xb370, "skein1024-128", "multihash"),
+    (SKEIN1024_128, 0xb370, "skein1024-128", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 136-bit digest.
-    (SKEIN1024_136, 0xb371, "skein1024-136", "multihash"),
+    (SKEIN1024_136, 0xb371, "skein1024-136", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 144-bit digest.
-    (SKEIN1024_144, 0xb372, "skein1024-144", "multihash"),
+    (SKEIN1024_144, 0xb372, "skein1024-144", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 152-bit digest.
-    (SKEIN1024_152, 0xb373, "skein1024-152", "multihash"),
+    (SKEIN1024_152, 0xb373, "skein1024-152", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 160-bit digest.
-    (SKEIN1024_160, 0xb374, "skein1024-160", "multihash"),
+    (SKEIN1024_160, 0xb374, "skein1024-160", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 168-bit digest.
-    (SKEIN1024_168, 0xb375, "skein1024-168", "multihash"),
+    (SKEIN1024_168, 0xb375, "skein1024-168", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 176-bit digest.
-    (SKEIN1024_176, 0xb376, "skein1024-176", "multihash"),
+    (SKEIN1024_176, 0xb376, "skein1024-176", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 184-bit digest.
-    (SKEIN1024_184, 0xb377, "skein1024-184", "multihash"),
+    (SKEIN1024_184, 0xb377, "skein1024-184", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 192-bit digest.
-    (SKEIN1024_192, 0xb378, "skein1024-192", "multihash"),
+    (SKEIN1024_192, 0xb378, "skein1024-192", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 200-bit digest.
-    (SKEIN1024_200, 0xb379, "skein1024-200", "multihash"),
+    (SKEIN1024_200, 0xb379, "skein1024-200", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 208-bit digest.
-    (SKEIN1024_208, 0xb37a, "skein1024-208", "multihash"),
+    (SKEIN1024_208, 0xb37a, "skein1024-208", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 216-bit digest.
-    (SKEIN1024_216, 0xb37b, "skein1024-216", "multihash"),
+    (SKEIN1024_216, 0xb37b, "skein1024-216", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 224-bit digest.
-    (SKEIN1024_224, 0xb37c, "skein1024-224", "multihash"),
+    (SKEIN1024_224, 0xb37c, "skein1024-224", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 232-bit digest.
-    (SKEIN1024_232, 0xb37d, "skein1024-232", "multihash"),
+    (SKEIN1024_232, 0xb37d, "skein1024-232", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 240-bit digest.
-    (SKEIN1024_240, 0xb37e, "skein1024-240", "multihash"),
+    (SKEIN1024_240, 0xb37e, "skein1024-240", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 248-bit digest.
-    (SKEIN1024_248, 0xb37f, "skein1024-248", "multihash"),
+    (SKEIN1024_248, 0xb37f, "skein1024-248", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 256-bit digest.
-    (SKEIN1024_256, 0xb380, "skein1024-256", "multihash"),
+    (SKEIN1024_256, 0xb380, "skein1024-256", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 264-bit digest.
-    (SKEIN1024_264, 0xb381, "skein1024-264", "multihash"),
+    (SKEIN1024_264, 0xb381, "skein1024-264", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 272-bit digest.
-    (SKEIN1024_272, 0xb382, "skein1024-272", "multihash"),
+    (SKEIN1024_272, 0xb382, "skein1024-272", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 280-bit digest.
-    (SKEIN1024_280, 0xb383, "skein1024-280", "multihash"),
+    (SKEIN1024_280, 0xb383, "skein1024-280", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 288-bit digest.
-    (SKEIN1024_288, 0xb384, "skein1024-288", "multihash"),
+    (SKEIN1024_288, 0xb384, "skein1024-288", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 296-bit digest.
-    (SKEIN1024_296, 0xb385, "skein1024-296", "multihash"),
+    (SKEIN1024_296, 0xb385, "skein1024-296", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 304-bit digest.
-    (SKEIN1024_304, 0xb386, "skein1024-304", "multihash"),
+    (SKEIN1024_304, 0xb386, "skein1024-304", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 312-bit digest.
-    (SKEIN1024_312, 0xb387, "skein1024-312", "multihash"),
+    (SKEIN1024_312, 0xb387, "skein1024-312", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 320-bit digest.
-    (SKEIN1024_320, 0xb388, "skein1024-320", "multihash"),
+    (SKEIN1024_320, 0xb388, "skein1024-320", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 328-bit digest.
-    (SKEIN1024_328, 0xb389, "skein1024-328", "multihash"),
+    (SKEIN1024_328, 0xb389, "skein1024-328", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 336-bit digest.
-    (SKEIN1024_336, 0xb38a, "skein1024-336", "multihash"),
+    (SKEIN1024_336, 0xb38a, "skein1024-336", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 344-bit digest.
-    (SKEIN1024_344, 0xb38b, "skein1024-344", "multihash"),
+    (SKEIN1024_344, 0xb38b, "skein1024-344", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 352-bit digest.
-    (SKEIN1024_352, 0xb38c, "skein1024-352", "multihash"),
+    (SKEIN1024_352, 0xb38c, "skein1024-352", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 360-bit digest.
-    (SKEIN1024_360, 0xb38d, "skein1024-360", "multihash"),
+    (SKEIN1024_360, 0xb38d, "skein1024-360", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 368-bit digest.
-    (SKEIN1024_368, 0xb38e, "skein1024-368", "multihash"),
+    (SKEIN1024_368, 0xb38e, "skein1024-368", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 376-bit digest.
-    (SKEIN1024_376, 0xb38f, "skein1024-376", "multihash"),
+    (SKEIN1024_376, 0xb38f, "skein1024-376", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 384-bit digest.
-    (SKEIN1024_384, 0xb390, "skein1024-384", "multihash"),
+    (SKEIN1024_384, 0xb390, "skein1024-384", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 392-bit digest.
-    (SKEIN1024_392, 0xb391, "skein1024-392", "multihash"),
+    (SKEIN1024_392, 0xb391, "skein1024-392", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 400-bit digest.
-    (SKEIN1024_400, 0xb392, "skein1024-400", "multihash"),
+    (SKEIN1024_400, 0xb392, "skein1024-400", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 408-bit digest.
-    (SKEIN1024_408, 0xb393, "skein1024-408", "multihash"),
+    (SKEIN1024_408, 0xb393, "skein1024-408", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 416-bit digest.
-    (SKEIN1024_416, 0xb394, "skein1024-416", "multihash"),
+    (SKEIN1024_416, 0xb394, "skein1024-416", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 424-bit digest.
-    (SKEIN1024_424, 0xb395, "skein1024-424", "multihash"),
+    (SKEIN1024_424, 0xb395, "skein1024-424", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 432-bit digest.
-    (SKEIN1024_432, 0xb396, "skein1024-432", "multihash"),
+    (SKEIN1024_432, 0xb396, "skein1024-432", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 440-bit digest.
-    (SKEIN1024_440, 0xb397, "skein1024-440", "multihash"),
+    (SKEIN1024_440, 0xb397, "skein1024-440", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 448-bit digest.
-    (SKEIN1024_448, 0xb398, "skein1024-448", "multihash"),
+    (SKEIN1024_448, 0xb398, "skein1024-448", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 456-bit digest.
-    (SKEIN1024_456, 0xb399, "skein1024-456", "multihash"),
+    (SKEIN1024_456, 0xb399, "skein1024-456", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 464-bit digest.
-    (SKEIN1024_464, 0xb39a, "skein1024-464", "multihash"),
+    (SKEIN1024_464, 0xb39a, "skein1024-464", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 472-bit digest.
-    (SKEIN1024_472, 0xb39b, "skein1024-472", "multihash"),
+    (SKEIN1024_472, 0xb39b, "skein1024-472", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 480-bit digest.
-    (SKEIN1024_480, 0xb39c, "skein1024-480", "multihash"),
+    (SKEIN1024_480, 0xb39c, "skein1024-480", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 488-bit digest.
-    (SKEIN1024_488, 0xb39d, "skein1024-488", "multihash"),
+    (SKEIN1024_488, 0xb39d, "skein1024-488", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 496-bit digest.
-    (SKEIN1024_496, 0xb39e, "skein1024-496", "multihash"),
+    (SKEIN1024_496, 0xb39e, "skein1024-496", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 504-bit digest.
-    (SKEIN1024_504, 0xb39f, "skein1024-504", "multihash"),
+    (SKEIN1024_504, 0xb39f, "skein1024-504", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 512-bit digest.
-    (SKEIN1024_512, 0xb3a0, "skein1024-512", "multihash"),
+    (SKEIN1024_512, 0xb3a0, "skein1024-512", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 520-bit digest.
-    (SKEIN1024_520, 0xb3a1, "skein1024-520", "multihash"),
+    (SKEIN1024_520, 0xb3a1, "skein1024-520", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 528-bit digest.
-    (SKEIN1024_528, 0xb3a2, "skein1024-528", "multihash"),
+    (SKEIN1024_528, 0xb3a2, "skein1024-528", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 536-bit digest.
-    (SKEIN1024_536, 0xb3a3, "skein1024-536", "multihash"),
+    (SKEIN1024_536, 0xb3a3, "skein1024-536", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 544-bit digest.
-    (SKEIN1024_544, 0xb3a4, "skein1024-544", "multihash"),
+    (SKEIN1024_544, 0xb3a4, "skein1024-544", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 552-bit digest.
-    (SKEIN1024_552, 0xb3a5, "skein1024-552", "multihash"),
+    (SKEIN1024_552, 0xb3a5, "skein1024-552", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 560-bit digest.
-    (SKEIN1024_560, 0xb3a6, "skein1024-560", "multihash"),
+    (SKEIN1024_560, 0xb3a6, "skein1024-560", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 568-bit digest.
-    (SKEIN1024_568, 0xb3a7, "skein1024-568", "multihash"),
+    (SKEIN1024_568, 0xb3a7, "skein1024-568", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 576-bit digest.
-    (SKEIN1024_576, 0xb3a8, "skein1024-576", "multihash"),
+    (SKEIN1024_576, 0xb3a8, "skein1024-576", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 584-bit digest.
-    (SKEIN1024_584, 0xb3a9, "skein1024-584", "multihash"),
+    (SKEIN1024_584, 0xb3a9, "skein1024-584", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 592-bit digest.
-    (SKEIN1024_592, 0xb3aa, "skein1024-592", "multihash"),
+    (SKEIN1024_592, 0xb3aa, "skein1024-592", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 600-bit digest.
-    (SKEIN1024_600, 0xb3ab, "skein1024-600", "multihash"),
+    (SKEIN1024_600, 0xb3ab, "skein1024-600", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 608-bit digest.
-    (SKEIN1024_608, 0xb3ac, "skein1024-608", "multihash"),
+    (SKEIN1024_608, 0xb3ac, "skein1024-608", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 616-bit digest.
-    (SKEIN1024_616, 0xb3ad, "skein1024-616", "multihash"),
+    (SKEIN1024_616, 0xb3ad, "skein1024-616", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 624-bit digest.
-    (SKEIN1024_624, 0xb3ae, "skein1024-624", "multihash"),
+    (SKEIN1024_624, 0xb3ae, "skein1024-624", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 632-bit digest.
-    (SKEIN1024_632, 0xb3af, "skein1024-632", "multihash"),
+    (SKEIN1024_632, 0xb3af, "skein1024-632", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 640-bit digest.
-    (SKEIN1024_640, 0xb3b0, "skein1024-640", "multihash"),
+    (SKEIN1024_640, 0xb3b0, "skein1024-640", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 648-bit digest.
-    (SKEIN1024_648, 0xb3b1, "skein1024-648", "multihash"),
+    (SKEIN1024_648, 0xb3b1, "skein1024-648", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 656-bit digest.
-    (SKEIN1024_656, 0xb3b2, "skein1024-656", "multihash"),
+    (SKEIN1024_656, 0xb3b2, "skein1024-656", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 664-bit digest.
-    (SKEIN1024_664, 0xb3b3, "skein1024-664", "multihash"),
+    (SKEIN1024_664, 0xb3b3, "skein1024-664", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 672-bit digest.
-    (SKEIN1024_672, 0xb3b4, "skein1024-672", "multihash"),
+    (SKEIN1024_672, 0xb3b4, "skein1024-672", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 680-bit digest.
-    (SKEIN1024_680, 0xb3b5, "skein1024-680", "multihash"),
+    (SKEIN1024_680, 0xb3b5, "skein1024-680", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 688-bit digest.
-    (SKEIN1024_688, 0xb3b6, "skein1024-688", "multihash"),
+    (SKEIN1024_688, 0xb3b6, "skein1024-688", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 696-bit digest.
-    (SKEIN1024_696, 0xb3b7, "skein1024-696", "multihash"),
+    (SKEIN1024_696, 0xb3b7, "skein1024-696", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 704-bit digest.
-    (SKEIN1024_704, 0xb3b8, "skein1024-704", "multihash"),
+    (SKEIN1024_704, 0xb3b8, "skein1024-704", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 712-bit digest.
-    (SKEIN1024_712, 0xb3b9, "skein1024-712", "multihash"),
+    (SKEIN1024_712, 0xb3b9, "skein1024-712", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 720-bit digest.
-    (SKEIN1024_720, 0xb3ba, "skein1024-720", "multihash"),
+    (SKEIN1024_720, 0xb3ba, "skein1024-720", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 728-bit digest.
-    (SKEIN1024_728, 0xb3bb, "skein1024-728", "multihash"),
+    (SKEIN1024_728, 0xb3bb, "skein1024-728", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 736-bit digest.
-    (SKEIN1024_736, 0xb3bc, "skein1024-736", "multihash"),
+    (SKEIN1024_736, 0xb3bc, "skein1024-736", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 744-bit digest.
-    (SKEIN1024_744, 0xb3bd, "skein1024-744", "multihash"),
+    (SKEIN1024_744, 0xb3bd, "skein1024-744", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 752-bit digest.
-    (SKEIN1024_752, 0xb3be, "skein1024-752", "multihash"),
+    (SKEIN1024_752, 0xb3be, "skein1024-752", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 760-bit digest.
-    (SKEIN1024_760, 0xb3bf, "skein1024-760", "multihash"),
+    (SKEIN1024_760, 0xb3bf, "skein1024-760", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 768-bit digest.
-    (SKEIN1024_768, 0xb3c0, "skein1024-768", "multihash"),
+    (SKEIN1024_768, 0xb3c0, "skein1024-768", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 776-bit digest.
-    (SKEIN1024_776, 0xb3c1, "skein1024-776", "multihash"),
+    (SKEIN1024_776, 0xb3c1, "skein1024-776", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 784-bit digest.
-    (SKEIN1024_784, 0xb3c2, "skein1024-784", "multihash"),
+    (SKEIN1024_784, 0xb3c2, "skein1024-784", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 792-bit digest.
-    (SKEIN1024_792, 0xb3c3, "skein1024-792", "multihash"),
+    (SKEIN1024_792, 0xb3c3, "skein1024-792", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 800-bit digest.
-    (SKEIN1024_800, 0xb3c4, "skein1024-800", "multihash"),
+    (SKEIN1024_800, 0xb3c4, "skein1024-800", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 808-bit digest.
-    (SKEIN1024_808, 0xb3c5, "skein1024-808", "multihash"),
+    (SKEIN1024_808, 0xb3c5, "skein1024-808", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 816-bit digest.
-    (SKEIN1024_816, 0xb3c6, "skein1024-816", "multihash"),
+    (SKEIN1024_816, 0xb3c6, "skein1024-816", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 824-bit digest.
-    (SKEIN1024_824, 0xb3c7, "skein1024-824", "multihash"),
+    (SKEIN1024_824, 0xb3c7, "skein1024-824", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 832-bit digest.
-    (SKEIN1024_832, 0xb3c8, "skein1024-832", "multihash"),
+    (SKEIN1024_832, 0xb3c8, "skein1024-832", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 840-bit digest.
-    (SKEIN1024_840, 0xb3c9, "skein1024-840", "multihash"),
+    (SKEIN1024_840, 0xb3c9, "skein1024-840", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 848-bit digest.
-    (SKEIN1024_848, 0xb3ca, "skein1024-848", "multihash"),
+    (SKEIN1024_848, 0xb3ca, "skein1024-848", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 856-bit digest.
-    (SKEIN1024_856, 0xb3cb, "skein1024-856", "multihash"),
+    (SKEIN1024_856, 0xb3cb, "skein1024-856", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 864-bit digest.
-    (SKEIN1024_864, 0xb3cc, "skein1024-864", "multihash"),
+    (SKEIN1024_864, 0xb3cc, "skein1024-864", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 872-bit digest.
-    (SKEIN1024_872, 0xb3cd, "skein1024-872", "multihash"),
+    (SKEIN1024_872, 0xb3cd, "skein1024-872", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 880-bit digest.
-    (SKEIN1024_880, 0xb3ce, "skein1024-880", "multihash"),
+    (SKEIN1024_880, 0xb3ce, "skein1024-880", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 888-bit digest.
-    (SKEIN1024_888, 0xb3cf, "skein1024-888", "multihash"),
+    (SKEIN1024_888, 0xb3cf, "skein1024-888", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 896-bit digest.
-    (SKEIN1024_896, 0xb3d0, "skein1024-896", "multihash"),
+    (SKEIN1024_896, 0xb3d0, "skein1024-896", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 904-bit digest.
-    (SKEIN1024_904, 0xb3d1, "skein1024-904", "multihash"),
+    (SKEIN1024_904, 0xb3d1, "skein1024-904", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 912-bit digest.
-    (SKEIN1024_912, 0xb3d2, "skein1024-912", "multihash"),
+    (SKEIN1024_912, 0xb3d2, "skein1024-912", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 920-bit digest.
-    (SKEIN1024_920, 0xb3d3, "skein1024-920", "multihash"),
+    (SKEIN1024_920, 0xb3d3, "skein1024-920", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 928-bit digest.
-    (SKEIN1024_928, 0xb3d4, "skein1024-928", "multihash"),
+    (SKEIN1024_928, 0xb3d4, "skein1024-928", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 936-bit digest.
-    (SKEIN1024_936, 0xb3d5, "skein1024-936", "multihash"),
+    (SKEIN1024_936, 0xb3d5, "skein1024-936", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 944-bit digest.
-    (SKEIN1024_944, 0xb3d6, "skein1024-944", "multihash"),
+    (SKEIN1024_944, 0xb3d6, "skein1024-944", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 952-bit digest.
-    (SKEIN1024_952, 0xb3d7, "skein1024-952", "multihash"),
+    (SKEIN1024_952, 0xb3d7, "skein1024-952", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 960-bit digest.
-    (SKEIN1024_960, 0xb3d8, "skein1024-960", "multihash"),
+    (SKEIN1024_960, 0xb3d8, "skein1024-960", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 968-bit digest.
-    (SKEIN1024_968, 0xb3d9, "skein1024-968", "multihash"),
+    (SKEIN1024_968, 0xb3d9, "skein1024-968", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 976-bit digest.
-    (SKEIN1024_976, 0xb3da, "skein1024-976", "multihash"),
+    (SKEIN1024_976, 0xb3da, "skein1024-976", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 984-bit digest.
-    (SKEIN1024_984, 0xb3db, "skein1024-984", "multihash"),
+    (SKEIN1024_984, 0xb3db, "skein1024-984", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 992-bit digest.
-    (SKEIN1024_992, 0xb3dc, "skein1024-992", "multihash"),
+    (SKEIN1024_992, 0xb3dc, "skein1024-992", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 1000-bit digest.
-    (SKEIN1024_1000, 0xb3dd, "skein1024-1000", "multihash"),
+    (SKEIN1024_1000, 0xb3dd, "skein1024-1000", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 1008-bit digest.
-    (SKEIN1024_1008, 0xb3de, "skein1024-1008", "multihash"),
+    (SKEIN1024_1008, 0xb3de, "skein1024-1008", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 1016-bit digest.
-    (SKEIN1024_1016, 0xb3df, "skein1024-1016", "multihash"),
+    (SKEIN1024_1016, 0xb3df, "skein1024-1016", Multihash),
     /// _multihash_, Skein hashing algorithm 1024-bit state, 1024-bit digest.
-    (SKEIN1024_1024, 0xb3e0, "skein1024-1024", "multihash"),
+    (SKEIN1024_1024, 0xb3e0, "skein1024-1024", Multihash),
     /// _multihash_
     (
         POSEIDON_BLS12_381_A2_FC1,
         0xb401,
         "poseidon-bls12_381-a2-fc1",
-        "multihash"
+        Multihash
     ),
     /// _multihash_
     (
         POSEIDON_BLS12_381_A2_FC1_SC,
         0xb402,
         "poseidon-bls12_381-a2-fc1-sc",
-        "multihash"
+        Multihash
     ),
     /// _multihash_
     (
         ZEROXCERT_IMPRINT_256,
         0xce11,
         "zeroxcert-imprint-256",
-        "zeroxcert"
+        Zeroxcert
     ),
     /// _multihash_
     (
         FIL_COMMITMENT_UNSEALED,
         0xf101,
         "fil-commitment-unsealed",
-        "filecoin"
+        Filecoin
     ),
     /// _multihash_
     (
         FIL_COMMITMENT_SEALED,
         0xf102,
         "fil-commitment-sealed",
-        "filecoin"
+        Filecoin
     ),
     /// _holochain_
-    (HOLOCHAIN_ADR_V0, 0x807124, "holochain-adr-v0", "holochain"),
+    (HOLOCHAIN_ADR_V0, 0x807124, "holochain-adr-v0", Holochain),
     /// _holochain_
-    (HOLOCHAIN_ADR_V1, 0x817124, "holochain-adr-v1", "holochain"),
+    (HOLOCHAIN_ADR_V1, 0x817124, "holochain-adr-v1", Holochain),
     /// _holochain_
-    (HOLOCHAIN_KEY_V0, 0x947124, "holochain-key-v0", "holochain"),
+    (HOLOCHAIN_KEY_V0, 0x947124, "holochain-key-v0", Holochain),
     /// _holochain_
-    (HOLOCHAIN_KEY_V1, 0x957124, "holochain-key-v1", "holochain"),
+    (HOLOCHAIN_KEY_V1, 0x957124, "holochain-key-v1", Holochain),
     /// _holochain_
-    (HOLOCHAIN_SIG_V0, 0xa27124, "holochain-sig-v0", "holochain"),
+    (HOLOCHAIN_SIG_V0, 0xa27124, "holochain-sig-v0", Holochain),
     /// _holochain_
-    (HOLOCHAIN_SIG_V1, 0xa37124, "holochain-sig-v1", "holochain"),
+    (HOLOCHAIN_SIG_V1, 0xa37124, "holochain-sig-v1", Holochain),
 ];
 
-/// Return a list of code-points tagged as "multihash".
+/// Return a list of code-points tagged as [Tag::Multihash].
 pub fn multihash_codes() -> Vec<u128> {
     TABLE_MULTIHASH
         .clone()
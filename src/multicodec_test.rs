@@ -42,7 +42,7 @@ fn test_multicodec_spec() {
     let pkg_lines: Vec<Vec<String>> = (&TABLE)
         .to_vec()
         .into_iter()
-        .map(|cp| vec![cp.name, format!("0x{:x}", cp.code), cp.tag])
+        .map(|cp| vec![cp.name, format!("0x{:x}", cp.code), cp.tag.to_string()])
         .collect();
 
     assert_eq!(
@@ -72,3 +72,184 @@ fn test_codec() {
         assert_eq!(res_buf, vec![].as_slice(), "{:?}", code);
     }
 }
+
+#[test]
+fn test_lookup() {
+    for entry in TABLE.iter() {
+        let cp = Codepoint::from_name(&entry.name).unwrap();
+        assert_eq!(cp.code, entry.code, "{}", entry.name);
+        let cp = Multicodec::from(entry.code).to_codepoint().unwrap();
+        assert_eq!(cp.code, entry.code);
+    }
+
+    // alias resolves to the same code, but code resolves to its name.
+    assert_eq!(Codepoint::from_name("ipfs").unwrap().code, P2P);
+    assert_eq!(Codepoint::from_code(P2P).unwrap().name, "p2p");
+
+    let codec = Multicodec::try_from("dag-cbor").unwrap();
+    assert_eq!(codec.to_code(), DAG_CBOR);
+    assert!(Multicodec::try_from("dag-xyz").is_err());
+    assert!(Multicodec::from(0xdead_beef).to_codepoint().is_none());
+
+    let handles: Vec<std::thread::JoinHandle<()>> = (0..4)
+        .map(|_| {
+            std::thread::spawn(|| {
+                for entry in TABLE.iter() {
+                    assert!(Codepoint::from_code(entry.code).is_some());
+                }
+            })
+        })
+        .collect();
+    for handle in handles.into_iter() {
+        handle.join().unwrap();
+    }
+}
+
+#[test]
+fn test_registry() {
+    let mut reg = Registry::new();
+    assert_eq!(reg.len(), TABLE.len());
+    assert_eq!(reg.by_name("dag-cbor").unwrap().code, DAG_CBOR);
+
+    let cp = Codepoint {
+        code: 0x300001,
+        name: "dag-draft".to_string(),
+        tag: Tag::Ipld,
+    };
+    reg.register(cp.clone()).unwrap();
+    assert_eq!(reg.len(), TABLE.len() + 1);
+    assert!(reg.by_code(0x300001).unwrap() == &cp);
+    assert!(reg.by_name("dag-draft").unwrap() == &cp);
+    assert!(reg.by_tag(Tag::Ipld).contains(&&cp));
+    assert!(reg.by_tag(Tag::Ipld).iter().any(|cp| cp.code == DAG_CBOR));
+    assert!(Codepoint::from_code(0x300001).is_none());
+
+    // same code, same name, outside private-use range.
+    let mut dup = cp.clone();
+    dup.name = "dag-draft2".to_string();
+    assert!(reg.register(dup).is_err());
+    let mut dup = cp.clone();
+    dup.code = 0x300002;
+    assert!(reg.register(dup).is_err());
+    let mut dup = cp.clone();
+    dup.code = 0x300002;
+    dup.name = "dag-cbor".to_string();
+    assert!(reg.register(dup).is_err());
+    let mut dup = cp.clone();
+    dup.code = DAG_JSON;
+    dup.name = "dag-draft2".to_string();
+    assert!(reg.register(dup).is_err());
+    assert_eq!(reg.len(), TABLE.len() + 1);
+}
+
+#[test]
+fn test_tag() {
+    for cp in TABLE.iter() {
+        assert_eq!(cp.tag.to_string().parse::<Tag>().unwrap(), cp.tag);
+    }
+    assert!("multi-hash".parse::<Tag>().is_err());
+
+    let codes: Vec<u128> = Multicodec::codepoints_by_tag(Tag::Multihash)
+        .map(|cp| cp.code)
+        .collect();
+    assert_eq!(codes, multihash_codes());
+    assert!(codes.contains(&SHA2_256));
+
+    let mut addrs = Multicodec::codepoints_by_tag(Tag::Multiaddr);
+    assert!(addrs.any(|cp| cp.name == "ipfs"));
+    assert!(Multicodec::codepoints_by_tag(Tag::Ipld).all(|cp| cp.tag == Tag::Ipld));
+}
+
+#[test]
+fn test_varint_fast_path() {
+    let codes: Vec<u128> = vec![
+        0,
+        0x7f,
+        0x80,
+        DAG_CBOR,
+        0x300001,
+        u64::MAX as u128,
+        (u64::MAX as u128) + 1,
+        u128::MAX,
+    ];
+    for code in codes.into_iter() {
+        let codec: Multicodec = code.into();
+        let mut buf = unsigned_varint::encode::u128_buffer();
+        let refr = unsigned_varint::encode::u128(code, &mut buf).to_vec();
+
+        let data = codec.encode().unwrap();
+        assert_eq!(data, refr, "{:x}", code);
+
+        let mut out = vec![0xaa];
+        assert_eq!(codec.encode_with(&mut out).unwrap(), refr.len());
+        assert_eq!(&out[1..], refr.as_slice());
+
+        let mut data = data;
+        data.push(0xff);
+        let (val, rem) = Multicodec::decode(&data).unwrap();
+        assert_eq!(val.to_code(), code);
+        assert_eq!(rem, &[0xff]);
+    }
+
+    assert!(Multicodec::decode(&[]).is_err());
+    assert!(Multicodec::decode(&[0x80]).is_err());
+}
+
+#[bench]
+fn bench_decode(b: &mut test::Bencher) {
+    let data: Vec<u8> = [SHA2_256, DAG_CBOR, IP4, TCP, P2P]
+        .iter()
+        .flat_map(|code| Multicodec::from(*code).encode().unwrap())
+        .collect();
+    b.iter(|| {
+        let mut rem = data.as_slice();
+        while !rem.is_empty() {
+            let (codec, r) = Multicodec::decode(rem).unwrap();
+            test::black_box(codec);
+            rem = r;
+        }
+    });
+}
+
+#[bench]
+fn bench_encode(b: &mut test::Bencher) {
+    let codecs: Vec<Multicodec> = [SHA2_256, DAG_CBOR, IP4, TCP, P2P]
+        .iter()
+        .map(|code| Multicodec::from(*code))
+        .collect();
+    let mut buf = Vec::with_capacity(64);
+    b.iter(|| {
+        buf.clear();
+        for codec in codecs.iter() {
+            codec.encode_with(&mut buf).unwrap();
+        }
+        test::black_box(&buf);
+    });
+}
+
+#[test]
+fn test_name_display() {
+    assert_eq!(
+        Multicodec::from_name("dag-cbor").unwrap().to_code(),
+        DAG_CBOR
+    );
+    assert_eq!(Multicodec::from_name("ipfs").unwrap().to_code(), P2P);
+    assert!(Multicodec::from_name("dag-cbor2").is_err());
+
+    let codec = Multicodec::from(DAG_CBOR);
+    assert_eq!(codec.to_string(), "dag-cbor");
+    assert_eq!(Multicodec::from(P2P).to_string(), "p2p");
+    assert_eq!(Multicodec::from(0x300001).to_string(), "0x300001");
+    for cp in TABLE.iter().filter(|cp| cp.name != "ipfs") {
+        let codec = Multicodec::from(cp);
+        assert_eq!(Multicodec::from_name(&codec.to_string()).unwrap(), codec);
+    }
+
+    assert!(Multicodec::from(SHA2_256).is_multihash());
+    assert!(!Multicodec::from(SHA2_256).is_ipld());
+    assert!(codec.is_ipld());
+    assert!(Multicodec::from(TCP).is_multiaddr());
+    assert!(!Multicodec::from(TCP).is_multihash());
+    assert_eq!(Multicodec::from(0x300001).to_tag(), None);
+    assert!(!Multicodec::from(0x300001).is_multihash());
+}
@@ -0,0 +1,172 @@
+//! Module implement bao style verified streaming for blake3 digests.
+//!
+//! Content is encoded in bao's combined format, an 8-byte little-endian
+//! content length, followed by the blake3 tree in pre-order, each parent
+//! node as the 64-byte pair of its children's chaining values and each
+//! leaf as the 1024-byte chunk. [VerifiedReader] checks every node
+//! against the expected hash before handing out its bytes, so large
+//! content can be streamed without holding the whole block in memory.
+//! _Refer [bao] spec for details_.
+//!
+//! [bao]: https://github.com/oconnor663/bao/blob/master/docs/spec.md
+
+use blake3::guts::{parent_cv, ChunkState};
+
+use std::io;
+
+use crate::{
+    multicodec,
+    multihash::{digest_size, Multihash},
+    Error, Result,
+};
+
+/// Size of a leaf chunk in blake3 tree.
+pub const CHUNK_LEN: usize = 1024;
+
+const HEADER_LEN: usize = 8;
+const PARENT_LEN: usize = 64;
+
+/// Encode `data` in bao's combined format. Return the blake3 multihash of
+/// `data` along with the encoded bytes.
+pub fn bao_encode(data: &[u8]) -> Result<(Multihash, Vec<u8>)> {
+    let mut out = Vec::with_capacity(HEADER_LEN + data.len() + (data.len() / CHUNK_LEN) * 64);
+    out.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    let hash = encode_subtree(data, 0, true, &mut out);
+
+    let mh = Multihash::from_digest(multicodec::BLAKE3.into(), hash.as_bytes())?;
+    Ok((mh, out))
+}
+
+fn encode_subtree(data: &[u8], chunk: u64, is_root: bool, out: &mut Vec<u8>) -> blake3::Hash {
+    if data.len() <= CHUNK_LEN {
+        out.extend_from_slice(data);
+        return ChunkState::new(chunk).update(data).finalize(is_root);
+    }
+
+    let off = out.len();
+    out.extend_from_slice(&[0; PARENT_LEN]);
+
+    let n = left_len(data.len() as u64) as usize;
+    let left = encode_subtree(&data[..n], chunk, false, out);
+    let right = encode_subtree(&data[n..], chunk + (n / CHUNK_LEN) as u64, false, out);
+    out[off..off + 32].copy_from_slice(left.as_bytes());
+    out[off + 32..off + PARENT_LEN].copy_from_slice(right.as_bytes());
+
+    parent_cv(&left, &right, is_root)
+}
+
+/// Reader that verifies bao encoded content, read from `R`, against a
+/// blake3 multihash. Only verified bytes are returned, reads fail with
+/// [io::ErrorKind::InvalidData] as soon as a node doesn't match.
+pub struct VerifiedReader<R> {
+    inner: R,
+    root: [u8; 32],
+    // subtrees yet to be read, (expected-hash, chunk-index, length, is-root)
+    stack: Option<Vec<([u8; 32], u64, u64, bool)>>,
+    chunk: Vec<u8>,
+    off: usize,
+}
+
+impl<R: io::Read> VerifiedReader<R> {
+    /// Create a reader for content addressed by `mh`, which must be a
+    /// 32-byte blake3 multihash.
+    pub fn new(mh: &Multihash, inner: R) -> Result<VerifiedReader<R>> {
+        let codec = mh.to_codec()?;
+        let digest = mh.to_digest()?;
+        match codec.to_code() {
+            multicodec::BLAKE3 if Some(digest.len()) == digest_size(multicodec::BLAKE3) => (),
+            _ => err_at!(Invalid, msg: "bao needs blake3-256 multihash, got {}", codec)?,
+        }
+
+        let mut root = [0; 32];
+        root.copy_from_slice(&digest);
+        let val = VerifiedReader {
+            inner,
+            root,
+            stack: None,
+            chunk: vec![],
+            off: 0,
+        };
+
+        Ok(val)
+    }
+
+    /// Return the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    // read and verify the next chunk, return false if there are no more
+    // chunks.
+    fn next_chunk(&mut self) -> io::Result<bool> {
+        if self.stack.is_none() {
+            let mut header = [0_u8; HEADER_LEN];
+            self.inner.read_exact(&mut header)?;
+            let content_len = u64::from_le_bytes(header);
+            self.stack = Some(vec![(self.root, 0, content_len, true)]);
+        }
+
+        let stack = self.stack.as_mut().unwrap();
+        while let Some((expected, chunk, len, is_root)) = stack.pop() {
+            if len <= CHUNK_LEN as u64 {
+                self.chunk.resize(len as usize, 0);
+                self.inner.read_exact(&mut self.chunk)?;
+                self.off = 0;
+                let hash = ChunkState::new(chunk).update(&self.chunk).finalize(is_root);
+                verify(&hash, &expected, chunk)?;
+                return Ok(true);
+            }
+
+            let mut parent = [0_u8; PARENT_LEN];
+            self.inner.read_exact(&mut parent)?;
+            let (mut left, mut right) = ([0_u8; 32], [0_u8; 32]);
+            left.copy_from_slice(&parent[..32]);
+            right.copy_from_slice(&parent[32..]);
+            let hash = parent_cv(&left.into(), &right.into(), is_root);
+            verify(&hash, &expected, chunk)?;
+
+            let n = left_len(len);
+            stack.push((right, chunk + n / (CHUNK_LEN as u64), len - n, false));
+            stack.push((left, chunk, n, false));
+        }
+
+        Ok(false)
+    }
+}
+
+impl<R: io::Read> io::Read for VerifiedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.off >= self.chunk.len() {
+            if !self.next_chunk()? {
+                return Ok(0);
+            }
+        }
+
+        let n = buf.len().min(self.chunk.len() - self.off);
+        buf[..n].copy_from_slice(&self.chunk[self.off..self.off + n]);
+        self.off += n;
+        Ok(n)
+    }
+}
+
+// length of left subtree for content of `len` bytes, that is, the largest
+// power-of-two number of chunks, leaving at least one byte for the right.
+fn left_len(len: u64) -> u64 {
+    let full_chunks = (len - 1) / (CHUNK_LEN as u64);
+    (1 << (63 - full_chunks.leading_zeros())) * (CHUNK_LEN as u64)
+}
+
+fn verify(hash: &blake3::Hash, expected: &[u8; 32], chunk: u64) -> io::Result<()> {
+    // blake3::Hash comparison is constant time.
+    match hash == expected {
+        true => Ok(()),
+        false => {
+            let msg = format!("bao hash mismatch at chunk {}", chunk);
+            Err(io::Error::new(io::ErrorKind::InvalidData, msg))
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "bao_test.rs"]
+mod bao_test;
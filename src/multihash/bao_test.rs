@@ -0,0 +1,78 @@
+use std::io::Read;
+
+use super::*;
+
+fn read_all(mh: &Multihash, encoded: &[u8]) -> io::Result<Vec<u8>> {
+    let mut reader = VerifiedReader::new(mh, encoded).unwrap();
+    let mut out = vec![];
+    reader.read_to_end(&mut out)?;
+    Ok(out)
+}
+
+#[test]
+fn test_bao_roundtrip() {
+    let sizes = [
+        0,
+        1,
+        1023,
+        1024,
+        1025,
+        2048,
+        2049,
+        3 * 1024 + 7,
+        65 * 1024 + 1,
+    ];
+    for size in sizes.iter() {
+        let data: Vec<u8> = (0..*size).map(|i| (i % 251) as u8).collect();
+        let (mh, encoded) = bao_encode(&data).unwrap();
+
+        // root hash is the regular blake3 digest of content.
+        let refmh = Multihash::new(multicodec::BLAKE3.into(), &data).unwrap();
+        assert!(mh == refmh, "size {}", size);
+
+        let chunks = std::cmp::max(1, (size + CHUNK_LEN - 1) / CHUNK_LEN);
+        assert_eq!(encoded.len(), 8 + size + (chunks - 1) * 64, "size {}", size);
+
+        assert_eq!(read_all(&mh, &encoded).unwrap(), data, "size {}", size);
+    }
+}
+
+#[test]
+fn test_bao_corrupt() {
+    let data: Vec<u8> = (0..5000).map(|i| (i % 251) as u8).collect();
+    let (mh, encoded) = bao_encode(&data).unwrap();
+
+    // flip a byte in the last chunk, earlier chunks are still handed out.
+    let mut bad = encoded.clone();
+    let n = bad.len();
+    bad[n - 1] ^= 0x01;
+    let mut reader = VerifiedReader::new(&mh, bad.as_slice()).unwrap();
+    let mut buf = vec![0; 4096];
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(buf, &data[..4096]);
+    let err = reader.read_to_end(&mut vec![]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    // tampered parent node.
+    let mut bad = encoded.clone();
+    bad[8] ^= 0x01;
+    assert!(read_all(&mh, &bad).is_err());
+
+    // tampered length header.
+    let mut bad = encoded.clone();
+    bad[0] ^= 0x01;
+    assert!(read_all(&mh, &bad).is_err());
+
+    // truncated stream.
+    assert!(read_all(&mh, &encoded[..encoded.len() - 10]).is_err());
+
+    // some other content.
+    let (other, _) = bao_encode(b"other").unwrap();
+    assert!(read_all(&other, &encoded).is_err());
+}
+
+#[test]
+fn test_bao_not_blake3() {
+    let mh = Multihash::new(multicodec::SHA2_256.into(), b"hello world").unwrap();
+    assert!(VerifiedReader::new(&mh, &b""[..]).is_err());
+}
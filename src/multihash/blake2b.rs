@@ -3,6 +3,7 @@ use crate::{multicodec, Error, Result};
 #[derive(Clone)]
 pub(crate) struct Blake2b {
     code: u128,
+    key: Vec<u8>,
     hasher: blake2b_simd::State,
     digest: Option<Vec<u8>>,
 }
@@ -16,6 +17,12 @@ impl PartialEq for Blake2b {
 }
 
 impl Blake2b {
+    fn to_state(code: u128, key: &[u8]) -> Result<blake2b_simd::State> {
+        let mut params = blake2b_simd::Params::new();
+        params.hash_length(Self::to_digest_bits(code)? / 8).key(key);
+        Ok(params.to_state())
+    }
+
     fn to_digest_bits(code: u128) -> Result<usize> {
         let len = match code {
             multicodec::BLAKE2B_8 => 8,
@@ -90,25 +97,28 @@ impl Blake2b {
 
 impl Blake2b {
     pub(crate) fn from_code(code: u128) -> Result<Blake2b> {
-        use blake2b_simd::Params;
+        Self::from_code_keyed(code, &[])
+    }
 
-        let mut hasher = Params::new();
-        hasher.hash_length(Self::to_digest_bits(code)?);
+    // keyed mode, where the hash acts as a MAC. An empty key is same as
+    // having no key.
+    pub(crate) fn from_code_keyed(code: u128, key: &[u8]) -> Result<Blake2b> {
+        if key.len() > blake2b_simd::KEYBYTES {
+            err_at!(BadInput, msg: "key length {} exceeds {}", key.len(), blake2b_simd::KEYBYTES)?
+        }
         Ok(Blake2b {
             code,
-            hasher: hasher.to_state(),
+            key: key.to_vec(),
+            hasher: Self::to_state(code, key)?,
             digest: None,
         })
     }
 
     pub(crate) fn decode(code: u128, digest: &[u8]) -> Result<Blake2b> {
-        use blake2b_simd::Params;
-
-        let mut hasher = Params::new();
-        hasher.hash_length(Self::to_digest_bits(code)?);
         Ok(Blake2b {
             code,
-            hasher: hasher.to_state(),
+            key: Vec::default(),
+            hasher: Self::to_state(code, &[])?,
             digest: Some(digest.to_vec()),
         })
     }
@@ -130,13 +140,7 @@ impl Blake2b {
     }
 
     pub(crate) fn reset(&mut self) -> Result<()> {
-        use blake2b_simd::Params;
-
-        self.hasher = {
-            let mut hasher = Params::new();
-            hasher.hash_length(Self::to_digest_bits(self.code)?);
-            hasher.to_state()
-        };
+        self.hasher = Self::to_state(self.code, &self.key)?;
         self.digest.take();
         Ok(())
     }
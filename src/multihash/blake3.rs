@@ -1,3 +1,5 @@
+use std::convert::TryInto;
+
 use crate::{Error, Result};
 
 #[derive(Clone)]
@@ -22,6 +24,22 @@ impl Blake3 {
         })
     }
 
+    // keyed mode, where the hash acts as a MAC, key must be exactly
+    // blake3::KEY_LEN bytes.
+    pub(crate) fn from_code_keyed(_code: u128, key: &[u8]) -> Result<Blake3> {
+        let key: &[u8; blake3::KEY_LEN] = match key.try_into() {
+            Ok(key) => key,
+            Err(_) => err_at!(
+                BadInput,
+                msg: "key length {} is not {}", key.len(), blake3::KEY_LEN
+            )?,
+        };
+        Ok(Blake3 {
+            hasher: blake3::Hasher::new_keyed(key),
+            digest: None,
+        })
+    }
+
     pub(crate) fn decode(_code: u128, digest: &[u8]) -> Result<Blake3> {
         Ok(Blake3 {
             hasher: blake3::Hasher::new(),
@@ -49,6 +67,7 @@ impl Blake3 {
     }
 
     pub(crate) fn reset(&mut self) -> Result<()> {
+        self.hasher.reset();
         self.digest.take();
         Ok(())
     }
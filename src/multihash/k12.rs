@@ -0,0 +1,80 @@
+use digest::{ExtendableOutput, Update, XofReader};
+
+use crate::{Error, Result};
+
+/// Default output length, in bytes, for kangarootwelve.
+pub const K12_SIZE: usize = 32;
+
+#[derive(Clone)]
+pub(crate) struct K12 {
+    buf: Vec<u8>,
+    size: usize,
+    digest: Option<Vec<u8>>,
+}
+
+impl Eq for K12 {}
+
+impl PartialEq for K12 {
+    fn eq(&self, other: &K12) -> bool {
+        self.digest == other.digest
+    }
+}
+
+impl K12 {
+    pub(crate) fn from_code(code: u128) -> Result<K12> {
+        Self::from_code_with_length(code, K12_SIZE)
+    }
+
+    // kangarootwelve is an extendable output function, generate `size`
+    // bytes of output.
+    pub(crate) fn from_code_with_length(_code: u128, size: usize) -> Result<K12> {
+        Ok(K12 {
+            buf: Vec::default(),
+            size,
+            digest: None,
+        })
+    }
+
+    pub(crate) fn decode(_code: u128, buf: &[u8]) -> Result<K12> {
+        Ok(K12 {
+            buf: Vec::default(),
+            size: buf.len(),
+            digest: Some(buf.to_vec()),
+        })
+    }
+
+    pub(crate) fn write(&mut self, bytes: &[u8]) -> Result<()> {
+        match &self.digest {
+            None => self.buf.extend_from_slice(bytes),
+            Some(_) => err_at!(Invalid, msg: "finalized")?,
+        };
+        Ok(())
+    }
+
+    pub(crate) fn finish(&mut self) -> Result<()> {
+        self.digest = match &self.digest {
+            None => {
+                let mut hasher = k12::KangarooTwelve::new();
+                hasher.update(&self.buf);
+                let mut digest = vec![0; self.size];
+                hasher.finalize_xof().read(&mut digest);
+                Some(digest)
+            }
+            Some(_) => err_at!(Invalid, msg: "double finalize")?,
+        };
+        Ok(())
+    }
+
+    pub(crate) fn reset(&mut self) -> Result<()> {
+        self.buf.clear();
+        self.digest.take();
+        Ok(())
+    }
+
+    pub(crate) fn as_digest(&self) -> Result<&[u8]> {
+        match &self.digest {
+            Some(digest) => Ok(digest),
+            None => err_at!(Invalid, msg: "no digest"),
+        }
+    }
+}
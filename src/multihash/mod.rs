@@ -2,34 +2,44 @@
 //!
 //! [multihash]: https://multiformats.io/multihash/
 
-// TODO:
-// 1. For Shake128 and Shake256 algorithm variable output length
-//    `d` must be included as part of the spec and API.
+mod bao;
 mod blake2b;
 mod blake2s;
 mod blake3;
 mod identity;
+mod k12;
 mod md4;
 mod md5;
+mod murmur3;
 mod ripemd;
 mod sha1;
 mod sha2;
 mod sha3;
 mod skein;
+mod sm3;
 
-use std::{fmt, io, result};
+use std::{cmp, fmt, hash, io, result};
+
+pub use bao::{bao_encode, VerifiedReader};
+pub use k12::K12_SIZE;
+pub use sha3::{SHAKE_128_SIZE, SHAKE_256_SIZE};
 
 use crate::multihash::{
-    blake2b::Blake2b, blake2s::Blake2s, blake3::Blake3, identity::Identity, md4::Md4, md5::Md5,
-    ripemd::RipeMd, sha1::Sha1, sha2::Sha2, sha3::Sha3, skein::Skein,
+    blake2b::Blake2b, blake2s::Blake2s, blake3::Blake3, identity::Identity, k12::K12, md4::Md4,
+    md5::Md5, murmur3::Murmur3, ripemd::RipeMd, sha1::Sha1, sha2::Sha2, sha3::Sha3, skein::Skein,
+    sm3::Sm3,
 };
 
-use crate::{multicodec, multicodec::Multicodec, Error, Result};
+use crate::{multicodec, multicodec::Multicodec, util, Error, Result};
 
 /// Type adapts several hashing algorithms within [multihash] specification.
 ///
 /// [multihash]: https://multiformats.io/multihash/
-#[derive(Clone, Eq, PartialEq)]
+///
+/// Equality, ordering and hashing are based on the codec and digest,
+/// ordered same as the binary encoding, refer [Multihash::encode].
+/// Multihash whose digest is not yet generated orders before others.
+#[derive(Clone)]
 pub struct Multihash {
     inner: Inner,
 }
@@ -48,6 +58,9 @@ enum Inner {
     Md5(Multicodec, Md5),
     Skein(Multicodec, Skein),
     RipeMd(Multicodec, RipeMd),
+    Murmur3(Multicodec, Murmur3),
+    Sm3(Multicodec, Sm3),
+    K12(Multicodec, K12),
 }
 
 impl fmt::Display for Multihash {
@@ -69,6 +82,9 @@ impl fmt::Display for Multihash {
                 Md5(c, h) => (c.clone(), h.as_digest().ok()?.to_vec()),
                 Skein(c, h) => (c.clone(), h.as_digest().ok()?.to_vec()),
                 RipeMd(c, h) => (c.clone(), h.as_digest().ok()?.to_vec()),
+                Murmur3(c, h) => (c.clone(), h.as_digest().ok()?.to_vec()),
+                Sm3(c, h) => (c.clone(), h.as_digest().ok()?.to_vec()),
+                K12(c, h) => (c.clone(), h.as_digest().ok()?.to_vec()),
                 Binary(data) => get_parts(&Multihash::decode(&data).ok()?.0.inner)?,
             };
 
@@ -91,17 +107,266 @@ impl fmt::Display for Multihash {
     }
 }
 
+/// Size of chunks read by [Multihash::digest_reader].
+pub const READ_CHUNK: usize = 64 * 1024;
+
+/// Return the digest size, in bytes, generated by multihash algorithm
+/// `code`. Return None for identity, whose digest is the data itself, for
+/// shake-128, shake-256 and kangarootwelve, whose output length is
+/// variable, refer [Multihash::from_codec_with_length], and for
+/// algorithms not implemented by this package.
+pub fn digest_size(code: u128) -> Option<usize> {
+    let size = match code {
+        multicodec::MURMUR3_32 => 4,
+        multicodec::MD4 | multicodec::MD5 | multicodec::RIPEMD_128 => 16,
+        multicodec::MURMUR3_128 => 16,
+        multicodec::SHA1 | multicodec::RIPEMD_160 => 20,
+        multicodec::SHA3_224 | multicodec::KECCAK_224 => 28,
+        multicodec::SHA2_256 | multicodec::DBL_SHA2_256 => 32,
+        multicodec::SHA2_256_TRUNC254_PADDED => 32,
+        multicodec::SHA3_256 | multicodec::KECCAK_256 => 32,
+        multicodec::BLAKE3 | multicodec::RIPEMD_256 => 32,
+        multicodec::SM3_256 => 32,
+        multicodec::RIPEMD_320 => 40,
+        multicodec::SHA3_384 | multicodec::KECCAK_384 => 48,
+        multicodec::SHA2_512 | multicodec::SHA3_512 | multicodec::KECCAK_512 => 64,
+        // blake2 and skein codes carry the digest size in their lower
+        // bits, in bytes.
+        multicodec::BLAKE2B_8..=multicodec::BLAKE2B_512 => code - 0xb200,
+        multicodec::BLAKE2S_8..=multicodec::BLAKE2S_256 => code - 0xb240,
+        multicodec::SKEIN256_8..=multicodec::SKEIN256_256 => code - 0xb300,
+        multicodec::SKEIN512_8..=multicodec::SKEIN512_512 => code - 0xb320,
+        multicodec::SKEIN1024_8..=multicodec::SKEIN1024_1024 => code - 0xb360,
+        _ => return None,
+    };
+    Some(size as usize)
+}
+
 impl From<Inner> for Multihash {
     fn from(inner: Inner) -> Multihash {
         Multihash { inner }
     }
 }
 
+impl Eq for Multihash {}
+
+impl PartialEq for Multihash {
+    fn eq(&self, other: &Multihash) -> bool {
+        self.to_key() == other.to_key()
+    }
+}
+
+impl PartialOrd for Multihash {
+    fn partial_cmp(&self, other: &Multihash) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Multihash {
+    fn cmp(&self, other: &Multihash) -> cmp::Ordering {
+        let (mut a, mut b) = (Scratch::default(), Scratch::default());
+        match (self.to_key(), other.to_key()) {
+            (Key::Pending(x), Key::Pending(y)) => x.cmp(&y),
+            (Key::Pending(_), _) => cmp::Ordering::Less,
+            (_, Key::Pending(_)) => cmp::Ordering::Greater,
+            (x, y) => {
+                let x = x.to_encoded(&mut a);
+                let y = y.to_encoded(&mut b);
+                x.iter()
+                    .flat_map(|s| s.iter())
+                    .cmp(y.iter().flat_map(|s| s.iter()))
+            }
+        }
+    }
+}
+
+impl hash::Hash for Multihash {
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: hash::Hasher,
+    {
+        self.to_key().hash(state)
+    }
+}
+
+// Multihash as codec and digest, borrowed from the value.
+#[derive(Eq, PartialEq, Hash)]
+enum Key<'a> {
+    // digest is not generated yet.
+    Pending(u128),
+    Digest(u128, &'a [u8]),
+    // lazy multihash, refer [Multihash::decode_lazy], that fail to parse.
+    Raw(&'a [u8]),
+}
+
+// buffers for codec and digest-length varints.
+#[derive(Default)]
+struct Scratch {
+    code: [u8; 19],
+    #[cfg(not(target_arch = "wasm32"))]
+    size: [u8; 10],
+    #[cfg(target_arch = "wasm32")]
+    size: [u8; 5],
+}
+
+impl<'a> Key<'a> {
+    // binary encoding, as a list of slices, without allocation.
+    fn to_encoded<'b>(&self, scratch: &'b mut Scratch) -> [&'b [u8]; 3]
+    where
+        'a: 'b,
+    {
+        use unsigned_varint::encode;
+
+        match *self {
+            Key::Pending(_) => [&[], &[], &[]],
+            Key::Digest(code, digest) => {
+                let size = encode::usize(digest.len(), &mut scratch.size);
+                [encode::u128(code, &mut scratch.code), size, digest]
+            }
+            Key::Raw(data) => [data, &[], &[]],
+        }
+    }
+}
+
 impl Multihash {
     /// Create a Multihash instance, of type multi-codec for data. Digest
     /// will be created for `data`, using the multi-hash algorithm specified
-    /// by `codec`.
+    /// by `codec`. Returned instance is finalized and ready to encode, same
+    /// as writing `data` via [io::Write] and flushing it.
     pub fn new(codec: Multicodec, data: &[u8]) -> Result<Multihash> {
+        let mut mh = Self::from_codec(codec)?;
+        mh.write(data)?.finish()?;
+
+        Ok(mh)
+    }
+
+    /// Verify that `data` hashes to this multihash's digest, using the
+    /// same algorithm. Digests are compared in constant time. Truncated
+    /// digests are verified against the prefix of the computed digest,
+    /// except for identity which must match exactly. Variable length
+    /// digests, like shake-128, are computed to the stored length.
+    pub fn verify(&self, data: &[u8]) -> Result<bool> {
+        let codec = self.to_codec()?;
+        let digest = self.to_digest()?;
+
+        let computed = match codec.to_code() {
+            multicodec::SHAKE_128 | multicodec::SHAKE_256 | multicodec::KANGAROOTWELVE
+                if !digest.is_empty() =>
+            {
+                let mut mh = Self::from_codec_with_length(codec, digest.len())?;
+                mh.write(data)?.finish()?;
+                mh.to_digest()?
+            }
+            _ => Self::new(codec, data)?.to_digest()?,
+        };
+
+        let val = match (codec.to_code(), digest.len()) {
+            // identity "digest" is the data itself, it can't be truncated.
+            (multicodec::IDENTITY, _) => util::ct_eq(&digest, &computed),
+            (_, 0) => computed.is_empty(),
+            (_, n) if n <= computed.len() => util::ct_eq(&digest, &computed[..n]),
+            (_, _) => false,
+        };
+        Ok(val)
+    }
+
+    /// Create a Multihash instance, of type multi-codec, for data read
+    /// from `r` until EOF. Data is consumed in chunks of [READ_CHUNK]
+    /// bytes, so that large files can be hashed without holding them in
+    /// memory.
+    pub fn digest_reader<R: io::Read>(codec: Multicodec, mut r: R) -> Result<Multihash> {
+        let mut mh = Self::from_codec(codec)?;
+        let mut buf = vec![0; READ_CHUNK];
+        loop {
+            match r.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => mh.write(&buf[..n])?,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => err_at!(IOError, msg: "read: {}", err)?,
+            };
+        }
+        mh.finish()?;
+
+        Ok(mh)
+    }
+
+    /// Async version of [Self::digest_reader], for data read from an
+    /// [AsyncRead][futures::io::AsyncRead].
+    #[cfg(feature = "async")]
+    pub async fn digest_reader_async<R>(codec: Multicodec, mut r: R) -> Result<Multihash>
+    where
+        R: futures::io::AsyncRead + Unpin,
+    {
+        use futures::io::AsyncReadExt;
+
+        let mut mh = Self::from_codec(codec)?;
+        let mut buf = vec![0; READ_CHUNK];
+        loop {
+            match r.read(&mut buf).await {
+                Ok(0) => break,
+                Ok(n) => mh.write(&buf[..n])?,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => err_at!(IOError, msg: "read: {}", err)?,
+            };
+        }
+        mh.finish()?;
+
+        Ok(mh)
+    }
+
+    /// Create a Multihash instance for shake-128, shake-256 or
+    /// kangarootwelve, generating a digest of `d` bytes. Data is
+    /// accumulated using the [io::Write] implementation and digest is
+    /// generated on flush. [Multihash::new] generates [SHAKE_128_SIZE],
+    /// [SHAKE_256_SIZE] and [K12_SIZE] bytes respectively.
+    pub fn from_codec_with_length(codec: Multicodec, d: usize) -> Result<Multihash> {
+        let code = codec.to_code();
+        let inner = match code {
+            _ if d == 0 => err_at!(BadInput, msg: "zero digest length for {}", codec)?,
+            multicodec::SHAKE_128 | multicodec::SHAKE_256 => {
+                let hasher = Sha3::from_code_with_length(code, d)?;
+                Inner::Sha3(codec, hasher)
+            }
+            multicodec::KANGAROOTWELVE => {
+                let hasher = K12::from_code_with_length(code, d)?;
+                Inner::K12(codec, hasher)
+            }
+            _ => err_at!(BadInput, msg: "{} has fixed digest length", codec)?,
+        };
+
+        Ok(inner.into())
+    }
+
+    /// Create a keyed Multihash instance for blake2b, blake2s or blake3,
+    /// where the digest acts as a MAC over data, for authenticating
+    /// content between peers sharing the `key`. Keys can be upto 64 bytes
+    /// for blake2b and 32 bytes for blake2s, blake3 keys must be exactly
+    /// 32 bytes. Data is accumulated using the [io::Write] implementation
+    /// and digest is generated on flush.
+    pub fn from_codec_keyed(codec: Multicodec, key: &[u8]) -> Result<Multihash> {
+        let code = codec.to_code();
+        let inner = match code {
+            multicodec::BLAKE2B_8..=multicodec::BLAKE2B_512 => {
+                let hasher = Blake2b::from_code_keyed(code, key)?;
+                Inner::Blake2b(codec, hasher)
+            }
+            multicodec::BLAKE2S_8..=multicodec::BLAKE2S_256 => {
+                let hasher = Blake2s::from_code_keyed(code, key)?;
+                Inner::Blake2s(codec, hasher)
+            }
+            multicodec::BLAKE3 => {
+                let hasher = Blake3::from_code_keyed(code, key)?;
+                Inner::Blake3(codec, hasher)
+            }
+            _ => err_at!(BadInput, msg: "{} does not support keyed hashing", codec)?,
+        };
+
+        Ok(inner.into())
+    }
+
+    // Create a Multihash instance, of type multi-codec, ready to
+    // accumulate data.
+    fn from_codec(codec: Multicodec) -> Result<Multihash> {
         let code = codec.to_code();
         let inner = match code {
             multicodec::IDENTITY => {
@@ -112,7 +377,10 @@ impl Multihash {
                 let hasher = Sha1::from_code(code)?;
                 Inner::Sha1(codec, hasher)
             }
-            multicodec::SHA2_256 | multicodec::SHA2_512 | multicodec::DBL_SHA2_256 => {
+            multicodec::SHA2_256
+            | multicodec::SHA2_512
+            | multicodec::DBL_SHA2_256
+            | multicodec::SHA2_256_TRUNC254_PADDED => {
                 let hasher = Sha2::from_code(code)?;
                 Inner::Sha2(codec, hasher)
             }
@@ -148,25 +416,41 @@ impl Multihash {
                 let hasher = RipeMd::from_code(code)?;
                 Inner::RipeMd(codec, hasher)
             }
-            // multicodec::SM3_256 => unimplemented!(),
+            multicodec::MURMUR3_128 | multicodec::MURMUR3_32 => {
+                let hasher = Murmur3::from_code(code)?;
+                Inner::Murmur3(codec, hasher)
+            }
+            multicodec::SM3_256 => {
+                let hasher = Sm3::from_code(code)?;
+                Inner::Sm3(codec, hasher)
+            }
+            multicodec::KANGAROOTWELVE => {
+                let hasher = K12::from_code(code)?;
+                Inner::K12(codec, hasher)
+            }
             // multicodec::POSEIDON_BLS12_381_A2_FC1 => unimplemented!(),
             // multicodec::POSEIDON_BLS12_381_A2_FC1_SC => unimplemented!(),
-            // multicodec::KANGAROOTWELVE => unimplemented!(),
             // multicodec::X11 => unimplemented!(),
             // multicodec::BMT => unimplemented!(),
-            // multicodec::SHA2_256_TRUNC254_PADDED => unimplemented!(),
             codec => err_at!(NotImplemented, msg: "codec {}", codec)?,
         };
 
-        let mut mh: Multihash = inner.into();
-        mh.write(data)?.finish()?;
-
-        Ok(mh)
+        Ok(inner.into())
     }
 
-    /// New multihash from digest and multihash-type.
+    /// New multihash from digest and multihash-type. Fail if digest is
+    /// longer than the algorithm's [digest_size], shorter digests are
+    /// treated as truncated.
     pub fn from_digest(codec: Multicodec, digest: &[u8]) -> Result<Multihash> {
         let code = codec.to_code();
+        match digest_size(code) {
+            Some(size) if digest.len() > size => err_at!(
+                BadInput,
+                msg: "{} digest length {} exceeds {}", codec, digest.len(), size
+            )?,
+            _ => (),
+        }
+
         let inner = match code {
             multicodec::IDENTITY => {
                 let hasher = Identity::decode(code, digest)?;
@@ -176,7 +460,10 @@ impl Multihash {
                 let hasher = Sha1::decode(code, digest)?;
                 Inner::Sha1(codec, hasher)
             }
-            multicodec::SHA2_256 | multicodec::SHA2_512 | multicodec::DBL_SHA2_256 => {
+            multicodec::SHA2_256
+            | multicodec::SHA2_512
+            | multicodec::DBL_SHA2_256
+            | multicodec::SHA2_256_TRUNC254_PADDED => {
                 let hasher = Sha2::decode(code, digest)?;
                 Inner::Sha2(codec, hasher)
             }
@@ -212,6 +499,18 @@ impl Multihash {
                 let hasher = RipeMd::decode(code, digest)?;
                 Inner::RipeMd(codec, hasher)
             }
+            multicodec::MURMUR3_128 | multicodec::MURMUR3_32 => {
+                let hasher = Murmur3::decode(code, digest)?;
+                Inner::Murmur3(codec, hasher)
+            }
+            multicodec::SM3_256 => {
+                let hasher = Sm3::decode(code, digest)?;
+                Inner::Sm3(codec, hasher)
+            }
+            multicodec::KANGAROOTWELVE => {
+                let hasher = K12::decode(code, digest)?;
+                Inner::K12(codec, hasher)
+            }
             codec => err_at!(NotImplemented, msg: "codec {}", codec)?,
         };
 
@@ -306,6 +605,9 @@ impl Multihash {
             Inner::Md5(_, hasher) => hasher.as_digest()?,
             Inner::Skein(_, hasher) => hasher.as_digest()?,
             Inner::RipeMd(_, hasher) => hasher.as_digest()?,
+            Inner::Murmur3(_, hasher) => hasher.as_digest()?,
+            Inner::Sm3(_, hasher) => hasher.as_digest()?,
+            Inner::K12(_, hasher) => hasher.as_digest()?,
         };
         let n = {
             let out = self.to_codec()?.encode()?;
@@ -352,6 +654,9 @@ impl Multihash {
             Inner::Md5(_, hasher) => hasher.write(data)?,
             Inner::Skein(_, hasher) => hasher.write(data)?,
             Inner::RipeMd(_, hasher) => hasher.write(data)?,
+            Inner::Murmur3(_, hasher) => hasher.write(data)?,
+            Inner::Sm3(_, hasher) => hasher.write(data)?,
+            Inner::K12(_, hasher) => hasher.write(data)?,
             Inner::Binary(_) => err_at!(Invalid, msg: "mh in binary form")?,
         };
         Ok(self)
@@ -372,6 +677,9 @@ impl Multihash {
             Inner::Md5(_, hasher) => hasher.finish()?,
             Inner::Skein(_, hasher) => hasher.finish()?,
             Inner::RipeMd(_, hasher) => hasher.finish()?,
+            Inner::Murmur3(_, hasher) => hasher.finish()?,
+            Inner::Sm3(_, hasher) => hasher.finish()?,
+            Inner::K12(_, hasher) => hasher.finish()?,
             Inner::Binary(_) => err_at!(Invalid, msg: "mh in binary form")?,
         };
         Ok(self)
@@ -393,6 +701,9 @@ impl Multihash {
             Inner::Md5(_, hasher) => hasher.reset()?,
             Inner::Skein(_, hasher) => hasher.reset()?,
             Inner::RipeMd(_, hasher) => hasher.reset()?,
+            Inner::Murmur3(_, hasher) => hasher.reset()?,
+            Inner::Sm3(_, hasher) => hasher.reset()?,
+            Inner::K12(_, hasher) => hasher.reset()?,
             Inner::Binary(_) => err_at!(Invalid, msg: "mh in binary form")?,
         };
         Ok(self)
@@ -400,6 +711,39 @@ impl Multihash {
 }
 
 impl Multihash {
+    fn to_key(&self) -> Key<'_> {
+        use unsigned_varint::decode;
+
+        let (code, digest) = match &self.inner {
+            Inner::Identity(c, h) => (c.to_code(), h.as_digest().ok()),
+            Inner::Sha1(c, h) => (c.to_code(), h.as_digest().ok()),
+            Inner::Sha2(c, h) => (c.to_code(), h.as_digest().ok()),
+            Inner::Sha3(c, h) => (c.to_code(), h.as_digest().ok()),
+            Inner::Blake3(c, h) => (c.to_code(), h.as_digest().ok()),
+            Inner::Blake2b(c, h) => (c.to_code(), h.as_digest().ok()),
+            Inner::Blake2s(c, h) => (c.to_code(), h.as_digest().ok()),
+            Inner::Md4(c, h) => (c.to_code(), h.as_digest().ok()),
+            Inner::Md5(c, h) => (c.to_code(), h.as_digest().ok()),
+            Inner::Skein(c, h) => (c.to_code(), h.as_digest().ok()),
+            Inner::RipeMd(c, h) => (c.to_code(), h.as_digest().ok()),
+            Inner::Murmur3(c, h) => (c.to_code(), h.as_digest().ok()),
+            Inner::Sm3(c, h) => (c.to_code(), h.as_digest().ok()),
+            Inner::K12(c, h) => (c.to_code(), h.as_digest().ok()),
+            Inner::Binary(data) => {
+                let parts =
+                    decode::u128(data).and_then(|(code, rem)| Ok((code, decode::usize(rem)?)));
+                return match parts {
+                    Ok((code, (n, digest))) if digest.len() == n => Key::Digest(code, digest),
+                    _ => Key::Raw(data),
+                };
+            }
+        };
+        match digest {
+            Some(digest) => Key::Digest(code, digest),
+            None => Key::Pending(code),
+        }
+    }
+
     /// Return the multihash codec.
     pub fn to_codec(&self) -> Result<Multicodec> {
         match &self.inner {
@@ -414,6 +758,9 @@ impl Multihash {
             Inner::Md5(codec, _) => Ok(codec.clone()),
             Inner::Skein(codec, _) => Ok(codec.clone()),
             Inner::RipeMd(codec, _) => Ok(codec.clone()),
+            Inner::Murmur3(codec, _) => Ok(codec.clone()),
+            Inner::Sm3(codec, _) => Ok(codec.clone()),
+            Inner::K12(codec, _) => Ok(codec.clone()),
             Inner::Binary(data) => Self::decode(data)?.0.to_codec(),
         }
     }
@@ -434,6 +781,9 @@ impl Multihash {
             Inner::Md5(_, h) => Ok(h.as_digest()?.to_vec()),
             Inner::Skein(_, h) => Ok(h.as_digest()?.to_vec()),
             Inner::RipeMd(_, h) => Ok(h.as_digest()?.to_vec()),
+            Inner::Murmur3(_, h) => Ok(h.as_digest()?.to_vec()),
+            Inner::Sm3(_, h) => Ok(h.as_digest()?.to_vec()),
+            Inner::K12(_, h) => Ok(h.as_digest()?.to_vec()),
             Inner::Binary(data) => Self::decode(data)?.0.to_digest(),
         }
     }
@@ -453,6 +803,9 @@ impl Multihash {
             Inner::Md5(c, h) => Ok((c.clone(), h.as_digest()?.to_vec())),
             Inner::Skein(c, h) => Ok((c.clone(), h.as_digest()?.to_vec())),
             Inner::RipeMd(c, h) => Ok((c.clone(), h.as_digest()?.to_vec())),
+            Inner::Murmur3(c, h) => Ok((c.clone(), h.as_digest()?.to_vec())),
+            Inner::Sm3(c, h) => Ok((c.clone(), h.as_digest()?.to_vec())),
+            Inner::K12(c, h) => Ok((c.clone(), h.as_digest()?.to_vec())),
             Inner::Binary(data) => Self::decode(data)?.0.unwrap(),
         }
     }
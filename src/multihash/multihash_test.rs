@@ -69,3 +69,378 @@ fn test_multihash_pretty() {
         "sha2-256-256-b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9".to_string(),
     );
 }
+
+#[test]
+fn test_digest_size() {
+    assert_eq!(digest_size(multicodec::SHA2_256), Some(32));
+    assert_eq!(digest_size(multicodec::SHA1), Some(20));
+    assert_eq!(digest_size(multicodec::BLAKE2B_256), Some(32));
+    assert_eq!(digest_size(multicodec::BLAKE2S_128), Some(16));
+    assert_eq!(digest_size(multicodec::SKEIN1024_1024), Some(128));
+    assert_eq!(digest_size(multicodec::IDENTITY), None);
+    assert_eq!(digest_size(multicodec::SHAKE_128), None);
+
+    // generated digests agree with the table.
+    for code in [
+        multicodec::SHA2_512,
+        multicodec::BLAKE2B_160,
+        multicodec::SKEIN512_256,
+    ]
+    .iter()
+    {
+        let mh = Multihash::new((*code).into(), b"hello world").unwrap();
+        assert_eq!(Some(mh.to_digest().unwrap().len()), digest_size(*code));
+    }
+
+    // truncated digest is accepted, over-long digest is rejected.
+    let mut data = vec![0x12, 20];
+    data.extend_from_slice(&[0xab; 20]);
+    assert!(Multihash::decode(&data).is_ok());
+
+    let mut data = vec![0x12, 33];
+    data.extend_from_slice(&[0xab; 33]);
+    assert!(Multihash::decode(&data).is_err());
+    assert!(Multihash::from_digest(multicodec::SHA1.into(), &[0; 21]).is_err());
+}
+
+#[test]
+fn test_digest_reader() {
+    let data: Vec<u8> = (0..(READ_CHUNK * 2 + 100)).map(|i| i as u8).collect();
+    for code in [
+        multicodec::SHA2_256,
+        multicodec::BLAKE3,
+        multicodec::BLAKE2B_256,
+        multicodec::MD5,
+        multicodec::IDENTITY,
+    ]
+    .iter()
+    {
+        let refmh = Multihash::new((*code).into(), &data).unwrap();
+        let mh = Multihash::digest_reader((*code).into(), data.as_slice()).unwrap();
+        assert!(mh == refmh, "{}", *code);
+    }
+
+    let mh = Multihash::digest_reader(multicodec::SHA2_256.into(), io::empty()).unwrap();
+    assert!(mh == Multihash::new(multicodec::SHA2_256.into(), b"").unwrap());
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn test_digest_reader_async() {
+    use futures::{executor::block_on, io::Cursor};
+
+    let data: Vec<u8> = (0..(READ_CHUNK + 100)).map(|i| i as u8).collect();
+    let refmh = Multihash::new(multicodec::SHA2_256.into(), &data).unwrap();
+    let fut = Multihash::digest_reader_async(multicodec::SHA2_256.into(), Cursor::new(data));
+    let mh = block_on(fut).unwrap();
+    assert!(mh == refmh);
+}
+
+#[test]
+fn test_shake_length() {
+    use std::io::Write;
+
+    let data = b"hello world";
+
+    let mh = Multihash::new(multicodec::SHAKE_128.into(), data).unwrap();
+    assert_eq!(mh.to_digest().unwrap().len(), SHAKE_128_SIZE);
+    let mh256 = Multihash::new(multicodec::SHAKE_256.into(), data).unwrap();
+    assert_eq!(mh256.to_digest().unwrap().len(), SHAKE_256_SIZE);
+
+    let mut mh20 = Multihash::from_codec_with_length(multicodec::SHAKE_128.into(), 20).unwrap();
+    mh20.write_all(data).unwrap();
+    mh20.flush().unwrap();
+    let digest = mh20.to_digest().unwrap();
+    assert_eq!(digest.len(), 20);
+    // xof output is a prefix of the longer output.
+    assert_eq!(digest.as_slice(), &mh.to_digest().unwrap()[..20]);
+
+    let buf = mh20.encode().unwrap();
+    let (decoded, rem) = Multihash::decode(&buf).unwrap();
+    assert!(rem.is_empty());
+    assert!(decoded == mh20);
+    assert_eq!(decoded.encode().unwrap(), buf);
+
+    assert!(Multihash::from_codec_with_length(multicodec::SHAKE_256.into(), 0).is_err());
+    assert!(Multihash::from_codec_with_length(multicodec::SHA2_256.into(), 20).is_err());
+}
+
+#[test]
+fn test_murmur3() {
+    use data_encoding::HEXLOWER;
+
+    let data = b"hello world";
+
+    let mh = Multihash::new(multicodec::MURMUR3_128.into(), data).unwrap();
+    let digest = mh.to_digest().unwrap();
+    assert_eq!(HEXLOWER.encode(&digest), "533f6046eb7f610eab97467d60eb63b1");
+    assert_eq!(Some(digest.len()), digest_size(multicodec::MURMUR3_128));
+
+    let mh = Multihash::new(multicodec::MURMUR3_32.into(), data).unwrap();
+    let digest = mh.to_digest().unwrap();
+    assert_eq!(HEXLOWER.encode(&digest), "5e928f0f");
+
+    let buf = mh.encode().unwrap();
+    let (decoded, _) = Multihash::decode(&buf).unwrap();
+    assert!(decoded == mh);
+    assert_eq!(
+        decoded.to_codec().unwrap().to_code(),
+        multicodec::MURMUR3_32
+    );
+}
+
+#[test]
+fn test_sm3() {
+    use data_encoding::HEXLOWER;
+
+    // vectors from GB/T 32905-2016, appendix A.
+    let mh = Multihash::new(multicodec::SM3_256.into(), b"abc").unwrap();
+    assert_eq!(
+        HEXLOWER.encode(&mh.to_digest().unwrap()),
+        "66c7f0f462eeedd9d1f2d46bdc10e4e24167c4875cf2f7a2297da02b8f4ba8e0"
+    );
+    let data = b"abcd".repeat(16);
+    let mh = Multihash::new(multicodec::SM3_256.into(), &data).unwrap();
+    assert_eq!(
+        HEXLOWER.encode(&mh.to_digest().unwrap()),
+        "debe9ff92275b8a138604889c18e5a4d6fdb70e5387e5765293dcba39c0c5732"
+    );
+
+    let buf = mh.encode().unwrap();
+    let (decoded, _) = Multihash::decode(&buf).unwrap();
+    assert!(decoded == mh);
+}
+
+#[test]
+fn test_k12() {
+    use data_encoding::HEXLOWER;
+    use std::io::Write;
+
+    // vector from the KangarooTwelve specification, empty message and
+    // empty customization string.
+    let mh = Multihash::new(multicodec::KANGAROOTWELVE.into(), b"").unwrap();
+    let digest = mh.to_digest().unwrap();
+    assert_eq!(digest.len(), K12_SIZE);
+    assert_eq!(
+        HEXLOWER.encode(&digest),
+        "1ac2d450fc3b4205d19da7bfca1b37513c0803577ac7167f06fe2ce1f0ef39e5"
+    );
+
+    let mut mh64 =
+        Multihash::from_codec_with_length(multicodec::KANGAROOTWELVE.into(), 64).unwrap();
+    mh64.flush().unwrap();
+    assert_eq!(&mh64.to_digest().unwrap()[..32], digest.as_slice());
+
+    // write, finish and reset lifecycle.
+    let mut mh = Multihash::from_codec_with_length(multicodec::KANGAROOTWELVE.into(), 32).unwrap();
+    mh.write_all(b"hello ").unwrap();
+    mh.write_all(b"world").unwrap();
+    mh.flush().unwrap();
+    assert!(mh == Multihash::new(multicodec::KANGAROOTWELVE.into(), b"hello world").unwrap());
+    assert!(mh.write_all(b"more").is_err());
+
+    let buf = mh64.encode().unwrap();
+    let (decoded, rem) = Multihash::decode(&buf).unwrap();
+    assert!(rem.is_empty());
+    assert!(decoded == mh64);
+}
+
+#[test]
+fn test_new() {
+    use std::io::Write;
+
+    let data = b"hello world";
+    for code in [
+        multicodec::IDENTITY,
+        multicodec::SHA2_256,
+        multicodec::SHA3_512,
+        multicodec::BLAKE3,
+        multicodec::MD5,
+    ]
+    .iter()
+    {
+        let mh = Multihash::new((*code).into(), data).unwrap();
+
+        let mut other = Multihash::from_codec((*code).into()).unwrap();
+        other.write_all(&data[..5]).unwrap();
+        other.write_all(&data[5..]).unwrap();
+        other.flush().unwrap();
+        assert!(mh == other);
+
+        // finalized, encodes without further calls and rejects writes.
+        let buf = mh.encode().unwrap();
+        assert!(Multihash::decode(&buf).unwrap().0 == mh);
+        assert!(mh.clone().write_all(data).is_err());
+    }
+
+    assert!(Multihash::new(multicodec::X11.into(), data).is_err());
+}
+
+#[test]
+fn test_verify() {
+    let data = b"hello world";
+    for code in [
+        multicodec::IDENTITY,
+        multicodec::SHA2_256,
+        multicodec::BLAKE2B_256,
+        multicodec::SHAKE_128,
+    ]
+    .iter()
+    {
+        let mh = Multihash::new((*code).into(), data).unwrap();
+        assert!(mh.verify(data).unwrap());
+        assert!(!mh.verify(b"hello worlD").unwrap());
+    }
+
+    // truncated digest.
+    let digest = Multihash::new(multicodec::SHA2_256.into(), data)
+        .unwrap()
+        .to_digest()
+        .unwrap();
+    let mh = Multihash::from_digest(multicodec::SHA2_256.into(), &digest[..20]).unwrap();
+    assert!(mh.verify(data).unwrap());
+    assert!(!mh.verify(b"").unwrap());
+
+    // identity is never truncated.
+    let mh = Multihash::from_digest(multicodec::IDENTITY.into(), b"hel").unwrap();
+    assert!(!mh.verify(data).unwrap());
+    assert!(mh.verify(b"hel").unwrap());
+
+    // variable length digest.
+    let mut mh = Multihash::from_codec_with_length(multicodec::SHAKE_256.into(), 20).unwrap();
+    mh.write(data).unwrap().finish().unwrap();
+    assert!(mh.verify(data).unwrap());
+}
+
+#[test]
+fn test_keyed() {
+    use data_encoding::HEXLOWER;
+    use std::io::Write;
+
+    let keyed = |code: u128, key: &[u8], data: &[u8]| {
+        let mut mh = Multihash::from_codec_keyed(code.into(), key).unwrap();
+        mh.write_all(data).unwrap();
+        mh.flush().unwrap();
+        mh
+    };
+
+    // vector from the BLAKE2 reference keyed test vectors.
+    let key: Vec<u8> = (0..64).collect();
+    let mh = keyed(multicodec::BLAKE2B_512, &key, b"");
+    assert_eq!(
+        HEXLOWER.encode(&mh.to_digest().unwrap()),
+        concat!(
+            "10ebb67700b1868efb4417987acf4690ae9d972fb7a590c2f02871799aaa4786",
+            "b5e996e8f0f4eb981fc214b005f42d2ff4233499391653df7aefcbc13fc51568"
+        )
+    );
+    assert!(!mh.verify(b"").unwrap());
+
+    let mh = keyed(multicodec::BLAKE2S_128, &key[..32], b"hello world");
+    assert_eq!(
+        HEXLOWER.encode(&mh.to_digest().unwrap()),
+        "9dc39de6198d670172a4bf1a20c88f06"
+    );
+
+    // unkeyed digest length follows the code.
+    let mh = Multihash::new(multicodec::BLAKE2B_160.into(), b"hello world").unwrap();
+    assert_eq!(
+        HEXLOWER.encode(&mh.to_digest().unwrap()),
+        "70e8ece5e293e1bda064deef6b080edde357010f"
+    );
+
+    let mh = keyed(multicodec::BLAKE3, &key[..32], b"hello world");
+    assert!(mh != Multihash::new(multicodec::BLAKE3.into(), b"hello world").unwrap());
+    assert!(mh == keyed(multicodec::BLAKE3, &key[..32], b"hello world"));
+
+    // reset keeps the key.
+    let mut mh = keyed(multicodec::BLAKE2S_256, &key[..32], b"hello world");
+    let digest = mh.to_digest().unwrap();
+    mh.reset().unwrap();
+    mh.write_all(b"hello world").unwrap();
+    mh.flush().unwrap();
+    assert_eq!(mh.to_digest().unwrap(), digest);
+
+    assert!(Multihash::from_codec_keyed(multicodec::BLAKE2S_256.into(), &key).is_err());
+    assert!(Multihash::from_codec_keyed(multicodec::BLAKE3.into(), &key[..16]).is_err());
+    assert!(Multihash::from_codec_keyed(multicodec::SHA2_256.into(), &key[..16]).is_err());
+}
+
+#[test]
+fn test_trunc254_padded() {
+    use crate::cid::Cid;
+    use data_encoding::HEXLOWER;
+
+    let codec: Multicodec = multicodec::SHA2_256_TRUNC254_PADDED.into();
+    let mh = Multihash::new(codec.clone(), b"hello world").unwrap();
+    assert_eq!(
+        HEXLOWER.encode(&mh.to_digest().unwrap()),
+        "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcd29"
+    );
+    assert_eq!(digest_size(multicodec::SHA2_256_TRUNC254_PADDED), Some(32));
+    assert!(mh.verify(b"hello world").unwrap());
+
+    let (out, rem) = Multihash::decode(&mh.encode().unwrap()).unwrap();
+    assert!(rem.is_empty());
+    assert!(out == mh);
+
+    // top two bits of the last byte shall be zero.
+    let mut digest = mh.to_digest().unwrap();
+    digest[31] |= 0xc0;
+    assert!(Multihash::from_digest(codec.clone(), &digest).is_err());
+
+    // fil-commitment-unsealed CID over the digest above, in the form of a
+    // filecoin piece commitment, not an actual piece. Shall round-trip
+    // byte-identically.
+    let text = "baga6ea4seaqlstjhxgju2pqiuuxffv62pwv7vree57rxuu4a52iir55m4lx42ki";
+    let cid = Cid::from_text(text).unwrap();
+    assert_eq!(
+        cid.to_content_type().to_code(),
+        multicodec::FIL_COMMITMENT_UNSEALED
+    );
+    assert!(cid.to_multihash() == mh);
+    assert_eq!(cid.to_text(None).unwrap(), text);
+}
+
+#[test]
+fn test_multihash_ord_hash() {
+    use std::collections::HashSet;
+
+    let codes = [
+        multicodec::IDENTITY,
+        multicodec::SHA1,
+        multicodec::SHA2_256,
+        multicodec::SHA2_512,
+        multicodec::BLAKE3,
+        multicodec::BLAKE2B_256,
+    ];
+    let mut mhs = vec![];
+    for code in codes.iter() {
+        for data in [&b"a"[..], b"b", b"hello world"].iter() {
+            mhs.push(Multihash::new((*code).into(), data).unwrap());
+        }
+    }
+
+    // ordered same as the binary encoding.
+    let mut sorted = mhs.clone();
+    sorted.sort();
+    let items: Vec<Vec<u8>> = sorted.iter().map(|mh| mh.encode().unwrap()).collect();
+    let mut expected: Vec<Vec<u8>> = mhs.iter().map(|mh| mh.encode().unwrap()).collect();
+    expected.sort();
+    assert_eq!(items, expected);
+
+    // lazy multihash is the same key as the parsed one.
+    let set: HashSet<Multihash> = mhs.iter().cloned().collect();
+    assert_eq!(set.len(), mhs.len());
+    for mh in mhs.iter() {
+        let lazy = Multihash::decode_lazy(&mh.encode().unwrap()).unwrap();
+        assert_eq!(lazy.cmp(mh), cmp::Ordering::Equal);
+        assert!(set.contains(&lazy));
+    }
+
+    // malformed lazy multihash are distinct by their bytes.
+    let x = Multihash::decode_lazy(&[0x12, 0x20, 0x01]).unwrap();
+    let y = Multihash::decode_lazy(&[0x12, 0x20, 0x02]).unwrap();
+    assert!(x != y);
+    assert!(x < y);
+}
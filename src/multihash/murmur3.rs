@@ -0,0 +1,87 @@
+use std::io;
+
+use crate::{multicodec, Error, Result};
+
+// Digest is in big-endian byte order, as generated by go-multihash. For
+// murmur3-128 the first 8 bytes, h1, is the murmur3-x64-64 hash used by
+// UnixFS HAMT sharding.
+#[derive(Clone)]
+pub(crate) struct Murmur3 {
+    code: u128,
+    buf: Vec<u8>,
+    digest: Option<Vec<u8>>,
+}
+
+impl Eq for Murmur3 {}
+
+impl PartialEq for Murmur3 {
+    fn eq(&self, other: &Murmur3) -> bool {
+        self.digest == other.digest
+    }
+}
+
+impl Murmur3 {
+    pub(crate) fn from_code(code: u128) -> Result<Murmur3> {
+        Ok(Murmur3 {
+            code,
+            buf: Vec::default(),
+            digest: None,
+        })
+    }
+
+    pub(crate) fn decode(code: u128, buf: &[u8]) -> Result<Murmur3> {
+        Ok(Murmur3 {
+            code,
+            buf: Vec::default(),
+            digest: Some(buf.to_vec()),
+        })
+    }
+
+    pub(crate) fn write(&mut self, bytes: &[u8]) -> Result<()> {
+        match &self.digest {
+            None => self.buf.extend_from_slice(bytes),
+            Some(_) => err_at!(Invalid, msg: "finalized")?,
+        };
+        Ok(())
+    }
+
+    pub(crate) fn finish(&mut self) -> Result<()> {
+        use murmur3::{murmur3_32, murmur3_x64_128};
+
+        self.digest = match &self.digest {
+            None => {
+                let mut r = io::Cursor::new(&self.buf);
+                let digest = match self.code {
+                    multicodec::MURMUR3_128 => {
+                        let x = err_at!(HashFail, murmur3_x64_128(&mut r, 0))?;
+                        let (h1, h2) = (x as u64, (x >> 64) as u64);
+                        let mut digest = h1.to_be_bytes().to_vec();
+                        digest.extend_from_slice(&h2.to_be_bytes());
+                        digest
+                    }
+                    multicodec::MURMUR3_32 => {
+                        let x = err_at!(HashFail, murmur3_32(&mut r, 0))?;
+                        x.to_be_bytes().to_vec()
+                    }
+                    _ => err_at!(Fatal, msg: "unreachable")?,
+                };
+                Some(digest)
+            }
+            Some(_) => err_at!(Invalid, msg: "double finalize")?,
+        };
+        Ok(())
+    }
+
+    pub(crate) fn reset(&mut self) -> Result<()> {
+        self.buf.clear();
+        self.digest.take();
+        Ok(())
+    }
+
+    pub(crate) fn as_digest(&self) -> Result<&[u8]> {
+        match &self.digest {
+            Some(digest) => Ok(digest),
+            None => err_at!(Invalid, msg: "no digest"),
+        }
+    }
+}
@@ -2,12 +2,18 @@ use digest::Digest;
 
 use crate::{multicodec, Error, Result};
 
+// sha2-256-trunc254-padded, used by filecoin for piece and sector
+// commitments, clears the two most significant bits of the last byte so
+// that the digest fits within a BLS12-381 field element.
+const TRUNC254_MASK: u8 = 0b0011_1111;
+
 #[derive(Clone)]
 pub(crate) enum Sha2 {
     Algo32 {
         hasher: sha2::Sha256,
         digest: Option<Vec<u8>>,
         double: bool,
+        trunc254: bool,
     },
     Algo64 {
         hasher: sha2::Sha512,
@@ -38,11 +44,19 @@ impl Sha2 {
                 hasher: sha2::Sha256::new(),
                 digest,
                 double: false,
+                trunc254: false,
             },
             multicodec::DBL_SHA2_256 => Sha2::Algo32 {
                 hasher: sha2::Sha256::new(),
                 digest,
                 double: true,
+                trunc254: false,
+            },
+            multicodec::SHA2_256_TRUNC254_PADDED => Sha2::Algo32 {
+                hasher: sha2::Sha256::new(),
+                digest,
+                double: false,
+                trunc254: true,
             },
             multicodec::SHA2_512 => Sha2::Algo64 {
                 hasher: sha2::Sha512::new(),
@@ -60,11 +74,25 @@ impl Sha2 {
                 hasher: sha2::Sha256::new(),
                 digest: Some(digest.to_vec()),
                 double: false,
+                trunc254: false,
             },
             multicodec::DBL_SHA2_256 => Sha2::Algo32 {
                 hasher: sha2::Sha256::new(),
                 digest: Some(digest.to_vec()),
                 double: true,
+                trunc254: false,
+            },
+            multicodec::SHA2_256_TRUNC254_PADDED => match digest.get(31) {
+                Some(b) if (b & !TRUNC254_MASK) != 0 => err_at!(
+                    BadInput,
+                    msg: "sha2-256-trunc254-padded top bits set {:x}", b
+                )?,
+                _ => Sha2::Algo32 {
+                    hasher: sha2::Sha256::new(),
+                    digest: Some(digest.to_vec()),
+                    double: false,
+                    trunc254: true,
+                },
             },
             multicodec::SHA2_512 => Sha2::Algo64 {
                 hasher: sha2::Sha512::new(),
@@ -99,8 +127,13 @@ impl Sha2 {
                 hasher,
                 digest: digest @ None,
                 double: false,
+                trunc254,
             } => {
-                *digest = Some(hasher.finalize_reset().as_slice().to_vec());
+                let mut hash = hasher.finalize_reset().as_slice().to_vec();
+                if *trunc254 {
+                    hash[31] &= TRUNC254_MASK;
+                }
+                *digest = Some(hash);
             }
             Sha2::Algo64 {
                 hasher,
@@ -113,6 +146,7 @@ impl Sha2 {
                 hasher,
                 digest: digest @ None,
                 double: true,
+                ..
             } => {
                 *digest = {
                     let hash = hasher.finalize_reset().as_slice().to_vec();
@@ -24,10 +24,12 @@ pub(crate) enum Sha3 {
     },
     Shake128 {
         hasher: sha3::Shake128,
+        size: usize,
         digest: Option<Vec<u8>>,
     },
     Shake256 {
         hasher: sha3::Shake256,
+        size: usize,
         digest: Option<Vec<u8>>,
     },
     Keccak224 {
@@ -70,6 +72,13 @@ impl PartialEq for Sha3 {
     }
 }
 
+/// Default output length, in bytes, for shake-128, matching its
+/// security strength.
+pub const SHAKE_128_SIZE: usize = 32;
+/// Default output length, in bytes, for shake-256, matching its
+/// security strength.
+pub const SHAKE_256_SIZE: usize = 64;
+
 impl Sha3 {
     pub(crate) fn from_code(code: u128) -> Result<Sha3> {
         let digest = None;
@@ -90,14 +99,8 @@ impl Sha3 {
                 let hasher = sha3::Sha3_224::new();
                 Sha3::Sha3_224 { hasher, digest }
             }
-            multicodec::SHAKE_128 => {
-                let hasher = sha3::Shake128::default();
-                Sha3::Shake128 { hasher, digest }
-            }
-            multicodec::SHAKE_256 => {
-                let hasher = sha3::Shake256::default();
-                Sha3::Shake256 { hasher, digest }
-            }
+            multicodec::SHAKE_128 => Self::from_code_with_length(code, SHAKE_128_SIZE)?,
+            multicodec::SHAKE_256 => Self::from_code_with_length(code, SHAKE_256_SIZE)?,
             multicodec::KECCAK_224 => {
                 let hasher = sha3::Keccak224::new();
                 Sha3::Keccak224 { hasher, digest }
@@ -119,6 +122,31 @@ impl Sha3 {
         Ok(val)
     }
 
+    // shake algorithms generate `size` bytes of output.
+    pub(crate) fn from_code_with_length(code: u128, size: usize) -> Result<Sha3> {
+        let digest = None;
+        let val = match code {
+            multicodec::SHAKE_128 => {
+                let hasher = sha3::Shake128::default();
+                Sha3::Shake128 {
+                    hasher,
+                    size,
+                    digest,
+                }
+            }
+            multicodec::SHAKE_256 => {
+                let hasher = sha3::Shake256::default();
+                Sha3::Shake256 {
+                    hasher,
+                    size,
+                    digest,
+                }
+            }
+            _ => err_at!(Fatal, msg: "unreachable")?,
+        };
+        Ok(val)
+    }
+
     pub(crate) fn decode(code: u128, digest: &[u8]) -> Result<Sha3> {
         let val = match code {
             multicodec::SHA3_512 => Sha3::Sha3_512 {
@@ -139,10 +167,12 @@ impl Sha3 {
             },
             multicodec::SHAKE_128 => Sha3::Shake128 {
                 hasher: sha3::Shake128::default(),
+                size: digest.len(),
                 digest: Some(digest.to_vec()),
             },
             multicodec::SHAKE_256 => Sha3::Shake256 {
                 hasher: sha3::Shake256::default(),
+                size: digest.len(),
                 digest: Some(digest.to_vec()),
             },
             multicodec::KECCAK_224 => Sha3::Keccak224 {
@@ -195,12 +225,14 @@ impl Sha3 {
             Sha3::Shake128 {
                 hasher,
                 digest: None,
+                ..
             } => {
                 <sha3::Shake128 as digest::Update>::update(hasher, bytes);
             }
             Sha3::Shake256 {
                 hasher,
                 digest: None,
+                ..
             } => {
                 <sha3::Shake256 as digest::Update>::update(hasher, bytes);
             }
@@ -263,20 +295,22 @@ impl Sha3 {
             }
             Sha3::Shake128 {
                 hasher,
+                size,
                 digest: digest @ None,
             } => {
-                let mut buf = Vec::default();
+                let mut buf = vec![0; *size];
                 let mut xof = hasher.finalize_xof_reset();
-                err_at!(IOError, xof.read_to_end(&mut buf))?;
+                err_at!(IOError, xof.read_exact(&mut buf))?;
                 *digest = Some(buf);
             }
             Sha3::Shake256 {
                 hasher,
+                size,
                 digest: digest @ None,
             } => {
-                let mut buf = Vec::default();
+                let mut buf = vec![0; *size];
                 let mut xof = hasher.finalize_xof_reset();
-                err_at!(IOError, xof.read_to_end(&mut buf))?;
+                err_at!(IOError, xof.read_exact(&mut buf))?;
                 *digest = Some(buf)
             }
             Sha3::Keccak224 {
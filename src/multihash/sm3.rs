@@ -0,0 +1,146 @@
+//! SM3 hash function, as specified by GB/T 32905-2016. There is no crate
+//! for SM3 that works with digest 0.9, hence implemented here.
+
+use crate::{Error, Result};
+
+const IV: [u32; 8] = [
+    0x7380_166f,
+    0x4914_b2b9,
+    0x1724_42d7,
+    0xda8a_0600,
+    0xa96f_30bc,
+    0x1631_38aa,
+    0xe38d_ee4d,
+    0xb0fb_0e4e,
+];
+
+#[derive(Clone)]
+pub(crate) struct Sm3 {
+    buf: Vec<u8>,
+    digest: Option<Vec<u8>>,
+}
+
+impl Eq for Sm3 {}
+
+impl PartialEq for Sm3 {
+    fn eq(&self, other: &Sm3) -> bool {
+        self.digest == other.digest
+    }
+}
+
+impl Sm3 {
+    pub(crate) fn from_code(_code: u128) -> Result<Sm3> {
+        Ok(Sm3 {
+            buf: Vec::default(),
+            digest: None,
+        })
+    }
+
+    pub(crate) fn decode(_code: u128, buf: &[u8]) -> Result<Sm3> {
+        Ok(Sm3 {
+            buf: Vec::default(),
+            digest: Some(buf.to_vec()),
+        })
+    }
+
+    pub(crate) fn write(&mut self, bytes: &[u8]) -> Result<()> {
+        match &self.digest {
+            None => self.buf.extend_from_slice(bytes),
+            Some(_) => err_at!(Invalid, msg: "finalized")?,
+        };
+        Ok(())
+    }
+
+    pub(crate) fn finish(&mut self) -> Result<()> {
+        self.digest = match &self.digest {
+            None => Some(sm3_256(&self.buf).to_vec()),
+            Some(_) => err_at!(Invalid, msg: "double finalize")?,
+        };
+        Ok(())
+    }
+
+    pub(crate) fn reset(&mut self) -> Result<()> {
+        self.buf.clear();
+        self.digest.take();
+        Ok(())
+    }
+
+    pub(crate) fn as_digest(&self) -> Result<&[u8]> {
+        match &self.digest {
+            Some(digest) => Ok(digest),
+            None => err_at!(Invalid, msg: "no digest"),
+        }
+    }
+}
+
+fn sm3_256(data: &[u8]) -> [u8; 32] {
+    // pad with 0x80, zeros and the 64-bit big-endian length in bits.
+    let mut msg = data.to_vec();
+    msg.push(0x80);
+    while msg.len() % 64 != 56 {
+        msg.push(0);
+    }
+    msg.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    let mut v = IV;
+    for block in msg.chunks(64) {
+        compress(&mut v, block);
+    }
+
+    let mut digest = [0_u8; 32];
+    for (i, word) in v.iter().enumerate() {
+        digest[i * 4..(i + 1) * 4].copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+fn compress(v: &mut [u32; 8], block: &[u8]) {
+    let p0 = |x: u32| x ^ x.rotate_left(9) ^ x.rotate_left(17);
+    let p1 = |x: u32| x ^ x.rotate_left(15) ^ x.rotate_left(23);
+
+    let mut w = [0_u32; 68];
+    for (i, word) in block.chunks(4).enumerate() {
+        w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+    }
+    for j in 16..68 {
+        w[j] = p1(w[j - 16] ^ w[j - 9] ^ w[j - 3].rotate_left(15))
+            ^ w[j - 13].rotate_left(7)
+            ^ w[j - 6];
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *v;
+    for j in 0..64 {
+        let (t, ff, gg) = match j {
+            0..=15 => (0x79cc_4519_u32, a ^ b ^ c, e ^ f ^ g),
+            _ => (
+                0x7a87_9d8a_u32,
+                (a & b) | (a & c) | (b & c),
+                (e & f) | (!e & g),
+            ),
+        };
+        let ss1 = a
+            .rotate_left(12)
+            .wrapping_add(e)
+            .wrapping_add(t.rotate_left(j % 32))
+            .rotate_left(7);
+        let ss2 = ss1 ^ a.rotate_left(12);
+        let w1 = w[j as usize] ^ w[j as usize + 4];
+        let tt1 = ff.wrapping_add(d).wrapping_add(ss2).wrapping_add(w1);
+        let tt2 = gg
+            .wrapping_add(h)
+            .wrapping_add(ss1)
+            .wrapping_add(w[j as usize]);
+        d = c;
+        c = b.rotate_left(9);
+        b = a;
+        a = tt1;
+        h = g;
+        g = f.rotate_left(19);
+        f = e;
+        e = p0(tt2);
+    }
+
+    for (x, y) in v.iter_mut().zip([a, b, c, d, e, f, g, h].iter()) {
+        *x ^= *y;
+    }
+}
@@ -1,6 +1,27 @@
+//! Module implement multistream-select, refer [multistream spec].
+//!
+//! When [SwarmHooks] are set on a [Multistream], stream opened event is
+//! dispatched before negotiation and protocol negotiated event after it.
+//! A hook returning error fails the negotiation, and the stream shall
+//! not be upgraded, caller is expected to close it.
+//!
+//! [multistream spec]: https://github.com/multiformats/multistream-select
+
+use bytes::{Bytes, BytesMut};
+
 use std::{io, marker::PhantomData};
 
-use crate::{util, Error, Result};
+use crate::{
+    ipfsd::{ConnInfo, StreamInfo, SwarmHooks},
+    util::frame,
+    Error, Result,
+};
+
+/// Maximum size of a multistream frame, same as go-multistream.
+pub const MAX_FRAME_SIZE: usize = 64 * 1024;
+
+/// Protocol id for multistream-select version 1.
+pub const PROTOCOL_ID: &str = "/multistream/1.0.0";
 
 /// Implemented by types that can negotiate protocol with remote and
 /// upgrade a connection C, to a protocol instance P.
@@ -25,9 +46,17 @@ where
         ver: Version1<C, P, T>,
         protocol: Option<T>,
         handlers: Vec<T>,
+        hooks: Option<StreamHooks>,
     },
 }
 
+// hooks dispatched by negotiate, along with the stream they apply to.
+struct StreamHooks {
+    hooks: SwarmHooks,
+    conn: ConnInfo,
+    stream: StreamInfo,
+}
+
 impl<C, P, T> Default for Multistream<C, P, T>
 where
     C: io::Read + io::Write,
@@ -40,6 +69,7 @@ where
             ver: Version1::default(),
             protocol: None,
             handlers: vec![],
+            hooks: None,
         }
     }
 }
@@ -54,7 +84,30 @@ where
             ver: Version1::default(),
             protocol,
             handlers: vec![],
+            hooks: None,
+        }
+    }
+
+    /// Dispatch `hooks` for stream `stream` on connection `conn`, while
+    /// negotiating.
+    pub fn set_hooks(
+        &mut self,
+        hooks: SwarmHooks,
+        conn: ConnInfo,
+        stream: StreamInfo,
+    ) -> &mut Self {
+        use Multistream::*;
+
+        match self {
+            V1 { hooks: val, .. } => {
+                *val = Some(StreamHooks {
+                    hooks,
+                    conn,
+                    stream,
+                })
+            }
         }
+        self
     }
 
     pub fn add_handler(&mut self, handler: T) -> &mut Self {
@@ -66,6 +119,9 @@ where
         self
     }
 
+    /// Negotiate protocol on `conn`, as initiator if this was created
+    /// with a protocol, else as listener matching `handlers`. Fail if
+    /// negotiation fails or if a hook rejects the stream.
     pub fn negotiate(&mut self, conn: &mut C) -> Result<()> {
         use Multistream::*;
 
@@ -74,12 +130,39 @@ where
                 ver,
                 protocol,
                 handlers,
-            } => ver.handshake(protocol.as_ref(), handlers, conn),
+                hooks,
+            } => {
+                if let Some(h) = hooks.as_ref() {
+                    h.hooks.stream_opened(&h.conn, &h.stream)?;
+                }
+                ver.handshake(protocol.as_ref(), handlers, conn)?;
+                let res = match (hooks.as_ref(), &*ver) {
+                    (Some(h), Version1::Fin(proto)) => {
+                        let name = proto.to_proto_path();
+                        h.hooks.protocol_negotiated(&h.conn, &h.stream, &name)
+                    }
+                    (_, _) => Ok(()),
+                };
+                // rejected stream can't be upgraded.
+                if res.is_err() {
+                    *ver = Version1::default();
+                }
+                res
+            }
         }
     }
 
-    pub fn upgrade(self, _conn: C) -> Result<P> {
-        todo!()
+    /// Upgrade `conn` to the negotiated protocol, fail if not negotiated.
+    pub fn upgrade(self, conn: C) -> Result<P> {
+        use Multistream::*;
+
+        match self {
+            V1 {
+                ver: Version1::Fin(proto),
+                ..
+            } => Ok(proto.upgrade(conn)),
+            V1 { .. } => err_at!(Invalid, msg: "multistream protocol not negotiated"),
+        }
     }
 }
 
@@ -88,7 +171,7 @@ where
     C: io::Read + io::Write,
     T: Clone + Protocol<C, P>,
 {
-    fn ls(protocol: Option<&T>, handlers: &[T]) -> Result<Vec<u8>> {
+    fn ls(protocol: Option<&T>, handlers: &[T]) -> Result<Bytes> {
         let mut ps: Vec<String> = {
             let iter = handlers.iter().map(|h| h.to_proto_path());
             iter.collect()
@@ -105,11 +188,11 @@ where
 
         let mut data = vec![];
         Self::encodes(&mut data, ps.iter().map(|x| x.as_bytes()).collect())?;
-        Ok(data)
+        Ok(data.into())
     }
 
     fn encode(buf: &mut Vec<u8>, bytes: &[u8]) -> Result<usize> {
-        let n = util::write_lpm(buf, bytes)?;
+        let n = frame::write_varint_frame(buf, bytes, MAX_FRAME_SIZE)?;
         buf.push('\n' as u8);
         Ok(n + 1)
     }
@@ -119,7 +202,7 @@ where
         for bytes in bytess.iter() {
             Self::encode(&mut data, bytes)?;
         }
-        let n = util::write_lpm(buf, &data)?;
+        let n = frame::write_varint_frame(buf, &data, MAX_FRAME_SIZE)?;
         buf.push('\n' as u8);
         Ok(n + 1)
     }
@@ -149,19 +232,68 @@ where
     C: io::Read + io::Write,
     T: Clone + Protocol<C, P>,
 {
-    fn handshake(&mut self, _protocol: Option<&T>, _handlers: &[T], _conn: &mut C) -> Result<()> {
-        todo!()
+    fn handshake(&mut self, protocol: Option<&T>, handlers: &[T], conn: &mut C) -> Result<()> {
+        let mut buf = BytesMut::new();
+
+        write_msg(conn, PROTOCOL_ID)?;
+        if let Some(proto) = protocol {
+            write_msg(conn, &proto.to_proto_path())?;
+        }
+        err_at!(IOError, conn.flush())?;
+        match read_msg(conn, &mut buf)? {
+            id if id == PROTOCOL_ID => (),
+            id => err_at!(BadInput, msg: "multistream header {:?}", id)?,
+        }
+
+        let proto = match protocol {
+            Some(proto) => match read_msg(conn, &mut buf)? {
+                name if name == proto.to_proto_path() => proto.clone(),
+                name => {
+                    err_at!(BadInput, msg: "multistream {:?} got {:?}", proto.to_proto_path(), name)?
+                }
+            },
+            None => loop {
+                let name = read_msg(conn, &mut buf)?;
+                let handler = handlers.iter().find(|h| h.try_match(&name));
+                match handler {
+                    Some(_) => write_msg(conn, &name)?,
+                    None => write_msg(conn, "na")?,
+                };
+                err_at!(IOError, conn.flush())?;
+                if let Some(handler) = handler {
+                    break handler.clone();
+                }
+            },
+        };
+
+        *self = Version1::Fin(proto);
+        Ok(())
+    }
+}
+
+fn write_msg<W: io::Write>(w: &mut W, msg: &str) -> Result<usize> {
+    let data = format!("{}\n", msg);
+    frame::write_varint_frame(w, data.as_bytes(), MAX_FRAME_SIZE)
+}
+
+// read a multistream message made of a single line.
+fn read_msg<R: io::Read>(r: &mut R, buf: &mut BytesMut) -> Result<String> {
+    let mut lines = read(r, buf)?;
+    match lines.len() {
+        1 => Ok(lines.remove(0)),
+        n => err_at!(DecodeError, msg: "multistream message with {} lines", n),
     }
 }
 
-fn read<T: io::Read>(r: &mut T) -> Result<Vec<String>> {
+// read a multistream message, `buf`'s capacity is reused across calls.
+fn read<T: io::Read>(r: &mut T, buf: &mut BytesMut) -> Result<Vec<String>> {
     use std::str::from_utf8;
 
     let nl = '\n' as u8;
-    let mut data = util::read_lpm(r)?;
-    let lines = match data.pop() {
+    let data = frame::read_varint_frame_bytes(r, MAX_FRAME_SIZE, buf)?;
+    let lines = match data.split_last() {
         None => err_at!(IOError, msg: "empty multistream read")?,
-        Some(b) if b == nl => {
+        Some((b, data)) if *b == nl => {
             let mut lines = vec![];
             for line in data.rsplit(|b| *b == nl) {
                 lines.push(err_at!(DecodeError, from_utf8(line))?.to_string())
@@ -173,3 +305,7 @@ fn read<T: io::Read>(r: &mut T) -> Result<Vec<String>> {
 
     Ok(lines)
 }
+
+#[cfg(test)]
+#[path = "multistream_test.rs"]
+mod multistream_test;
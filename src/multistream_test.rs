@@ -0,0 +1,150 @@
+use std::{
+    os::unix::net::UnixStream,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use super::*;
+use crate::{ipfsd::Direction, multiaddr::Multiaddr, peer_id::PeerId};
+
+// protocol that upgrades a stream to its own protocol path.
+#[derive(Clone)]
+struct Proto(&'static str);
+
+impl Protocol<UnixStream, String> for Proto {
+    fn to_proto_path(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn try_match(&self, proto: &str) -> bool {
+        self.0 == proto
+    }
+
+    fn upgrade(self, _conn: UnixStream) -> String {
+        self.0.to_string()
+    }
+}
+
+type Select = Multistream<UnixStream, String, Proto>;
+
+fn make_conn(direction: Direction) -> ConnInfo {
+    ConnInfo {
+        id: 1,
+        peer: PeerId::generate().unwrap(),
+        local: Multiaddr::from_text("/ip4/127.0.0.1/tcp/4001").unwrap(),
+        remote: Multiaddr::from_text("/ip4/10.0.0.1/tcp/4001").unwrap(),
+        direction,
+    }
+}
+
+fn make_stream(direction: Direction) -> StreamInfo {
+    StreamInfo { id: 7, direction }
+}
+
+// negotiate `proto` from a dialer thread, return the dialer's outcome
+// along with the listener's.
+fn negotiate(proto: &'static str, mut listener: Select) -> (Result<String>, Result<String>) {
+    let (mut a, mut b) = UnixStream::pair().unwrap();
+    let handle = thread::spawn(move || {
+        let mut dialer = Select::new_v1(Some(Proto(proto)));
+        dialer.negotiate(&mut a)?;
+        dialer.upgrade(a)
+    });
+    let res = listener.negotiate(&mut b).and_then(|_| listener.upgrade(b));
+    (handle.join().unwrap(), res)
+}
+
+#[test]
+fn test_multistream_negotiate() {
+    let mut listener = Select::new_v1(None);
+    listener
+        .add_handler(Proto("/ipfs/id/1.0.0"))
+        .add_handler(Proto("/ipfs/ping/1.0.0"));
+    let (dialer, res) = negotiate("/ipfs/ping/1.0.0", listener);
+    assert_eq!(dialer.unwrap(), "/ipfs/ping/1.0.0");
+    assert_eq!(res.unwrap(), "/ipfs/ping/1.0.0");
+
+    // unsupported protocol, listener keeps waiting for another proposal.
+    let mut listener = Select::new_v1(None);
+    listener.add_handler(Proto("/ipfs/id/1.0.0"));
+    let (dialer, res) = negotiate("/ipfs/ping/1.0.0", listener);
+    assert!(dialer.is_err());
+    assert!(res.is_err());
+
+    // not negotiated, or remote closed before negotiation.
+    let (mut a, b) = UnixStream::pair().unwrap();
+    assert!(Select::new_v1(None)
+        .upgrade(a.try_clone().unwrap())
+        .is_err());
+    std::mem::drop(b);
+    assert!(Select::new_v1(None).negotiate(&mut a).is_err());
+}
+
+#[test]
+fn test_multistream_hooks() {
+    let events = Arc::new(Mutex::new(vec![]));
+    let mut hooks = SwarmHooks::new();
+    {
+        let events = Arc::clone(&events);
+        hooks.on_stream_opened(move |_, stream| {
+            events.lock().unwrap().push(format!("opened:{}", stream.id));
+            Ok(())
+        });
+    }
+    {
+        let events = Arc::clone(&events);
+        hooks.on_protocol_negotiated(move |conn, _, protocol| {
+            events
+                .lock()
+                .unwrap()
+                .push(format!("negotiated:{}", protocol));
+            match protocol {
+                "/ipfs/id/1.0.0" => Ok(()),
+                _ => err_at!(Invalid, msg: "{} vetoed", conn.id),
+            }
+        });
+    }
+    let conn = make_conn(Direction::Inbound);
+    let stream = make_stream(Direction::Inbound);
+
+    let mut listener = Select::new_v1(None);
+    listener
+        .add_handler(Proto("/ipfs/id/1.0.0"))
+        .add_handler(Proto("/ipfs/ping/1.0.0"))
+        .set_hooks(hooks.clone(), conn.clone(), stream.clone());
+    let (_, res) = negotiate("/ipfs/id/1.0.0", listener);
+    assert_eq!(res.unwrap(), "/ipfs/id/1.0.0");
+
+    // vetoed after negotiation, stream is not upgraded.
+    let mut listener = Select::new_v1(None);
+    listener
+        .add_handler(Proto("/ipfs/ping/1.0.0"))
+        .set_hooks(hooks, conn.clone(), stream.clone());
+    let (_, res) = negotiate("/ipfs/ping/1.0.0", listener);
+    let msg = res.unwrap_err().to_string();
+    assert!(msg.contains("vetoed"), "{}", msg);
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![
+            "opened:7".to_string(),
+            "negotiated:/ipfs/id/1.0.0".to_string(),
+            "opened:7".to_string(),
+            "negotiated:/ipfs/ping/1.0.0".to_string(),
+        ]
+    );
+
+    // vetoed when opened, nothing is written to the stream.
+    let mut hooks = SwarmHooks::new();
+    hooks.on_stream_opened(|_, _| err_at!(Invalid, msg: "no streams"));
+    let (mut a, mut b) = UnixStream::pair().unwrap();
+    let mut select = Select::new_v1(Some(Proto("/ipfs/id/1.0.0")));
+    select.set_hooks(
+        hooks,
+        make_conn(Direction::Outbound),
+        make_stream(Direction::Outbound),
+    );
+    assert!(select.negotiate(&mut a).is_err());
+    std::mem::drop(a);
+    let mut buf = vec![];
+    assert_eq!(io::Read::read_to_end(&mut b, &mut buf).unwrap(), 0);
+}
@@ -9,7 +9,7 @@ use crate::{
     Error, Result,
 };
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum NetAddr {
     Tcp(net::SocketAddr),
     Udp(net::SocketAddr),
@@ -1,11 +1,121 @@
 // TODO: raw-socket, ip-network
 
-use std::{net, os::unix};
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, Read, Write},
+    net,
+    os::unix::{self, fs::FileTypeExt},
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use crate::{net_addr::NetAddr, Error, Result};
+use socket2::{Domain, Socket, Type};
+
+use crate::{
+    multiaddr::Multiaddr,
+    net_addr::NetAddr,
+    peer_id::PeerId,
+    pnet::{PnetConn, Psk},
+    util::{Clock, SystemClock},
+    Error, Result,
+};
+
+/// Default timeout for establishing a TCP connection.
+pub const DIAL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Base, per-attempt coefficient and maximum for dial backoff, same as
+/// go-libp2p. Backoff after `n` failed attempts is `base + coef * n^2`.
+pub const BACKOFF_BASE: Duration = Duration::from_secs(5);
+pub const BACKOFF_COEF: Duration = Duration::from_secs(1);
+pub const BACKOFF_MAX: Duration = Duration::from_secs(300);
+
+/// Default port for swarm listeners, shared by TCP and QUIC.
+pub const SWARM_PORT: u16 = 4001;
+
+// same as std::net::TcpListener.
+const LISTEN_BACKLOG: i32 = 128;
+
+/// Return `Addresses.swarm` entries for listening on `port` on all IPv4
+/// and IPv6 interfaces, with TCP and QUIC sharing the same port number.
+/// Operators then need a single firewall rule, for tcp and udp, per port.
+pub fn swarm_addrs(port: u16) -> Vec<String> {
+    vec![
+        format!("/ip4/0.0.0.0/tcp/{}", port),
+        format!("/ip6/::/tcp/{}", port),
+        format!("/ip4/0.0.0.0/udp/{}/quic", port),
+        format!("/ip6/::/udp/{}/quic", port),
+    ]
+}
+
+/// Check that no two addresses in `addrs` would bind the same socket.
+/// TCP and UDP on the same port don't collide, while the same transport
+/// and port on the same or wildcard address of an IP family does. Port 0
+/// is picked by the OS and never collides.
+///
+/// IPv4 and IPv6 don't collide, since [Listener] binds IPv6 sockets with
+/// `IPV6_V6ONLY`. Without it, Linux binds `/ip6/::` on IPv4 as well.
+pub fn check_collisions(addrs: &[Multiaddr]) -> Result<()> {
+    let mut keys: Vec<(BindKey, &Multiaddr)> = vec![];
+    for ma in addrs.iter() {
+        let key = match BindKey::from_multiaddr(ma)? {
+            Some(key) => key,
+            None => continue,
+        };
+        for (other, oma) in keys.iter() {
+            if key.collides(other) {
+                err_at!(
+                    BadAddr,
+                    msg: "{} collides with {}", ma.to_text()?, oma.to_text()?
+                )?
+            }
+        }
+        keys.push((key, ma));
+    }
+    Ok(())
+}
+
+// transport and socket address bound by a listening multiaddr.
+enum BindKey {
+    Tcp(net::SocketAddr),
+    Udp(net::SocketAddr),
+    Unix(String),
+}
+
+impl BindKey {
+    fn from_multiaddr(ma: &Multiaddr) -> Result<Option<BindKey>> {
+        use Multiaddr::{Ip4, Ip6, Tcp, Udp, Unix};
+
+        let key = match ma.clone().parse()? {
+            Ip4(ip, box Tcp(tcp, _)) => BindKey::Tcp((ip.to_addr(), tcp.to_port()).into()),
+            Ip6(ip, box Tcp(tcp, _)) => BindKey::Tcp((ip.to_addr(), tcp.to_port()).into()),
+            Ip4(ip, box Udp(udp, _)) => BindKey::Udp((ip.to_addr(), udp.to_port()).into()),
+            Ip6(ip, box Udp(udp, _)) => BindKey::Udp((ip.to_addr(), udp.to_port()).into()),
+            Unix(val, _) => BindKey::Unix(val.to_path()),
+            _ => return Ok(None),
+        };
+
+        Ok(Some(key))
+    }
+
+    fn collides(&self, other: &BindKey) -> bool {
+        match (self, other) {
+            (BindKey::Tcp(a), BindKey::Tcp(b)) | (BindKey::Udp(a), BindKey::Udp(b)) => {
+                let ip = a.ip() == b.ip() || a.ip().is_unspecified() || b.ip().is_unspecified();
+                a.port() != 0 && a.port() == b.port() && a.is_ipv4() == b.is_ipv4() && ip
+            }
+            (BindKey::Unix(a), BindKey::Unix(b)) => a == b,
+            (_, _) => false,
+        }
+    }
+}
 
 pub enum Listener {
     Tcp(net::TcpListener),
+    /// Socket bound for QUIC, connections are accepted by the QUIC
+    /// transport, not by [Listener::accept].
+    Udp(net::UdpSocket),
     Unix(unix::net::UnixListener),
 }
 
@@ -13,21 +123,71 @@ impl Listener {
     pub fn bind(addr: NetAddr) -> Result<Listener> {
         let val = match addr {
             NetAddr::Tcp(addr) => {
-                let listn = err_at!(IOError, net::TcpListener::bind(addr))?;
-                Listener::Tcp(listn)
+                let sock = new_socket(&addr, Type::stream())?;
+                err_at!(IOError, sock.set_reuse_address(true))?;
+                err_at!(IOError, sock.bind(&addr.into()), "{}", addr)?;
+                err_at!(IOError, sock.listen(LISTEN_BACKLOG), "{}", addr)?;
+                Listener::Tcp(sock.into_tcp_listener())
+            }
+            NetAddr::Udp(addr) => {
+                let sock = new_socket(&addr, Type::dgram())?;
+                err_at!(IOError, sock.bind(&addr.into()), "{}", addr)?;
+                Listener::Udp(sock.into_udp_socket())
             }
             NetAddr::Unix(addr) if addr.as_pathname().is_some() => {
-                let path = addr.as_pathname().unwrap();
-                let listn = err_at!(IOError, unix::net::UnixListener::bind(path))?;
-                Listener::Unix(listn)
+                Listener::Unix(bind_unix(addr.as_pathname().unwrap())?)
             }
             NetAddr::Unix(addr) => err_at!(Invalid, msg: "invalid addr {:?}", addr)?,
-            NetAddr::Udp(_) => err_at!(Invalid, msg: "no listener for udp {:?}", addr)?,
         };
 
         Ok(val)
     }
 
+    /// Bind a listener on multiaddr `ma`. Unlike [Listener::bind], `/unix`
+    /// addresses are bound directly on the socket-path, without resolving
+    /// them to a [NetAddr].
+    ///
+    /// A socket file left behind by a previous process, that no longer
+    /// accepts connections, is removed before binding.
+    pub fn bind_multiaddr(ma: Multiaddr) -> Result<Listener> {
+        match ma.parse()? {
+            Multiaddr::Unix(val, _) => Ok(Listener::Unix(bind_unix(val.to_path())?)),
+            ma => Listener::bind(NetAddr::from_multiaddr(ma)?),
+        }
+    }
+
+    /// Bind a listener for each multiaddr in `addrs`, typically the list
+    /// of `Addresses.api` or `Addresses.gateway` from config, and return
+    /// them in the same order. Colliding addresses are rejected before
+    /// binding, refer [check_collisions]. If any of them fail to bind,
+    /// listeners bound so far are closed, and their socket files removed,
+    /// before returning the error.
+    pub fn bind_all(addrs: Vec<Multiaddr>) -> Result<Vec<Listener>> {
+        check_collisions(&addrs)?;
+
+        let mut listeners = Vec::with_capacity(addrs.len());
+        for ma in addrs.into_iter() {
+            match Listener::bind_multiaddr(ma) {
+                Ok(listn) => listeners.push(listn),
+                Err(err) => {
+                    listeners.into_iter().for_each(|listn| listn.close());
+                    return Err(err);
+                }
+            }
+        }
+        Ok(listeners)
+    }
+
+    /// Close the listener, for `/unix` listeners remove the socket file.
+    pub fn close(self) {
+        if let Listener::Unix(listn) = &self {
+            let path = listn.local_addr().ok();
+            if let Some(path) = path.as_ref().and_then(|a| a.as_pathname()) {
+                fs::remove_file(path).ok();
+            }
+        }
+    }
+
     pub fn accept(&self) -> Result<Conn> {
         let conn = match self {
             Listener::Tcp(listn) => {
@@ -38,6 +198,10 @@ impl Listener {
                     conn,
                 }
             }
+            Listener::Udp(sock) => {
+                let addr = err_at!(IOError, sock.local_addr())?;
+                err_at!(Invalid, msg: "no accept on udp {}", addr)?
+            }
             Listener::Unix(listn) => {
                 let (conn, raddr) = err_at!(IOError, listn.accept())?;
                 Conn::Unix {
@@ -51,16 +215,29 @@ impl Listener {
         Ok(conn)
     }
 
+    /// Same as [Listener::accept], but when `psk` is supplied, wrap the
+    /// accepted connection for private network, refer [Conn::into_pnet].
+    pub fn accept_with(&self, psk: Option<&Psk>) -> Result<Conn> {
+        let conn = self.accept()?;
+        match psk {
+            Some(psk) => Ok(conn.into_pnet(psk)),
+            None => Ok(conn),
+        }
+    }
+
     pub fn to_local_addr(&self) -> Result<NetAddr> {
         let addr = match self {
             Listener::Tcp(listn) => {
                 let addr = err_at!(IOError, listn.local_addr())?;
                 NetAddr::Tcp(addr)
             }
-            Listener::Unix(_listn) => {
-                todo!()
-                //let addr = err_at!(IOError, listn.local_addr())?;
-                //NetAddr::Unix(addr)
+            Listener::Udp(sock) => {
+                let addr = err_at!(IOError, sock.local_addr())?;
+                NetAddr::Udp(addr)
+            }
+            Listener::Unix(listn) => {
+                let addr = err_at!(IOError, listn.local_addr())?;
+                NetAddr::Unix(addr)
             }
         };
 
@@ -68,6 +245,39 @@ impl Listener {
     }
 }
 
+// IPv6 sockets are IPV6_V6ONLY, so that `/ip4/0.0.0.0` and `/ip6/::` can
+// listen on the same port, refer check_collisions.
+fn new_socket(addr: &net::SocketAddr, ty: Type) -> Result<Socket> {
+    let domain = match addr {
+        net::SocketAddr::V4(_) => Domain::ipv4(),
+        net::SocketAddr::V6(_) => Domain::ipv6(),
+    };
+    let sock = err_at!(IOError, Socket::new(domain, ty, None))?;
+    if addr.is_ipv6() {
+        err_at!(IOError, sock.set_only_v6(true))?;
+    }
+    Ok(sock)
+}
+
+// bind unix socket on `path`, removing a stale socket file if any.
+fn bind_unix<P: AsRef<Path>>(path: P) -> Result<unix::net::UnixListener> {
+    let path = path.as_ref();
+    let is_socket = match fs::symlink_metadata(path) {
+        Ok(md) => md.file_type().is_socket(),
+        Err(_) => false,
+    };
+    if is_socket {
+        match unix::net::UnixStream::connect(path) {
+            Ok(_) => err_at!(IOError, msg: "{:?} in use", path)?,
+            Err(err) if err.kind() == io::ErrorKind::ConnectionRefused => {
+                err_at!(IOError, fs::remove_file(path), "stale socket {:?}", path)?
+            }
+            Err(_) => (),
+        }
+    }
+    err_at!(IOError, unix::net::UnixListener::bind(path), "{:?}", path)
+}
+
 pub enum Conn {
     Tcp {
         laddr: NetAddr,
@@ -79,6 +289,14 @@ pub enum Conn {
         raddr: NetAddr,
         conn: unix::net::UnixStream,
     },
+    /// Stream to an i2p peer, tunneled through SAM bridge, refer
+    /// [SamSession][crate::net_i2p::SamSession].
+    I2p {
+        raddr: Multiaddr,
+        conn: net::TcpStream,
+    },
+    /// Connection to a private network peer, refer [Conn::into_pnet].
+    Pnet { conn: Box<PnetConn<Conn>> },
 }
 
 impl Conn {
@@ -109,6 +327,95 @@ impl Conn {
         Ok(conn)
     }
 
+    /// Same as [Conn::dial], but for tcp addresses give up if connection
+    /// is not established within `timeout`.
+    pub fn dial_timeout(raddr: NetAddr, timeout: Duration) -> Result<Conn> {
+        match raddr {
+            NetAddr::Tcp(raddr) => {
+                let res = net::TcpStream::connect_timeout(&raddr, timeout);
+                let conn = err_at!(IOError, res, "dial {}", raddr)?;
+                let laddr = err_at!(IOError, conn.local_addr())?;
+                Ok(Conn::Tcp {
+                    laddr: NetAddr::Tcp(laddr),
+                    raddr: NetAddr::Tcp(raddr),
+                    conn,
+                })
+            }
+            raddr => Conn::dial(raddr),
+        }
+    }
+
+    /// Dial a remote multiaddr `ma`. Like [Listener::bind_multiaddr],
+    /// `/unix` addresses are dialed directly on the socket-path.
+    pub fn dial_multiaddr(ma: Multiaddr) -> Result<Conn> {
+        match ma.parse()? {
+            Multiaddr::Unix(val, _) => {
+                let path = val.to_path();
+                let conn = err_at!(IOError, unix::net::UnixStream::connect(&path), "{}", path)?;
+                Ok(Conn::Unix {
+                    laddr: NetAddr::Unix(err_at!(IOError, conn.local_addr())?),
+                    raddr: NetAddr::Unix(err_at!(IOError, conn.peer_addr())?),
+                    conn,
+                })
+            }
+            ma => Conn::dial(NetAddr::from_multiaddr(ma)?),
+        }
+    }
+
+    /// Same as [Conn::dial_multiaddr], with `timeout` for tcp addresses.
+    pub fn dial_multiaddr_timeout(ma: Multiaddr, timeout: Duration) -> Result<Conn> {
+        match ma.parse()? {
+            ma @ Multiaddr::Unix(_, _) => Conn::dial_multiaddr(ma),
+            ma => Conn::dial_timeout(NetAddr::from_multiaddr(ma)?, timeout),
+        }
+    }
+
+    /// Same as [Conn::dial_multiaddr_timeout], but fail with the
+    /// io::Error from connect, so that callers can tell a refused
+    /// connection from a timeout by its [io::ErrorKind]. Addresses that
+    /// can't be dialed fail with [io::ErrorKind::InvalidInput].
+    pub fn connect_multiaddr(ma: Multiaddr, timeout: Duration) -> io::Result<Conn> {
+        let invalid = |err: Error| io::Error::new(io::ErrorKind::InvalidInput, err.to_string());
+
+        match ma.parse().map_err(invalid)? {
+            Multiaddr::Unix(val, _) => {
+                let conn = unix::net::UnixStream::connect(&val.to_path())?;
+                Ok(Conn::Unix {
+                    laddr: NetAddr::Unix(conn.local_addr()?),
+                    raddr: NetAddr::Unix(conn.peer_addr()?),
+                    conn,
+                })
+            }
+            ma => match NetAddr::from_multiaddr(ma).map_err(invalid)? {
+                NetAddr::Tcp(raddr) => {
+                    let conn = net::TcpStream::connect_timeout(&raddr, timeout)?;
+                    Ok(Conn::Tcp {
+                        laddr: NetAddr::Tcp(conn.local_addr()?),
+                        raddr: NetAddr::Tcp(raddr),
+                        conn,
+                    })
+                }
+                raddr => {
+                    let msg = format!("no dial for {:?}", raddr);
+                    Err(io::Error::new(io::ErrorKind::InvalidInput, msg))
+                }
+            },
+        }
+    }
+
+    /// Wrap the connection for private network `psk`, all bytes on the
+    /// wire are encrypted with the pre-shared-key, refer [PnetConn].
+    pub fn into_pnet(self, psk: &Psk) -> Conn {
+        Conn::Pnet {
+            conn: Box::new(PnetConn::new(self, psk)),
+        }
+    }
+
+    /// Return whether the connection is wrapped for private network.
+    pub fn is_pnet(&self) -> bool {
+        matches!(self, Conn::Pnet { .. })
+    }
+
     pub fn recv(&self) {
         todo!()
     }
@@ -117,8 +424,17 @@ impl Conn {
         todo!()
     }
 
+    /// Shutdown both directions of the connection and drop it.
     pub fn close(self) {
-        todo!()
+        match self {
+            Conn::Tcp { conn, .. } | Conn::I2p { conn, .. } => {
+                conn.shutdown(net::Shutdown::Both).ok();
+            }
+            Conn::Unix { conn, .. } => {
+                conn.shutdown(net::Shutdown::Both).ok();
+            }
+            Conn::Pnet { conn } => conn.into_inner().close(),
+        }
     }
 
     pub fn set_read_timeout(&self) {
@@ -129,12 +445,33 @@ impl Conn {
         todo!()
     }
 
+    /// Return the local address, for i2p it is the local end of the
+    /// tunnel to SAM bridge.
     pub fn to_local_addr(&self) -> Result<NetAddr> {
-        todo!()
+        match self {
+            Conn::Tcp { laddr, .. } | Conn::Unix { laddr, .. } => Ok(laddr.clone()),
+            Conn::I2p { conn, .. } => Ok(NetAddr::Tcp(err_at!(IOError, conn.local_addr())?)),
+            Conn::Pnet { conn } => conn.as_inner().to_local_addr(),
+        }
     }
 
+    /// Return the remote address, fail for i2p, refer
+    /// [Conn::to_remote_multiaddr].
     pub fn to_remote_addr(&self) -> Result<NetAddr> {
-        todo!()
+        match self {
+            Conn::Tcp { raddr, .. } | Conn::Unix { raddr, .. } => Ok(raddr.clone()),
+            Conn::I2p { raddr, .. } => err_at!(Invalid, msg: "i2p remote {}", raddr.to_text()?),
+            Conn::Pnet { conn } => conn.as_inner().to_remote_addr(),
+        }
+    }
+
+    /// Return the remote address as multiaddr, for all transports.
+    pub fn to_remote_multiaddr(&self) -> Result<Multiaddr> {
+        match self {
+            Conn::I2p { raddr, .. } => Ok(raddr.clone()),
+            Conn::Pnet { conn } => conn.as_inner().to_remote_multiaddr(),
+            _ => self.to_remote_addr()?.to_multiaddr(),
+        }
     }
 
     pub fn close_read(&mut self) -> Result<()> {
@@ -145,3 +482,106 @@ impl Conn {
         todo!()
     }
 }
+
+impl io::Read for Conn {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Conn::Tcp { conn, .. } | Conn::I2p { conn, .. } => conn.read(buf),
+            Conn::Unix { conn, .. } => conn.read(buf),
+            Conn::Pnet { conn } => conn.read(buf),
+        }
+    }
+}
+
+impl io::Write for Conn {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Conn::Tcp { conn, .. } | Conn::I2p { conn, .. } => conn.write(buf),
+            Conn::Unix { conn, .. } => conn.write(buf),
+            Conn::Pnet { conn } => conn.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Conn::Tcp { conn, .. } | Conn::I2p { conn, .. } => conn.flush(),
+            Conn::Unix { conn, .. } => conn.flush(),
+            Conn::Pnet { conn } => conn.flush(),
+        }
+    }
+}
+
+/// Dial backoff cache, keyed by peer and address. Addresses that failed
+/// to dial are not re-dialed until their backoff period expires, which
+/// keeps the dialer from hammering dead addresses.
+pub struct DialBackoff {
+    clock: Arc<dyn Clock>,
+    entries: HashMap<(PeerId, Vec<u8>), (usize, Instant)>,
+}
+
+impl Default for DialBackoff {
+    fn default() -> DialBackoff {
+        DialBackoff::with_clock(Arc::new(SystemClock))
+    }
+}
+
+impl DialBackoff {
+    pub fn new() -> DialBackoff {
+        DialBackoff::default()
+    }
+
+    /// Create a backoff cache that reads time from `clock`.
+    pub fn with_clock(clock: Arc<dyn Clock>) -> DialBackoff {
+        DialBackoff {
+            clock,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Record a failed dial to `addr` of `peer`.
+    pub fn add_failure(&mut self, peer: &PeerId, addr: &Multiaddr) -> Result<()> {
+        let key = (peer.clone(), addr.canonical_bytes()?);
+        let now = self.clock.now();
+        let entry = self.entries.entry(key).or_insert((0, now));
+        *entry = (entry.0 + 1, now + Self::to_backoff(entry.0 + 1));
+        Ok(())
+    }
+
+    /// Return true if dials to `addr` of `peer` shall be skipped.
+    pub fn is_backoff(&self, peer: &PeerId, addr: &Multiaddr) -> Result<bool> {
+        let key = (peer.clone(), addr.canonical_bytes()?);
+        let val = match self.entries.get(&key) {
+            Some((_, until)) => self.clock.now() < *until,
+            None => false,
+        };
+        Ok(val)
+    }
+
+    /// Forget failures for `addr` of `peer`, typically after a successful
+    /// dial.
+    pub fn clear(&mut self, peer: &PeerId, addr: &Multiaddr) -> Result<()> {
+        let key = (peer.clone(), addr.canonical_bytes()?);
+        self.entries.remove(&key);
+        Ok(())
+    }
+
+    /// Remove expired entries.
+    pub fn purge(&mut self) {
+        let now = self.clock.now();
+        self.entries.retain(|_, (_, until)| now < *until);
+    }
+
+    /// Return the backoff period after `n` failed attempts.
+    pub fn to_backoff(n: usize) -> Duration {
+        let n = n.min(u32::MAX as usize) as u32;
+        let coef = BACKOFF_COEF.checked_mul(n.saturating_mul(n));
+        match coef.and_then(|coef| BACKOFF_BASE.checked_add(coef)) {
+            Some(val) if val < BACKOFF_MAX => val,
+            _ => BACKOFF_MAX,
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "net_conn_test.rs"]
+mod net_conn_test;
@@ -0,0 +1,143 @@
+use super::*;
+
+#[test]
+fn test_dial_backoff() {
+    assert_eq!(DialBackoff::to_backoff(0), BACKOFF_BASE);
+    assert_eq!(DialBackoff::to_backoff(1), Duration::from_secs(6));
+    assert_eq!(DialBackoff::to_backoff(10), Duration::from_secs(105));
+    assert_eq!(DialBackoff::to_backoff(100), BACKOFF_MAX);
+    assert_eq!(DialBackoff::to_backoff(usize::MAX), BACKOFF_MAX);
+
+    let peer = PeerId::generate().unwrap();
+    let addr = Multiaddr::from_text("/ip4/127.0.0.1/tcp/4001").unwrap();
+    let other = Multiaddr::from_text("/ip4/127.0.0.1/tcp/4002").unwrap();
+
+    let mut backoff = DialBackoff::new();
+    assert!(!backoff.is_backoff(&peer, &addr).unwrap());
+
+    backoff.add_failure(&peer, &addr).unwrap();
+    assert!(backoff.is_backoff(&peer, &addr).unwrap());
+    assert!(!backoff.is_backoff(&peer, &other).unwrap());
+    assert!(!backoff
+        .is_backoff(&PeerId::generate().unwrap(), &addr)
+        .unwrap());
+
+    backoff.purge();
+    assert!(backoff.is_backoff(&peer, &addr).unwrap());
+
+    backoff.clear(&peer, &addr).unwrap();
+    assert!(!backoff.is_backoff(&peer, &addr).unwrap());
+}
+
+#[test]
+fn test_dial_backoff_expiry() {
+    use crate::util::MockClock;
+
+    let clock = MockClock::new();
+    let mut backoff = DialBackoff::with_clock(Arc::new(clock.clone()));
+
+    let peer = PeerId::generate().unwrap();
+    let addr = Multiaddr::from_text("/ip4/127.0.0.1/tcp/4001").unwrap();
+
+    backoff.add_failure(&peer, &addr).unwrap();
+    clock.advance(Duration::from_secs(5));
+    assert!(backoff.is_backoff(&peer, &addr).unwrap());
+    clock.advance(Duration::from_secs(1));
+    assert!(!backoff.is_backoff(&peer, &addr).unwrap());
+
+    // second failure backs off for 5 + 1*2^2 seconds.
+    backoff.add_failure(&peer, &addr).unwrap();
+    clock.advance(Duration::from_secs(8));
+    backoff.purge();
+    assert!(backoff.is_backoff(&peer, &addr).unwrap());
+    clock.advance(Duration::from_secs(1));
+    backoff.purge();
+    assert!(!backoff.is_backoff(&peer, &addr).unwrap());
+}
+
+#[test]
+fn test_swarm_addrs() {
+    let mut addrs = Multiaddr::from_text_list("Addresses.swarm", &swarm_addrs(SWARM_PORT)).unwrap();
+    assert_eq!(addrs.len(), 4);
+    assert_eq!(addrs[2].to_text().unwrap(), "/ip4/0.0.0.0/udp/4001/quic");
+    check_collisions(&addrs).unwrap();
+
+    let ok = [
+        "/ip4/127.0.0.1/tcp/4002",
+        "/ip4/127.0.0.1/tcp/0",
+        "/ip4/127.0.0.1/tcp/0",
+        "/unix/tmp/ipfs.sock",
+    ];
+    for text in ok.iter() {
+        addrs.push(Multiaddr::from_text(text).unwrap());
+    }
+    check_collisions(&addrs).unwrap();
+
+    let bad = [
+        "/ip4/127.0.0.1/tcp/4001",
+        "/ip6/::1/udp/4001/quic",
+        "/ip4/0.0.0.0/tcp/4001",
+        "/unix/tmp/ipfs.sock",
+    ];
+    for text in bad.iter() {
+        let mut addrs = addrs.clone();
+        addrs.push(Multiaddr::from_text(text).unwrap());
+        assert!(check_collisions(&addrs).is_err(), "{}", text);
+        assert!(Listener::bind_all(addrs).is_err(), "{}", text);
+    }
+}
+
+#[test]
+fn test_bind_dual_stack() {
+    // hosts without IPv6 can't exercise this.
+    if net::TcpListener::bind("[::1]:0").is_err() {
+        return;
+    }
+
+    let port = match Listener::bind(NetAddr::Tcp(([0, 0, 0, 0], 0).into())).unwrap() {
+        Listener::Tcp(listn) => listn.local_addr().unwrap().port(),
+        _ => unreachable!(),
+    };
+    // without IPV6_V6ONLY, Linux fails to bind `/ip6/::` after
+    // `/ip4/0.0.0.0` on the same port.
+    let texts = swarm_addrs(port);
+    let addrs = Multiaddr::from_text_list("Addresses.swarm", &texts).unwrap();
+    let listeners = Listener::bind_all(addrs).unwrap();
+    assert_eq!(listeners.len(), 4);
+
+    match listeners[3].to_local_addr().unwrap() {
+        NetAddr::Udp(addr) => assert_eq!(addr.port(), port),
+        addr => panic!("{:?}", addr),
+    }
+    assert!(listeners[3].accept().is_err());
+    listeners.into_iter().for_each(|listn| listn.close());
+}
+
+#[test]
+fn test_bind_unix_stale() {
+    let path = std::env::temp_dir().join(format!("iprs-net-conn-{}.sock", std::process::id()));
+    fs::remove_file(&path).ok();
+    let ma = Multiaddr::from_text(&format!("/unix{}", path.to_str().unwrap())).unwrap();
+
+    // socket file left behind by a listener that went away.
+    drop(unix::net::UnixListener::bind(&path).unwrap());
+    assert!(path.exists());
+    let listn = Listener::bind_multiaddr(ma.clone()).unwrap();
+
+    // live listener is not clobbered.
+    assert!(Listener::bind_multiaddr(ma.clone()).is_err());
+    assert!(Conn::dial_multiaddr(ma.clone()).is_ok());
+
+    // a regular file is not removed.
+    listn.close();
+    assert!(!path.exists());
+    fs::write(&path, b"data").unwrap();
+    assert!(Listener::bind_multiaddr(ma.clone()).is_err());
+    assert_eq!(fs::read(&path).unwrap(), b"data");
+    fs::remove_file(&path).unwrap();
+
+    // on failure bind_all removes socket files it created.
+    let addrs = vec![ma, Multiaddr::from_text("/ip4/1.1.1.1/tcp/1").unwrap()];
+    assert!(Listener::bind_all(addrs).is_err());
+    assert!(!path.exists());
+}
@@ -0,0 +1,211 @@
+//! Module implement dialing I2P peers through a SAMv3 bridge.
+//!
+//! `/garlic64` and `/garlic32` addresses can't be dialed directly, they
+//! are reached through the I2P router running alongside the daemon. The
+//! router exposes a line oriented text protocol, SAM, typically on
+//! [SAM_ENDPOINT]. A [SamSession] holds a transient I2P destination for
+//! the lifetime of its control connection, and each [SamSession::dial]
+//! opens a new connection to the bridge that, once the remote accepts,
+//! carries the stream's data.
+//!
+//! Refer [SAMv3](https://geti2p.net/en/docs/api/samv3) for details.
+
+use std::{
+    collections::HashMap,
+    io::{self, Read, Write},
+    net, process,
+    sync::Mutex,
+    time::Duration,
+};
+
+use crate::{multiaddr::Multiaddr, net_conn::Conn, Error, Result};
+
+/// Default endpoint for SAM bridge, as configured by I2P routers.
+pub const SAM_ENDPOINT: &str = "127.0.0.1:7656";
+
+/// Default timeout for SAM commands, creating a session and building
+/// tunnels to a remote can take a while.
+pub const SAM_TIMEOUT: Duration = Duration::from_secs(60);
+
+// SIGNATURE_TYPE requires 3.1.
+const HELLO: &str = "HELLO VERSION MIN=3.1 MAX=3.3";
+
+// limit on reply lines from the bridge.
+const MAX_LINE: usize = 64 * 1024;
+
+/// Session with a SAM bridge, shareable across threads.
+pub struct SamSession {
+    endpoint: net::SocketAddr,
+    id: String,
+    timeout: Duration,
+    // session lives as long as this connection is open.
+    control: Mutex<net::TcpStream>,
+}
+
+impl SamSession {
+    /// Create a streaming session on the SAM bridge at `endpoint`, with a
+    /// transient destination. `timeout` applies to each SAM command,
+    /// default is [SAM_TIMEOUT].
+    pub fn create(endpoint: net::SocketAddr, timeout: Duration) -> Result<SamSession> {
+        let id = format!("iprs-{}-{:08x}", process::id(), rand::random::<u32>());
+
+        let mut control = connect(endpoint, timeout)?;
+        let cmd = format!(
+            "SESSION CREATE STYLE=STREAM ID={} DESTINATION=TRANSIENT SIGNATURE_TYPE=7",
+            id
+        );
+        command(&mut control, &cmd, "SESSION STATUS")?;
+
+        Ok(SamSession {
+            endpoint,
+            id,
+            timeout,
+            control: Mutex::new(control),
+        })
+    }
+
+    /// Return the session id, as registered with the bridge.
+    pub fn to_id(&self) -> String {
+        self.id.clone()
+    }
+
+    /// Return the bridge endpoint for this session.
+    pub fn to_endpoint(&self) -> net::SocketAddr {
+        self.endpoint
+    }
+
+    /// Dial a `/garlic64` or `/garlic32` address, base32 addresses are
+    /// resolved to their full destination via the bridge.
+    pub fn dial(&self, ma: &Multiaddr) -> Result<Conn> {
+        let dest = match to_destination(ma)? {
+            Some(dest) if dest.ends_with(".b32.i2p") => self.lookup(&dest)?,
+            Some(dest) => dest,
+            None => err_at!(BadAddr, msg: "not an i2p address {:?}", ma)?,
+        };
+
+        let mut conn = connect(self.endpoint, self.timeout)?;
+        let cmd = format!(
+            "STREAM CONNECT ID={} DESTINATION={} SILENT=false",
+            self.id, dest
+        );
+        command(&mut conn, &cmd, "STREAM STATUS")?;
+        // from here on, it is the stream's data.
+        err_at!(IOError, conn.set_read_timeout(None))?;
+        err_at!(IOError, conn.set_write_timeout(None))?;
+
+        Ok(Conn::I2p {
+            raddr: ma.clone(),
+            conn,
+        })
+    }
+
+    /// Resolve `name`, like `<base32>.b32.i2p`, to its base64 destination.
+    pub fn lookup(&self, name: &str) -> Result<String> {
+        let mut control = err_at!(Fatal, self.control.lock())?;
+        let cmd = format!("NAMING LOOKUP NAME={}", name);
+        let mut reply = command(&mut control, &cmd, "NAMING REPLY")?;
+        match reply.remove("VALUE") {
+            Some(dest) => Ok(dest),
+            None => err_at!(IPCFail, msg: "sam lookup {} no value", name),
+        }
+    }
+}
+
+/// Return the SAM destination for i2p address `ma`, None if `ma` does
+/// not start with a garlic component. Base32 addresses are returned as
+/// `<base32>.b32.i2p` names, refer [SamSession::lookup].
+pub fn to_destination(ma: &Multiaddr) -> Result<Option<String>> {
+    let dest = match ma.clone().parse()? {
+        Multiaddr::Garlic64(val, _) => Some(val.to_destination()),
+        Multiaddr::Garlic32(val, _) => Some(val.to_destination()),
+        _ => None,
+    };
+    Ok(dest)
+}
+
+// connect to the bridge and negotiate protocol version.
+fn connect(endpoint: net::SocketAddr, timeout: Duration) -> Result<net::TcpStream> {
+    let res = net::TcpStream::connect_timeout(&endpoint, timeout);
+    let mut conn = err_at!(IOError, res, "sam {}", endpoint)?;
+    err_at!(IOError, conn.set_read_timeout(Some(timeout)))?;
+    err_at!(IOError, conn.set_write_timeout(Some(timeout)))?;
+
+    command(&mut conn, HELLO, "HELLO REPLY")?;
+    Ok(conn)
+}
+
+// send command `cmd` and read its reply, fail unless the reply is for
+// `topic` with RESULT=OK. Return the reply's key-value pairs.
+fn command(conn: &mut net::TcpStream, cmd: &str, topic: &str) -> Result<HashMap<String, String>> {
+    err_at!(IOError, conn.write_all(format!("{}\n", cmd).as_bytes()))?;
+
+    let line = read_line(conn)?;
+    let (head, reply) = parse_reply(&line)?;
+    if head != topic {
+        err_at!(IPCFail, msg: "sam expected {:?} got {:?}", topic, line)?
+    }
+    match reply.get("RESULT").map(|s| s.as_str()) {
+        Some("OK") => Ok(reply),
+        Some(result) => {
+            let msg = reply.get("MESSAGE").cloned().unwrap_or_default();
+            err_at!(IPCFail, msg: "sam {} {} {}", topic, result, msg)
+        }
+        None => err_at!(IPCFail, msg: "sam no result {:?}", line),
+    }
+}
+
+// read a reply line one byte at a time, so that stream data following
+// the reply is left unread.
+fn read_line<R: Read>(r: &mut R) -> Result<String> {
+    let mut line = vec![];
+    let mut byte = [0_u8; 1];
+    loop {
+        match r.read(&mut byte) {
+            Ok(0) => err_at!(IOError, msg: "sam connection closed")?,
+            Ok(_) if byte[0] == b'\n' => break,
+            Ok(_) if line.len() >= MAX_LINE => err_at!(IPCFail, msg: "sam line too long")?,
+            Ok(_) => line.push(byte[0]),
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => err_at!(IOError, Err(err))?,
+        }
+    }
+    err_at!(IPCFail, String::from_utf8(line))
+}
+
+// parse `<topic> <sub-topic> KEY=VALUE ..`, values may be double quoted.
+fn parse_reply(line: &str) -> Result<(String, HashMap<String, String>)> {
+    let mut tokens = vec![];
+    let (mut token, mut quoted) = (String::new(), false);
+    for ch in line.trim_end_matches('\r').chars() {
+        match ch {
+            '"' => quoted = !quoted,
+            ' ' if !quoted => {
+                if !token.is_empty() {
+                    tokens.push(std::mem::take(&mut token));
+                }
+            }
+            ch => token.push(ch),
+        }
+    }
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+
+    if tokens.len() < 2 {
+        err_at!(IPCFail, msg: "sam bad reply {:?}", line)?
+    }
+    let head = format!("{} {}", tokens[0], tokens[1]);
+    let mut reply = HashMap::new();
+    for token in tokens[2..].iter() {
+        match token.find('=') {
+            Some(n) => reply.insert(token[..n].to_string(), token[n + 1..].to_string()),
+            None => reply.insert(token.to_string(), "".to_string()),
+        };
+    }
+
+    Ok((head, reply))
+}
+
+#[cfg(test)]
+#[path = "net_i2p_test.rs"]
+mod net_i2p_test;
@@ -0,0 +1,98 @@
+use std::{
+    io::{BufRead, BufReader},
+    thread,
+};
+
+use super::*;
+
+// fake SAM bridge, accepts `n` connections. Destinations starting with
+// "A" are reachable and echo back one line of data.
+fn sam_bridge(n: usize) -> net::SocketAddr {
+    let listn = net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let endpoint = listn.local_addr().unwrap();
+    thread::spawn(move || {
+        for conn in listn.incoming().take(n) {
+            thread::spawn(move || {
+                let mut conn = conn.unwrap();
+                let mut r = BufReader::new(conn.try_clone().unwrap());
+                let mut line = String::new();
+                while r.read_line(&mut line).unwrap() > 0 {
+                    let reply = match line.trim_end() {
+                        l if l.starts_with("HELLO VERSION") => {
+                            "HELLO REPLY RESULT=OK VERSION=3.3".to_string()
+                        }
+                        l if l.starts_with("SESSION CREATE") => {
+                            "SESSION STATUS RESULT=OK DESTINATION=xyz".to_string()
+                        }
+                        l if l.starts_with("NAMING LOOKUP") => {
+                            let name = l.split("NAME=").nth(1).unwrap();
+                            format!("NAMING REPLY RESULT=OK NAME={} VALUE=AAAA", name)
+                        }
+                        l if l.contains("DESTINATION=A") => "STREAM STATUS RESULT=OK".to_string(),
+                        l if l.starts_with("STREAM CONNECT") => {
+                            "STREAM STATUS RESULT=CANT_REACH_PEER MESSAGE=\"peer not found\""
+                                .to_string()
+                        }
+                        data => format!("echo {}", data),
+                    };
+                    conn.write_all(format!("{}\n", reply).as_bytes()).unwrap();
+                    line.clear();
+                }
+            });
+        }
+    });
+    endpoint
+}
+
+#[test]
+fn test_sam_dial() {
+    let endpoint = sam_bridge(4);
+    let sam = SamSession::create(endpoint, Duration::from_secs(5)).unwrap();
+    assert!(sam.to_id().starts_with("iprs-"));
+    assert_eq!(sam.to_endpoint(), endpoint);
+
+    let text = format!("/garlic64/{}", "A".repeat(516));
+    let ma = Multiaddr::from_text(&text).unwrap();
+    assert_eq!(to_destination(&ma).unwrap(), Some("A".repeat(516)));
+    match sam.dial(&ma).unwrap() {
+        Conn::I2p { raddr, mut conn } => {
+            assert_eq!(raddr, ma);
+            conn.write_all(b"hello\n").unwrap();
+            assert_eq!(read_line(&mut conn).unwrap(), "echo hello");
+        }
+        _ => panic!("expected i2p connection"),
+    }
+
+    // base32 is resolved before connecting.
+    let ma = Multiaddr::from_text(&format!("/garlic32/{}", "a".repeat(52))).unwrap();
+    let name = format!("{}.b32.i2p", "a".repeat(52));
+    assert_eq!(to_destination(&ma).unwrap(), Some(name.clone()));
+    assert_eq!(sam.lookup(&name).unwrap(), "AAAA");
+    assert!(sam.dial(&ma).is_ok());
+
+    let text = format!("/garlic64/{}", "B".repeat(516));
+    let err = sam.dial(&Multiaddr::from_text(&text).unwrap()).unwrap_err();
+    assert!(
+        err.to_string().contains("CANT_REACH_PEER peer not found"),
+        "{}",
+        err
+    );
+
+    let ma = Multiaddr::from_text("/ip4/127.0.0.1/tcp/4001").unwrap();
+    assert_eq!(to_destination(&ma).unwrap(), None);
+    assert!(sam.dial(&ma).is_err());
+}
+
+#[test]
+fn test_parse_reply() {
+    let (head, reply) = parse_reply("HELLO REPLY RESULT=OK VERSION=3.3\r").unwrap();
+    assert_eq!(head, "HELLO REPLY");
+    assert_eq!(reply.get("RESULT").unwrap(), "OK");
+    assert_eq!(reply.get("VERSION").unwrap(), "3.3");
+
+    let line = "STREAM STATUS RESULT=I2P_ERROR MESSAGE=\"no tunnels, try later\"";
+    let (_, reply) = parse_reply(line).unwrap();
+    assert_eq!(reply.get("MESSAGE").unwrap(), "no tunnels, try later");
+
+    assert!(parse_reply("PING").is_err());
+}
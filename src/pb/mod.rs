@@ -4,3 +4,5 @@
 pub mod key_pair_proto;
 /// Module auto-generated from peer_record.proto
 pub mod peer_record_proto;
+/// Module auto-generated from envelope.proto
+pub mod envelope_proto;
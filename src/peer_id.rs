@@ -7,9 +7,10 @@ use bs58;
 use multibase::Base;
 use rand::Rng;
 
-use std::{fmt, hash};
+use std::{cmp, convert::TryFrom, fmt, hash};
 
 use crate::{
+    cid::Cid,
     identity::PublicKey,
     multibase::Multibase,
     multicodec::{self, Multicodec},
@@ -59,24 +60,72 @@ impl fmt::Display for PeerId {
     }
 }
 
+// equality, ordering and hashing are on the multihash, ordered same as
+// its binary encoding, like go-libp2p which compares the raw bytes of
+// peer id.
+impl PartialEq<PeerId> for PeerId {
+    fn eq(&self, other: &PeerId) -> bool {
+        self.mh == other.mh
+    }
+}
+
+impl PartialOrd for PeerId {
+    fn partial_cmp(&self, other: &PeerId) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PeerId {
+    fn cmp(&self, other: &PeerId) -> cmp::Ordering {
+        self.mh.cmp(&other.mh)
+    }
+}
+
 impl hash::Hash for PeerId {
     fn hash<H>(&self, state: &mut H)
     where
         H: hash::Hasher,
     {
-        hash::Hash::hash(&self.mh.encode().unwrap(), state)
+        self.mh.hash(state)
     }
 }
 
-impl PartialEq<PeerId> for PeerId {
-    fn eq(&self, other: &PeerId) -> bool {
-        self.mh == other.mh
+impl TryFrom<Multihash> for PeerId {
+    type Error = Error;
+
+    /// Multihash must either be an identity hash over a valid
+    /// protobuf-encoded public-key, or a sha2-256 hash.
+    fn try_from(mh: Multihash) -> Result<PeerId> {
+        let (codec, digest) = mh.clone().unwrap()?;
+        match codec.to_code() {
+            multicodec::IDENTITY if digest.len() <= MAX_INLINE_KEY_LENGTH => {
+                PublicKey::from_protobuf_encoding(&digest)?;
+            }
+            multicodec::IDENTITY => {
+                err_at!(BadInput, msg: "inlined key too long {}", digest.len())?
+            }
+            multicodec::SHA2_256 if digest.len() == 32 => (),
+            multicodec::SHA2_256 => err_at!(BadInput, msg: "sha2-256 digest {}", digest.len())?,
+            _ => err_at!(BadInput, msg: "peer-id hash {}", codec)?,
+        }
+        Ok(PeerId { mh })
     }
 }
 
-impl From<Multihash> for PeerId {
-    fn from(mh: Multihash) -> Self {
-        PeerId { mh }
+impl TryFrom<Cid> for PeerId {
+    type Error = Error;
+
+    /// Cid must either be in legacy format, or in version-1 format with
+    /// _LIBP2P_KEY_ as content-type. Its multihash is validated as with
+    /// `TryFrom<Multihash>`.
+    fn try_from(cid: Cid) -> Result<PeerId> {
+        match cid {
+            Cid::Zero(mh) => PeerId::try_from(mh),
+            Cid::One(_, codec, mh) if codec.to_code() == multicodec::LIBP2P_KEY => {
+                PeerId::try_from(mh)
+            }
+            Cid::One(_, codec, _) => err_at!(BadInput, msg: "peer-id content-type {}", codec),
+        }
     }
 }
 
@@ -87,6 +136,13 @@ impl From<PeerId> for Multihash {
 }
 
 impl PeerId {
+    /// Wrap `mh` as PeerId without validating it, for internal use where
+    /// the multihash is already known to be a peer-id. Use `TryFrom` for
+    /// multihash from untrusted sources.
+    pub(crate) fn from_multihash_unchecked(mh: Multihash) -> PeerId {
+        PeerId { mh }
+    }
+
     /// Builds a `PeerId` from a public key.
     pub fn from_public_key(key: PublicKey) -> Result<PeerId> {
         let enc_buf = key.into_protobuf_encoding()?;
@@ -97,38 +153,23 @@ impl PeerId {
         };
 
         let mh = Multihash::new(codec, &enc_buf)?;
-        Ok(PeerId { mh })
+        Ok(PeerId::from_multihash_unchecked(mh))
     }
 
     /// Generates a random peer ID from a cryptographically secure PRNG.
+    /// Peer ID is a sha2-256 multihash over random bytes, hence valid as
+    /// per `TryFrom<Multihash>`, though there is no key behind it.
     ///
     /// This is useful for randomly walking on a DHT, or for testing purposes.
     pub fn generate() -> Result<PeerId> {
-        let (bytes, codec) = match rand::thread_rng().gen::<bool>() {
-            true => {
-                let codec: Multicodec = multicodec::IDENTITY.into();
-                let bytes = rand::thread_rng().gen::<[u8; 32]>().to_vec();
-                (bytes, codec)
-            }
-            false => {
-                let codec: Multicodec = multicodec::SHA2_256.into();
-                let bytes = {
-                    let mut data = vec![];
-                    data.extend_from_slice(&rand::thread_rng().gen::<[u8; 32]>());
-                    data.extend_from_slice(&rand::thread_rng().gen::<[u8; 32]>());
-                    data
-                };
-                (bytes, codec)
-            }
-        };
-
-        let mh = Multihash::new(codec, &bytes)?;
-        Ok(PeerId { mh })
+        let bytes = rand::thread_rng().gen::<[u8; 32]>();
+        let mh = Multihash::new(multicodec::SHA2_256.into(), &bytes)?;
+        Ok(PeerId::from_multihash_unchecked(mh))
     }
 
     /// Decode a base encoded PeerId, human readable text. Peerid format
     /// can either be in legacy format (base58btc) or multi-base encoded
-    /// CID format.
+    /// CID format. Multihash is validated as with `TryFrom<Multihash>`.
     pub fn from_text(text: &str) -> Result<PeerId> {
         let mut chars = text.chars();
         let peer_id = match (chars.next(), chars.next()) {
@@ -136,7 +177,7 @@ impl PeerId {
                 // legacy format base58btc.
                 let bytes = err_at!(BadInput, bs58::decode(text.as_bytes()).into_vec())?;
                 let (mh, _) = Multihash::decode(&bytes)?;
-                PeerId { mh }
+                PeerId::try_from(mh)?
             }
             _ => {
                 let bytes = {
@@ -159,7 +200,7 @@ impl PeerId {
                     _ => err_at!(BadInput, msg: "codec {}", codec)?,
                 }
                 let (mh, _) = Multihash::decode(bytes)?;
-                PeerId { mh }
+                PeerId::try_from(mh)?
             }
         };
 
@@ -195,10 +236,11 @@ impl PeerId {
         self.mh.encode()
     }
 
-    /// Decode PeerId from multihash-binary-format.
+    /// Decode PeerId from multihash-binary-format. Multihash is
+    /// validated as with `TryFrom<Multihash>`.
     pub fn decode(buf: &[u8]) -> Result<(PeerId, &[u8])> {
         let (mh, rem) = Multihash::decode(buf)?;
-        Ok((PeerId { mh }, rem))
+        Ok((PeerId::try_from(mh)?, rem))
     }
 
     /// Checks whether the public key passed as parameter matches the
@@ -76,3 +76,78 @@ fn test_peer_id_examples() {
     let data = peer_id.encode().unwrap();
     assert_eq!(PeerId::decode(&data).unwrap().0, peer_id);
 }
+
+#[test]
+fn test_peer_id_try_from() {
+    use multibase::Base::Base32Lower;
+    use std::convert::TryFrom;
+
+    let peer_id = identity::Keypair::generate_ed25519()
+        .unwrap()
+        .to_public_key()
+        .into_peer_id()
+        .unwrap();
+
+    let mh: Multihash = peer_id.clone().into();
+    assert_eq!(PeerId::try_from(mh).unwrap(), peer_id);
+
+    let cid = Cid::from_peer_id_v1(Base32Lower, peer_id.clone());
+    assert_eq!(PeerId::try_from(cid.clone()).unwrap(), peer_id);
+    assert_eq!(cid.to_peer_id(), Some(peer_id.clone()));
+
+    // identity hash over garbage is not a public key.
+    let mh = Multihash::new(multicodec::IDENTITY.into(), b"not a key").unwrap();
+    assert!(PeerId::try_from(mh.clone()).is_err());
+    let cid = Cid::from_raw(Base32Lower, multicodec::LIBP2P_KEY.into(), mh);
+    assert!(PeerId::try_from(cid.clone()).is_err());
+    assert_eq!(cid.to_peer_id(), None);
+
+    let mh = Multihash::new(multicodec::SHA2_512.into(), b"data").unwrap();
+    assert!(PeerId::try_from(mh).is_err());
+
+    // content-type other than libp2p-key.
+    let cid = Cid::new_v1(Base32Lower, multicodec::RAW.into(), b"data").unwrap();
+    assert!(PeerId::try_from(cid).is_err());
+
+    // text and binary forms are validated the same way.
+    let bad = vec![
+        Multihash::new(multicodec::IDENTITY.into(), b"not a key").unwrap(),
+        Multihash::new(multicodec::SHA2_512.into(), b"data").unwrap(),
+    ];
+    for mh in bad.into_iter() {
+        let data = mh.encode().unwrap();
+        assert!(PeerId::decode(&data).is_err());
+        assert!(PeerId::from_text(&bs58::encode(&data).into_string()).is_err());
+        let cid = Cid::from_raw(Base32Lower, multicodec::LIBP2P_KEY.into(), mh);
+        assert!(PeerId::from_text(&cid.to_text(None).unwrap()).is_err());
+    }
+
+    let peer_id = PeerId::generate().unwrap();
+    let mh: Multihash = peer_id.clone().into();
+    assert_eq!(PeerId::try_from(mh).unwrap(), peer_id);
+}
+
+#[test]
+fn test_peer_id_ord() {
+    use std::collections::{BTreeMap, HashSet};
+
+    let peers: Vec<PeerId> = (0..8).map(|_| PeerId::generate().unwrap()).collect();
+
+    let mut sorted = peers.clone();
+    sorted.sort();
+    for w in sorted.windows(2) {
+        assert!(w[0].encode().unwrap() <= w[1].encode().unwrap());
+    }
+
+    let map: BTreeMap<PeerId, usize> = peers.iter().cloned().zip(0..).collect();
+    for (i, peer) in peers.iter().enumerate() {
+        assert_eq!(map.get(peer), Some(&i));
+    }
+    let set: HashSet<PeerId> = peers.iter().cloned().collect();
+    assert_eq!(set.len(), peers.len());
+
+    let text = peers[0].to_base58btc().unwrap();
+    let peer = PeerId::from_text(&text).unwrap();
+    assert_eq!(peer.cmp(&peers[0]), std::cmp::Ordering::Equal);
+    assert!(set.contains(&peer));
+}
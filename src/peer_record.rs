@@ -5,18 +5,18 @@ use std::{
 
 use crate::{
     addr_info::AddrInfo,
+    identity::Keypair,
     multiaddr::Multiaddr,
     multicodec::{self, Multicodec},
     pb::peer_record_proto,
     peer_id::PeerId,
+    record::Envelope,
     Error, Result,
 };
 
 // Multicodec value for libp2p-peer-record
 pub const MULTICODEC: u128 = multicodec::LIBP2P_PEER_RECORD;
 
-// TODO: How to sign a peer_record and return the envelope.
-
 /// PeerRecord contains information that is broadly useful to share
 /// with other peers, either through a direct exchange (as in the libp2p
 /// identify protocol), or through a Peer Routing provider, such as a DHT.
@@ -95,7 +95,7 @@ impl TryFrom<PeerRecord> for peer_record_proto::PeerRecord {
         let mut addresses = vec![];
         for addr in val.addrs.into_iter() {
             let address = peer_record_proto::peer_record::AddressInfo {
-                multiaddr: addr.encode()?,
+                multiaddr: addr.canonical_bytes()?,
             };
             addresses.push(address);
         }
@@ -144,6 +144,41 @@ impl PeerRecord {
         Ok(buf)
     }
 
+    /// Return the canonical protobuf encoding of this record, this is
+    /// the payload signed within an envelope. Fields are encoded in tag
+    /// order and multiaddrs in their canonical form.
+    pub fn canonical_bytes(&self) -> Result<Vec<u8>> {
+        self.clone().encode_protobuf()
+    }
+
+    /// Sign this record with `key` and return the sealed envelope. `key`
+    /// must belong to the record's peer.
+    pub fn into_envelope(self, key: &Keypair) -> Result<Envelope> {
+        let peer_id = key.to_public_key().into_peer_id()?;
+        if peer_id != self.peer_id {
+            err_at!(SigningError, msg: "key is not of record's peer")?
+        }
+
+        let payload = self.canonical_bytes()?;
+        Envelope::seal(key, &self.to_domain(), self.to_multicodec(), payload)
+    }
+
+    /// Extract peer-record from a verified envelope, refer [Envelope::open].
+    /// Return error if envelope is not signed by the record's peer.
+    pub fn from_envelope(envelope: &Envelope) -> Result<PeerRecord> {
+        let typ = envelope.to_payload_type()?;
+        if typ.to_code() != MULTICODEC {
+            err_at!(DecodeError, msg: "payload type {} not peer-record", typ)?
+        }
+
+        let rec = PeerRecord::decode_protobuf(envelope.as_payload())?;
+        if envelope.to_public_key().into_peer_id()? != rec.peer_id {
+            err_at!(SigningError, msg: "envelope not signed by record's peer")?
+        }
+
+        Ok(rec)
+    }
+
     pub fn to_domain(&self) -> String {
         "libp2p-peer-record".to_string()
     }
@@ -152,3 +187,7 @@ impl PeerRecord {
         multicodec::LIBP2P_PEER_RECORD.into()
     }
 }
+
+#[cfg(test)]
+#[path = "peer_record_test.rs"]
+mod peer_record_test;
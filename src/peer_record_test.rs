@@ -0,0 +1,46 @@
+use super::*;
+
+#[test]
+fn test_canonical_bytes() {
+    let golden = include_str!("testdata/canonical/peer_record.golden");
+    for line in golden.lines().filter(|l| !l.starts_with('#')) {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let bytes = data_encoding::HEXLOWER.decode(parts[1].as_bytes()).unwrap();
+
+        let rec = PeerRecord {
+            peer_id: PeerId::from_text(parts[0]).unwrap(),
+            addrs: vec![
+                Multiaddr::from_text("/ip4/127.0.0.1/tcp/4001").unwrap(),
+                Multiaddr::Text("/ip6/::1/udp/4001".to_string()),
+            ],
+            seq: 1,
+        };
+        assert_eq!(rec.canonical_bytes().unwrap(), bytes, "{}", parts[0]);
+
+        let rec = PeerRecord::decode_protobuf(&bytes).unwrap();
+        assert_eq!(rec.canonical_bytes().unwrap(), bytes, "{}", parts[0]);
+    }
+}
+
+#[test]
+fn test_envelope() {
+    let key = Keypair::generate_ed25519().unwrap();
+    let peer_id = key.to_public_key().into_peer_id().unwrap();
+    let addrs = vec![Multiaddr::from_text("/ip4/127.0.0.1/tcp/4001").unwrap()];
+    let rec = PeerRecord::from_peer_id(peer_id, addrs).unwrap();
+
+    let data = {
+        let env = rec.clone().into_envelope(&key).unwrap();
+        env.encode_protobuf().unwrap()
+    };
+
+    let env = Envelope::open(&data, &rec.to_domain()).unwrap();
+    let res = PeerRecord::from_envelope(&env).unwrap();
+    assert_eq!(
+        res.canonical_bytes().unwrap(),
+        rec.canonical_bytes().unwrap()
+    );
+
+    let other = Keypair::generate_ed25519().unwrap();
+    assert!(rec.into_envelope(&other).is_err());
+}
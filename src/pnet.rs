@@ -0,0 +1,206 @@
+//! Module implement private networks, using pre-shared-key. _Refer
+//! [pnet] spec for details.
+//!
+//! Peers of a private network share a 32-byte key, typically read from
+//! `swarm.key` file in the repo. Every transport connection is encrypted
+//! using XSalsa20 stream cipher keyed with the shared key, before any
+//! other protocol is negotiated. Each side picks a random 24-byte nonce
+//! and sends it as the first bytes it writes. Peers without the key can't
+//! complete protocol negotiation, hence are refused.
+//!
+//! Dialed connections are wrapped by [Dialer::set_psk][crate::dialer::Dialer::set_psk]
+//! and accepted connections by [Listener::accept_with][crate::net_conn::Listener::accept_with].
+//! When the repo has a `swarm.key`, swarm refuses to listen on transports
+//! that can't be wrapped, like QUIC.
+//!
+//! [pnet]: https://github.com/libp2p/specs/blob/master/pnet/Private-Networks-PSK-V1.md
+
+use rand::Rng;
+use salsa20::{
+    cipher::stream::{NewStreamCipher, SyncStreamCipher},
+    Key, XNonce, XSalsa20,
+};
+use zeroize::Zeroize;
+
+use std::{fs, io, path};
+
+use crate::{util, Error, Result};
+
+/// Name of the file, under repo root, holding the pre-shared-key.
+pub const SWARM_KEY_FILE: &str = "swarm.key";
+
+/// Environment variable, when set to true, refuse to run without a
+/// pre-shared-key.
+pub const ENV_FORCE_PNET: &str = "LIBP2P_FORCE_PNET";
+
+/// Header for version 1 of pre-shared-key file.
+pub const PSK_HEADER: &str = "/key/swarm/psk/1.0.0/";
+
+/// Length of the nonce exchanged at the start of a connection.
+pub const NONCE_SIZE: usize = 24;
+
+/// Pre-shared-key for a private network.
+#[derive(Clone)]
+pub struct Psk {
+    key: [u8; 32],
+}
+
+impl Drop for Psk {
+    fn drop(&mut self) {
+        self.key.zeroize()
+    }
+}
+
+impl Psk {
+    /// Parse pre-shared-key from the content of `swarm.key` file. Key can
+    /// be encoded as `/base16/`, `/base64/` or `/bin/`.
+    pub fn from_swarm_key(data: &[u8]) -> Result<Psk> {
+        use data_encoding::{BASE64, HEXLOWER_PERMISSIVE};
+
+        let (header, data) = next_line(data);
+        if trim(header) != PSK_HEADER.as_bytes() {
+            err_at!(BadInput, msg: "swarm key header {:?}", String::from_utf8_lossy(header))?
+        }
+
+        let (encoding, data) = next_line(data);
+        let mut key = match trim(encoding) {
+            b"/base16/" => err_at!(
+                BadInput,
+                HEXLOWER_PERMISSIVE.decode(trim(next_line(data).0))
+            )?,
+            b"/base64/" => err_at!(BadInput, BASE64.decode(trim(next_line(data).0)))?,
+            b"/bin/" => data.to_vec(),
+            enc => err_at!(BadInput, msg: "swarm key encoding {:?}", String::from_utf8_lossy(enc))?,
+        };
+
+        let res = match key.len() {
+            32 => {
+                let mut psk = Psk { key: [0; 32] };
+                psk.key.copy_from_slice(&key);
+                Ok(psk)
+            }
+            n => err_at!(BadInput, msg: "swarm key must be 32 bytes, got {}", n),
+        };
+        key.zeroize();
+        res
+    }
+
+    /// Read pre-shared-key from `swarm.key` under repo `root`. Return None
+    /// if there is no key file, fail if [ENV_FORCE_PNET] is set and there
+    /// is no key file.
+    pub fn from_repo(root: &path::Path) -> Result<Option<Psk>> {
+        let loc = root.join(SWARM_KEY_FILE);
+        match fs::read(&loc) {
+            Ok(mut data) => {
+                let res = Psk::from_swarm_key(&data);
+                data.zeroize();
+                Ok(Some(res?))
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                match util::get_env_bool(ENV_FORCE_PNET.to_string()) {
+                    true => err_at!(Invalid, msg: "{} set, but no {:?}", ENV_FORCE_PNET, loc),
+                    false => Ok(None),
+                }
+            }
+            Err(err) => err_at!(IOError, Err(err), "{:?}", loc),
+        }
+    }
+
+    fn to_cipher(&self, nonce: &[u8; NONCE_SIZE]) -> XSalsa20 {
+        XSalsa20::new(Key::from_slice(&self.key), XNonce::from_slice(nonce))
+    }
+}
+
+/// Connection wrapper encrypting all bytes written to, and decrypting all
+/// bytes read from, the underlying connection `C`. Refer module
+/// documentation for details.
+pub struct PnetConn<C> {
+    psk: Psk,
+    conn: C,
+    writer: Option<XSalsa20>,
+    reader: Option<XSalsa20>,
+}
+
+impl<C> PnetConn<C> {
+    /// Wrap `conn` for private network `psk`. Nonces are exchanged lazily,
+    /// with the first read and first write.
+    pub fn new(conn: C, psk: &Psk) -> PnetConn<C> {
+        PnetConn {
+            psk: psk.clone(),
+            conn,
+            writer: None,
+            reader: None,
+        }
+    }
+
+    /// Return a reference to the underlying connection.
+    pub fn as_inner(&self) -> &C {
+        &self.conn
+    }
+
+    /// Unwrap the underlying connection.
+    pub fn into_inner(self) -> C {
+        self.conn
+    }
+}
+
+impl<C: io::Read> io::Read for PnetConn<C> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.reader.is_none() {
+            let mut nonce = [0_u8; NONCE_SIZE];
+            self.conn.read_exact(&mut nonce)?;
+            self.reader = Some(self.psk.to_cipher(&nonce));
+        }
+
+        let n = self.conn.read(buf)?;
+        if let Some(reader) = self.reader.as_mut() {
+            reader.apply_keystream(&mut buf[..n]);
+        }
+        Ok(n)
+    }
+}
+
+impl<C: io::Write> io::Write for PnetConn<C> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.writer.is_none() {
+            let nonce: [u8; NONCE_SIZE] = rand::thread_rng().gen();
+            self.conn.write_all(&nonce)?;
+            self.writer = Some(self.psk.to_cipher(&nonce));
+        }
+
+        // encrypt and write all of buf, keystream position must stay in
+        // sync with the bytes that went on the wire.
+        let mut data = buf.to_vec();
+        if let Some(writer) = self.writer.as_mut() {
+            writer.apply_keystream(&mut data);
+        }
+        self.conn.write_all(&data)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.conn.flush()
+    }
+}
+
+fn trim(data: &[u8]) -> &[u8] {
+    let is_space = |b: &u8| b.is_ascii_whitespace();
+    let start = data.iter().position(|b| !is_space(b)).unwrap_or(data.len());
+    let end = data
+        .iter()
+        .rposition(|b| !is_space(b))
+        .map(|n| n + 1)
+        .unwrap_or(start);
+    &data[start..end.max(start)]
+}
+
+fn next_line(data: &[u8]) -> (&[u8], &[u8]) {
+    match data.iter().position(|b| *b == b'\n') {
+        Some(off) => (&data[..off], &data[off + 1..]),
+        None => (data, &[]),
+    }
+}
+
+#[cfg(test)]
+#[path = "pnet_test.rs"]
+mod pnet_test;
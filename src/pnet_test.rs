@@ -0,0 +1,94 @@
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+
+use super::*;
+
+const SWARM_KEY: &str = "/key/swarm/psk/1.0.0/\n/base16/\n\
+    e5cb10b6ba1a4d2a1f1e9c2a8c64ca1e6c8bc10a2f5fd5e4d5ad2fb9b2c1d3e4\n";
+
+#[test]
+fn test_psk_from_swarm_key() {
+    let psk = Psk::from_swarm_key(SWARM_KEY.as_bytes()).unwrap();
+    assert_eq!(psk.key[0], 0xe5);
+    assert_eq!(psk.key[31], 0xe4);
+
+    let text = "/key/swarm/psk/1.0.0/\r\n/base64/\r\n".to_string()
+        + &data_encoding::BASE64.encode(&psk.key);
+    assert_eq!(Psk::from_swarm_key(text.as_bytes()).unwrap().key, psk.key);
+
+    let mut data = b"/key/swarm/psk/1.0.0/\n/bin/\n".to_vec();
+    data.extend_from_slice(&psk.key);
+    assert_eq!(Psk::from_swarm_key(&data).unwrap().key, psk.key);
+
+    let bad = vec![
+        "/key/swarm/psk/2.0.0/\n/base16/\n00",
+        "/key/swarm/psk/1.0.0/\n/base58/\n00",
+        "/key/swarm/psk/1.0.0/\n/base16/\nabcd",
+        "/key/swarm/psk/1.0.0/\n/base16/\nzz",
+        "",
+    ];
+    for text in bad.into_iter() {
+        assert!(Psk::from_swarm_key(text.as_bytes()).is_err(), "{:?}", text);
+    }
+}
+
+#[test]
+fn test_psk_from_repo() {
+    let root = std::env::temp_dir().join("iprs-pnet-test");
+    fs::create_dir_all(&root).unwrap();
+
+    let loc = root.join(SWARM_KEY_FILE);
+    fs::remove_file(&loc).ok();
+    assert!(Psk::from_repo(&root).unwrap().is_none());
+
+    fs::write(&loc, SWARM_KEY).unwrap();
+    assert!(Psk::from_repo(&root).unwrap().is_some());
+
+    fs::write(&loc, "garbage").unwrap();
+    assert!(Psk::from_repo(&root).is_err());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_pnet_conn() {
+    let psk = Psk::from_swarm_key(SWARM_KEY.as_bytes()).unwrap();
+    let (a, b) = UnixStream::pair().unwrap();
+    let (mut a, mut b) = (PnetConn::new(a, &psk), PnetConn::new(b, &psk));
+
+    a.write_all(b"hello ").unwrap();
+    a.write_all(b"world").unwrap();
+    let mut buf = [0_u8; 11];
+    b.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello world");
+
+    b.write_all(b"pong").unwrap();
+    let mut buf = [0_u8; 4];
+    a.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"pong");
+
+    // on the wire, bytes are prefixed with nonce and encrypted.
+    let (a, mut b) = UnixStream::pair().unwrap();
+    let mut a = PnetConn::new(a, &psk);
+    a.write_all(b"hello world").unwrap();
+    let mut buf = [0_u8; NONCE_SIZE + 11];
+    b.read_exact(&mut buf).unwrap();
+    assert!(&buf[NONCE_SIZE..] != b"hello world");
+}
+
+#[test]
+fn test_pnet_conn_mismatch() {
+    let psk = Psk::from_swarm_key(SWARM_KEY.as_bytes()).unwrap();
+    let other = {
+        let text = SWARM_KEY.replace("e5cb", "0000");
+        Psk::from_swarm_key(text.as_bytes()).unwrap()
+    };
+
+    let (a, b) = UnixStream::pair().unwrap();
+    let (mut a, mut b) = (PnetConn::new(a, &psk), PnetConn::new(b, &other));
+
+    a.write_all(b"/multistream/1.0.0\n").unwrap();
+    let mut buf = [0_u8; 19];
+    b.read_exact(&mut buf).unwrap();
+    assert!(&buf != b"/multistream/1.0.0\n");
+}
@@ -0,0 +1,226 @@
+//! Module implement application hooks for gossipsub.
+//!
+//! Applications built on pubsub, like CRDT sync and IPNS over pubsub, need
+//! to check messages before they are delivered and forwarded, and may need
+//! to identify messages by their content rather than by sender and
+//! sequence number. [Topics] keeps per-topic [Validator]s and
+//! [MessageIdFn]s registered by the application, the router consults it
+//! for every received message.
+//!
+//! Outcome of validation also feeds into peer scoring, refer
+//! [Validation] and [Topics::validate_scored].
+
+use std::{collections::BTreeMap, fmt, sync::Arc};
+
+use crate::{peer_id::PeerId, Error, Result};
+
+mod score;
+
+pub use score::{PeerScore, PubsubScore, ScoreParams, ScoreThresholds, TopicScoreParams};
+
+/// Pubsub message, as received from the network.
+#[derive(Clone, Debug)]
+pub struct Message {
+    /// Original author of the message, None for anonymous messages.
+    pub from: Option<PeerId>,
+    pub data: Vec<u8>,
+    /// Sequence number, as big-endian bytes, None for anonymous messages.
+    pub seqno: Option<Vec<u8>>,
+    pub topic: String,
+}
+
+/// Outcome of validating a message.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Validation {
+    /// Deliver the message to application and forward it to peers.
+    Accept,
+    /// Drop the message without penalty, like a duplicate or a message
+    /// that is not interesting to the application.
+    Ignore,
+    /// Drop the message and penalize the peer that forwarded it, counted
+    /// as invalid message delivery for peer scoring.
+    Reject,
+}
+
+impl fmt::Display for Validation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Validation::Accept => write!(f, "accept"),
+            Validation::Ignore => write!(f, "ignore"),
+            Validation::Reject => write!(f, "reject"),
+        }
+    }
+}
+
+impl Validation {
+    /// Return whether the forwarding peer shall be penalized.
+    pub fn is_penalized(&self) -> bool {
+        matches!(self, Validation::Reject)
+    }
+
+    /// Return whether the message shall be delivered and forwarded.
+    pub fn is_accepted(&self) -> bool {
+        matches!(self, Validation::Accept)
+    }
+}
+
+/// Message validator registered by application for a topic. Validators
+/// are called from the router thread and must not block.
+pub trait Validator: Send + Sync {
+    /// Validate message `msg` received from peer `src`, which is not
+    /// necessarily the author.
+    fn validate(&self, src: &PeerId, msg: &Message) -> Validation;
+}
+
+impl<F> Validator for F
+where
+    F: Fn(&PeerId, &Message) -> Validation + Send + Sync,
+{
+    fn validate(&self, src: &PeerId, msg: &Message) -> Validation {
+        self(src, msg)
+    }
+}
+
+/// Function to compute message-id, used to de-duplicate messages and in
+/// IHAVE/IWANT gossip.
+pub trait MessageIdFn: Send + Sync {
+    fn to_message_id(&self, msg: &Message) -> Vec<u8>;
+}
+
+impl<F> MessageIdFn for F
+where
+    F: Fn(&Message) -> Vec<u8> + Send + Sync,
+{
+    fn to_message_id(&self, msg: &Message) -> Vec<u8> {
+        self(msg)
+    }
+}
+
+/// Default message-id, author's peer-id followed by sequence number, as
+/// done by go-libp2p.
+pub fn default_message_id(msg: &Message) -> Vec<u8> {
+    let mut id = match &msg.from {
+        Some(from) => from.encode().unwrap_or_default(),
+        None => vec![],
+    };
+    id.extend_from_slice(msg.seqno.as_deref().unwrap_or(&[]));
+    id
+}
+
+/// Message-id computed as sha256 of message data, for applications where
+/// the same content published by different peers is the same message.
+pub fn content_message_id(msg: &Message) -> Vec<u8> {
+    use digest::Digest;
+
+    sha2::Sha256::digest(&msg.data).to_vec()
+}
+
+/// Per-topic validators and message-id functions, refer module
+/// documentation.
+#[derive(Clone)]
+pub struct Topics {
+    validators: BTreeMap<String, Vec<Arc<dyn Validator>>>,
+    message_ids: BTreeMap<String, Arc<dyn MessageIdFn>>,
+    default_message_id: Arc<dyn MessageIdFn>,
+}
+
+impl fmt::Debug for Topics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let validators: Vec<(&String, usize)> =
+            self.validators.iter().map(|(t, v)| (t, v.len())).collect();
+        let message_ids: Vec<&String> = self.message_ids.keys().collect();
+        write!(
+            f,
+            "Topics<validators:{:?},message_ids:{:?}>",
+            validators, message_ids
+        )
+    }
+}
+
+impl Default for Topics {
+    fn default() -> Topics {
+        Topics {
+            validators: BTreeMap::new(),
+            message_ids: BTreeMap::new(),
+            default_message_id: Arc::new(default_message_id),
+        }
+    }
+}
+
+impl Topics {
+    /// Create a registry without validators, message-id is computed
+    /// using [default_message_id] for all topics.
+    pub fn new() -> Topics {
+        Topics::default()
+    }
+
+    /// Register a validator for `topic`, a topic can have more than one
+    /// validator, refer [Self::validate].
+    pub fn add_validator(&mut self, topic: &str, v: Arc<dyn Validator>) -> &mut Self {
+        self.validators
+            .entry(topic.to_string())
+            .or_insert_with(Vec::new)
+            .push(v);
+        self
+    }
+
+    /// Remove all validators for `topic`, return the number removed.
+    pub fn remove_validators(&mut self, topic: &str) -> usize {
+        self.validators.remove(topic).map(|v| v.len()).unwrap_or(0)
+    }
+
+    /// Set message-id function for `topic`, fail if one is already set.
+    pub fn set_message_id_fn(&mut self, topic: &str, f: Arc<dyn MessageIdFn>) -> Result<&mut Self> {
+        if self.message_ids.contains_key(topic) {
+            err_at!(Invalid, msg: "message-id function for {:?} already set", topic)?
+        }
+        self.message_ids.insert(topic.to_string(), f);
+        Ok(self)
+    }
+
+    /// Set message-id function for topics that don't have one.
+    pub fn set_default_message_id_fn(&mut self, f: Arc<dyn MessageIdFn>) -> &mut Self {
+        self.default_message_id = f;
+        self
+    }
+
+    /// Validate `msg` received from `src` using all validators registered
+    /// for its topic. Most severe outcome wins, Reject over Ignore over
+    /// Accept. Messages on topics without validators are accepted.
+    pub fn validate(&self, src: &PeerId, msg: &Message) -> Validation {
+        let mut res = Validation::Accept;
+        for v in self.validators.get(&msg.topic).into_iter().flatten() {
+            res = res.max(v.validate(src, msg));
+            if res == Validation::Reject {
+                break;
+            }
+        }
+        res
+    }
+
+    /// Same as [Self::validate], and count the outcome against `src` in
+    /// `score`, rejected messages are penalized as invalid deliveries,
+    /// refer [PeerScore::validated].
+    pub fn validate_scored(
+        &self,
+        src: &PeerId,
+        msg: &Message,
+        score: &mut PeerScore,
+    ) -> Validation {
+        let res = self.validate(src, msg);
+        score.validated(src, &msg.topic, res);
+        res
+    }
+
+    /// Return message-id for `msg`, using the function set for its topic.
+    pub fn to_message_id(&self, msg: &Message) -> Vec<u8> {
+        match self.message_ids.get(&msg.topic) {
+            Some(f) => f.to_message_id(msg),
+            None => self.default_message_id.to_message_id(msg),
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "pubsub_test.rs"]
+mod pubsub_test;
@@ -0,0 +1,132 @@
+use super::*;
+
+fn make_message(topic: &str, data: &[u8]) -> Message {
+    Message {
+        from: Some(PeerId::generate().unwrap()),
+        data: data.to_vec(),
+        seqno: Some(1_u64.to_be_bytes().to_vec()),
+        topic: topic.to_string(),
+    }
+}
+
+#[test]
+fn test_validate() {
+    let src = PeerId::generate().unwrap();
+    let mut topics = Topics::new();
+
+    let msg = make_message("crdt", b"hello");
+    assert_eq!(topics.validate(&src, &msg), Validation::Accept);
+
+    topics.add_validator(
+        "crdt",
+        Arc::new(|_: &PeerId, msg: &Message| match msg.data.is_empty() {
+            true => Validation::Reject,
+            false => Validation::Accept,
+        }),
+    );
+    topics.add_validator(
+        "crdt",
+        Arc::new(|_: &PeerId, msg: &Message| match msg.data.as_slice() {
+            b"dup" => Validation::Ignore,
+            _ => Validation::Accept,
+        }),
+    );
+
+    assert_eq!(topics.validate(&src, &msg), Validation::Accept);
+    let res = topics.validate(&src, &make_message("crdt", b"dup"));
+    assert_eq!(res, Validation::Ignore);
+    assert!(!res.is_penalized());
+    let res = topics.validate(&src, &make_message("crdt", b""));
+    assert_eq!(res, Validation::Reject);
+    assert!(res.is_penalized());
+    // other topics are not affected.
+    let res = topics.validate(&src, &make_message("ipns", b""));
+    assert_eq!(res, Validation::Accept);
+
+    assert_eq!(topics.remove_validators("crdt"), 2);
+    let res = topics.validate(&src, &make_message("crdt", b""));
+    assert_eq!(res, Validation::Accept);
+}
+
+#[test]
+fn test_message_id() {
+    let mut topics = Topics::new();
+
+    let a = make_message("crdt", b"hello");
+    let mut b = make_message("crdt", b"hello");
+    assert_ne!(topics.to_message_id(&a), topics.to_message_id(&b));
+    b.from = a.from.clone();
+    assert_eq!(topics.to_message_id(&a), topics.to_message_id(&b));
+
+    let c = make_message("crdt", b"hello");
+    topics
+        .set_message_id_fn("crdt", Arc::new(content_message_id))
+        .unwrap();
+    assert_eq!(topics.to_message_id(&a), topics.to_message_id(&c));
+    assert!(topics
+        .set_message_id_fn("crdt", Arc::new(default_message_id))
+        .is_err());
+
+    let d = make_message("ipns", b"hello");
+    assert_eq!(topics.to_message_id(&d), default_message_id(&d));
+    topics.set_default_message_id_fn(Arc::new(|_: &Message| vec![1, 2]));
+    assert_eq!(topics.to_message_id(&d), vec![1, 2]);
+}
+
+#[test]
+fn test_validate_scored() {
+    use std::{net::IpAddr, time::Instant};
+
+    let mut params = ScoreParams::default();
+    let topic = TopicScoreParams::default();
+    params.topics.insert("crdt".to_string(), topic);
+    let mut score = PeerScore::new(params, ScoreThresholds::default()).unwrap();
+
+    let mut topics = Topics::new();
+    topics.add_validator(
+        "crdt",
+        Arc::new(|_: &PeerId, msg: &Message| match msg.data.as_slice() {
+            b"" => Validation::Reject,
+            b"dup" => Validation::Ignore,
+            _ => Validation::Accept,
+        }),
+    );
+
+    let (src, now) = (PeerId::generate().unwrap(), Instant::now());
+    score.add_peer(src.clone(), IpAddr::from([10, 0, 0, 1]));
+
+    let msg = make_message("crdt", b"hello");
+    assert_eq!(
+        topics.validate_scored(&src, &msg, &mut score),
+        Validation::Accept
+    );
+    let msg = make_message("crdt", b"dup");
+    assert_eq!(
+        topics.validate_scored(&src, &msg, &mut score),
+        Validation::Ignore
+    );
+    assert_eq!(score.to_score(&src, now), 0.0);
+
+    // each rejected message is an invalid delivery, 4^2 * -1 * 0.5.
+    let msg = make_message("crdt", b"");
+    for _ in 0..4 {
+        let res = topics.validate_scored(&src, &msg, &mut score);
+        assert_eq!(res, Validation::Reject);
+    }
+    assert!((score.to_score(&src, now) + 8.0).abs() < 1e-9);
+    assert!(score.accepts_gossip(&src, now));
+    topics.validate_scored(&src, &msg, &mut score);
+    assert!(!score.accepts_gossip(&src, now));
+
+    // rejected on unscored topic, score is unaffected.
+    topics.add_validator(
+        "ipns",
+        Arc::new(|_: &PeerId, _: &Message| Validation::Reject),
+    );
+    let msg = make_message("ipns", b"hello");
+    assert_eq!(
+        topics.validate_scored(&src, &msg, &mut score),
+        Validation::Reject
+    );
+    assert!((score.to_score(&src, now) + 12.5).abs() < 1e-9);
+}
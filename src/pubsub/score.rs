@@ -0,0 +1,683 @@
+//! Module implement gossipsub v1.1 peer scoring.
+//!
+//! Each peer's score is a weighted sum of counters, refer [gossipsub
+//! v1.1] for the rationale behind each of them:
+//!
+//! * P1, time in mesh, per topic.
+//! * P2, first message deliveries, per topic.
+//! * P3, mesh message delivery deficit, per topic.
+//! * P3b, mesh failure penalty, deficit carried over on prune, per topic.
+//! * P4, invalid message deliveries, per topic, refer
+//!   [Validation::Reject].
+//! * P5, application specific score.
+//! * P6, IP colocation factor, penalize many peers from the same IP.
+//! * P7, behaviour penalty, like GRAFT during backoff.
+//!
+//! Topic scores are multiplied by topic weight and their sum is capped
+//! by `topic_score_cap`. Counters decay every `decay_interval`, refer
+//! [PeerScore::refresh]. Router compares the score against
+//! [ScoreThresholds], peers below `graylist` are ignored altogether.
+//!
+//! `Pubsub.Score` from config is parsed into [PubsubScore] and converted
+//! into validated parameters and thresholds, refer [PubsubScore::to_score].
+//!
+//! [gossipsub v1.1]: https://github.com/libp2p/specs/blob/master/pubsub/gossipsub/gossipsub-v1.1.md
+
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    time::{Duration, Instant},
+};
+
+use crate::{peer_id::PeerId, pubsub::Validation, Error, Result};
+
+/// Score parameters for a topic.
+#[derive(Clone, Debug)]
+pub struct TopicScoreParams {
+    pub topic_weight: f64,
+
+    /// P1, positive.
+    pub time_in_mesh_weight: f64,
+    pub time_in_mesh_quantum: Duration,
+    pub time_in_mesh_cap: f64,
+
+    /// P2, positive.
+    pub first_message_deliveries_weight: f64,
+    pub first_message_deliveries_decay: f64,
+    pub first_message_deliveries_cap: f64,
+
+    /// P3, negative.
+    pub mesh_message_deliveries_weight: f64,
+    pub mesh_message_deliveries_decay: f64,
+    pub mesh_message_deliveries_cap: f64,
+    pub mesh_message_deliveries_threshold: f64,
+    /// Time in mesh before P3 kicks in.
+    pub mesh_message_deliveries_activation: Duration,
+
+    /// P3b, negative.
+    pub mesh_failure_penalty_weight: f64,
+    pub mesh_failure_penalty_decay: f64,
+
+    /// P4, negative.
+    pub invalid_message_deliveries_weight: f64,
+    pub invalid_message_deliveries_decay: f64,
+}
+
+impl Default for TopicScoreParams {
+    fn default() -> TopicScoreParams {
+        TopicScoreParams {
+            topic_weight: 0.5,
+
+            time_in_mesh_weight: 1.0,
+            time_in_mesh_quantum: Duration::from_secs(1),
+            time_in_mesh_cap: 3600.0,
+
+            first_message_deliveries_weight: 1.0,
+            first_message_deliveries_decay: 0.5,
+            first_message_deliveries_cap: 2000.0,
+
+            mesh_message_deliveries_weight: -1.0,
+            mesh_message_deliveries_decay: 0.5,
+            mesh_message_deliveries_cap: 100.0,
+            mesh_message_deliveries_threshold: 20.0,
+            mesh_message_deliveries_activation: Duration::from_secs(5),
+
+            mesh_failure_penalty_weight: -1.0,
+            mesh_failure_penalty_decay: 0.5,
+
+            invalid_message_deliveries_weight: -1.0,
+            invalid_message_deliveries_decay: 0.3,
+        }
+    }
+}
+
+/// Score parameters, refer module documentation.
+#[derive(Clone, Debug)]
+pub struct ScoreParams {
+    /// Parameters for scored topics, messages on other topics don't
+    /// affect the score.
+    pub topics: HashMap<String, TopicScoreParams>,
+    /// Cap on the sum of topic scores, zero for no cap.
+    pub topic_score_cap: f64,
+
+    /// P5.
+    pub app_specific_weight: f64,
+
+    /// P6, negative.
+    pub ip_colocation_factor_weight: f64,
+    pub ip_colocation_factor_threshold: usize,
+    pub ip_colocation_factor_whitelist: Vec<IpAddr>,
+
+    /// P7, negative.
+    pub behaviour_penalty_weight: f64,
+    pub behaviour_penalty_threshold: f64,
+    pub behaviour_penalty_decay: f64,
+
+    /// Interval between decaying counters.
+    pub decay_interval: Duration,
+    /// Decayed counters below this value are reset to zero.
+    pub decay_to_zero: f64,
+    /// Time to remember the score of a disconnected peer.
+    pub retain_score: Duration,
+}
+
+impl Default for ScoreParams {
+    fn default() -> ScoreParams {
+        ScoreParams {
+            topics: HashMap::new(),
+            topic_score_cap: 3600.0,
+
+            app_specific_weight: 10.0,
+
+            ip_colocation_factor_weight: -5.0,
+            ip_colocation_factor_threshold: 10,
+            ip_colocation_factor_whitelist: vec![],
+
+            behaviour_penalty_weight: -10.0,
+            behaviour_penalty_threshold: 0.0,
+            behaviour_penalty_decay: 0.2,
+
+            decay_interval: Duration::from_secs(1),
+            decay_to_zero: 0.1,
+            retain_score: Duration::from_secs(3600),
+        }
+    }
+}
+
+impl ScoreParams {
+    /// Check parameters for sign and range, weights that are meant as
+    /// penalties must not be positive.
+    pub fn validate(&self) -> Result<()> {
+        let check = |ok: bool, what: &str| match ok {
+            true => Ok(()),
+            false => err_at!(Invalid, msg: "score param {}", what),
+        };
+        let decay = |d: f64| d > 0.0 && d < 1.0;
+
+        for (topic, p) in self.topics.iter() {
+            let what = |name: &str| format!("{:?} {}", topic, name);
+            check(p.topic_weight >= 0.0, &what("topic_weight"))?;
+            check(p.time_in_mesh_weight >= 0.0, &what("time_in_mesh_weight"))?;
+            check(
+                p.time_in_mesh_quantum > Duration::from_secs(0),
+                &what("time_in_mesh_quantum"),
+            )?;
+            check(
+                p.first_message_deliveries_weight >= 0.0,
+                &what("first_message_deliveries_weight"),
+            )?;
+            check(
+                decay(p.first_message_deliveries_decay),
+                &what("first_message_deliveries_decay"),
+            )?;
+            check(
+                p.mesh_message_deliveries_weight <= 0.0,
+                &what("mesh_message_deliveries_weight"),
+            )?;
+            check(
+                decay(p.mesh_message_deliveries_decay),
+                &what("mesh_message_deliveries_decay"),
+            )?;
+            check(
+                p.mesh_failure_penalty_weight <= 0.0,
+                &what("mesh_failure_penalty_weight"),
+            )?;
+            check(
+                decay(p.mesh_failure_penalty_decay),
+                &what("mesh_failure_penalty_decay"),
+            )?;
+            check(
+                p.invalid_message_deliveries_weight <= 0.0,
+                &what("invalid_message_deliveries_weight"),
+            )?;
+            check(
+                decay(p.invalid_message_deliveries_decay),
+                &what("invalid_message_deliveries_decay"),
+            )?;
+        }
+        check(self.topic_score_cap >= 0.0, "topic_score_cap")?;
+        check(
+            self.ip_colocation_factor_weight <= 0.0,
+            "ip_colocation_factor_weight",
+        )?;
+        check(
+            self.behaviour_penalty_weight <= 0.0,
+            "behaviour_penalty_weight",
+        )?;
+        check(
+            self.behaviour_penalty_threshold >= 0.0,
+            "behaviour_penalty_threshold",
+        )?;
+        check(
+            decay(self.behaviour_penalty_decay),
+            "behaviour_penalty_decay",
+        )?;
+        check(
+            self.decay_interval > Duration::from_secs(0),
+            "decay_interval",
+        )?;
+        check(decay(self.decay_to_zero), "decay_to_zero")?;
+        Ok(())
+    }
+}
+
+/// Score thresholds, all but `accept_px` and `opportunistic_graft` are
+/// negative.
+#[derive(Clone, Debug)]
+pub struct ScoreThresholds {
+    /// Below this, no gossip is emitted to or accepted from the peer.
+    pub gossip: f64,
+    /// Below this, self published messages are not sent to the peer.
+    pub publish: f64,
+    /// Below this, all RPCs from the peer are ignored.
+    pub graylist: f64,
+    /// Above this, peer exchange on PRUNE is accepted from the peer.
+    pub accept_px: f64,
+    /// Median mesh score below this triggers opportunistic grafting.
+    pub opportunistic_graft: f64,
+}
+
+impl Default for ScoreThresholds {
+    fn default() -> ScoreThresholds {
+        ScoreThresholds {
+            gossip: -10.0,
+            publish: -50.0,
+            graylist: -80.0,
+            accept_px: 10.0,
+            opportunistic_graft: 5.0,
+        }
+    }
+}
+
+impl ScoreThresholds {
+    /// Check thresholds for sign and order, `graylist <= publish <=
+    /// gossip <= 0`.
+    pub fn validate(&self) -> Result<()> {
+        let check = |ok: bool, what: &str| match ok {
+            true => Ok(()),
+            false => err_at!(Invalid, msg: "score threshold {}", what),
+        };
+        check(self.gossip <= 0.0, "gossip")?;
+        check(self.publish <= self.gossip, "publish")?;
+        check(self.graylist <= self.publish, "graylist")?;
+        check(self.accept_px >= 0.0, "accept_px")?;
+        check(self.opportunistic_graft >= 0.0, "opportunistic_graft")?;
+        Ok(())
+    }
+}
+
+/// Peer scoring as configured in `Pubsub.Score`, durations are text like
+/// "500ms", "1s", "10m" or "1h", and whitelist entries are IP addresses.
+#[derive(Clone, Debug)]
+pub struct PubsubScore {
+    /// Per topic parameters, topics not listed here are not scored.
+    pub topics: HashMap<String, TopicScoreParams>,
+    pub topic_score_cap: f64,
+    pub app_specific_weight: f64,
+    pub ip_colocation_factor_weight: f64,
+    pub ip_colocation_factor_threshold: usize,
+    pub ip_colocation_factor_whitelist: Vec<String>,
+    pub behaviour_penalty_weight: f64,
+    pub behaviour_penalty_threshold: f64,
+    pub behaviour_penalty_decay: f64,
+    pub decay_interval: String,
+    pub decay_to_zero: f64,
+    pub retain_score: String,
+
+    pub gossip_threshold: f64,
+    pub publish_threshold: f64,
+    pub graylist_threshold: f64,
+    pub accept_px_threshold: f64,
+    pub opportunistic_graft_threshold: f64,
+}
+
+impl Default for PubsubScore {
+    fn default() -> PubsubScore {
+        let (p, t) = (ScoreParams::default(), ScoreThresholds::default());
+        PubsubScore {
+            topics: p.topics,
+            topic_score_cap: p.topic_score_cap,
+            app_specific_weight: p.app_specific_weight,
+            ip_colocation_factor_weight: p.ip_colocation_factor_weight,
+            ip_colocation_factor_threshold: p.ip_colocation_factor_threshold,
+            ip_colocation_factor_whitelist: vec![],
+            behaviour_penalty_weight: p.behaviour_penalty_weight,
+            behaviour_penalty_threshold: p.behaviour_penalty_threshold,
+            behaviour_penalty_decay: p.behaviour_penalty_decay,
+            decay_interval: "1s".to_string(),
+            decay_to_zero: p.decay_to_zero,
+            retain_score: "1h".to_string(),
+
+            gossip_threshold: t.gossip,
+            publish_threshold: t.publish,
+            graylist_threshold: t.graylist,
+            accept_px_threshold: t.accept_px,
+            opportunistic_graft_threshold: t.opportunistic_graft,
+        }
+    }
+}
+
+impl PubsubScore {
+    /// Convert into score parameters and thresholds, fail if a duration
+    /// or whitelisted address can't be parsed, or if the result does not
+    /// validate, refer [ScoreParams::validate] and
+    /// [ScoreThresholds::validate].
+    pub fn to_score(&self) -> Result<(ScoreParams, ScoreThresholds)> {
+        let mut whitelist = vec![];
+        for (i, ip) in self.ip_colocation_factor_whitelist.iter().enumerate() {
+            let field = "Pubsub.Score.IPColocationFactorWhitelist";
+            match ip.parse() {
+                Ok(ip) => whitelist.push(ip),
+                Err(err) => err_at!(BadInput, msg: "{}[{}] {:?}: {}", field, i, ip, err)?,
+            }
+        }
+
+        let params = ScoreParams {
+            topics: self.topics.clone(),
+            topic_score_cap: self.topic_score_cap,
+            app_specific_weight: self.app_specific_weight,
+            ip_colocation_factor_weight: self.ip_colocation_factor_weight,
+            ip_colocation_factor_threshold: self.ip_colocation_factor_threshold,
+            ip_colocation_factor_whitelist: whitelist,
+            behaviour_penalty_weight: self.behaviour_penalty_weight,
+            behaviour_penalty_threshold: self.behaviour_penalty_threshold,
+            behaviour_penalty_decay: self.behaviour_penalty_decay,
+            decay_interval: parse_duration("Pubsub.Score.DecayInterval", &self.decay_interval)?,
+            decay_to_zero: self.decay_to_zero,
+            retain_score: parse_duration("Pubsub.Score.RetainScore", &self.retain_score)?,
+        };
+        params.validate()?;
+
+        let thresholds = ScoreThresholds {
+            gossip: self.gossip_threshold,
+            publish: self.publish_threshold,
+            graylist: self.graylist_threshold,
+            accept_px: self.accept_px_threshold,
+            opportunistic_graft: self.opportunistic_graft_threshold,
+        };
+        thresholds.validate()?;
+
+        Ok((params, thresholds))
+    }
+}
+
+// parse duration text, an integer followed by one of ms, s, m or h.
+fn parse_duration(field: &str, text: &str) -> Result<Duration> {
+    let n = text
+        .find(|ch: char| !ch.is_ascii_digit())
+        .unwrap_or(text.len());
+    let val: u64 = match text[..n].parse() {
+        Ok(val) => val,
+        Err(err) => err_at!(BadInput, msg: "{} {:?}: {}", field, text, err)?,
+    };
+    match &text[n..] {
+        "ms" => Ok(Duration::from_millis(val)),
+        "s" => Ok(Duration::from_secs(val)),
+        "m" => Ok(Duration::from_secs(val.saturating_mul(60))),
+        "h" => Ok(Duration::from_secs(val.saturating_mul(3600))),
+        unit => err_at!(BadInput, msg: "{} {:?}: bad unit {:?}", field, text, unit),
+    }
+}
+
+#[derive(Clone, Default)]
+struct TopicStats {
+    grafted_at: Option<Instant>,
+    first_message_deliveries: f64,
+    mesh_message_deliveries: f64,
+    mesh_failure_penalty: f64,
+    invalid_message_deliveries: f64,
+}
+
+#[derive(Clone, Default)]
+struct PeerStats {
+    connected: bool,
+    disconnected_at: Option<Instant>,
+    ips: Vec<IpAddr>,
+    topics: HashMap<String, TopicStats>,
+    behaviour_penalty: f64,
+    app_score: f64,
+}
+
+/// Peer scores, refer module documentation.
+pub struct PeerScore {
+    params: ScoreParams,
+    thresholds: ScoreThresholds,
+    peers: HashMap<PeerId, PeerStats>,
+    // number of connected peers on each IP.
+    ips: HashMap<IpAddr, usize>,
+    last_decay: Option<Instant>,
+}
+
+impl PeerScore {
+    pub fn new(params: ScoreParams, thresholds: ScoreThresholds) -> Result<PeerScore> {
+        params.validate()?;
+        thresholds.validate()?;
+        let val = PeerScore {
+            params,
+            thresholds,
+            peers: HashMap::new(),
+            ips: HashMap::new(),
+            last_decay: None,
+        };
+        Ok(val)
+    }
+
+    pub fn to_thresholds(&self) -> ScoreThresholds {
+        self.thresholds.clone()
+    }
+
+    /// Peer connected from `ip`, stats retained from an earlier
+    /// connection are restored.
+    pub fn add_peer(&mut self, peer: PeerId, ip: IpAddr) {
+        let stats = self.peers.entry(peer).or_insert_with(PeerStats::default);
+        stats.connected = true;
+        stats.disconnected_at = None;
+        if !stats.ips.contains(&ip) {
+            stats.ips.push(ip);
+            *self.ips.entry(ip).or_insert(0) += 1;
+        }
+    }
+
+    /// Peer disconnected. Stats of a peer with a negative score are
+    /// retained for `retain_score`, so that it can't reset its score by
+    /// reconnecting.
+    pub fn remove_peer(&mut self, peer: &PeerId, now: Instant) {
+        let score = self.to_score(peer, now);
+        let stats = match self.peers.get_mut(peer) {
+            Some(stats) => stats,
+            None => return,
+        };
+        for ip in stats.ips.drain(..) {
+            if let Some(n) = self.ips.get_mut(&ip) {
+                *n = n.saturating_sub(1);
+            }
+        }
+
+        if score < 0.0 {
+            stats.connected = false;
+            stats.disconnected_at = Some(now);
+            for (_, ts) in stats.topics.iter_mut() {
+                ts.grafted_at = None;
+                // decaying counters shall be zero, refer spec.
+                ts.first_message_deliveries = 0.0;
+                ts.mesh_message_deliveries = 0.0;
+            }
+        } else {
+            self.peers.remove(peer);
+        }
+    }
+
+    /// Peer joined our mesh for `topic`.
+    pub fn graft(&mut self, peer: &PeerId, topic: &str, now: Instant) {
+        if let Some(ts) = self.to_topic_stats(peer, topic) {
+            ts.grafted_at = Some(now);
+            ts.mesh_message_deliveries = 0.0;
+        }
+    }
+
+    /// Peer left our mesh for `topic`, a delivery deficit at this point is
+    /// carried over as mesh failure penalty.
+    pub fn prune(&mut self, peer: &PeerId, topic: &str, now: Instant) {
+        let p = match self.params.topics.get(topic) {
+            Some(p) => p.clone(),
+            None => return,
+        };
+        if let Some(ts) = self.to_topic_stats(peer, topic) {
+            if let Some(deficit) = to_deficit(ts, &p, now) {
+                ts.mesh_failure_penalty += deficit * deficit;
+            }
+            ts.grafted_at = None;
+        }
+    }
+
+    /// Peer delivered a valid message on `topic`, `first` if it was the
+    /// first to deliver the message.
+    pub fn deliver_message(&mut self, peer: &PeerId, topic: &str, first: bool) {
+        let p = match self.params.topics.get(topic) {
+            Some(p) => p.clone(),
+            None => return,
+        };
+        if let Some(ts) = self.to_topic_stats(peer, topic) {
+            if first {
+                ts.first_message_deliveries =
+                    (ts.first_message_deliveries + 1.0).min(p.first_message_deliveries_cap);
+            }
+            if ts.grafted_at.is_some() {
+                ts.mesh_message_deliveries =
+                    (ts.mesh_message_deliveries + 1.0).min(p.mesh_message_deliveries_cap);
+            }
+        }
+    }
+
+    /// Message on `topic` from peer was validated as `res`, rejected
+    /// messages count as invalid deliveries.
+    pub fn validated(&mut self, peer: &PeerId, topic: &str, res: Validation) {
+        if !res.is_penalized() || !self.params.topics.contains_key(topic) {
+            return;
+        }
+        if let Some(ts) = self.to_topic_stats(peer, topic) {
+            ts.invalid_message_deliveries += 1.0;
+        }
+    }
+
+    /// Add behaviour penalty, P7, for misbehaving peer.
+    pub fn add_penalty(&mut self, peer: &PeerId, count: usize) {
+        if let Some(stats) = self.peers.get_mut(peer) {
+            stats.behaviour_penalty += count as f64;
+        }
+    }
+
+    /// Set application specific score, P5, for peer.
+    pub fn set_app_score(&mut self, peer: &PeerId, score: f64) {
+        if let Some(stats) = self.peers.get_mut(peer) {
+            stats.app_score = score;
+        }
+    }
+
+    /// Decay counters if `decay_interval` has elapsed since the last
+    /// decay, and forget disconnected peers past `retain_score`.
+    pub fn refresh(&mut self, now: Instant) {
+        match self.last_decay {
+            Some(at) if now.saturating_duration_since(at) < self.params.decay_interval => return,
+            _ => self.last_decay = Some(now),
+        }
+
+        let (params, retain) = (&self.params, self.params.retain_score);
+        let decay_to_zero = params.decay_to_zero;
+        let decay = |val: &mut f64, factor: f64| {
+            *val *= factor;
+            if *val < decay_to_zero {
+                *val = 0.0;
+            }
+        };
+
+        self.peers.retain(|_, stats| match stats.disconnected_at {
+            Some(at) => now.saturating_duration_since(at) < retain,
+            None => true,
+        });
+        for (_, stats) in self.peers.iter_mut() {
+            for (topic, ts) in stats.topics.iter_mut() {
+                let p = match params.topics.get(topic) {
+                    Some(p) => p,
+                    None => continue,
+                };
+                decay(
+                    &mut ts.first_message_deliveries,
+                    p.first_message_deliveries_decay,
+                );
+                decay(
+                    &mut ts.mesh_message_deliveries,
+                    p.mesh_message_deliveries_decay,
+                );
+                decay(&mut ts.mesh_failure_penalty, p.mesh_failure_penalty_decay);
+                decay(
+                    &mut ts.invalid_message_deliveries,
+                    p.invalid_message_deliveries_decay,
+                );
+            }
+            decay(&mut stats.behaviour_penalty, params.behaviour_penalty_decay);
+        }
+    }
+
+    /// Return the score of peer, zero for unknown peers.
+    pub fn to_score(&self, peer: &PeerId, now: Instant) -> f64 {
+        let stats = match self.peers.get(peer) {
+            Some(stats) => stats,
+            None => return 0.0,
+        };
+
+        let mut topic_score = 0.0;
+        for (topic, ts) in stats.topics.iter() {
+            let p = match self.params.topics.get(topic) {
+                Some(p) => p,
+                None => continue,
+            };
+            let mut score = 0.0;
+            if let Some(at) = ts.grafted_at {
+                let quanta = now.saturating_duration_since(at).as_secs_f64()
+                    / p.time_in_mesh_quantum.as_secs_f64();
+                score += p.time_in_mesh_weight * quanta.min(p.time_in_mesh_cap);
+            }
+            score += p.first_message_deliveries_weight * ts.first_message_deliveries;
+            if let Some(deficit) = to_deficit(ts, p, now) {
+                score += p.mesh_message_deliveries_weight * deficit * deficit;
+            }
+            score += p.mesh_failure_penalty_weight * ts.mesh_failure_penalty;
+            let invalid = ts.invalid_message_deliveries;
+            score += p.invalid_message_deliveries_weight * invalid * invalid;
+
+            topic_score += p.topic_weight * score;
+        }
+        if self.params.topic_score_cap > 0.0 {
+            topic_score = topic_score.min(self.params.topic_score_cap);
+        }
+
+        let mut score = topic_score;
+        score += self.params.app_specific_weight * stats.app_score;
+
+        let threshold = self.params.ip_colocation_factor_threshold;
+        for ip in stats.ips.iter() {
+            if self.params.ip_colocation_factor_whitelist.contains(ip) {
+                continue;
+            }
+            let n = self.ips.get(ip).cloned().unwrap_or(0);
+            if n > threshold {
+                let surplus = (n - threshold) as f64;
+                score += self.params.ip_colocation_factor_weight * surplus * surplus;
+            }
+        }
+
+        let threshold = self.params.behaviour_penalty_threshold;
+        if stats.behaviour_penalty > threshold {
+            let excess = stats.behaviour_penalty - threshold;
+            score += self.params.behaviour_penalty_weight * excess * excess;
+        }
+
+        score
+    }
+
+    /// Return whether all RPCs from peer shall be ignored.
+    pub fn is_graylisted(&self, peer: &PeerId, now: Instant) -> bool {
+        self.to_score(peer, now) < self.thresholds.graylist
+    }
+
+    /// Return whether gossip can be exchanged with peer.
+    pub fn accepts_gossip(&self, peer: &PeerId, now: Instant) -> bool {
+        self.to_score(peer, now) >= self.thresholds.gossip
+    }
+
+    /// Return whether self published messages can be sent to peer.
+    pub fn accepts_publish(&self, peer: &PeerId, now: Instant) -> bool {
+        self.to_score(peer, now) >= self.thresholds.publish
+    }
+
+    /// Return whether peer exchange on PRUNE from peer is accepted.
+    pub fn accepts_px(&self, peer: &PeerId, now: Instant) -> bool {
+        self.to_score(peer, now) >= self.thresholds.accept_px
+    }
+
+    fn to_topic_stats(&mut self, peer: &PeerId, topic: &str) -> Option<&mut TopicStats> {
+        let stats = self.peers.get_mut(peer)?;
+        Some(
+            stats
+                .topics
+                .entry(topic.to_string())
+                .or_insert_with(TopicStats::default),
+        )
+    }
+}
+
+// delivery deficit of a peer in mesh, once P3 is active.
+fn to_deficit(ts: &TopicStats, p: &TopicScoreParams, now: Instant) -> Option<f64> {
+    let at = ts.grafted_at?;
+    let active = now.saturating_duration_since(at) >= p.mesh_message_deliveries_activation;
+    let threshold = p.mesh_message_deliveries_threshold;
+    match active && ts.mesh_message_deliveries < threshold {
+        true => Some(threshold - ts.mesh_message_deliveries),
+        false => None,
+    }
+}
+
+#[cfg(test)]
+#[path = "score_test.rs"]
+mod score_test;
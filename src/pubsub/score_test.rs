@@ -0,0 +1,220 @@
+use std::net::Ipv4Addr;
+
+use super::*;
+
+fn make_params() -> ScoreParams {
+    let mut params = ScoreParams::default();
+    params
+        .topics
+        .insert("blocks".to_string(), TopicScoreParams::default());
+    params
+}
+
+fn make_ip(n: u8) -> IpAddr {
+    IpAddr::V4(Ipv4Addr::new(10, 0, 0, n))
+}
+
+#[test]
+fn test_validate_params() {
+    assert!(make_params().validate().is_ok());
+
+    let mut params = make_params();
+    params.behaviour_penalty_weight = 1.0;
+    assert!(params.validate().is_err());
+
+    let mut params = make_params();
+    let p = params.topics.get_mut("blocks").unwrap();
+    p.invalid_message_deliveries_decay = 1.0;
+    assert!(params.validate().is_err());
+    assert!(PeerScore::new(params, ScoreThresholds::default()).is_err());
+}
+
+#[test]
+fn test_time_in_mesh() {
+    let mut ps = PeerScore::new(make_params(), ScoreThresholds::default()).unwrap();
+    let peer = PeerId::generate().unwrap();
+    let now = Instant::now();
+
+    ps.add_peer(peer.clone(), make_ip(1));
+    assert_eq!(ps.to_score(&peer, now), 0.0);
+
+    ps.graft(&peer, "blocks", now);
+    // 4 seconds in mesh, before P3 activation, times topic weight 0.5.
+    let score = ps.to_score(&peer, now + Duration::from_secs(4));
+    assert!((score - 2.0).abs() < 1e-9, "{}", score);
+
+    // deficit kicks in after activation, 5 * 0.5 - 20^2 * 0.5.
+    let score = ps.to_score(&peer, now + Duration::from_secs(5));
+    assert!((score + 197.5).abs() < 1e-9, "{}", score);
+    assert!(ps.is_graylisted(&peer, now + Duration::from_secs(5)));
+
+    for _ in 0..20 {
+        ps.deliver_message(&peer, "blocks", false);
+    }
+    let score = ps.to_score(&peer, now + Duration::from_secs(5));
+    assert!((score - 2.5).abs() < 1e-9, "{}", score);
+
+    // unscored topics don't count.
+    ps.graft(&peer, "other", now);
+    let score = ps.to_score(&peer, now + Duration::from_secs(5));
+    assert!((score - 2.5).abs() < 1e-9, "{}", score);
+}
+
+#[test]
+fn test_invalid_messages() {
+    let mut ps = PeerScore::new(make_params(), ScoreThresholds::default()).unwrap();
+    let peer = PeerId::generate().unwrap();
+    let now = Instant::now();
+
+    ps.add_peer(peer.clone(), make_ip(1));
+    ps.validated(&peer, "blocks", Validation::Ignore);
+    assert_eq!(ps.to_score(&peer, now), 0.0);
+    assert!(ps.accepts_gossip(&peer, now));
+
+    for _ in 0..5 {
+        ps.validated(&peer, "blocks", Validation::Reject);
+    }
+    // 5^2 * -1 * 0.5
+    assert!((ps.to_score(&peer, now) + 12.5).abs() < 1e-9);
+    assert!(!ps.accepts_gossip(&peer, now));
+    assert!(ps.accepts_publish(&peer, now));
+
+    // decay, 5 * 0.3 = 1.5
+    ps.refresh(now);
+    assert!((ps.to_score(&peer, now) + 1.125).abs() < 1e-9);
+    // within decay interval, no change.
+    ps.refresh(now + Duration::from_millis(500));
+    assert!((ps.to_score(&peer, now) + 1.125).abs() < 1e-9);
+    // 1.5 -> 0.45 -> 0.135 -> below decay_to_zero.
+    ps.refresh(now + Duration::from_secs(1));
+    ps.refresh(now + Duration::from_secs(2));
+    assert!(ps.to_score(&peer, now) < 0.0);
+    ps.refresh(now + Duration::from_secs(3));
+    assert_eq!(ps.to_score(&peer, now), 0.0);
+}
+
+#[test]
+fn test_mesh_failure_penalty() {
+    let mut ps = PeerScore::new(make_params(), ScoreThresholds::default()).unwrap();
+    let peer = PeerId::generate().unwrap();
+    let now = Instant::now();
+
+    ps.add_peer(peer.clone(), make_ip(1));
+    ps.graft(&peer, "blocks", now);
+    for _ in 0..10 {
+        ps.deliver_message(&peer, "blocks", true);
+    }
+    let later = now + Duration::from_secs(10);
+    ps.prune(&peer, "blocks", later);
+    // first deliveries 10, failure penalty 10^2, times topic weight.
+    let score = ps.to_score(&peer, later);
+    assert!((score + 45.0).abs() < 1e-9, "{}", score);
+}
+
+#[test]
+fn test_ip_colocation() {
+    let mut params = make_params();
+    params.ip_colocation_factor_threshold = 2;
+    params.ip_colocation_factor_whitelist = vec![make_ip(2)];
+    let mut ps = PeerScore::new(params, ScoreThresholds::default()).unwrap();
+    let now = Instant::now();
+
+    let peers: Vec<PeerId> = (0..4).map(|_| PeerId::generate().unwrap()).collect();
+    for peer in peers.iter() {
+        ps.add_peer(peer.clone(), make_ip(1));
+    }
+    // 2 surplus peers, 2^2 * -5
+    for peer in peers.iter() {
+        assert!((ps.to_score(peer, now) + 20.0).abs() < 1e-9);
+    }
+
+    let others: Vec<PeerId> = (0..4).map(|_| PeerId::generate().unwrap()).collect();
+    for peer in others.iter() {
+        ps.add_peer(peer.clone(), make_ip(2));
+    }
+    for peer in others.iter() {
+        assert_eq!(ps.to_score(peer, now), 0.0);
+    }
+
+    ps.remove_peer(&peers[0], now);
+    assert!((ps.to_score(&peers[1], now) + 5.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_retain_score() {
+    let mut ps = PeerScore::new(make_params(), ScoreThresholds::default()).unwrap();
+    let (good, bad) = (PeerId::generate().unwrap(), PeerId::generate().unwrap());
+    let now = Instant::now();
+
+    ps.add_peer(good.clone(), make_ip(1));
+    ps.add_peer(bad.clone(), make_ip(2));
+    ps.set_app_score(&good, 1.0);
+    ps.add_penalty(&bad, 3);
+    assert!((ps.to_score(&good, now) - 10.0).abs() < 1e-9);
+    assert!((ps.to_score(&bad, now) + 90.0).abs() < 1e-9);
+    assert!(ps.is_graylisted(&bad, now));
+
+    ps.remove_peer(&good, now);
+    ps.remove_peer(&bad, now);
+    assert_eq!(ps.to_score(&good, now), 0.0);
+
+    // reconnecting doesn't reset a negative score.
+    ps.add_peer(bad.clone(), make_ip(2));
+    assert!(ps.to_score(&bad, now) < 0.0);
+    ps.remove_peer(&bad, now);
+
+    ps.refresh(now + Duration::from_secs(3601));
+    ps.add_peer(bad.clone(), make_ip(2));
+    assert_eq!(ps.to_score(&bad, now), 0.0);
+}
+
+#[test]
+fn test_pubsub_score() {
+    let mut config = PubsubScore::default();
+    config
+        .topics
+        .insert("blocks".to_string(), TopicScoreParams::default());
+    config.ip_colocation_factor_whitelist = vec!["10.0.0.1".to_string()];
+    config.behaviour_penalty_threshold = 2.0;
+    config.decay_interval = "500ms".to_string();
+    config.decay_to_zero = 0.01;
+    config.retain_score = "10m".to_string();
+    config.graylist_threshold = -100.0;
+
+    let (params, thresholds) = config.to_score().unwrap();
+    assert!(params.topics.contains_key("blocks"));
+    assert_eq!(params.ip_colocation_factor_whitelist, vec![make_ip(1)]);
+    assert_eq!(params.behaviour_penalty_threshold, 2.0);
+    assert_eq!(params.decay_interval, Duration::from_millis(500));
+    assert_eq!(params.decay_to_zero, 0.01);
+    assert_eq!(params.retain_score, Duration::from_secs(600));
+    assert_eq!(thresholds.graylist, -100.0);
+    assert_eq!(thresholds.gossip, ScoreThresholds::default().gossip);
+
+    // penalties below the threshold are free, 3 - 2 = 1 in excess.
+    let mut ps = PeerScore::new(params, thresholds).unwrap();
+    let peer = PeerId::generate().unwrap();
+    ps.add_peer(peer.clone(), make_ip(2));
+    ps.add_penalty(&peer, 2);
+    assert_eq!(ps.to_score(&peer, Instant::now()), 0.0);
+    ps.add_penalty(&peer, 1);
+    assert!((ps.to_score(&peer, Instant::now()) + 10.0).abs() < 1e-9);
+
+    let bad = |f: fn(&mut PubsubScore)| {
+        let mut config = PubsubScore::default();
+        f(&mut config);
+        config.to_score().unwrap_err().to_string()
+    };
+    let msg = bad(|c| c.ip_colocation_factor_whitelist = vec!["x".to_string()]);
+    assert!(msg.contains("IPColocationFactorWhitelist[0]"), "{}", msg);
+    let msg = bad(|c| c.decay_interval = "1d".to_string());
+    assert!(msg.contains("DecayInterval"), "{}", msg);
+    let msg = bad(|c| c.retain_score = "h".to_string());
+    assert!(msg.contains("RetainScore"), "{}", msg);
+    let msg = bad(|c| c.decay_to_zero = 1.5);
+    assert!(msg.contains("decay_to_zero"), "{}", msg);
+    let msg = bad(|c| c.behaviour_penalty_threshold = -1.0);
+    assert!(msg.contains("behaviour_penalty_threshold"), "{}", msg);
+    let msg = bad(|c| c.graylist_threshold = 0.0);
+    assert!(msg.contains("graylist"), "{}", msg);
+}
@@ -0,0 +1,176 @@
+//! Signed records, refer [signed envelope spec] for details.
+//!
+//! [signed envelope spec]: https://github.com/libp2p/specs/blob/master/RFC/0002-signed-envelopes.md
+
+use std::convert::{TryFrom, TryInto};
+
+use crate::{
+    identity::{Keypair, PublicKey},
+    multicodec::Multicodec,
+    pb::envelope_proto,
+    Error, Result,
+};
+
+/// Envelope contains an arbitrary payload, signed by a libp2p peer.
+///
+/// Signature is computed over the domain string, payload type and
+/// payload, each prefixed with its unsigned-varint length. Domain string
+/// is not part of the envelope, sender and receiver shall agree upon it
+/// based on the payload type, e.g. [PeerRecord] use `libp2p-peer-record`.
+///
+/// [PeerRecord]: crate::peer_record::PeerRecord
+#[derive(Clone, PartialEq, Eq)]
+pub struct Envelope {
+    public_key: PublicKey,
+    payload_type: Vec<u8>,
+    payload: Vec<u8>,
+    signature: Vec<u8>,
+}
+
+impl TryFrom<envelope_proto::Envelope> for Envelope {
+    type Error = Error;
+
+    fn try_from(val: envelope_proto::Envelope) -> Result<Self> {
+        use prost::Message;
+
+        let public_key = match val.public_key {
+            Some(public_key) => {
+                let mut buf = Vec::with_capacity(public_key.encoded_len());
+                err_at!(EncodeError, public_key.encode(&mut buf))?;
+                PublicKey::from_protobuf_encoding(&buf)?
+            }
+            None => err_at!(DecodeError, msg: "envelope without public key")?,
+        };
+
+        let val = Envelope {
+            public_key,
+            payload_type: val.payload_type,
+            payload: val.payload,
+            signature: val.signature,
+        };
+
+        Ok(val)
+    }
+}
+
+impl TryFrom<Envelope> for envelope_proto::Envelope {
+    type Error = Error;
+
+    fn try_from(val: Envelope) -> Result<Self> {
+        use crate::pb::key_pair_proto;
+        use prost::Message;
+
+        let public_key = {
+            let data = val.public_key.into_protobuf_encoding()?;
+            err_at!(
+                DecodeError,
+                key_pair_proto::PublicKey::decode(data.as_slice())
+            )?
+        };
+
+        let env = envelope_proto::Envelope {
+            public_key: Some(public_key),
+            payload_type: val.payload_type,
+            payload: val.payload,
+            signature: val.signature,
+        };
+
+        Ok(env)
+    }
+}
+
+impl Envelope {
+    /// Sign `payload` of `payload_type` using `key`, within `domain`, and
+    /// return the sealed envelope.
+    pub fn seal(key: &Keypair, domain: &str, typ: Multicodec, payload: Vec<u8>) -> Result<Self> {
+        let payload_type = typ.encode()?;
+        let signature = {
+            let data = make_unsigned(domain, &payload_type, &payload);
+            key.sign(&data)?
+        };
+
+        let val = Envelope {
+            public_key: key.to_public_key(),
+            payload_type,
+            payload,
+            signature,
+        };
+
+        Ok(val)
+    }
+
+    /// Decode a binary envelope and verify its signature within `domain`.
+    /// Return error if signature does not match.
+    pub fn open(data: &[u8], domain: &str) -> Result<Self> {
+        let val = Self::decode_protobuf(data)?;
+        match val.verify(domain) {
+            true => Ok(val),
+            false => err_at!(SigningError, msg: "invalid envelope signature for {}", domain),
+        }
+    }
+
+    /// Verify envelope's signature within `domain`.
+    pub fn verify(&self, domain: &str) -> bool {
+        let data = make_unsigned(domain, &self.payload_type, &self.payload);
+        self.public_key.verify(&data, &self.signature)
+    }
+
+    /// Decode a binary envelope without verifying its signature, use
+    /// [Envelope::open] unless the signature is verified later.
+    pub fn decode_protobuf(data: &[u8]) -> Result<Self> {
+        use prost::Message;
+
+        let env = {
+            let res = envelope_proto::Envelope::decode(data);
+            err_at!(DecodeError, res)?
+        };
+        Ok(env.try_into()?)
+    }
+
+    pub fn encode_protobuf(self) -> Result<Vec<u8>> {
+        use prost::Message;
+
+        let env: envelope_proto::Envelope = self.try_into()?;
+
+        let mut buf = Vec::with_capacity(env.encoded_len());
+        err_at!(EncodeError, env.encode(&mut buf))?;
+        Ok(buf)
+    }
+
+    /// Return the public key of the signer.
+    pub fn to_public_key(&self) -> PublicKey {
+        self.public_key.clone()
+    }
+
+    /// Return the payload type as multicodec.
+    pub fn to_payload_type(&self) -> Result<Multicodec> {
+        let (codec, _) = Multicodec::decode(&self.payload_type)?;
+        Ok(codec)
+    }
+
+    /// Return the enclosed payload.
+    pub fn as_payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// Return the signature over domain, payload-type and payload.
+    pub fn as_signature(&self) -> &[u8] {
+        &self.signature
+    }
+}
+
+fn make_unsigned(domain: &str, payload_type: &[u8], payload: &[u8]) -> Vec<u8> {
+    use unsigned_varint::encode as uve;
+
+    let mut data = vec![];
+    for field in [domain.as_bytes(), payload_type, payload].iter() {
+        let mut buf = [0_u8; 10];
+        data.extend_from_slice(uve::usize(field.len(), &mut buf));
+        data.extend_from_slice(field);
+    }
+    data
+}
+
+#[cfg(test)]
+#[path = "record_test.rs"]
+mod record_test;
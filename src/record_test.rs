@@ -0,0 +1,26 @@
+use super::*;
+
+use crate::multicodec;
+
+#[test]
+fn test_envelope_seal_open() {
+    let key = Keypair::generate_ed25519().unwrap();
+    let typ: Multicodec = multicodec::LIBP2P_PEER_RECORD.into();
+    let payload = b"hello world".to_vec();
+
+    let env = Envelope::seal(&key, "test-domain", typ, payload.clone()).unwrap();
+    assert!(env.verify("test-domain"));
+    assert!(!env.verify("other-domain"));
+
+    let data = env.clone().encode_protobuf().unwrap();
+    let env = Envelope::open(&data, "test-domain").unwrap();
+    assert_eq!(env.as_payload(), payload.as_slice());
+    assert_eq!(env.to_payload_type().unwrap(), typ);
+    assert_eq!(env.to_public_key(), key.to_public_key());
+
+    assert!(Envelope::open(&data, "other-domain").is_err());
+
+    let mut env = Envelope::decode_protobuf(&data).unwrap();
+    env.payload = b"hello world!".to_vec();
+    assert!(!env.verify("test-domain"));
+}
@@ -0,0 +1,187 @@
+//! Module implement [Serialize] and [Deserialize] for types defined by
+//! this package.
+//!
+//! Enabled with `serde` feature. Human readable formats, like JSON and
+//! TOML, get the text representation of a value, while binary formats,
+//! like CBOR and bincode, get its canonical binary representation.
+//!
+//! * [Multihash], base58btc text or binary multihash.
+//! * [Multicodec], code-point name or the code as integer.
+//! * [Cid], multibase text or binary CID.
+//! * [Multiaddr], `/ip4/..` text or binary multiaddr.
+
+use serde::{
+    de::{self, Deserializer, Visitor},
+    ser::{self, Serializer},
+    Deserialize, Serialize,
+};
+
+use std::{convert::TryFrom, fmt, result};
+
+use crate::{cid::Cid, multiaddr::Multiaddr, multicodec::Multicodec, multihash::Multihash};
+
+impl Serialize for Multihash {
+    fn serialize<S: Serializer>(&self, s: S) -> result::Result<S::Ok, S::Error> {
+        let bytes = self.encode().map_err(ser::Error::custom)?;
+        match s.is_human_readable() {
+            true => s.serialize_str(&bs58::encode(bytes).into_string()),
+            false => s.serialize_bytes(&bytes),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Multihash {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> result::Result<Self, D::Error> {
+        let bytes = match d.is_human_readable() {
+            true => {
+                let text = d.deserialize_str(TextVisitor)?;
+                bs58::decode(text.as_bytes())
+                    .into_vec()
+                    .map_err(de::Error::custom)?
+            }
+            false => d.deserialize_bytes(BytesVisitor)?,
+        };
+        match Multihash::decode(&bytes).map_err(de::Error::custom)? {
+            (mh, []) => Ok(mh),
+            (_, rem) => Err(de::Error::custom(trailing("multihash", rem))),
+        }
+    }
+}
+
+impl Serialize for Multicodec {
+    fn serialize<S: Serializer>(&self, s: S) -> result::Result<S::Ok, S::Error> {
+        match (s.is_human_readable(), self.to_codepoint()) {
+            (true, Some(cp)) => s.serialize_str(&cp.name),
+            (true, None) => s.serialize_str(&format!("0x{:x}", self.to_code())),
+            (false, _) => {
+                let code = u64::try_from(self.to_code()).map_err(ser::Error::custom)?;
+                s.serialize_u64(code)
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Multicodec {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> result::Result<Self, D::Error> {
+        match d.is_human_readable() {
+            true => {
+                let text = d.deserialize_str(TextVisitor)?;
+                match text.strip_prefix("0x") {
+                    Some(hex) => {
+                        let code = u128::from_str_radix(hex, 16).map_err(de::Error::custom)?;
+                        Ok(code.into())
+                    }
+                    None => Multicodec::try_from(text.as_str()).map_err(de::Error::custom),
+                }
+            }
+            false => Ok(u128::from(u64::deserialize(d)?).into()),
+        }
+    }
+}
+
+impl Serialize for Cid {
+    fn serialize<S: Serializer>(&self, s: S) -> result::Result<S::Ok, S::Error> {
+        match s.is_human_readable() {
+            true => s.serialize_str(&self.to_text(None).map_err(ser::Error::custom)?),
+            false => s.serialize_bytes(&self.encode().map_err(ser::Error::custom)?),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Cid {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> result::Result<Self, D::Error> {
+        match d.is_human_readable() {
+            true => {
+                let text = d.deserialize_str(TextVisitor)?;
+                Cid::from_text(&text).map_err(de::Error::custom)
+            }
+            false => {
+                let bytes = d.deserialize_bytes(BytesVisitor)?;
+                match Cid::decode(&bytes).map_err(de::Error::custom)? {
+                    (cid, []) => Ok(cid),
+                    (_, rem) => Err(de::Error::custom(trailing("cid", rem))),
+                }
+            }
+        }
+    }
+}
+
+impl Serialize for Multiaddr {
+    fn serialize<S: Serializer>(&self, s: S) -> result::Result<S::Ok, S::Error> {
+        match s.is_human_readable() {
+            true => s.serialize_str(&self.to_text().map_err(ser::Error::custom)?),
+            false => s.serialize_bytes(&self.encode().map_err(ser::Error::custom)?),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Multiaddr {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> result::Result<Self, D::Error> {
+        match d.is_human_readable() {
+            true => {
+                let text = d.deserialize_str(TextVisitor)?;
+                Multiaddr::parse_text(&text).map_err(de::Error::custom)
+            }
+            false => {
+                let bytes = d.deserialize_bytes(BytesVisitor)?;
+                match Multiaddr::decode(&bytes).map_err(de::Error::custom)? {
+                    (ma, []) => Ok(ma),
+                    (_, rem) => Err(de::Error::custom(trailing("multiaddr", rem))),
+                }
+            }
+        }
+    }
+}
+
+fn trailing(what: &str, rem: &[u8]) -> String {
+    format!("{} has {} trailing bytes", what, rem.len())
+}
+
+struct TextVisitor;
+
+impl<'de> Visitor<'de> for TextVisitor {
+    type Value = String;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a string")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> result::Result<String, E> {
+        Ok(v.to_string())
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> result::Result<String, E> {
+        Ok(v)
+    }
+}
+
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a byte array")
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> result::Result<Vec<u8>, E> {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> result::Result<Vec<u8>, E> {
+        Ok(v)
+    }
+
+    // some formats serialize bytes as a sequence of integers.
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> result::Result<Vec<u8>, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(b) = seq.next_element()? {
+            bytes.push(b);
+        }
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+#[path = "serde_impl_test.rs"]
+mod serde_impl_test;
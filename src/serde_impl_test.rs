@@ -0,0 +1,70 @@
+use super::*;
+
+use crate::multicodec;
+
+#[test]
+fn test_serde_json() {
+    let mh = Multihash::new(multicodec::SHA2_256.into(), b"hello world").unwrap();
+    let text = serde_json::to_string(&mh).unwrap();
+    assert_eq!(text, "\"QmaozNR7DZHQK1ZcU9p7QdrshMvXqWK6gpu5rmrkPdT3L4\"");
+    assert!(serde_json::from_str::<Multihash>(&text).unwrap() == mh);
+
+    let codec: Multicodec = multicodec::DAG_CBOR.into();
+    let text = serde_json::to_string(&codec).unwrap();
+    assert_eq!(text, "\"dag-cbor\"");
+    assert_eq!(serde_json::from_str::<Multicodec>(&text).unwrap(), codec);
+
+    let codec: Multicodec = 0xdead_beef.into();
+    let text = serde_json::to_string(&codec).unwrap();
+    assert_eq!(text, "\"0xdeadbeef\"");
+    assert_eq!(serde_json::from_str::<Multicodec>(&text).unwrap(), codec);
+
+    let text = "\"bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi\"";
+    let cid: Cid = serde_json::from_str(text).unwrap();
+    assert_eq!(serde_json::to_string(&cid).unwrap(), text);
+
+    let text = "\"/ip4/127.0.0.1/tcp/4001\"";
+    let ma: Multiaddr = serde_json::from_str(text).unwrap();
+    assert_eq!(ma, Multiaddr::from_text("/ip4/127.0.0.1/tcp/4001").unwrap());
+    assert_eq!(serde_json::to_string(&ma).unwrap(), text);
+
+    let err = serde_json::from_str::<Multiaddr>("\"/ip4/127.0.0.1/tcp/port\"").unwrap_err();
+    assert!(err.to_string().contains("component 1"), "{}", err);
+    assert!(serde_json::from_str::<Multicodec>("\"dag-xyz\"").is_err());
+}
+
+#[test]
+fn test_serde_cbor() {
+    let mh = Multihash::new(multicodec::SHA2_256.into(), b"hello world").unwrap();
+    let data = serde_cbor::to_vec(&mh).unwrap();
+    // byte-string header, followed by the binary multihash.
+    assert_eq!(&data[2..], mh.encode().unwrap().as_slice());
+    assert!(serde_cbor::from_slice::<Multihash>(&data).unwrap() == mh);
+
+    let codec: Multicodec = multicodec::DAG_CBOR.into();
+    let data = serde_cbor::to_vec(&codec).unwrap();
+    assert_eq!(data, vec![0x18, 0x71]);
+    assert_eq!(serde_cbor::from_slice::<Multicodec>(&data).unwrap(), codec);
+
+    let cid = Cid::new_v1(
+        multibase::Base::Base32Lower,
+        multicodec::RAW.into(),
+        b"hello world",
+    )
+    .unwrap();
+    let data = serde_cbor::to_vec(&cid).unwrap();
+    assert_eq!(&data[2..], cid.encode().unwrap().as_slice());
+    let out: Cid = serde_cbor::from_slice(&data).unwrap();
+    assert_eq!(out.encode().unwrap(), cid.encode().unwrap());
+
+    let ma = Multiaddr::from_text("/ip6/::1/udp/4001/quic").unwrap();
+    let data = serde_cbor::to_vec(&ma).unwrap();
+    let out: Multiaddr = serde_cbor::from_slice(&data).unwrap();
+    assert_eq!(out.encode().unwrap(), ma.encode().unwrap());
+
+    // trailing bytes after the multihash are rejected.
+    let mut bytes = mh.encode().unwrap();
+    bytes.push(0);
+    let data = serde_cbor::to_vec(&serde_cbor::Value::Bytes(bytes)).unwrap();
+    assert!(serde_cbor::from_slice::<Multihash>(&data).is_err());
+}
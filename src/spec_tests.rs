@@ -0,0 +1,279 @@
+//! Module implement conformance checks against multiformats spec vectors.
+//!
+//! Spec tables and test vectors for [multibase] and [multicodec] are
+//! shipped as data files under `src/testdata/spec`, and compiled into the
+//! library when `spec-tests` feature is enabled. Forks adding new bases
+//! or code-points can call [run_spec_tests] from their own test suite to
+//! check that the tables in this crate still agree with the spec.
+//!
+//! Columns are looked up by their header, so that the data files can be
+//! replaced with upstream copies as is. Where this crate deliberately
+//! differs from the spec, the difference is listed in
+//! [MULTIBASE_DIVERGENCES] or [MULTICODEC_DIVERGENCES], every other
+//! difference fails the check, and so does a listed divergence that no
+//! longer diverges.
+//!
+//! [multibase]: https://github.com/multiformats/multibase
+//! [multicodec]: https://github.com/multiformats/multicodec
+
+use crate::{
+    multibase::{self, Multibase},
+    multicodec::Codepoint,
+    Error, Result,
+};
+
+/// multibase table, as `encoding, code, description`.
+pub const MULTIBASE_CSV: &str = include_str!("testdata/spec/multibase.csv");
+
+/// multicodec table, as `name,tag,code`.
+pub const MULTICODEC_CSV: &str = include_str!("testdata/spec/multicodec.csv");
+
+/// multibase test vectors, first line is `encoding, "<input>"` followed by
+/// one `<encoding>, "<multibase-text>"` line for each base.
+pub const MULTIBASE_VECTORS: [(&str, &str); 3] = [
+    (
+        "multibase_basic.csv",
+        include_str!("testdata/spec/multibase_basic.csv"),
+    ),
+    (
+        "multibase_leading_zero.csv",
+        include_str!("testdata/spec/multibase_leading_zero.csv"),
+    ),
+    (
+        "multibase_two_leading_zeros.csv",
+        include_str!("testdata/spec/multibase_two_leading_zeros.csv"),
+    ),
+];
+
+/// Encodings in the multibase spec not supported by this crate, as
+/// `(encoding, reason)`.
+pub const MULTIBASE_DIVERGENCES: [(&str, &str); 2] = [
+    ("base36", "not implemented by the multibase codec"),
+    ("base36upper", "not implemented by the multibase codec"),
+];
+
+/// Code-points in the multicodec table not defined by this crate, as
+/// `(name, reason)`.
+pub const MULTICODEC_DIVERGENCES: [(&str, &str); 0] = [];
+
+/// Outcome of [run_spec_tests].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SpecReport {
+    /// Number of checks that passed.
+    pub passed: usize,
+    /// Checks that were skipped because of a listed divergence, as
+    /// `<file>: <name>`.
+    pub skipped: Vec<String>,
+}
+
+/// Check multibase and multicodec tables, along with multibase encoding
+/// and decoding, against the spec vectors. Return an error describing the
+/// first mismatch.
+pub fn run_spec_tests() -> Result<SpecReport> {
+    let mut report = SpecReport::default();
+    check_multibase_table(&mut report)?;
+    for (file, text) in MULTIBASE_VECTORS.iter() {
+        check_multibase_vectors(file, text, &mut report)?;
+    }
+    check_multicodec_table(&mut report)?;
+    Ok(report)
+}
+
+fn check_multibase_table(report: &mut SpecReport) -> Result<()> {
+    let mut lines = MULTIBASE_CSV.lines().filter(|line| !line.trim().is_empty());
+    let cols = csv_columns(
+        "multibase.csv",
+        lines.next(),
+        &[&["encoding"], &["code", "character"], &["description"]],
+    )?;
+
+    let mut names = vec![];
+    for line in lines {
+        let row: Vec<&str> = line.split(',').map(|col| col.trim()).collect();
+        let (name, code, descr) = match (row.get(cols[0]), row.get(cols[1]), row.get(cols[2])) {
+            (Some(name), Some(code), Some(descr)) => (*name, *code, *descr),
+            _ => err_at!(ParseError, msg: "multibase.csv bad row {:?}", row)?,
+        };
+        let code = match code {
+            "0x00" | "NUL" => '\0',
+            code if code.chars().count() == 1 => code.chars().next().unwrap(),
+            _ => err_at!(ParseError, msg: "multibase.csv bad row {:?}", row)?,
+        };
+        names.push(name);
+
+        match multibase::TABLE.iter().find(|row| row.0 == name) {
+            Some(item) if item.1 == code && item.2 == descr => (),
+            Some(item) => err_at!(Invalid, msg: "multibase.csv {:?} != {:?}", row, item)?,
+            None => {
+                skip_divergence("multibase.csv", name, &MULTIBASE_DIVERGENCES, report)?;
+                continue;
+            }
+        }
+        report.passed += 1;
+    }
+
+    for (name, _, _) in multibase::TABLE.iter() {
+        if !names.contains(name) {
+            err_at!(Invalid, msg: "multibase.csv missing {:?}", name)?
+        }
+    }
+
+    Ok(())
+}
+
+fn check_multibase_vectors(file: &str, text: &str, report: &mut SpecReport) -> Result<()> {
+    let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+    let input = match lines.next().map(parse_vector) {
+        Some(Ok((_, input))) => unescape(&input)?,
+        Some(Err(err)) => return Err(err),
+        None => err_at!(ParseError, msg: "{} empty", file)?,
+    };
+
+    for line in lines {
+        let (name, expected) = parse_vector(line)?;
+        let ch = match multibase::TABLE.iter().find(|row| row.0 == name) {
+            Some(row) => row.1,
+            None => {
+                skip_divergence(file, &name, &MULTIBASE_DIVERGENCES, report)?;
+                continue;
+            }
+        };
+        let mb = match Multibase::with_char(ch, &input) {
+            Ok(mb) => mb,
+            Err(_) => {
+                skip_divergence(file, &name, &MULTIBASE_DIVERGENCES, report)?;
+                continue;
+            }
+        };
+        if is_divergence(&name, &MULTIBASE_DIVERGENCES) {
+            err_at!(Invalid, msg: "{} {} is supported, drop the divergence", file, name)?
+        }
+
+        let encoded = mb.to_text()?;
+        if encoded != expected {
+            err_at!(
+                Invalid, msg: "{} {} encode {:?} != {:?}", file, name, encoded, expected
+            )?
+        }
+        let decoded = Multibase::from_text(&expected)?.to_bytes();
+        if decoded.as_deref() != Some(input.as_slice()) {
+            err_at!(Invalid, msg: "{} {} decode {:?}", file, name, decoded)?
+        }
+        report.passed += 1;
+    }
+
+    Ok(())
+}
+
+fn check_multicodec_table(report: &mut SpecReport) -> Result<()> {
+    let mut lines = MULTICODEC_CSV
+        .lines()
+        .filter(|line| !line.trim().is_empty());
+    let cols = csv_columns(
+        "multicodec.csv",
+        lines.next(),
+        &[&["name"], &["tag"], &["code"]],
+    )?;
+
+    for line in lines {
+        let row: Vec<&str> = line.split(',').map(|col| col.trim()).collect();
+        let (name, tag, code) = match (row.get(cols[0]), row.get(cols[1]), row.get(cols[2])) {
+            (Some(name), Some(tag), Some(code)) => (*name, *tag, *code),
+            _ => err_at!(ParseError, msg: "multicodec.csv bad row {:?}", row)?,
+        };
+        let code = match code.strip_prefix("0x") {
+            Some(hex) => err_at!(ParseError, u128::from_str_radix(hex, 16), "{:?}", row)?,
+            None => err_at!(ParseError, msg: "multicodec.csv bad code {:?}", row)?,
+        };
+
+        match Codepoint::from_name(name) {
+            Some(_) if is_divergence(name, &MULTICODEC_DIVERGENCES) => {
+                err_at!(Invalid, msg: "multicodec {} is defined, drop the divergence", name)?
+            }
+            Some(cp) if cp.code == code && cp.tag.to_string() == tag => (),
+            Some(cp) => err_at!(Invalid, msg: "multicodec {:?} != {}/{:x}", row, cp.tag, cp.code)?,
+            None => {
+                skip_divergence("multicodec.csv", name, &MULTICODEC_DIVERGENCES, report)?;
+                continue;
+            }
+        }
+        match Codepoint::from_code(code) {
+            Some(cp) if cp.name == name => (),
+            Some(cp) => err_at!(Invalid, msg: "multicodec {:?} != {}", row, cp.name)?,
+            None => err_at!(Invalid, msg: "multicodec missing code {:?}", row)?,
+        }
+        report.passed += 1;
+    }
+
+    Ok(())
+}
+
+// return the offset of each column in `header`, a column is matched by
+// any of its alternate names.
+fn csv_columns(file: &str, header: Option<&str>, names: &[&[&str]]) -> Result<Vec<usize>> {
+    let header: Vec<&str> = match header {
+        Some(header) => header.split(',').map(|col| col.trim()).collect(),
+        None => err_at!(ParseError, msg: "{} empty", file)?,
+    };
+
+    let mut cols = vec![];
+    for alts in names.iter() {
+        match header.iter().position(|col| alts.contains(col)) {
+            Some(off) => cols.push(off),
+            None => err_at!(ParseError, msg: "{} missing column {:?}", file, alts)?,
+        }
+    }
+    Ok(cols)
+}
+
+fn is_divergence(name: &str, divergences: &[(&str, &str)]) -> bool {
+    divergences.iter().any(|(n, _)| *n == name)
+}
+
+// record `name` as skipped if it is a listed divergence, else fail.
+fn skip_divergence(
+    file: &str,
+    name: &str,
+    divergences: &[(&str, &str)],
+    report: &mut SpecReport,
+) -> Result<()> {
+    match is_divergence(name, divergences) {
+        true => {
+            report.skipped.push(format!("{}: {}", file, name));
+            Ok(())
+        }
+        false => err_at!(Invalid, msg: "{} {:?} not supported", file, name),
+    }
+}
+
+// parse `<encoding>, "<text>"`.
+fn parse_vector(line: &str) -> Result<(String, String)> {
+    let mut cols = line.splitn(2, ',');
+    let name = cols.next().unwrap_or_default().trim();
+    let text = cols.next().unwrap_or_default().trim();
+    match text.strip_prefix('"').and_then(|t| t.strip_suffix('"')) {
+        Some(text) => Ok((name.to_string(), text.to_string())),
+        None => err_at!(ParseError, msg: "bad vector {:?}", line),
+    }
+}
+
+// spec vectors escape bytes as `\xNN`.
+fn unescape(text: &str) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' if chars.as_str().starts_with('x') => {
+                let hex: String = chars.by_ref().skip(1).take(2).collect();
+                let byte = err_at!(ParseError, u8::from_str_radix(&hex, 16), "{:?}", text)?;
+                out.push(byte);
+            }
+            ch => out.extend_from_slice(ch.encode_utf8(&mut [0; 4]).as_bytes()),
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+#[path = "spec_tests_test.rs"]
+mod spec_tests_test;
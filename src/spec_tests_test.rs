@@ -0,0 +1,48 @@
+use super::*;
+
+#[test]
+fn test_run_spec_tests() {
+    let report = run_spec_tests().unwrap();
+    println!(".... spec tests {:?}", report);
+
+    // base36 is a listed divergence, not supported by the multibase codec.
+    let mut skipped = vec![];
+    for (file, _) in MULTIBASE_VECTORS.iter() {
+        skipped.push(format!("{}: base36", file));
+        skipped.push(format!("{}: base36upper", file));
+    }
+    assert_eq!(report.skipped, skipped);
+    assert!(report.passed > 500, "{}", report.passed);
+}
+
+#[test]
+fn test_divergences() {
+    let mut report = SpecReport::default();
+    skip_divergence("x.csv", "base36", &MULTIBASE_DIVERGENCES, &mut report).unwrap();
+    assert_eq!(report.skipped, vec!["x.csv: base36".to_string()]);
+    assert!(skip_divergence("x.csv", "base58btc", &MULTIBASE_DIVERGENCES, &mut report).is_err());
+    assert!(skip_divergence("x.csv", "blake3", &MULTICODEC_DIVERGENCES, &mut report).is_err());
+
+    // upstream layout, with extra and reordered columns.
+    let header = Some("unicode, character, encoding, description, status");
+    let cols = csv_columns(
+        "x.csv",
+        header,
+        &[&["encoding"], &["code", "character"], &["description"]],
+    )
+    .unwrap();
+    assert_eq!(cols, vec![2, 1, 3]);
+    assert!(csv_columns("x.csv", Some("name, tag"), &[&["code"]]).is_err());
+    assert!(csv_columns("x.csv", None, &[&["code"]]).is_err());
+}
+
+#[test]
+fn test_unescape() {
+    assert_eq!(unescape("yes").unwrap(), b"yes".to_vec());
+    assert_eq!(unescape("\\x00\\x00y").unwrap(), b"\0\0y".to_vec());
+    assert!(unescape("\\xzz").is_err());
+
+    let (name, text) = parse_vector("base16, \"f00\"").unwrap();
+    assert_eq!((name.as_str(), text.as_str()), ("base16", "f00"));
+    assert!(parse_vector("base16, f00").is_err());
+}
@@ -0,0 +1,406 @@
+//! Split file content into chunks, that become leaf blocks in UnixFS.
+//!
+//! Chunkers are selected using the same strings as go-ipfs `--chunker`
+//! option, refer [Spec] for details. Content-defined chunking, using
+//! rabin fingerprints, is implemented by [RabinSplitter] with the same
+//! polynomial, window and cut condition as go-ipfs, so that chunk
+//! boundaries, and hence CIDs, match across implementations.
+//!
+//! `buzhash` is rejected as not implemented, its boundaries depend on a
+//! 256 entry byte-hash table published with go-ipfs-chunker.
+
+use bytes::Bytes;
+
+use std::{fmt, io, str::FromStr};
+
+use crate::{Error, Result};
+
+/// Default chunk size, same as go-ipfs.
+pub const DEFAULT_SIZE: usize = 256 * 1024;
+
+/// Chunk size shall not exceed this limit, same as go-ipfs.
+pub const SIZE_LIMIT: usize = 1024 * 1024;
+
+/// Irreducible polynomial, over GF(2), used by go-ipfs for rabin
+/// fingerprints.
+pub const RABIN_POLY: u64 = 0x3DF3_05DF_B2A8_05;
+
+/// Minimum chunk size for rabin chunker, same as go-ipfs.
+pub const RABIN_MIN: usize = 16;
+
+// rabin fingerprint is computed over a sliding window of these many bytes.
+const WINDOW_SIZE: usize = 16;
+
+// size of buffer for reading the stream.
+const READ_SIZE: usize = 64 * 1024;
+
+/// Chunker split a stream of bytes into one or more chunks.
+pub trait Chunker {
+    /// Return the next chunk, None once the stream is exhausted. Empty
+    /// stream is returned as a single empty chunk.
+    fn next_chunk(&mut self) -> Result<Option<Bytes>>;
+}
+
+/// Chunker specification, parsed from strings like:
+///
+/// * `size-<bytes>`, for fixed size chunks, `size-262144`.
+/// * `rabin-<min>-<avg>-<max>`, for content-defined chunks, sizes can be
+///   labelled as in `rabin-min:16-avg:32-max:64`. `rabin-<avg>` picks
+///   `avg/3` as minimum and `avg + avg/2` as maximum, while `rabin` picks
+///   [DEFAULT_SIZE] as average.
+///
+/// Empty string and `default` select the default chunker.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Spec {
+    Size(usize),
+    Rabin { min: usize, avg: usize, max: usize },
+}
+
+impl Default for Spec {
+    fn default() -> Spec {
+        Spec::Size(DEFAULT_SIZE)
+    }
+}
+
+impl fmt::Display for Spec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Spec::Size(size) => write!(f, "size-{}", size),
+            Spec::Rabin { min, avg, max } => write!(f, "rabin-{}-{}-{}", min, avg, max),
+        }
+    }
+}
+
+impl FromStr for Spec {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Spec> {
+        let parts: Vec<&str> = s.split('-').collect();
+        let spec = match parts.as_slice() {
+            [""] | ["default"] => Spec::default(),
+            ["size", size] => Spec::Size(parse_size(size)?),
+            ["rabin"] => Spec::from_rabin_avg(DEFAULT_SIZE),
+            ["rabin", avg] => Spec::from_rabin_avg(parse_size(avg)?),
+            ["rabin", min, avg, max] => Spec::Rabin {
+                min: parse_label("min", min)?,
+                avg: parse_label("avg", avg)?,
+                max: parse_label("max", max)?,
+            },
+            ["buzhash"] => err_at!(NotImplemented, msg: "chunker {:?}", s)?,
+            _ => err_at!(BadInput, msg: "unrecognized chunker {:?}", s)?,
+        };
+
+        spec.validate()?;
+        Ok(spec)
+    }
+}
+
+impl Spec {
+    /// Rabin chunker for average chunk size `avg`, same as go-ipfs.
+    pub fn from_rabin_avg(avg: usize) -> Spec {
+        Spec::Rabin {
+            min: avg / 3,
+            avg,
+            max: avg + (avg / 2),
+        }
+    }
+
+    /// Check chunk sizes against limits.
+    pub fn validate(&self) -> Result<()> {
+        match *self {
+            Spec::Size(0) => err_at!(BadInput, msg: "chunker size must be > 0"),
+            Spec::Size(size) if size > SIZE_LIMIT => {
+                err_at!(BadInput, msg: "chunker size {} > {}", size, SIZE_LIMIT)
+            }
+            Spec::Size(_) => Ok(()),
+            Spec::Rabin { min, .. } if min < RABIN_MIN => {
+                err_at!(BadInput, msg: "rabin min {} < {}", min, RABIN_MIN)
+            }
+            Spec::Rabin { max, .. } if max > SIZE_LIMIT => {
+                err_at!(BadInput, msg: "rabin max {} > {}", max, SIZE_LIMIT)
+            }
+            Spec::Rabin { min, avg, max } if !(min < avg && avg < max) => {
+                err_at!(BadInput, msg: "rabin min {} < avg {} < max {}", min, avg, max)
+            }
+            Spec::Rabin { .. } => Ok(()),
+        }
+    }
+
+    /// Create a chunker, as per this spec, reading from `r`.
+    pub fn into_chunker<R>(self, r: R) -> Result<Box<dyn Chunker>>
+    where
+        R: 'static + io::Read,
+    {
+        self.validate()?;
+        match self {
+            Spec::Size(size) => Ok(Box::new(SizeSplitter::new(r, size))),
+            Spec::Rabin { min, avg, max } => Ok(Box::new(RabinSplitter::new(r, min, avg, max))),
+        }
+    }
+}
+
+/// Split stream into fixed size chunks, the last chunk can be smaller.
+pub struct SizeSplitter<R>
+where
+    R: io::Read,
+{
+    r: R,
+    size: usize,
+    n_chunks: usize,
+    eof: bool,
+}
+
+impl<R> SizeSplitter<R>
+where
+    R: io::Read,
+{
+    pub fn new(r: R, size: usize) -> SizeSplitter<R> {
+        SizeSplitter {
+            r,
+            size,
+            n_chunks: 0,
+            eof: false,
+        }
+    }
+}
+
+impl<R> Chunker for SizeSplitter<R>
+where
+    R: io::Read,
+{
+    fn next_chunk(&mut self) -> Result<Option<Bytes>> {
+        if self.eof {
+            return Ok(None);
+        }
+
+        let mut buf = vec![0; self.size];
+        let mut n = 0;
+        while n < self.size {
+            match self.r.read(&mut buf[n..]) {
+                Ok(0) => {
+                    self.eof = true;
+                    break;
+                }
+                Ok(m) => n += m,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => (),
+                Err(err) => err_at!(IOError, Err(err))?,
+            }
+        }
+        buf.truncate(n);
+
+        match n {
+            0 if self.n_chunks > 0 => Ok(None),
+            _ => {
+                self.n_chunks += 1;
+                Ok(Some(buf.into()))
+            }
+        }
+    }
+}
+
+/// Split stream into content-defined chunks, using rabin fingerprint of
+/// a sliding window, same as go-ipfs. A chunk ends after the byte at
+/// which fingerprint's lower `log2(avg)` bits are all zero, chunks are
+/// at least `min` bytes, and at most `max` bytes, except the last one.
+pub struct RabinSplitter<R>
+where
+    R: io::Read,
+{
+    r: R,
+    min: usize,
+    max: usize,
+    hash: Rabin,
+    buf: Vec<u8>,
+    bpos: usize,
+    n_chunks: usize,
+    eof: bool,
+}
+
+impl<R> RabinSplitter<R>
+where
+    R: io::Read,
+{
+    /// Sizes must be valid, refer [Spec::validate].
+    pub fn new(r: R, min: usize, avg: usize, max: usize) -> RabinSplitter<R> {
+        // log2(avg), rounded down.
+        let bits = (usize::BITS - 1 - avg.leading_zeros()) as u64;
+        RabinSplitter {
+            r,
+            min,
+            max,
+            hash: Rabin::new(RABIN_POLY, (1 << bits) - 1),
+            buf: vec![],
+            bpos: 0,
+            n_chunks: 0,
+            eof: false,
+        }
+    }
+
+    // return false if stream is exhausted.
+    fn fill(&mut self) -> Result<bool> {
+        self.buf.resize(READ_SIZE, 0);
+        self.bpos = 0;
+        loop {
+            match self.r.read(&mut self.buf) {
+                Ok(n) => {
+                    self.buf.truncate(n);
+                    break Ok(n > 0);
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => (),
+                Err(err) => break err_at!(IOError, Err(err)),
+            }
+        }
+    }
+}
+
+impl<R> Chunker for RabinSplitter<R>
+where
+    R: io::Read,
+{
+    fn next_chunk(&mut self) -> Result<Option<Bytes>> {
+        if self.eof {
+            return Ok(None);
+        }
+
+        self.hash.reset();
+        // first bytes of a chunk are not fingerprinted, only the last
+        // window of minimum chunk is.
+        let skip = self.min - WINDOW_SIZE;
+        let mut chunk: Vec<u8> = vec![];
+        loop {
+            if self.bpos >= self.buf.len() && !self.fill()? {
+                self.eof = true;
+                break;
+            }
+
+            let data = &self.buf[self.bpos..];
+            if chunk.len() < skip {
+                let n = data.len().min(skip - chunk.len());
+                chunk.extend_from_slice(&data[..n]);
+                self.bpos += n;
+                continue;
+            }
+
+            let mut cut = None;
+            for (i, byte) in data.iter().enumerate() {
+                self.hash.slide(*byte);
+                let n = chunk.len() + i + 1;
+                if n >= self.min && (self.hash.is_boundary() || n >= self.max) {
+                    cut = Some(i + 1);
+                    break;
+                }
+            }
+            let n = cut.unwrap_or_else(|| data.len());
+            chunk.extend_from_slice(&data[..n]);
+            self.bpos += n;
+            if cut.is_some() {
+                self.n_chunks += 1;
+                return Ok(Some(chunk.into()));
+            }
+        }
+
+        match chunk.len() {
+            0 if self.n_chunks > 0 => Ok(None),
+            _ => {
+                self.n_chunks += 1;
+                Ok(Some(chunk.into()))
+            }
+        }
+    }
+}
+
+// rabin fingerprint, over GF(2), of a sliding window.
+struct Rabin {
+    // fingerprint of a byte followed by WINDOW_SIZE-1 zero bytes, to
+    // slide out the byte.
+    out: [u64; 256],
+    // reduction modulo polynomial, indexed by the 8 bits shifted above
+    // the polynomial's degree.
+    modulo: [u64; 256],
+    shift: u32,
+    mask: u64,
+    window: [u8; WINDOW_SIZE],
+    wpos: usize,
+    digest: u64,
+}
+
+impl Rabin {
+    fn new(pol: u64, mask: u64) -> Rabin {
+        let k = degree(pol) as u32;
+        let mut out = [0; 256];
+        let mut modulo = [0; 256];
+        for b in 0..256_u64 {
+            let mut h = pol_mod(b, pol);
+            for _ in 0..(WINDOW_SIZE - 1) {
+                h = pol_mod(h << 8, pol);
+            }
+            out[b as usize] = h;
+            modulo[b as usize] = pol_mod(b << k, pol) | (b << k);
+        }
+
+        let mut val = Rabin {
+            out,
+            modulo,
+            shift: k - 8,
+            mask,
+            window: [0; WINDOW_SIZE],
+            wpos: 0,
+            digest: 0,
+        };
+        val.reset();
+        val
+    }
+
+    // same as go-ipfs, a chunk starts with byte 1 slided into the window.
+    fn reset(&mut self) {
+        self.window = [0; WINDOW_SIZE];
+        self.wpos = 0;
+        self.digest = 0;
+        self.slide(1);
+    }
+
+    fn slide(&mut self, byte: u8) {
+        let out = self.window[self.wpos];
+        self.window[self.wpos] = byte;
+        self.digest ^= self.out[out as usize];
+        self.wpos = (self.wpos + 1) % WINDOW_SIZE;
+
+        let index = (self.digest >> self.shift) as usize;
+        self.digest = (self.digest << 8) | (byte as u64);
+        self.digest ^= self.modulo[index];
+    }
+
+    fn is_boundary(&self) -> bool {
+        (self.digest & self.mask) == 0
+    }
+}
+
+// degree of polynomial `x`, -1 for zero.
+fn degree(x: u64) -> i32 {
+    63 - (x.leading_zeros() as i32)
+}
+
+// remainder of polynomial division `x / d`, over GF(2).
+fn pol_mod(mut x: u64, d: u64) -> u64 {
+    while x != 0 && degree(x) >= degree(d) {
+        x ^= d << (degree(x) - degree(d));
+    }
+    x
+}
+
+fn parse_size(s: &str) -> Result<usize> {
+    err_at!(BadInput, s.parse::<usize>(), "chunker size {:?}", s)
+}
+
+// rabin sizes can be labelled, like `min:16`.
+fn parse_label(label: &str, s: &str) -> Result<usize> {
+    match s.split_once(':') {
+        Some((l, size)) if l == label => parse_size(size),
+        Some((l, _)) => err_at!(BadInput, msg: "rabin label {:?}, expected {:?}", l, label),
+        None => parse_size(s),
+    }
+}
+
+#[cfg(test)]
+#[path = "chunker_test.rs"]
+mod chunker_test;
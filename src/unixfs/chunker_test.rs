@@ -0,0 +1,168 @@
+use super::*;
+
+#[test]
+fn test_spec() {
+    let testcases = vec![
+        ("", Spec::Size(DEFAULT_SIZE)),
+        ("default", Spec::Size(DEFAULT_SIZE)),
+        ("size-262144", Spec::Size(262144)),
+        ("size-1", Spec::Size(1)),
+        ("rabin", Spec::from_rabin_avg(DEFAULT_SIZE)),
+        (
+            "rabin-262144",
+            Spec::Rabin {
+                min: 87381,
+                avg: 262144,
+                max: 393216,
+            },
+        ),
+        (
+            "rabin-16-32-64",
+            Spec::Rabin {
+                min: 16,
+                avg: 32,
+                max: 64,
+            },
+        ),
+        (
+            "rabin-min:16-avg:32-max:64",
+            Spec::Rabin {
+                min: 16,
+                avg: 32,
+                max: 64,
+            },
+        ),
+    ];
+    for (text, spec) in testcases.into_iter() {
+        assert_eq!(text.parse::<Spec>().unwrap(), spec, "{}", text);
+    }
+
+    let bad = vec![
+        "size-0",
+        "size-2097152",
+        "size-abc",
+        "rabin-15-32-64",
+        "rabin-16-64-32",
+        "rabin-16-32-2097152",
+        "rabin-1048576",
+        "rabin-avg:16-min:32-max:64",
+        "rabin-16-32",
+        "buzhash",
+        "fixed",
+    ];
+    for text in bad.into_iter() {
+        assert!(text.parse::<Spec>().is_err(), "{}", text);
+    }
+
+    for text in ["size-1024", "rabin-16-32-64"].iter() {
+        let spec: Spec = text.parse().unwrap();
+        assert_eq!(spec.to_string(), *text);
+        assert_eq!(spec.to_string().parse::<Spec>().unwrap(), spec);
+    }
+}
+
+#[test]
+fn test_size_splitter() {
+    let data: Vec<u8> = (0..10).collect();
+    let mut chunker = Spec::Size(4).into_chunker(io::Cursor::new(data)).unwrap();
+
+    let mut chunks = vec![];
+    while let Some(chunk) = chunker.next_chunk().unwrap() {
+        chunks.push(chunk.to_vec());
+    }
+    assert_eq!(chunks, vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9]]);
+
+    let mut chunker = Spec::Size(4).into_chunker(io::empty()).unwrap();
+    assert_eq!(chunker.next_chunk().unwrap().unwrap().len(), 0);
+    assert!(chunker.next_chunk().unwrap().is_none());
+
+    let data = vec![1_u8; 8];
+    let mut chunker = Spec::Size(4).into_chunker(io::Cursor::new(data)).unwrap();
+    assert_eq!(chunker.next_chunk().unwrap().unwrap().len(), 4);
+    assert_eq!(chunker.next_chunk().unwrap().unwrap().len(), 4);
+    assert!(chunker.next_chunk().unwrap().is_none());
+}
+
+fn chunk_all(spec: Spec, data: Vec<u8>) -> Vec<Vec<u8>> {
+    let mut chunker = spec.into_chunker(io::Cursor::new(data)).unwrap();
+    let mut chunks = vec![];
+    while let Some(chunk) = chunker.next_chunk().unwrap() {
+        chunks.push(chunk.to_vec());
+    }
+    chunks
+}
+
+// xorshift, for reproducible content.
+fn make_data(n: usize, mut seed: u64) -> Vec<u8> {
+    (0..n)
+        .map(|_| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed as u8
+        })
+        .collect()
+}
+
+#[test]
+fn test_rabin_hash() {
+    assert_eq!(degree(RABIN_POLY), 53);
+    assert_eq!(degree(0), -1);
+    assert_eq!(pol_mod(RABIN_POLY, RABIN_POLY), 0);
+
+    // rolling fingerprint of a window is its fingerprint from scratch,
+    // once the initial byte has slided out.
+    let data = make_data(100, 7);
+    let mut hash = Rabin::new(RABIN_POLY, 0);
+    for (i, byte) in data.iter().enumerate() {
+        hash.slide(*byte);
+        if i + 1 < WINDOW_SIZE {
+            continue;
+        }
+        let window = &data[i + 1 - WINDOW_SIZE..=i];
+        let fp = window
+            .iter()
+            .fold(0, |h, b| pol_mod((h << 8) | (*b as u64), RABIN_POLY));
+        assert_eq!(hash.digest, fp, "{}", i);
+    }
+}
+
+#[test]
+fn test_rabin_splitter() {
+    let spec: Spec = "rabin-64-256-1024".parse().unwrap();
+
+    // window of zeros fingerprint to zero, cut at minimum size.
+    let chunks = chunk_all(spec, vec![0; 1000]);
+    let sizes: Vec<usize> = chunks.iter().map(|c| c.len()).collect();
+    assert_eq!(sizes[..15], [64; 15]);
+    assert_eq!(sizes[15..], [40]);
+
+    assert_eq!(chunk_all(spec, vec![]), vec![Vec::<u8>::new()]);
+
+    let data = make_data(64 * 1024, 0x2545F4914F6CDD1D);
+    let chunks = chunk_all(spec, data.clone());
+    assert_eq!(chunks.concat(), data);
+    let (last, chunks) = chunks.split_last().unwrap();
+    assert!(last.len() <= 1024);
+    for chunk in chunks.iter() {
+        assert!(chunk.len() >= 64 && chunk.len() <= 1024, "{}", chunk.len());
+    }
+    assert!(chunks.iter().any(|c| c.len() < 1024));
+
+    // boundaries are content defined, they re-align after an insert.
+    let ends = |chunks: Vec<Vec<u8>>, skip: usize| -> Vec<usize> {
+        let mut off = 0;
+        let ends = chunks.iter().map(|c| {
+            off += c.len();
+            off
+        });
+        ends.filter(|end| *end > skip)
+            .map(|end| end - skip)
+            .collect()
+    };
+    let mut shifted = make_data(100, 3);
+    shifted.extend_from_slice(&data);
+    let a = ends(chunk_all(spec, data), 0);
+    let b = ends(chunk_all(spec, shifted), 100);
+    assert_eq!(a[a.len() - 10..], b[b.len() - 10..]);
+}
@@ -0,0 +1,254 @@
+//! Import file content into UnixFS blocks, using balanced DAG layout.
+//!
+//! Options follow go-ipfs `add` flags, `--raw-leaves`, `--cid-version`,
+//! `--hash`, `--inline` and `--chunker`, so that same content added with
+//! same options shall hash to same CID on both implementations.
+
+use multibase::Base::Base32Lower;
+
+use std::io;
+
+use crate::{
+    cid::{Cid, Version},
+    ipld::block::Block,
+    multicodec::{self, Multicodec},
+    multihash::Multihash,
+    unixfs::chunker::{Chunker, Spec},
+    Error, Result,
+};
+
+/// Default size limit for inlining blocks as identity CID.
+pub const DEFAULT_INLINE_LIMIT: usize = 32;
+
+/// Maximum number of links in an intermediate node, same as go-ipfs.
+pub const LINKS_PER_BLOCK: usize = 174;
+
+// UnixFS Data.Type for files.
+const DATA_TYPE_FILE: u64 = 2;
+
+/// Importer options.
+#[derive(Clone, Debug)]
+pub struct Options {
+    chunker: Spec,
+    raw_leaves: bool,
+    cid_version: Version,
+    hash: Multicodec,
+    inline: Option<usize>,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            chunker: Spec::default(),
+            raw_leaves: false,
+            cid_version: Version::Zero,
+            hash: multicodec::SHA2_256.into(),
+            inline: None,
+        }
+    }
+}
+
+impl Options {
+    /// Default options for `version`. CIDv1 implies raw-leaves, use
+    /// [Options::set_raw_leaves] to override.
+    pub fn from_cid_version(version: Version) -> Result<Options> {
+        let mut opts = Options::default();
+        match version {
+            Version::Zero => (),
+            Version::One => {
+                opts.cid_version = Version::One;
+                opts.raw_leaves = true;
+            }
+            version => err_at!(NotImplemented, msg: "cid version {:?}", version)?,
+        }
+        Ok(opts)
+    }
+
+    pub fn set_chunker(&mut self, chunker: Spec) -> &mut Self {
+        self.chunker = chunker;
+        self
+    }
+
+    pub fn set_raw_leaves(&mut self, raw_leaves: bool) -> &mut Self {
+        self.raw_leaves = raw_leaves;
+        self
+    }
+
+    /// Hash function other than sha2-256 implies CIDv1, like go-ipfs.
+    pub fn set_hash(&mut self, hash: Multicodec) -> &mut Self {
+        if hash.to_code() != multicodec::SHA2_256 && self.cid_version == Version::Zero {
+            self.cid_version = Version::One;
+            self.raw_leaves = true;
+        }
+        self.hash = hash;
+        self
+    }
+
+    /// Blocks whose encoded size is less than or equal to `limit` are
+    /// inlined as identity CID, None disables inlining.
+    pub fn set_inline(&mut self, limit: Option<usize>) -> &mut Self {
+        self.inline = limit;
+        self
+    }
+
+    pub fn to_cid_version(&self) -> Version {
+        self.cid_version.clone()
+    }
+
+    pub fn is_raw_leaves(&self) -> bool {
+        self.raw_leaves
+    }
+}
+
+/// Importer for file content.
+pub struct Importer {
+    opts: Options,
+    blocks: Vec<Block>,
+}
+
+// Reference to an imported node, along with its sizes.
+struct Node {
+    cid: Cid,
+    filesize: u64, // size of file content under this node.
+    tsize: u64,    // cumulative size of encoded blocks under this node.
+}
+
+impl Importer {
+    pub fn new(opts: Options) -> Importer {
+        Importer {
+            opts,
+            blocks: vec![],
+        }
+    }
+
+    /// Import file content from `r`. Return all the blocks created for
+    /// the file, the root block being the last.
+    pub fn import<R>(mut self, r: R) -> Result<Vec<Block>>
+    where
+        R: 'static + io::Read,
+    {
+        let mut chunker = self.opts.chunker.into_chunker(r)?;
+
+        let mut nodes = vec![];
+        while let Some(chunk) = chunker.next_chunk()? {
+            nodes.push(self.add_leaf(&chunk)?);
+        }
+
+        // a single chunk file is its own root, otherwise group them
+        // bottom-up, leaves first, which is same as go-ipfs balanced layout.
+        while nodes.len() > 1 {
+            let mut parents = vec![];
+            for children in nodes.chunks(LINKS_PER_BLOCK) {
+                parents.push(self.add_parent(children)?);
+            }
+            nodes = parents;
+        }
+
+        Ok(self.blocks)
+    }
+
+    fn add_leaf(&mut self, data: &[u8]) -> Result<Node> {
+        let (codec, block) = match self.opts.raw_leaves {
+            true => (multicodec::RAW, data.to_vec()),
+            false => {
+                let fs_data = encode_fs_data(data, data.len() as u64, &[]);
+                (multicodec::DAG_PB, encode_pb_node(&[], &fs_data)?)
+            }
+        };
+
+        let tsize = block.len() as u64;
+        let cid = self.add_block(codec.into(), block)?;
+        let node = Node {
+            cid,
+            filesize: data.len() as u64,
+            tsize,
+        };
+        Ok(node)
+    }
+
+    fn add_parent(&mut self, children: &[Node]) -> Result<Node> {
+        let filesize: u64 = children.iter().map(|n| n.filesize).sum();
+        let blocksizes: Vec<u64> = children.iter().map(|n| n.filesize).collect();
+
+        let fs_data = encode_fs_data(&[], filesize, &blocksizes);
+        let block = encode_pb_node(children, &fs_data)?;
+
+        let tsize = (block.len() as u64) + children.iter().map(|n| n.tsize).sum::<u64>();
+        let cid = self.add_block(multicodec::DAG_PB.into(), block)?;
+        let node = Node {
+            cid,
+            filesize,
+            tsize,
+        };
+        Ok(node)
+    }
+
+    fn add_block(&mut self, codec: Multicodec, data: Vec<u8>) -> Result<Cid> {
+        let inline = matches!(self.opts.inline, Some(limit) if data.len() <= limit);
+
+        let cid = if inline {
+            let mh = Multihash::new(multicodec::IDENTITY.into(), &data)?;
+            Cid::from_raw(Base32Lower, codec, mh)
+        } else {
+            let mh = Multihash::new(self.opts.hash, &data)?;
+            match (&self.opts.cid_version, codec.to_code()) {
+                (Version::Zero, multicodec::DAG_PB) => Cid::Zero(mh),
+                (_, _) => Cid::from_raw(Base32Lower, codec, mh),
+            }
+        };
+
+        self.blocks.push(Block::new(cid.clone(), data.into()));
+        Ok(cid)
+    }
+}
+
+// Encode UnixFS Data message for a file, fields are encoded in tag order
+// and Data is skipped when empty, like go-ipfs.
+fn encode_fs_data(data: &[u8], filesize: u64, blocksizes: &[u64]) -> Vec<u8> {
+    let mut buf = vec![];
+    encode_uvarint_field(&mut buf, 1, DATA_TYPE_FILE);
+    if !data.is_empty() {
+        encode_bytes_field(&mut buf, 2, data);
+    }
+    encode_uvarint_field(&mut buf, 3, filesize);
+    for size in blocksizes.iter() {
+        encode_uvarint_field(&mut buf, 4, *size);
+    }
+    buf
+}
+
+// Encode dag-pb PBNode, Links are encoded before Data as per the
+// canonical form of dag-pb.
+fn encode_pb_node(links: &[Node], data: &[u8]) -> Result<Vec<u8>> {
+    let mut buf = vec![];
+    for link in links.iter() {
+        let mut lbuf = vec![];
+        encode_bytes_field(&mut lbuf, 1, &link.cid.encode()?);
+        encode_bytes_field(&mut lbuf, 2, &[]);
+        encode_uvarint_field(&mut lbuf, 3, link.tsize);
+        encode_bytes_field(&mut buf, 2, &lbuf);
+    }
+    encode_bytes_field(&mut buf, 1, data);
+    Ok(buf)
+}
+
+fn encode_uvarint_field(buf: &mut Vec<u8>, tag: u64, val: u64) {
+    use unsigned_varint::encode as uve;
+
+    let mut scratch = [0_u8; 10];
+    buf.extend_from_slice(uve::u64(tag << 3, &mut scratch));
+    buf.extend_from_slice(uve::u64(val, &mut scratch));
+}
+
+fn encode_bytes_field(buf: &mut Vec<u8>, tag: u64, val: &[u8]) {
+    use unsigned_varint::encode as uve;
+
+    let mut scratch = [0_u8; 10];
+    buf.extend_from_slice(uve::u64((tag << 3) | 2, &mut scratch));
+    buf.extend_from_slice(uve::usize(val.len(), &mut scratch));
+    buf.extend_from_slice(val);
+}
+
+#[cfg(test)]
+#[path = "importer_test.rs"]
+mod importer_test;
@@ -0,0 +1,132 @@
+use super::*;
+
+// CIDs as computed by go-ipfs `ipfs add` with equivalent flags.
+#[test]
+fn test_import_compat() {
+    let mut inline = Options::from_cid_version(Version::One).unwrap();
+    inline.set_inline(Some(DEFAULT_INLINE_LIMIT));
+
+    let testcases = vec![
+        (
+            Options::default(),
+            "hello world",
+            "Qmf412jQZiuVUtdgnB36FXFX7xg5V6KEbSJ4dpQuhkLyfD",
+        ),
+        (
+            Options::default(),
+            "hello world\n",
+            "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o",
+        ),
+        (
+            Options::default(),
+            "",
+            "QmbFMke1KXqnYyBBWxB74N4c5SBnJMVAiMNRcGu6x1AwQH",
+        ),
+        (
+            Options::from_cid_version(Version::One).unwrap(),
+            "hello world\n",
+            "bafkreifjjcie6lypi6ny7amxnfftagclbuxndqonfipmb64f2km2devei4",
+        ),
+        (
+            Options::from_cid_version(Version::One).unwrap(),
+            "",
+            "bafkreihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku",
+        ),
+        (inline, "hello world\n", "bafkqaddimvwgy3zao5xxe3debi"),
+    ];
+
+    for (opts, data, cid) in testcases.into_iter() {
+        let r = io::Cursor::new(data.as_bytes().to_vec());
+        let blocks = Importer::new(opts).import(r).unwrap();
+        let root = blocks.last().unwrap().to_cid().unwrap();
+        assert_eq!(root.to_text(None).unwrap(), cid, "{:?}", data);
+    }
+}
+
+// Multi-leaf file, "abcdefghij" with `--chunker=size-4`, root block is
+// assembled byte by byte from dag-pb and UnixFS specs.
+// TODO: pin the root CID against go-ipfs `ipfs add --chunker=size-4`.
+#[test]
+fn test_import_multi_leaf() {
+    let mut opts = Options::default();
+    opts.set_chunker(Spec::Size(4));
+    let r = io::Cursor::new(b"abcdefghij".to_vec());
+    let blocks = Importer::new(opts).import(r).unwrap();
+    assert_eq!(blocks.len(), 4);
+
+    // PBNode{Data: Data{Type: File, Data: chunk, filesize: len(chunk)}}
+    let leaves: Vec<Vec<u8>> = vec![
+        vec![
+            0x0a, 0x0a, 0x08, 0x02, 0x12, 0x04, b'a', b'b', b'c', b'd', 0x18, 0x04,
+        ],
+        vec![
+            0x0a, 0x0a, 0x08, 0x02, 0x12, 0x04, b'e', b'f', b'g', b'h', 0x18, 0x04,
+        ],
+        vec![0x0a, 0x08, 0x08, 0x02, 0x12, 0x02, b'i', b'j', 0x18, 0x02],
+    ];
+
+    let mut root = vec![];
+    for (leaf, block) in leaves.iter().zip(blocks.iter()) {
+        assert_eq!(block.as_block_data().unwrap(), leaf.as_slice());
+        let mh = Multihash::new(multicodec::SHA2_256.into(), leaf).unwrap();
+        assert_eq!(block.to_cid().unwrap(), Cid::Zero(mh.clone()));
+
+        // PBLink{Hash: cid, Name: "", Tsize: len(leaf)}
+        let hash = mh.encode().unwrap();
+        root.extend_from_slice(&[0x12, 0x28, 0x0a, 0x22]);
+        root.extend_from_slice(&hash);
+        root.extend_from_slice(&[0x12, 0x00, 0x18, leaf.len() as u8]);
+    }
+    // Data{Type: File, filesize: 10, blocksizes: [4, 4, 2]}
+    root.extend_from_slice(&[
+        0x0a, 0x0a, 0x08, 0x02, 0x18, 0x0a, 0x20, 0x04, 0x20, 0x04, 0x20, 0x02,
+    ]);
+
+    assert_eq!(blocks[3].as_block_data().unwrap(), root.as_slice());
+    let mh = Multihash::new(multicodec::SHA2_256.into(), &root).unwrap();
+    assert_eq!(blocks[3].to_cid().unwrap(), Cid::Zero(mh));
+}
+
+#[test]
+fn test_import_layout() {
+    let data: Vec<u8> = (0..10).collect();
+
+    let mut opts = Options::default();
+    opts.set_chunker(Spec::Size(4));
+    let blocks = Importer::new(opts)
+        .import(io::Cursor::new(data.clone()))
+        .unwrap();
+    assert_eq!(blocks.len(), 4);
+    assert_eq!(blocks[3].to_cid().unwrap().to_version(), Version::Zero);
+
+    let mut opts = Options::from_cid_version(Version::One).unwrap();
+    opts.set_chunker(Spec::Size(4));
+    let blocks = Importer::new(opts).import(io::Cursor::new(data)).unwrap();
+    assert_eq!(blocks.len(), 4);
+    for block in blocks[..3].iter() {
+        assert_eq!(
+            block.to_cid().unwrap().to_content_type().to_code(),
+            multicodec::RAW
+        );
+    }
+    let root = blocks[3].to_cid().unwrap();
+    assert_eq!(root.to_content_type().to_code(), multicodec::DAG_PB);
+    assert_eq!(root.to_version(), Version::One);
+}
+
+#[test]
+fn test_options() {
+    let mut opts = Options::default();
+    assert_eq!(opts.to_cid_version(), Version::Zero);
+    assert!(!opts.is_raw_leaves());
+
+    opts.set_hash(multicodec::BLAKE2B_256.into());
+    assert_eq!(opts.to_cid_version(), Version::One);
+    assert!(opts.is_raw_leaves());
+
+    let mut opts = Options::from_cid_version(Version::One).unwrap();
+    opts.set_raw_leaves(false);
+    assert!(!opts.is_raw_leaves());
+
+    assert!(Options::from_cid_version(Version::Two).is_err());
+}
@@ -0,0 +1,7 @@
+//! Module implement UnixFS, files and directories on top of IPLD.
+
+pub mod chunker;
+pub mod importer;
+
+#[cfg(test)]
+pub(crate) mod test_util;
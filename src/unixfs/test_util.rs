@@ -0,0 +1,36 @@
+//! Fixtures shared by tests that need a UnixFS DAG.
+
+use std::io;
+
+use crate::{
+    cid::Cid,
+    ipld::{
+        block::Block,
+        store::{MemStore, Store},
+    },
+    unixfs::{
+        chunker::Spec,
+        importer::{Importer, Options},
+    },
+};
+
+/// Chunk size used by fixtures, small enough for a few bytes of data to
+/// span several leaves.
+pub const CHUNK_SIZE: usize = 4;
+
+/// Import `data` as a UnixFS file using `opts`, chunked into
+/// [CHUNK_SIZE] bytes. Return the blocks, root last.
+pub fn import_blocks(mut opts: Options, data: &[u8]) -> Vec<Block> {
+    opts.set_chunker(Spec::Size(CHUNK_SIZE));
+    Importer::new(opts).import(io::Cursor::new(data)).unwrap()
+}
+
+/// Same as [import_blocks], and add the blocks to `store`. Return the
+/// root along with the blocks.
+pub fn import_into(opts: Options, data: &[u8], store: &mut MemStore) -> (Cid, Vec<Block>) {
+    let blocks = import_blocks(opts, data);
+    for block in blocks.iter() {
+        store.put(block.clone()).unwrap();
+    }
+    (blocks.last().unwrap().to_cid().unwrap(), blocks)
+}
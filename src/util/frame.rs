@@ -0,0 +1,150 @@
+//! Module implement varint length-prefixed framing.
+//!
+//! A frame is an unsigned-varint length followed by that many bytes of
+//! payload. This is the common framing for multistream-select and most
+//! libp2p protocols. Readers take a buffer that is cleared and reused
+//! across frames, so that a stream of messages can be read without an
+//! allocation per message.
+
+use bytes::{Bytes, BytesMut};
+
+use std::{convert::TryInto, io};
+
+use crate::{Error, Result};
+
+/// Maximum number of bytes in a varint length prefix.
+pub const MAX_PREFIX_SIZE: usize = 10;
+
+/// Read a frame from `r` into `buf`, fail if the payload exceeds `max`
+/// bytes. `buf` is cleared before reading, and the size is checked before
+/// growing it. Return the payload size.
+pub fn read_varint_frame<R: io::Read>(r: &mut R, max: usize, buf: &mut Vec<u8>) -> Result<usize> {
+    let n = read_frame_size(r, max)?;
+    buf.clear();
+    buf.resize(n, 0);
+    err_at!(IOError, r.read_exact(buf))?;
+
+    Ok(n)
+}
+
+/// Same as [read_varint_frame], but return the payload as reference
+/// counted [Bytes]. Payload is read into `buf` and split off from it,
+/// without copying, so that it can be handed over to upper layers while
+/// `buf`'s spare capacity is reused for the next frame.
+pub fn read_varint_frame_bytes<R: io::Read>(
+    r: &mut R,
+    max: usize,
+    buf: &mut BytesMut,
+) -> Result<Bytes> {
+    let n = read_frame_size(r, max)?;
+    buf.clear();
+    buf.resize(n, 0);
+    err_at!(IOError, r.read_exact(buf))?;
+
+    Ok(buf.split().freeze())
+}
+
+/// Write `data` as a frame to `w`, fail if it exceeds `max` bytes.
+/// Return the number of bytes written, including the length prefix.
+pub fn write_varint_frame<W: io::Write>(w: &mut W, data: &[u8], max: usize) -> Result<usize> {
+    let mut prefix = [0_u8; MAX_PREFIX_SIZE];
+    let prefix = encode_prefix(data, max, &mut prefix)?;
+
+    err_at!(IOError, w.write_all(prefix))?;
+    err_at!(IOError, w.write_all(data))?;
+
+    Ok(prefix.len() + data.len())
+}
+
+/// Async version of [read_varint_frame].
+#[cfg(feature = "async")]
+pub async fn read_varint_frame_async<R>(r: &mut R, max: usize, buf: &mut Vec<u8>) -> Result<usize>
+where
+    R: futures::io::AsyncRead + Unpin,
+{
+    use futures::io::AsyncReadExt;
+
+    let mut prefix = [0_u8; MAX_PREFIX_SIZE];
+    let mut i = 0;
+    loop {
+        if i >= prefix.len() {
+            err_at!(DecodeError, msg: "frame length prefix overflow")?
+        }
+        err_at!(IOError, r.read_exact(&mut prefix[i..i + 1]).await)?;
+        i += 1;
+        if (prefix[i - 1] & 0x80) == 0 {
+            break;
+        }
+    }
+
+    let n = to_frame_size(&prefix[..i], max)?;
+    buf.clear();
+    buf.resize(n, 0);
+    err_at!(IOError, r.read_exact(buf).await)?;
+
+    Ok(n)
+}
+
+/// Async version of [write_varint_frame].
+#[cfg(feature = "async")]
+pub async fn write_varint_frame_async<W>(w: &mut W, data: &[u8], max: usize) -> Result<usize>
+where
+    W: futures::io::AsyncWrite + Unpin,
+{
+    use futures::io::AsyncWriteExt;
+
+    let mut prefix = [0_u8; MAX_PREFIX_SIZE];
+    let prefix = encode_prefix(data, max, &mut prefix)?;
+
+    err_at!(IOError, w.write_all(prefix).await)?;
+    err_at!(IOError, w.write_all(data).await)?;
+
+    Ok(prefix.len() + data.len())
+}
+
+// read the length prefix one byte at a time, so that we don't consume
+// bytes beyond this frame.
+fn read_frame_size<R: io::Read>(r: &mut R, max: usize) -> Result<usize> {
+    let mut prefix = [0_u8; MAX_PREFIX_SIZE];
+    let mut i = 0;
+    loop {
+        if i >= prefix.len() {
+            err_at!(DecodeError, msg: "frame length prefix overflow")?
+        }
+        err_at!(IOError, r.read_exact(&mut prefix[i..i + 1]))?;
+        i += 1;
+        if (prefix[i - 1] & 0x80) == 0 {
+            break;
+        }
+    }
+
+    to_frame_size(&prefix[..i], max)
+}
+
+fn to_frame_size(prefix: &[u8], max: usize) -> Result<usize> {
+    use unsigned_varint::decode as uvd;
+
+    let (n, _) = err_at!(DecodeError, uvd::u64(prefix))?;
+    let n: usize = err_at!(FailConvert, n.try_into())?;
+    if n > max {
+        err_at!(DecodeError, msg: "frame size {} exceeds limit {}", n, max)?
+    }
+    Ok(n)
+}
+
+fn encode_prefix<'a>(
+    data: &[u8],
+    max: usize,
+    prefix: &'a mut [u8; MAX_PREFIX_SIZE],
+) -> Result<&'a [u8]> {
+    use unsigned_varint::encode as uve;
+
+    if data.len() > max {
+        err_at!(EncodeError, msg: "frame size {} exceeds limit {}", data.len(), max)?
+    }
+    Ok(uve::usize(data.len(), prefix))
+}
+
+#[cfg(test)]
+#[path = "frame_test.rs"]
+mod frame_test;
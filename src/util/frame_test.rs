@@ -0,0 +1,69 @@
+use super::*;
+
+#[test]
+fn test_varint_frame() {
+    let payloads: Vec<Vec<u8>> = vec![vec![], b"hello".to_vec(), vec![0xab; 300]];
+
+    let mut data = vec![];
+    for payload in payloads.iter() {
+        let n = write_varint_frame(&mut data, payload, 1024).unwrap();
+        assert_eq!(n, payload.len() + if payload.len() < 128 { 1 } else { 2 });
+    }
+
+    let mut r = io::Cursor::new(&data);
+    let mut buf = vec![];
+    for payload in payloads.iter() {
+        let n = read_varint_frame(&mut r, 1024, &mut buf).unwrap();
+        assert_eq!(n, payload.len());
+        assert_eq!(&buf, payload);
+    }
+    assert!(read_varint_frame(&mut r, 1024, &mut buf).is_err());
+
+    // limits are enforced on both ends.
+    assert!(write_varint_frame(&mut vec![], &[0; 300], 299).is_err());
+    let mut r = io::Cursor::new(&data);
+    read_varint_frame(&mut r, 299, &mut buf).unwrap();
+    read_varint_frame(&mut r, 299, &mut buf).unwrap();
+    assert!(read_varint_frame(&mut r, 299, &mut buf).is_err());
+
+    let mut r = io::Cursor::new(vec![0xff; 11]);
+    assert!(read_varint_frame(&mut r, usize::MAX, &mut buf).is_err());
+}
+
+#[test]
+fn test_varint_frame_bytes() {
+    let mut data = vec![];
+    write_varint_frame(&mut data, b"hello", 1024).unwrap();
+    write_varint_frame(&mut data, b"world", 1024).unwrap();
+
+    let mut r = io::Cursor::new(&data);
+    let mut buf = BytesMut::with_capacity(64);
+    let hello = read_varint_frame_bytes(&mut r, 1024, &mut buf).unwrap();
+    let world = read_varint_frame_bytes(&mut r, 1024, &mut buf).unwrap();
+    assert_eq!(hello.as_ref(), b"hello");
+    assert_eq!(world.as_ref(), b"world");
+
+    // payloads are handed out without copying.
+    let clone = hello.clone();
+    assert_eq!(clone.as_ptr(), hello.as_ptr());
+    assert_eq!(hello.slice(1..).as_ptr(), hello.as_ptr().wrapping_add(1));
+
+    assert!(read_varint_frame_bytes(&mut r, 1024, &mut buf).is_err());
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn test_varint_frame_async() {
+    use futures::{executor::block_on, io::Cursor};
+
+    let mut w = Cursor::new(vec![]);
+    block_on(write_varint_frame_async(&mut w, b"hello", 1024)).unwrap();
+    block_on(write_varint_frame_async(&mut w, b"world", 1024)).unwrap();
+    assert!(block_on(write_varint_frame_async(&mut w, b"world", 4)).is_err());
+
+    let mut r = Cursor::new(w.into_inner());
+    let mut buf = vec![];
+    block_on(read_varint_frame_async(&mut r, 1024, &mut buf)).unwrap();
+    assert_eq!(buf, b"hello");
+    block_on(read_varint_frame_async(&mut r, 4, &mut buf)).unwrap_err();
+}
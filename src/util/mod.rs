@@ -0,0 +1,404 @@
+//! Module implement useful functions.
+
+use crossbeam_channel as cbm;
+use rand::{
+    rngs::{SmallRng, StdRng},
+    SeedableRng,
+};
+
+use std::{
+    collections::HashMap,
+    ffi, hash, io, path,
+    sync::{Arc, Mutex},
+    time,
+};
+
+use crate::{Error, Result};
+
+/// Short form to compose Error values.
+///
+/// Here are few possible ways:
+///
+/// ```ignore
+/// use crate::Error;
+/// err_at!(Error::Invalid(String::default(), "bad argument"));
+/// ```
+///
+/// ```ignore
+/// use crate::Error;
+/// err_at!(Invalid, msg: format!("bad argument"));
+/// ```
+///
+/// ```ignore
+/// use crate::Error;
+/// err_at!(Invalid, std::io::read(buf));
+/// ```
+///
+/// ```ignore
+/// use crate::Error;
+/// err_at!(Invalid, std::fs::read(file_path), format!("read failed"));
+/// ```
+///
+#[macro_export]
+macro_rules! err_at {
+    ($v:ident, msg: $($arg:expr),+) => {{
+        use log::error;
+
+        let prefix = format!("{}:{}", file!(), line!());
+        let err = Error::$v(prefix, format!($($arg),+));
+
+        error!("{}", err);
+        Err(err)
+    }};
+    ($v:ident, $e:expr) => {{
+        use log::error;
+
+        match $e {
+            Ok(val) => Ok(val),
+            Err(err) => {
+                let prefix = format!("{}:{}", file!(), line!());
+                let err = Error::$v(prefix, format!("{}", err));
+
+                error!("{}", err);
+                Err(err)
+            }
+        }
+    }};
+    ($v:ident, $e:expr, $($arg:expr),+) => {{
+        use log::error;
+
+        match $e {
+            Ok(val) => Ok(val),
+            Err(err) => {
+                let prefix = format!("{}:{}", file!(), line!());
+                let msg = format!($($arg),+);
+                let err = Error::$v(prefix, format!("{} {}", err, msg));
+
+                error!("{}", err);
+
+                Err(err)
+            }
+        }
+    }};
+}
+
+pub mod frame;
+
+pub fn ctrl_channel() -> Result<cbm::Receiver<time::Instant>> {
+    let (sender, receiver) = cbm::bounded(100);
+    err_at!(
+        SysFail,
+        ctrlc::set_handler(move || {
+            let _ = sender.send(time::Instant::now());
+        })
+    )?;
+
+    Ok(receiver)
+}
+
+/// Source of time. Components with expiry logic, like TTLs and
+/// backoffs, read time through a Clock, so that they can be tested
+/// using [MockClock] without sleeping.
+pub trait Clock: Send + Sync {
+    /// Return the current monotonic instant.
+    fn now(&self) -> time::Instant;
+
+    /// Return the current wall-clock time.
+    fn now_system(&self) -> time::SystemTime;
+}
+
+/// Clock backed by the operating system.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> time::Instant {
+        time::Instant::now()
+    }
+
+    fn now_system(&self) -> time::SystemTime {
+        time::SystemTime::now()
+    }
+}
+
+/// Clock that moves only when advanced, for testing. Clones share the
+/// same time.
+#[derive(Clone)]
+pub struct MockClock {
+    inner: Arc<Mutex<(time::Instant, time::SystemTime)>>,
+}
+
+impl Default for MockClock {
+    fn default() -> MockClock {
+        let val = (time::Instant::now(), time::SystemTime::now());
+        MockClock {
+            inner: Arc::new(Mutex::new(val)),
+        }
+    }
+}
+
+impl MockClock {
+    pub fn new() -> MockClock {
+        MockClock::default()
+    }
+
+    /// Move the clock forward by `dur`.
+    pub fn advance(&self, dur: time::Duration) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.0 += dur;
+        inner.1 += dur;
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> time::Instant {
+        self.inner.lock().unwrap().0
+    }
+
+    fn now_system(&self) -> time::SystemTime {
+        self.inner.lock().unwrap().1
+    }
+}
+
+/// Token bucket, holds upto `burst` tokens and refills at `rate` tokens
+/// per second.
+#[derive(Clone, Debug)]
+pub struct TokenBucket {
+    rate: f64,
+    burst: f64,
+    tokens: f64,
+    last: time::Instant,
+}
+
+impl TokenBucket {
+    /// Create a full bucket at instant `now`.
+    pub fn new(rate: f64, burst: usize, now: time::Instant) -> TokenBucket {
+        TokenBucket {
+            rate,
+            burst: burst as f64,
+            tokens: burst as f64,
+            last: now,
+        }
+    }
+
+    /// Take `n` tokens at instant `now`, return false if bucket does not
+    /// have enough tokens, in which case no tokens are taken.
+    pub fn try_take(&mut self, n: usize, now: time::Instant) -> bool {
+        self.refill(now);
+        match self.tokens >= (n as f64) {
+            true => {
+                self.tokens -= n as f64;
+                true
+            }
+            false => false,
+        }
+    }
+
+    /// Return whether the bucket is full at instant `now`.
+    pub fn is_full(&mut self, now: time::Instant) -> bool {
+        self.refill(now);
+        self.tokens >= self.burst
+    }
+
+    fn refill(&mut self, now: time::Instant) {
+        if now > self.last {
+            let elapsed = (now - self.last).as_secs_f64();
+            self.tokens = (self.tokens + elapsed * self.rate).min(self.burst);
+            self.last = now;
+        }
+    }
+}
+
+/// Rate limiter with a [TokenBucket] per key, typically a peer, so that
+/// a single key can't starve others.
+pub struct RateLimiter<K> {
+    rate: f64,
+    burst: usize,
+    clock: Arc<dyn Clock>,
+    buckets: HashMap<K, TokenBucket>,
+    n_rejected: usize,
+}
+
+impl<K> RateLimiter<K>
+where
+    K: Clone + Eq + hash::Hash,
+{
+    /// Create a rate limiter allowing `rate` requests per second, with
+    /// bursts upto `burst` requests, for each key.
+    pub fn new(rate: f64, burst: usize) -> RateLimiter<K> {
+        RateLimiter::with_clock(rate, burst, Arc::new(SystemClock))
+    }
+
+    /// Same as [Self::new], reading time from `clock`.
+    pub fn with_clock(rate: f64, burst: usize, clock: Arc<dyn Clock>) -> RateLimiter<K> {
+        RateLimiter {
+            rate,
+            burst,
+            clock,
+            buckets: HashMap::new(),
+            n_rejected: 0,
+        }
+    }
+
+    /// Return true if a request from `key` is allowed, false if it must
+    /// be rejected.
+    pub fn try_acquire(&mut self, key: &K) -> bool {
+        let now = self.clock.now();
+        let (rate, burst) = (self.rate, self.burst);
+        let bucket = self
+            .buckets
+            .entry(key.clone())
+            .or_insert_with(|| TokenBucket::new(rate, burst, now));
+        let ok = bucket.try_take(1, now);
+        if !ok {
+            self.n_rejected += 1;
+        }
+        ok
+    }
+
+    /// Return the number of rejected requests so far.
+    pub fn to_rejected(&self) -> usize {
+        self.n_rejected
+    }
+
+    /// Forget keys whose buckets have refilled, to bound memory.
+    pub fn purge(&mut self) {
+        let now = self.clock.now();
+        self.buckets.retain(|_, bucket| !bucket.is_full(now));
+    }
+
+    pub fn len(&self) -> usize {
+        self.buckets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buckets.is_empty()
+    }
+}
+
+/// Convert relative path, and ~ path into absolute path. Note that
+/// the supplied path must exist.
+pub fn canonicalize(loc: ffi::OsString) -> Result<ffi::OsString> {
+    use std::iter::FromIterator;
+
+    let loc = err_at!(FilePath, loc.into_string().map_err(|e| format!("{:?}", e)))?;
+    let mut chars = loc.chars();
+
+    let home = dirs::home_dir();
+    let mut pbuf = path::PathBuf::new();
+
+    let loc = match (chars.next(), chars.next()) {
+        (Some('~'), Some(path::MAIN_SEPARATOR)) if home.is_some() => {
+            pbuf.push(home.unwrap());
+            pbuf.push(String::from_iter(
+                loc.chars().take(2).collect::<Vec<char>>(),
+            ));
+            pbuf.into()
+        }
+        (Some('~'), Some(path::MAIN_SEPARATOR)) => loc.into(),
+        _ => {
+            pbuf.push(loc);
+            err_at!(FilePath, pbuf.canonicalize())?.into()
+        }
+    };
+
+    Ok(loc)
+}
+
+/// Create a new insecure but fast psuedo-random-number-generator.
+pub fn new_prng(seed: Option<u128>) -> Result<SmallRng> {
+    let rng = match seed {
+        Some(seed) => SmallRng::from_seed(seed.to_be_bytes()),
+        None => SmallRng::from_entropy(),
+    };
+
+    Ok(rng)
+}
+
+/// Create a new cryptographically secure psuedo-random-number-generator.
+pub fn new_csprng(seed: Option<u128>) -> Result<StdRng> {
+    let seed = seed.unwrap_or(
+        err_at!(
+            SysFail,
+            time::SystemTime::now().duration_since(time::UNIX_EPOCH)
+        )?
+        .as_nanos(),
+    );
+    let seed = {
+        let mut s = [0_u8; 32];
+        let seed = seed.to_be_bytes();
+        s[..16].copy_from_slice(&seed);
+        s[16..].copy_from_slice(&seed);
+        s
+    };
+    Ok(StdRng::from_seed(seed))
+}
+
+/// Check whether _ENV_VAR_ `name` is set to "true" or "t" or "1"
+pub fn get_env_bool(name: String) -> bool {
+    use std::env;
+
+    match env::var(&name).unwrap_or("".to_string()).as_str() {
+        "true" | "t" | "1" => true,
+        _ => false,
+    }
+}
+
+/// XOR two slice and return the new slice.
+#[inline]
+pub fn xor_slice(a: &[u8], b: &[u8]) -> Vec<u8> {
+    a.iter().zip(b.iter()).map(|(a, b)| a ^ b).collect()
+}
+
+/// Compare two slices in constant time, time taken depends only on their
+/// length and not on their content. Use this to compare digests and MACs.
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    // accumulate differences across all bytes, without an early return.
+    let acc = a
+        .iter()
+        .zip(b.iter())
+        .fold(0_u8, |acc, (x, y)| acc | (x ^ y));
+    acc == 0
+}
+
+/// Default upper limit on the size of a length-prefixed-message, 1MiB.
+pub const MAX_LPM_SIZE: usize = 1024 * 1024;
+
+/// Read length-prefixed-message, of size not more than [MAX_LPM_SIZE].
+pub fn read_lpm<R: io::Read>(r: &mut R) -> Result<Vec<u8>> {
+    read_lpm_max(r, MAX_LPM_SIZE)
+}
+
+/// Read length-prefixed-message, fail if the message size exceeds `max`
+/// bytes. Size is checked before allocating for the message.
+pub fn read_lpm_max<R: io::Read>(r: &mut R, max: usize) -> Result<Vec<u8>> {
+    let mut data = vec![];
+    frame::read_varint_frame(r, max, &mut data)?;
+    Ok(data)
+}
+
+/// Write data as length-prefixed-message.
+pub fn write_lpm<W: io::Write>(w: &mut W, data: &[u8]) -> Result<usize> {
+    frame::write_varint_frame(w, data, usize::MAX)
+}
+
+/// Write data as length-prefixed-message and flush the writer.
+pub fn flush_lpm<W: io::Write>(w: &mut W, data: &[u8]) -> Result<usize> {
+    let n = match data.len() {
+        0 => 0,
+        _ => {
+            let n = write_lpm(w, data)?;
+            err_at!(IOError, w.flush())?;
+            n
+        }
+    };
+
+    Ok(n)
+}
+
+#[cfg(test)]
+#[path = "util_test.rs"]
+mod util_test;
@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use super::*;
+
+#[test]
+fn test_token_bucket() {
+    let now = time::Instant::now();
+    let mut bucket = TokenBucket::new(2.0, 4, now);
+
+    assert!(bucket.try_take(4, now));
+    assert!(!bucket.try_take(1, now));
+    assert!(!bucket.is_full(now));
+
+    let now = now + Duration::from_millis(500);
+    assert!(bucket.try_take(1, now));
+    assert!(!bucket.try_take(1, now));
+
+    // never refills beyond burst.
+    let now = now + Duration::from_secs(100);
+    assert!(bucket.is_full(now));
+    assert!(!bucket.try_take(5, now));
+    assert!(bucket.try_take(4, now));
+}
+
+#[test]
+fn test_rate_limiter() {
+    let clock = MockClock::new();
+    let mut limiter: RateLimiter<&str> = RateLimiter::with_clock(1.0, 2, Arc::new(clock.clone()));
+
+    assert!(limiter.try_acquire(&"a"));
+    assert!(limiter.try_acquire(&"a"));
+    assert!(!limiter.try_acquire(&"a"));
+    // other keys have their own quota.
+    assert!(limiter.try_acquire(&"b"));
+    assert_eq!(limiter.to_rejected(), 1);
+    assert_eq!(limiter.len(), 2);
+
+    clock.advance(Duration::from_secs(1));
+    assert!(limiter.try_acquire(&"a"));
+    assert!(!limiter.try_acquire(&"a"));
+    assert_eq!(limiter.to_rejected(), 2);
+
+    clock.advance(Duration::from_secs(2));
+    limiter.purge();
+    assert!(limiter.is_empty());
+}
+
+#[test]
+fn test_ct_eq() {
+    assert!(ct_eq(b"", b""));
+    assert!(ct_eq(b"digest", b"digest"));
+    assert!(!ct_eq(b"digest", b"digesT"));
+    assert!(!ct_eq(b"digest", b"diges"));
+}